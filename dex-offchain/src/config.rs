@@ -12,9 +12,9 @@ pub struct DexOffchainConfig {
     pub max_retries: u8,       // 最大重试次数
 }
 
-impl DexOffchainConfig {
+impl Default for DexOffchainConfig {
     /// 创建默认配置。
-    pub fn default() -> Self {
+    fn default() -> Self {
         Self {
             api_url: String::from("https://api.dex.example.com"),
             api_key: String::new(),
@@ -22,8 +22,94 @@ impl DexOffchainConfig {
             max_retries: 3,
         }
     }
+}
+
+impl DexOffchainConfig {
     /// 检查配置有效性。
     pub fn is_valid(&self) -> bool {
         !self.api_url.is_empty() && self.timeout_secs > 0 && self.max_retries > 0
     }
+    /// 按配置创建共享 REST 客户端（重试次数取 max_retries），重试与熔断状态经 DexLogger 输出。
+    pub fn rest_client(&self) -> offchain_rest::RestClient {
+        let config = offchain_rest::RestClientConfig::new(&self.api_url, &self.api_key, self.timeout_secs)
+            .with_max_retries(self.max_retries);
+        offchain_rest::RestClient::from_config(config).with_observer(std::sync::Arc::new(crate::logging::DexLogger))
+    }
+}
+
+/// 单个适配器的服务配置（TOML `[[adapters]]`）。
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct DexAdapterConfig {
+    pub name: String,          // 适配器名称
+    pub api_url: String,       // 上游 API 基础 URL
+    #[serde(default)]
+    pub api_key: String,       // API 密钥
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,     // 请求超时时间（秒）
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u8,       // 最大重试次数
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,         // 是否启用
+}
+
+impl DexAdapterConfig {
+    /// 按适配器配置创建共享 REST 客户端，重试与熔断状态经 DexLogger 输出。
+    pub fn rest_client(&self) -> offchain_rest::RestClient {
+        let config = offchain_rest::RestClientConfig::new(&self.api_url, &self.api_key, self.timeout_secs)
+            .with_max_retries(self.max_retries);
+        offchain_rest::RestClient::from_config(config).with_observer(std::sync::Arc::new(crate::logging::DexLogger))
+    }
+}
+
+/// DEX 离线服务配置文件（TOML）。
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize)]
+pub struct DexServiceConfig {
+    #[serde(default)]
+    pub adapters: Vec<DexAdapterConfig>, // 适配器列表
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+fn default_max_retries() -> u8 {
+    offchain_rest::DEFAULT_MAX_RETRIES
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 读取并解析 TOML 配置文件。
+pub fn load_config(path: &str) -> Result<DexServiceConfig, crate::error::OffchainDexError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| crate::error::OffchainDexError::Config(format!("{}: {}", path, e)))?;
+    toml::from_str(&content).map_err(|e| crate::error::OffchainDexError::Config(format!("{}: {}", path, e)))
+}
+
+/// 配置文件轮询间隔。
+const CONFIG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// 监听配置文件变更（按修改时间轮询），变更且解析成功后回调；解析失败保留旧配置并记录日志。
+pub async fn watch_config<F>(path: &str, mut on_change: F)
+where
+    F: FnMut(DexServiceConfig) + Send,
+{
+    let mut last_modified = None;
+    let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let modified = match std::fs::metadata(path).and_then(|meta| meta.modified()) {
+            Ok(modified) => modified,
+            Err(_) => continue, // 文件暂不存在
+        };
+        if last_modified == Some(modified) {
+            continue;
+        }
+        last_modified = Some(modified);
+        match load_config(path) {
+            Ok(cfg) => on_change(cfg),
+            Err(e) => tracing::warn!("[Config] reload failed: {}", e),
+        }
+    }
 }
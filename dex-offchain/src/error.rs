@@ -0,0 +1,65 @@
+//!
+//! DEX Offchain Error Definitions
+//!
+//! 本模块定义 DEX 离线服务的错误类型，覆盖网络、上游 API、解析、注册表查找与参数校验等场景。
+
+use offchain_rest::RestError;
+use thiserror::Error;
+
+/// DEX 离线服务错误类型枚举。
+#[derive(Debug, Error)]
+pub enum OffchainDexError {
+    /// 网络请求失败（连接、超时等）。
+    #[error("http request failed: {0}")]
+    Http(String),
+    /// 上游 API 返回非 2xx 状态码。
+    #[error("upstream returned {status}: {message}")]
+    Api { status: u16, message: String },
+    /// 响应数据解析失败。
+    #[error("failed to decode response: {0}")]
+    Decode(String),
+    /// 适配器未注册。
+    #[error("dex adapter not registered: {0}")]
+    AdapterNotFound(String),
+    /// 输入参数无效。
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    /// 适配器不支持该操作。
+    #[error("operation not supported by {0}")]
+    Unsupported(String),
+    /// 配置加载失败。
+    #[error("invalid config: {0}")]
+    Config(String),
+    /// 上游熔断中，暂不可用。
+    #[error("upstream unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl From<reqwest::Error> for OffchainDexError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            OffchainDexError::Decode(err.to_string())
+        } else {
+            OffchainDexError::Http(err.to_string())
+        }
+    }
+}
+
+impl From<RestError> for OffchainDexError {
+    fn from(err: RestError) -> Self {
+        match err {
+            RestError::Http { message, .. } => OffchainDexError::Http(message),
+            RestError::Api { status, message } => OffchainDexError::Api { status, message },
+            RestError::Decode(message) => OffchainDexError::Decode(message),
+            RestError::Config(message) => OffchainDexError::Config(message),
+            RestError::CircuitOpen(base_url) => OffchainDexError::Unavailable(base_url),
+        }
+    }
+}
+
+/// 适配器 trait 以字符串返回错误，便于跨 crate 实现；typed 错误经 Display 转换。
+impl From<OffchainDexError> for String {
+    fn from(err: OffchainDexError) -> Self {
+        err.to_string()
+    }
+}
@@ -3,7 +3,11 @@
 //!
 //! 本模块实现 DEX 离线服务工厂模式，支持多种 DEX 实例的创建、注册与管理，确保可扩展性与合规性。
 
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
 use crate::config::DexOffchainConfig;
+use crate::traits::OffchainDexAdapter;
 
 /// DEX 工厂结构体。
 pub struct DexFactory {
@@ -21,3 +25,39 @@ impl DexFactory {
         println!("Initializing DEX with config: {:?}", self.config);
     }
 }
+
+/// 异步 DEX 适配器注册表（线程安全，按名称注册/查找）。
+#[derive(Default)]
+pub struct OffchainDexFactory {
+    adapters: RwLock<HashMap<String, Arc<dyn OffchainDexAdapter>>>, // 名称 -> 适配器
+}
+
+impl OffchainDexFactory {
+    /// 创建空注册表。
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 注册适配器；同名适配器被替换并返回旧实例。
+    pub fn register(&self, name: &str, adapter: Arc<dyn OffchainDexAdapter>) -> Option<Arc<dyn OffchainDexAdapter>> {
+        self.adapters.write().unwrap_or_else(|e| e.into_inner()).insert(name.to_string(), adapter)
+    }
+    /// 注销适配器。
+    pub fn unregister(&self, name: &str) -> Option<Arc<dyn OffchainDexAdapter>> {
+        self.adapters.write().unwrap_or_else(|e| e.into_inner()).remove(name)
+    }
+    /// 按名称查找适配器。
+    pub fn get(&self, name: &str) -> Option<Arc<dyn OffchainDexAdapter>> {
+        self.adapters.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned()
+    }
+    /// 已注册适配器名称（按字母序）。
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.adapters.read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 全局 DEX 适配器注册表，REST 路由从此查找适配器。
+    pub static ref OFFCHAIN_DEX_FACTORY: OffchainDexFactory = OffchainDexFactory::new();
+}
@@ -1,22 +1,276 @@
 //!
 //! Jupiter DEX Offchain Adapter
 //!
-//! 本模块实现 Jupiter DEX 离线适配器，提供与 Jupiter API 的集成与操作接口，确保交易路由与聚合合规、可维护。
+//! 本模块实现 Jupiter DEX 离线适配器，通过 `RestClient` 调用 Jupiter v6 的 `/quote` 与 `/swap-instructions` 接口，
+//! 将路由计划解析为类型化结构体，并把返回的指令转换为 Solana `Instruction`，便于与本程序指令组合进同一笔交易。
+
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Deserializer};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::error::OffchainDexError;
+use crate::rest::RestClient;
+use crate::traits::{OffchainDexAdapter, OffchainQuoteRequest, OffchainQuoteResponse, OffchainSwapRequest, OffchainSwapResponse};
+
+/// Jupiter v6 API 默认地址。
+pub const JUPITER_API_URL: &str = "https://quote-api.jup.ag/v6";
+
+/// Jupiter 以字符串返回 u64 数量，统一在反序列化时解析。
+fn de_u64_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+/// 路由中单个市场的成交信息。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterSwapInfo {
+    pub amm_key: String,                    // 市场（池）地址
+    #[serde(default)]
+    pub label: String,                      // 市场名称（如 Whirlpool、Raydium）
+    pub input_mint: String,                 // 本跳输入 mint
+    pub output_mint: String,                // 本跳输出 mint
+    #[serde(deserialize_with = "de_u64_string")]
+    pub in_amount: u64,                     // 本跳输入数量
+    #[serde(deserialize_with = "de_u64_string")]
+    pub out_amount: u64,                    // 本跳输出数量
+    #[serde(deserialize_with = "de_u64_string")]
+    pub fee_amount: u64,                    // 本跳手续费
+    pub fee_mint: String,                   // 手续费 mint
+}
+
+/// 路由计划中的一步（同一跳可按 percent 拆分到多个市场）。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterRoutePlanStep {
+    pub swap_info: JupiterSwapInfo, // 市场成交信息
+    pub percent: u8,                // 占本跳输入的百分比
+}
+
+/// `/quote` 返回的报价。
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterQuote {
+    pub input_mint: String,                 // 输入 mint
+    #[serde(deserialize_with = "de_u64_string")]
+    pub in_amount: u64,                     // 输入数量
+    pub output_mint: String,                // 输出 mint
+    #[serde(deserialize_with = "de_u64_string")]
+    pub out_amount: u64,                    // 预期输出数量
+    #[serde(deserialize_with = "de_u64_string")]
+    pub other_amount_threshold: u64,        // 扣除滑点后的最小输出（ExactIn）
+    pub swap_mode: String,                  // ExactIn / ExactOut
+    pub slippage_bps: u16,                  // 滑点（基点）
+    pub price_impact_pct: String,           // 价格冲击（小数，如 "0.0012" 即 0.12%）
+    pub route_plan: Vec<JupiterRoutePlanStep>, // 路由计划
+    #[serde(default)]
+    pub context_slot: u64,                  // 报价所基于的 slot
+    #[serde(skip)]
+    pub raw: serde_json::Value,             // 原始报价，/swap-instructions 需原样回传
+}
+
+impl JupiterQuote {
+    /// 价格冲击（基点），无法解析时视为 0。
+    pub fn price_impact_bps(&self) -> u64 {
+        self.price_impact_pct
+            .parse::<f64>()
+            .map(|pct| (pct * 10_000.0).round().max(0.0) as u64)
+            .unwrap_or(0)
+    }
+    /// 路由经过的市场名称（按路由顺序）。
+    pub fn route_labels(&self) -> Vec<String> {
+        self.route_plan.iter().map(|step| step.swap_info.label.clone()).collect()
+    }
+    /// 路由手续费合计（按手续费 mint 汇总）。
+    pub fn total_fees(&self) -> Vec<(String, u64)> {
+        let mut fees: Vec<(String, u64)> = Vec::new();
+        for step in &self.route_plan {
+            match fees.iter_mut().find(|(mint, _)| *mint == step.swap_info.fee_mint) {
+                Some((_, amount)) => *amount += step.swap_info.fee_amount,
+                None => fees.push((step.swap_info.fee_mint.clone(), step.swap_info.fee_amount)),
+            }
+        }
+        fees
+    }
+}
+
+/// Jupiter 返回的账户元数据。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterAccountMeta {
+    pub pubkey: String,    // 账户地址
+    pub is_signer: bool,   // 是否签名
+    pub is_writable: bool, // 是否可写
+}
+
+/// Jupiter 返回的指令（data 为 base64）。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterInstruction {
+    pub program_id: String,               // 程序地址
+    pub accounts: Vec<JupiterAccountMeta>, // 账户列表
+    pub data: String,                     // 指令数据（base64）
+}
+
+/// 解析 base58 地址。
+fn parse_pubkey(value: &str) -> Result<Pubkey, OffchainDexError> {
+    Pubkey::from_str(value).map_err(|e| OffchainDexError::Decode(format!("invalid pubkey {}: {}", value, e)))
+}
+
+impl TryFrom<&JupiterInstruction> for Instruction {
+    type Error = OffchainDexError;
+
+    fn try_from(ix: &JupiterInstruction) -> Result<Self, Self::Error> {
+        let accounts = ix
+            .accounts
+            .iter()
+            .map(|meta| {
+                let pubkey = parse_pubkey(&meta.pubkey)?;
+                Ok(if meta.is_writable {
+                    AccountMeta::new(pubkey, meta.is_signer)
+                } else {
+                    AccountMeta::new_readonly(pubkey, meta.is_signer)
+                })
+            })
+            .collect::<Result<Vec<_>, OffchainDexError>>()?;
+        let data = BASE64
+            .decode(&ix.data)
+            .map_err(|e| OffchainDexError::Decode(format!("invalid instruction data: {}", e)))?;
+        Ok(Instruction { program_id: parse_pubkey(&ix.program_id)?, accounts, data })
+    }
+}
+
+/// `/swap-instructions` 原始响应。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterSwapInstructionsResponse {
+    token_ledger_instruction: Option<JupiterInstruction>,
+    #[serde(default)]
+    compute_budget_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    setup_instructions: Vec<JupiterInstruction>,
+    swap_instruction: JupiterInstruction,
+    cleanup_instruction: Option<JupiterInstruction>,
+    #[serde(default)]
+    other_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    address_lookup_table_addresses: Vec<String>,
+}
+
+/// 转换后的交换指令组，可与本程序指令组合后由调用方签名。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JupiterSwapInstructions {
+    pub compute_budget_instructions: Vec<Instruction>, // 计算预算
+    pub token_ledger_instruction: Option<Instruction>, // token ledger（可选）
+    pub setup_instructions: Vec<Instruction>,          // 创建 ATA 等准备指令
+    pub swap_instruction: Instruction,                 // 交换指令
+    pub cleanup_instruction: Option<Instruction>,      // 解包 wSOL 等清理指令
+    pub other_instructions: Vec<Instruction>,          // 其他指令
+    pub address_lookup_table_addresses: Vec<Pubkey>,   // 地址查找表
+}
+
+impl JupiterSwapInstructions {
+    /// 按执行顺序展开全部指令。
+    pub fn all_instructions(&self) -> Vec<Instruction> {
+        let mut instructions = self.compute_budget_instructions.clone();
+        instructions.extend(self.token_ledger_instruction.iter().cloned());
+        instructions.extend(self.setup_instructions.iter().cloned());
+        instructions.push(self.swap_instruction.clone());
+        instructions.extend(self.cleanup_instruction.iter().cloned());
+        instructions.extend(self.other_instructions.iter().cloned());
+        instructions
+    }
+}
+
+impl TryFrom<JupiterSwapInstructionsResponse> for JupiterSwapInstructions {
+    type Error = OffchainDexError;
+
+    fn try_from(resp: JupiterSwapInstructionsResponse) -> Result<Self, Self::Error> {
+        let convert = |list: &[JupiterInstruction]| list.iter().map(Instruction::try_from).collect::<Result<Vec<_>, _>>();
+        Ok(Self {
+            compute_budget_instructions: convert(&resp.compute_budget_instructions)?,
+            token_ledger_instruction: resp.token_ledger_instruction.as_ref().map(Instruction::try_from).transpose()?,
+            setup_instructions: convert(&resp.setup_instructions)?,
+            swap_instruction: Instruction::try_from(&resp.swap_instruction)?,
+            cleanup_instruction: resp.cleanup_instruction.as_ref().map(Instruction::try_from).transpose()?,
+            other_instructions: convert(&resp.other_instructions)?,
+            address_lookup_table_addresses: resp
+                .address_lookup_table_addresses
+                .iter()
+                .map(|address| parse_pubkey(address))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
 
 /// Jupiter DEX 离线适配器结构体。
-pub struct JupiterOffchainAdapter;
+pub struct JupiterOffchainAdapter {
+    client: RestClient, // Jupiter API 客户端
+}
 
 impl JupiterOffchainAdapter {
-    /// 执行报价查询。
-    pub fn quote(&self, from_token: &str, to_token: &str, amount: u64) -> u64 {
-        // 模拟报价逻辑，实际应调用 Jupiter API
-        println!("Quoting {} -> {} for amount {}", from_token, to_token, amount);
-        100 // 返回模拟报价
-    }
-    /// 执行交易。
-    pub fn swap(&self, from_token: &str, to_token: &str, amount: u64) -> bool {
-        // 模拟交易逻辑，实际应调用 Jupiter API
-        println!("Swapping {} -> {} for amount {}", from_token, to_token, amount);
-        true // 返回模拟成功
+    /// 使用指定客户端创建适配器。
+    pub fn new(client: RestClient) -> Self {
+        Self { client }
+    }
+    /// 查询报价。
+    pub async fn get_quote(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<JupiterQuote, OffchainDexError> {
+        let params = [
+            ("inputMint", input_mint.to_string()),
+            ("outputMint", output_mint.to_string()),
+            ("amount", amount.to_string()),
+            ("slippageBps", slippage_bps.to_string()),
+        ];
+        let raw: serde_json::Value = self.client.get_json("quote", &params).await?;
+        let mut quote: JupiterQuote =
+            serde_json::from_value(raw.clone()).map_err(|e| OffchainDexError::Decode(e.to_string()))?;
+        quote.raw = raw;
+        Ok(quote)
+    }
+    /// 获取报价对应的交换指令（由 user 签名，wSOL 自动包装/解包）。
+    pub async fn swap_instructions(&self, quote: &JupiterQuote, user: &Pubkey) -> Result<JupiterSwapInstructions, OffchainDexError> {
+        let body = serde_json::json!({
+            "quoteResponse": quote.raw,
+            "userPublicKey": user.to_string(),
+            "wrapAndUnwrapSol": true,
+        });
+        // 仅构建指令、不提交交易，可安全重试
+        let resp: JupiterSwapInstructionsResponse = self.client.post_json_idempotent("swap-instructions", &body).await?;
+        JupiterSwapInstructions::try_from(resp)
+    }
+}
+
+#[async_trait]
+impl OffchainDexAdapter for JupiterOffchainAdapter {
+    fn name(&self) -> &'static str {
+        "jupiter"
+    }
+    async fn quote(&self, req: OffchainQuoteRequest) -> Result<OffchainQuoteResponse, String> {
+        let input_mint = Pubkey::from_str(&req.token_in)
+            .map_err(|_| OffchainDexError::InvalidRequest(format!("token_in is not a mint: {}", req.token_in)))?;
+        let output_mint = Pubkey::from_str(&req.token_out)
+            .map_err(|_| OffchainDexError::InvalidRequest(format!("token_out is not a mint: {}", req.token_out)))?;
+        let quote = self.get_quote(&input_mint, &output_mint, req.amount_in, req.slippage_bps).await?;
+        Ok(OffchainQuoteResponse {
+            amount_out: quote.out_amount,
+            min_amount_out: quote.other_amount_threshold,
+            price_impact_bps: quote.price_impact_bps(),
+            route: quote.route_labels(),
+        })
+    }
+    /// Jupiter 交易需由用户签名，请使用 swap_instructions 组装交易。
+    async fn swap(&self, _req: OffchainSwapRequest) -> Result<OffchainSwapResponse, String> {
+        Err(OffchainDexError::Unsupported(self.name().to_string()).into())
     }
 }
@@ -5,6 +5,7 @@
 
 // 导入并公开所有子模块。
 pub mod config;    // 配置模块
+pub mod error;     // 错误类型模块
 pub mod factory;   // 工厂模块
 pub mod jupiter;   // Jupiter 适配器模块
 pub mod logging;   // 日志模块
@@ -13,6 +14,7 @@ pub mod traits;    // 通用 trait 模块
 
 // 重新导出常用类型和函数，便于外部访问。
 pub use config::*;
+pub use error::*;
 pub use factory::*;
 pub use jupiter::*;
 pub use logging::*;
@@ -1,64 +1,173 @@
 //!
 //! DEX Offchain REST API Module
 //!
-//! 本模块实现 DEX 离线服务的 RESTful API 客户端，支持 HTTP 请求、响应解析、错误处理等，确保与 DEX 后端安全、合规、高效交互。
-
-use std::collections::HashMap;
-use std::time::Duration;
-use reqwest::blocking::{Client, Response};
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, AUTHORIZATION};
-
-/// REST 客户端结构体。
-pub struct RestClient {
-    pub base_url: String,         // API 基础 URL
-    pub api_key: String,          // API 密钥
-    pub timeout_secs: u64,        // 超时时间（秒）
+//! 本模块实现 DEX 离线服务的 RESTful 服务端路由与 REST 转发适配器，HTTP 请求经共享客户端发送（重试、限流、熔断），支持响应解析、错误处理等，确保与 DEX 后端安全、合规、高效交互。
+
+use actix_web::{web, HttpResponse};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OffchainDexError;
+use crate::factory::OFFCHAIN_DEX_FACTORY;
+use crate::traits::{OffchainDexAdapter, OffchainQuoteRequest, OffchainQuoteResponse, OffchainSwapRequest, OffchainSwapResponse};
+
+/// 共享异步 REST 客户端（连接复用、退避重试、限流与熔断），见 `offchain-rest`。
+pub use offchain_rest::{RestClient, RestClientConfig, RestError};
+
+/// 通过 REST 转发到远端 DEX 服务的适配器（远端需提供与本服务一致的 /quote、/swap 接口）。
+pub struct RestDexAdapter {
+    name: &'static str,  // 适配器名称
+    client: RestClient,  // 远端服务客户端
 }
 
-impl RestClient {
-    /// 创建新的 REST 客户端。
-    pub fn new(base_url: &str, api_key: &str, timeout_secs: u64) -> Self {
-        Self {
-            base_url: base_url.to_string(),
-            api_key: api_key.to_string(),
-            timeout_secs,
-        }
+impl RestDexAdapter {
+    /// 创建 REST 转发适配器。
+    pub fn new(name: &'static str, client: RestClient) -> Self {
+        Self { name, client }
     }
-    /// 构建 HTTP 客户端。
-    fn build_client(&self) -> Client {
-        Client::builder()
-            .timeout(Duration::from_secs(self.timeout_secs))
-            .build()
-            .expect("Failed to build HTTP client")
+}
+
+#[async_trait]
+impl OffchainDexAdapter for RestDexAdapter {
+    fn name(&self) -> &'static str {
+        self.name
     }
-    /// 构建请求头。
-    fn build_headers(&self) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        if !self.api_key.is_empty() {
-            headers.insert(AUTHORIZATION, HeaderValue::from_str(&self.api_key).unwrap());
-        }
-        headers
+    async fn quote(&self, req: OffchainQuoteRequest) -> Result<OffchainQuoteResponse, String> {
+        Ok(self.client.post_json_idempotent("quote", &req).await.map_err(OffchainDexError::from)?)
     }
-    /// 发送 GET 请求。
-    pub fn get(&self, endpoint: &str, params: Option<&HashMap<&str, &str>>) -> Result<Response, reqwest::Error> {
-        let client = self.build_client();
-        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint.trim_start_matches('/'));
-        let req = client.get(&url).headers(self.build_headers());
-        let req = if let Some(p) = params {
-            req.query(p)
-        } else {
-            req
-        };
-        req.send()
+    async fn swap(&self, req: OffchainSwapRequest) -> Result<OffchainSwapResponse, String> {
+        Ok(self.client.post_json("swap", &req).await.map_err(OffchainDexError::from)?)
     }
-    /// 发送 POST 请求。
-    pub fn post(&self, endpoint: &str, body: &str) -> Result<Response, reqwest::Error> {
-        let client = self.build_client();
-        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint.trim_start_matches('/'));
-        client.post(&url)
-            .headers(self.build_headers())
-            .body(body.to_string())
-            .send()
+}
+
+/// POST /quote 请求体。
+#[derive(Debug, Deserialize)]
+pub struct QuoteBody {
+    pub dex_type: String,      // 适配器名称
+    pub input_token: String,   // 输入代币
+    pub output_token: String,  // 输出代币
+    pub amount_in: u64,        // 输入数量
+    #[serde(default)]
+    pub slippage_bps: u16,     // 允许滑点（基点）
+}
+
+/// POST /swap 请求体。
+#[derive(Debug, Deserialize)]
+pub struct SwapBody {
+    pub dex_type: String,      // 适配器名称
+    pub input_token: String,   // 输入代币
+    pub output_token: String,  // 输出代币
+    pub amount_in: u64,        // 输入数量
+    #[serde(default)]
+    pub min_out: u64,          // 最小可接受输出
+}
+
+/// 错误响应体。
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String, // 错误描述
+}
+
+/// 健康检查响应体。
+#[derive(Debug, Serialize)]
+struct HealthBody {
+    status: &'static str,  // 服务状态
+    adapters: Vec<String>, // 已注册适配器
+}
+
+/// 将错误映射为 HTTP 响应。
+fn error_response(err: OffchainDexError) -> HttpResponse {
+    let body = ErrorBody { error: err.to_string() };
+    match err {
+        OffchainDexError::AdapterNotFound(_) => HttpResponse::NotFound().json(body),
+        OffchainDexError::InvalidRequest(_) => HttpResponse::UnprocessableEntity().json(body),
+        OffchainDexError::Unsupported(_) => HttpResponse::NotImplemented().json(body),
+        OffchainDexError::Unavailable(_) => HttpResponse::ServiceUnavailable().json(body),
+        _ => HttpResponse::BadGateway().json(body),
     }
 }
+
+/// 适配器返回的错误统一映射为 502。
+fn adapter_error(message: String) -> HttpResponse {
+    HttpResponse::BadGateway().json(ErrorBody { error: message })
+}
+
+/// 查找已注册适配器。
+fn lookup(dex_type: &str) -> Result<std::sync::Arc<dyn OffchainDexAdapter>, OffchainDexError> {
+    OFFCHAIN_DEX_FACTORY
+        .get(dex_type)
+        .ok_or_else(|| OffchainDexError::AdapterNotFound(dex_type.to_string()))
+}
+
+/// GET /health/dex
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().json(HealthBody { status: "ok", adapters: OFFCHAIN_DEX_FACTORY.names() })
+}
+
+/// POST /quote
+async fn quote(body: web::Json<QuoteBody>) -> HttpResponse {
+    let body = body.into_inner();
+    let adapter = match lookup(&body.dex_type) {
+        Ok(adapter) => adapter,
+        Err(err) => return error_response(err),
+    };
+    let req = OffchainQuoteRequest {
+        token_in: body.input_token,
+        token_out: body.output_token,
+        amount_in: body.amount_in,
+        slippage_bps: body.slippage_bps,
+    };
+    match adapter.quote(req).await {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(message) => adapter_error(message),
+    }
+}
+
+/// POST /swap
+/// - min_out > 0 时先询价，预期输出不足则拒绝，不发起交易
+async fn swap(body: web::Json<SwapBody>) -> HttpResponse {
+    let body = body.into_inner();
+    let adapter = match lookup(&body.dex_type) {
+        Ok(adapter) => adapter,
+        Err(err) => return error_response(err),
+    };
+    if body.amount_in == 0 {
+        return error_response(OffchainDexError::InvalidRequest("amount_in must be positive".to_string()));
+    }
+    if body.min_out > 0 {
+        let quote = adapter
+            .quote(OffchainQuoteRequest {
+                token_in: body.input_token.clone(),
+                token_out: body.output_token.clone(),
+                amount_in: body.amount_in,
+                slippage_bps: 0,
+            })
+            .await;
+        match quote {
+            Ok(quote) if quote.amount_out < body.min_out => {
+                return error_response(OffchainDexError::InvalidRequest(format!(
+                    "quoted amount_out {} below min_out {}",
+                    quote.amount_out, body.min_out
+                )))
+            }
+            Ok(_) => {}
+            Err(message) => return adapter_error(message),
+        }
+    }
+    let req = OffchainSwapRequest {
+        token_in: body.input_token,
+        token_out: body.output_token,
+        amount_in: body.amount_in,
+    };
+    match adapter.swap(req).await {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(message) => adapter_error(message),
+    }
+}
+
+/// 注册 DEX 离线服务路由。
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/health/dex", web::get().to(health))
+        .route("/quote", web::post().to(quote))
+        .route("/swap", web::post().to(swap));
+}
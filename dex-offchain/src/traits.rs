@@ -1,30 +1,56 @@
 //!
 //! DEX Offchain Traits Module
 //!
-//! 本模块定义 DEX 离线服务的通用 trait，规范适配器、工厂、API 客户端等接口，确保可扩展性、合规性与可维护性。
+//! 本模块定义 DEX 离线服务的异步适配器 trait 以及报价/交易的请求与响应结构体，确保可扩展性、合规性与可维护性。
 
-/// DEX 离线适配器 trait。
-pub trait DexOffchainAdapter {
-    /// 获取适配器名称。
-    fn name(&self) -> &str;
-    /// 获取适配器版本。
-    fn version(&self) -> &str;
-    /// 查询报价。
-    fn quote(&self, from_token: &str, to_token: &str, amount: u64) -> u64;
-    /// 执行交易。
-    fn swap(&self, from_token: &str, to_token: &str, amount: u64) -> bool;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OffchainDexError;
+
+/// 报价请求。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffchainQuoteRequest {
+    pub token_in: String,  // 输入代币（mint 或符号）
+    pub token_out: String, // 输出代币（mint 或符号）
+    pub amount_in: u64,    // 输入数量（最小单位）
+    pub slippage_bps: u16, // 允许滑点（基点）
+}
+
+/// 报价响应。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffchainQuoteResponse {
+    pub amount_out: u64,       // 预期输出数量
+    pub min_amount_out: u64,   // 扣除滑点后的最小输出
+    pub price_impact_bps: u64, // 价格冲击（基点）
+    pub route: Vec<String>,    // 路由经过的市场/DEX 标签
+}
+
+/// 交易请求。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffchainSwapRequest {
+    pub token_in: String,  // 输入代币（mint 或符号）
+    pub token_out: String, // 输出代币（mint 或符号）
+    pub amount_in: u64,    // 输入数量（最小单位）
 }
 
-/// DEX 工厂 trait。
-pub trait DexFactoryTrait {
-    /// 创建适配器实例。
-    fn create_adapter(&self) -> Box<dyn DexOffchainAdapter>;
+/// 交易响应。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffchainSwapResponse {
+    pub amount_out: u64, // 实际输出数量
+    pub tx_hash: String, // 交易签名
 }
 
-/// REST 客户端 trait。
-pub trait RestClientTrait {
-    /// 发送 GET 请求。
-    fn get(&self, endpoint: &str) -> String;
-    /// 发送 POST 请求。
-    fn post(&self, endpoint: &str, body: &str) -> String;
+/// DEX 离线适配器 trait（异步、线程安全）。
+/// - 错误以字符串返回，内容与 OffchainDexError 的 Display 一致，实现方可直接用 `?` 传播 OffchainDexError。
+#[async_trait]
+pub trait OffchainDexAdapter: Send + Sync {
+    /// 获取适配器名称（注册表唯一标识）。
+    fn name(&self) -> &'static str;
+    /// 查询报价；默认不支持。
+    async fn quote(&self, _req: OffchainQuoteRequest) -> Result<OffchainQuoteResponse, String> {
+        Err(OffchainDexError::Unsupported(self.name().to_string()).into())
+    }
+    /// 执行交易。
+    async fn swap(&self, req: OffchainSwapRequest) -> Result<OffchainSwapResponse, String>;
 }
@@ -4,14 +4,14 @@ use std::sync::Arc;
 
 #[cfg(test)]
 mod tests {
-    use crate::rest;
+    use dex_offchain::rest;
     use actix_web::{test, App};
 
     #[actix_rt::test]
     async fn test_health_check() {
-        let mut app = test::init_service(App::new().configure(rest::configure)).await;
+        let app = test::init_service(App::new().configure(rest::configure)).await;
         let req = test::TestRequest::get().uri("/health/dex").to_request();
-        let resp = test::call_service(&mut app, req).await;
+        let resp = test::call_service(&app, req).await;
         assert!(resp.status().is_success());
     }
 }
@@ -45,3 +45,252 @@ async fn test_offchain_dex_factory_register_and_swap() {
     assert_eq!(resp.amount_out, 20);
     assert_eq!(resp.tx_hash, "dummyhash");
 }
+
+#[tokio::test]
+async fn test_rest_dex_adapter_against_mock_server() {
+    use dex_offchain::rest::{RestClient, RestDexAdapter};
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/swap"))
+        .and(header("authorization", "secret"))
+        .and(body_json(serde_json::json!({"token_in": "USDC", "token_out": "SOL", "amount_in": 1_000_000})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"amount_out": 6_850, "tx_hash": "5h1t"})))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/quote"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("maintenance"))
+        .mount(&server)
+        .await;
+
+    let adapter = RestDexAdapter::new("remote", RestClient::new(&server.uri(), "secret", 5));
+    let factory = OffchainDexFactory::new();
+    factory.register("remote", Arc::new(adapter));
+    let remote = factory.get("remote").unwrap();
+    let resp = remote
+        .swap(OffchainSwapRequest { token_in: "USDC".to_string(), token_out: "SOL".to_string(), amount_in: 1_000_000 })
+        .await
+        .unwrap();
+    assert_eq!(resp, OffchainSwapResponse { amount_out: 6_850, tx_hash: "5h1t".to_string() });
+
+    let err = remote
+        .quote(OffchainQuoteRequest { token_in: "USDC".to_string(), token_out: "SOL".to_string(), amount_in: 1, slippage_bps: 50 })
+        .await
+        .unwrap_err();
+    assert_eq!(err, "upstream returned 503: maintenance");
+    assert!(factory.unregister("remote").is_some());
+    assert!(factory.get("remote").is_none());
+}
+
+#[actix_rt::test]
+async fn test_swap_route_uses_registered_adapter() {
+    use actix_web::{test, App};
+    use dex_offchain::factory::OFFCHAIN_DEX_FACTORY;
+
+    OFFCHAIN_DEX_FACTORY.register("dummy_route", Arc::new(DummyDex));
+    let app = test::init_service(App::new().configure(dex_offchain::rest::configure)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/swap")
+        .set_json(serde_json::json!({"dex_type": "dummy_route", "input_token": "A", "output_token": "B", "amount_in": 21}))
+        .to_request();
+    let resp: OffchainSwapResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp.amount_out, 42);
+
+    // DummyDex 不支持询价，带 min_out 的请求在交易前被拒绝
+    let req = test::TestRequest::post()
+        .uri("/swap")
+        .set_json(serde_json::json!({"dex_type": "dummy_route", "input_token": "A", "output_token": "B", "amount_in": 21, "min_out": 40}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 502);
+
+    let req = test::TestRequest::post()
+        .uri("/swap")
+        .set_json(serde_json::json!({"dex_type": "missing", "input_token": "A", "output_token": "B", "amount_in": 1}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+const JUPITER_QUOTE_FIXTURE: &str = include_str!("fixtures/jupiter_quote.json");
+const JUPITER_SWAP_INSTRUCTIONS_FIXTURE: &str = include_str!("fixtures/jupiter_swap_instructions.json");
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// 以录制的 Jupiter 响应启动本地 mock 服务。
+async fn jupiter_mock_server() -> wiremock::MockServer {
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let quote: serde_json::Value = serde_json::from_str(JUPITER_QUOTE_FIXTURE).unwrap();
+    Mock::given(method("GET"))
+        .and(path("/quote"))
+        .and(query_param("inputMint", SOL_MINT))
+        .and(query_param("outputMint", USDC_MINT))
+        .and(query_param("amount", "1000000000"))
+        .and(query_param("slippageBps", "50"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(JUPITER_QUOTE_FIXTURE))
+        .mount(&server)
+        .await;
+    // 报价须原样回传
+    Mock::given(method("POST"))
+        .and(path("/swap-instructions"))
+        .and(body_partial_json(serde_json::json!({
+            "quoteResponse": quote,
+            "userPublicKey": "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(JUPITER_SWAP_INSTRUCTIONS_FIXTURE))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_jupiter_quote_parses_route_plan() {
+    use dex_offchain::jupiter::JupiterOffchainAdapter;
+    use dex_offchain::rest::RestClient;
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    let server = jupiter_mock_server().await;
+    let adapter = JupiterOffchainAdapter::new(RestClient::new(&server.uri(), "", 5));
+    let sol = Pubkey::from_str(SOL_MINT).unwrap();
+    let usdc = Pubkey::from_str(USDC_MINT).unwrap();
+
+    let quote = adapter.get_quote(&sol, &usdc, 1_000_000_000, 50).await.unwrap();
+    assert_eq!(quote.in_amount, 1_000_000_000);
+    assert_eq!(quote.out_amount, 150_000_000);
+    assert_eq!(quote.other_amount_threshold, 149_250_000);
+    assert_eq!(quote.slippage_bps, 50);
+    assert_eq!(quote.price_impact_bps(), 12);
+    assert_eq!(quote.route_plan.len(), 2);
+    assert_eq!(quote.route_plan[0].percent, 60);
+    assert_eq!(quote.route_plan[1].swap_info.amm_key, "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2");
+    assert_eq!(quote.route_labels(), vec!["Whirlpool".to_string(), "Raydium".to_string()]);
+    assert_eq!(quote.total_fees(), vec![(SOL_MINT.to_string(), 1_300_000)]);
+
+    // 通过 trait 查询时映射为通用报价
+    let resp = adapter
+        .quote(OffchainQuoteRequest { token_in: SOL_MINT.to_string(), token_out: USDC_MINT.to_string(), amount_in: 1_000_000_000, slippage_bps: 50 })
+        .await
+        .unwrap();
+    assert_eq!(
+        resp,
+        OffchainQuoteResponse {
+            amount_out: 150_000_000,
+            min_amount_out: 149_250_000,
+            price_impact_bps: 12,
+            route: vec!["Whirlpool".to_string(), "Raydium".to_string()],
+        }
+    );
+
+    // 非 mint 地址在请求前即被拒绝
+    let err = adapter
+        .quote(OffchainQuoteRequest { token_in: "SOL".to_string(), token_out: USDC_MINT.to_string(), amount_in: 1, slippage_bps: 50 })
+        .await
+        .unwrap_err();
+    assert_eq!(err, "invalid request: token_in is not a mint: SOL");
+}
+
+#[tokio::test]
+async fn test_jupiter_swap_instructions_convert_to_solana_instructions() {
+    use dex_offchain::jupiter::JupiterOffchainAdapter;
+    use dex_offchain::rest::RestClient;
+    use solana_program::instruction::AccountMeta;
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    let server = jupiter_mock_server().await;
+    let adapter = JupiterOffchainAdapter::new(RestClient::new(&server.uri(), "", 5));
+    let sol = Pubkey::from_str(SOL_MINT).unwrap();
+    let usdc = Pubkey::from_str(USDC_MINT).unwrap();
+    let user = Pubkey::from_str("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM").unwrap();
+
+    let quote = adapter.get_quote(&sol, &usdc, 1_000_000_000, 50).await.unwrap();
+    let ixs = adapter.swap_instructions(&quote, &user).await.unwrap();
+
+    let compute_budget = Pubkey::from_str("ComputeBudget111111111111111111111111111111").unwrap();
+    assert_eq!(ixs.compute_budget_instructions.len(), 2);
+    assert_eq!(ixs.compute_budget_instructions[0].program_id, compute_budget);
+    // SetComputeUnitLimit(1_400_000)
+    assert_eq!(ixs.compute_budget_instructions[0].data, vec![2, 0xc0, 0x5c, 0x15, 0x00]);
+    assert!(ixs.token_ledger_instruction.is_none());
+
+    let swap = &ixs.swap_instruction;
+    assert_eq!(swap.program_id, Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap());
+    assert_eq!(swap.accounts.len(), 7);
+    assert_eq!(swap.accounts[1], AccountMeta::new_readonly(user, true));
+    assert_eq!(
+        swap.accounts[3],
+        AccountMeta::new(Pubkey::from_str("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ").unwrap(), false)
+    );
+    assert_eq!(&swap.data[..8], &[229, 23, 203, 151, 122, 227, 173, 42]);
+
+    let cleanup = ixs.cleanup_instruction.as_ref().unwrap();
+    assert_eq!(cleanup.data, vec![9]); // CloseAccount
+    assert_eq!(
+        ixs.address_lookup_table_addresses,
+        vec![Pubkey::from_str("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB").unwrap()]
+    );
+
+    // 执行顺序：计算预算 -> 准备 -> 交换 -> 清理
+    let all = ixs.all_instructions();
+    assert_eq!(all.len(), 5);
+    assert_eq!(all[3], *swap);
+    assert_eq!(all[4], *cleanup);
+}
+
+#[test]
+fn test_jupiter_rejects_malformed_instruction() {
+    use dex_offchain::jupiter::JupiterInstruction;
+    use solana_program::instruction::Instruction;
+
+    let ix = JupiterInstruction {
+        program_id: "ComputeBudget111111111111111111111111111111".to_string(),
+        accounts: vec![],
+        data: "not base64!".to_string(),
+    };
+    assert!(Instruction::try_from(&ix).unwrap_err().to_string().starts_with("failed to decode response: invalid instruction data"));
+
+    let ix = JupiterInstruction { program_id: "bogus".to_string(), accounts: vec![], data: "AQ==".to_string() };
+    assert!(Instruction::try_from(&ix).unwrap_err().to_string().contains("invalid pubkey bogus"));
+}
+
+#[tokio::test]
+async fn test_rest_dex_adapter_retries_quote_per_config() {
+    use dex_offchain::config::DexServiceConfig;
+    use dex_offchain::rest::RestDexAdapter;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/quote"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/quote"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"amount_out": 990, "min_amount_out": 985, "price_impact_bps": 3, "route": ["remote"]})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let cfg: DexServiceConfig = toml::from_str(&format!(
+        "[[adapters]]\nname = \"remote\"\napi_url = \"{}\"\nmax_retries = 1\n",
+        server.uri()
+    ))
+    .unwrap();
+    assert_eq!(cfg.adapters[0].max_retries, 1);
+    let adapter = RestDexAdapter::new("remote", cfg.adapters[0].rest_client());
+    let resp = adapter
+        .quote(OffchainQuoteRequest { token_in: "USDC".to_string(), token_out: "SOL".to_string(), amount_in: 1_000, slippage_bps: 50 })
+        .await
+        .unwrap(); // 首次 502 后重试成功
+    assert_eq!(resp.amount_out, 990);
+}
@@ -0,0 +1,178 @@
+//!
+//! Offchain Integration Bridge Module
+//!
+//! 本模块将 dex-offchain、oracle-offchain 的异步适配器转换为本 SDK 的 trait 对象：请求/响应类型逐字段转换，
+//! 以字符串返回的适配器错误按内容还原为 `OffchainIntegrationError`（不支持的操作保持 Unsupported，其余归为 DEX/预言机失败）。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use dex_offchain::{OffchainDexError, OffchainQuoteRequest, OffchainQuoteResponse, OffchainSwapRequest, OffchainSwapResponse};
+use oracle_offchain::{OffchainOracleError, OffchainPriceRequest, OffchainPriceResponse, OffchainTwapRequest, OffchainTwapResponse};
+
+use crate::dex::{DexQuote, DexTradeParams, DexTradeResult};
+use crate::error::OffchainIntegrationError;
+use crate::oracles::{OracleQueryParams, OracleQueryResult};
+use crate::traits::{OffchainDexAdapter, OffchainOracleAdapter};
+
+impl From<&DexTradeParams> for OffchainQuoteRequest {
+    fn from(params: &DexTradeParams) -> Self {
+        Self {
+            token_in: params.from_token.clone(),
+            token_out: params.to_token.clone(),
+            amount_in: params.amount,
+            slippage_bps: params.slippage_bps,
+        }
+    }
+}
+
+impl From<&DexTradeParams> for OffchainSwapRequest {
+    fn from(params: &DexTradeParams) -> Self {
+        Self { token_in: params.from_token.clone(), token_out: params.to_token.clone(), amount_in: params.amount }
+    }
+}
+
+impl From<OffchainQuoteResponse> for DexQuote {
+    fn from(resp: OffchainQuoteResponse) -> Self {
+        Self {
+            amount_out: resp.amount_out,
+            min_amount_out: resp.min_amount_out,
+            price_impact_bps: resp.price_impact_bps,
+            route: resp.route,
+        }
+    }
+}
+
+impl From<OffchainSwapResponse> for DexTradeResult {
+    fn from(resp: OffchainSwapResponse) -> Self {
+        Self { amount_out: resp.amount_out, tx_hash: resp.tx_hash }
+    }
+}
+
+impl From<&OracleQueryParams> for OffchainPriceRequest {
+    fn from(params: &OracleQueryParams) -> Self {
+        Self { symbol: params.token.clone() }
+    }
+}
+
+impl From<OffchainPriceResponse> for OracleQueryResult {
+    fn from(resp: OffchainPriceResponse) -> Self {
+        Self { price: resp.price, timestamp: resp.last_updated }
+    }
+}
+
+impl From<OffchainTwapResponse> for OracleQueryResult {
+    fn from(resp: OffchainTwapResponse) -> Self {
+        Self { price: resp.twap, timestamp: resp.last_updated }
+    }
+}
+
+/// dex-offchain 适配器桥接。
+#[derive(Clone)]
+pub struct DexOffchainBridge {
+    inner: Arc<dyn dex_offchain::OffchainDexAdapter>, // 被桥接的适配器
+}
+
+impl DexOffchainBridge {
+    /// 包装 dex-offchain 适配器。
+    pub fn new(inner: Arc<dyn dex_offchain::OffchainDexAdapter>) -> Self {
+        Self { inner }
+    }
+    /// 从 dex-offchain 全局注册表按名称获取并包装适配器。
+    pub fn from_registry(name: &str) -> Result<Self, OffchainIntegrationError> {
+        dex_offchain::OFFCHAIN_DEX_FACTORY
+            .get(name)
+            .map(Self::new)
+            .ok_or_else(|| OffchainIntegrationError::AdapterNotFound(name.to_string()))
+    }
+    /// 还原适配器错误。
+    fn map_error(&self, message: String) -> OffchainIntegrationError {
+        if message == OffchainDexError::Unsupported(self.inner.name().to_string()).to_string() {
+            OffchainIntegrationError::Unsupported(self.inner.name().to_string())
+        } else {
+            OffchainIntegrationError::DexOperationFailed(message)
+        }
+    }
+}
+
+impl From<Arc<dyn dex_offchain::OffchainDexAdapter>> for DexOffchainBridge {
+    fn from(inner: Arc<dyn dex_offchain::OffchainDexAdapter>) -> Self {
+        Self::new(inner)
+    }
+}
+
+#[async_trait]
+impl OffchainDexAdapter for DexOffchainBridge {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+    async fn quote(&self, params: &DexTradeParams) -> Result<DexQuote, OffchainIntegrationError> {
+        self.inner.quote(params.into()).await.map(DexQuote::from).map_err(|e| self.map_error(e))
+    }
+    async fn swap(&self, params: &DexTradeParams) -> Result<DexTradeResult, OffchainIntegrationError> {
+        self.inner.swap(params.into()).await.map(DexTradeResult::from).map_err(|e| self.map_error(e))
+    }
+}
+
+/// oracle-offchain 适配器桥接；无效价格（非有限或非正）返回 InvalidOracleData。
+#[derive(Clone)]
+pub struct OracleOffchainBridge {
+    inner: Arc<dyn oracle_offchain::OffchainOracleAdapter>, // 被桥接的适配器
+}
+
+impl OracleOffchainBridge {
+    /// 包装 oracle-offchain 适配器。
+    pub fn new(inner: Arc<dyn oracle_offchain::OffchainOracleAdapter>) -> Self {
+        Self { inner }
+    }
+    /// 从 oracle-offchain 全局注册表按名称获取并包装适配器。
+    pub fn from_registry(name: &str) -> Result<Self, OffchainIntegrationError> {
+        oracle_offchain::OFFCHAIN_ORACLE_FACTORY
+            .get(name)
+            .map(Self::new)
+            .ok_or_else(|| OffchainIntegrationError::AdapterNotFound(name.to_string()))
+    }
+    /// 还原适配器错误。
+    fn map_error(&self, message: String) -> OffchainIntegrationError {
+        if message == OffchainOracleError::Unsupported(self.inner.name().to_string()).to_string() {
+            OffchainIntegrationError::Unsupported(self.inner.name().to_string())
+        } else {
+            OffchainIntegrationError::OracleQueryFailed(message)
+        }
+    }
+    /// 校验价格有效性。
+    fn validate(&self, result: OracleQueryResult, token: &str) -> Result<OracleQueryResult, OffchainIntegrationError> {
+        if result.is_valid() {
+            Ok(result)
+        } else {
+            Err(OffchainIntegrationError::InvalidOracleData(format!(
+                "{} returned price {} for {}",
+                self.inner.name(),
+                result.price,
+                token
+            )))
+        }
+    }
+}
+
+impl From<Arc<dyn oracle_offchain::OffchainOracleAdapter>> for OracleOffchainBridge {
+    fn from(inner: Arc<dyn oracle_offchain::OffchainOracleAdapter>) -> Self {
+        Self::new(inner)
+    }
+}
+
+#[async_trait]
+impl OffchainOracleAdapter for OracleOffchainBridge {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+    async fn get_price(&self, params: &OracleQueryParams) -> Result<OracleQueryResult, OffchainIntegrationError> {
+        let result = self.inner.get_price(params.into()).await.map_err(|e| self.map_error(e))?;
+        self.validate(result.into(), &params.token)
+    }
+    async fn get_twap(&self, params: &OracleQueryParams, window_secs: u64) -> Result<OracleQueryResult, OffchainIntegrationError> {
+        let req = OffchainTwapRequest { symbol: params.token.clone(), window_secs };
+        let result = self.inner.get_twap(req).await.map_err(|e| self.map_error(e))?;
+        self.validate(result.into(), &params.token)
+    }
+}
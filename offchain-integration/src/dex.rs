@@ -1,28 +1,44 @@
 //!
 //! Offchain Integration DEX Module
 //!
-//! 本模块实现离线集成服务的 DEX 相关逻辑，支持报价、交易等功能，确保与链下 DEX 交互的合规性与安全性。
+//! 本模块定义离线集成服务的 DEX 报价、交易参数与结果类型，确保与链下 DEX 交互的合规性与安全性。
+
+use serde::{Deserialize, Serialize};
 
 /// DEX 交易参数结构体。
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DexTradeParams {
     pub from_token: String,    // 源代币
     pub to_token: String,      // 目标代币
-    pub amount: u64,           // 交易数量
+    pub amount: u64,           // 交易数量（最小单位）
+    #[serde(default)]
+    pub slippage_bps: u16,     // 允许滑点（基点）
+}
+
+impl DexTradeParams {
+    /// 创建交易参数（滑点为 0）。
+    pub fn new(from_token: &str, to_token: &str, amount: u64) -> Self {
+        Self { from_token: from_token.to_string(), to_token: to_token.to_string(), amount, slippage_bps: 0 }
+    }
+    /// 设置允许滑点。
+    pub fn with_slippage_bps(mut self, slippage_bps: u16) -> Self {
+        self.slippage_bps = slippage_bps;
+        self
+    }
+}
+
+/// DEX 报价结构体。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DexQuote {
+    pub amount_out: u64,       // 预期获得数量
+    pub min_amount_out: u64,   // 扣除滑点后的最小获得数量
+    pub price_impact_bps: u64, // 价格冲击（基点）
+    pub route: Vec<String>,    // 路由经过的市场/DEX 标签
 }
 
 /// DEX 交易结果结构体。
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DexTradeResult {
-    pub success: bool,         // 交易是否成功
     pub amount_out: u64,       // 实际获得数量
     pub tx_hash: String,       // 交易哈希
 }
-
-/// DEX 适配器 trait。
-pub trait DexAdapter {
-    /// 查询报价。
-    fn quote(&self, params: &DexTradeParams) -> u64;
-    /// 执行交易。
-    fn swap(&self, params: &DexTradeParams) -> DexTradeResult;
-}
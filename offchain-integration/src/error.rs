@@ -2,22 +2,88 @@
 //! Offchain Integration Error Definitions
 //!
 //! 本模块定义离线集成服务的错误类型、错误码与 trait 实现，确保错误处理合规、可追溯、可维护。
+//! dex-offchain、oracle-offchain 的错误经 `From` 统一转换为 `OffchainIntegrationError`。
+
+use dex_offchain::OffchainDexError;
+use oracle_offchain::OffchainOracleError;
+use thiserror::Error;
 
 /// 离线集成错误类型枚举。
-#[derive(Debug)]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
 pub enum OffchainIntegrationError {
     /// 未知错误。
-    Unknown,
+    #[error("unknown error: {0}")]
+    Unknown(String),
     /// 网络请求失败。
-    NetworkError,
+    #[error("network error: {0}")]
+    NetworkError(String),
+    /// 上游 API 返回非 2xx 状态码。
+    #[error("upstream returned {status}: {message}")]
+    Upstream { status: u16, message: String },
+    /// 上游熔断中，暂不可用。
+    #[error("upstream unavailable: {0}")]
+    Unavailable(String),
     /// 数据解析失败。
-    ParseError,
+    #[error("failed to parse data: {0}")]
+    ParseError(String),
     /// 权限不足。
-    Unauthorized,
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
     /// 输入参数无效。
-    InvalidInput,
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
     /// 预言机数据无效。
-    InvalidOracleData,
+    #[error("invalid oracle data: {0}")]
+    InvalidOracleData(String),
+    /// 预言机查询失败。
+    #[error("oracle query failed: {0}")]
+    OracleQueryFailed(String),
     /// DEX 操作失败。
-    DexOperationFailed,
+    #[error("dex operation failed: {0}")]
+    DexOperationFailed(String),
+    /// 适配器未注册。
+    #[error("adapter not registered: {0}")]
+    AdapterNotFound(String),
+    /// 适配器不支持该操作。
+    #[error("operation not supported by {0}")]
+    Unsupported(String),
+}
+
+/// 上游状态码映射：401/403 视为权限不足。
+fn upstream(status: u16, message: String) -> OffchainIntegrationError {
+    match status {
+        401 | 403 => OffchainIntegrationError::Unauthorized(message),
+        _ => OffchainIntegrationError::Upstream { status, message },
+    }
+}
+
+impl From<OffchainDexError> for OffchainIntegrationError {
+    fn from(err: OffchainDexError) -> Self {
+        match err {
+            OffchainDexError::Http(message) => OffchainIntegrationError::NetworkError(message),
+            OffchainDexError::Api { status, message } => upstream(status, message),
+            OffchainDexError::Decode(message) => OffchainIntegrationError::ParseError(message),
+            OffchainDexError::AdapterNotFound(name) => OffchainIntegrationError::AdapterNotFound(name),
+            OffchainDexError::InvalidRequest(message) => OffchainIntegrationError::InvalidInput(message),
+            OffchainDexError::Unsupported(name) => OffchainIntegrationError::Unsupported(name),
+            OffchainDexError::Config(message) => OffchainIntegrationError::Unknown(message),
+            OffchainDexError::Unavailable(base_url) => OffchainIntegrationError::Unavailable(base_url),
+        }
+    }
+}
+
+impl From<OffchainOracleError> for OffchainIntegrationError {
+    fn from(err: OffchainOracleError) -> Self {
+        match err {
+            OffchainOracleError::Http(message) => OffchainIntegrationError::NetworkError(message),
+            OffchainOracleError::Api { status, message } => upstream(status, message),
+            OffchainOracleError::Decode(message) => OffchainIntegrationError::ParseError(message),
+            OffchainOracleError::AdapterNotFound(name) => OffchainIntegrationError::AdapterNotFound(name),
+            OffchainOracleError::NoData(symbol) => OffchainIntegrationError::InvalidOracleData(format!("no price data for {}", symbol)),
+            OffchainOracleError::InvalidRequest(message) => OffchainIntegrationError::InvalidInput(message),
+            OffchainOracleError::Unsupported(name) => OffchainIntegrationError::Unsupported(name),
+            OffchainOracleError::Config(message) => OffchainIntegrationError::Unknown(message),
+            OffchainOracleError::Unavailable(base_url) => OffchainIntegrationError::Unavailable(base_url),
+        }
+    }
 }
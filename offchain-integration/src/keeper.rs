@@ -0,0 +1,633 @@
+//!
+//! Offchain Integration Keeper Module
+//!
+//! 本模块实现链下 keeper：从交易日志中解码程序发出的 OffchainDexRequest / OffchainOracleRequest 事件，
+//! 分发给已注册的 DEX/预言机适配器，并构建由响应者签名的 `submit_offchain_response` 指令与交易。
+//! 仅处理指定本 keeper 为响应者的请求；swap 会动用 keeper 资金，仅对白名单请求人执行，
+//! 且执行前经 `AccountSource` 读取链上 PendingRequest，确认请求仍待响应（重启或日志回放后不会重复成交）。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_keypair::Keypair;
+use solana_program::hash::Hash;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use thiserror::Error;
+
+use crate::dex::DexTradeParams;
+use crate::error::OffchainIntegrationError;
+use crate::oracles::OracleQueryParams;
+use crate::traits::{OffchainDexAdapter, OffchainOracleAdapter};
+
+/// 链下请求 PDA 种子前缀（与链上 PendingRequest 一致）。
+pub const PENDING_REQUEST_SEED: &[u8] = b"pending_request";
+/// 响应结果最大字节数（与链上 MAX_OFFCHAIN_RESULT_LEN 一致）。
+pub const MAX_RESPONSE_RESULT_LEN: usize = 256;
+/// 错误信息最大长度（与链上 MAX_OFFCHAIN_ERROR_LEN 一致）。
+pub const MAX_RESPONSE_ERROR_LEN: usize = 128;
+
+/// 程序事件日志前缀。
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// keeper 错误类型。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum KeeperError {
+    /// 事件数据不是合法 base64。
+    #[error("invalid event log: {0}")]
+    InvalidLog(String),
+    /// 事件数据解码失败。
+    #[error("failed to decode event: {0}")]
+    Decode(String),
+    /// 请求参数无效。
+    #[error("invalid request params: {0}")]
+    InvalidParams(String),
+    /// 适配器未注册。
+    #[error("adapter not registered: {0}")]
+    AdapterNotFound(String),
+    /// 操作不受支持。
+    #[error("unsupported action {action} for {target}")]
+    UnsupportedAction { target: String, action: String },
+    /// 适配器执行失败。
+    #[error("adapter {0} failed: {1}")]
+    AdapterFailed(String, String),
+    /// 响应结果过长。
+    #[error("response result too large: {0} bytes")]
+    ResultTooLarge(usize),
+    /// 请求人不在 swap 白名单内。
+    #[error("requester {0} not allowed to swap")]
+    RequesterNotAllowed(Pubkey),
+    /// 未配置链上账户读取，无法确认 swap 请求状态。
+    #[error("no account source to verify pending request")]
+    AccountSourceMissing,
+    /// 读取链上账户失败。
+    #[error("failed to fetch account {0}: {1}")]
+    AccountFetch(Pubkey, String),
+    /// 链上请求不存在、已响应或与事件不一致。
+    #[error("pending request {0} not awaiting response")]
+    RequestNotPending(Pubkey),
+}
+
+/// 链上 OffchainDexRequest 事件（字段顺序与链上定义一致）。
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct OffchainDexRequestEvent {
+    pub request_id: u64,   // 请求编号
+    pub dex_name: String,  // DEX 名称
+    pub action: String,    // 操作类型（swap/quote）
+    pub params: Vec<u8>,   // 请求参数（JSON）
+    pub requester: Pubkey, // 请求人
+    pub timestamp: i64,    // 请求时间
+    pub responder: Pubkey, // 指定响应者
+    pub expires_at: i64,   // 过期时间
+}
+
+/// 链上 OffchainOracleRequest 事件（字段顺序与链上定义一致）。
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct OffchainOracleRequestEvent {
+    pub request_id: u64,     // 请求编号
+    pub oracle_name: String, // 预言机名称
+    pub action: String,      // 操作类型（price/twap）
+    pub params: Vec<u8>,     // 请求参数（JSON）
+    pub requester: Pubkey,   // 请求人
+    pub timestamp: i64,      // 请求时间
+    pub responder: Pubkey,   // 指定响应者
+    pub expires_at: i64,     // 过期时间
+}
+
+/// 链上 OffchainResponse 事件（字段顺序与链上定义一致）。
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct OffchainResponseEvent {
+    pub request_id: u64,           // 请求编号
+    pub success: bool,             // 是否成功
+    pub result: Vec<u8>,           // 结果数据（JSON）
+    pub error_msg: Option<String>, // 错误描述
+    pub responder: Pubkey,         // 响应者
+    pub timestamp: i64,            // 响应时间
+}
+
+/// 解码后的链下事件。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OffchainEvent {
+    DexRequest(OffchainDexRequestEvent),       // DEX 请求
+    OracleRequest(OffchainOracleRequestEvent), // 预言机请求
+    Response(OffchainResponseEvent),           // 响应
+}
+
+/// Anchor 事件判别码：sha256("event:<Name>") 前 8 字节。
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    sighash("event", name)
+}
+
+/// Anchor 账户判别码：sha256("account:<Name>") 前 8 字节。
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    sighash("account", name)
+}
+
+/// Anchor 指令判别码：sha256("global:<name>") 前 8 字节。
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    sighash("global", name)
+}
+
+fn sighash(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("{}:{}", namespace, name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// 将事件编码为 Anchor 事件数据（判别码 + borsh），用于日志回放与测试。
+pub fn encode_event(event: &OffchainEvent) -> Vec<u8> {
+    let (name, body) = match event {
+        OffchainEvent::DexRequest(e) => ("OffchainDexRequest", borsh::to_vec(e)),
+        OffchainEvent::OracleRequest(e) => ("OffchainOracleRequest", borsh::to_vec(e)),
+        OffchainEvent::Response(e) => ("OffchainResponse", borsh::to_vec(e)),
+    };
+    let mut data = event_discriminator(name).to_vec();
+    data.extend(body.unwrap_or_default());
+    data
+}
+
+/// 解码单条事件数据；非本模块关注的事件返回 None。
+pub fn decode_event(data: &[u8]) -> Result<Option<OffchainEvent>, KeeperError> {
+    if data.len() < 8 {
+        return Err(KeeperError::Decode(format!("event data too short: {} bytes", data.len())));
+    }
+    let (discriminator, mut body) = data.split_at(8);
+    let decode_err = |e: std::io::Error| KeeperError::Decode(e.to_string());
+    let event = if discriminator == event_discriminator("OffchainDexRequest") {
+        OffchainEvent::DexRequest(OffchainDexRequestEvent::deserialize(&mut body).map_err(decode_err)?)
+    } else if discriminator == event_discriminator("OffchainOracleRequest") {
+        OffchainEvent::OracleRequest(OffchainOracleRequestEvent::deserialize(&mut body).map_err(decode_err)?)
+    } else if discriminator == event_discriminator("OffchainResponse") {
+        OffchainEvent::Response(OffchainResponseEvent::deserialize(&mut body).map_err(decode_err)?)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(event))
+}
+
+/// 运行时输出的调用帧日志行。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameLog {
+    Invoke(Pubkey), // `Program <id> invoke [n]`
+    Exit(Pubkey),   // `Program <id> success` / `Program <id> failed: ...`
+}
+
+/// 严格解析调用帧日志行；`Program log:`/`data:`/`return:` 等其他行返回 None。
+fn parse_frame_log(line: &str) -> Option<FrameLog> {
+    let rest = line.strip_prefix("Program ")?;
+    let (id, tail) = rest.split_once(' ')?;
+    let id = id.parse::<Pubkey>().ok()?; // log:/data:/return: 等前缀不是合法 Pubkey
+    if let Some(depth) = tail.strip_prefix("invoke [").and_then(|t| t.strip_suffix(']')) {
+        return depth.parse::<u32>().ok().map(|_| FrameLog::Invoke(id));
+    }
+    if tail == "success" || tail.starts_with("failed: ") {
+        return Some(FrameLog::Exit(id));
+    }
+    None
+}
+
+/// 从一笔交易的日志中解析本程序发出的链下事件。
+/// - 仅按 `Program <id> invoke [n]` / `success` / `failed: ...` 维护调用栈，采纳栈顶为 program_id 时输出的 `Program data:` 行
+/// - `Program log:`/`Program return:` 等行（含程序自行输出的伪造帧文本）不影响调用栈
+/// - 退出帧与栈顶不一致时视为日志不可信，清空调用栈
+/// - 无法解码的事件记录后跳过，不影响同一交易中的其他事件
+pub fn parse_events(program_id: &Pubkey, logs: &[String]) -> Vec<OffchainEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if stack.last() != Some(program_id) {
+                continue;
+            }
+            let decoded = BASE64
+                .decode(data.trim())
+                .map_err(|e| KeeperError::InvalidLog(e.to_string()))
+                .and_then(|bytes| decode_event(&bytes));
+            match decoded {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("[Keeper] skip event: {}", e),
+            }
+            continue;
+        }
+        let Some(frame) = parse_frame_log(line) else {
+            continue;
+        };
+        match frame {
+            FrameLog::Invoke(id) => stack.push(id),
+            FrameLog::Exit(id) => {
+                if stack.pop() != Some(id) {
+                    tracing::warn!("[Keeper] unbalanced program frame: {}", line);
+                    stack.clear();
+                }
+            }
+        }
+    }
+    events
+}
+
+/// 链下请求 PDA 地址。
+pub fn pending_request_address(program_id: &Pubkey, requester: &Pubkey, request_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PENDING_REQUEST_SEED, requester.as_ref(), &request_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// 链上 OffchainRequestKind（变体顺序与链上定义一致）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum PendingRequestKind {
+    Dex,    // DEX 请求
+    Oracle, // 预言机请求
+}
+
+/// 链上 PendingRequestStatus（变体顺序与链上定义一致）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum PendingRequestStatus {
+    Pending,   // 等待响应
+    Fulfilled, // 已成功响应
+    Failed,    // 已响应失败
+}
+
+/// 链上 PendingRequest 账户（字段顺序与链上定义一致）。
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct PendingRequestAccount {
+    pub request_id: u64,              // 请求编号
+    pub requester: Pubkey,            // 请求人
+    pub responder: Pubkey,            // 指定响应者
+    pub kind: PendingRequestKind,     // 请求类别
+    pub target: String,               // 目标 DEX/预言机名称
+    pub action: String,               // 操作类型
+    pub params: Vec<u8>,              // 请求参数（JSON）
+    pub status: PendingRequestStatus, // 请求状态
+    pub result: Vec<u8>,              // 响应结果
+    pub error_msg: String,            // 错误信息
+    pub created_at: i64,              // 创建时间
+    pub expires_at: i64,              // 过期时间
+    pub responded_at: i64,            // 响应时间（未响应为 0）
+    pub bump: u8,                     // PDA bump
+}
+
+impl PendingRequestAccount {
+    /// 编码为 Anchor 账户数据（判别码 + borsh），用于测试。
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = account_discriminator("PendingRequest").to_vec();
+        data.extend(borsh::to_vec(self).unwrap_or_default());
+        data
+    }
+    /// 解码 Anchor 账户数据（忽略 InitSpace 预留的尾部空间）。
+    pub fn decode(data: &[u8]) -> Result<Self, KeeperError> {
+        if data.len() < 8 || data[..8] != account_discriminator("PendingRequest") {
+            return Err(KeeperError::Decode("not a PendingRequest account".to_string()));
+        }
+        Self::deserialize(&mut &data[8..]).map_err(|e| KeeperError::Decode(e.to_string()))
+    }
+    /// 是否仍在等待响应。
+    pub fn is_awaiting_response(&self) -> bool {
+        self.status == PendingRequestStatus::Pending && self.responded_at == 0
+    }
+}
+
+/// 链上账户读取（如 RPC getAccountInfo）。
+#[async_trait]
+pub trait AccountSource: Send + Sync {
+    /// 读取账户数据；账户不存在返回 None。
+    async fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, OffchainIntegrationError>;
+}
+
+/// submit_offchain_response 指令参数（borsh 顺序与链上一致）。
+#[derive(BorshSerialize)]
+struct SubmitOffchainResponseArgs {
+    request_id: u64,
+    success: bool,
+    result: Vec<u8>,
+    error_msg: Option<String>,
+}
+
+/// keeper 对一次请求的响应。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeeperResponse {
+    pub requester: Pubkey,         // 请求人（PDA 推导）
+    pub request_id: u64,           // 请求编号
+    pub success: bool,             // 是否成功
+    pub result: Vec<u8>,           // 结果数据（JSON）
+    pub error_msg: Option<String>, // 错误描述
+}
+
+/// 构建 submit_offchain_response 指令（账户：请求 PDA 可写、响应者签名）。
+pub fn submit_offchain_response_ix(program_id: &Pubkey, responder: &Pubkey, response: &KeeperResponse) -> Instruction {
+    let (pending_request, _) = pending_request_address(program_id, &response.requester, response.request_id);
+    let args = SubmitOffchainResponseArgs {
+        request_id: response.request_id,
+        success: response.success,
+        result: response.result.clone(),
+        error_msg: response.error_msg.clone(),
+    };
+    let mut data = instruction_discriminator("submit_offchain_response").to_vec();
+    data.extend(borsh::to_vec(&args).unwrap_or_default());
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![AccountMeta::new(pending_request, false), AccountMeta::new_readonly(*responder, true)],
+        data,
+    }
+}
+
+/// DEX 请求参数（JSON）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DexRequestParams {
+    pub token_in: String,  // 输入代币
+    pub token_out: String, // 输出代币
+    pub amount_in: u64,    // 输入数量
+    #[serde(default)]
+    pub slippage_bps: u16, // 允许滑点（基点）
+}
+
+/// TWAP 请求默认时间窗口（秒）。
+pub const DEFAULT_TWAP_WINDOW_SECS: u64 = 300;
+
+fn default_twap_window_secs() -> u64 {
+    DEFAULT_TWAP_WINDOW_SECS
+}
+
+/// 预言机请求参数（JSON）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OracleRequestParams {
+    pub symbol: String,   // 交易对或代币
+    #[serde(default = "default_twap_window_secs")]
+    pub window_secs: u64, // TWAP 时间窗口（秒），仅 twap 请求使用
+}
+
+/// 一笔交易的日志（对应 RPC logsSubscribe 通知）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionLogs {
+    pub signature: String, // 交易签名
+    pub failed: bool,      // 交易是否失败（失败交易的事件不生效）
+    pub logs: Vec<String>, // 日志行
+}
+
+/// 请求事件的公共字段。
+struct RequestMeta {
+    requester: Pubkey, // 请求人
+    request_id: u64,   // 请求编号
+    responder: Pubkey, // 指定响应者
+    timestamp: i64,    // 请求时间
+    expires_at: i64,   // 过期时间
+}
+
+impl RequestMeta {
+    fn of(event: &OffchainEvent) -> Option<Self> {
+        match event {
+            OffchainEvent::DexRequest(e) => Some(Self {
+                requester: e.requester,
+                request_id: e.request_id,
+                responder: e.responder,
+                timestamp: e.timestamp,
+                expires_at: e.expires_at,
+            }),
+            OffchainEvent::OracleRequest(e) => Some(Self {
+                requester: e.requester,
+                request_id: e.request_id,
+                responder: e.responder,
+                timestamp: e.timestamp,
+                expires_at: e.expires_at,
+            }),
+            OffchainEvent::Response(_) => None,
+        }
+    }
+}
+
+/// 链下 keeper。
+pub struct Keeper {
+    program_id: Pubkey,                                             // 目标程序
+    responder: Keypair,                                             // 响应者密钥（需为请求指定的 responder）
+    dex_adapters: HashMap<String, Arc<dyn OffchainDexAdapter>>,     // DEX 名称 -> 适配器
+    oracle_adapters: HashMap<String, Arc<dyn OffchainOracleAdapter>>, // 预言机名称 -> 适配器
+    swap_requesters: HashSet<Pubkey>,                               // 允许 swap 的请求人白名单
+    accounts: Option<Arc<dyn AccountSource>>,                       // 链上账户读取（swap 前确认请求状态）
+    handled: HashMap<(Pubkey, u64), i64>,                           // 已处理的 (请求人, 请求编号) -> 过期时间
+    latest_timestamp: i64,                                          // 已见请求的最新链上时间
+}
+
+impl Keeper {
+    /// 创建 keeper。
+    pub fn new(program_id: Pubkey, responder: Keypair) -> Self {
+        Self {
+            program_id,
+            responder,
+            dex_adapters: HashMap::new(),
+            oracle_adapters: HashMap::new(),
+            swap_requesters: HashSet::new(),
+            accounts: None,
+            handled: HashMap::new(),
+            latest_timestamp: i64::MIN,
+        }
+    }
+    /// 响应者公钥。
+    pub fn responder(&self) -> Pubkey {
+        self.responder.pubkey()
+    }
+    /// 按适配器名称注册 DEX 适配器（dex-offchain 适配器经 DexOffchainBridge 转换）。
+    pub fn register_dex(&mut self, adapter: Arc<dyn OffchainDexAdapter>) {
+        self.dex_adapters.insert(adapter.name().to_string(), adapter);
+    }
+    /// 按适配器名称注册预言机适配器（oracle-offchain 适配器经 OracleOffchainBridge 转换）。
+    pub fn register_oracle(&mut self, adapter: Arc<dyn OffchainOracleAdapter>) {
+        self.oracle_adapters.insert(adapter.name().to_string(), adapter);
+    }
+    /// 允许请求人发起 swap（swap 由 keeper 资金成交，默认不对任何请求人执行）。
+    pub fn allow_swap_requester(&mut self, requester: Pubkey) {
+        self.swap_requesters.insert(requester);
+    }
+    /// 设置链上账户读取；未设置时所有 swap 请求均得到失败响应。
+    pub fn set_account_source(&mut self, source: Arc<dyn AccountSource>) {
+        self.accounts = Some(source);
+    }
+    /// 读取链上 PendingRequest，确认其与事件一致且仍待本 keeper 响应。
+    /// - 内存中的已处理记录在重启后丢失，日志回放时以链上状态为准，避免重复动用 keeper 资金
+    async fn verify_pending(&self, event: &OffchainDexRequestEvent) -> Result<(), KeeperError> {
+        let source = self.accounts.as_ref().ok_or(KeeperError::AccountSourceMissing)?;
+        let (address, _) = pending_request_address(&self.program_id, &event.requester, event.request_id);
+        let data = source
+            .account_data(&address)
+            .await
+            .map_err(|e| KeeperError::AccountFetch(address, e.to_string()))?
+            .ok_or(KeeperError::RequestNotPending(address))?; // 已关闭
+        let request = PendingRequestAccount::decode(&data)?;
+        let matches = request.request_id == event.request_id
+            && request.requester == event.requester
+            && request.responder == self.responder()
+            && request.kind == PendingRequestKind::Dex
+            && request.target == event.dex_name
+            && request.action == event.action
+            && request.params == event.params;
+        if !matches || !request.is_awaiting_response() {
+            return Err(KeeperError::RequestNotPending(address));
+        }
+        Ok(())
+    }
+    /// 已处理且尚未清理的请求数量。
+    pub fn handled_len(&self) -> usize {
+        self.handled.len()
+    }
+    /// 执行 DEX 请求，返回 JSON 结果。
+    async fn dispatch_dex(&self, event: &OffchainDexRequestEvent) -> Result<Vec<u8>, KeeperError> {
+        if event.action == "swap" && !self.swap_requesters.contains(&event.requester) {
+            return Err(KeeperError::RequesterNotAllowed(event.requester));
+        }
+        let adapter = self
+            .dex_adapters
+            .get(&event.dex_name)
+            .ok_or_else(|| KeeperError::AdapterNotFound(event.dex_name.clone()))?;
+        let params: DexRequestParams =
+            serde_json::from_slice(&event.params).map_err(|e| KeeperError::InvalidParams(e.to_string()))?;
+        let trade = DexTradeParams::new(&params.token_in, &params.token_out, params.amount_in).with_slippage_bps(params.slippage_bps);
+        let adapter_err = |e| adapter_error(&event.dex_name, &event.action, e);
+        let result = match event.action.as_str() {
+            "quote" => {
+                let quote = adapter.quote(&trade).await.map_err(adapter_err)?;
+                serde_json::json!({ "amount_out": quote.amount_out })
+            }
+            "swap" => {
+                self.verify_pending(event).await?; // 成交前确认链上请求仍待响应
+                let trade = adapter.swap(&trade).await.map_err(adapter_err)?;
+                serde_json::json!({ "amount_out": trade.amount_out, "tx_hash": trade.tx_hash })
+            }
+            action => {
+                return Err(KeeperError::UnsupportedAction { target: event.dex_name.clone(), action: action.to_string() });
+            }
+        };
+        Ok(result.to_string().into_bytes())
+    }
+    /// 执行预言机请求，返回 JSON 结果。
+    async fn dispatch_oracle(&self, event: &OffchainOracleRequestEvent) -> Result<Vec<u8>, KeeperError> {
+        let adapter = self
+            .oracle_adapters
+            .get(&event.oracle_name)
+            .ok_or_else(|| KeeperError::AdapterNotFound(event.oracle_name.clone()))?;
+        let params: OracleRequestParams =
+            serde_json::from_slice(&event.params).map_err(|e| KeeperError::InvalidParams(e.to_string()))?;
+        let query = OracleQueryParams::new(&params.symbol);
+        let adapter_err = |e| adapter_error(&event.oracle_name, &event.action, e);
+        let result = match event.action.as_str() {
+            "price" => {
+                let price = adapter.get_price(&query).await.map_err(adapter_err)?;
+                serde_json::json!({ "price": price.price, "timestamp": price.timestamp })
+            }
+            "twap" => {
+                let twap = adapter.get_twap(&query, params.window_secs).await.map_err(adapter_err)?;
+                serde_json::json!({ "twap": twap.price, "window_secs": params.window_secs, "timestamp": twap.timestamp })
+            }
+            action => {
+                return Err(KeeperError::UnsupportedAction { target: event.oracle_name.clone(), action: action.to_string() });
+            }
+        };
+        Ok(result.to_string().into_bytes())
+    }
+    /// 处理单个事件；请求事件返回响应（失败也会响应，避免请求悬挂）。
+    /// - 指定其他响应者、已过期或重复的请求以及响应事件返回 None
+    /// - 已处理记录以请求中的链上时间为时钟，过期后清理（链上已拒绝过期响应，重放无需去重）
+    pub async fn handle_event(&mut self, event: &OffchainEvent) -> Option<KeeperResponse> {
+        let meta = RequestMeta::of(event)?;
+        if meta.responder != self.responder() {
+            return None; // 指定其他 keeper 响应
+        }
+        self.latest_timestamp = self.latest_timestamp.max(meta.timestamp);
+        let now = self.latest_timestamp;
+        self.handled.retain(|_, expires_at| *expires_at >= now);
+        if meta.expires_at < now {
+            return None; // 已过期，链上不再接受响应
+        }
+        let (requester, request_id) = (meta.requester, meta.request_id);
+        if self.handled.insert((requester, request_id), meta.expires_at).is_some() {
+            return None; // 日志重放或重连导致的重复请求，避免重复执行
+        }
+        let outcome = match event {
+            OffchainEvent::DexRequest(e) => self.dispatch_dex(e).await,
+            OffchainEvent::OracleRequest(e) => self.dispatch_oracle(e).await,
+            OffchainEvent::Response(_) => return None,
+        };
+        let outcome = outcome.and_then(|result| {
+            if result.len() > MAX_RESPONSE_RESULT_LEN {
+                Err(KeeperError::ResultTooLarge(result.len()))
+            } else {
+                Ok(result)
+            }
+        });
+        Some(match outcome {
+            Ok(result) => KeeperResponse { requester, request_id, success: true, result, error_msg: None },
+            Err(e) => KeeperResponse {
+                requester,
+                request_id,
+                success: false,
+                result: Vec::new(),
+                error_msg: Some(truncate_error(e.to_string())),
+            },
+        })
+    }
+    /// 处理一笔交易日志，返回需提交的 submit_offchain_response 指令；失败交易被忽略。
+    pub async fn process_logs(&mut self, logs: &TransactionLogs) -> Vec<Instruction> {
+        if logs.failed {
+            return Vec::new();
+        }
+        let responder = self.responder();
+        let program_id = self.program_id;
+        let mut instructions = Vec::new();
+        for event in parse_events(&program_id, &logs.logs) {
+            if let Some(response) = self.handle_event(&event).await {
+                instructions.push(submit_offchain_response_ix(&program_id, &responder, &response));
+            }
+        }
+        instructions
+    }
+    /// 以响应者为付款人构建并签名交易。
+    pub fn build_transaction(&self, instructions: &[Instruction], recent_blockhash: Hash) -> Transaction {
+        Transaction::new_signed_with_payer(instructions, Some(&self.responder()), &[&self.responder], recent_blockhash)
+    }
+    /// 消费日志流：每个响应单独生成一笔签名交易交给 submit，单笔失败（如请求已被关闭）不影响其他响应；返回提交的交易数量。
+    pub async fn run<S, B, F>(&mut self, stream: S, mut recent_blockhash: B, mut submit: F) -> usize
+    where
+        S: IntoIterator<Item = TransactionLogs>,
+        B: FnMut() -> Hash,
+        F: FnMut(Transaction),
+    {
+        let mut submitted = 0;
+        for logs in stream {
+            for instruction in self.process_logs(&logs).await {
+                submit(self.build_transaction(std::slice::from_ref(&instruction), recent_blockhash()));
+                submitted += 1;
+            }
+        }
+        submitted
+    }
+}
+
+/// 适配器错误转换：不支持的操作保持 UnsupportedAction，其余记为 AdapterFailed。
+fn adapter_error(target: &str, action: &str, err: OffchainIntegrationError) -> KeeperError {
+    match err {
+        OffchainIntegrationError::Unsupported(_) => {
+            KeeperError::UnsupportedAction { target: target.to_string(), action: action.to_string() }
+        }
+        other => KeeperError::AdapterFailed(target.to_string(), other.to_string()),
+    }
+}
+
+/// 截断错误信息至链上上限（按字符边界）。
+fn truncate_error(mut message: String) -> String {
+    if message.len() > MAX_RESPONSE_ERROR_LEN {
+        let mut end = MAX_RESPONSE_ERROR_LEN;
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+        message.truncate(end);
+    }
+    message
+}
@@ -1,18 +1,26 @@
 //!
 //! Offchain Integration Library Entry
 //!
-//! 本模块为离线集成服务库主入口，统一导出所有子模块，便于外部集成与调用。
+//! 本模块为离线集成 SDK 主入口，统一导出所有子模块，便于外部集成与调用。
+//! 调用方依赖 `OffchainDexAdapter`、`OffchainOracleAdapter` 与组合服务 `PriceAndRouteProvider`，
+//! dex-offchain、oracle-offchain 的适配器经 `bridge` 模块接入。
 
 // 导入并公开所有子模块。
+pub mod bridge;   // 适配器桥接模块
 pub mod dex;      // DEX 相关模块
 pub mod error;    // 错误类型模块
+pub mod keeper;   // 链下请求 keeper 模块
 pub mod oracles;  // 预言机相关模块
+pub mod provider; // 价格与路由组合服务模块
 pub mod traits;   // 通用 trait 模块
 
 // 重新导出常用类型和函数，便于外部访问。
+pub use bridge::*;
 pub use dex::*;
 pub use error::*;
+pub use keeper::*;
 pub use oracles::*;
+pub use provider::*;
 pub use traits::*;
 
 /// 离线集成服务库版本号。
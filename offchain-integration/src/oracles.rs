@@ -1,27 +1,33 @@
 //!
 //! Offchain Integration Oracles Module
 //!
-//! 本模块实现离线集成服务的预言机相关逻辑，支持价格查询、TWAP/VWAP 等功能，确保与链下预言机交互的合规性与安全性。
+//! 本模块定义离线集成服务的预言机查询参数与结果类型，支持价格、TWAP 查询，确保与链下预言机交互的合规性与安全性。
+
+use serde::{Deserialize, Serialize};
 
 /// 预言机查询参数结构体。
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OracleQueryParams {
-    pub token: String,         // 查询代币
+    pub token: String,         // 查询代币或交易对（如 SOL/USD）
+}
+
+impl OracleQueryParams {
+    /// 创建查询参数。
+    pub fn new(token: &str) -> Self {
+        Self { token: token.to_string() }
+    }
 }
 
 /// 预言机查询结果结构体。
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OracleQueryResult {
-    pub price: u64,            // 查询价格
-    pub timestamp: u64,        // 价格时间戳
+    pub price: f64,            // 查询价格（TWAP 查询时为时间加权平均价格）
+    pub timestamp: i64,        // 价格时间戳（Unix 秒）
 }
 
-/// 预言机适配器 trait。
-pub trait OracleAdapter {
-    /// 查询价格。
-    fn get_price(&self, params: &OracleQueryParams) -> OracleQueryResult;
-    /// 查询 TWAP。
-    fn get_twap(&self, params: &OracleQueryParams) -> OracleQueryResult;
-    /// 查询 VWAP。
-    fn get_vwap(&self, params: &OracleQueryParams) -> OracleQueryResult;
+impl OracleQueryResult {
+    /// 价格是否有效（有限且为正）。
+    pub fn is_valid(&self) -> bool {
+        self.price.is_finite() && self.price > 0.0
+    }
 }
@@ -0,0 +1,154 @@
+//!
+//! Offchain Integration Price And Route Provider Module
+//!
+//! 本模块实现组合价格与路由服务：预言机按注册顺序回退取价，DEX 并发询价选出最优路由，并可附带输入/输出代币价格，
+//! 供需要“价格 + 路由”的上层服务统一依赖。
+
+use std::sync::Arc;
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::dex::{DexQuote, DexTradeParams, DexTradeResult};
+use crate::error::OffchainIntegrationError;
+use crate::oracles::{OracleQueryParams, OracleQueryResult};
+use crate::traits::{OffchainDexAdapter, OffchainOracleAdapter};
+
+/// 带来源的价格。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourcedPrice {
+    pub source: String,            // 作答的预言机
+    pub result: OracleQueryResult, // 价格结果
+}
+
+/// 带来源的报价。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteQuote {
+    pub dex: String,      // 报价最优的 DEX
+    pub quote: DexQuote,  // 报价
+}
+
+/// 价格与路由组合结果。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceAndRoute {
+    pub route: RouteQuote,          // 最优路由
+    pub input_price: SourcedPrice,  // 输入代币价格
+    pub output_price: SourcedPrice, // 输出代币价格
+}
+
+impl PriceAndRoute {
+    /// 路由成交价相对预言机价格的偏差（基点，正数表示路由优于预言机）；decimals 为代币精度。
+    pub fn deviation_bps(&self, amount_in: u64, decimals_in: u8, decimals_out: u8) -> Option<f64> {
+        if amount_in == 0 {
+            return None;
+        }
+        let value_in = amount_in as f64 / 10f64.powi(decimals_in as i32) * self.input_price.result.price;
+        let value_out = self.route.quote.amount_out as f64 / 10f64.powi(decimals_out as i32) * self.output_price.result.price;
+        (value_in > 0.0).then(|| (value_out / value_in - 1.0) * 10_000.0)
+    }
+}
+
+/// 组合价格与路由服务。
+#[derive(Clone, Default)]
+pub struct PriceAndRouteProvider {
+    oracles: Vec<Arc<dyn OffchainOracleAdapter>>, // 预言机（按优先级）
+    dexes: Vec<Arc<dyn OffchainDexAdapter>>,      // 参与询价的 DEX
+}
+
+impl PriceAndRouteProvider {
+    /// 创建空服务。
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 追加预言机（先追加者优先）。
+    pub fn with_oracle(mut self, oracle: Arc<dyn OffchainOracleAdapter>) -> Self {
+        self.oracles.push(oracle);
+        self
+    }
+    /// 追加参与询价的 DEX。
+    pub fn with_dex(mut self, dex: Arc<dyn OffchainDexAdapter>) -> Self {
+        self.dexes.push(dex);
+        self
+    }
+    /// 预言机名称（按优先级）。
+    pub fn oracle_names(&self) -> Vec<String> {
+        self.oracles.iter().map(|oracle| oracle.name().to_string()).collect()
+    }
+    /// DEX 名称。
+    pub fn dex_names(&self) -> Vec<String> {
+        self.dexes.iter().map(|dex| dex.name().to_string()).collect()
+    }
+    /// 查询价格：按优先级依次尝试，返回首个有效价格；全部失败时返回最后一个错误。
+    pub async fn price(&self, token: &str) -> Result<SourcedPrice, OffchainIntegrationError> {
+        let params = OracleQueryParams::new(token);
+        let mut last_err = OffchainIntegrationError::AdapterNotFound("oracle".to_string());
+        for oracle in &self.oracles {
+            match oracle.get_price(&params).await {
+                Ok(result) if result.is_valid() => return Ok(SourcedPrice { source: oracle.name().to_string(), result }),
+                Ok(result) => {
+                    last_err = OffchainIntegrationError::InvalidOracleData(format!("{} returned price {} for {}", oracle.name(), result.price, token))
+                }
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+    /// 查询 TWAP：按优先级依次尝试，跳过不支持 TWAP 的预言机。
+    pub async fn twap(&self, token: &str, window_secs: u64) -> Result<SourcedPrice, OffchainIntegrationError> {
+        let params = OracleQueryParams::new(token);
+        let mut last_err = OffchainIntegrationError::AdapterNotFound("oracle".to_string());
+        for oracle in &self.oracles {
+            match oracle.get_twap(&params, window_secs).await {
+                Ok(result) if result.is_valid() => return Ok(SourcedPrice { source: oracle.name().to_string(), result }),
+                Ok(result) => {
+                    last_err = OffchainIntegrationError::InvalidOracleData(format!("{} returned twap {} for {}", oracle.name(), result.price, token))
+                }
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+    /// 并发向全部 DEX 询价，返回预期输出最大的报价（相同时取价格冲击更小者，再按注册顺序）；全部失败时返回首个错误。
+    pub async fn best_route(&self, params: &DexTradeParams) -> Result<RouteQuote, OffchainIntegrationError> {
+        if params.amount == 0 {
+            return Err(OffchainIntegrationError::InvalidInput("amount must be positive".to_string()));
+        }
+        let quotes = join_all(self.dexes.iter().map(|dex| dex.quote(params))).await;
+        let mut best: Option<RouteQuote> = None;
+        let mut first_err = None;
+        for (dex, quote) in self.dexes.iter().zip(quotes) {
+            match quote {
+                Ok(quote) => {
+                    let better = best.as_ref().is_none_or(|current| {
+                        (quote.amount_out, std::cmp::Reverse(quote.price_impact_bps))
+                            > (current.quote.amount_out, std::cmp::Reverse(current.quote.price_impact_bps))
+                    });
+                    if better {
+                        best = Some(RouteQuote { dex: dex.name().to_string(), quote });
+                    }
+                }
+                Err(e) => {
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        best.ok_or_else(|| first_err.unwrap_or_else(|| OffchainIntegrationError::AdapterNotFound("dex".to_string())))
+    }
+    /// 最优路由并附带输入、输出代币价格（价格与询价并发查询）。
+    pub async fn price_and_route(&self, params: &DexTradeParams) -> Result<PriceAndRoute, OffchainIntegrationError> {
+        let (route, input_price, output_price) =
+            futures::join!(self.best_route(params), self.price(&params.from_token), self.price(&params.to_token));
+        Ok(PriceAndRoute { route: route?, input_price: input_price?, output_price: output_price? })
+    }
+    /// 在最优路由的 DEX 上执行交易，返回所用报价与成交结果。
+    pub async fn swap_best(&self, params: &DexTradeParams) -> Result<(RouteQuote, DexTradeResult), OffchainIntegrationError> {
+        let route = self.best_route(params).await?;
+        let dex = self
+            .dexes
+            .iter()
+            .find(|dex| dex.name() == route.dex)
+            .ok_or_else(|| OffchainIntegrationError::AdapterNotFound(route.dex.clone()))?;
+        let result = dex.swap(params).await?;
+        Ok((route, result))
+    }
+}
@@ -1,22 +1,37 @@
 //!
 //! Offchain Integration Traits Module
 //!
-//! 本模块定义离线集成服务的通用 trait，规范 DEX、预言机等接口，确保可扩展性、合规性与可维护性。
+//! 本模块定义离线集成 SDK 面向调用方的异步 trait：DEX、预言机各一个，统一返回 `Result<_, OffchainIntegrationError>`。
+//! dex-offchain、oracle-offchain 的适配器可经 `bridge` 模块转换为这里的 trait 对象。
 
-/// 离线 DEX 适配器 trait。
-pub trait OffchainDexAdapter {
+use async_trait::async_trait;
+
+use crate::dex::{DexQuote, DexTradeParams, DexTradeResult};
+use crate::error::OffchainIntegrationError;
+use crate::oracles::{OracleQueryParams, OracleQueryResult};
+
+/// 离线 DEX 适配器 trait（异步、线程安全）。
+#[async_trait]
+pub trait OffchainDexAdapter: Send + Sync {
     /// 获取适配器名称。
     fn name(&self) -> &str;
     /// 查询报价。
-    fn quote(&self, from_token: &str, to_token: &str, amount: u64) -> u64;
-    /// 执行交易。
-    fn swap(&self, from_token: &str, to_token: &str, amount: u64) -> bool;
+    async fn quote(&self, params: &DexTradeParams) -> Result<DexQuote, OffchainIntegrationError>;
+    /// 执行交易；默认不支持。
+    async fn swap(&self, _params: &DexTradeParams) -> Result<DexTradeResult, OffchainIntegrationError> {
+        Err(OffchainIntegrationError::Unsupported(self.name().to_string()))
+    }
 }
 
-/// 离线预言机适配器 trait。
-pub trait OffchainOracleAdapter {
+/// 离线预言机适配器 trait（异步、线程安全）。
+#[async_trait]
+pub trait OffchainOracleAdapter: Send + Sync {
     /// 获取适配器名称。
     fn name(&self) -> &str;
     /// 查询价格。
-    fn get_price(&self, token: &str) -> u64;
+    async fn get_price(&self, params: &OracleQueryParams) -> Result<OracleQueryResult, OffchainIntegrationError>;
+    /// 查询 TWAP；默认不支持。
+    async fn get_twap(&self, _params: &OracleQueryParams, _window_secs: u64) -> Result<OracleQueryResult, OffchainIntegrationError> {
+        Err(OffchainIntegrationError::Unsupported(self.name().to_string()))
+    }
 }
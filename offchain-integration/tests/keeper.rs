@@ -0,0 +1,385 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use offchain_integration::keeper::*;
+use offchain_integration::traits::{OffchainDexAdapter, OffchainOracleAdapter};
+use offchain_integration::{DexQuote, DexTradeParams, DexTradeResult, OffchainIntegrationError, OracleQueryParams, OracleQueryResult};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use solana_keypair::Keypair;
+use solana_program::hash::Hash;
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use solana_signer::Signer;
+
+struct MockDex;
+#[async_trait::async_trait]
+impl OffchainDexAdapter for MockDex {
+    fn name(&self) -> &str {
+        "mock_dex"
+    }
+    async fn quote(&self, params: &DexTradeParams) -> Result<DexQuote, OffchainIntegrationError> {
+        Ok(DexQuote { amount_out: params.amount * 2, min_amount_out: params.amount * 2, price_impact_bps: 0, route: vec![] })
+    }
+    async fn swap(&self, params: &DexTradeParams) -> Result<DexTradeResult, OffchainIntegrationError> {
+        if params.amount == 0 {
+            return Err(OffchainIntegrationError::DexOperationFailed("swap rejected".to_string()));
+        }
+        Ok(DexTradeResult { amount_out: params.amount * 2, tx_hash: "sig".to_string() })
+    }
+}
+
+struct MockOracle;
+#[async_trait::async_trait]
+impl OffchainOracleAdapter for MockOracle {
+    fn name(&self) -> &str {
+        "mock_oracle"
+    }
+    async fn get_price(&self, _params: &OracleQueryParams) -> Result<OracleQueryResult, OffchainIntegrationError> {
+        Ok(OracleQueryResult { price: 151.25, timestamp: 1_700_000_000 })
+    }
+}
+
+/// 内存中的链上账户。
+#[derive(Default)]
+struct MockAccounts(Mutex<HashMap<Pubkey, Vec<u8>>>);
+#[async_trait::async_trait]
+impl AccountSource for MockAccounts {
+    async fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, OffchainIntegrationError> {
+        Ok(self.0.lock().unwrap().get(address).cloned())
+    }
+}
+
+impl MockAccounts {
+    /// 按请求事件写入链上 PendingRequest（附带 InitSpace 尾部空间）。
+    fn put(&self, program_id: &Pubkey, event: &OffchainEvent, status: PendingRequestStatus) {
+        let OffchainEvent::DexRequest(e) = event else { unreachable!() };
+        let account = PendingRequestAccount {
+            request_id: e.request_id,
+            requester: e.requester,
+            responder: e.responder,
+            kind: PendingRequestKind::Dex,
+            target: e.dex_name.clone(),
+            action: e.action.clone(),
+            params: e.params.clone(),
+            status,
+            result: vec![],
+            error_msg: String::new(),
+            created_at: e.timestamp,
+            expires_at: e.expires_at,
+            responded_at: if status == PendingRequestStatus::Pending { 0 } else { e.timestamp + 1 },
+            bump: 255,
+        };
+        let mut data = account.encode();
+        data.resize(data.len() + 64, 0);
+        let (address, _) = pending_request_address(program_id, &e.requester, e.request_id);
+        self.0.lock().unwrap().insert(address, data);
+    }
+}
+
+fn data_line(event: &OffchainEvent) -> String {
+    format!("Program data: {}", BASE64.encode(encode_event(event)))
+}
+
+const REQUEST_TIME: i64 = 1_700_000_000;
+const REQUEST_TTL: i64 = 60;
+
+fn dex_request(request_id: u64, requester: Pubkey, responder: Pubkey, action: &str, params: &str) -> OffchainEvent {
+    OffchainEvent::DexRequest(OffchainDexRequestEvent {
+        request_id,
+        dex_name: "mock_dex".to_string(),
+        action: action.to_string(),
+        params: params.as_bytes().to_vec(),
+        requester,
+        timestamp: REQUEST_TIME,
+        responder,
+        expires_at: REQUEST_TIME + REQUEST_TTL,
+    })
+}
+
+fn oracle_request(request_id: u64, requester: Pubkey, responder: Pubkey, oracle_name: &str) -> OffchainEvent {
+    OffchainEvent::OracleRequest(OffchainOracleRequestEvent {
+        request_id,
+        oracle_name: oracle_name.to_string(),
+        action: "price".to_string(),
+        params: br#"{"symbol":"SOL/USD"}"#.to_vec(),
+        requester,
+        timestamp: REQUEST_TIME,
+        responder,
+        expires_at: REQUEST_TIME + REQUEST_TTL,
+    })
+}
+
+/// 模拟一笔调用本程序的交易日志，附带其他程序（CPI）发出的同格式数据。
+fn program_logs(program_id: &Pubkey, other: &Pubkey, events: &[OffchainEvent]) -> Vec<String> {
+    let mut logs = vec![
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+        "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+        format!("Program {} invoke [1]", program_id),
+        "Program log: Instruction: CreateOffchainRequest".to_string(),
+        format!("Program {} invoke [2]", other),
+        data_line(&events[0]), // CPI 内其他程序发出，不应被采纳
+        format!("Program {} consumed 2000 of 190000 compute units", other),
+        format!("Program {} success", other),
+    ];
+    logs.extend(events.iter().map(data_line));
+    logs.push("Program data: !!not-base64!!".to_string());
+    logs.push(format!("Program {} consumed 12000 of 200000 compute units", program_id));
+    logs.push(format!("Program {} success", program_id));
+    logs
+}
+
+#[test]
+fn test_event_roundtrip_and_discriminators() {
+    // Anchor 判别码：sha256("event:OffchainResponse") 前 8 字节
+    assert_eq!(event_discriminator("OffchainResponse"), [0x53, 0x36, 0xc5, 0x6d, 0x36, 0x22, 0x9f, 0xae]);
+    assert_ne!(instruction_discriminator("submit_offchain_response"), event_discriminator("OffchainResponse"));
+    let response = OffchainEvent::Response(OffchainResponseEvent {
+        request_id: 9,
+        success: false,
+        result: vec![],
+        error_msg: Some("timeout".to_string()),
+        responder: Pubkey::new_unique(),
+        timestamp: 1,
+    });
+    assert_eq!(decode_event(&encode_event(&response)).unwrap(), Some(response));
+    assert_eq!(decode_event(&[0u8; 16]).unwrap(), None); // 其他事件
+    assert!(decode_event(&event_discriminator("OffchainDexRequest")).is_err()); // 事件体缺失
+}
+
+#[test]
+fn test_parse_events_only_from_program_frames() {
+    let program_id = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let requester = Pubkey::new_unique();
+    let responder = Pubkey::new_unique();
+    let events = vec![
+        dex_request(1, requester, responder, "quote", r#"{"token_in":"USDC","token_out":"SOL","amount_in":500}"#),
+        oracle_request(2, requester, responder, "mock_oracle"),
+    ];
+    let parsed = parse_events(&program_id, &program_logs(&program_id, &other, &events));
+    assert_eq!(parsed, events);
+    // 调用栈外的数据行被忽略
+    assert!(parse_events(&program_id, &[data_line(&events[0])]).is_empty());
+
+    // 其他程序通过 log/return 行伪造帧文本，不能切换到本程序的调用栈
+    let forged = vec![
+        format!("Program {} invoke [1]", other),
+        format!("Program log: {} invoke [2]", program_id),
+        format!("Program return: {} invoke [2]", program_id),
+        format!("Program {} invoke", program_id),
+        format!("Program {} invoke [x]", program_id),
+        data_line(&events[0]),
+        format!("Program {} success", other),
+    ];
+    assert!(parse_events(&program_id, &forged).is_empty());
+
+    // log 行中的伪造 success 不弹出本程序帧；失败帧正常出栈
+    let framed = vec![
+        format!("Program {} invoke [1]", program_id),
+        format!("Program log: {} success", program_id),
+        format!("Program {} invoke [2]", other),
+        format!("Program {} failed: custom program error: 0x1", other),
+        format!("Program {} consumed 100 of 200000 compute units", program_id),
+        data_line(&events[1]),
+        format!("Program {} success", program_id),
+        data_line(&events[0]),
+    ];
+    assert_eq!(parse_events(&program_id, &framed), vec![events[1].clone()]);
+
+    // 退出帧与栈顶不一致时清空调用栈
+    let unbalanced = vec![
+        format!("Program {} invoke [1]", program_id),
+        format!("Program {} success", other),
+        data_line(&events[0]),
+    ];
+    assert!(parse_events(&program_id, &unbalanced).is_empty());
+}
+
+#[tokio::test]
+async fn test_keeper_answers_mocked_log_stream() {
+    let program_id = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let requester = Pubkey::new_unique();
+    let responder = Keypair::new();
+    let responder_key = responder.pubkey();
+    let mut keeper = Keeper::new(program_id, responder);
+    keeper.register_dex(Arc::new(MockDex));
+    keeper.register_oracle(Arc::new(MockOracle));
+
+    let quote = dex_request(1, requester, responder_key, "quote", r#"{"token_in":"USDC","token_out":"SOL","amount_in":500}"#);
+    let price = oracle_request(2, requester, responder_key, "mock_oracle");
+    let missing = oracle_request(3, requester, responder_key, "unknown_oracle");
+    let bad_params = dex_request(4, requester, responder_key, "swap", "not json");
+    // 指定其他 keeper 的请求不响应
+    let foreign = oracle_request(6, requester, Pubkey::new_unique(), "mock_oracle");
+    let stream = vec![
+        TransactionLogs { signature: "tx1".to_string(), failed: false, logs: program_logs(&program_id, &other, &[quote.clone(), price.clone(), foreign]) },
+        // 失败交易中的请求不生效
+        TransactionLogs { signature: "tx2".to_string(), failed: true, logs: program_logs(&program_id, &other, &[dex_request(5, requester, responder_key, "swap", "{}")]) },
+        // 重放的请求不重复响应
+        TransactionLogs { signature: "tx1".to_string(), failed: false, logs: program_logs(&program_id, &other, std::slice::from_ref(&quote)) },
+        TransactionLogs { signature: "tx3".to_string(), failed: false, logs: program_logs(&program_id, &other, &[missing, bad_params]) },
+    ];
+
+    let blockhash = Hash::new_from_array([7u8; 32]);
+    let mut submitted = Vec::new();
+    let count = keeper.run(stream, || blockhash, |tx| submitted.push(tx)).await;
+    // 每个响应单独成交易：quote、price、missing、bad_params
+    assert_eq!(count, 4);
+    assert_eq!(submitted.len(), 4);
+
+    for tx in &submitted {
+        assert!(tx.is_signed());
+        assert_eq!(tx.message.account_keys[0], responder_key); // 响应者付费并签名
+        assert_eq!(tx.message.recent_blockhash, blockhash);
+        assert_eq!(tx.message.instructions.len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn test_submit_offchain_response_instruction_layout() {
+    let program_id = Pubkey::new_unique();
+    let requester = Pubkey::new_unique();
+    let responder = Keypair::new();
+    let responder_key = responder.pubkey();
+    let mut keeper = Keeper::new(program_id, responder);
+    keeper.register_dex(Arc::new(MockDex));
+    keeper.register_oracle(Arc::new(MockOracle));
+
+    let response = keeper
+        .handle_event(&dex_request(42, requester, responder_key, "quote", r#"{"token_in":"USDC","token_out":"SOL","amount_in":500}"#))
+        .await
+        .unwrap();
+    assert!(response.success);
+    assert_eq!(response.result, br#"{"amount_out":1000}"#.to_vec());
+
+    let ix = submit_offchain_response_ix(&program_id, &responder_key, &response);
+    let (pda, _) = Pubkey::find_program_address(&[b"pending_request", requester.as_ref(), &42u64.to_le_bytes()], &program_id);
+    assert_eq!(ix.program_id, program_id);
+    assert_eq!(ix.accounts, vec![AccountMeta::new(pda, false), AccountMeta::new_readonly(responder_key, true)]);
+    // 判别码 + request_id + success + result(Vec<u8>) + error_msg(None)
+    let mut expected = instruction_discriminator("submit_offchain_response").to_vec();
+    expected.extend(42u64.to_le_bytes());
+    expected.push(1);
+    expected.extend((response.result.len() as u32).to_le_bytes());
+    expected.extend(&response.result);
+    expected.push(0);
+    assert_eq!(ix.data, expected);
+
+    // 失败请求同样响应，错误信息写入 error_msg
+    let failed = keeper.handle_event(&oracle_request(43, requester, responder_key, "unknown_oracle")).await.unwrap();
+    assert!(!failed.success);
+    assert_eq!(failed.error_msg.as_deref(), Some("adapter not registered: unknown_oracle"));
+    let twap = keeper
+        .handle_event(&OffchainEvent::OracleRequest(OffchainOracleRequestEvent {
+            request_id: 44,
+            oracle_name: "mock_oracle".to_string(),
+            action: "twap".to_string(),
+            params: br#"{"symbol":"SOL/USD"}"#.to_vec(),
+            requester,
+            timestamp: REQUEST_TIME,
+            responder: responder_key,
+            expires_at: REQUEST_TIME + REQUEST_TTL,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(twap.error_msg.as_deref(), Some("unsupported action twap for mock_oracle"));
+    // 同一请求不重复处理
+    assert!(keeper.handle_event(&oracle_request(43, requester, responder_key, "unknown_oracle")).await.is_none());
+    let price = keeper.handle_event(&oracle_request(45, requester, responder_key, "mock_oracle")).await.unwrap();
+    assert_eq!(price.result, br#"{"price":151.25,"timestamp":1700000000}"#.to_vec());
+}
+
+#[tokio::test]
+async fn test_keeper_swap_allowlist_and_handled_eviction() {
+    let program_id = Pubkey::new_unique();
+    let requester = Pubkey::new_unique();
+    let responder = Keypair::new();
+    let responder_key = responder.pubkey();
+    let mut keeper = Keeper::new(program_id, responder);
+    keeper.register_dex(Arc::new(MockDex));
+    keeper.register_oracle(Arc::new(MockOracle));
+    let swap_params = r#"{"token_in":"USDC","token_out":"SOL","amount_in":500}"#;
+
+    // 白名单外的请求人不执行 swap，但仍响应失败
+    let rejected = keeper.handle_event(&dex_request(1, requester, responder_key, "swap", swap_params)).await.unwrap();
+    assert!(!rejected.success);
+    assert_eq!(rejected.error_msg, Some(format!("requester {} not allowed to swap", requester)));
+
+    keeper.allow_swap_requester(requester);
+    let request = dex_request(2, requester, responder_key, "swap", swap_params);
+    let accounts = Arc::new(MockAccounts::default());
+    accounts.put(&program_id, &request, PendingRequestStatus::Pending);
+    keeper.set_account_source(accounts.clone());
+    let swapped = keeper.handle_event(&request).await.unwrap();
+    assert!(swapped.success);
+    assert_eq!(swapped.result, br#"{"amount_out":1000,"tx_hash":"sig"}"#.to_vec());
+    assert_eq!(keeper.handled_len(), 2);
+
+    // 指定其他响应者的请求不记录、不响应
+    assert!(keeper.handle_event(&oracle_request(3, requester, Pubkey::new_unique(), "mock_oracle")).await.is_none());
+    assert_eq!(keeper.handled_len(), 2);
+
+    // 链上时间越过过期时间后清理已处理记录，重放的过期请求不再响应
+    let OffchainEvent::OracleRequest(mut later) = oracle_request(4, requester, responder_key, "mock_oracle") else { unreachable!() };
+    later.timestamp = REQUEST_TIME + REQUEST_TTL + 1;
+    later.expires_at = later.timestamp + REQUEST_TTL;
+    assert!(keeper.handle_event(&OffchainEvent::OracleRequest(later)).await.unwrap().success);
+    assert_eq!(keeper.handled_len(), 1);
+    assert!(keeper.handle_event(&dex_request(2, requester, responder_key, "swap", swap_params)).await.is_none());
+    assert_eq!(keeper.handled_len(), 1);
+}
+
+#[tokio::test]
+async fn test_keeper_swap_requires_pending_onchain_request() {
+    let program_id = Pubkey::new_unique();
+    let requester = Pubkey::new_unique();
+    let responder = Keypair::new();
+    let responder_key = responder.pubkey();
+    let swap_params = r#"{"token_in":"USDC","token_out":"SOL","amount_in":500}"#;
+    let request = dex_request(7, requester, responder_key, "swap", swap_params);
+    let (address, _) = pending_request_address(&program_id, &requester, 7);
+    let accounts = Arc::new(MockAccounts::default());
+    let new_keeper = || {
+        let mut keeper = Keeper::new(program_id, responder.insecure_clone());
+        keeper.register_dex(Arc::new(MockDex));
+        keeper.allow_swap_requester(requester);
+        keeper
+    };
+
+    // 未配置账户读取时不执行 swap
+    let response = new_keeper().handle_event(&request).await.unwrap();
+    assert_eq!(response.error_msg, Some(KeeperError::AccountSourceMissing.to_string()));
+
+    // 链上请求已关闭
+    let mut keeper = new_keeper();
+    keeper.set_account_source(accounts.clone());
+    let response = keeper.handle_event(&request).await.unwrap();
+    assert_eq!(response.error_msg, Some(KeeperError::RequestNotPending(address).to_string()));
+
+    // 重启后回放已响应的请求：内存记录已丢失，以链上状态为准不再成交
+    accounts.put(&program_id, &request, PendingRequestStatus::Fulfilled);
+    let mut restarted = new_keeper();
+    restarted.set_account_source(accounts.clone());
+    let response = restarted.handle_event(&request).await.unwrap();
+    assert!(!response.success);
+    assert_eq!(response.error_msg, Some(KeeperError::RequestNotPending(address).to_string()));
+
+    // 链上记录与事件参数不一致
+    let tampered = dex_request(7, requester, responder_key, "swap", r#"{"token_in":"USDC","token_out":"SOL","amount_in":9}"#);
+    accounts.put(&program_id, &tampered, PendingRequestStatus::Pending);
+    let mut keeper = new_keeper();
+    keeper.set_account_source(accounts.clone());
+    assert!(!keeper.handle_event(&request).await.unwrap().success);
+
+    // 仍待响应时成交
+    accounts.put(&program_id, &request, PendingRequestStatus::Pending);
+    let mut keeper = new_keeper();
+    keeper.set_account_source(accounts.clone());
+    let response = keeper.handle_event(&request).await.unwrap();
+    assert!(response.success);
+    assert_eq!(response.result, br#"{"amount_out":1000,"tx_hash":"sig"}"#.to_vec());
+
+    // 非 PendingRequest 账户数据
+    assert!(PendingRequestAccount::decode(&[0u8; 64]).is_err());
+}
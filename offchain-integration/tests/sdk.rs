@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use offchain_integration::*;
+
+/// dex-offchain 适配器：按固定汇率报价，swap 未实现。
+struct FixedRateDex {
+    name: &'static str,
+    rate: u64,
+    impact_bps: u64,
+}
+
+#[async_trait::async_trait]
+impl dex_offchain::OffchainDexAdapter for FixedRateDex {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    async fn quote(&self, req: dex_offchain::OffchainQuoteRequest) -> Result<dex_offchain::OffchainQuoteResponse, String> {
+        if req.token_in == "BAD" {
+            return Err(dex_offchain::OffchainDexError::Api { status: 500, message: "boom".to_string() }.into());
+        }
+        let amount_out = req.amount_in * self.rate;
+        Ok(dex_offchain::OffchainQuoteResponse {
+            amount_out,
+            min_amount_out: amount_out - amount_out * req.slippage_bps as u64 / 10_000,
+            price_impact_bps: self.impact_bps,
+            route: vec![self.name.to_string()],
+        })
+    }
+    async fn swap(&self, req: dex_offchain::OffchainSwapRequest) -> Result<dex_offchain::OffchainSwapResponse, String> {
+        Ok(dex_offchain::OffchainSwapResponse { amount_out: req.amount_in * self.rate, tx_hash: format!("{}-tx", self.name) })
+    }
+}
+
+/// dex-offchain 适配器：仅实现 swap（quote 使用默认的不支持）。
+struct SwapOnlyDex;
+
+#[async_trait::async_trait]
+impl dex_offchain::OffchainDexAdapter for SwapOnlyDex {
+    fn name(&self) -> &'static str {
+        "swap_only"
+    }
+    async fn swap(&self, _req: dex_offchain::OffchainSwapRequest) -> Result<dex_offchain::OffchainSwapResponse, String> {
+        Err("not used".to_string())
+    }
+}
+
+/// oracle-offchain 适配器：固定价格（None 表示无数据）。
+struct FixedOracle {
+    name: &'static str,
+    price: Option<f64>,
+}
+
+#[async_trait::async_trait]
+impl oracle_offchain::OffchainOracleAdapter for FixedOracle {
+    fn name(&self) -> &str {
+        self.name
+    }
+    async fn get_price(&self, req: oracle_offchain::OffchainPriceRequest) -> Result<oracle_offchain::OffchainPriceResponse, String> {
+        let price = self.price.ok_or_else(|| oracle_offchain::OffchainOracleError::NoData(req.symbol.clone()))?;
+        let price = if req.symbol == "USDC" { 1.0 } else { price };
+        Ok(oracle_offchain::OffchainPriceResponse { price, last_updated: 1_700_000_000 })
+    }
+}
+
+fn dex(name: &'static str, rate: u64, impact_bps: u64) -> Arc<dyn OffchainDexAdapter> {
+    let inner: Arc<dyn dex_offchain::OffchainDexAdapter> = Arc::new(FixedRateDex { name, rate, impact_bps });
+    Arc::new(DexOffchainBridge::from(inner))
+}
+
+fn oracle(name: &'static str, price: Option<f64>) -> Arc<dyn OffchainOracleAdapter> {
+    let inner: Arc<dyn oracle_offchain::OffchainOracleAdapter> = Arc::new(FixedOracle { name, price });
+    Arc::new(OracleOffchainBridge::from(inner))
+}
+
+#[test]
+fn test_error_conversions() {
+    use dex_offchain::OffchainDexError;
+    use oracle_offchain::OffchainOracleError;
+
+    assert_eq!(
+        OffchainIntegrationError::from(OffchainDexError::Api { status: 401, message: "bad key".to_string() }),
+        OffchainIntegrationError::Unauthorized("bad key".to_string())
+    );
+    assert_eq!(
+        OffchainIntegrationError::from(OffchainDexError::Unavailable("https://quote-api.jup.ag".to_string())),
+        OffchainIntegrationError::Unavailable("https://quote-api.jup.ag".to_string())
+    );
+    assert_eq!(
+        OffchainIntegrationError::from(OffchainOracleError::NoData("SOL/USD".to_string())),
+        OffchainIntegrationError::InvalidOracleData("no price data for SOL/USD".to_string())
+    );
+    assert_eq!(
+        OffchainIntegrationError::from(OffchainOracleError::Api { status: 503, message: "down".to_string() }),
+        OffchainIntegrationError::Upstream { status: 503, message: "down".to_string() }
+    );
+}
+
+#[tokio::test]
+async fn test_bridges_convert_requests_and_errors() {
+    let params = DexTradeParams::new("USDC", "SOL", 1_000).with_slippage_bps(50);
+    let quote = dex("raydium", 3, 10).quote(&params).await.unwrap();
+    assert_eq!(quote, DexQuote { amount_out: 3_000, min_amount_out: 2_985, price_impact_bps: 10, route: vec!["raydium".to_string()] });
+    let trade = dex("raydium", 3, 10).swap(&params).await.unwrap();
+    assert_eq!(trade, DexTradeResult { amount_out: 3_000, tx_hash: "raydium-tx".to_string() });
+    assert_eq!(
+        dex("raydium", 3, 10).quote(&DexTradeParams::new("BAD", "SOL", 1)).await.unwrap_err(),
+        OffchainIntegrationError::DexOperationFailed("upstream returned 500: boom".to_string())
+    );
+    let swap_only = DexOffchainBridge::new(Arc::new(SwapOnlyDex));
+    assert_eq!(swap_only.quote(&params).await.unwrap_err(), OffchainIntegrationError::Unsupported("swap_only".to_string()));
+
+    let pyth = oracle("pyth", Some(151.25));
+    let price = pyth.get_price(&OracleQueryParams::new("SOL/USD")).await.unwrap();
+    assert_eq!(price, OracleQueryResult { price: 151.25, timestamp: 1_700_000_000 });
+    assert_eq!(
+        pyth.get_twap(&OracleQueryParams::new("SOL/USD"), 300).await.unwrap_err(),
+        OffchainIntegrationError::Unsupported("pyth".to_string())
+    );
+    assert!(matches!(
+        oracle("broken", Some(f64::NAN)).get_price(&OracleQueryParams::new("SOL/USD")).await,
+        Err(OffchainIntegrationError::InvalidOracleData(_))
+    ));
+    assert_eq!(
+        oracle("empty", None).get_price(&OracleQueryParams::new("SOL/USD")).await.unwrap_err(),
+        OffchainIntegrationError::OracleQueryFailed("no price data for SOL/USD".to_string())
+    );
+    assert!(matches!(DexOffchainBridge::from_registry("missing"), Err(OffchainIntegrationError::AdapterNotFound(_))));
+}
+
+#[tokio::test]
+async fn test_provider_falls_back_and_picks_best_route() {
+    let provider = PriceAndRouteProvider::new()
+        .with_oracle(oracle("primary", None))
+        .with_oracle(oracle("backup", Some(150.0)))
+        .with_dex(dex("orca", 6, 20))
+        .with_dex(dex("raydium", 7, 30))
+        .with_dex(dex("phoenix", 7, 5));
+    assert_eq!(provider.oracle_names(), vec!["primary", "backup"]);
+
+    let price = provider.price("SOL/USD").await.unwrap();
+    assert_eq!(price.source, "backup"); // 首选无数据，回退到备用
+    assert_eq!(price.result.price, 150.0);
+    assert_eq!(provider.twap("SOL/USD", 300).await.unwrap_err(), OffchainIntegrationError::Unsupported("backup".to_string()));
+
+    let params = DexTradeParams::new("USDC", "SOL", 1_000_000);
+    let route = provider.best_route(&params).await.unwrap();
+    assert_eq!(route.dex, "phoenix"); // 输出相同，取价格冲击更小者
+    assert_eq!(route.quote.amount_out, 7_000_000);
+
+    let priced = provider.price_and_route(&params).await.unwrap();
+    assert_eq!(priced.input_price.result.price, 1.0);
+    assert_eq!(priced.output_price.result.price, 150.0);
+    // 1 USDC（6 位精度）换 0.007 SOL（9 位精度）≈ 1.05 USD，路由优于预言机 500 基点
+    let deviation = priced.deviation_bps(1_000_000, 6, 9).unwrap();
+    assert!((deviation - 500.0).abs() < 1e-6);
+
+    let (route, trade) = provider.swap_best(&params).await.unwrap();
+    assert_eq!(route.dex, "phoenix");
+    assert_eq!(trade.tx_hash, "phoenix-tx");
+
+    assert!(matches!(provider.best_route(&DexTradeParams::new("BAD", "SOL", 1)).await, Err(OffchainIntegrationError::DexOperationFailed(_))));
+    assert!(matches!(provider.best_route(&DexTradeParams::new("USDC", "SOL", 0)).await, Err(OffchainIntegrationError::InvalidInput(_))));
+    assert_eq!(
+        PriceAndRouteProvider::new().price("SOL/USD").await.unwrap_err(),
+        OffchainIntegrationError::AdapterNotFound("oracle".to_string())
+    );
+}
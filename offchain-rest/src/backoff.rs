@@ -0,0 +1,37 @@
+//!
+//! Offchain REST Backoff Module
+//!
+//! 本模块实现带抖动的指数退避：第 n 次重试的上限为 base * 2^n（不超过 max），实际等待在上限的 [1/2, 1] 区间内随机取值，避免多个客户端同时重试。
+
+use std::time::Duration;
+
+use rand::Rng;
+
+/// 指数退避策略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub base: Duration, // 初始退避时间
+    pub max: Duration,  // 最大退避时间
+}
+
+impl Backoff {
+    /// 创建退避策略。
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self { base, max: max.max(base) }
+    }
+    /// 第 attempt 次重试（从 0 开始）的退避上限。
+    pub fn ceiling(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.min(31)).unwrap_or(u32::MAX);
+        self.base.saturating_mul(factor).min(self.max)
+    }
+    /// 按给定抖动系数（0..=1）计算退避时间，便于测试。
+    pub fn delay_with_jitter(&self, attempt: u32, jitter: f64) -> Duration {
+        let ceiling = self.ceiling(attempt);
+        let half = ceiling / 2;
+        half + (ceiling - half).mul_f64(jitter.clamp(0.0, 1.0))
+    }
+    /// 第 attempt 次重试的随机退避时间。
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.delay_with_jitter(attempt, rand::thread_rng().gen::<f64>())
+    }
+}
@@ -0,0 +1,154 @@
+//!
+//! Offchain REST Circuit Breaker Module
+//!
+//! 本模块实现熔断器：连续失败达到阈值后打开并快速拒绝请求，冷却期后放行一个试探请求（半开），试探成功则关闭、失败则重新打开。
+//! 经 acquire 取得的试探许可未结算即被丢弃（如请求 future 被取消）时按失败记录，半开状态不会因试探位悬挂而永久拒绝请求。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 熔断器状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,   // 正常放行
+    Open,     // 快速拒绝
+    HalfOpen, // 放行单个试探请求
+}
+
+/// 熔断器内部状态。
+#[derive(Debug)]
+struct BreakerInner {
+    state: CircuitState,        // 当前状态
+    consecutive_failures: u32,  // 连续失败次数
+    opened_at: Option<Instant>, // 打开时间
+    trial_in_flight: bool,      // 半开状态下是否已有试探请求
+}
+
+/// 熔断器。
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32, // 熔断阈值，0 表示不熔断
+    cooldown: Duration,     // 冷却时间
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    /// 创建熔断器。
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            inner: Mutex::new(BreakerInner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                trial_in_flight: false,
+            }),
+        }
+    }
+    /// 当前状态。
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).state
+    }
+    /// 请求前检查是否放行；Err 携带拒绝时的状态，Ok(Some(state)) 表示状态发生变化。
+    /// - 放行试探请求后调用方须记录结果；跨 await 的请求应改用 acquire，由许可在取消时兜底记录失败。
+    pub fn try_acquire(&self, now: Instant) -> Result<Option<CircuitState>, CircuitState> {
+        self.acquire_slot(now).map(|(state, _)| state)
+    }
+    /// 请求前检查是否放行，返回放行许可；Err 携带拒绝时的状态。
+    pub fn acquire(&self, now: Instant) -> Result<BreakerPermit<'_>, CircuitState> {
+        let (state_change, trial) = self.acquire_slot(now)?;
+        Ok(BreakerPermit { breaker: self, state_change, trial })
+    }
+    /// 放行检查；Ok 携带 (状态变化, 是否占用半开试探位)。
+    fn acquire_slot(&self, now: Instant) -> Result<(Option<CircuitState>, bool), CircuitState> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match inner.state {
+            CircuitState::Closed => Ok((None, false)),
+            CircuitState::Open => {
+                let cooled = inner.opened_at.is_none_or(|at| now.saturating_duration_since(at) >= self.cooldown);
+                if !cooled {
+                    return Err(CircuitState::Open);
+                }
+                inner.state = CircuitState::HalfOpen;
+                inner.trial_in_flight = true;
+                Ok((Some(CircuitState::HalfOpen), true))
+            }
+            CircuitState::HalfOpen => {
+                if inner.trial_in_flight {
+                    return Err(CircuitState::HalfOpen);
+                }
+                inner.trial_in_flight = true;
+                Ok((None, true))
+            }
+        }
+    }
+    /// 记录成功；返回变化后的状态。
+    pub fn record_success(&self) -> Option<CircuitState> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.consecutive_failures = 0;
+        inner.trial_in_flight = false;
+        if inner.state == CircuitState::Closed {
+            return None;
+        }
+        inner.state = CircuitState::Closed;
+        inner.opened_at = None;
+        Some(CircuitState::Closed)
+    }
+    /// 记录失败；返回变化后的状态。
+    pub fn record_failure(&self, now: Instant) -> Option<CircuitState> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        inner.trial_in_flight = false;
+        let trip = match inner.state {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => self.failure_threshold > 0 && inner.consecutive_failures >= self.failure_threshold,
+            CircuitState::Open => false,
+        };
+        if !trip {
+            return None;
+        }
+        inner.state = CircuitState::Open;
+        inner.opened_at = Some(now);
+        Some(CircuitState::Open)
+    }
+}
+
+/// 熔断器放行许可，以 record_success / record_failure 结算请求结果。
+/// - 半开试探许可未结算即被丢弃时按失败记录（重新打开熔断器），避免试探位被永久占用
+#[must_use = "permit should be settled with record_success or record_failure"]
+#[derive(Debug)]
+pub struct BreakerPermit<'a> {
+    breaker: &'a CircuitBreaker,         // 所属熔断器
+    state_change: Option<CircuitState>,  // 放行时的状态变化
+    trial: bool,                         // 是否为未结算的半开试探
+}
+
+impl BreakerPermit<'_> {
+    /// 放行时的状态变化（打开转半开时为 Some(HalfOpen)）。
+    pub fn state_change(&self) -> Option<CircuitState> {
+        self.state_change
+    }
+    /// 是否为半开试探许可。
+    pub fn is_trial(&self) -> bool {
+        self.trial
+    }
+    /// 记录成功；返回变化后的状态。
+    pub fn record_success(mut self) -> Option<CircuitState> {
+        self.trial = false;
+        self.breaker.record_success()
+    }
+    /// 记录失败；返回变化后的状态。
+    pub fn record_failure(mut self, now: Instant) -> Option<CircuitState> {
+        self.trial = false;
+        self.breaker.record_failure(now)
+    }
+}
+
+impl Drop for BreakerPermit<'_> {
+    fn drop(&mut self) {
+        if self.trial {
+            self.breaker.record_failure(Instant::now()); // 试探请求被取消，结果未知，按失败处理
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//!
+//! Offchain REST Client Module
+//!
+//! 本模块实现共享异步 REST 客户端：复用连接池，按接口限流，按 max_retries 指数退避重试，连续失败后熔断，并通过观察者上报重试与熔断状态。
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::backoff::Backoff;
+use crate::breaker::{CircuitBreaker, CircuitState};
+use crate::config::RestClientConfig;
+use crate::error::RestError;
+use crate::limiter::RateLimiter;
+
+/// 客户端事件观察者，由各服务的日志模块实现。
+pub trait RestObserver: Send + Sync {
+    /// 即将重试。
+    fn on_retry(&self, _client: &str, _endpoint: &str, _attempt: u32, _delay: Duration, _error: &RestError) {}
+    /// 熔断器状态变化。
+    fn on_circuit_state(&self, _client: &str, _state: CircuitState) {}
+}
+
+/// 单次请求失败：错误与上游建议的重试等待（Retry-After）。
+struct Failure {
+    error: RestError,
+    retry_after: Option<Duration>,
+}
+
+impl From<RestError> for Failure {
+    fn from(error: RestError) -> Self {
+        Self { error, retry_after: None }
+    }
+}
+
+/// 共享异步 REST 客户端（克隆后共享连接池、限流与熔断状态）。
+#[derive(Clone)]
+pub struct RestClient {
+    config: Arc<RestClientConfig>,             // 客户端配置
+    client: Client,                            // 复用的 HTTP 客户端
+    backoff: Backoff,                          // 退避策略
+    limiter: Arc<RateLimiter>,                 // 按接口限流
+    breaker: Arc<CircuitBreaker>,              // 熔断器
+    observer: Option<Arc<dyn RestObserver>>,   // 事件观察者
+}
+
+impl RestClient {
+    /// 以默认重试、熔断参数创建客户端。
+    pub fn new(base_url: &str, api_key: &str, timeout_secs: u64) -> Self {
+        Self::from_config(RestClientConfig::new(base_url, api_key, timeout_secs))
+    }
+    /// 按配置创建客户端。
+    pub fn from_config(config: RestClientConfig) -> Self {
+        let client = Client::builder().timeout(config.timeout).build().unwrap_or_default();
+        Self {
+            client,
+            backoff: Backoff::new(config.base_backoff, config.max_backoff),
+            limiter: Arc::new(RateLimiter::new(config.rate_limits.clone())),
+            breaker: Arc::new(CircuitBreaker::new(config.failure_threshold, config.breaker_cooldown)),
+            observer: None,
+            config: Arc::new(config),
+        }
+    }
+    /// 设置事件观察者。
+    pub fn with_observer(mut self, observer: Arc<dyn RestObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+    /// 客户端配置。
+    pub fn config(&self) -> &RestClientConfig {
+        &self.config
+    }
+    /// API 基础 URL。
+    pub fn base_url(&self) -> &str {
+        &self.config.base_url
+    }
+    /// 熔断器当前状态。
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.state()
+    }
+    /// 拼接完整 URL。
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}/{}", self.config.base_url.trim_end_matches('/'), endpoint.trim_start_matches('/'))
+    }
+    /// 构建请求头；API 密钥含非法字符时返回 Config 错误。
+    fn build_headers(&self) -> Result<HeaderMap, RestError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if !self.config.api_key.is_empty() {
+            let value = HeaderValue::from_str(&self.config.api_key)
+                .map_err(|e| RestError::Config(format!("invalid api key header: {}", e)))?;
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+    /// 发送 GET 请求并解析 JSON（幂等，可重试）。
+    pub async fn get_json<T: DeserializeOwned>(&self, endpoint: &str, params: &[(&str, String)]) -> Result<T, RestError> {
+        let url = self.url(endpoint);
+        self.execute(endpoint, true, || self.client.get(&url).query(params)).await
+    }
+    /// 发送 POST 请求并解析 JSON（非幂等：仅在请求未到达服务端或被 429 拒绝时重试）。
+    pub async fn post_json<B: Serialize + ?Sized, T: DeserializeOwned>(&self, endpoint: &str, body: &B) -> Result<T, RestError> {
+        let url = self.url(endpoint);
+        self.execute(endpoint, false, || self.client.post(&url).json(body)).await
+    }
+    /// 发送幂等 POST 请求（如询价）并解析 JSON，失败时与 GET 一样重试。
+    pub async fn post_json_idempotent<B: Serialize + ?Sized, T: DeserializeOwned>(&self, endpoint: &str, body: &B) -> Result<T, RestError> {
+        let url = self.url(endpoint);
+        self.execute(endpoint, true, || self.client.post(&url).json(body)).await
+    }
+    /// 上报熔断状态变化。
+    fn report_state(&self, state: Option<CircuitState>) {
+        if let (Some(state), Some(observer)) = (state, &self.observer) {
+            observer.on_circuit_state(&self.config.base_url, state);
+        }
+    }
+    /// 执行请求：熔断检查 -> 限流 -> 发送 -> 记录结果 -> 按需退避重试。
+    async fn execute<T, F>(&self, endpoint: &str, idempotent: bool, build: F) -> Result<T, RestError>
+    where
+        T: DeserializeOwned,
+        F: Fn() -> RequestBuilder,
+    {
+        let headers = self.build_headers()?;
+        let mut attempt = 0u32;
+        loop {
+            // 许可跨越限流与发送的 await；请求 future 被取消时由许可记录试探失败
+            let permit = match self.breaker.acquire(Instant::now()) {
+                Ok(permit) => permit,
+                Err(_) => return Err(RestError::CircuitOpen(self.config.base_url.clone())),
+            };
+            self.report_state(permit.state_change());
+            self.limiter.acquire(endpoint).await;
+            let failure = match Self::send_once(build().headers(headers.clone())).await {
+                Ok(value) => {
+                    self.report_state(permit.record_success());
+                    return Ok(value);
+                }
+                Err(failure) => failure,
+            };
+            if failure.error.is_upstream_failure() {
+                self.report_state(permit.record_failure(Instant::now()));
+            } else {
+                self.report_state(permit.record_success()); // 上游可达（4xx、解析失败），不计入熔断
+            }
+            if attempt >= self.config.max_retries as u32 || !failure.error.is_retryable(idempotent) {
+                return Err(failure.error);
+            }
+            let mut delay = self.backoff.delay(attempt);
+            if let Some(retry_after) = failure.retry_after {
+                delay = delay.max(retry_after.min(self.config.max_backoff));
+            }
+            if let Some(observer) = &self.observer {
+                observer.on_retry(&self.config.base_url, endpoint, attempt + 1, delay, &failure.error);
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+    /// 发送一次请求，校验状态码并解析 JSON 响应。
+    async fn send_once<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, Failure> {
+        let response = request.send().await.map_err(RestError::from)?;
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            let message = response.text().await.unwrap_or_default();
+            return Err(Failure { error: RestError::Api { status: status.as_u16(), message }, retry_after });
+        }
+        Ok(response.json::<T>().await.map_err(RestError::from)?)
+    }
+}
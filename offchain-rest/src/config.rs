@@ -0,0 +1,71 @@
+//!
+//! Offchain REST Client Config Module
+//!
+//! 本模块定义共享 REST 客户端的配置：超时、重试次数与退避区间、按接口限流、熔断阈值与冷却时间。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 默认最大重试次数。
+pub const DEFAULT_MAX_RETRIES: u8 = 3;
+/// 默认初始退避时间。
+pub const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(100);
+/// 默认最大退避时间。
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+/// 默认熔断阈值（连续失败次数）。
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+/// 默认熔断冷却时间。
+pub const DEFAULT_BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// REST 客户端配置。
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestClientConfig {
+    pub base_url: String,                 // API 基础 URL
+    pub api_key: String,                  // API 密钥（Authorization 头）
+    pub timeout: Duration,                // 单次请求超时
+    pub max_retries: u8,                  // 最大重试次数（不含首次请求）
+    pub base_backoff: Duration,           // 初始退避时间
+    pub max_backoff: Duration,            // 最大退避时间
+    pub rate_limits: HashMap<String, u32>, // 接口 -> 每秒请求数上限
+    pub failure_threshold: u32,           // 连续失败多少次后熔断，0 表示不熔断
+    pub breaker_cooldown: Duration,       // 熔断后多久允许试探请求
+}
+
+impl RestClientConfig {
+    /// 以默认重试、熔断参数创建配置。
+    pub fn new(base_url: &str, api_key: &str, timeout_secs: u64) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+            timeout: Duration::from_secs(timeout_secs),
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            rate_limits: HashMap::new(),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            breaker_cooldown: DEFAULT_BREAKER_COOLDOWN,
+        }
+    }
+    /// 设置最大重试次数。
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+    /// 设置退避区间。
+    pub fn with_backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.base_backoff = base;
+        self.max_backoff = max;
+        self
+    }
+    /// 设置接口限流（每秒请求数）。
+    pub fn with_rate_limit(mut self, endpoint: &str, per_second: u32) -> Self {
+        self.rate_limits.insert(endpoint.trim_matches('/').to_string(), per_second);
+        self
+    }
+    /// 设置熔断阈值与冷却时间。
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.failure_threshold = failure_threshold;
+        self.breaker_cooldown = cooldown;
+        self
+    }
+}
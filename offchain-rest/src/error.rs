@@ -0,0 +1,55 @@
+//!
+//! Offchain REST Error Definitions
+//!
+//! 本模块定义共享 REST 客户端的错误类型，并区分可重试与不可重试错误。
+
+use thiserror::Error;
+
+/// REST 客户端错误类型枚举。
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum RestError {
+    /// 网络请求失败；connect 为 true 表示请求未到达服务端。
+    #[error("http request failed: {message}")]
+    Http { message: String, connect: bool },
+    /// 上游 API 返回非 2xx 状态码。
+    #[error("upstream returned {status}: {message}")]
+    Api { status: u16, message: String },
+    /// 响应数据解析失败。
+    #[error("failed to decode response: {0}")]
+    Decode(String),
+    /// 客户端配置无效。
+    #[error("invalid config: {0}")]
+    Config(String),
+    /// 熔断器打开，请求被快速拒绝。
+    #[error("circuit open for {0}")]
+    CircuitOpen(String),
+}
+
+impl RestError {
+    /// 是否为上游故障（计入熔断器失败次数）。
+    pub fn is_upstream_failure(&self) -> bool {
+        match self {
+            RestError::Http { .. } => true,
+            RestError::Api { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+    /// 是否可重试；非幂等请求仅在请求未到达服务端或被限流（429）时重试。
+    pub fn is_retryable(&self, idempotent: bool) -> bool {
+        match self {
+            RestError::Http { connect, .. } => idempotent || *connect,
+            RestError::Api { status, .. } => *status == 429 || (idempotent && *status >= 500),
+            _ => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RestError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            RestError::Decode(err.to_string())
+        } else {
+            RestError::Http { message: err.to_string(), connect: err.is_connect() }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//!
+//! Offchain REST Client Library Entry
+//!
+//! 本模块为链下服务共享的异步 REST 客户端主入口，提供连接复用、指数退避重试、按接口限流与熔断，供 dex-offchain、oracle-offchain 复用。
+
+// 导入并公开所有子模块。
+pub mod backoff;  // 退避策略模块
+pub mod breaker;  // 熔断器模块
+pub mod client;   // REST 客户端模块
+pub mod config;   // 客户端配置模块
+pub mod error;    // 错误类型模块
+pub mod limiter;  // 限流模块
+
+// 重新导出常用类型和函数，便于外部访问。
+pub use backoff::*;
+pub use breaker::*;
+pub use client::*;
+pub use config::*;
+pub use error::*;
+pub use limiter::*;
@@ -0,0 +1,67 @@
+//!
+//! Offchain REST Rate Limiter Module
+//!
+//! 本模块实现按接口的令牌桶限流：每秒补充 N 个令牌、桶容量为 N；令牌不足时预占并返回需等待的时间，使并发请求按到达顺序排队。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 令牌桶。
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,       // 剩余令牌（可为负，表示已预占的排队请求）
+    updated: Instant,  // 上次补充时间
+}
+
+/// 按接口限流器。
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    limits: HashMap<String, u32>,             // 接口 -> 每秒请求数
+    buckets: Mutex<HashMap<String, Bucket>>,  // 接口 -> 令牌桶
+}
+
+impl RateLimiter {
+    /// 按接口限额创建限流器（限额为 0 的接口不限流）。
+    pub fn new(limits: HashMap<String, u32>) -> Self {
+        let limits = limits.into_iter().filter(|(_, per_second)| *per_second > 0).collect();
+        Self { limits, buckets: Mutex::new(HashMap::new()) }
+    }
+    /// 匹配接口限额：精确匹配或按路径段前缀匹配（如 `price` 匹配 `price/SOL/USD`），取最长者。
+    fn matching_limit(&self, endpoint: &str) -> Option<(&str, u32)> {
+        let endpoint = endpoint.trim_matches('/');
+        self.limits
+            .iter()
+            .filter(|(key, _)| {
+                endpoint == key.as_str()
+                    || (endpoint.starts_with(key.as_str()) && endpoint[key.len()..].starts_with('/'))
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(key, per_second)| (key.as_str(), *per_second))
+    }
+    /// 预占一个令牌，返回需等待的时间（未配置限额时为 0）。
+    pub fn reserve(&self, endpoint: &str, now: Instant) -> Duration {
+        let Some((key, per_second)) = self.matching_limit(endpoint) else {
+            return Duration::ZERO;
+        };
+        let rate = per_second as f64;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket { tokens: rate, updated: now });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+    /// 等待直到获得接口令牌。
+    pub async fn acquire(&self, endpoint: &str) {
+        let wait = self.reserve(endpoint, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use offchain_rest::{Backoff, CircuitBreaker, CircuitState, RateLimiter, RestClient, RestClientConfig, RestError, RestObserver};
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// 记录客户端事件的观察者。
+#[derive(Default)]
+struct RecordingObserver {
+    retries: Mutex<Vec<(String, u32)>>,
+    states: Mutex<Vec<CircuitState>>,
+}
+
+impl RestObserver for RecordingObserver {
+    fn on_retry(&self, _client: &str, endpoint: &str, attempt: u32, _delay: Duration, _error: &RestError) {
+        self.retries.lock().unwrap().push((endpoint.to_string(), attempt));
+    }
+    fn on_circuit_state(&self, _client: &str, state: CircuitState) {
+        self.states.lock().unwrap().push(state);
+    }
+}
+
+fn fast_config(uri: &str, max_retries: u8) -> RestClientConfig {
+    RestClientConfig::new(uri, "", 5)
+        .with_max_retries(max_retries)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+}
+
+#[test]
+fn test_backoff_is_exponential_with_bounded_jitter() {
+    let backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(1_000));
+    assert_eq!(backoff.ceiling(0), Duration::from_millis(100));
+    assert_eq!(backoff.ceiling(2), Duration::from_millis(400));
+    assert_eq!(backoff.ceiling(10), Duration::from_millis(1_000)); // 不超过最大退避
+    assert_eq!(backoff.delay_with_jitter(1, 0.0), Duration::from_millis(100));
+    assert_eq!(backoff.delay_with_jitter(1, 1.0), Duration::from_millis(200));
+    for attempt in 0..8 {
+        let delay = backoff.delay(attempt);
+        assert!(delay >= backoff.ceiling(attempt) / 2 && delay <= backoff.ceiling(attempt));
+    }
+}
+
+#[test]
+fn test_rate_limiter_spaces_requests_per_endpoint() {
+    let limiter = RateLimiter::new(HashMap::from([("price".to_string(), 2)]));
+    let now = Instant::now();
+    assert_eq!(limiter.reserve("price/SOL/USD", now), Duration::ZERO);
+    assert_eq!(limiter.reserve("price/BTC/USD", now), Duration::ZERO);
+    assert_eq!(limiter.reserve("price/ETH/USD", now), Duration::from_millis(500)); // 桶已空，排队等待
+    assert_eq!(limiter.reserve("pricefeed", now), Duration::ZERO); // 非路径段前缀，不限流
+    assert_eq!(limiter.reserve("twap/SOL/USD", now), Duration::ZERO);
+    assert_eq!(limiter.reserve("price/SOL/USD", now + Duration::from_secs(2)), Duration::ZERO); // 令牌已补充
+}
+
+#[test]
+fn test_circuit_breaker_transitions() {
+    let breaker = CircuitBreaker::new(2, Duration::from_secs(10));
+    let now = Instant::now();
+    assert_eq!(breaker.record_failure(now), None);
+    assert_eq!(breaker.record_failure(now), Some(CircuitState::Open));
+    assert!(breaker.try_acquire(now + Duration::from_secs(1)).is_err());
+    assert_eq!(breaker.try_acquire(now + Duration::from_secs(10)), Ok(Some(CircuitState::HalfOpen)));
+    assert!(breaker.try_acquire(now + Duration::from_secs(10)).is_err()); // 仅放行一个试探请求
+    assert_eq!(breaker.record_failure(now + Duration::from_secs(10)), Some(CircuitState::Open));
+    assert_eq!(breaker.try_acquire(now + Duration::from_secs(20)), Ok(Some(CircuitState::HalfOpen)));
+    assert_eq!(breaker.record_success(), Some(CircuitState::Closed));
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[test]
+fn test_dropped_trial_permit_reopens_breaker() {
+    let breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+    let now = Instant::now();
+    assert_eq!(breaker.record_failure(now), Some(CircuitState::Open));
+
+    let permit = breaker.acquire(now + Duration::from_secs(10)).unwrap();
+    assert!(permit.is_trial());
+    assert_eq!(permit.state_change(), Some(CircuitState::HalfOpen));
+    assert!(breaker.acquire(now + Duration::from_secs(10)).is_err()); // 试探进行中
+    drop(permit); // 试探被取消，按失败记录
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    // 冷却后重新放行试探，结算后不再重复记录
+    let permit = breaker.acquire(Instant::now() + Duration::from_secs(10)).unwrap();
+    assert_eq!(permit.record_success(), Some(CircuitState::Closed));
+    let permit = breaker.acquire(Instant::now()).unwrap();
+    assert!(!permit.is_trial());
+    drop(permit);
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_get_retries_server_errors_until_success() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "price": 1.5 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let observer = Arc::new(RecordingObserver::default());
+    let client = RestClient::from_config(fast_config(&server.uri(), 3)).with_observer(observer.clone());
+    let body: Value = client.get_json("price", &[]).await.unwrap();
+    assert_eq!(body["price"], 1.5);
+    assert_eq!(*observer.retries.lock().unwrap(), vec![("price".to_string(), 1), ("price".to_string(), 2)]);
+}
+
+#[tokio::test]
+async fn test_retries_are_bounded_by_max_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(3) // 首次请求 + 2 次重试
+        .mount(&server)
+        .await;
+
+    let client = RestClient::from_config(fast_config(&server.uri(), 2));
+    let err = client.get_json::<Value>("price", &[]).await.unwrap_err();
+    assert!(matches!(err, RestError::Api { status: 500, .. }));
+}
+
+#[tokio::test]
+async fn test_client_errors_and_non_idempotent_posts_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/swap"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/quote"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = RestClient::from_config(fast_config(&server.uri(), 1));
+    assert!(matches!(client.get_json::<Value>("missing", &[]).await, Err(RestError::Api { status: 404, .. })));
+    assert!(client.post_json::<_, Value>("swap", &json!({})).await.is_err());
+    assert!(client.post_json_idempotent::<_, Value>("quote", &json!({})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_post_retries_when_rate_limited() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/swap"))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/swap"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true })))
+        .mount(&server)
+        .await;
+
+    let client = RestClient::from_config(fast_config(&server.uri(), 1));
+    let body: Value = client.post_json("swap", &json!({})).await.unwrap();
+    assert_eq!(body["ok"], true);
+}
+
+#[tokio::test]
+async fn test_circuit_opens_fails_fast_and_recovers() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "price": 2.0 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let observer = Arc::new(RecordingObserver::default());
+    let config = fast_config(&server.uri(), 0).with_circuit_breaker(2, Duration::from_millis(50));
+    let client = RestClient::from_config(config).with_observer(observer.clone());
+    for _ in 0..2 {
+        assert!(matches!(client.get_json::<Value>("price", &[]).await, Err(RestError::Api { status: 500, .. })));
+    }
+    assert_eq!(client.circuit_state(), CircuitState::Open);
+    assert!(matches!(client.get_json::<Value>("price", &[]).await, Err(RestError::CircuitOpen(_)))); // 快速失败，不访问上游
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let body: Value = client.get_json("price", &[]).await.unwrap();
+    assert_eq!(body["price"], 2.0);
+    assert_eq!(client.circuit_state(), CircuitState::Closed);
+    assert_eq!(
+        *observer.states.lock().unwrap(),
+        vec![CircuitState::Open, CircuitState::HalfOpen, CircuitState::Closed]
+    );
+}
+
+#[tokio::test]
+async fn test_cancelled_trial_request_does_not_wedge_half_open() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "price": 3.0 })).set_delay(Duration::from_millis(200)))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "price": 2.0 })))
+        .mount(&server)
+        .await;
+
+    let config = fast_config(&server.uri(), 0).with_circuit_breaker(1, Duration::from_millis(50));
+    let client = RestClient::from_config(config);
+    assert!(client.get_json::<Value>("price", &[]).await.is_err());
+    assert_eq!(client.circuit_state(), CircuitState::Open);
+
+    // 试探请求在响应前被取消
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(tokio::time::timeout(Duration::from_millis(20), client.get_json::<Value>("price", &[])).await.is_err());
+    assert_eq!(client.circuit_state(), CircuitState::Open); // 按失败记录，未卡在半开
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    client.get_json::<Value>("price", &[]).await.unwrap();
+    assert_eq!(client.circuit_state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_cloned_clients_share_rate_limit() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .mount(&server)
+        .await;
+
+    let client = RestClient::from_config(fast_config(&server.uri(), 0).with_rate_limit("price", 5));
+    let start = Instant::now();
+    let requests: Vec<_> = (0..7)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.get_json::<Value>("price", &[]).await })
+        })
+        .collect();
+    for request in requests {
+        request.await.unwrap().unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(350)); // 第 6、7 个请求分别等待 200ms、400ms
+}
@@ -0,0 +1,68 @@
+//!
+//! Oracle Offchain Error Definitions
+//!
+//! 本模块定义预言机离线服务的错误类型，覆盖网络、上游 API、解析、注册表查找、数据缺失与参数校验等场景。
+
+use offchain_rest::RestError;
+use thiserror::Error;
+
+/// 预言机离线服务错误类型枚举。
+#[derive(Debug, Error)]
+pub enum OffchainOracleError {
+    /// 网络请求失败（连接、超时等）。
+    #[error("http request failed: {0}")]
+    Http(String),
+    /// 上游 API 返回非 2xx 状态码。
+    #[error("upstream returned {status}: {message}")]
+    Api { status: u16, message: String },
+    /// 响应数据解析失败。
+    #[error("failed to decode response: {0}")]
+    Decode(String),
+    /// 适配器未注册。
+    #[error("oracle adapter not registered: {0}")]
+    AdapterNotFound(String),
+    /// 无可用价格数据。
+    #[error("no price data for {0}")]
+    NoData(String),
+    /// 输入参数无效。
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    /// 适配器不支持该操作。
+    #[error("operation not supported by {0}")]
+    Unsupported(String),
+    /// 配置加载失败。
+    #[error("invalid config: {0}")]
+    Config(String),
+    /// 上游熔断中，暂不可用。
+    #[error("upstream unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl From<reqwest::Error> for OffchainOracleError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            OffchainOracleError::Decode(err.to_string())
+        } else {
+            OffchainOracleError::Http(err.to_string())
+        }
+    }
+}
+
+impl From<RestError> for OffchainOracleError {
+    fn from(err: RestError) -> Self {
+        match err {
+            RestError::Http { message, .. } => OffchainOracleError::Http(message),
+            RestError::Api { status, message } => OffchainOracleError::Api { status, message },
+            RestError::Decode(message) => OffchainOracleError::Decode(message),
+            RestError::Config(message) => OffchainOracleError::Config(message),
+            RestError::CircuitOpen(base_url) => OffchainOracleError::Unavailable(base_url),
+        }
+    }
+}
+
+/// 适配器 trait 以字符串返回错误，便于跨 crate 实现；typed 错误经 Display 转换。
+impl From<OffchainOracleError> for String {
+    fn from(err: OffchainOracleError) -> Self {
+        err.to_string()
+    }
+}
@@ -3,7 +3,14 @@
 //!
 //! 本模块实现预言机离线服务工厂模式，支持多种预言机实例的创建、注册与管理，确保可扩展性与合规性。
 
-use crate::config::OracleOffchainConfig;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use crate::config::{OracleAdapterConfig, OracleOffchainConfig, OracleServiceConfig};
+use crate::error::OffchainOracleError;
+use crate::history::PRICE_HISTORY;
+use crate::rest::RestOracleAdapter;
+use crate::traits::OffchainOracleAdapter;
 
 /// 预言机工厂结构体。
 pub struct OracleFactory {
@@ -21,3 +28,73 @@ impl OracleFactory {
         println!("Initializing Oracle with config: {:?}", self.config);
     }
 }
+
+/// 按配置创建适配器；未知类型返回 Config 错误。
+pub fn build_adapter(cfg: &OracleAdapterConfig) -> Result<Arc<dyn OffchainOracleAdapter>, OffchainOracleError> {
+    match cfg.kind.as_str() {
+        "rest" => Ok(Arc::new(RestOracleAdapter::new(&cfg.name, cfg.rest_client()))),
+        other => Err(OffchainOracleError::Config(format!("unknown adapter kind {} for {}", other, cfg.name))),
+    }
+}
+
+/// 异步预言机适配器注册表（线程安全，按名称注册/查找）。
+#[derive(Default)]
+pub struct OffchainOracleFactory {
+    adapters: RwLock<HashMap<String, Arc<dyn OffchainOracleAdapter>>>, // 名称 -> 适配器
+    managed: RwLock<HashSet<String>>,                                  // 由配置文件注册的适配器
+}
+
+impl OffchainOracleFactory {
+    /// 创建空注册表。
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 注册适配器；同名适配器被替换并返回旧实例。
+    pub fn register(&self, name: &str, adapter: Arc<dyn OffchainOracleAdapter>) -> Option<Arc<dyn OffchainOracleAdapter>> {
+        self.adapters.write().unwrap_or_else(|e| e.into_inner()).insert(name.to_string(), adapter)
+    }
+    /// 注销适配器。
+    pub fn unregister(&self, name: &str) -> Option<Arc<dyn OffchainOracleAdapter>> {
+        self.adapters.write().unwrap_or_else(|e| e.into_inner()).remove(name)
+    }
+    /// 按名称查找适配器。
+    pub fn get(&self, name: &str) -> Option<Arc<dyn OffchainOracleAdapter>> {
+        self.adapters.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned()
+    }
+    /// 已注册适配器名称（按字母序）。
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.adapters.read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+        names.sort();
+        names
+    }
+    /// 按配置同步注册表：注册/替换启用的适配器，注销此前由配置注册、现已删除或禁用的适配器。
+    /// - 代码中手动注册的适配器不受影响
+    /// - 单个适配器创建失败仅记录日志，不影响其余适配器
+    /// - 返回本次注销的适配器名称
+    pub fn apply_config(&self, cfg: &OracleServiceConfig) -> Vec<String> {
+        let mut managed = self.managed.write().unwrap_or_else(|e| e.into_inner());
+        let mut next = HashSet::new();
+        for adapter_cfg in cfg.adapters.iter().filter(|a| a.enabled) {
+            match build_adapter(adapter_cfg) {
+                Ok(adapter) => {
+                    self.register(&adapter_cfg.name, adapter);
+                    next.insert(adapter_cfg.name.clone());
+                }
+                Err(e) => tracing::warn!("[Config] skip adapter: {}", e),
+            }
+        }
+        let mut removed: Vec<String> = managed.difference(&next).cloned().collect();
+        removed.sort();
+        for name in &removed {
+            self.unregister(name);
+            PRICE_HISTORY.clear_adapter(name);
+        }
+        *managed = next;
+        removed
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 全局预言机适配器注册表，REST 路由从此查找适配器。
+    pub static ref OFFCHAIN_ORACLE_FACTORY: OffchainOracleFactory = OffchainOracleFactory::new();
+}
@@ -0,0 +1,83 @@
+//!
+//! Oracle Offchain Price History Module
+//!
+//! 本模块按（适配器, 交易对）保存最近的价格样本，供上游不支持 TWAP 时在本地计算时间加权平均价格。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+use crate::traits::OffchainTwapResponse;
+
+/// 每个（适配器, 交易对）保留的默认样本数。
+pub const DEFAULT_HISTORY_CAPACITY: usize = 1024;
+
+/// 价格样本。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceSample {
+    pub timestamp: i64, // 价格发布时间（Unix 秒）
+    pub price: f64,     // 价格
+}
+
+/// 价格样本存储（线程安全、按容量淘汰最旧样本）。
+pub struct PriceHistoryStore {
+    samples: RwLock<HashMap<(String, String), VecDeque<PriceSample>>>, // (适配器, 交易对) -> 按时间升序的样本
+    capacity: usize,                                                  // 单个序列容量
+}
+
+impl PriceHistoryStore {
+    /// 创建指定容量的存储。
+    pub fn new(capacity: usize) -> Self {
+        Self { samples: RwLock::new(HashMap::new()), capacity: capacity.max(1) }
+    }
+    /// 记录样本；时间不晚于最新样本的重复发布被忽略，返回是否写入。
+    pub fn record(&self, adapter: &str, symbol: &str, sample: PriceSample) -> bool {
+        let mut samples = self.samples.write().unwrap_or_else(|e| e.into_inner());
+        let series = samples.entry((adapter.to_string(), symbol.to_string())).or_default();
+        if series.back().is_some_and(|last| last.timestamp >= sample.timestamp) {
+            return false;
+        }
+        if series.len() == self.capacity {
+            series.pop_front();
+        }
+        series.push_back(sample);
+        true
+    }
+    /// 最新样本。
+    pub fn latest(&self, adapter: &str, symbol: &str) -> Option<PriceSample> {
+        let samples = self.samples.read().unwrap_or_else(|e| e.into_inner());
+        samples.get(&(adapter.to_string(), symbol.to_string())).and_then(|series| series.back().copied())
+    }
+    /// 计算 [now - window_secs, now] 内的 TWAP。
+    /// - 每个样本价格持续到下一样本（最新样本持续到 now），窗口前最后一个样本覆盖窗口起点
+    /// - 窗口内没有任何样本时返回 None，避免以过期价格作答
+    pub fn twap(&self, adapter: &str, symbol: &str, now: i64, window_secs: u64) -> Option<OffchainTwapResponse> {
+        let samples = self.samples.read().unwrap_or_else(|e| e.into_inner());
+        let series = samples.get(&(adapter.to_string(), symbol.to_string()))?;
+        let start = now.saturating_sub(window_secs as i64);
+        let latest = series.iter().rev().find(|s| s.timestamp <= now)?;
+        if latest.timestamp < start {
+            return None;
+        }
+        let mut weighted = 0.0;
+        let mut total = 0i64;
+        for (i, sample) in series.iter().enumerate() {
+            let next = series.get(i + 1).map_or(now, |n| n.timestamp.min(now));
+            let seg_start = sample.timestamp.max(start);
+            if next > seg_start {
+                weighted += sample.price * (next - seg_start) as f64;
+                total += next - seg_start;
+            }
+        }
+        let twap = if total > 0 { weighted / total as f64 } else { latest.price };
+        Some(OffchainTwapResponse { twap, window_secs, last_updated: latest.timestamp })
+    }
+    /// 清除适配器的全部样本（适配器注销时调用）。
+    pub fn clear_adapter(&self, adapter: &str) {
+        self.samples.write().unwrap_or_else(|e| e.into_inner()).retain(|(name, _), _| name != adapter);
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 全局价格样本存储，价格查询与后台刷新均写入此处。
+    pub static ref PRICE_HISTORY: PriceHistoryStore = PriceHistoryStore::new(DEFAULT_HISTORY_CAPACITY);
+}
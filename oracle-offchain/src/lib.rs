@@ -6,9 +6,13 @@
 // 导入并公开所有子模块。
 pub mod chainlink;    // Chainlink 适配器模块
 pub mod config;       // 配置模块
+pub mod error;        // 错误类型模块
 pub mod factory;      // 工厂模块
+pub mod history;      // 价格样本模块
 pub mod logging;      // 日志模块
+pub mod metrics;      // 指标模块
 pub mod pyth;         // Pyth 适配器模块
+pub mod refresh;      // 后台刷新模块
 pub mod rest;         // REST API 客户端模块
 pub mod switchboard;  // Switchboard 适配器模块
 pub mod traits;       // 通用 trait 模块
@@ -16,9 +20,13 @@ pub mod traits;       // 通用 trait 模块
 // 重新导出常用类型和函数，便于外部访问。
 pub use chainlink::*;
 pub use config::*;
+pub use error::*;
 pub use factory::*;
+pub use history::*;
 pub use logging::*;
+pub use metrics::*;
 pub use pyth::*;
+pub use refresh::*;
 pub use rest::*;
 pub use switchboard::*;
 pub use traits::*;
@@ -37,8 +45,12 @@ pub async fn main() -> std::io::Result<()> {
     tokio::spawn(async move {
         config::watch_config(config_path, |cfg| {
             tracing::info!("[Config] Oracle config changed: {:?}", cfg);
-            // 这里可根据cfg.adapters动态注册/注销Adapter并调用init
-            // 示例：遍历cfg.adapters，注册/注销Adapter
+            // 按配置注册/注销Adapter，并重建后台刷新任务
+            let removed = factory::OFFCHAIN_ORACLE_FACTORY.apply_config(&cfg);
+            if !removed.is_empty() {
+                tracing::info!("[Config] Oracle adapters removed: {:?}", removed);
+            }
+            refresh::PRICE_REFRESHER.reload(&cfg);
         })
         .await;
     });
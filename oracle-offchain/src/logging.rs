@@ -3,6 +3,10 @@
 //!
 //! 本模块实现预言机离线服务日志工具，支持标准化日志输出，便于调试与运维。
 
+use std::time::Duration;
+
+use offchain_rest::{CircuitState, RestError, RestObserver};
+
 /// 日志工具结构体。
 pub struct OracleLogger;
 
@@ -15,4 +19,17 @@ impl OracleLogger {
     pub fn error(msg: &str) {
         eprintln!("[ERROR] {}", msg);
     }
-} 
\ No newline at end of file
+}
+
+/// 上报共享 REST 客户端的重试与熔断状态。
+impl RestObserver for OracleLogger {
+    fn on_retry(&self, client: &str, endpoint: &str, attempt: u32, delay: Duration, error: &RestError) {
+        Self::info(&format!("[Rest] {} /{} retry #{} in {:?}: {}", client, endpoint, attempt, delay, error));
+    }
+    fn on_circuit_state(&self, client: &str, state: CircuitState) {
+        match state {
+            CircuitState::Open => Self::error(&format!("[Rest] {} circuit opened", client)),
+            _ => Self::info(&format!("[Rest] {} circuit {:?}", client, state)),
+        }
+    }
+}
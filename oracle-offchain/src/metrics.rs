@@ -0,0 +1,65 @@
+//!
+//! Oracle Offchain Metrics Module
+//!
+//! 本模块基于 prometheus 统计各适配器的请求次数、错误次数与延迟，并以文本格式导出供 `/metrics` 抓取。
+
+use std::time::Instant;
+
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+/// 适配器请求指标。
+pub struct OracleMetrics {
+    registry: Registry,           // 独立注册表，避免与进程内其他指标冲突
+    requests: IntCounterVec,      // 请求次数（adapter, operation）
+    errors: IntCounterVec,        // 错误次数（adapter, operation）
+    latency: HistogramVec,        // 请求延迟（秒）
+}
+
+impl OracleMetrics {
+    /// 创建并注册全部指标。
+    pub fn new() -> Self {
+        let labels = &["adapter", "operation"];
+        let requests = IntCounterVec::new(Opts::new("oracle_adapter_requests_total", "Oracle adapter requests"), labels)
+            .expect("valid requests metric");
+        let errors = IntCounterVec::new(Opts::new("oracle_adapter_errors_total", "Oracle adapter failed requests"), labels)
+            .expect("valid errors metric");
+        let latency = HistogramVec::new(
+            HistogramOpts::new("oracle_adapter_latency_seconds", "Oracle adapter request latency in seconds"),
+            labels,
+        )
+        .expect("valid latency metric");
+        let registry = Registry::new();
+        registry.register(Box::new(requests.clone())).expect("register requests metric");
+        registry.register(Box::new(errors.clone())).expect("register errors metric");
+        registry.register(Box::new(latency.clone())).expect("register latency metric");
+        Self { registry, requests, errors, latency }
+    }
+    /// 记录一次请求结果。
+    pub fn observe(&self, adapter: &str, operation: &str, started: Instant, ok: bool) {
+        let labels = [adapter, operation];
+        self.requests.with_label_values(&labels).inc();
+        self.latency.with_label_values(&labels).observe(started.elapsed().as_secs_f64());
+        if !ok {
+            self.errors.with_label_values(&labels).inc();
+        }
+    }
+    /// 以 Prometheus 文本格式导出。
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::warn!("[Metrics] encode failed: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+impl Default for OracleMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 全局指标实例。
+    pub static ref ORACLE_METRICS: OracleMetrics = OracleMetrics::new();
+}
@@ -0,0 +1,67 @@
+//!
+//! Oracle Offchain Refresh Module
+//!
+//! 本模块按配置的刷新间隔在后台轮询各适配器价格，写入本地样本供 TWAP 计算；配置变更时重建刷新任务。
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::config::OracleServiceConfig;
+use crate::factory::OFFCHAIN_ORACLE_FACTORY;
+use crate::rest::fetch_price;
+
+/// 后台价格刷新器。
+#[derive(Default)]
+pub struct PriceRefresher {
+    tasks: Mutex<Vec<JoinHandle<()>>>, // 运行中的刷新任务
+}
+
+impl PriceRefresher {
+    /// 创建空刷新器。
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 停止旧任务并按新配置启动刷新任务（需在 tokio 运行时内调用）。
+    /// - 仅启用、refresh_interval_secs > 0 且配置了 symbols 的适配器会被刷新
+    pub fn reload(&self, cfg: &OracleServiceConfig) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.drain(..).for_each(|task| task.abort());
+        for adapter_cfg in &cfg.adapters {
+            if !adapter_cfg.enabled || adapter_cfg.refresh_interval_secs == 0 || adapter_cfg.symbols.is_empty() {
+                continue;
+            }
+            let name = adapter_cfg.name.clone();
+            let symbols = adapter_cfg.symbols.clone();
+            let period = Duration::from_secs(adapter_cfg.refresh_interval_secs);
+            tasks.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    interval.tick().await;
+                    let Some(adapter) = OFFCHAIN_ORACLE_FACTORY.get(&name) else {
+                        continue;
+                    };
+                    for symbol in &symbols {
+                        if let Err(e) = fetch_price(&adapter, symbol).await {
+                            tracing::warn!("[Refresh] {} {}: {}", name, symbol, e);
+                        }
+                    }
+                }
+            }));
+        }
+    }
+    /// 运行中的刷新任务数量。
+    pub fn task_count(&self) -> usize {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+    /// 停止全部刷新任务。
+    pub fn stop(&self) {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner()).drain(..).for_each(|task| task.abort());
+    }
+}
+
+lazy_static::lazy_static! {
+    /// 全局价格刷新器。
+    pub static ref PRICE_REFRESHER: PriceRefresher = PriceRefresher::new();
+}
@@ -1,64 +1,275 @@
 //!
 //! Oracle Offchain REST API Module
 //!
-//! 本模块实现预言机离线服务的 RESTful API 客户端，支持 HTTP 请求、响应解析、错误处理等，确保与预言机后端安全、合规、高效交互。
-
-use std::collections::HashMap;
-use std::time::Duration;
-use reqwest::blocking::{Client, Response};
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, AUTHORIZATION};
-
-/// REST 客户端结构体。
-pub struct RestClient {
-    pub base_url: String,         // API 基础 URL
-    pub api_key: String,          // API 密钥
-    pub timeout_secs: u64,        // 超时时间（秒）
-}
-
-impl RestClient {
-    /// 创建新的 REST 客户端。
-    pub fn new(base_url: &str, api_key: &str, timeout_secs: u64) -> Self {
-        Self {
-            base_url: base_url.to_string(),
-            api_key: api_key.to_string(),
-            timeout_secs,
+//! 本模块实现预言机离线服务的 RESTful 服务端路由与 REST 转发适配器，HTTP 请求经共享客户端发送（重试、限流、熔断），支持响应解析、错误处理、指标统计等，确保与预言机后端安全、合规、高效交互。
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use actix_web::{web, HttpResponse};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OffchainOracleError;
+use crate::factory::OFFCHAIN_ORACLE_FACTORY;
+use crate::history::{PriceSample, PRICE_HISTORY};
+use crate::metrics::ORACLE_METRICS;
+use crate::traits::{OffchainOracleAdapter, OffchainPriceRequest, OffchainPriceResponse, OffchainTwapRequest, OffchainTwapResponse};
+
+/// TWAP 默认时间窗口（秒）。
+pub const DEFAULT_TWAP_WINDOW_SECS: u64 = 300;
+
+/// 共享异步 REST 客户端（连接复用、退避重试、限流与熔断），见 `offchain-rest`。
+pub use offchain_rest::{RestClient, RestClientConfig, RestError};
+
+/// 通过 REST 转发到远端价格服务的适配器（远端需提供 GET /price/{symbol}，可选 GET /twap/{symbol}）。
+pub struct RestOracleAdapter {
+    name: String,        // 适配器名称
+    client: RestClient,  // 远端服务客户端
+}
+
+impl RestOracleAdapter {
+    /// 创建 REST 转发适配器。
+    pub fn new(name: &str, client: RestClient) -> Self {
+        Self { name: name.to_string(), client }
+    }
+}
+
+#[async_trait]
+impl OffchainOracleAdapter for RestOracleAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn get_price(&self, req: OffchainPriceRequest) -> Result<OffchainPriceResponse, String> {
+        Ok(self.client.get_json(&format!("price/{}", req.symbol), &[]).await.map_err(OffchainOracleError::from)?)
+    }
+    async fn get_twap(&self, req: OffchainTwapRequest) -> Result<OffchainTwapResponse, String> {
+        let params = [("window_secs", req.window_secs.to_string())];
+        Ok(self.client.get_json(&format!("twap/{}", req.symbol), &params).await.map_err(OffchainOracleError::from)?)
+    }
+}
+
+/// 查询价格：统计指标，并将有效价格写入本地样本。
+pub async fn fetch_price(adapter: &Arc<dyn OffchainOracleAdapter>, symbol: &str) -> Result<OffchainPriceResponse, String> {
+    let started = Instant::now();
+    let result = adapter
+        .get_price(OffchainPriceRequest { symbol: symbol.to_string() })
+        .await
+        .and_then(|resp| {
+            if resp.price.is_finite() && resp.price > 0.0 {
+                Ok(resp)
+            } else {
+                Err(OffchainOracleError::Decode(format!("invalid price {} for {}", resp.price, symbol)).into())
+            }
+        });
+    ORACLE_METRICS.observe(adapter.name(), "price", started, result.is_ok());
+    if let Ok(resp) = &result {
+        let timestamp = if resp.last_updated > 0 { resp.last_updated } else { chrono::Utc::now().timestamp() };
+        PRICE_HISTORY.record(adapter.name(), symbol, PriceSample { timestamp, price: resp.price });
+    }
+    result
+}
+
+/// 查询 TWAP 的结果来源。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TwapSource {
+    Upstream, // 上游适配器直接返回
+    Local,    // 本地样本计算
+}
+
+/// 查询 TWAP：优先使用上游结果，失败或不支持时回退到本地样本。
+pub async fn fetch_twap(
+    adapter: &Arc<dyn OffchainOracleAdapter>,
+    symbol: &str,
+    window_secs: u64,
+    now: i64,
+) -> Result<(OffchainTwapResponse, TwapSource), String> {
+    let started = Instant::now();
+    let upstream = adapter
+        .get_twap(OffchainTwapRequest { symbol: symbol.to_string(), window_secs })
+        .await;
+    let unsupported: String = OffchainOracleError::Unsupported(adapter.name().to_string()).into();
+    match upstream {
+        Ok(resp) => {
+            ORACLE_METRICS.observe(adapter.name(), "twap", started, true);
+            Ok((resp, TwapSource::Upstream))
         }
+        Err(message) => {
+            if message != unsupported {
+                ORACLE_METRICS.observe(adapter.name(), "twap", started, false);
+            }
+            match PRICE_HISTORY.twap(adapter.name(), symbol, now, window_secs) {
+                Some(resp) => Ok((resp, TwapSource::Local)),
+                None if message == unsupported => Err(OffchainOracleError::NoData(symbol.to_string()).into()),
+                None => Err(message),
+            }
+        }
+    }
+}
+
+/// 查询参数：可选指定适配器。
+#[derive(Debug, Deserialize)]
+pub struct PriceQuery {
+    pub adapter: Option<String>, // 适配器名称；缺省时按名称顺序尝试全部适配器
+}
+
+/// TWAP 查询参数。
+#[derive(Debug, Deserialize)]
+pub struct TwapQuery {
+    pub adapter: Option<String>,  // 适配器名称；缺省时按名称顺序尝试全部适配器
+    pub window_secs: Option<u64>, // 时间窗口（秒），缺省为 DEFAULT_TWAP_WINDOW_SECS
+}
+
+/// GET /price/{symbol} 响应体。
+#[derive(Debug, Serialize)]
+struct PriceBody {
+    symbol: String,    // 交易对
+    adapter: String,   // 实际作答的适配器
+    price: f64,        // 价格
+    last_updated: i64, // 价格发布时间
+}
+
+/// GET /twap/{symbol} 响应体。
+#[derive(Debug, Serialize)]
+struct TwapBody {
+    symbol: String,     // 交易对
+    adapter: String,    // 实际作答的适配器
+    twap: f64,          // 时间加权平均价格
+    window_secs: u64,   // 时间窗口（秒）
+    last_updated: i64,  // 窗口内最新样本时间
+    source: TwapSource, // 结果来源
+}
+
+/// 错误响应体。
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String, // 错误描述
+}
+
+/// 健康检查响应体。
+#[derive(Debug, Serialize)]
+struct HealthBody {
+    status: &'static str,  // 服务状态
+    adapters: Vec<String>, // 已注册适配器
+}
+
+/// 将错误映射为 HTTP 响应。
+fn error_response(err: OffchainOracleError) -> HttpResponse {
+    let body = ErrorBody { error: err.to_string() };
+    match err {
+        OffchainOracleError::AdapterNotFound(_) | OffchainOracleError::NoData(_) => HttpResponse::NotFound().json(body),
+        OffchainOracleError::InvalidRequest(_) => HttpResponse::UnprocessableEntity().json(body),
+        OffchainOracleError::Unsupported(_) => HttpResponse::NotImplemented().json(body),
+        OffchainOracleError::Unavailable(_) => HttpResponse::ServiceUnavailable().json(body),
+        _ => HttpResponse::BadGateway().json(body),
     }
-    /// 构建 HTTP 客户端。
-    fn build_client(&self) -> Client {
-        Client::builder()
-            .timeout(Duration::from_secs(self.timeout_secs))
-            .build()
-            .expect("Failed to build HTTP client")
+}
+
+/// 适配器返回的错误统一映射为 502。
+fn adapter_error(message: String) -> HttpResponse {
+    HttpResponse::BadGateway().json(ErrorBody { error: message })
+}
+
+/// 解析候选适配器：指定名称时仅该适配器，否则为全部已注册适配器（按名称顺序）。
+fn candidates(adapter: Option<&str>, symbol: &str) -> Result<Vec<Arc<dyn OffchainOracleAdapter>>, OffchainOracleError> {
+    match adapter {
+        Some(name) => OFFCHAIN_ORACLE_FACTORY
+            .get(name)
+            .map(|adapter| vec![adapter])
+            .ok_or_else(|| OffchainOracleError::AdapterNotFound(name.to_string())),
+        None => {
+            let adapters: Vec<_> = OFFCHAIN_ORACLE_FACTORY
+                .names()
+                .iter()
+                .filter_map(|name| OFFCHAIN_ORACLE_FACTORY.get(name))
+                .collect();
+            if adapters.is_empty() {
+                return Err(OffchainOracleError::NoData(symbol.to_string()));
+            }
+            Ok(adapters)
+        }
     }
-    /// 构建请求头。
-    fn build_headers(&self) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        if !self.api_key.is_empty() {
-            headers.insert(AUTHORIZATION, HeaderValue::from_str(&self.api_key).unwrap());
+}
+
+/// GET /health
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().json(HealthBody { status: "ok", adapters: OFFCHAIN_ORACLE_FACTORY.names() })
+}
+
+/// GET /metrics
+async fn metrics() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(ORACLE_METRICS.render())
+}
+
+/// GET /price/{symbol}
+/// - 依次尝试候选适配器，返回第一个成功结果；全部失败时返回最后一个错误
+async fn price(symbol: web::Path<String>, query: web::Query<PriceQuery>) -> HttpResponse {
+    let symbol = symbol.into_inner();
+    let adapters = match candidates(query.adapter.as_deref(), &symbol) {
+        Ok(adapters) => adapters,
+        Err(err) => return error_response(err),
+    };
+    let mut last_error = String::new();
+    for adapter in adapters {
+        match fetch_price(&adapter, &symbol).await {
+            Ok(resp) => {
+                return HttpResponse::Ok().json(PriceBody {
+                    symbol,
+                    adapter: adapter.name().to_string(),
+                    price: resp.price,
+                    last_updated: resp.last_updated,
+                })
+            }
+            Err(message) => last_error = message,
         }
-        headers
     }
-    /// 发送 GET 请求。
-    pub fn get(&self, endpoint: &str, params: Option<&HashMap<&str, &str>>) -> Result<Response, reqwest::Error> {
-        let client = self.build_client();
-        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint.trim_start_matches('/'));
-        let req = client.get(&url).headers(self.build_headers());
-        let req = if let Some(p) = params {
-            req.query(p)
-        } else {
-            req
-        };
-        req.send()
+    adapter_error(last_error)
+}
+
+/// GET /twap/{symbol}
+/// - 依次尝试候选适配器；无可用数据时返回 404，上游错误返回 502
+async fn twap(symbol: web::Path<String>, query: web::Query<TwapQuery>) -> HttpResponse {
+    let symbol = symbol.into_inner();
+    let window_secs = query.window_secs.unwrap_or(DEFAULT_TWAP_WINDOW_SECS);
+    if window_secs == 0 {
+        return error_response(OffchainOracleError::InvalidRequest("window_secs must be positive".to_string()));
+    }
+    let adapters = match candidates(query.adapter.as_deref(), &symbol) {
+        Ok(adapters) => adapters,
+        Err(err) => return error_response(err),
+    };
+    let now = chrono::Utc::now().timestamp();
+    let no_data: String = OffchainOracleError::NoData(symbol.clone()).into();
+    let mut last_error = no_data.clone();
+    for adapter in adapters {
+        match fetch_twap(&adapter, &symbol, window_secs, now).await {
+            Ok((resp, source)) => {
+                return HttpResponse::Ok().json(TwapBody {
+                    symbol,
+                    adapter: adapter.name().to_string(),
+                    twap: resp.twap,
+                    window_secs: resp.window_secs,
+                    last_updated: resp.last_updated,
+                    source,
+                })
+            }
+            Err(message) if message == no_data => {}
+            Err(message) => last_error = message,
+        }
     }
-    /// 发送 POST 请求。
-    pub fn post(&self, endpoint: &str, body: &str) -> Result<Response, reqwest::Error> {
-        let client = self.build_client();
-        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint.trim_start_matches('/'));
-        client.post(&url)
-            .headers(self.build_headers())
-            .body(body.to_string())
-            .send()
+    if last_error == no_data {
+        return error_response(OffchainOracleError::NoData(symbol));
     }
+    adapter_error(last_error)
+}
+
+/// 注册预言机离线服务路由（symbol 可含 `/`，如 SOL/USD）。
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/health", web::get().to(health))
+        .route("/metrics", web::get().to(metrics))
+        .route("/price/{symbol:.+}", web::get().to(price))
+        .route("/twap/{symbol:.+}", web::get().to(twap));
 }
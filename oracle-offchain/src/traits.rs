@@ -1,26 +1,51 @@
 //!
 //! Oracle Offchain Traits Module
 //!
-//! 本模块定义预言机离线服务的通用 trait，规范适配器、工厂、API 客户端等接口，确保可扩展性、合规性与可维护性。
+//! 本模块定义预言机离线服务的异步适配器 trait 以及价格/TWAP 查询的请求与响应结构体，确保可扩展性、合规性与可维护性。
 
-/// 预言机离线适配器 trait。
-pub trait OracleOffchainAdapter {
-    /// 获取适配器名称。
-    fn name(&self) -> &str;
-    /// 查询价格。
-    fn get_price(&self, token: &str) -> u64;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OffchainOracleError;
+
+/// 价格查询请求。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffchainPriceRequest {
+    pub symbol: String, // 交易对符号（如 SOL/USD）
+}
+
+/// 价格查询响应。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OffchainPriceResponse {
+    pub price: f64,        // 最新价格
+    pub last_updated: i64, // 价格发布时间（Unix 秒）
+}
+
+/// TWAP 查询请求。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffchainTwapRequest {
+    pub symbol: String,   // 交易对符号
+    pub window_secs: u64, // 时间窗口（秒）
 }
 
-/// 预言机工厂 trait。
-pub trait OracleFactoryTrait {
-    /// 创建适配器实例。
-    fn create_adapter(&self) -> Box<dyn OracleOffchainAdapter>;
+/// TWAP 查询响应。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OffchainTwapResponse {
+    pub twap: f64,         // 时间加权平均价格
+    pub window_secs: u64,  // 时间窗口（秒）
+    pub last_updated: i64, // 窗口内最新样本时间（Unix 秒）
 }
 
-/// REST 客户端 trait。
-pub trait RestClientTrait {
-    /// 发送 GET 请求。
-    fn get(&self, endpoint: &str) -> String;
-    /// 发送 POST 请求。
-    fn post(&self, endpoint: &str, body: &str) -> String;
+/// 预言机离线适配器 trait（异步、线程安全）。
+/// - 错误以字符串返回，内容与 OffchainOracleError 的 Display 一致，实现方可直接用 `?` 传播 OffchainOracleError。
+#[async_trait]
+pub trait OffchainOracleAdapter: Send + Sync {
+    /// 获取适配器名称（注册表唯一标识）。
+    fn name(&self) -> &str;
+    /// 查询最新价格。
+    async fn get_price(&self, req: OffchainPriceRequest) -> Result<OffchainPriceResponse, String>;
+    /// 查询 TWAP；默认不支持，服务端回退到本地采样计算。
+    async fn get_twap(&self, _req: OffchainTwapRequest) -> Result<OffchainTwapResponse, String> {
+        Err(OffchainOracleError::Unsupported(self.name().to_string()).into())
+    }
 }
@@ -5,7 +5,7 @@ use std::sync::Arc;
 struct DummyOracle;
 #[async_trait::async_trait]
 impl OffchainOracleAdapter for DummyOracle {
-    async fn get_price(&self, req: OffchainPriceRequest) -> Result<OffchainPriceResponse, String> {
+    async fn get_price(&self, _req: OffchainPriceRequest) -> Result<OffchainPriceResponse, String> {
         Ok(OffchainPriceResponse {
             price: 42.0,
             last_updated: 123456,
@@ -32,27 +32,25 @@ async fn test_offchain_oracle_factory_register_and_get_price() {
 
 #[cfg(test)]
 mod tests {
-    use crate::rest;
+    use oracle_offchain::rest;
     use actix_web::{test, App};
-    use serde_json::json;
 
     #[actix_rt::test]
     async fn test_health_check() {
-        let mut app = test::init_service(App::new().configure(rest::configure)).await;
-        let req = test::TestRequest::get().uri("/health/oracle").to_request();
-        let resp = test::call_service(&mut app, req).await;
+        let app = test::init_service(App::new().configure(rest::configure)).await;
+        let req = test::TestRequest::get().uri("/health").to_request();
+        let resp = test::call_service(&app, req).await;
         assert!(resp.status().is_success());
     }
 
     #[actix_rt::test]
     async fn test_price_endpoint() {
-        let mut app = test::init_service(App::new().configure(rest::configure)).await;
-        let req = test::TestRequest::post()
-            .uri("/pyth/price")
-            .set_json(&json!({"base_mint": "So11111111111111111111111111111111111111112", "quote_mint": "USDC111111111111111111111111111111111111111",}))
+        let app = test::init_service(App::new().configure(rest::configure)).await;
+        let req = test::TestRequest::get()
+            .uri("/price/SOL/USD?adapter=pyth")
             .to_request();
-        let resp = test::call_service(&mut app, req).await;
-        // 501/400/200均可，主要验证接口可用
+        let resp = test::call_service(&app, req).await;
+        // 未注册适配器时返回 404，主要验证接口可用
         assert!(
             resp.status().is_client_error()
                 || resp.status().is_success()
@@ -62,12 +60,11 @@ mod tests {
 
     #[actix_rt::test]
     async fn test_twap_endpoint() {
-        let mut app = test::init_service(App::new().configure(rest::configure)).await;
-        let req = test::TestRequest::post()
-            .uri("/pyth/twap")
-            .set_json(&json!({"base_mint": "So11111111111111111111111111111111111111112", "quote_mint": "USDC111111111111111111111111111111111111111", "period": 60}))
+        let app = test::init_service(App::new().configure(rest::configure)).await;
+        let req = test::TestRequest::get()
+            .uri("/twap/SOL/USD?adapter=pyth&window_secs=60")
             .to_request();
-        let resp = test::call_service(&mut app, req).await;
+        let resp = test::call_service(&app, req).await;
         assert!(
             resp.status().is_client_error()
                 || resp.status().is_success()
@@ -75,3 +72,211 @@ mod tests {
         );
     }
 }
+
+/// 返回固定价格、可配置失败的测试适配器。
+struct FixedOracle {
+    name: &'static str,
+    price: Option<f64>,
+}
+
+#[async_trait::async_trait]
+impl OffchainOracleAdapter for FixedOracle {
+    fn name(&self) -> &str {
+        self.name
+    }
+    async fn get_price(&self, req: OffchainPriceRequest) -> Result<OffchainPriceResponse, String> {
+        match self.price {
+            Some(price) => Ok(OffchainPriceResponse { price, last_updated: chrono::Utc::now().timestamp() }),
+            None => Err(format!("{} unavailable for {}", self.name, req.symbol)),
+        }
+    }
+}
+
+#[actix_rt::test]
+async fn test_price_route_falls_back_and_exports_metrics() {
+    use actix_web::{test, App};
+    use oracle_offchain::factory::OFFCHAIN_ORACLE_FACTORY;
+
+    OFFCHAIN_ORACLE_FACTORY.register("route_a_down", Arc::new(FixedOracle { name: "route_a_down", price: None }));
+    OFFCHAIN_ORACLE_FACTORY.register("route_b_up", Arc::new(FixedOracle { name: "route_b_up", price: Some(151.25) }));
+    let app = test::init_service(App::new().configure(oracle_offchain::rest::configure)).await;
+
+    // 指定适配器：上游错误映射为 502
+    let req = test::TestRequest::get().uri("/price/JTO/USD?adapter=route_a_down").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 502);
+
+    let req = test::TestRequest::get().uri("/price/JTO/USD?adapter=route_b_up").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["symbol"], "JTO/USD");
+    assert_eq!(body["adapter"], "route_b_up");
+    assert_eq!(body["price"], 151.25);
+
+    let req = test::TestRequest::get().uri("/price/JTO/USD?adapter=missing").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // 本地样本已有 route_b_up 的价格，TWAP 由本地计算
+    let req = test::TestRequest::get().uri("/twap/JTO/USD?adapter=route_b_up&window_secs=60").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["twap"], 151.25);
+    assert_eq!(body["source"], "local");
+
+    let req = test::TestRequest::get().uri("/twap/JTO/USD?adapter=route_a_down").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+    let req = test::TestRequest::get().uri("/twap/JTO/USD?adapter=route_b_up&window_secs=0").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains(r#"oracle_adapter_errors_total{adapter="route_a_down",operation="price"} 1"#));
+    assert!(body.contains(r#"oracle_adapter_requests_total{adapter="route_b_up",operation="price"} 1"#));
+    assert!(body.contains(r#"oracle_adapter_latency_seconds_count{adapter="route_b_up",operation="price"} 1"#));
+
+    OFFCHAIN_ORACLE_FACTORY.unregister("route_a_down");
+    OFFCHAIN_ORACLE_FACTORY.unregister("route_b_up");
+}
+
+#[test]
+fn test_price_history_twap_is_time_weighted() {
+    use oracle_offchain::history::{PriceHistoryStore, PriceSample};
+
+    let store = PriceHistoryStore::new(3);
+    assert!(store.record("a", "SOL/USD", PriceSample { timestamp: 1_000, price: 100.0 }));
+    assert!(store.record("a", "SOL/USD", PriceSample { timestamp: 1_030, price: 110.0 }));
+    // 重复发布被忽略
+    assert!(!store.record("a", "SOL/USD", PriceSample { timestamp: 1_030, price: 999.0 }));
+    assert!(store.record("a", "SOL/USD", PriceSample { timestamp: 1_040, price: 120.0 }));
+
+    // [1_000, 1_060]: 100*30 + 110*10 + 120*20 = 6_500 / 60
+    let twap = store.twap("a", "SOL/USD", 1_060, 60).unwrap();
+    assert!((twap.twap - 6_500.0 / 60.0).abs() < 1e-9);
+    assert_eq!(twap.last_updated, 1_040);
+    // [1_020, 1_060]: 窗口前的样本覆盖起点，100*10 + 110*10 + 120*20 = 4_500 / 40
+    let twap = store.twap("a", "SOL/USD", 1_060, 40).unwrap();
+    assert!((twap.twap - 4_500.0 / 40.0).abs() < 1e-9);
+    // 窗口内无样本
+    assert!(store.twap("a", "SOL/USD", 2_000, 60).is_none());
+
+    // 容量为 3，最旧样本被淘汰
+    assert!(store.record("a", "SOL/USD", PriceSample { timestamp: 1_050, price: 130.0 }));
+    let twap = store.twap("a", "SOL/USD", 1_060, 60).unwrap();
+    assert!((twap.twap - (110.0 * 10.0 + 120.0 * 10.0 + 130.0 * 10.0) / 30.0).abs() < 1e-9);
+
+    store.clear_adapter("a");
+    assert!(store.latest("a", "SOL/USD").is_none());
+}
+
+#[tokio::test]
+async fn test_rest_oracle_adapter_against_mock_server() {
+    use oracle_offchain::rest::{RestClient, RestOracleAdapter};
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/price/SOL/USD"))
+        .and(header("authorization", "secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"price": 152.5, "last_updated": 1_700_000_000})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/twap/SOL/USD"))
+        .and(query_param("window_secs", "60"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"twap": 151.0, "window_secs": 60, "last_updated": 1_700_000_000})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/price/BONK/USD"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("unknown symbol"))
+        .mount(&server)
+        .await;
+
+    let adapter = RestOracleAdapter::new("remote", RestClient::new(&server.uri(), "secret", 5));
+    let resp = adapter.get_price(OffchainPriceRequest { symbol: "SOL/USD".to_string() }).await.unwrap();
+    assert_eq!(resp, OffchainPriceResponse { price: 152.5, last_updated: 1_700_000_000 });
+    let twap = adapter.get_twap(OffchainTwapRequest { symbol: "SOL/USD".to_string(), window_secs: 60 }).await.unwrap();
+    assert_eq!(twap.twap, 151.0);
+    let err = adapter.get_price(OffchainPriceRequest { symbol: "BONK/USD".to_string() }).await.unwrap_err();
+    assert_eq!(err, "upstream returned 404: unknown symbol");
+}
+
+#[tokio::test]
+async fn test_rest_oracle_adapter_fails_fast_when_circuit_open() {
+    use oracle_offchain::rest::{RestClient, RestClientConfig, RestOracleAdapter};
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/price/SOL/USD"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2) // 首次请求 + 1 次重试，熔断后不再访问上游
+        .mount(&server)
+        .await;
+
+    let config = RestClientConfig::new(&server.uri(), "", 5)
+        .with_max_retries(1)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+        .with_circuit_breaker(2, Duration::from_secs(60));
+    let adapter = RestOracleAdapter::new("remote", RestClient::from_config(config));
+    let req = || OffchainPriceRequest { symbol: "SOL/USD".to_string() };
+    assert_eq!(adapter.get_price(req()).await.unwrap_err(), "upstream returned 500: ");
+    assert_eq!(adapter.get_price(req()).await.unwrap_err(), format!("upstream unavailable: {}", server.uri()));
+}
+
+#[tokio::test]
+async fn test_apply_config_registers_and_unregisters_adapters() {
+    use oracle_offchain::config::load_config;
+    use oracle_offchain::refresh::PriceRefresher;
+
+    let path = std::env::temp_dir().join(format!("oracle_config_{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+[[adapters]]
+name = "hermes"
+endpoint = "http://127.0.0.1:1"
+api_key = "k"
+refresh_interval_secs = 5
+symbols = ["SOL/USD"]
+
+[[adapters]]
+name = "backup"
+endpoint = "http://127.0.0.1:2"
+
+[[adapters]]
+name = "legacy"
+kind = "grpc"
+endpoint = "http://127.0.0.1:3"
+"#,
+    )
+    .unwrap();
+    let cfg = load_config(path.to_str().unwrap()).unwrap();
+    assert_eq!(cfg.adapters.len(), 3);
+    assert_eq!(cfg.adapters[0].kind, "rest");
+    assert_eq!(cfg.adapters[0].timeout_secs, 10);
+    assert_eq!(cfg.adapters[0].max_retries, 3);
+    assert_eq!(cfg.adapters[1].refresh_interval_secs, 0);
+
+    let factory = OffchainOracleFactory::new();
+    factory.register("manual", Arc::new(DummyOracle));
+    // 未知类型被跳过
+    assert!(factory.apply_config(&cfg).is_empty());
+    assert_eq!(factory.names(), vec!["backup", "hermes", "manual"]);
+
+    let refresher = PriceRefresher::new();
+    refresher.reload(&cfg);
+    assert_eq!(refresher.task_count(), 1);
+
+    // 禁用 backup、删除 hermes：两者被注销，手动注册的适配器保留
+    std::fs::write(&path, "[[adapters]]\nname = \"backup\"\nendpoint = \"http://127.0.0.1:2\"\nenabled = false\n").unwrap();
+    let cfg = load_config(path.to_str().unwrap()).unwrap();
+    assert_eq!(factory.apply_config(&cfg), vec!["backup", "hermes"]);
+    assert_eq!(factory.names(), vec!["manual"]);
+    refresher.reload(&cfg);
+    assert_eq!(refresher.task_count(), 0);
+
+    std::fs::write(&path, "adapters = 1").unwrap();
+    assert!(load_config(path.to_str().unwrap()).unwrap_err().to_string().starts_with("invalid config:"));
+    std::fs::remove_file(&path).unwrap();
+}
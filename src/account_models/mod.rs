@@ -15,9 +15,14 @@ pub mod basket; // 组合篮子账户子模块，统一管理篮子相关账户
 pub mod basket_index_state_account; // 通用篮子/资产/指数账户结构体子模块，统一账户模型
 pub mod index_token; // 指数代币账户子模块，统一管理指数相关账户类型，便于指数相关指令复用
 pub mod oracle_registry_account; // Oracle注册表账户子模块，统一管理Oracle相关账户类型
+pub mod oracle_policy_account; // Oracle来源策略账户子模块，按mint配置主备来源回退链
 pub mod algorithm_registry_account; // 算法注册表账户子模块，统一管理算法相关账户类型
 pub mod strategy_registry_account; // 策略注册表账户子模块，统一管理策略相关账户类型
 pub mod dex_registry_account; // DEX注册表账户子模块，统一管理DEX相关账户类型
+pub mod twap_order_account; // TWAP订单账户子模块，持久化分片执行的TWAP订单
+pub mod pov_order_account; // POV订单账户子模块，持久化按市场成交量百分比执行的订单
+pub mod price_history_account; // 价格历史账户子模块，持久化预言机观测环形缓冲区与累计价格
+pub mod pending_request_account; // 链下请求账户子模块，持久化待 keeper 履约的DEX/预言机请求
 pub use basket_index_state_account::*; // 统一 re-export 通用账户结构体，便于外部直接引用和复用
 
 /// 创建组合篮子账户参数结构体（示例）
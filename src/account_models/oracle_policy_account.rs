@@ -0,0 +1,312 @@
+//!
+//! 预言机来源策略账户（PDA 持久化）
+//! 该账户按 mint 持久化主/备/第三预言机来源及其健康阈值，并提供按顺序回退的价格解析器。
+//! 采用 Anchor #[account] 宏声明，PDA 账户持久化，遵循 Solana/Anchor 最佳实践。
+//!
+//! # 设计说明
+//! - 每个已配置来源的账户都必须传入，缺失任一账户返回 MissingSourceAccount，调用方无法通过省略账户挑选来源。
+//! - 解析时按 primary → secondary → tertiary 顺序读取，仅过期、置信区间过宽或解码失败的来源被跳过。
+//! - 配置了多个来源时，被选中的来源必须与至少一个其他健康来源的偏差不超过 max_deviation_bps，
+//!   从而剔除单个偏离的喂价；健康来源互相矛盾时无法判定，返回 SourcesDiverge。
+//! - 只剩一个健康来源时（含单一来源策略）无法交叉确认，回退到该来源并将结果标记为未确认（confirmed = false），
+//!   主备之一过期不会阻断定价，但调用方与事件都能看到该价格未经确认。
+//! - 解析结果携带来源下标与名称，由指令层写入 OraclePriceQueried 事件，便于链下审计实际使用的来源。
+//! - 单个喂价过期只会触发回退，不会阻塞依赖该资产价格的篮子再平衡。
+
+use anchor_lang::prelude::*; // Anchor 预导入，包含账户声明、宏、类型、Context、Result等
+use crate::account_models::price_history_account::PriceHistorySource; // 预言机来源类型（与价格历史共用解码）
+use crate::oracles::traits::DEFAULT_PRICE_EXPONENT; // 统一价格精度
+
+/// 每个策略最多配置的来源数量（主/备/第三）
+pub const MAX_ORACLE_POLICY_SOURCES: usize = 3;
+
+/// 单个预言机来源配置
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct OracleSourceConfig {
+    /// 来源类型
+    pub source: PriceHistorySource,
+    /// 预言机价格账户
+    pub oracle_account: Pubkey,
+    /// Pyth 价格源 ID（其他来源忽略）
+    pub feed_id: [u8; 32],
+}
+
+/// 预言机来源策略账户结构体
+/// - PDA seeds: ["oracle_policy", authority, mint]，与其他注册表一致按管理人隔离，任何人无法抢先占用他人的策略地址
+/// - 采用 Anchor #[account] 宏声明，支持 InitSpace 自动空间计算
+#[account] // Anchor账户声明宏，自动实现生命周期、权限、序列化等校验逻辑
+#[derive(InitSpace)] // Anchor自动推断账户所需空间，便于部署和升级
+pub struct OraclePolicy {
+    /// 策略管理人（参与 PDA 推导）
+    pub authority: Pubkey, // 策略管理人公钥
+    /// 资产 mint（参与 PDA 推导）
+    pub mint: Pubkey, // 基础资产 mint
+    /// 报价资产 mint
+    pub quote_mint: Pubkey, // 报价资产 mint
+    /// 按优先级排列的来源（下标 0 为主来源）
+    #[max_len(3)]
+    pub sources: Vec<OracleSourceConfig>, // 来源回退链
+    /// 最大可接受时延（秒）
+    pub max_staleness_secs: u64, // 最大时延
+    /// 最大置信区间（基点）
+    pub max_confidence_bps: u64, // 最大置信区间
+    /// 来源间最大偏差（基点）
+    pub max_deviation_bps: u64, // 最大跨源偏差
+    /// 最后更新时间戳
+    pub last_updated: i64, // 最后更新时间
+    /// PDA bump
+    pub bump: u8, // PDA bump 种子
+}
+
+/// 预言机来源策略错误码（Anchor错误）
+#[error_code]
+pub enum OraclePolicyError {
+    /// 策略配置无效
+    #[msg("Invalid oracle policy config")] InvalidConfig,
+    /// 没有健康的来源
+    #[msg("No healthy oracle source")] NoHealthySource,
+    /// 健康来源之间偏差过大且无法判定
+    #[msg("Oracle sources diverge")] SourcesDiverge,
+    /// 未传入已配置来源的预言机账户
+    #[msg("Missing oracle source account")] MissingSourceAccount,
+}
+
+/// 解析后的价格
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedOraclePrice {
+    /// 价格（1e-8 精度）
+    pub price: u64,
+    /// 置信区间（与价格同精度）
+    pub confidence: u64,
+    /// 来源价格更新时间戳
+    pub last_updated: i64,
+    /// 实际使用的来源下标（0 主 / 1 备 / 2 第三）
+    pub source_index: u8,
+    /// 实际使用的来源类型
+    pub source: PriceHistorySource,
+    /// 实际使用的预言机账户
+    pub oracle_account: Pubkey,
+    /// 是否得到其他健康来源的交叉确认（仅一个健康来源时为 false）
+    pub confirmed: bool,
+}
+
+impl ResolvedOraclePrice {
+    /// 是否发生了回退（未使用主来源）
+    pub fn is_fallback(&self) -> bool {
+        self.source_index > 0
+    }
+}
+
+impl OraclePolicy {
+    /// 校验来源与阈值配置
+    pub fn validate_config(&self) -> anchor_lang::Result<()> {
+        require!(
+            !self.sources.is_empty() && self.sources.len() <= MAX_ORACLE_POLICY_SOURCES,
+            OraclePolicyError::InvalidConfig
+        ); // 来源数量需在 [1, 3]
+        require!(
+            self.max_staleness_secs > 0 && self.max_confidence_bps > 0 && self.max_deviation_bps > 0,
+            OraclePolicyError::InvalidConfig
+        ); // 阈值必须为正
+        for (i, source) in self.sources.iter().enumerate() {
+            require!(source.oracle_account != Pubkey::default(), OraclePolicyError::InvalidConfig); // 必须指定账户
+            require!(
+                source.source != PriceHistorySource::Pyth || source.feed_id != [0u8; 32],
+                OraclePolicyError::InvalidConfig
+            ); // Pyth 来源必须提供 feed_id
+            require!(
+                self.sources[..i].iter().all(|other| other.oracle_account != source.oracle_account),
+                OraclePolicyError::InvalidConfig
+            ); // 来源账户不得重复
+        }
+        Ok(())
+    }
+
+    /// 读取单个来源；解码失败、过期或置信区间过宽返回 None
+    fn read_source(&self, config: &OracleSourceConfig, account: &AccountInfo, now: i64, current_slot: u64) -> Option<(u64, u64, i64)> {
+        let result = config
+            .source
+            .read_price(account, &config.feed_id, now, current_slot, self.max_staleness_secs, self.max_confidence_bps)
+            .ok()?; // 解码并校验 owner 与时效
+        if result.confidence_bps() > self.max_confidence_bps {
+            return None; // 置信区间过宽
+        }
+        let (price, confidence) = result.scaled_to(DEFAULT_PRICE_EXPONENT)?; // 统一精度便于跨源比较
+        (price > 0).then_some((price, confidence, result.last_updated))
+    }
+
+    /// 两个价格的相对偏差（基点，以较小者为基准）
+    fn deviation_bps(a: u64, b: u64) -> u64 {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        if low == 0 {
+            return u64::MAX;
+        }
+        (((high - low) as u128 * 10_000) / low as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// 按回退链解析价格
+    /// - accounts: 各来源的预言机账户（顺序不限，必须包含全部已配置来源）
+    /// - 至少两个健康来源时，返回第一个得到其他健康来源确认的来源价格
+    /// - 仅一个健康来源时回退到该来源，结果标记为未确认
+    pub fn resolve(&self, accounts: &[AccountInfo], now: i64, current_slot: u64) -> anchor_lang::Result<ResolvedOraclePrice> {
+        let mut healthy: Vec<(usize, u64, u64, i64)> = Vec::with_capacity(self.sources.len());
+        for (i, config) in self.sources.iter().enumerate() {
+            let account = accounts
+                .iter()
+                .find(|account| *account.key == config.oracle_account)
+                .ok_or(OraclePolicyError::MissingSourceAccount)?; // 每个已配置来源都必须传入
+            if let Some((price, confidence, last_updated)) = self.read_source(config, account, now, current_slot) {
+                healthy.push((i, price, confidence, last_updated)); // 健康来源（保持优先级顺序）
+            }
+        }
+        require!(!healthy.is_empty(), OraclePolicyError::NoHealthySource);
+        let confirmed = healthy.len() > 1; // 仅在至少两个健康来源时交叉确认
+        let selected = healthy
+            .iter()
+            .find(|(i, price, _, _)| {
+                !confirmed
+                    || healthy
+                        .iter()
+                        .any(|(j, other, _, _)| j != i && Self::deviation_bps(*price, *other) <= self.max_deviation_bps)
+            })
+            .ok_or(OraclePolicyError::SourcesDiverge)?; // 需至少得到一个其他健康来源的确认
+        let (index, price, confidence, last_updated) = *selected;
+        let config = &self.sources[index];
+        Ok(ResolvedOraclePrice {
+            price,
+            confidence,
+            last_updated,
+            source_index: index as u8,
+            source: config.source,
+            oracle_account: config.oracle_account,
+            confirmed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracles::chainlink::CHAINLINK_STORE_PROGRAM_ID;
+
+    /// 单轮 Chainlink Transmissions 账户字节（decimals = 8）
+    fn transmissions(timestamp: u32, answer: i128) -> Vec<u8> {
+        let mut data = vec![0u8; 200 + 48];
+        data[..8].copy_from_slice(&[96, 179, 69, 66, 128, 129, 73, 117]);
+        data[138] = 8; // decimals
+        data[148..152].copy_from_slice(&1u32.to_le_bytes()); // live_length
+        data[152..156].copy_from_slice(&1u32.to_le_bytes()); // live_cursor
+        data[208..212].copy_from_slice(&timestamp.to_le_bytes());
+        data[216..232].copy_from_slice(&answer.to_le_bytes());
+        data
+    }
+
+    fn policy(keys: &[Pubkey]) -> OraclePolicy {
+        OraclePolicy {
+            authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            sources: keys
+                .iter()
+                .map(|key| OracleSourceConfig { source: PriceHistorySource::Chainlink, oracle_account: *key, feed_id: [0u8; 32] })
+                .collect(),
+            max_staleness_secs: 60,
+            max_confidence_bps: 100,
+            max_deviation_bps: 100,
+            last_updated: 0,
+            bump: 255,
+        }
+    }
+
+    /// 以 Chainlink 账户（时间戳, 报价）解析价格，策略按 feeds 数量配置来源
+    fn resolve_with<const N: usize>(feeds: [(u32, i128); N], present: [bool; N]) -> anchor_lang::Result<ResolvedOraclePrice> {
+        let keys: Vec<Pubkey> = (0..N).map(|_| Pubkey::new_unique()).collect();
+        let mut lamports = [0u64; N];
+        let mut data: Vec<Vec<u8>> = feeds.iter().map(|(ts, answer)| transmissions(*ts, *answer)).collect();
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .zip(present)
+            .filter(|(_, present)| *present)
+            .map(|(((key, lamports), data), _)| {
+                AccountInfo::new(key, false, false, lamports, data, &CHAINLINK_STORE_PROGRAM_ID, false, 0)
+            })
+            .collect();
+        policy(&keys).resolve(&accounts, 1_000_060, 0)
+    }
+
+    #[test]
+    fn test_resolve_prefers_primary() {
+        let resolved = resolve_with([(1_000_050, 10_000_000_000), (1_000_050, 10_020_000_000), (1_000_050, 9_990_000_000)], [true; 3]).unwrap();
+        assert_eq!((resolved.source_index, resolved.price), (0, 10_000_000_000));
+        assert!(!resolved.is_fallback());
+        assert!(resolved.confirmed);
+    }
+
+    #[test]
+    fn test_resolve_falls_back_on_stale_primary() {
+        let stale = resolve_with([(999_990, 10_000_000_000), (1_000_050, 10_020_000_000), (1_000_050, 9_990_000_000)], [true; 3]).unwrap();
+        assert_eq!((stale.source_index, stale.price), (1, 10_020_000_000));
+        let invalid = resolve_with([(1_000_050, 0), (1_000_050, 10_020_000_000), (1_000_050, 9_990_000_000)], [true; 3]).unwrap();
+        assert_eq!(invalid.source_index, 1);
+        assert!(resolve_with([(999_990, 1), (999_990, 1), (999_990, 1)], [true; 3]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_requires_every_source_account() {
+        let feeds = [(1_000_050, 10_000_000_000), (1_000_050, 10_020_000_000), (1_000_050, 9_990_000_000)];
+        // 仅传入第三来源不能让其为 mint 定价
+        assert_eq!(
+            resolve_with(feeds, [false, false, true]).unwrap_err(),
+            OraclePolicyError::MissingSourceAccount.into()
+        );
+        assert!(resolve_with(feeds, [true, true, false]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_single_healthy_source_is_unconfirmed() {
+        // 三个来源中只剩一个健康来源，回退到该来源并标记为未确认
+        let last = resolve_with([(999_990, 10_000_000_000), (999_990, 10_020_000_000), (1_000_050, 9_990_000_000)], [true; 3]).unwrap();
+        assert_eq!((last.source_index, last.price, last.confirmed), (2, 9_990_000_000, false));
+        // 单一来源策略无法确认
+        let single = resolve_with([(1_000_050, 10_000_000_000)], [true]).unwrap();
+        assert_eq!((single.source_index, single.price, single.confirmed), (0, 10_000_000_000, false));
+    }
+
+    #[test]
+    fn test_resolve_two_sources_with_stale_primary() {
+        // 主备两个来源，主来源过期：回退到备用来源且不报错
+        let resolved = resolve_with([(999_990, 10_000_000_000), (1_000_050, 10_020_000_000)], [true; 2]).unwrap();
+        assert_eq!((resolved.source_index, resolved.price), (1, 10_020_000_000));
+        assert!(resolved.is_fallback());
+        assert!(!resolved.confirmed);
+        // 两个来源都健康时仍需交叉确认
+        let both = resolve_with([(1_000_050, 10_000_000_000), (1_000_050, 10_020_000_000)], [true; 2]).unwrap();
+        assert_eq!((both.source_index, both.confirmed), (0, true));
+        assert_eq!(
+            resolve_with([(1_000_050, 10_500_000_000), (1_000_050, 10_000_000_000)], [true; 2]).unwrap_err(),
+            OraclePolicyError::SourcesDiverge.into()
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_deviating_source() {
+        // 主来源偏离 5%，备用与第三来源相互确认
+        let resolved = resolve_with([(1_000_050, 10_500_000_000), (1_000_050, 10_020_000_000), (1_000_050, 9_990_000_000)], [true; 3]).unwrap();
+        assert_eq!(resolved.source_index, 1);
+        // 仅剩两个互相矛盾的健康来源
+        assert!(resolve_with([(1_000_050, 10_500_000_000), (1_000_050, 10_000_000_000), (999_000, 1)], [true; 3]).is_err());
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        assert!(policy(&keys).validate_config().is_ok());
+        assert!(policy(&[keys[0], keys[0]]).validate_config().is_err());
+        assert!(policy(&[]).validate_config().is_err());
+        let mut pyth = policy(&keys);
+        pyth.sources[0].source = PriceHistorySource::Pyth;
+        assert!(pyth.validate_config().is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//!
+//! 链下请求账户（PDA 持久化）
+//! 该账户记录一次链上发起、由链下 keeper 履约的 DEX/预言机请求，保存请求参数、指定响应者、有效期与响应结果。
+//! 采用 Anchor #[account] 宏声明，PDA 账户持久化，遵循 Solana/Anchor 最佳实践。
+//!
+//! # 设计说明
+//! - 请求创建时发出 OffchainDexRequest / OffchainOracleRequest 事件，keeper 监听日志后调用 submit_offchain_response 回填结果。
+//! - 仅创建时指定的响应者可回填，且指令参数中的 request_id 参与 PDA 推导并与账户内记录比对，防止串单。
+//! - 每个请求只能响应一次；过期请求不再接受响应，请求人可关闭账户回收租金。
+
+use anchor_lang::prelude::*; // Anchor 预导入，包含账户声明、宏、类型、Context、Result等
+
+/// 目标 DEX/预言机名称最大长度
+pub const MAX_OFFCHAIN_TARGET_LEN: usize = 32;
+/// 操作类型最大长度
+pub const MAX_OFFCHAIN_ACTION_LEN: usize = 16;
+/// 请求参数最大字节数
+pub const MAX_OFFCHAIN_PARAMS_LEN: usize = 256;
+/// 响应结果最大字节数
+pub const MAX_OFFCHAIN_RESULT_LEN: usize = 256;
+/// 错误信息最大长度
+pub const MAX_OFFCHAIN_ERROR_LEN: usize = 128;
+/// 请求最长有效期（秒）
+pub const MAX_OFFCHAIN_TTL_SECS: i64 = 86_400;
+
+/// 链下请求类别
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum OffchainRequestKind {
+    /// DEX 请求（swap/quote）
+    Dex,
+    /// 预言机请求（price/twap）
+    Oracle,
+}
+
+impl OffchainRequestKind {
+    /// 该类别允许的操作类型
+    pub fn allowed_actions(&self) -> &'static [&'static str] {
+        match self {
+            OffchainRequestKind::Dex => &["swap", "quote"],
+            OffchainRequestKind::Oracle => &["price", "twap"],
+        }
+    }
+}
+
+/// 链下请求状态
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PendingRequestStatus {
+    /// 等待响应
+    Pending,
+    /// 已成功响应
+    Fulfilled,
+    /// 已响应失败
+    Failed,
+}
+
+/// 链下请求账户结构体
+/// - PDA seeds: ["pending_request", requester, request_id.to_le_bytes()]
+/// - 采用 Anchor #[account] 宏声明，支持 InitSpace 自动空间计算
+#[account] // Anchor账户声明宏，自动实现生命周期、权限、序列化等校验逻辑
+#[derive(InitSpace)] // Anchor自动推断账户所需空间，便于部署和升级
+pub struct PendingRequest {
+    /// 请求编号（同一请求人下唯一，参与 PDA 推导，随事件发出）
+    pub request_id: u64, // 请求编号
+    /// 请求人（关闭账户时租金接收人）
+    pub requester: Pubkey, // 请求人公钥
+    /// 指定响应者（链下 keeper 身份）
+    pub responder: Pubkey, // 响应者公钥
+    /// 请求类别
+    pub kind: OffchainRequestKind, // DEX / 预言机
+    /// 目标 DEX/预言机名称
+    #[max_len(32)]
+    pub target: String, // 目标名称
+    /// 操作类型
+    #[max_len(16)]
+    pub action: String, // 操作类型
+    /// 请求参数（JSON 序列化）
+    #[max_len(256)]
+    pub params: Vec<u8>, // 请求参数
+    /// 请求状态
+    pub status: PendingRequestStatus, // 请求状态
+    /// 响应结果（JSON 序列化）
+    #[max_len(256)]
+    pub result: Vec<u8>, // 响应结果
+    /// 失败响应的错误信息（成功或未响应为空）
+    #[max_len(128)]
+    pub error_msg: String, // 错误信息
+    /// 创建时间戳
+    pub created_at: i64, // 创建时间
+    /// 过期时间戳
+    pub expires_at: i64, // 过期时间
+    /// 响应时间戳（未响应为 0）
+    pub responded_at: i64, // 响应时间
+    /// PDA bump
+    pub bump: u8, // PDA bump 种子
+}
+
+/// 链下请求错误码（Anchor错误）
+#[error_code]
+pub enum PendingRequestError {
+    /// 目标名称无效
+    #[msg("Invalid off-chain request target")] InvalidTarget,
+    /// 操作类型无效
+    #[msg("Invalid off-chain request action")] InvalidAction,
+    /// 请求参数过长
+    #[msg("Off-chain request params too large")] ParamsTooLarge,
+    /// 有效期无效
+    #[msg("Invalid off-chain request TTL")] InvalidTtl,
+    /// 响应者无效
+    #[msg("Invalid off-chain responder")] InvalidResponder,
+    /// 非指定响应者
+    #[msg("Responder not authorized for this request")] UnauthorizedResponder,
+    /// 请求编号不匹配
+    #[msg("Request id mismatch")] RequestIdMismatch,
+    /// 请求已响应
+    #[msg("Off-chain request already responded")] AlreadyResponded,
+    /// 请求已过期
+    #[msg("Off-chain request expired")] RequestExpired,
+    /// 请求仍在等待响应且未过期
+    #[msg("Off-chain request still pending")] RequestStillPending,
+    /// 响应结果或错误信息过长
+    #[msg("Off-chain response too large")] ResultTooLarge,
+    /// 数学溢出
+    #[msg("Math overflow")] MathOverflow,
+}
+
+impl PendingRequest {
+    /// 校验请求参数
+    pub fn validate_request(
+        kind: OffchainRequestKind,
+        target: &str,
+        action: &str,
+        params: &[u8],
+        responder: &Pubkey,
+        ttl_secs: i64,
+    ) -> anchor_lang::Result<()> {
+        require!(
+            !target.is_empty() && target.len() <= MAX_OFFCHAIN_TARGET_LEN,
+            PendingRequestError::InvalidTarget
+        ); // 目标名称非空且不超长
+        require!(kind.allowed_actions().contains(&action), PendingRequestError::InvalidAction); // 操作类型需与类别匹配
+        require!(params.len() <= MAX_OFFCHAIN_PARAMS_LEN, PendingRequestError::ParamsTooLarge); // 参数不超长
+        require!(*responder != Pubkey::default(), PendingRequestError::InvalidResponder); // 必须指定响应者
+        require!(ttl_secs > 0 && ttl_secs <= MAX_OFFCHAIN_TTL_SECS, PendingRequestError::InvalidTtl); // 有效期需在 (0, MAX_OFFCHAIN_TTL_SECS]
+        Ok(())
+    }
+
+    /// 是否已过期
+    pub fn is_expired(&self, now: i64) -> bool {
+        now > self.expires_at
+    }
+
+    /// 校验响应者、请求编号与状态
+    pub fn assert_respondable(&self, responder: &Pubkey, request_id: u64, now: i64) -> anchor_lang::Result<()> {
+        require_keys_eq!(*responder, self.responder, PendingRequestError::UnauthorizedResponder); // 仅指定响应者
+        require!(request_id == self.request_id, PendingRequestError::RequestIdMismatch); // 请求编号一致
+        require!(self.status == PendingRequestStatus::Pending, PendingRequestError::AlreadyResponded); // 只能响应一次
+        require!(!self.is_expired(now), PendingRequestError::RequestExpired); // 未过期
+        Ok(())
+    }
+
+    /// 记录响应结果
+    pub fn record_response(&mut self, success: bool, result: Vec<u8>, error_msg: Option<&str>, now: i64) -> anchor_lang::Result<()> {
+        require!(result.len() <= MAX_OFFCHAIN_RESULT_LEN, PendingRequestError::ResultTooLarge); // 结果不超长
+        require!(
+            error_msg.is_none_or(|msg| msg.len() <= MAX_OFFCHAIN_ERROR_LEN),
+            PendingRequestError::ResultTooLarge
+        ); // 错误信息不超长
+        self.status = if success { PendingRequestStatus::Fulfilled } else { PendingRequestStatus::Failed }; // 更新状态
+        self.result = result; // 保存结果
+        self.error_msg = error_msg.unwrap_or_default().to_string(); // 保存错误信息
+        self.responded_at = now; // 响应时间
+        Ok(())
+    }
+
+    /// 校验请求可关闭：已响应或已过期
+    pub fn assert_closable(&self, now: i64) -> anchor_lang::Result<()> {
+        require!(
+            self.status != PendingRequestStatus::Pending || self.is_expired(now),
+            PendingRequestError::RequestStillPending
+        ); // 等待中的请求需过期后才能关闭
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_request(responder: Pubkey) -> PendingRequest {
+        PendingRequest {
+            request_id: 7,
+            requester: Pubkey::new_unique(),
+            responder,
+            kind: OffchainRequestKind::Oracle,
+            target: "pyth".to_string(),
+            action: "price".to_string(),
+            params: br#"{"symbol":"SOL/USD"}"#.to_vec(),
+            status: PendingRequestStatus::Pending,
+            result: Vec::new(),
+            error_msg: String::new(),
+            created_at: 1_000,
+            expires_at: 1_060,
+            responded_at: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let responder = Pubkey::new_unique();
+        assert!(PendingRequest::validate_request(OffchainRequestKind::Dex, "jupiter", "quote", &[], &responder, 60).is_ok());
+        assert!(PendingRequest::validate_request(OffchainRequestKind::Dex, "jupiter", "price", &[], &responder, 60).is_err()); // 操作与类别不匹配
+        assert!(PendingRequest::validate_request(OffchainRequestKind::Oracle, "", "price", &[], &responder, 60).is_err());
+        assert!(PendingRequest::validate_request(OffchainRequestKind::Oracle, "pyth", "price", &[0; 257], &responder, 60).is_err());
+        assert!(PendingRequest::validate_request(OffchainRequestKind::Oracle, "pyth", "price", &[], &Pubkey::default(), 60).is_err());
+        assert!(PendingRequest::validate_request(OffchainRequestKind::Oracle, "pyth", "twap", &[], &responder, 0).is_err());
+        assert!(PendingRequest::validate_request(OffchainRequestKind::Oracle, "pyth", "twap", &[], &responder, 86_401).is_err());
+    }
+
+    #[test]
+    fn test_respond_once_by_authorized_responder() {
+        let responder = Pubkey::new_unique();
+        let mut request = sample_request(responder);
+        assert!(request.assert_respondable(&Pubkey::new_unique(), 7, 1_010).is_err()); // 非指定响应者
+        assert!(request.assert_respondable(&responder, 8, 1_010).is_err()); // 请求编号不匹配
+        assert!(request.assert_respondable(&responder, 7, 1_061).is_err()); // 已过期
+        assert!(request.assert_closable(1_010).is_err()); // 等待中不可关闭
+        request.assert_respondable(&responder, 7, 1_060).unwrap();
+        assert!(request.record_response(true, vec![0; 257], None, 1_060).is_err()); // 结果过长
+        request.record_response(true, br#"{"price":15125000000}"#.to_vec(), None, 1_060).unwrap();
+        assert_eq!(request.status, PendingRequestStatus::Fulfilled);
+        assert_eq!(request.responded_at, 1_060);
+        assert!(request.error_msg.is_empty());
+        assert!(request.assert_respondable(&responder, 7, 1_060).is_err()); // 只能响应一次
+        assert!(request.assert_closable(1_060).is_ok());
+    }
+
+    #[test]
+    fn test_expired_request_closable() {
+        let mut request = sample_request(Pubkey::new_unique());
+        assert!(request.assert_closable(1_061).is_ok());
+        assert!(request.record_response(false, Vec::new(), Some(&"x".repeat(129)), 1_000).is_err()); // 错误信息过长
+        request.record_response(false, Vec::new(), Some("adapter unavailable"), 1_000).unwrap();
+        assert_eq!(request.status, PendingRequestStatus::Failed);
+        assert_eq!(request.error_msg, "adapter unavailable");
+    }
+}
@@ -0,0 +1,236 @@
+//!
+//! POV 订单账户（PDA 持久化）
+//! 该账户用于持久化一笔按市场成交量百分比（POV）执行的订单，保存 POV 执行引擎状态、指定成交量上报者、分片间隔与 keeper 小费。
+//! 采用 Anchor #[account] 宏声明，PDA 账户持久化，遵循 Solana/Anchor 最佳实践。
+//!
+//! # 设计说明
+//! - 市场成交量由创建时指定的上报者（DEX swap 事件索引器或链下成交量源）通过 report 指令写入，其他账户无权上报。
+//! - 分片由任意 keeper 调用 crank 指令执行，数量由 PovEngine 按累计成交量与参与率带宽决定，与 TWAP 订单共用同一 crank 模型。
+//! - 创建时全部输入转入订单 PDA 持有的输入金库（seeds: ["pov_input_vault", order]），分片输出进入输出金库（["pov_output_vault", order]）；
+//!   分片经 DexAdapter swap CPI 以订单 PDA 签名成交，按金库余额变化记账后才推进进度、支付小费。
+//! - 最新观测价格偏离到达价格超限时暂停执行；成交时以偏离上限计算最小输出，防止 keeper 在不利价格成交。
+//! - keeper 小费按最大分片数量在创建时预存入订单 PDA；撤单时退还剩余输入、转出累计输出，剩余 lamports 退还下单人。
+
+use anchor_lang::prelude::*; // Anchor 预导入，包含账户声明、宏、类型、Context、Result等
+use crate::algorithms::pov::{PovEngine, PovSliceDecision, VolumeObservation}; // POV 执行引擎
+
+/// POV 订单最大分片数量
+pub const MAX_POV_SLICES: u32 = 1_000;
+/// POV 订单 DEX 名称最大长度
+pub const MAX_POV_DEX_NAME_LEN: usize = 32;
+
+/// POV 订单账户结构体
+/// - PDA seeds: ["pov_order", owner, order_id.to_le_bytes()]
+/// - 采用 Anchor #[account] 宏声明，支持 InitSpace 自动空间计算
+#[account] // Anchor账户声明宏，自动实现生命周期、权限、序列化等校验逻辑
+#[derive(InitSpace)] // Anchor自动推断账户所需空间，便于部署和升级
+pub struct PovOrder {
+    /// 下单人（订单所有者，撤单与剩余资金接收人）
+    pub owner: Pubkey, // 订单所有者公钥
+    /// 订单编号（同一下单人下唯一，参与 PDA 推导）
+    pub order_id: u64, // 订单编号
+    /// 输入 token mint
+    pub input_mint: Pubkey, // 卖出资产
+    /// 输出 token mint
+    pub output_mint: Pubkey, // 买入资产
+    /// 路由使用的 DEX 适配器名称（ESCROW_SWAP_DEX_NAMES 之一）
+    #[max_len(32)]
+    pub dex_name: String, // DEX 适配器名称
+    /// 创建时锁定的池/市场账户（crank 的首个池账户必须与之一致）
+    pub pool: Pubkey, // 池/市场账户
+    /// 指定市场成交量上报者
+    pub volume_reporter: Pubkey, // 成交量上报者公钥
+    /// POV 执行引擎状态
+    pub engine: PovEngine, // 参与率、已观测成交量与成交进度
+    /// 分片最小间隔（slot）
+    pub slot_interval: u64, // 分片间隔
+    /// 下一可执行 slot
+    pub next_eligible_slot: u64, // 下一可执行 slot
+    /// 最大分片数量（限制碎片化并决定小费预算）
+    pub max_slices: u32, // 最大分片数量
+    /// 每个分片支付给 keeper 的小费（lamports）
+    pub keeper_tip_lamports: u64, // keeper 小费
+    /// 订单状态
+    pub status: PovOrderStatus, // 订单状态
+    /// 创建时间戳
+    pub created_at: i64, // 创建时间
+    /// 最后一次成交时间戳
+    pub last_fill_at: i64, // 最后成交时间
+    /// PDA bump
+    pub bump: u8, // PDA bump 种子
+}
+
+/// POV 订单状态枚举
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PovOrderStatus {
+    /// 执行中
+    Active,
+    /// 已全部成交
+    Completed,
+}
+
+/// POV 订单错误码（Anchor错误）
+#[error_code]
+pub enum PovOrderError {
+    /// 分片数量无效
+    #[msg("Invalid number of POV slices")] InvalidSliceCount,
+    /// 分片间隔无效
+    #[msg("Invalid POV slot interval")] InvalidSlotInterval,
+    /// 输入输出资产相同
+    #[msg("Input and output mint must differ")] InvalidMints,
+    /// DEX 名称无效
+    #[msg("Invalid DEX name")] InvalidDexName,
+    /// 成交量上报者无效
+    #[msg("Invalid volume reporter")] InvalidReporter,
+    /// 非指定成交量上报者
+    #[msg("Volume reporter not authorized for this order")] UnauthorizedReporter,
+    /// 订单不处于执行中
+    #[msg("POV order is not active")] OrderNotActive,
+    /// 分片间隔尚未到期
+    #[msg("POV slice is not yet eligible")] SliceNotEligible,
+    /// 已达到最大分片数量
+    #[msg("POV slice limit reached")] SliceLimitReached,
+    /// 参与率已达目标，等待更多市场成交量
+    #[msg("POV participation target reached, waiting for market volume")] NothingToExecute,
+    /// 市场价格偏离到达价格超限
+    #[msg("Market price outside POV deviation band")] PriceOutOfBand,
+    /// DEX 不支持托管订单成交
+    #[msg("DEX adapter not supported for escrowed orders")] DexAdapterNotFound,
+    /// 金库余额变化与分片不符
+    #[msg("Slice fill does not match vault balance change")] InvalidFill,
+    /// 成交价格低于偏离下限
+    #[msg("Slice fill below POV price limit")] LimitPriceNotMet,
+    /// keeper 小费余额不足
+    #[msg("Insufficient keeper tip balance")] InsufficientTipBalance,
+    /// 数学溢出
+    #[msg("Math overflow")] MathOverflow,
+    /// 池账户无效或与订单锁定的池不一致
+    #[msg("Pool account does not match the order")] PoolMismatch,
+}
+
+impl PovOrder {
+    /// 校验成交量上报者
+    pub fn assert_reporter(&self, reporter: &Pubkey) -> anchor_lang::Result<()> {
+        require_keys_eq!(*reporter, self.volume_reporter, PovOrderError::UnauthorizedReporter); // 仅指定上报者
+        require!(self.status == PovOrderStatus::Active, PovOrderError::OrderNotActive); // 订单需处于执行中
+        Ok(())
+    }
+
+    /// 记录一次市场成交量观测
+    pub fn record_volume(&mut self, observation: &VolumeObservation) -> anchor_lang::Result<()> {
+        self.engine.observe(observation)
+    }
+
+    /// 校验当前 slot 是否可执行下一个分片
+    pub fn assert_crankable(&self, current_slot: u64) -> anchor_lang::Result<()> {
+        require!(
+            self.status == PovOrderStatus::Active && !self.engine.is_complete(),
+            PovOrderError::OrderNotActive
+        ); // 订单需处于执行中
+        require!(self.engine.slices_executed < self.max_slices, PovOrderError::SliceLimitReached); // 分片数量上限
+        require!(current_slot >= self.next_eligible_slot, PovOrderError::SliceNotEligible); // 分片间隔需已到期
+        Ok(())
+    }
+
+    /// 校验 crank 传入的池账户与创建时锁定的池一致
+    pub fn assert_pool(&self, pool: &Pubkey) -> anchor_lang::Result<()> {
+        require_keys_eq!(*pool, self.pool, PovOrderError::PoolMismatch); // 防止keeper改用薄池或被操纵的池成交
+        Ok(())
+    }
+
+    /// 下一个分片的输入数量与最小输出
+    pub fn next_slice(&self) -> anchor_lang::Result<(u64, u64)> {
+        match self.engine.next_slice() {
+            PovSliceDecision::Execute { amount, min_amount_out } => Ok((amount, min_amount_out)),
+            PovSliceDecision::Wait => err!(PovOrderError::NothingToExecute),
+            PovSliceDecision::PriceOutOfBand => err!(PovOrderError::PriceOutOfBand),
+            PovSliceDecision::Complete => err!(PovOrderError::OrderNotActive),
+        }
+    }
+
+    /// 记录一个分片成交并推进状态
+    pub fn record_fill(&mut self, amount_in: u64, amount_out: u64, current_slot: u64, now: i64) -> anchor_lang::Result<()> {
+        require!(amount_out >= self.engine.min_amount_out(amount_in), PovOrderError::LimitPriceNotMet); // 价格偏离保护
+        self.engine.record_fill(amount_in, amount_out)?; // 更新引擎成交进度
+        self.next_eligible_slot = current_slot
+            .checked_add(self.slot_interval)
+            .ok_or(PovOrderError::MathOverflow)?; // 以本次成交 slot 为基准计算下一可执行 slot
+        self.last_fill_at = now; // 更新最后成交时间
+        if self.engine.is_complete() {
+            self.status = PovOrderStatus::Completed; // 全部成交
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_order(max_slices: u32) -> PovOrder {
+        PovOrder {
+            owner: Pubkey::new_unique(),
+            order_id: 1,
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            dex_name: "mock_dex".to_string(),
+            pool: Pubkey::new_unique(),
+            volume_reporter: Pubkey::new_unique(),
+            engine: PovEngine::new(1_000, 1_000, 500, 1_500, 100, 0, 1_000_000).unwrap(),
+            slot_interval: 10,
+            next_eligible_slot: 100,
+            max_slices,
+            keeper_tip_lamports: 5_000,
+            status: PovOrderStatus::Active,
+            created_at: 0,
+            last_fill_at: 0,
+            bump: 255,
+        }
+    }
+
+    fn observation(timestamp: i64, volume: u64, price: u64) -> VolumeObservation {
+        VolumeObservation { timestamp, volume, price }
+    }
+
+    #[test]
+    fn test_reporter_and_volume_driven_slices() {
+        let mut order = sample_order(10);
+        assert!(order.assert_reporter(&Pubkey::new_unique()).is_err()); // 非指定上报者
+        order.assert_pool(&order.pool).unwrap();
+        assert!(order.assert_pool(&Pubkey::new_unique()).is_err()); // 非锁定的池
+        order.assert_reporter(&order.volume_reporter).unwrap();
+        assert!(order.next_slice().is_err()); // 尚无市场成交量
+        order.record_volume(&observation(1, 2_000, 1_000_000)).unwrap();
+        assert!(order.assert_crankable(99).is_err()); // 未到期
+        order.assert_crankable(100).unwrap();
+        assert_eq!(order.next_slice().unwrap(), (200, 198));
+        assert!(order.record_fill(200, 197, 100, 1).is_err()); // 低于价格下限
+        order.record_fill(200, 199, 100, 1).unwrap();
+        assert_eq!(order.next_eligible_slot, 110);
+        assert!(order.next_slice().is_err()); // 参与率已达目标
+
+        order.record_volume(&observation(2, 1_000, 970_000)).unwrap();
+        assert!(order.next_slice().is_err()); // 价格偏离超限
+        order.record_volume(&observation(3, 7_000, 1_000_000)).unwrap();
+        let (amount, min_out) = order.next_slice().unwrap();
+        assert_eq!(amount, 800);
+        order.record_fill(amount, min_out, 110, 2).unwrap();
+        assert_eq!(order.status, PovOrderStatus::Completed);
+        assert!(order.assert_crankable(u64::MAX).is_err()); // 已完成订单不可再 crank
+        assert_eq!(order.engine.report().realized_rate_bps, 1_000);
+    }
+
+    #[test]
+    fn test_slice_limit_stops_cranking() {
+        let mut order = sample_order(2);
+        for (i, slot) in [100, 110].into_iter().enumerate() {
+            order.record_volume(&observation(i as i64, 1_000, 1_000_000)).unwrap();
+            order.assert_crankable(slot).unwrap();
+            let (amount, min_out) = order.next_slice().unwrap();
+            order.record_fill(amount, min_out, slot, i as i64).unwrap();
+        }
+        assert_eq!(order.engine.filled_amount, 200);
+        assert_eq!(order.status, PovOrderStatus::Active);
+        assert!(order.assert_crankable(u64::MAX).is_err()); // 达到分片上限，剩余数量需撤单取回
+    }
+}
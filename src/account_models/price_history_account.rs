@@ -0,0 +1,433 @@
+//!
+//! 价格历史账户（PDA 持久化）
+//! 该账户按 mint 持久化预言机价格观测的定长环形缓冲区与累计价格，供链上计算任意窗口的 TWAP / EMA。
+//! 采用 Anchor #[account] 宏声明，PDA 账户持久化，遵循 Solana/Anchor 最佳实践。
+//!
+//! # 设计说明
+//! - 观测由无许可的 record_price_observation 指令写入，价格只从管理人绑定的预言机账户读取，调用方无法伪造。
+//! - PDA 由管理人与 mint 共同推导，消费方（如权重策略）须按信任的管理人校验 authority，而不能只比对 mint；
+//!   预言机绑定只能由管理人通过 rebind_price_history 更换，更换时清空旧来源的观测。
+//! - 每条观测记录 (slot, timestamp, price, confidence) 以及截至该时刻的累计价格（∑ price × Δt），
+//!   任意时刻的累计价格可由前一条观测线性外推，TWAP = Δ累计价格 / Δt。
+//! - timestamp 为预言机发布时间而非写入时的链上时间，同一喂价被重复写入不会被计作新的价格区间；
+//!   发布时间不晚于最新观测的喂价返回 ObservationNotNewer。
+//! - 写满 PRICE_HISTORY_CAPACITY 后覆盖最旧观测；min_record_interval_secs 限制写入频率，防止刷写挤出历史。
+//! - 价格与置信区间统一为 DEFAULT_PRICE_EXPONENT（1e-8）精度。
+
+use anchor_lang::prelude::*; // Anchor 预导入，包含账户声明、宏、类型、Context、Result等
+use crate::oracles::chainlink::ChainlinkAdapter; // Chainlink Transmissions 解码
+use crate::oracles::pyth::PythAdapter; // Pyth PriceUpdateV2 解码
+use crate::oracles::switchboard::SwitchboardAdapter; // Switchboard 拉取式喂价解码
+use crate::oracles::traits::OraclePriceResult; // 统一价格结果
+
+/// 价格历史环形缓冲区容量
+pub const PRICE_HISTORY_CAPACITY: usize = 128;
+
+/// 单条价格观测
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct PriceObservation {
+    /// 记录时 slot
+    pub slot: u64,
+    /// 预言机发布时间戳
+    pub timestamp: i64,
+    /// 价格（1e-8 精度）
+    pub price: u64,
+    /// 置信区间（±，与价格同精度）
+    pub confidence: u64,
+    /// 截至本条观测的累计价格（∑ price × 秒）
+    pub cumulative_price: u128,
+}
+
+/// 价格历史绑定的预言机来源
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PriceHistorySource {
+    /// Pyth PriceUpdateV2 账户（需 feed_id）
+    Pyth,
+    /// Switchboard On-Demand 拉取式喂价账户
+    SwitchboardOnDemand,
+    /// Chainlink OCR2 Transmissions 账户
+    Chainlink,
+}
+
+impl PriceHistorySource {
+    /// 来源名称（与各适配器 name 一致）
+    pub fn name(&self) -> &'static str {
+        match self {
+            PriceHistorySource::Pyth => "pyth",
+            PriceHistorySource::SwitchboardOnDemand => "switchboard",
+            PriceHistorySource::Chainlink => "chainlink",
+        }
+    }
+
+    /// 按来源解码预言机账户并校验时效（Pyth 额外校验 feed_id 与置信区间）
+    pub fn read_price(
+        &self,
+        account: &AccountInfo,
+        feed_id: &[u8; 32],
+        now: i64,
+        current_slot: u64,
+        max_age: u64,
+        max_confidence_bps: u64,
+    ) -> anchor_lang::Result<OraclePriceResult> {
+        match self {
+            PriceHistorySource::Pyth => PythAdapter.price_from_account(account, feed_id, now, max_age, max_confidence_bps),
+            PriceHistorySource::SwitchboardOnDemand => SwitchboardAdapter.price_from_account(account, now, current_slot, max_age),
+            PriceHistorySource::Chainlink => ChainlinkAdapter.price_from_account(account, now, max_age),
+        }
+    }
+}
+
+/// 价格历史账户结构体
+/// - PDA seeds: ["price_history", authority, mint]
+/// - 采用 Anchor #[account] 宏声明，支持 InitSpace 自动空间计算
+#[account] // Anchor账户声明宏，自动实现生命周期、权限、序列化等校验逻辑
+#[derive(InitSpace)] // Anchor自动推断账户所需空间，便于部署和升级
+pub struct PriceHistory {
+    /// 管理人（预言机绑定配置的所有者，参与 PDA 推导）
+    pub authority: Pubkey, // 配置所有者
+    /// 资产 mint（参与 PDA 推导）
+    pub mint: Pubkey, // 资产 mint
+    /// 预言机来源
+    pub source: PriceHistorySource, // 预言机来源
+    /// 预言机价格账户（record 时按地址约束）
+    pub oracle_account: Pubkey, // 预言机账户
+    /// Pyth 价格源 ID（其他来源忽略）
+    pub feed_id: [u8; 32], // Pyth feed id
+    /// 预言机价格及历史数据的最大可接受时延（秒）
+    pub max_staleness_secs: u64, // 最大时延
+    /// 可接受的最大置信区间（基点）
+    pub max_confidence_bps: u64, // 最大置信区间
+    /// 两次观测之间的最小间隔（秒）
+    pub min_record_interval_secs: u64, // 最小写入间隔
+    /// 下一条观测写入位置（缓冲区写满后指向最旧观测）
+    pub head: u32, // 环形缓冲区写指针
+    /// 累计写入观测数量
+    pub total_observations: u64, // 累计观测数
+    /// 观测环形缓冲区
+    #[max_len(128)]
+    pub observations: Vec<PriceObservation>, // 观测列表
+    /// PDA bump
+    pub bump: u8, // PDA bump 种子
+}
+
+/// 价格历史错误码（Anchor错误）
+#[error_code]
+pub enum PriceHistoryError {
+    /// 配置参数无效
+    #[msg("Invalid price history config")] InvalidConfig,
+    /// 价格无效
+    #[msg("Invalid observation price")] InvalidPrice,
+    /// 置信区间过宽
+    #[msg("Observation confidence too wide")] ConfidenceTooWide,
+    /// 距上次观测间隔不足
+    #[msg("Observation recorded too soon")] RecordTooSoon,
+    /// 查询窗口无效
+    #[msg("Invalid price window")] InvalidWindow,
+    /// 历史数据不足以覆盖查询窗口
+    #[msg("Insufficient price history")] InsufficientHistory,
+    /// 最新观测过期
+    #[msg("Price history is stale")] StaleHistory,
+    /// 数学溢出
+    #[msg("Math overflow")] MathOverflow,
+    /// 预言机发布时间不晚于最新观测
+    #[msg("Observation is not newer than the latest one")] ObservationNotNewer,
+}
+
+impl PriceHistory {
+    /// 校验并写入预言机绑定配置
+    pub fn bind_source(
+        &mut self,
+        source: PriceHistorySource,
+        oracle_account: Pubkey,
+        feed_id: [u8; 32],
+        max_staleness_secs: u64,
+        max_confidence_bps: u64,
+    ) -> anchor_lang::Result<()> {
+        require!(oracle_account != Pubkey::default(), PriceHistoryError::InvalidConfig); // 必须绑定预言机账户
+        require!(max_staleness_secs > 0 && max_confidence_bps > 0, PriceHistoryError::InvalidConfig); // 时延与置信区间上限必须为正
+        require!(source != PriceHistorySource::Pyth || feed_id != [0u8; 32], PriceHistoryError::InvalidConfig); // Pyth来源必须提供feed_id
+        self.source = source;
+        self.oracle_account = oracle_account;
+        self.feed_id = feed_id;
+        self.max_staleness_secs = max_staleness_secs;
+        self.max_confidence_bps = max_confidence_bps;
+        Ok(())
+    }
+
+    /// 更换预言机绑定并清空旧来源的观测，避免新旧喂价混入同一 TWAP 窗口
+    pub fn rebind_source(
+        &mut self,
+        source: PriceHistorySource,
+        oracle_account: Pubkey,
+        feed_id: [u8; 32],
+        max_staleness_secs: u64,
+        max_confidence_bps: u64,
+    ) -> anchor_lang::Result<()> {
+        self.bind_source(source, oracle_account, feed_id, max_staleness_secs, max_confidence_bps)?;
+        self.head = 0;
+        self.total_observations = 0;
+        self.observations.clear();
+        Ok(())
+    }
+
+    /// 按时间先后遍历观测（最旧在前）
+    pub fn ordered_observations(&self) -> impl Iterator<Item = &PriceObservation> + '_ {
+        let split = if self.observations.len() < PRICE_HISTORY_CAPACITY { 0 } else { self.head as usize };
+        self.observations[split..].iter().chain(self.observations[..split].iter())
+    }
+
+    /// 最新观测
+    pub fn latest(&self) -> Option<&PriceObservation> {
+        if self.observations.is_empty() {
+            return None;
+        }
+        let index = (self.head as usize + self.observations.len() - 1) % self.observations.len();
+        self.observations.get(index)
+    }
+
+    /// 最旧观测
+    pub fn oldest(&self) -> Option<&PriceObservation> {
+        self.ordered_observations().next()
+    }
+
+    /// 写入一条观测并维护累计价格
+    /// - timestamp 为预言机发布时间，必须晚于最新观测（否则返回 ObservationNotNewer）
+    /// - slot 必须严格递增，且发布时间距上次观测不少于 min_record_interval_secs
+    pub fn record(&mut self, slot: u64, timestamp: i64, price: u64, confidence: u64) -> anchor_lang::Result<()> {
+        require!(price > 0, PriceHistoryError::InvalidPrice); // 价格必须为正
+        require!(
+            (confidence as u128) * 10_000 <= (price as u128) * self.max_confidence_bps as u128,
+            PriceHistoryError::ConfidenceTooWide
+        ); // 置信区间需在上限内
+        let cumulative_price = match self.latest() {
+            Some(last) => {
+                require!(timestamp > last.timestamp, PriceHistoryError::ObservationNotNewer); // 拒绝重复或更旧的喂价
+                let elapsed = timestamp - last.timestamp; // 距上次观测秒数
+                require!(
+                    slot > last.slot && elapsed as u64 >= self.min_record_interval_secs,
+                    PriceHistoryError::RecordTooSoon
+                ); // 写入频率限制
+                last.cumulative_price
+                    .checked_add((last.price as u128) * elapsed as u128)
+                    .ok_or(PriceHistoryError::MathOverflow)?
+            }
+            None => 0,
+        };
+        let observation = PriceObservation { slot, timestamp, price, confidence, cumulative_price };
+        if self.observations.len() < PRICE_HISTORY_CAPACITY {
+            self.observations.push(observation); // 缓冲区未满，追加
+        } else {
+            self.observations[self.head as usize] = observation; // 缓冲区已满，覆盖最旧观测
+        }
+        self.head = ((self.head as usize + 1) % PRICE_HISTORY_CAPACITY) as u32; // 推进写指针
+        self.total_observations += 1; // 累计观测数
+        Ok(())
+    }
+
+    /// 任意时刻的累计价格（由该时刻前最近一条观测线性外推）
+    /// - at 早于最旧观测返回 InsufficientHistory
+    pub fn cumulative_price_at(&self, at: i64) -> anchor_lang::Result<u128> {
+        let base = self
+            .ordered_observations()
+            .take_while(|observation| observation.timestamp <= at)
+            .last()
+            .ok_or(PriceHistoryError::InsufficientHistory)?; // at 之前最近一条观测
+        base.cumulative_price
+            .checked_add((base.price as u128) * (at - base.timestamp) as u128)
+            .ok_or_else(|| PriceHistoryError::MathOverflow.into())
+    }
+
+    /// 校验查询窗口：最新观测未过期，且历史覆盖 [now - window_secs, now]
+    fn window_start(&self, now: i64, window_secs: u64) -> anchor_lang::Result<i64> {
+        require!(window_secs > 0 && window_secs <= i64::MAX as u64, PriceHistoryError::InvalidWindow); // 窗口必须为正
+        let latest = self.latest().ok_or(PriceHistoryError::InsufficientHistory)?;
+        require!(now >= latest.timestamp, PriceHistoryError::InvalidWindow); // 不支持查询未来
+        require!(
+            (now - latest.timestamp) as u64 <= self.max_staleness_secs,
+            PriceHistoryError::StaleHistory
+        ); // 最新观测需在时延上限内
+        let start = now.checked_sub(window_secs as i64).ok_or(PriceHistoryError::InvalidWindow)?;
+        let oldest = self.oldest().ok_or(PriceHistoryError::InsufficientHistory)?;
+        require!(start >= oldest.timestamp, PriceHistoryError::InsufficientHistory); // 历史需覆盖整个窗口
+        Ok(start)
+    }
+
+    /// 时间加权平均价格：[now - window_secs, now] 内累计价格差 / 窗口长度
+    pub fn twap(&self, now: i64, window_secs: u64) -> anchor_lang::Result<u64> {
+        let start = self.window_start(now, window_secs)?;
+        let delta = self.cumulative_price_at(now)? - self.cumulative_price_at(start)?;
+        u64::try_from(delta / window_secs as u128).map_err(|_| PriceHistoryError::MathOverflow.into())
+    }
+
+    /// 指数移动平均价格（时间常数 period_secs）
+    /// - 价格在两次观测之间视为常数，每段持续 dt 秒按 α = dt / (period_secs + dt) 向该段价格收敛
+    /// - 从 now - period_secs 时刻的价格起算，历史需覆盖整个周期
+    pub fn ema(&self, now: i64, period_secs: u64) -> anchor_lang::Result<u64> {
+        let start = self.window_start(now, period_secs)?;
+        let mut ema = self.price_at(start)? as i128; // 以周期起点价格为初值
+        let mut cursor = start; // 当前段起点
+        let mut segment_price = ema; // 当前段价格
+        let boundaries = self
+            .ordered_observations()
+            .filter(|observation| observation.timestamp > start)
+            .map(|observation| (observation.timestamp, observation.price as i128))
+            .chain(std::iter::once((now, 0))); // 以 now 收尾
+        for (timestamp, next_price) in boundaries {
+            let dt = (timestamp - cursor) as i128; // 当前段持续秒数
+            if dt > 0 {
+                ema += (segment_price - ema) * dt / (period_secs as i128 + dt); // 向当前段价格收敛
+            }
+            cursor = timestamp;
+            segment_price = next_price;
+        }
+        Ok(ema as u64)
+    }
+
+    /// 任意时刻生效的价格（该时刻前最近一条观测）
+    pub fn price_at(&self, at: i64) -> anchor_lang::Result<u64> {
+        self.ordered_observations()
+            .take_while(|observation| observation.timestamp <= at)
+            .last()
+            .map(|observation| observation.price)
+            .ok_or_else(|| PriceHistoryError::InsufficientHistory.into())
+    }
+
+    /// 将 [now - window_secs, now] 等分为 points 段，返回各段 TWAP（最旧在前）
+    /// - 用于动量等需要等间隔价格序列的策略
+    pub fn sampled_twaps(&self, now: i64, window_secs: u64, points: u32) -> anchor_lang::Result<Vec<u64>> {
+        require!(points > 0 && window_secs >= points as u64, PriceHistoryError::InvalidWindow); // 每段至少 1 秒
+        let start = self.window_start(now, window_secs)?;
+        let step = window_secs / points as u64; // 每段秒数，余数计入首段
+        let mut prices = Vec::with_capacity(points as usize);
+        let mut segment_start = start;
+        for index in 0..points as u64 {
+            let segment_end = now - (step * (points as u64 - 1 - index)) as i64; // 段终点
+            let delta = self.cumulative_price_at(segment_end)? - self.cumulative_price_at(segment_start)?;
+            prices.push((delta / (segment_end - segment_start) as u128) as u64);
+            segment_start = segment_end;
+        }
+        Ok(prices)
+    }
+
+    /// 窗口内相邻观测收益率的标准差（基点）
+    /// - 窗口内不足 2 条观测返回 InsufficientHistory
+    pub fn volatility_bps(&self, now: i64, window_secs: u64) -> anchor_lang::Result<u64> {
+        let start = self.window_start(now, window_secs)?;
+        let prices: Vec<u64> = self
+            .ordered_observations()
+            .filter(|observation| observation.timestamp >= start)
+            .map(|observation| observation.price)
+            .collect();
+        require!(prices.len() >= 2, PriceHistoryError::InsufficientHistory); // 至少一个收益率
+        let returns: Vec<i128> = prices
+            .windows(2)
+            .map(|pair| (pair[1] as i128 - pair[0] as i128) * 10_000 / pair[0] as i128)
+            .collect(); // 相邻收益率（基点）
+        let count = returns.len() as i128;
+        let mean = returns.iter().sum::<i128>() / count;
+        let variance = returns.iter().map(|r| (r - mean) * (r - mean)).sum::<i128>() / count;
+        Ok((variance as f64).sqrt() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_history() -> PriceHistory {
+        PriceHistory {
+            authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            source: PriceHistorySource::Pyth,
+            oracle_account: Pubkey::new_unique(),
+            feed_id: [0u8; 32],
+            max_staleness_secs: 60,
+            max_confidence_bps: 100,
+            min_record_interval_secs: 5,
+            head: 0,
+            total_observations: 0,
+            observations: Vec::new(),
+            bump: 255,
+        }
+    }
+
+    /// 100 → 110 → 120，分别在 t = 1000 / 1010 / 1030 写入
+    fn recorded_history() -> PriceHistory {
+        let mut history = sample_history();
+        history.record(100, 1_000, 100_000_000, 50_000).unwrap();
+        history.record(125, 1_010, 110_000_000, 50_000).unwrap();
+        history.record(175, 1_030, 120_000_000, 50_000).unwrap();
+        history
+    }
+
+    #[test]
+    fn test_record_maintains_cumulative_price() {
+        let mut history = recorded_history();
+        assert_eq!(history.latest().unwrap().cumulative_price, 100_000_000 * 10 + 110_000_000 * 20);
+        assert_eq!(history.cumulative_price_at(1_040).unwrap(), 4_400_000_000);
+        assert!(history.cumulative_price_at(999).is_err());
+        assert!(history.record(180, 1_033, 120_000_000, 0).is_err()); // 间隔不足
+        assert!(history.record(170, 1_040, 120_000_000, 0).is_err()); // slot 回退
+        assert!(history.record(180, 1_040, 120_000_000, 1_300_000).is_err()); // 置信区间过宽
+        assert!(history.record(180, 1_040, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_record_rejects_non_newer_observation() {
+        let mut history = recorded_history();
+        // 同一喂价在后续 slot 重复写入
+        assert_eq!(
+            history.record(180, 1_030, 120_000_000, 0).unwrap_err(),
+            PriceHistoryError::ObservationNotNewer.into()
+        );
+        // 发布时间早于最新观测
+        assert_eq!(
+            history.record(180, 1_020, 120_000_000, 0).unwrap_err(),
+            PriceHistoryError::ObservationNotNewer.into()
+        );
+        assert_eq!(history.total_observations, 3);
+        history.record(180, 1_035, 120_000_000, 0).unwrap();
+        assert_eq!(history.latest().unwrap().timestamp, 1_035);
+    }
+
+    #[test]
+    fn test_rebind_source_clears_observations() {
+        let mut history = recorded_history();
+        let oracle_account = Pubkey::new_unique();
+        assert!(history.rebind_source(PriceHistorySource::Pyth, oracle_account, [0u8; 32], 60, 100).is_err()); // Pyth 缺少 feed_id
+        assert_eq!(history.observations.len(), 3); // 校验失败不改动
+        history.rebind_source(PriceHistorySource::Chainlink, oracle_account, [0u8; 32], 30, 50).unwrap();
+        assert_eq!((history.oracle_account, history.source, history.max_staleness_secs), (oracle_account, PriceHistorySource::Chainlink, 30));
+        assert!(history.observations.is_empty() && history.latest().is_none());
+        assert_eq!((history.head, history.total_observations), (0, 0));
+        history.record(200, 1_100, 100_000_000, 0).unwrap();
+        assert_eq!(history.oldest().unwrap().cumulative_price, 0);
+    }
+
+    #[test]
+    fn test_ring_buffer_overwrites_oldest() {
+        let mut history = sample_history();
+        for i in 0..(PRICE_HISTORY_CAPACITY as u64 + 3) {
+            history.record(i + 1, 1_000 + 10 * i as i64, 100_000_000 + i, 0).unwrap();
+        }
+        assert_eq!(history.observations.len(), PRICE_HISTORY_CAPACITY);
+        assert_eq!(history.total_observations, PRICE_HISTORY_CAPACITY as u64 + 3);
+        assert_eq!(history.oldest().unwrap().price, 100_000_003);
+        assert_eq!(history.latest().unwrap().price, 100_000_000 + PRICE_HISTORY_CAPACITY as u64 + 2);
+        let ordered: Vec<i64> = history.ordered_observations().map(|o| o.timestamp).collect();
+        assert!(ordered.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_twap_and_ema_windows() {
+        let history = recorded_history();
+        assert_eq!(history.twap(1_040, 40).unwrap(), 110_000_000); // (100×10 + 110×20 + 120×10) / 40
+        assert_eq!(history.twap(1_040, 20).unwrap(), 115_000_000); // (110×10 + 120×10) / 20
+        assert!(history.twap(1_040, 41).is_err()); // 历史不足
+        assert!(history.twap(1_091, 40).is_err()); // 最新观测过期
+        assert_eq!(history.ema(1_040, 20).unwrap(), 113_333_333); // 110 起算，120 持续 10s：α = 10 / 30
+        assert_eq!(history.ema(1_040, 40).unwrap(), 106_666_666); // 100 → 103.33（110 持续 20s）→ 106.67（120 持续 10s）
+        assert_eq!(history.sampled_twaps(1_040, 40, 4).unwrap(), vec![100_000_000, 110_000_000, 110_000_000, 120_000_000]);
+        assert_eq!(history.volatility_bps(1_040, 40).unwrap(), 45);
+    }
+}
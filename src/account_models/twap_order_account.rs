@@ -0,0 +1,286 @@
+//!
+//! TWAP 订单账户（PDA 持久化）
+//! 该账户用于持久化一笔按时间分片执行的 TWAP 订单，保存分片计划、已成交数量、下一可执行 slot 与分片限价。
+//! 采用 Anchor #[account] 宏声明，PDA 账户持久化，遵循 Solana/Anchor 最佳实践。
+//!
+//! # 设计说明
+//! - 订单创建后不立即成交，由任意 keeper 在每个分片间隔到期后调用 crank 指令逐片执行。
+//! - 创建时全部输入转入订单 PDA 持有的输入金库（seeds: ["twap_input_vault", order]），分片输出进入输出金库（["twap_output_vault", order]）。
+//! - 每个分片在创建时锁定的池上，通过按 dex_name 静态选取的 DexAdapter（phoenix/openbook/meteora）构造的 swap 指令、以订单 PDA 签名 CPI 成交，按金库余额变化记账并以分片限价校验实际输出。
+//! - keeper 小费在创建时预存入订单 PDA，每成功执行一个分片支付一次；撤单时退还剩余输入、转出累计输出，剩余 lamports 退还下单人。
+//! - 分片拆分、限价换算、状态推进等纯逻辑均在本文件实现，便于单元测试与审计。
+
+use anchor_lang::prelude::*; // Anchor 预导入，包含账户声明、宏、类型、Context、Result等
+
+/// TWAP 订单最大分片数量（与 TwapAlgorithm 参数校验上限保持一致）
+pub const MAX_TWAP_SLICES: usize = 100;
+/// TWAP 订单 DEX 名称最大长度
+pub const MAX_TWAP_DEX_NAME_LEN: usize = 32;
+/// 分片限价精度（与 DexSwapResult.avg_price 精度一致，1e6）
+pub const TWAP_LIMIT_PRICE_SCALE: u128 = 1_000_000;
+
+/// TWAP 订单账户结构体
+/// - PDA seeds: ["twap_order", owner, order_id.to_le_bytes()]
+/// - 采用 Anchor #[account] 宏声明，支持 InitSpace 自动空间计算
+#[account] // Anchor账户声明宏，自动实现生命周期、权限、序列化等校验逻辑
+#[derive(InitSpace)] // Anchor自动推断账户所需空间，便于部署和升级
+pub struct TwapOrder {
+    /// 下单人（订单所有者，撤单与剩余资金接收人）
+    pub owner: Pubkey, // 订单所有者公钥
+    /// 订单编号（同一下单人下唯一，参与 PDA 推导）
+    pub order_id: u64, // 订单编号
+    /// 输入 token mint
+    pub input_mint: Pubkey, // 卖出资产
+    /// 输出 token mint
+    pub output_mint: Pubkey, // 买入资产
+    /// 路由使用的 DEX 适配器名称（ESCROW_SWAP_DEX_NAMES 之一）
+    #[max_len(32)]
+    pub dex_name: String, // DEX 适配器名称
+    /// 创建时锁定的池/市场账户（crank 的首个池账户必须与之一致）
+    pub pool: Pubkey, // 池/市场账户
+    /// 订单总输入数量
+    pub total_amount: u64, // 总输入数量
+    /// 分片计划（每个分片的输入数量，总和等于 total_amount）
+    #[max_len(100)]
+    pub slice_amounts: Vec<u64>, // 分片计划
+    /// 下一个待执行分片索引
+    pub next_slice: u32, // 下一个分片索引
+    /// 已成交输入数量
+    pub filled_amount: u64, // 已成交输入数量
+    /// 已收到输出数量
+    pub received_amount: u64, // 累计输出数量
+    /// 分片间隔（slot）
+    pub slot_interval: u64, // 分片间隔
+    /// 下一可执行 slot
+    pub next_eligible_slot: u64, // 下一可执行 slot
+    /// 分片限价（每单位输入最少可得输出，精度 TWAP_LIMIT_PRICE_SCALE）
+    pub slice_limit_price: u64, // 分片限价
+    /// 每个分片支付给 keeper 的小费（lamports）
+    pub keeper_tip_lamports: u64, // keeper 小费
+    /// 订单状态
+    pub status: TwapOrderStatus, // 订单状态
+    /// 创建时间戳
+    pub created_at: i64, // 创建时间
+    /// 最后一次成交时间戳
+    pub last_fill_at: i64, // 最后成交时间
+    /// PDA bump
+    pub bump: u8, // PDA bump 种子
+}
+
+/// TWAP 订单状态枚举
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TwapOrderStatus {
+    /// 执行中
+    Active,
+    /// 全部分片已执行
+    Completed,
+}
+
+/// TWAP 订单错误码（Anchor错误）
+#[error_code]
+pub enum TwapOrderError {
+    /// 订单数量无效
+    #[msg("Invalid TWAP order amount")] InvalidAmount,
+    /// 分片数量无效
+    #[msg("Invalid number of TWAP slices")] InvalidSliceCount,
+    /// 分片间隔无效
+    #[msg("Invalid TWAP slot interval")] InvalidSlotInterval,
+    /// 输入输出资产相同
+    #[msg("Input and output mint must differ")] InvalidMints,
+    /// DEX 名称无效
+    #[msg("Invalid DEX name")] InvalidDexName,
+    /// 订单不处于执行中
+    #[msg("TWAP order is not active")] OrderNotActive,
+    /// 分片间隔尚未到期
+    #[msg("TWAP slice is not yet eligible")] SliceNotEligible,
+    /// DEX 不支持托管订单成交
+    #[msg("DEX adapter not supported for escrowed orders")] DexAdapterNotFound,
+    /// 成交价格低于分片限价
+    #[msg("Slice fill below limit price")] LimitPriceNotMet,
+    /// 金库余额变化与分片不符
+    #[msg("Slice fill does not match vault balance change")] InvalidFill,
+    /// keeper 小费余额不足
+    #[msg("Insufficient keeper tip balance")] InsufficientTipBalance,
+    /// 数学溢出
+    #[msg("Math overflow")] MathOverflow,
+    /// 分片限价无效
+    #[msg("Slice limit price must be positive")] InvalidLimitPrice,
+    /// 池账户无效或与订单锁定的池不一致
+    #[msg("Pool account does not match the order")] PoolMismatch,
+}
+
+impl TwapOrder {
+    /// 将总数量均匀拆分为 num_slices 个分片，余数计入最后一个分片
+    /// - 保证所有分片之和严格等于 total_amount，且每个分片大于 0
+    pub fn split_into_slices(total_amount: u64, num_slices: u32) -> anchor_lang::Result<Vec<u64>> {
+        require!(total_amount > 0, TwapOrderError::InvalidAmount); // 总数量必须为正
+        require!(
+            num_slices > 0 && num_slices as usize <= MAX_TWAP_SLICES,
+            TwapOrderError::InvalidSliceCount
+        ); // 分片数量需在 [1, MAX_TWAP_SLICES]
+        require!(total_amount >= num_slices as u64, TwapOrderError::InvalidSliceCount); // 每个分片至少 1 个单位
+        let base = total_amount / num_slices as u64; // 基础分片大小
+        let remainder = total_amount % num_slices as u64; // 余数
+        let mut slices = vec![base; num_slices as usize]; // 初始化分片计划
+        if let Some(last) = slices.last_mut() {
+            *last += remainder; // 余数计入最后一个分片
+        }
+        Ok(slices)
+    }
+
+    /// 订单是否已全部执行
+    pub fn is_complete(&self) -> bool {
+        self.next_slice as usize >= self.slice_amounts.len()
+    }
+
+    /// 剩余未成交输入数量
+    pub fn remaining_amount(&self) -> u64 {
+        self.total_amount.saturating_sub(self.filled_amount)
+    }
+
+    /// 剩余未执行分片数量
+    pub fn remaining_slices(&self) -> u64 {
+        self.slice_amounts.len().saturating_sub(self.next_slice as usize) as u64
+    }
+
+    /// 校验当前 slot 是否可执行下一个分片
+    pub fn assert_crankable(&self, current_slot: u64) -> anchor_lang::Result<()> {
+        require!(
+            self.status == TwapOrderStatus::Active && !self.is_complete(),
+            TwapOrderError::OrderNotActive
+        ); // 订单需处于执行中
+        require!(current_slot >= self.next_eligible_slot, TwapOrderError::SliceNotEligible); // 分片间隔需已到期
+        Ok(())
+    }
+
+    /// 校验 crank 传入的池账户与创建时锁定的池一致
+    pub fn assert_pool(&self, pool: &Pubkey) -> anchor_lang::Result<()> {
+        require_keys_eq!(*pool, self.pool, TwapOrderError::PoolMismatch); // 防止keeper改用薄池或被操纵的池成交
+        Ok(())
+    }
+
+    /// 下一个待执行分片的输入数量
+    pub fn next_slice_amount(&self) -> anchor_lang::Result<u64> {
+        self.slice_amounts
+            .get(self.next_slice as usize)
+            .copied()
+            .ok_or_else(|| TwapOrderError::OrderNotActive.into())
+    }
+
+    /// 根据分片限价计算分片最小可接受输出
+    pub fn min_amount_out(&self, slice_amount: u64) -> anchor_lang::Result<u64> {
+        let min_out = (slice_amount as u128)
+            .checked_mul(self.slice_limit_price as u128)
+            .ok_or(TwapOrderError::MathOverflow)?
+            / TWAP_LIMIT_PRICE_SCALE; // 输出 = 输入 * 限价 / 精度
+        u64::try_from(min_out).map_err(|_| TwapOrderError::MathOverflow.into())
+    }
+
+    /// 记录一个分片成交并推进状态
+    /// - slice_amount: 本分片实际消耗的输入数量（输入金库余额变化）
+    /// - amount_out: 本分片实际输出数量（输出金库余额变化）
+    /// - current_slot: 成交时 slot，用于计算下一可执行 slot
+    /// - now: 成交时间戳
+    pub fn record_fill(&mut self, slice_amount: u64, amount_out: u64, current_slot: u64, now: i64) -> anchor_lang::Result<()> {
+        self.filled_amount = self
+            .filled_amount
+            .checked_add(slice_amount)
+            .ok_or(TwapOrderError::MathOverflow)?; // 累加已成交输入
+        self.received_amount = self
+            .received_amount
+            .checked_add(amount_out)
+            .ok_or(TwapOrderError::MathOverflow)?; // 累加输出
+        self.next_slice += 1; // 推进分片索引
+        self.next_eligible_slot = current_slot
+            .checked_add(self.slot_interval)
+            .ok_or(TwapOrderError::MathOverflow)?; // 以本次成交 slot 为基准计算下一可执行 slot，避免 keeper 延迟后连续补单
+        self.last_fill_at = now; // 更新最后成交时间
+        if self.is_complete() {
+            self.status = TwapOrderStatus::Completed; // 全部分片完成
+        }
+        Ok(())
+    }
+
+    /// 已成交部分的平均价格（精度 TWAP_LIMIT_PRICE_SCALE）
+    pub fn average_fill_price(&self) -> u64 {
+        if self.filled_amount == 0 {
+            return 0;
+        }
+        ((self.received_amount as u128 * TWAP_LIMIT_PRICE_SCALE) / self.filled_amount as u128) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_order(total_amount: u64, num_slices: u32) -> TwapOrder {
+        TwapOrder {
+            owner: Pubkey::new_unique(),
+            order_id: 1,
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            dex_name: "mock_dex".to_string(),
+            pool: Pubkey::new_unique(),
+            total_amount,
+            slice_amounts: TwapOrder::split_into_slices(total_amount, num_slices).unwrap(),
+            next_slice: 0,
+            filled_amount: 0,
+            received_amount: 0,
+            slot_interval: 150,
+            next_eligible_slot: 1_000,
+            slice_limit_price: 990_000,
+            keeper_tip_lamports: 5_000,
+            status: TwapOrderStatus::Active,
+            created_at: 0,
+            last_fill_at: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_split_into_slices_preserves_total() {
+        let slices = TwapOrder::split_into_slices(1_003, 10).unwrap();
+        assert_eq!(slices.len(), 10);
+        assert_eq!(slices.iter().sum::<u64>(), 1_003);
+        assert_eq!(slices[9], 103);
+        assert!(TwapOrder::split_into_slices(0, 10).is_err());
+        assert!(TwapOrder::split_into_slices(5, 10).is_err());
+        assert!(TwapOrder::split_into_slices(1_000, 101).is_err());
+    }
+
+    #[test]
+    fn test_slice_eligibility_and_progress() {
+        let mut order = sample_order(1_000, 4);
+        assert!(order.assert_crankable(999).is_err()); // 未到期
+        assert!(order.assert_crankable(1_000).is_ok());
+        order.record_fill(250, 248, 1_000, 10).unwrap();
+        assert_eq!(order.next_eligible_slot, 1_150);
+        assert!(order.assert_crankable(1_100).is_err()); // 下一分片未到期
+        for slot in [1_150, 1_300, 1_450] {
+            order.assert_crankable(slot).unwrap();
+            let amount = order.next_slice_amount().unwrap();
+            order.record_fill(amount, amount, slot, 20).unwrap();
+        }
+        assert_eq!(order.status, TwapOrderStatus::Completed);
+        assert_eq!(order.filled_amount, 1_000);
+        assert_eq!(order.remaining_amount(), 0);
+        assert!(order.assert_crankable(u64::MAX).is_err()); // 已完成订单不可再 crank
+    }
+
+    #[test]
+    fn test_pool_is_pinned() {
+        let order = sample_order(1_000, 4);
+        assert!(order.assert_pool(&order.pool).is_ok());
+        assert!(order.assert_pool(&Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_min_amount_out_and_average_price() {
+        let mut order = sample_order(1_000, 2);
+        assert_eq!(order.min_amount_out(500).unwrap(), 495); // 500 * 0.99
+        order.record_fill(500, 500, 1_000, 0).unwrap();
+        order.record_fill(500, 490, 1_150, 0).unwrap();
+        assert_eq!(order.average_fill_price(), 990_000);
+    }
+}
@@ -9,6 +9,7 @@
  * - 精英保留策略
  * - 多样性维护
  * - 性能监控和指标收集
+ * - 种子驱动的确定性随机数，执行计划可逐位复现
  */
 
 use anchor_lang::prelude::*;
@@ -16,9 +17,8 @@ use crate::algorithms::execution_optimizer::{
     ExecutionOptimizerParams, MarketData, OptimizationResult, ExecutionPlan, 
     ExecutionSegment, ExecutionStrategyType, OptimizationMetrics, Optimizer
 };
+use crate::algorithms::execution_optimizer::rng::OptimizerRng;
 // use crate::errors::algorithm_error::AlgorithmError; // 暂时注释掉
-use std::time::{SystemTime, UNIX_EPOCH};
-use rand::Rng;
 
 /// 遗传算法个体
 #[derive(Clone, Debug, PartialEq)]
@@ -60,6 +60,8 @@ pub struct GeneticConfig {
     pub enable_adaptive_params: bool,
     /// 是否启用多样性维护
     pub enable_diversity_maintenance: bool,
+    /// 默认随机种子
+    pub seed: u64,
 }
 
 /// 遗传算法优化器
@@ -67,8 +69,6 @@ pub struct GeneticConfig {
 pub struct GeneticOptimizer {
     /// 算法配置
     config: GeneticConfig,
-    /// 随机数生成器
-    rng: rand::rngs::ThreadRng,
     /// 性能统计
     stats: GeneticStats,
 }
@@ -102,16 +102,21 @@ impl Default for GeneticConfig {
             max_generations_without_improvement: 50,
             enable_adaptive_params: true,
             enable_diversity_maintenance: true,
+            seed: 0,
         }
     }
 }
 
 impl Optimizer for GeneticOptimizer {
     fn optimize(&self, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<OptimizationResult> {
-        let start_time = self.get_current_timestamp();
+        self.optimize_with_seed(params, market_data, self.config.seed)
+    }
+    
+    fn optimize_with_seed(&self, params: &ExecutionOptimizerParams, market_data: &MarketData, seed: u64) -> anchor_lang::Result<OptimizationResult> {
+        let mut rng = OptimizerRng::new(seed);
         
         // 初始化种群
-        let mut population = self.initialize_population(params, market_data)?;
+        let mut population = self.initialize_population(params, market_data, &mut rng)?;
         
         // 遗传算法主循环
         let mut generation = 0;
@@ -141,7 +146,7 @@ impl Optimizer for GeneticOptimizer {
             }
             
             // 生成新一代
-            population = self.generate_next_generation(&population, params, market_data)?;
+            population = self.generate_next_generation(&population, params, market_data, &mut rng)?;
             
             generation += 1;
         }
@@ -150,7 +155,7 @@ impl Optimizer for GeneticOptimizer {
         let best_individual = &population[0];
         
         // 构建优化结果
-        let result = self.build_optimization_result(best_individual, params, market_data, generation, start_time)?;
+        let result = self.build_optimization_result(best_individual, params, market_data, generation, seed, &mut rng)?;
         
         Ok(result)
     }
@@ -165,7 +170,6 @@ impl GeneticOptimizer {
     pub fn new() -> Self {
         Self {
             config: GeneticConfig::default(),
-            rng: rand::thread_rng(),
             stats: GeneticStats::default(),
         }
     }
@@ -174,17 +178,16 @@ impl GeneticOptimizer {
     pub fn with_config(config: GeneticConfig) -> Self {
         Self {
             config,
-            rng: rand::thread_rng(),
             stats: GeneticStats::default(),
         }
     }
     
     /// 初始化种群
-    fn initialize_population(&self, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<Vec<GeneticIndividual>> {
+    fn initialize_population(&self, params: &ExecutionOptimizerParams, market_data: &MarketData, rng: &mut OptimizerRng) -> anchor_lang::Result<Vec<GeneticIndividual>> {
         let mut population = Vec::with_capacity(self.config.population_size);
         
         for i in 0..self.config.population_size {
-            let individual = self.create_random_individual(params, market_data, i)?;
+            let individual = self.create_random_individual(params, market_data, i, rng)?;
             population.push(individual);
         }
         
@@ -192,7 +195,7 @@ impl GeneticOptimizer {
     }
     
     /// 创建随机个体
-    fn create_random_individual(&self, params: &ExecutionOptimizerParams, market_data: &MarketData, index: usize) -> anchor_lang::Result<GeneticIndividual> {
+    fn create_random_individual(&self, params: &ExecutionOptimizerParams, market_data: &MarketData, index: usize, rng: &mut OptimizerRng) -> anchor_lang::Result<GeneticIndividual> {
         let segment_count = self.calculate_optimal_segment_count(params, market_data);
         let mut segments = Vec::with_capacity(segment_count);
         
@@ -206,6 +209,7 @@ impl GeneticOptimizer {
                 &mut remaining_time,
                 params,
                 market_data,
+                rng,
             )?;
             segments.push(segment);
         }
@@ -237,12 +241,13 @@ impl GeneticOptimizer {
         remaining_time: &mut u64,
         params: &ExecutionOptimizerParams,
         market_data: &MarketData,
+        rng: &mut OptimizerRng,
     ) -> anchor_lang::Result<ExecutionSegment> {
         let amount = if *remaining_amount > 0 {
             let max_amount = (*remaining_amount).min(market_data.liquidity / 10);
             let min_amount = 1u64;
             if max_amount > min_amount {
-                self.rng.gen_range(min_amount..=max_amount)
+                rng.gen_range_u64(min_amount, max_amount)
             } else {
                 *remaining_amount
             }
@@ -254,7 +259,7 @@ impl GeneticOptimizer {
             let max_time = (*remaining_time).min(300); // 最大5分钟
             let min_time = 30u64; // 最小30秒
             if max_time > min_time {
-                self.rng.gen_range(min_time..=max_time)
+                rng.gen_range_u64(min_time, max_time)
             } else {
                 *remaining_time
             }
@@ -262,10 +267,10 @@ impl GeneticOptimizer {
             0
         };
         
-        let target_price = self.calculate_target_price(market_data, amount);
+        let target_price = self.calculate_target_price(market_data, amount, rng);
         let expected_cost = self.calculate_expected_cost(amount, target_price, market_data);
         let expected_market_impact = self.calculate_market_impact(amount, market_data);
-        let execution_strategy = self.select_random_execution_strategy();
+        let execution_strategy = self.select_random_execution_strategy(rng);
         
         *remaining_amount = remaining_amount.saturating_sub(amount);
         *remaining_time = remaining_time.saturating_sub(execution_time);
@@ -282,12 +287,12 @@ impl GeneticOptimizer {
     }
     
     /// 计算目标价格
-    fn calculate_target_price(&self, market_data: &MarketData, amount: u64) -> u64 {
+    fn calculate_target_price(&self, market_data: &MarketData, amount: u64, rng: &mut OptimizerRng) -> u64 {
         let base_price = market_data.current_price;
         let impact_factor = (amount as f64 / market_data.liquidity as f64) * 0.1;
         let price_adjustment = (base_price as f64 * impact_factor) as u64;
         
-        if self.rng.gen_bool(0.5) {
+        if rng.gen_bool(0.5) {
             base_price.saturating_add(price_adjustment)
         } else {
             base_price.saturating_sub(price_adjustment)
@@ -310,7 +315,7 @@ impl GeneticOptimizer {
     }
     
     /// 选择随机执行策略
-    fn select_random_execution_strategy(&self) -> ExecutionStrategyType {
+    fn select_random_execution_strategy(&self, rng: &mut OptimizerRng) -> ExecutionStrategyType {
         let strategies = vec![
             ExecutionStrategyType::Immediate,
             ExecutionStrategyType::LimitOrder,
@@ -319,7 +324,7 @@ impl GeneticOptimizer {
             ExecutionStrategyType::VolumeWeighted,
         ];
         
-        let index = self.rng.gen_range(0..strategies.len());
+        let index = rng.gen_index(strategies.len());
         strategies[index].clone()
     }
     
@@ -410,7 +415,7 @@ impl GeneticOptimizer {
     }
     
     /// 生成新一代
-    fn generate_next_generation(&self, population: &[GeneticIndividual], params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<Vec<GeneticIndividual>> {
+    fn generate_next_generation(&self, population: &[GeneticIndividual], params: &ExecutionOptimizerParams, market_data: &MarketData, rng: &mut OptimizerRng) -> anchor_lang::Result<Vec<GeneticIndividual>> {
         let mut new_population = Vec::with_capacity(self.config.population_size);
         
         // 精英保留
@@ -421,16 +426,16 @@ impl GeneticOptimizer {
         
         // 生成新个体
         while new_population.len() < self.config.population_size {
-            if self.rng.gen_bool(self.config.crossover_rate) {
+            if rng.gen_bool(self.config.crossover_rate) {
                 // 交叉
-                let parent1 = self.select_parent(population);
-                let parent2 = self.select_parent(population);
-                let child = self.crossover(parent1, parent2, params, market_data)?;
+                let parent1 = self.select_parent(population, rng);
+                let parent2 = self.select_parent(population, rng);
+                let child = self.crossover(parent1, parent2, params, market_data, rng)?;
                 new_population.push(child);
             } else {
                 // 变异
-                let parent = self.select_parent(population);
-                let child = self.mutate(parent, params, market_data)?;
+                let parent = self.select_parent(population, rng);
+                let child = self.mutate(parent, params, market_data, rng)?;
                 new_population.push(child);
             }
         }
@@ -439,10 +444,10 @@ impl GeneticOptimizer {
     }
     
     /// 选择父代
-    fn select_parent(&self, population: &[GeneticIndividual]) -> &GeneticIndividual {
+    fn select_parent<'a>(&self, population: &'a [GeneticIndividual], rng: &mut OptimizerRng) -> &'a GeneticIndividual {
         // 轮盘赌选择
         let total_fitness: f64 = population.iter().map(|i| i.fitness_score).sum();
-        let random_value = self.rng.gen_range(0.0..total_fitness);
+        let random_value = rng.gen_range_f64(0.0, total_fitness);
         
         let mut cumulative_fitness = 0.0;
         for individual in population {
@@ -456,8 +461,8 @@ impl GeneticOptimizer {
     }
     
     /// 交叉操作
-    fn crossover(&self, parent1: &GeneticIndividual, parent2: &GeneticIndividual, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<GeneticIndividual> {
-        let crossover_point = self.rng.gen_range(0..parent1.segments.len().min(parent2.segments.len()));
+    fn crossover(&self, parent1: &GeneticIndividual, parent2: &GeneticIndividual, params: &ExecutionOptimizerParams, market_data: &MarketData, rng: &mut OptimizerRng) -> anchor_lang::Result<GeneticIndividual> {
+        let crossover_point = rng.gen_index(parent1.segments.len().min(parent2.segments.len()));
         
         let mut child_segments = Vec::new();
         
@@ -479,15 +484,16 @@ impl GeneticOptimizer {
                 &mut 0,
                 params,
                 market_data,
+                rng,
             )?;
             child_segments.push(segment);
         }
         
         // 调整数量和时间为有效值
-        self.adjust_segments(&mut child_segments, params, market_data)?;
+        self.adjust_segments(&mut child_segments, params, market_data, rng)?;
         
         Ok(GeneticIndividual {
-            id: format!("genetic_child_{}", self.rng.gen::<u32>()),
+            id: format!("genetic_child_{}", rng.next_u32()),
             segments: child_segments,
             fitness_score: 0.0,
             cost_score: 0.0,
@@ -498,36 +504,36 @@ impl GeneticOptimizer {
     }
     
     /// 变异操作
-    fn mutate(&self, parent: &GeneticIndividual, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<GeneticIndividual> {
+    fn mutate(&self, parent: &GeneticIndividual, params: &ExecutionOptimizerParams, market_data: &MarketData, rng: &mut OptimizerRng) -> anchor_lang::Result<GeneticIndividual> {
         let mut child_segments = parent.segments.clone();
         
         // 随机变异一些分段
         for segment in child_segments.iter_mut() {
-            if self.rng.gen_bool(self.config.mutation_rate) {
+            if rng.gen_bool(self.config.mutation_rate) {
                 // 变异数量
                 let amount_variation = (segment.amount as f64 * 0.2) as u64;
                 segment.amount = segment.amount.saturating_add(
-                    self.rng.gen_range(0..=amount_variation)
+                    rng.gen_range_u64(0, amount_variation)
                 );
                 
                 // 变异时间
                 let time_variation = (segment.execution_time as f64 * 0.2) as u64;
                 segment.execution_time = segment.execution_time.saturating_add(
-                    self.rng.gen_range(0..=time_variation)
+                    rng.gen_range_u64(0, time_variation)
                 );
                 
                 // 变异执行策略
-                if self.rng.gen_bool(0.3) {
-                    segment.execution_strategy = self.select_random_execution_strategy();
+                if rng.gen_bool(0.3) {
+                    segment.execution_strategy = self.select_random_execution_strategy(rng);
                 }
             }
         }
         
         // 调整变异后的分段
-        self.adjust_segments(&mut child_segments, params, market_data)?;
+        self.adjust_segments(&mut child_segments, params, market_data, rng)?;
         
         Ok(GeneticIndividual {
-            id: format!("genetic_mutant_{}", self.rng.gen::<u32>()),
+            id: format!("genetic_mutant_{}", rng.next_u32()),
             segments: child_segments,
             fitness_score: 0.0,
             cost_score: 0.0,
@@ -538,7 +544,7 @@ impl GeneticOptimizer {
     }
     
     /// 调整分段
-    fn adjust_segments(&self, segments: &mut Vec<ExecutionSegment>, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<()> {
+    fn adjust_segments(&self, segments: &mut Vec<ExecutionSegment>, params: &ExecutionOptimizerParams, market_data: &MarketData, rng: &mut OptimizerRng) -> anchor_lang::Result<()> {
         let total_amount: u64 = segments.iter().map(|s| s.amount).sum();
         let total_time: u64 = segments.iter().map(|s| s.execution_time).sum();
         
@@ -560,7 +566,7 @@ impl GeneticOptimizer {
         
         // 重新计算价格和成本
         for segment in segments.iter_mut() {
-            segment.target_price = self.calculate_target_price(market_data, segment.amount);
+            segment.target_price = self.calculate_target_price(market_data, segment.amount, rng);
             segment.expected_cost = self.calculate_expected_cost(segment.amount, segment.target_price, market_data);
             segment.expected_market_impact_bps = self.calculate_market_impact(segment.amount, market_data);
         }
@@ -575,7 +581,8 @@ impl GeneticOptimizer {
         params: &ExecutionOptimizerParams,
         market_data: &MarketData,
         generation: u32,
-        start_time: u64,
+        seed: u64,
+        rng: &mut OptimizerRng,
     ) -> anchor_lang::Result<OptimizationResult> {
         let total_amount: u64 = best_individual.segments.iter().map(|s| s.amount).sum();
         let total_time: u64 = best_individual.segments.iter().map(|s| s.execution_time).sum();
@@ -583,7 +590,7 @@ impl GeneticOptimizer {
         let total_market_impact: u32 = best_individual.segments.iter().map(|s| s.expected_market_impact_bps).sum();
         
         let execution_plan = ExecutionPlan {
-            id: format!("genetic_plan_{}", self.rng.gen::<u32>()),
+            id: format!("genetic_plan_{}", rng.next_u32()),
             segments: best_individual.segments.clone(),
             total_execution_amount: total_amount,
             total_execution_time: total_time,
@@ -591,10 +598,8 @@ impl GeneticOptimizer {
             total_market_impact_bps: total_market_impact,
         };
         
-        let optimization_time = self.get_current_timestamp() - start_time;
-        
         let metrics = OptimizationMetrics {
-            optimization_time_ms: optimization_time * 1000,
+            optimization_time_ms: 0, // 不读取系统时钟，耗时由调用方在链下统计
             iteration_count: generation,
             convergence_count: 1, // 假设收敛
             cost_improvement_rate: 1.0 - (total_cost as f64 / params.order_size as f64),
@@ -604,7 +609,7 @@ impl GeneticOptimizer {
         };
         
         Ok(OptimizationResult {
-            id: format!("genetic_result_{}", self.rng.gen::<u32>()),
+            id: format!("genetic_result_{}", rng.next_u32()),
             optimal_execution_plan: execution_plan,
             expected_cost: total_cost,
             expected_market_impact_bps: total_market_impact,
@@ -612,16 +617,10 @@ impl GeneticOptimizer {
             optimization_score: best_individual.fitness_score,
             risk_score: best_individual.risk_score,
             metrics,
+            seed,
+            predicted_cost_bps: None, // 遗传算法不使用成本模型
         })
     }
-    
-    /// 获取当前时间戳
-    fn get_current_timestamp(&self) -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
-    }
 }
 
 #[cfg(test)]
@@ -676,9 +675,10 @@ mod tests {
             volume: 1_000_000,
             spread_bps: 20,
             market_sentiment: crate::algorithms::execution_optimizer::MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
         };
         
-        let individual = optimizer.create_random_individual(&params, &market_data, 0).unwrap();
+        let individual = optimizer.create_random_individual(&params, &market_data, 0, &mut OptimizerRng::new(42)).unwrap();
         
         assert!(!individual.segments.is_empty());
         assert_eq!(individual.id, "genetic_individual_0");
@@ -726,6 +726,7 @@ mod tests {
             volume: 1_000_000,
             spread_bps: 20,
             market_sentiment: crate::algorithms::execution_optimizer::MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
         };
         
         let result = optimizer.optimize(&params, &market_data).unwrap();
@@ -734,6 +735,100 @@ mod tests {
         assert!(!result.optimal_execution_plan.segments.is_empty());
         assert!(result.expected_cost > 0);
         assert!(result.optimization_score > 0.0);
-        assert!(result.metrics.optimization_time_ms > 0);
+        assert_eq!(result.metrics.optimization_time_ms, 0); // 优化过程不读取系统时钟
     }
-} 
\ No newline at end of file
+    
+    fn sample_inputs() -> (ExecutionOptimizerParams, MarketData) {
+        let params = ExecutionOptimizerParams {
+            order_size: 100_000,
+            target_execution_time: 1800,
+            max_slippage_tolerance_bps: 100,
+            enable_cost_optimization: true,
+            enable_market_impact_optimization: true,
+            enable_timing_optimization: true,
+            optimization_strategy: crate::algorithms::execution_optimizer::OptimizationStrategy::Genetic,
+            risk_params: crate::algorithms::execution_optimizer::ExecutionOptimizerRiskParams {
+                max_market_impact_bps: 200,
+                max_execution_cost_bps: 100,
+                max_execution_time: 3600,
+                enable_emergency_stop: true,
+                emergency_stop_threshold_bps: 500,
+                max_volatility_tolerance_bps: 8000,
+                min_liquidity_requirement: 1000,
+            },
+            monitoring_params: crate::algorithms::execution_optimizer::ExecutionOptimizerMonitoringParams {
+                enable_monitoring: true,
+                metrics_interval: 30,
+                enable_detailed_logging: true,
+                enable_performance_warnings: true,
+                enable_optimization_analysis: true,
+            },
+        };
+        let market_data = MarketData {
+            current_price: 1_000_000,
+            volatility: 0.05,
+            liquidity: 10_000_000,
+            market_depth: vec![
+                crate::algorithms::execution_optimizer::PriceLevel { price: 999_000, size: 1000, cumulative_size: 1000 },
+                crate::algorithms::execution_optimizer::PriceLevel { price: 1_001_000, size: 1000, cumulative_size: 1000 },
+            ],
+            volume: 1_000_000,
+            spread_bps: 20,
+            market_sentiment: crate::algorithms::execution_optimizer::MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
+        };
+        (params, market_data)
+    }
+    
+    #[test]
+    fn test_seeded_optimization_is_reproducible() {
+        let optimizer = GeneticOptimizer::with_config(GeneticConfig {
+            population_size: 20,
+            max_generations: 10,
+            seed: 7,
+            ..GeneticConfig::default()
+        });
+        let (params, market_data) = sample_inputs();
+        
+        let first = optimizer.optimize_with_seed(&params, &market_data, 42).unwrap();
+        let second = optimizer.optimize_with_seed(&params, &market_data, 42).unwrap();
+        assert_eq!(first.seed, 42);
+        assert_eq!(first.id, second.id);
+        assert_eq!(first, second); // 同一种子逐位复现（不依赖系统时钟）
+        
+        let other = optimizer.optimize_with_seed(&params, &market_data, 43).unwrap();
+        assert_ne!(first.id, other.id);
+        assert_eq!(optimizer.optimize(&params, &market_data).unwrap().seed, 7); // 默认使用配置种子
+    }
+    
+    /// 黄金向量：固定种子与行情下的执行计划逐字段固定
+    #[test]
+    fn test_seeded_optimization_golden_vector() {
+        use crate::algorithms::execution_optimizer::ExecutionStrategyType::{Immediate, VolumeWeighted};
+        let optimizer = GeneticOptimizer::with_config(GeneticConfig {
+            population_size: 20,
+            max_generations: 10,
+            seed: 7,
+            ..GeneticConfig::default()
+        });
+        let (params, market_data) = sample_inputs();
+        let result = optimizer.optimize_with_seed(&params, &market_data, 42).unwrap();
+        
+        assert_eq!(result.seed, 42);
+        assert_eq!(result.id, "genetic_result_3314690962");
+        assert_eq!(result.optimal_execution_plan.id, "genetic_plan_4202669894");
+        // (数量, 执行时间, 目标价格, 预期成本, 市场冲击bps, 执行策略)
+        let segments: Vec<_> = result.optimal_execution_plan.segments.iter()
+            .map(|s| (s.index, s.amount, s.execution_time, s.target_price, s.expected_cost, s.expected_market_impact_bps, s.execution_strategy.clone()))
+            .collect();
+        assert_eq!(segments, vec![
+            (0, 79355, 872, 999207, 79450, 79, Immediate),
+            (1, 5593, 565, 999945, 5603, 5, VolumeWeighted),
+            (2, 14563, 136, 1000145, 14594, 14, Immediate),
+            (3, 487, 226, 999996, 486, 0, Immediate),
+        ]);
+        assert_eq!((result.expected_cost, result.expected_market_impact_bps, result.expected_execution_time), (100133, 98, 1799));
+        
+        assert_eq!(optimizer.optimize_with_seed(&params, &market_data, 42).unwrap(), result); // 再次运行输出一致
+    }
+}
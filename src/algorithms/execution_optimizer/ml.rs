@@ -10,6 +10,9 @@
  * - 自适应学习率
  * - 特征工程和预处理
  * - 模型验证和测试
+ * - 种子驱动的确定性随机数，执行计划可逐位复现
+ * - 基于历史成交的离线训练（特征标准化、验证集划分、早停）
+ * - 模型 postcard 紧凑二进制序列化，可加载到优化器或存入链上账户
  */
 
 use anchor_lang::prelude::*;
@@ -17,12 +20,23 @@ use crate::algorithms::execution_optimizer::{
     ExecutionOptimizerParams, MarketData, OptimizationResult, ExecutionPlan, 
     ExecutionSegment, ExecutionStrategy, OptimizationMetrics, Optimizer
 };
-use crate::algorithms::traits::AlgorithmError;
-use std::time::{SystemTime, UNIX_EPOCH};
-use rand::Rng;
+use crate::algorithms::execution_optimizer::rng::OptimizerRng;
+use crate::errors::algorithm_error::AlgorithmError;
+use serde::{Deserialize, Serialize};
+
+/// 模型序列化格式版本
+pub const MODEL_FORMAT_VERSION: u8 = 1;
+/// 最少训练样本数量
+pub const MIN_TRAINING_SAMPLES: usize = 2;
+/// 学习率调度衰减系数（逆时间衰减）
+const LEARNING_RATE_DECAY: f64 = 0.01;
+/// 验证损失最小改善幅度（低于该值视为无改善）
+const MIN_LOSS_IMPROVEMENT: f64 = 1e-9;
+/// 预测准确率容差（相对误差不超过 10% 视为准确）
+const ACCURACY_TOLERANCE: f64 = 0.1;
 
 /// 机器学习模型类型
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum MLModelType {
     /// 神经网络
     NeuralNetwork,
@@ -80,6 +94,17 @@ pub struct MLPrediction {
     pub segment_time_distribution: Vec<u64>,
     /// 预测的性能指标
     pub predicted_metrics: crate::algorithms::execution_optimizer::OptimizationMetrics,
+    /// 模型预测的执行成本（基点，无训练模型时为空）
+    pub predicted_cost_bps: Option<f64>,
+}
+
+/// 训练样本：订单特征与实际执行成本
+#[derive(Clone, Debug, PartialEq)]
+pub struct MLTrainingSample {
+    /// 订单特征
+    pub features: MLFeatures,
+    /// 实际执行成本（基点）
+    pub realized_cost_bps: f64,
 }
 
 /// 机器学习模型配置
@@ -109,6 +134,12 @@ pub struct MLConfig {
     pub enable_batch_normalization: bool,
     /// 是否启用学习率调度
     pub enable_learning_rate_scheduling: bool,
+    /// 隐藏层神经元数量（线性回归模型忽略）
+    pub hidden_layers: Vec<usize>,
+    /// 隐藏层激活函数
+    pub activation_function: ActivationFunction,
+    /// 默认随机种子（同时用于训练集划分与权重初始化）
+    pub seed: u64,
 }
 
 /// 机器学习优化器
@@ -122,12 +153,10 @@ pub struct MLOptimizer {
     feature_scaler: FeatureScaler,
     /// 性能统计
     stats: MLStats,
-    /// 随机数生成器
-    rng: rand::rngs::ThreadRng,
 }
 
-/// 机器学习模型
-#[derive(Clone, Debug)]
+/// 机器学习模型（前馈网络，输出层为线性）
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MLModel {
     /// 模型类型
     pub model_type: MLModelType,
@@ -135,7 +164,7 @@ pub struct MLModel {
     pub weights: Vec<f64>,
     /// 模型偏置
     pub biases: Vec<f64>,
-    /// 模型层数
+    /// 各层神经元数量（含输入层与输出层）
     pub layers: Vec<usize>,
     /// 激活函数
     pub activation_function: ActivationFunction,
@@ -144,7 +173,7 @@ pub struct MLModel {
 }
 
 /// 激活函数
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ActivationFunction {
     /// ReLU
     ReLU,
@@ -157,11 +186,11 @@ pub enum ActivationFunction {
 }
 
 /// 模型性能指标
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct ModelPerformanceMetrics {
-    /// 训练损失
+    /// 训练损失（均方误差）
     pub training_loss: f64,
-    /// 验证损失
+    /// 验证损失（均方误差）
     pub validation_loss: f64,
     /// 预测准确率
     pub accuracy: f64,
@@ -171,10 +200,12 @@ pub struct ModelPerformanceMetrics {
     pub rmse: f64,
     /// R平方值
     pub r_squared: f64,
+    /// 实际训练轮数
+    pub epochs_trained: u32,
 }
 
 /// 特征标准化器
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct FeatureScaler {
     /// 特征均值
     pub means: Vec<f64>,
@@ -182,6 +213,21 @@ pub struct FeatureScaler {
     pub stds: Vec<f64>,
     /// 是否已拟合
     pub fitted: bool,
+    /// 目标值均值
+    pub target_mean: f64,
+    /// 目标值标准差
+    pub target_std: f64,
+}
+
+/// 模型快照：模型与其特征标准化器，序列化后可加载到优化器或存入链上账户
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MLModelSnapshot {
+    /// 序列化格式版本
+    pub version: u8,
+    /// 训练好的模型
+    pub model: MLModel,
+    /// 特征标准化器
+    pub scaler: FeatureScaler,
 }
 
 /// 机器学习统计信息
@@ -214,13 +260,299 @@ impl Default for MLConfig {
             dropout_rate: 0.2,
             enable_batch_normalization: true,
             enable_learning_rate_scheduling: true,
+            hidden_layers: vec![16, 8],
+            activation_function: ActivationFunction::ReLU,
+            seed: 0,
+        }
+    }
+}
+
+impl MLFeatures {
+    /// 转换为神经网络输入向量
+    pub fn neural_input(&self) -> Vec<f64> {
+        vec![
+            self.order_size,
+            self.target_execution_time,
+            self.current_price,
+            self.volatility,
+            self.liquidity,
+            self.volume,
+            self.spread_bps,
+            self.market_sentiment,
+            self.price_trend,
+            self.volume_trend,
+            self.market_depth,
+        ]
+    }
+}
+
+impl ActivationFunction {
+    /// 应用激活函数
+    pub fn apply(&self, x: f64) -> f64 {
+        match self {
+            ActivationFunction::ReLU => x.max(0.0),
+            ActivationFunction::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            ActivationFunction::Tanh => x.tanh(),
+            ActivationFunction::Linear => x,
+        }
+    }
+    
+    /// 激活函数在预激活值 z 处的导数
+    pub fn derivative(&self, z: f64) -> f64 {
+        match self {
+            ActivationFunction::ReLU => if z > 0.0 { 1.0 } else { 0.0 },
+            ActivationFunction::Sigmoid => {
+                let s = self.apply(z);
+                s * (1.0 - s)
+            }
+            ActivationFunction::Tanh => 1.0 - z.tanh().powi(2),
+            ActivationFunction::Linear => 1.0,
+        }
+    }
+}
+
+impl FeatureScaler {
+    /// 按训练集拟合各特征与目标值的均值和标准差
+    pub fn fit(inputs: &[Vec<f64>], targets: &[f64]) -> Self {
+        let dimension = inputs.first().map_or(0, |input| input.len());
+        let count = inputs.len().max(1) as f64;
+        let means: Vec<f64> = (0..dimension)
+            .map(|j| inputs.iter().map(|input| input[j]).sum::<f64>() / count)
+            .collect();
+        let stds = (0..dimension)
+            .map(|j| (inputs.iter().map(|input| (input[j] - means[j]).powi(2)).sum::<f64>() / count).sqrt())
+            .collect();
+        let target_count = targets.len().max(1) as f64;
+        let target_mean = targets.iter().sum::<f64>() / target_count;
+        let target_std = (targets.iter().map(|y| (y - target_mean).powi(2)).sum::<f64>() / target_count).sqrt();
+        Self { means, stds, fitted: true, target_mean, target_std }
+    }
+    
+    /// 标准化特征向量（标准差为 0 的特征仅去均值）
+    pub fn transform(&self, input: &[f64]) -> Vec<f64> {
+        if !self.fitted {
+            return input.to_vec();
+        }
+        input.iter().enumerate().map(|(j, &x)| {
+            let mean = self.means.get(j).copied().unwrap_or(0.0);
+            let std = self.stds.get(j).copied().unwrap_or(0.0);
+            if std > 0.0 { (x - mean) / std } else { x - mean }
+        }).collect()
+    }
+    
+    /// 标准化目标值
+    pub fn transform_target(&self, y: f64) -> f64 {
+        if !self.fitted {
+            return y;
+        }
+        (y - self.target_mean) / self.target_scale()
+    }
+    
+    /// 还原目标值
+    pub fn inverse_transform_target(&self, y: f64) -> f64 {
+        if !self.fitted {
+            return y;
+        }
+        y * self.target_scale() + self.target_mean
+    }
+    
+    /// 目标值缩放系数（标准差为 0 时取 1）
+    fn target_scale(&self) -> f64 {
+        if self.target_std > 0.0 { self.target_std } else { 1.0 }
+    }
+}
+
+impl MLModel {
+    /// 按层结构创建模型，权重 Xavier 均匀初始化，偏置为 0
+    pub fn initialize(model_type: MLModelType, layers: Vec<usize>, activation_function: ActivationFunction, rng: &mut OptimizerRng) -> Self {
+        let mut weights = Vec::new();
+        let mut biases = Vec::new();
+        for pair in layers.windows(2) {
+            let (fan_in, fan_out) = (pair[0], pair[1]);
+            let limit = (6.0 / (fan_in + fan_out) as f64).sqrt();
+            weights.extend((0..fan_in * fan_out).map(|_| rng.gen_range_f64(-limit, limit)));
+            biases.resize(biases.len() + fan_out, 0.0);
+        }
+        Self {
+            model_type,
+            weights,
+            biases,
+            layers,
+            activation_function,
+            performance_metrics: ModelPerformanceMetrics::default(),
+        }
+    }
+    
+    /// 校验层结构与参数数量是否一致（加载外部模型时防止越界）
+    pub fn validate(&self) -> anchor_lang::Result<()> {
+        require!(
+            self.layers.len() >= 2 && self.layers.iter().all(|&size| size > 0),
+            AlgorithmError::InvalidInputData {
+                reason: "Model must have at least an input and an output layer".to_string(),
+            }
+        );
+        let (weight_count, bias_count) = self.layers.windows(2)
+            .fold((0usize, 0usize), |(w, b), pair| (w + pair[0] * pair[1], b + pair[1]));
+        require!(
+            self.weights.len() == weight_count && self.biases.len() == bias_count,
+            AlgorithmError::InvalidInputData {
+                reason: format!("Model expects {} weights and {} biases", weight_count, bias_count),
+            }
+        );
+        require!(
+            self.weights.iter().chain(self.biases.iter()).all(|v| v.is_finite()),
+            AlgorithmError::InvalidInputData {
+                reason: "Model parameters must be finite".to_string(),
+            }
+        );
+        Ok(())
+    }
+    
+    /// 前向传播：隐藏层应用激活函数，输出层线性
+    pub fn forward(&self, input: &[f64]) -> anchor_lang::Result<Vec<f64>> {
+        require!(
+            self.layers.first() == Some(&input.len()),
+            AlgorithmError::InvalidInputData {
+                reason: format!("Model expects {} inputs, got {}", self.layers.first().copied().unwrap_or(0), input.len()),
+            }
+        );
+        Ok(self.forward_layers(input, None).activations.pop().unwrap_or_default())
+    }
+    
+    /// 逐层前向传播，记录各层预激活值与输出；训练时按 dropout 比例随机丢弃隐藏层神经元
+    fn forward_layers(&self, input: &[f64], mut dropout: Option<(f64, &mut OptimizerRng)>) -> ForwardPass {
+        let last_layer = self.layers.len() - 2;
+        let mut pass = ForwardPass {
+            activations: vec![input.to_vec()],
+            pre_activations: Vec::with_capacity(self.layers.len() - 1),
+            dropout_scales: Vec::with_capacity(self.layers.len() - 1),
+        };
+        let (mut weight_offset, mut bias_offset) = (0, 0);
+        for (l, pair) in self.layers.windows(2).enumerate() {
+            let (fan_in, fan_out) = (pair[0], pair[1]);
+            let previous = &pass.activations[l];
+            let z: Vec<f64> = (0..fan_out).map(|i| {
+                let row = &self.weights[weight_offset + i * fan_in..weight_offset + (i + 1) * fan_in];
+                self.biases[bias_offset + i] + row.iter().zip(previous).map(|(w, a)| w * a).sum::<f64>()
+            }).collect();
+            let scales: Vec<f64> = match dropout.as_mut() {
+                Some((rate, rng)) if l < last_layer => (0..fan_out)
+                    .map(|_| if rng.gen_bool(*rate) { 0.0 } else { 1.0 / (1.0 - *rate) }) // inverted dropout
+                    .collect(),
+                _ => vec![1.0; fan_out],
+            };
+            let next = z.iter().zip(&scales).map(|(&zi, &scale)| {
+                if l == last_layer { zi } else { self.activation_function.apply(zi) * scale }
+            }).collect();
+            pass.activations.push(next);
+            pass.pre_activations.push(z);
+            pass.dropout_scales.push(scales);
+            weight_offset += fan_in * fan_out;
+            bias_offset += fan_out;
+        }
+        pass
+    }
+    
+    /// 单样本反向传播，梯度累加到 grad_weights/grad_biases，返回平方误差
+    fn accumulate_gradients(
+        &self,
+        input: &[f64],
+        target: f64,
+        dropout: Option<(f64, &mut OptimizerRng)>,
+        grad_weights: &mut [f64],
+        grad_biases: &mut [f64],
+    ) -> f64 {
+        let pass = self.forward_layers(input, dropout);
+        let output = pass.activations[pass.activations.len() - 1][0];
+        let mut delta = vec![output - target]; // 损失 0.5 * (y_hat - y)^2 对输出的梯度
+        
+        let mut weight_offset = self.weights.len();
+        let mut bias_offset = self.biases.len();
+        for l in (0..self.layers.len() - 1).rev() {
+            let (fan_in, fan_out) = (self.layers[l], self.layers[l + 1]);
+            weight_offset -= fan_in * fan_out;
+            bias_offset -= fan_out;
+            let previous = &pass.activations[l];
+            for (i, d) in delta.iter().enumerate() {
+                grad_biases[bias_offset + i] += d;
+                let row = &mut grad_weights[weight_offset + i * fan_in..weight_offset + (i + 1) * fan_in];
+                for (g, a) in row.iter_mut().zip(previous) {
+                    *g += d * a;
+                }
+            }
+            if l > 0 {
+                delta = (0..fan_in).map(|j| {
+                    let upstream: f64 = delta.iter().enumerate()
+                        .map(|(i, d)| self.weights[weight_offset + i * fan_in + j] * d)
+                        .sum();
+                    upstream * self.activation_function.derivative(pass.pre_activations[l - 1][j]) * pass.dropout_scales[l - 1][j]
+                }).collect();
+            }
         }
+        (output - target).powi(2)
+    }
+    
+    /// 预测单个样本（标准化空间）
+    fn predict_scaled(&self, input: &[f64]) -> f64 {
+        self.forward_layers(input, None).activations.pop().and_then(|output| output.first().copied()).unwrap_or(0.0)
+    }
+}
+
+/// 前向传播中间结果
+struct ForwardPass {
+    /// 各层输出（首项为输入）
+    activations: Vec<Vec<f64>>,
+    /// 各层预激活值
+    pre_activations: Vec<Vec<f64>>,
+    /// 各层 dropout 缩放系数（0 表示丢弃）
+    dropout_scales: Vec<Vec<f64>>,
+}
+
+impl MLModelSnapshot {
+    /// 序列化为 postcard 紧凑二进制
+    pub fn to_bytes(&self) -> anchor_lang::Result<Vec<u8>> {
+        postcard::to_allocvec(self).map_err(|e| AlgorithmError::InvalidOutputData {
+            reason: format!("Failed to serialize ML model: {}", e),
+        }.into())
+    }
+    
+    /// 从 postcard 二进制反序列化并校验
+    pub fn from_bytes(bytes: &[u8]) -> anchor_lang::Result<Self> {
+        let snapshot: Self = postcard::from_bytes(bytes).map_err(|e| AlgorithmError::InvalidInputData {
+            reason: format!("Failed to deserialize ML model: {}", e),
+        })?;
+        require!(
+            snapshot.version == MODEL_FORMAT_VERSION,
+            AlgorithmError::VersionIncompatible {
+                current: snapshot.version.to_string(),
+                required: MODEL_FORMAT_VERSION.to_string(),
+            }
+        );
+        snapshot.model.validate()?;
+        require!(
+            snapshot.scaler.fitted && snapshot.scaler.means.len() == snapshot.model.layers[0],
+            AlgorithmError::InvalidInputData {
+                reason: "Feature scaler does not match model input layer".to_string(),
+            }
+        );
+        Ok(snapshot)
+    }
+    
+    /// 预测执行成本（基点），可脱离优化器用于链上推理
+    pub fn predict_cost_bps(&self, features: &MLFeatures) -> anchor_lang::Result<f64> {
+        let input = self.scaler.transform(&features.neural_input());
+        let output = self.model.forward(&input)?;
+        Ok(self.scaler.inverse_transform_target(output[0]))
     }
 }
 
 impl Optimizer for MLOptimizer {
     fn optimize(&self, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<OptimizationResult> {
-        let start_time = self.get_current_timestamp();
+        self.optimize_with_seed(params, market_data, self.config.seed)
+    }
+    
+    fn optimize_with_seed(&self, params: &ExecutionOptimizerParams, market_data: &MarketData, seed: u64) -> anchor_lang::Result<OptimizationResult> {
+        let mut rng = OptimizerRng::new(seed);
         
         // 提取特征
         let features = self.extract_features(params, market_data)?;
@@ -229,13 +561,13 @@ impl Optimizer for MLOptimizer {
         let normalized_features = self.normalize_features(&features)?;
         
         // 使用模型进行预测
-        let prediction = self.predict(&normalized_features)?;
+        let prediction = self.predict(&features, &normalized_features)?;
         
         // 根据预测结果构建执行计划
-        let execution_plan = self.build_execution_plan_from_prediction(&prediction, params, market_data)?;
+        let execution_plan = self.build_execution_plan_from_prediction(&prediction, params, market_data, &mut rng)?;
         
         // 构建优化结果
-        let result = self.build_optimization_result(&execution_plan, params, market_data, start_time)?;
+        let result = self.build_optimization_result(&execution_plan, params, market_data, seed, prediction.predicted_cost_bps, &mut rng)?;
         
         Ok(result)
     }
@@ -251,13 +583,8 @@ impl MLOptimizer {
         Self {
             config: MLConfig::default(),
             model: None,
-            feature_scaler: FeatureScaler {
-                means: Vec::new(),
-                stds: Vec::new(),
-                fitted: false,
-            },
+            feature_scaler: FeatureScaler::default(),
             stats: MLStats::default(),
-            rng: rand::thread_rng(),
         }
     }
     
@@ -266,13 +593,225 @@ impl MLOptimizer {
         Self {
             config,
             model: None,
-            feature_scaler: FeatureScaler {
-                means: Vec::new(),
-                stds: Vec::new(),
-                fitted: false,
-            },
+            feature_scaler: FeatureScaler::default(),
             stats: MLStats::default(),
-            rng: rand::thread_rng(),
+        }
+    }
+    
+    /// 使用历史成交数据离线训练执行成本预测模型
+    ///
+    /// 训练集/验证集按配置种子确定性划分，特征标准化器仅在训练集上拟合；
+    /// 监控损失连续 `early_stopping_patience` 轮无改善时早停，并回退到最佳权重。
+    pub fn train(&mut self, samples: &[MLTrainingSample]) -> anchor_lang::Result<ModelPerformanceMetrics> {
+        self.validate_training_data(samples)?;
+        let mut rng = OptimizerRng::new(self.config.seed);
+        
+        let inputs: Vec<Vec<f64>> = samples.iter().map(|sample| sample.features.neural_input()).collect();
+        let targets: Vec<f64> = samples.iter().map(|sample| sample.realized_cost_bps).collect();
+        
+        // 确定性打乱后划分验证集
+        let mut order: Vec<usize> = (0..samples.len()).collect();
+        shuffle(&mut order, &mut rng);
+        let (validation_idx, training_idx) = order.split_at(self.validation_count(samples.len()));
+        let select = |idx: &[usize]| -> (Vec<Vec<f64>>, Vec<f64>) {
+            (idx.iter().map(|&i| inputs[i].clone()).collect(), idx.iter().map(|&i| targets[i]).collect())
+        };
+        let (training_inputs, training_targets) = select(training_idx);
+        let (validation_inputs, validation_targets) = select(validation_idx);
+        
+        // 标准化
+        let scaler = FeatureScaler::fit(&training_inputs, &training_targets);
+        let scale_inputs = |rows: &[Vec<f64>]| -> Vec<Vec<f64>> { rows.iter().map(|row| scaler.transform(row)).collect() };
+        let scale_targets = |ys: &[f64]| -> Vec<f64> { ys.iter().map(|&y| scaler.transform_target(y)).collect() };
+        let (train_x, train_y) = (scale_inputs(&training_inputs), scale_targets(&training_targets));
+        let (validation_x, validation_y) = (scale_inputs(&validation_inputs), scale_targets(&validation_targets));
+        
+        // 初始化网络：线性回归模型无隐藏层
+        let mut layers = vec![train_x[0].len()];
+        if self.config.model_type != MLModelType::LinearRegression {
+            layers.extend(self.config.hidden_layers.iter().copied());
+        }
+        layers.push(1);
+        let mut model = MLModel::initialize(
+            self.config.model_type.clone(),
+            layers,
+            self.config.activation_function.clone(),
+            &mut rng,
+        );
+        
+        let mut best_model = model.clone();
+        let mut best_loss = f64::INFINITY;
+        let mut stale_epochs = 0;
+        let mut epochs_trained = 0;
+        let mut batch_order: Vec<usize> = (0..train_x.len()).collect();
+        for epoch in 0..self.config.max_epochs {
+            let learning_rate = if self.config.enable_learning_rate_scheduling {
+                self.config.learning_rate / (1.0 + LEARNING_RATE_DECAY * epoch as f64)
+            } else {
+                self.config.learning_rate
+            };
+            shuffle(&mut batch_order, &mut rng);
+            for batch in batch_order.chunks(self.config.batch_size) {
+                self.train_batch(&mut model, batch, &train_x, &train_y, learning_rate, &mut rng);
+            }
+            epochs_trained = epoch + 1;
+            
+            // 早停：无验证集时监控训练损失
+            let monitored_loss = if validation_x.is_empty() {
+                scaled_mse(&model, &train_x, &train_y)
+            } else {
+                scaled_mse(&model, &validation_x, &validation_y)
+            };
+            if monitored_loss < best_loss - MIN_LOSS_IMPROVEMENT {
+                best_loss = monitored_loss;
+                best_model = model.clone();
+                stale_epochs = 0;
+            } else {
+                stale_epochs += 1;
+                if stale_epochs >= self.config.early_stopping_patience {
+                    break;
+                }
+            }
+        }
+        
+        // 以原始成本单位评估最佳模型
+        let mut model = best_model;
+        let (training_loss, ..) = regression_metrics(&model, &scaler, &train_x, &training_targets);
+        let (evaluation_x, evaluation_targets) = if validation_x.is_empty() {
+            (&train_x, &training_targets)
+        } else {
+            (&validation_x, &validation_targets)
+        };
+        let (validation_loss, mae, r_squared, accuracy) = regression_metrics(&model, &scaler, evaluation_x, evaluation_targets);
+        let metrics = ModelPerformanceMetrics {
+            training_loss,
+            validation_loss,
+            accuracy,
+            mae,
+            rmse: validation_loss.sqrt(),
+            r_squared,
+            epochs_trained,
+        };
+        model.performance_metrics = metrics.clone();
+        
+        self.model = Some(model);
+        self.feature_scaler = scaler;
+        self.stats.model_updates += 1;
+        self.stats.model_accuracy = accuracy;
+        self.stats.avg_prediction_error = mae;
+        Ok(metrics)
+    }
+    
+    /// 导出模型快照（postcard 二进制）
+    pub fn export_model(&self) -> anchor_lang::Result<Vec<u8>> {
+        let model = self.model.as_ref().ok_or_else(|| AlgorithmError::MissingDependency {
+            dependency: "trained ML model".to_string(),
+        })?;
+        MLModelSnapshot {
+            version: MODEL_FORMAT_VERSION,
+            model: model.clone(),
+            scaler: self.feature_scaler.clone(),
+        }.to_bytes()
+    }
+    
+    /// 加载模型快照（来自 export_model 或链上账户数据）
+    pub fn load_model(&mut self, bytes: &[u8]) -> anchor_lang::Result<()> {
+        let snapshot = MLModelSnapshot::from_bytes(bytes)?;
+        self.model = Some(snapshot.model);
+        self.feature_scaler = snapshot.scaler;
+        self.stats.model_updates += 1;
+        Ok(())
+    }
+    
+    /// 当前模型
+    pub fn model(&self) -> Option<&MLModel> {
+        self.model.as_ref()
+    }
+    
+    /// 使用训练好的模型预测执行成本（基点）
+    pub fn predict_cost_bps(&self, features: &MLFeatures) -> anchor_lang::Result<f64> {
+        let model = self.model.as_ref().ok_or_else(|| AlgorithmError::MissingDependency {
+            dependency: "trained ML model".to_string(),
+        })?;
+        let output = model.forward(&self.feature_scaler.transform(&features.neural_input()))?;
+        Ok(self.feature_scaler.inverse_transform_target(output[0]))
+    }
+    
+    /// 校验训练数据与训练配置
+    fn validate_training_data(&self, samples: &[MLTrainingSample]) -> anchor_lang::Result<()> {
+        let config = &self.config;
+        require!(
+            matches!(config.model_type, MLModelType::NeuralNetwork | MLModelType::LinearRegression),
+            AlgorithmError::ConfigurationError {
+                reason: format!("Training is not supported for {:?} models", config.model_type),
+            }
+        );
+        require!(
+            config.learning_rate.is_finite() && config.learning_rate > 0.0
+                && config.batch_size > 0
+                && config.max_epochs > 0
+                && (0.0..1.0).contains(&config.validation_split)
+                && (!config.enable_dropout || (0.0..1.0).contains(&config.dropout_rate))
+                && config.hidden_layers.iter().all(|&size| size > 0),
+            AlgorithmError::ConfigurationError {
+                reason: "Invalid ML training configuration".to_string(),
+            }
+        );
+        require!(
+            samples.len() >= MIN_TRAINING_SAMPLES,
+            AlgorithmError::InvalidInputData {
+                reason: format!("At least {} training samples are required", MIN_TRAINING_SAMPLES),
+            }
+        );
+        require!(
+            samples.iter().all(|sample| {
+                sample.realized_cost_bps.is_finite() && sample.features.neural_input().iter().all(|x| x.is_finite())
+            }),
+            AlgorithmError::InvalidInputData {
+                reason: "Training samples must be finite".to_string(),
+            }
+        );
+        Ok(())
+    }
+    
+    /// 验证集样本数量（启用验证时至少 1 个，且保留至少 1 个训练样本）
+    fn validation_count(&self, sample_count: usize) -> usize {
+        if self.config.validation_split <= 0.0 {
+            return 0;
+        }
+        ((sample_count as f64 * self.config.validation_split).round() as usize)
+            .max(1)
+            .min(sample_count - 1)
+    }
+    
+    /// 小批量梯度下降一步（含 L2 正则化与 dropout）
+    fn train_batch(
+        &self,
+        model: &mut MLModel,
+        batch: &[usize],
+        inputs: &[Vec<f64>],
+        targets: &[f64],
+        learning_rate: f64,
+        rng: &mut OptimizerRng,
+    ) {
+        let mut grad_weights = vec![0.0; model.weights.len()];
+        let mut grad_biases = vec![0.0; model.biases.len()];
+        for &i in batch {
+            let dropout = if self.config.enable_dropout && self.config.dropout_rate > 0.0 {
+                Some((self.config.dropout_rate, &mut *rng))
+            } else {
+                None
+            };
+            model.accumulate_gradients(&inputs[i], targets[i], dropout, &mut grad_weights, &mut grad_biases);
+        }
+        
+        let batch_scale = 1.0 / batch.len() as f64;
+        let l2 = if self.config.enable_regularization { self.config.regularization_coefficient } else { 0.0 };
+        for (weight, grad) in model.weights.iter_mut().zip(&grad_weights) {
+            *weight -= learning_rate * (grad * batch_scale + l2 * *weight);
+        }
+        for (bias, grad) in model.biases.iter_mut().zip(&grad_biases) {
+            *bias -= learning_rate * grad * batch_scale;
         }
     }
     
@@ -299,8 +838,8 @@ impl MLOptimizer {
         // 计算市场深度
         let market_depth = market_data.market_depth.iter().map(|level| level.size as f64).sum::<f64>();
         
-        // 时间特征（小时、分钟等）
-        let current_time = self.get_current_timestamp();
+        // 时间特征（小时、分钟等），取自行情时间戳
+        let current_time = market_data.timestamp.max(0) as u64;
         let time_features = vec![
             (current_time / 3600) as f64 % 24.0, // 小时
             (current_time / 60) as f64 % 60.0,   // 分钟
@@ -323,56 +862,44 @@ impl MLOptimizer {
         })
     }
     
-    /// 标准化特征
+    /// 启发式特征归一化（min-max），训练模型的输入由 FeatureScaler 单独标准化
     fn normalize_features(&self, features: &MLFeatures) -> anchor_lang::Result<MLFeatures> {
-        if !self.feature_scaler.fitted {
-            // 如果标准化器未拟合，使用简单的min-max标准化
-            return Ok(MLFeatures {
-                order_size: features.order_size / 1_000_000.0, // 假设最大订单大小为1M
-                target_execution_time: features.target_execution_time / 86400.0, // 假设最大时间为1天
-                current_price: features.current_price / 1_000_000.0, // 假设最大价格为1M
-                volatility: features.volatility, // 已经是0-1范围
-                liquidity: features.liquidity / 100_000_000.0, // 假设最大流动性为100M
-                volume: features.volume / 10_000_000.0, // 假设最大成交量为10M
-                spread_bps: features.spread_bps / 1000.0, // 假设最大价差为1000bps
-                market_sentiment: (features.market_sentiment + 1.0) / 2.0, // 转换为0-1范围
-                price_trend: (features.price_trend + 1.0) / 2.0, // 转换为0-1范围
-                volume_trend: (features.volume_trend + 1.0) / 2.0, // 转换为0-1范围
-                market_depth: features.market_depth / 50_000_000.0, // 假设最大深度为50M
-                time_features: features.time_features.iter().map(|&x| x / 60.0).collect(), // 标准化时间特征
-            });
-        }
-        
-        // 使用拟合的标准化器
-        let mut normalized_features = features.clone();
-        for (i, mean) in self.feature_scaler.means.iter().enumerate() {
-            let std = self.feature_scaler.stds[i];
-            if std > 0.0 {
-                // 这里需要根据特征索引进行标准化
-                // 简化实现，实际应该根据具体特征进行标准化
-            }
-        }
-        
-        Ok(normalized_features)
+        Ok(MLFeatures {
+            order_size: features.order_size / 1_000_000.0, // 假设最大订单大小为1M
+            target_execution_time: features.target_execution_time / 86400.0, // 假设最大时间为1天
+            current_price: features.current_price / 1_000_000.0, // 假设最大价格为1M
+            volatility: features.volatility, // 已经是0-1范围
+            liquidity: features.liquidity / 100_000_000.0, // 假设最大流动性为100M
+            volume: features.volume / 10_000_000.0, // 假设最大成交量为10M
+            spread_bps: features.spread_bps / 1000.0, // 假设最大价差为1000bps
+            market_sentiment: (features.market_sentiment + 1.0) / 2.0, // 转换为0-1范围
+            price_trend: (features.price_trend + 1.0) / 2.0, // 转换为0-1范围
+            volume_trend: (features.volume_trend + 1.0) / 2.0, // 转换为0-1范围
+            market_depth: features.market_depth / 50_000_000.0, // 假设最大深度为50M
+            time_features: features.time_features.iter().map(|&x| x / 60.0).collect(), // 标准化时间特征
+        })
     }
     
-    /// 使用模型进行预测
-    fn predict(&self, features: &MLFeatures) -> anchor_lang::Result<MLPrediction> {
+    /// 使用模型进行预测（features 为原始特征，normalized_features 为启发式归一化特征）
+    fn predict(&self, features: &MLFeatures, normalized_features: &MLFeatures) -> anchor_lang::Result<MLPrediction> {
         // 如果模型未训练，使用启发式方法
-        if self.model.is_none() {
-            return self.heuristic_prediction(features);
-        }
+        let Some(model) = self.model.as_ref() else {
+            return self.heuristic_prediction(normalized_features);
+        };
         
         // 使用训练好的模型进行预测
-        let model = self.model.as_ref().unwrap();
-        self.neural_network_prediction(model, features)
+        self.neural_network_prediction(model, features, normalized_features)
     }
     
     /// 启发式预测（当模型未训练时使用）
     fn heuristic_prediction(&self, features: &MLFeatures) -> anchor_lang::Result<MLPrediction> {
         // 基于特征计算最优分段数量
         let optimal_segment_count = self.calculate_optimal_segment_count_heuristic(features);
-        
+        self.prediction_with_segment_count(features, optimal_segment_count)
+    }
+    
+    /// 按给定分段数量生成分段分布、执行策略与预测指标
+    fn prediction_with_segment_count(&self, features: &MLFeatures, optimal_segment_count: u32) -> anchor_lang::Result<MLPrediction> {
         // 计算分段大小分布
         let segment_size_distribution = self.calculate_segment_size_distribution(features, optimal_segment_count);
         
@@ -400,19 +927,29 @@ impl MLOptimizer {
                 execution_time_improvement_rate: 0.0,
                 optimization_success_rate: 1.0,
             },
+            predicted_cost_bps: None,
         })
     }
     
     /// 神经网络预测
-    fn neural_network_prediction(&self, model: &MLModel, features: &MLFeatures) -> anchor_lang::Result<MLPrediction> {
+    fn neural_network_prediction(&self, model: &MLModel, features: &MLFeatures, normalized_features: &MLFeatures) -> anchor_lang::Result<MLPrediction> {
         // 将特征转换为神经网络输入
-        let input = self.features_to_neural_input(features);
+        let input = self.feature_scaler.transform(&features.neural_input());
         
         // 前向传播
         let output = self.forward_propagation(model, &input)?;
         
+        // 成本回归模型：按模型预测的执行成本确定分段数量
+        if output.len() == 1 {
+            let predicted_cost_bps = self.feature_scaler.inverse_transform_target(output[0]);
+            let segment_count = self.calculate_segment_count_for_cost(normalized_features, predicted_cost_bps, features.spread_bps);
+            let mut prediction = self.prediction_with_segment_count(normalized_features, segment_count)?;
+            prediction.predicted_cost_bps = Some(predicted_cost_bps);
+            return Ok(prediction);
+        }
+        
         // 解析输出
-        let prediction = self.parse_neural_output(&output, features)?;
+        let prediction = self.parse_neural_output(&output, normalized_features)?;
         
         Ok(prediction)
     }
@@ -426,6 +963,19 @@ impl MLOptimizer {
         (base_segments + time_based_segments) / 2 * volatility_factor
     }
     
+    /// 按预测成本调整启发式分段数量
+    ///
+    /// 预测成本与价差相当时冲击成本可忽略，沿用启发式分段；预测成本相对价差越高，拆分越细（倍数限制在 0.5~4 之间）。
+    fn calculate_segment_count_for_cost(&self, features: &MLFeatures, predicted_cost_bps: f64, spread_bps: f64) -> u32 {
+        let base_segments = self.calculate_optimal_segment_count_heuristic(features).max(1);
+        let cost_ratio = if predicted_cost_bps.is_finite() {
+            (predicted_cost_bps / spread_bps.max(1.0)).clamp(0.5, 4.0)
+        } else {
+            1.0 // 预测值异常时不调整
+        };
+        ((base_segments as f64 * cost_ratio).round() as u32).max(1)
+    }
+    
     /// 计算分段大小分布
     fn calculate_segment_size_distribution(&self, features: &MLFeatures, segment_count: u32) -> Vec<u64> {
         let mut distribution = Vec::with_capacity(segment_count as usize);
@@ -492,59 +1042,9 @@ impl MLOptimizer {
         (predicted_cost, predicted_market_impact, predicted_execution_time)
     }
     
-    /// 特征转换为神经网络输入
-    fn features_to_neural_input(&self, features: &MLFeatures) -> Vec<f64> {
-        vec![
-            features.order_size,
-            features.target_execution_time,
-            features.current_price,
-            features.volatility,
-            features.liquidity,
-            features.volume,
-            features.spread_bps,
-            features.market_sentiment,
-            features.price_trend,
-            features.volume_trend,
-            features.market_depth,
-        ]
-    }
-    
     /// 前向传播
     fn forward_propagation(&self, model: &MLModel, input: &[f64]) -> anchor_lang::Result<Vec<f64>> {
-        let mut current_layer = input.to_vec();
-        
-        for layer_idx in 0..model.layers.len() - 1 {
-            let input_size = if layer_idx == 0 { input.len() } else { model.layers[layer_idx - 1] };
-            let output_size = model.layers[layer_idx];
-            
-            let mut next_layer = vec![0.0; output_size];
-            
-            // 矩阵乘法 + 偏置
-            for i in 0..output_size {
-                let mut sum = model.biases[layer_idx * output_size + i];
-                for j in 0..input_size {
-                    let weight_idx = layer_idx * input_size * output_size + i * input_size + j;
-                    if weight_idx < model.weights.len() {
-                        sum += current_layer[j] * model.weights[weight_idx];
-                    }
-                }
-                next_layer[i] = self.apply_activation(sum, &model.activation_function);
-            }
-            
-            current_layer = next_layer;
-        }
-        
-        Ok(current_layer)
-    }
-    
-    /// 应用激活函数
-    fn apply_activation(&self, x: f64, activation: &ActivationFunction) -> f64 {
-        match activation {
-            ActivationFunction::ReLU => x.max(0.0),
-            ActivationFunction::Sigmoid => 1.0 / (1.0 + (-x).exp()),
-            ActivationFunction::Tanh => x.tanh(),
-            ActivationFunction::Linear => x,
-        }
+        model.forward(input)
     }
     
     /// 解析神经网络输出
@@ -578,23 +1078,31 @@ impl MLOptimizer {
                 execution_time_improvement_rate: 0.0,
                 optimization_success_rate: 1.0,
             },
+            predicted_cost_bps: None,
         })
     }
     
     /// 根据预测结果构建执行计划
-    fn build_execution_plan_from_prediction(&self, prediction: &MLPrediction, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<ExecutionPlan> {
+    fn build_execution_plan_from_prediction(&self, prediction: &MLPrediction, params: &ExecutionOptimizerParams, market_data: &MarketData, rng: &mut OptimizerRng) -> anchor_lang::Result<ExecutionPlan> {
         let mut segments = Vec::with_capacity(prediction.optimal_segment_count as usize);
         let mut remaining_amount = params.order_size;
         let mut remaining_time = params.target_execution_time;
         
+        // 分布按权重解释；权重总和为 0 时均匀分配
+        let uniform_ratio = 1.0 / prediction.optimal_segment_count as f64;
+        let weight_ratio = |distribution: &[u64], i: usize| {
+            let total: u64 = distribution.iter().sum();
+            distribution.get(i).filter(|_| total > 0).map_or(uniform_ratio, |&weight| weight as f64 / total as f64)
+        };
+        
         for i in 0..prediction.optimal_segment_count {
-            let amount_ratio = prediction.segment_size_distribution.get(i).unwrap_or(&(1.0 / prediction.optimal_segment_count as f64));
-            let time_ratio = prediction.segment_time_distribution.get(i).unwrap_or(&(1.0 / prediction.optimal_segment_count as f64));
+            let amount_ratio = weight_ratio(&prediction.segment_size_distribution, i as usize);
+            let time_ratio = weight_ratio(&prediction.segment_time_distribution, i as usize);
             
             let amount = (params.order_size as f64 * amount_ratio) as u64;
             let execution_time = (params.target_execution_time as f64 * time_ratio) as u64;
             
-            let target_price = self.calculate_target_price(market_data, amount);
+            let target_price = self.calculate_target_price(market_data, amount, rng);
             let expected_cost = self.calculate_expected_cost(amount, target_price, market_data);
             let expected_market_impact = self.calculate_market_impact(amount, market_data);
             
@@ -618,7 +1126,7 @@ impl MLOptimizer {
         let total_market_impact: u32 = segments.iter().map(|s| s.expected_market_impact_bps).sum();
         
         Ok(ExecutionPlan {
-            id: format!("ml_plan_{}", self.rng.gen::<u32>()),
+            id: format!("ml_plan_{}", rng.next_u32()),
             segments,
             total_execution_amount: total_amount,
             total_execution_time: total_time,
@@ -628,12 +1136,12 @@ impl MLOptimizer {
     }
     
     /// 计算目标价格
-    fn calculate_target_price(&self, market_data: &MarketData, amount: u64) -> u64 {
+    fn calculate_target_price(&self, market_data: &MarketData, amount: u64, rng: &mut OptimizerRng) -> u64 {
         let base_price = market_data.current_price;
         let impact_factor = (amount as f64 / market_data.liquidity as f64) * 0.1;
         let price_adjustment = (base_price as f64 * impact_factor) as u64;
         
-        if self.rng.gen_bool(0.5) {
+        if rng.gen_bool(0.5) {
             base_price.saturating_add(price_adjustment)
         } else {
             base_price.saturating_sub(price_adjustment)
@@ -656,11 +1164,9 @@ impl MLOptimizer {
     }
     
     /// 构建优化结果
-    fn build_optimization_result(&self, execution_plan: &ExecutionPlan, params: &ExecutionOptimizerParams, market_data: &MarketData, start_time: u64) -> anchor_lang::Result<OptimizationResult> {
-        let optimization_time = self.get_current_timestamp() - start_time;
-        
+    fn build_optimization_result(&self, execution_plan: &ExecutionPlan, params: &ExecutionOptimizerParams, market_data: &MarketData, seed: u64, predicted_cost_bps: Option<f64>, rng: &mut OptimizerRng) -> anchor_lang::Result<OptimizationResult> {
         let metrics = OptimizationMetrics {
-            optimization_time_ms: optimization_time * 1000,
+            optimization_time_ms: 0, // 不读取系统时钟，耗时由调用方在链下统计
             iteration_count: 1, // ML优化器通常只需要一次前向传播
             convergence_count: 1,
             cost_improvement_rate: 1.0 - (execution_plan.total_cost as f64 / params.order_size as f64),
@@ -670,7 +1176,7 @@ impl MLOptimizer {
         };
         
         Ok(OptimizationResult {
-            id: format!("ml_result_{}", self.rng.gen::<u32>()),
+            id: format!("ml_result_{}", rng.next_u32()),
             optimal_execution_plan: execution_plan.clone(),
             expected_cost: execution_plan.total_cost,
             expected_market_impact_bps: execution_plan.total_market_impact_bps,
@@ -678,16 +1184,48 @@ impl MLOptimizer {
             optimization_score: 0.8, // ML优化器的默认分数
             risk_score: 0.2, // ML优化器的默认风险分数
             metrics,
+            seed,
+            predicted_cost_bps,
         })
     }
-    
-    /// 获取当前时间戳
-    fn get_current_timestamp(&self) -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
+}
+
+/// Fisher-Yates 洗牌（种子驱动，可复现）
+fn shuffle(order: &mut [usize], rng: &mut OptimizerRng) {
+    for i in (1..order.len()).rev() {
+        order.swap(i, rng.gen_index(i + 1));
+    }
+}
+
+/// 标准化空间的均方误差（用于早停监控）
+fn scaled_mse(model: &MLModel, inputs: &[Vec<f64>], targets: &[f64]) -> f64 {
+    if inputs.is_empty() {
+        return 0.0;
+    }
+    inputs.iter().zip(targets)
+        .map(|(x, y)| (model.predict_scaled(x) - y).powi(2))
+        .sum::<f64>() / inputs.len() as f64
+}
+
+/// 原始成本单位的回归指标：(均方误差, 平均绝对误差, R平方, 准确率)
+fn regression_metrics(model: &MLModel, scaler: &FeatureScaler, inputs: &[Vec<f64>], targets: &[f64]) -> (f64, f64, f64, f64) {
+    if inputs.is_empty() {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+    let count = inputs.len() as f64;
+    let mean = targets.iter().sum::<f64>() / count;
+    let (mut squared, mut absolute, mut total, mut accurate) = (0.0, 0.0, 0.0, 0usize);
+    for (x, &y) in inputs.iter().zip(targets) {
+        let error = scaler.inverse_transform_target(model.predict_scaled(x)) - y;
+        squared += error * error;
+        absolute += error.abs();
+        total += (y - mean).powi(2);
+        if error.abs() <= ACCURACY_TOLERANCE * y.abs().max(1.0) {
+            accurate += 1; // 相对误差在容差内（成本接近 0 时按 1bps 计）
+        }
     }
+    let r_squared = if total > 0.0 { 1.0 - squared / total } else if squared == 0.0 { 1.0 } else { 0.0 };
+    (squared / count, absolute / count, r_squared, accurate as f64 / count)
 }
 
 #[cfg(test)]
@@ -742,6 +1280,7 @@ mod tests {
             volume: 1_000_000,
             spread_bps: 20,
             market_sentiment: crate::algorithms::execution_optimizer::MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
         };
         
         let features = optimizer.extract_features(&params, &market_data).unwrap();
@@ -754,7 +1293,7 @@ mod tests {
         assert_eq!(features.volume, 1_000_000.0);
         assert_eq!(features.spread_bps, 20.0);
         assert_eq!(features.market_sentiment, 0.0); // Neutral
-        assert!(!features.time_features.is_empty());
+        assert_eq!(features.time_features, vec![22.0, 13.0, 20.0]); // 1_700_000_000 = 22:13:20 UTC
     }
     
     #[test]
@@ -798,6 +1337,7 @@ mod tests {
             volume: 1_000_000,
             spread_bps: 20,
             market_sentiment: crate::algorithms::execution_optimizer::MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
         };
         
         let result = optimizer.optimize(&params, &market_data).unwrap();
@@ -806,6 +1346,243 @@ mod tests {
         assert!(!result.optimal_execution_plan.segments.is_empty());
         assert!(result.expected_cost > 0);
         assert!(result.optimization_score > 0.0);
-        assert!(result.metrics.optimization_time_ms > 0);
+        assert_eq!(result.metrics.optimization_time_ms, 0); // 优化过程不读取系统时钟
     }
-} 
\ No newline at end of file
+    
+    fn sample_inputs() -> (ExecutionOptimizerParams, MarketData) {
+        let params = ExecutionOptimizerParams {
+            order_size: 100_000,
+            target_execution_time: 1800,
+            max_slippage_tolerance_bps: 100,
+            enable_cost_optimization: true,
+            enable_market_impact_optimization: true,
+            enable_timing_optimization: true,
+            optimization_strategy: crate::algorithms::execution_optimizer::OptimizationStrategy::MachineLearning,
+            risk_params: crate::algorithms::execution_optimizer::ExecutionOptimizerRiskParams {
+                max_market_impact_bps: 200,
+                max_execution_cost_bps: 100,
+                max_execution_time: 3600,
+                enable_emergency_stop: true,
+                emergency_stop_threshold_bps: 500,
+                max_volatility_tolerance_bps: 8000,
+                min_liquidity_requirement: 1000,
+            },
+            monitoring_params: crate::algorithms::execution_optimizer::ExecutionOptimizerMonitoringParams {
+                enable_monitoring: true,
+                metrics_interval: 30,
+                enable_detailed_logging: true,
+                enable_performance_warnings: true,
+                enable_optimization_analysis: true,
+            },
+        };
+        let market_data = MarketData {
+            current_price: 1_000_000,
+            volatility: 0.05,
+            liquidity: 10_000_000,
+            market_depth: vec![
+                crate::algorithms::execution_optimizer::PriceLevel { price: 999_000, size: 1000, cumulative_size: 1000 },
+                crate::algorithms::execution_optimizer::PriceLevel { price: 1_001_000, size: 1000, cumulative_size: 1000 },
+            ],
+            volume: 1_000_000,
+            spread_bps: 20,
+            market_sentiment: crate::algorithms::execution_optimizer::MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
+        };
+        (params, market_data)
+    }
+    
+    #[test]
+    fn test_seeded_optimization_is_reproducible() {
+        let optimizer = MLOptimizer::new();
+        let (params, market_data) = sample_inputs();
+        
+        let first = optimizer.optimize_with_seed(&params, &market_data, 42).unwrap();
+        let second = optimizer.optimize_with_seed(&params, &market_data, 42).unwrap();
+        assert_eq!(first.seed, 42);
+        assert_eq!(first.id, second.id);
+        assert_eq!(first, second); // 同一种子逐位复现（不依赖系统时钟）
+        
+        let other = optimizer.optimize_with_seed(&params, &market_data, 43).unwrap();
+        assert_ne!(first.optimal_execution_plan.id, other.optimal_execution_plan.id);
+        assert_eq!(optimizer.optimize(&params, &market_data).unwrap().seed, 0); // 默认使用配置种子
+    }
+    
+    fn training_features(order_size: f64, volatility: f64, spread_bps: f64) -> MLFeatures {
+        MLFeatures {
+            order_size,
+            target_execution_time: 3600.0,
+            current_price: 1_000_000.0,
+            volatility,
+            liquidity: 10_000_000.0,
+            volume: 1_000_000.0,
+            spread_bps,
+            market_sentiment: 0.0,
+            price_trend: 0.0,
+            volume_trend: 0.0,
+            market_depth: 2_000.0,
+            time_features: vec![0.0; 3],
+        }
+    }
+    
+    /// 合成数据集：成本 = 2 + 0.5 × 订单规模(千) + 100 × 波动率 + 价差
+    fn training_dataset(count: usize) -> Vec<MLTrainingSample> {
+        let mut rng = OptimizerRng::new(11);
+        (0..count).map(|_| {
+            let order_size = rng.gen_range_f64(1_000.0, 100_000.0);
+            let volatility = rng.gen_range_f64(0.01, 0.1);
+            let spread_bps = rng.gen_range_f64(5.0, 30.0);
+            MLTrainingSample {
+                features: training_features(order_size, volatility, spread_bps),
+                realized_cost_bps: 2.0 + 0.5 * order_size / 1_000.0 + 100.0 * volatility + spread_bps,
+            }
+        }).collect()
+    }
+    
+    fn training_config() -> MLConfig {
+        MLConfig {
+            learning_rate: 0.01,
+            batch_size: 16,
+            max_epochs: 300,
+            early_stopping_patience: 20,
+            hidden_layers: vec![8],
+            enable_dropout: false,
+            regularization_coefficient: 0.0001,
+            seed: 5,
+            ..MLConfig::default()
+        }
+    }
+    
+    #[test]
+    fn test_train_cost_model() {
+        let mut optimizer = MLOptimizer::with_config(training_config());
+        let metrics = optimizer.train(&training_dataset(200)).unwrap();
+        
+        assert!(metrics.r_squared > 0.95, "r_squared = {}", metrics.r_squared);
+        assert!(metrics.epochs_trained > 0 && metrics.epochs_trained <= 300);
+        assert!(metrics.validation_loss.is_finite());
+        assert_eq!(optimizer.model().unwrap().layers, vec![11, 8, 1]);
+        assert!(optimizer.feature_scaler.fitted);
+        
+        let predicted = optimizer.predict_cost_bps(&training_features(50_000.0, 0.05, 10.0)).unwrap();
+        assert!((predicted - 42.0).abs() < 4.0, "predicted = {}", predicted); // 真实成本 42bps
+        
+        // 同一种子逐位复现训练结果
+        let mut again = MLOptimizer::with_config(training_config());
+        again.train(&training_dataset(200)).unwrap();
+        assert_eq!(again.model(), optimizer.model());
+    }
+    
+    #[test]
+    fn test_early_stopping_and_linear_model() {
+        let mut optimizer = MLOptimizer::with_config(MLConfig {
+            model_type: MLModelType::LinearRegression,
+            max_epochs: 10_000,
+            early_stopping_patience: 3,
+            enable_dropout: true,
+            ..training_config()
+        });
+        let metrics = optimizer.train(&training_dataset(100)).unwrap();
+        assert_eq!(optimizer.model().unwrap().layers, vec![11, 1]);
+        assert!(metrics.epochs_trained < 10_000); // 验证损失不再改善时提前停止
+        assert!(metrics.r_squared > 0.9, "r_squared = {}", metrics.r_squared);
+    }
+    
+    #[test]
+    fn test_training_rejects_invalid_input() {
+        let mut optimizer = MLOptimizer::with_config(training_config());
+        assert!(optimizer.train(&training_dataset(1)).is_err()); // 样本不足
+        
+        let mut dataset = training_dataset(10);
+        dataset[3].realized_cost_bps = f64::NAN;
+        assert!(optimizer.train(&dataset).is_err()); // 非有限值
+        
+        let mut optimizer = MLOptimizer::with_config(MLConfig {
+            model_type: MLModelType::RandomForest,
+            ..training_config()
+        });
+        assert!(optimizer.train(&training_dataset(10)).is_err()); // 不支持的模型类型
+        assert!(optimizer.export_model().is_err()); // 未训练模型不可导出
+    }
+    
+    #[test]
+    fn test_model_serialization_roundtrip() {
+        let mut optimizer = MLOptimizer::with_config(MLConfig { max_epochs: 50, ..training_config() });
+        optimizer.train(&training_dataset(50)).unwrap();
+        let bytes = optimizer.export_model().unwrap();
+        
+        let mut loaded = MLOptimizer::new();
+        loaded.load_model(&bytes).unwrap();
+        assert_eq!(loaded.model(), optimizer.model());
+        let features = training_features(20_000.0, 0.03, 12.0);
+        assert_eq!(
+            loaded.predict_cost_bps(&features).unwrap().to_bits(),
+            optimizer.predict_cost_bps(&features).unwrap().to_bits()
+        );
+        
+        // 快照可脱离优化器直接推理（链上账户数据）
+        let snapshot = MLModelSnapshot::from_bytes(&bytes).unwrap();
+        assert_eq!(snapshot.predict_cost_bps(&features).unwrap(), optimizer.predict_cost_bps(&features).unwrap());
+        
+        assert!(loaded.load_model(&bytes[..bytes.len() / 2]).is_err()); // 截断数据
+        let mut wrong_version = snapshot.clone();
+        wrong_version.version = MODEL_FORMAT_VERSION + 1;
+        assert!(MLModelSnapshot::from_bytes(&wrong_version.to_bytes().unwrap()).is_err());
+        let mut corrupt = snapshot;
+        corrupt.model.weights.pop();
+        assert!(MLModelSnapshot::from_bytes(&corrupt.to_bytes().unwrap()).is_err()); // 参数数量与层结构不符
+    }
+    
+    #[test]
+    fn test_loaded_cost_model_drives_segmentation() {
+        let (mut params, market_data) = sample_inputs();
+        params.target_execution_time = 3600; // 与训练集分布一致
+        let heuristic = MLOptimizer::new().optimize_with_seed(&params, &market_data, 7).unwrap();
+        assert_eq!(heuristic.predicted_cost_bps, None);
+        
+        let mut trained = MLOptimizer::with_config(training_config());
+        trained.train(&training_dataset(200)).unwrap();
+        let mut loaded = MLOptimizer::new();
+        loaded.load_model(&trained.export_model().unwrap()).unwrap();
+        let result = loaded.optimize_with_seed(&params, &market_data, 7).unwrap();
+        
+        // 预测成本远高于 20bps 价差，分段更细
+        let predicted = result.predicted_cost_bps.unwrap();
+        assert!(predicted > 40.0, "predicted = {}", predicted);
+        assert!(
+            result.optimal_execution_plan.segments.len() > heuristic.optimal_execution_plan.segments.len(),
+            "segments {} vs {}",
+            result.optimal_execution_plan.segments.len(),
+            heuristic.optimal_execution_plan.segments.len()
+        );
+        assert_eq!(loaded.optimize_with_seed(&params, &market_data, 7).unwrap(), result); // 加载模型后同一种子仍可复现
+    }
+    
+    /// 黄金向量：固定种子与行情下的执行计划逐字段固定（未加载模型，启发式分段）
+    #[test]
+    fn test_seeded_optimization_golden_vector() {
+        use crate::algorithms::execution_optimizer::ExecutionStrategyType::Iceberg;
+        let optimizer = MLOptimizer::new();
+        let (params, market_data) = sample_inputs();
+        let result = optimizer.optimize_with_seed(&params, &market_data, 42).unwrap();
+        
+        assert_eq!(result.seed, 42);
+        assert_eq!(result.id, "ml_result_3650758467");
+        assert_eq!(result.optimal_execution_plan.id, "ml_plan_3089192069");
+        // (数量, 执行时间, 目标价格, 预期成本, 市场冲击bps, 执行策略)
+        let segments: Vec<_> = result.optimal_execution_plan.segments.iter()
+            .map(|s| (s.index, s.amount, s.execution_time, s.target_price, s.expected_cost, s.expected_market_impact_bps, s.execution_strategy.clone()))
+            .collect();
+        assert_eq!(segments, vec![
+            (0, 16666, 300, 1000166, 16701, 16, Iceberg),
+            (1, 16666, 300, 1000166, 16701, 16, Iceberg),
+            (2, 16666, 300, 999834, 16696, 16, Iceberg),
+            (3, 16666, 300, 999834, 16696, 16, Iceberg),
+            (4, 16666, 300, 999834, 16696, 16, Iceberg),
+            (5, 16666, 300, 999834, 16696, 16, Iceberg),
+        ]);
+        assert_eq!((result.expected_cost, result.expected_market_impact_bps, result.expected_execution_time), (100186, 96, 1800));
+        assert_eq!(result.predicted_cost_bps, None);
+        
+        assert_eq!(optimizer.optimize_with_seed(&params, &market_data, 42).unwrap(), result); // 再次运行输出一致
+    }
+}
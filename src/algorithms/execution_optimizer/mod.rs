@@ -23,7 +23,6 @@ use crate::core::types::AlgoParams;
 // use crate::errors::algorithm_error::AlgorithmError; // 暂时注释掉
 // use crate::core::constants::*; // 暂时注释掉
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
 use crate::algorithms::traits::AlgorithmError;
 
 /// 最大再平衡间隔（秒）
@@ -34,6 +33,7 @@ const MAX_SLIPPAGE_BPS: u64 = 1000; // 10%
 pub mod types;
 pub mod genetic;
 pub mod ml;
+pub mod rng;
 
 use genetic::GeneticOptimizer;
 use ml::MLOptimizer;
@@ -131,6 +131,10 @@ pub struct OptimizationResult {
     pub risk_score: f64,
     /// 优化指标
     pub metrics: OptimizationMetrics,
+    /// 随机种子（以同一种子重新优化可逐位复现执行计划）
+    pub seed: u64,
+    /// 成本模型预测的执行成本（基点，未加载模型时为空）
+    pub predicted_cost_bps: Option<f64>,
 }
 
 /// 执行计划
@@ -222,6 +226,8 @@ pub struct MarketData {
     pub spread_bps: u32,
     /// 市场情绪
     pub market_sentiment: MarketSentiment,
+    /// 行情时间戳（Unix 秒，链上取 Clock；优化器不读取系统时钟）
+    pub timestamp: i64,
 }
 
 /// 价格层级
@@ -278,7 +284,10 @@ pub struct ExecutionOptimizerConfig {
 
 /// 优化器trait
 pub trait Optimizer: Send + Sync {
+    /// 使用配置中的默认种子优化
     fn optimize(&self, params: &ExecutionOptimizerParams, market_data: &MarketData) -> anchor_lang::Result<OptimizationResult>;
+    /// 使用调用方提供的种子优化（链上可传入 slot hash 派生的种子）
+    fn optimize_with_seed(&self, params: &ExecutionOptimizerParams, market_data: &MarketData, seed: u64) -> anchor_lang::Result<OptimizationResult>;
     fn name(&self) -> &'static str;
 }
 
@@ -336,8 +345,8 @@ impl Algorithm for ExecutionOptimizerAlgorithm {
         // 验证参数
         self.validate_optimizer_params(&optimizer_params)?;
         
-        // 获取市场数据
-        let market_data = self.get_market_data(&optimizer_params)?;
+        // 获取市场数据（以链上时钟为行情时间）
+        let market_data = self.get_market_data(&optimizer_params, Clock::get()?.unix_timestamp)?;
         
         // 执行优化
         let optimization_result = self.optimize_execution(&optimizer_params, &market_data)?;
@@ -461,8 +470,8 @@ impl ExecutionOptimizerAlgorithm {
         Ok(())
     }
     
-    /// 获取市场数据
-    fn get_market_data(&self, params: &ExecutionOptimizerParams) -> anchor_lang::Result<MarketData> {
+    /// 获取市场数据（timestamp 为行情时间，由调用方传入）
+    fn get_market_data(&self, params: &ExecutionOptimizerParams, timestamp: i64) -> anchor_lang::Result<MarketData> {
         // 实际实现应调用Oracle和DEX接口获取实时市场数据
         Ok(MarketData {
             current_price: 1_000_000, // 模拟价格
@@ -477,6 +486,7 @@ impl ExecutionOptimizerAlgorithm {
             volume: 1_000_000,
             spread_bps: 20,
             market_sentiment: MarketSentiment::Neutral,
+            timestamp,
         })
     }
     
@@ -488,21 +498,15 @@ impl ExecutionOptimizerAlgorithm {
         // 选择优化器
         let optimizer = self.select_optimizer(&params.optimization_strategy)?;
         
-        // 执行优化
-        let start_time = self.get_current_timestamp();
+        // 执行优化（不读取系统时钟，同一输入与种子结果可复现）
         let result = optimizer.optimize(params, market_data)?;
-        let optimization_time = self.get_current_timestamp() - start_time;
-        
-        // 更新优化指标
-        let mut updated_result = result.clone();
-        updated_result.metrics.optimization_time_ms = optimization_time * 1000;
         
         // 记录性能指标
         if params.monitoring_params.enable_monitoring {
-            self.record_optimization_metrics(&updated_result)?;
+            self.record_optimization_metrics(&result)?;
         }
         
-        Ok(updated_result)
+        Ok(result)
     }
     
     /// 选择优化器
@@ -580,14 +584,6 @@ impl ExecutionOptimizerAlgorithm {
         
         Ok(())
     }
-    
-    /// 获取当前时间戳
-    fn get_current_timestamp(&self) -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
-    }
 }
 
 /// Anchor 自动注册宏
@@ -679,7 +675,7 @@ mod tests {
             },
         };
         
-        let market_data = algo.get_market_data(&params).unwrap();
+        let market_data = algo.get_market_data(&params, 1_700_000_000).unwrap();
         
         assert!(market_data.current_price > 0);
         assert!(market_data.volatility > 0.0);
@@ -730,6 +726,7 @@ mod tests {
             volume: 1_000_000,
             spread_bps: 20,
             market_sentiment: MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
         };
         
         assert!(algo.check_risk_limits(&params, &market_data).is_ok());
@@ -746,6 +743,7 @@ mod tests {
             volume: 1_000_000,
             spread_bps: 20,
             market_sentiment: MarketSentiment::Neutral,
+            timestamp: 1_700_000_000,
         };
         
         assert!(algo.check_risk_limits(&params, &high_risk_market_data).is_err());
@@ -0,0 +1,165 @@
+/*!
+ * 可复现随机数模块
+ *
+ * 为遗传算法与机器学习优化器提供由调用方种子驱动的确定性随机数流：
+ * - xoshiro256** 生成器，SplitMix64 展开种子，无外部依赖、可在链上运行
+ * - 同一种子产生逐位一致的随机序列，便于审计时重新生成执行计划
+ * - 支持由 slot hash 派生种子，供链上调用使用
+ */
+
+/// SplitMix64 增量常数
+const SPLITMIX64_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// 优化器随机数生成器（xoshiro256**）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptimizerRng {
+    /// 生成器内部状态
+    state: [u64; 4],
+}
+
+impl OptimizerRng {
+    /// 使用调用方提供的种子创建生成器
+    pub fn new(seed: u64) -> Self {
+        let mut splitmix = seed;
+        let mut state = [0u64; 4];
+        for word in state.iter_mut() {
+            *word = Self::splitmix64(&mut splitmix); // SplitMix64 展开种子，保证状态非全零
+        }
+        Self { state }
+    }
+
+    /// 使用 slot hash 创建生成器
+    pub fn from_slot_hash(slot_hash: &[u8; 32]) -> Self {
+        Self::new(Self::seed_from_slot_hash(slot_hash))
+    }
+
+    /// 由 slot hash 派生 64 位种子（记录到优化结果中即可复现）
+    pub fn seed_from_slot_hash(slot_hash: &[u8; 32]) -> u64 {
+        let mut mixer = 0u64;
+        let mut seed = 0u64;
+        for chunk in slot_hash.chunks_exact(8) {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(chunk);
+            mixer ^= u64::from_le_bytes(word);
+            seed = Self::splitmix64(&mut mixer); // 逐字混合，任一字节变化都会影响种子
+        }
+        seed
+    }
+
+    /// 生成下一个 64 位随机数
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    /// 生成下一个 32 位随机数
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32 // 取高位，质量更好
+    }
+
+    /// 生成 [0, 1) 区间内的浮点数
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64) // 53 位尾数精度
+    }
+
+    /// 生成 [low, high] 闭区间内的整数（low >= high 时返回 low）
+    pub fn gen_range_u64(&mut self, low: u64, high: u64) -> u64 {
+        if low >= high {
+            return low;
+        }
+        let span = high - low;
+        if span == u64::MAX {
+            return self.next_u64();
+        }
+        let range = span + 1;
+        let limit = u64::MAX - u64::MAX % range; // 拒绝采样，消除取模偏差
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return low + value % range;
+            }
+        }
+    }
+
+    /// 生成 [0, len) 区间内的下标（len 为 0 时返回 0）
+    pub fn gen_index(&mut self, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        self.gen_range_u64(0, len as u64 - 1) as usize
+    }
+
+    /// 生成 [low, high) 区间内的浮点数（high <= low 时返回 low）
+    pub fn gen_range_f64(&mut self, low: f64, high: f64) -> f64 {
+        if high <= low {
+            return low;
+        }
+        low + (high - low) * self.next_f64()
+    }
+
+    /// 以概率 p 返回 true（p 超出 [0, 1] 时按边界处理）
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// SplitMix64 单步
+    fn splitmix64(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(SPLITMIX64_GAMMA);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_stream() {
+        let mut a = OptimizerRng::new(42);
+        let mut b = OptimizerRng::new(42);
+        let mut c = OptimizerRng::new(43);
+        let stream_a: Vec<u64> = (0..16).map(|_| a.next_u64()).collect();
+        let stream_b: Vec<u64> = (0..16).map(|_| b.next_u64()).collect();
+        let stream_c: Vec<u64> = (0..16).map(|_| c.next_u64()).collect();
+        assert_eq!(stream_a, stream_b);
+        assert_ne!(stream_a, stream_c);
+    }
+
+    #[test]
+    fn test_ranges_and_bounds() {
+        let mut rng = OptimizerRng::new(7);
+        for _ in 0..1_000 {
+            let value = rng.gen_range_u64(30, 300);
+            assert!((30..=300).contains(&value));
+            assert!(rng.gen_index(5) < 5);
+            let x = rng.next_f64();
+            assert!((0.0..1.0).contains(&x));
+            let y = rng.gen_range_f64(2.0, 3.0);
+            assert!((2.0..3.0).contains(&y));
+        }
+        assert_eq!(rng.gen_range_u64(9, 9), 9);
+        assert_eq!(rng.gen_index(0), 0);
+        assert_eq!(rng.gen_range_f64(1.0, 1.0), 1.0);
+        assert!(!rng.gen_bool(0.0));
+        assert!(rng.gen_bool(1.0));
+    }
+
+    #[test]
+    fn test_slot_hash_seed() {
+        let mut hash = [7u8; 32];
+        let seed = OptimizerRng::seed_from_slot_hash(&hash);
+        assert_eq!(OptimizerRng::from_slot_hash(&hash), OptimizerRng::new(seed));
+        hash[31] ^= 1;
+        assert_ne!(OptimizerRng::seed_from_slot_hash(&hash), seed); // 任一字节变化都会改变种子
+    }
+}
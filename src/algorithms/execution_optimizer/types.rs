@@ -189,13 +189,24 @@ pub struct TransactionCostModel {
 }
 
 /// 市场冲击模型枚举
-#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MarketImpactModel {
     Linear,       // 线性模型
     SquareRoot,   // 平方根模型
     Logarithmic,  // 对数模型
 }
 
+impl MarketImpactModel {
+    /// 冲击驱动因子：冲击（基点）= 截距 + 斜率 × driver(成交数量)
+    pub fn driver(&self, size: f64) -> f64 {
+        match self {
+            MarketImpactModel::Linear => size,           // 与数量成正比
+            MarketImpactModel::SquareRoot => size.sqrt(), // 与数量平方根成正比
+            MarketImpactModel::Logarithmic => size.ln_1p(), // 与数量对数成正比
+        }
+    }
+}
+
 /// 算法工厂结构体，支持算法注册、获取、默认算法管理
 pub struct AlgorithmFactory {
     algorithms: RwLock<HashMap<String, Arc<dyn ExecutionStrategy + Send + Sync>>>, // 算法注册表
@@ -5,6 +5,7 @@
 //! - 执行逻辑：基于实现短缺的算法执行
 //! - 服务层调用：委托给IsService执行核心业务逻辑
 //! - 事件发射：发射IS算法执行事件用于审计和追踪
+//! - 最优轨迹：Almgren-Chriss 模型在给定波动率、临时/永久冲击与风险厌恶下求解最优交易计划及成本-方差前沿
 //! 
 //! 设计特点：
 //! - 最小功能单元：专注于IS算法执行功能
@@ -29,6 +30,9 @@ use crate::{
     services::*,
     utils::*,
 };
+use crate::algorithms::execution_optimizer::{ExecutionPlan, ExecutionSegment, ExecutionStrategyType};
+use crate::algorithms::market_impact::MarketImpactCalculator;
+use crate::errors::algorithm_error::AlgorithmError;
 
 /// IS算法参数结构体
 /// 
@@ -342,6 +346,357 @@ impl IsService {
     }
 }
 
+/// 一天的秒数（日波动率换算为每秒波动率）
+const SECONDS_PER_DAY: f64 = 86_400.0;
+/// ExecutionPlan 价格精度（与 DEX 报价均价一致，1e6）
+const AC_PRICE_SCALE: f64 = 1_000_000.0;
+/// 紧急程度 100 对应的无量纲衰减强度 κT（持仓半衰期约为期限的 7%）
+pub const MAX_URGENCY_KAPPA_T: f64 = 10.0;
+
+/// Almgren-Chriss 冲击系数
+/// - 临时冲击 η：单期成交速率 v = n/τ 带来的单位价格让步 η·v
+/// - 永久冲击 γ：每成交一单位带来的价格永久漂移
+/// - 固定成本 ε：每单位的半价差/手续费
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ImpactCoefficients {
+    /// 临时冲击系数 η（价格 × 秒 / 单位）
+    pub temporary: f64,
+    /// 永久冲击系数 γ（价格 / 单位）
+    pub permanent: f64,
+    /// 固定成本 ε（价格 / 单位）
+    pub fixed: f64,
+}
+
+impl ImpactCoefficients {
+    /// 由 DEX 报价曲线估计冲击系数
+    /// - quote_curve: (报价数量, 价格冲击基点) 样本，按过原点最小二乘拟合单位数量的相对冲击 k
+    /// - 单期一次性成交 n 的价格让步为 k·n·P，对应 η = k·P·τ
+    /// - permanent_share: 冲击中在下一期前未被套利恢复的比例，γ = permanent_share·k·P
+    /// - fee_bps: 手续费与半价差（基点），ε = fee_bps·P / 10000
+    pub fn from_quote_curve(
+        quote_curve: &[(u64, u32)],
+        arrival_price: f64,
+        interval_secs: f64,
+        permanent_share: f64,
+        fee_bps: u32,
+    ) -> anchor_lang::Result<Self> {
+        let (sxy, sxx) = quote_curve.iter().fold((0.0, 0.0), |(sxy, sxx), (size, impact_bps)| {
+            let size = *size as f64;
+            (sxy + size * *impact_bps as f64 / 10_000.0, sxx + size * size)
+        });
+        require!(
+            sxx > 0.0 && sxy > 0.0 && arrival_price > 0.0 && interval_secs > 0.0,
+            AlgorithmError::InvalidParameters {
+                reason: "Quote curve must contain positive sizes and impacts".to_string(),
+            }
+        );
+        require!(
+            (0.0..=1.0).contains(&permanent_share),
+            AlgorithmError::InvalidParameters {
+                reason: "Permanent impact share must be in [0, 1]".to_string(),
+            }
+        );
+        let slope = sxy / sxx;
+        Ok(Self {
+            temporary: slope * arrival_price * interval_secs,
+            permanent: permanent_share * slope * arrival_price,
+            fixed: fee_bps as f64 / 10_000.0 * arrival_price,
+        })
+    }
+}
+
+impl ImpactCoefficients {
+    /// 由历史成交校准的市场冲击模型推导冲击系数
+    /// - 以单期子单 total_amount / num_intervals 的校准冲击做割线线性化，得到单位数量的相对冲击 k
+    /// - η = k·P·τ，γ = permanent_share·k·P（permanent_share 取自冲击模型配置），ε 取校准截距
+    pub fn from_market_impact(
+        calculator: &MarketImpactCalculator,
+        asset: &Pubkey,
+        arrival_price: f64,
+        total_amount: u64,
+        horizon_secs: u64,
+        num_intervals: u32,
+    ) -> anchor_lang::Result<Self> {
+        require!(
+            num_intervals > 0 && horizon_secs > 0 && total_amount >= num_intervals as u64 && arrival_price > 0.0,
+            AlgorithmError::InvalidParameters {
+                reason: "Invalid amount, horizon, intervals or arrival price".to_string(),
+            }
+        );
+        let impact = calculator.asset_impact(asset)?;
+        let child_size = total_amount / num_intervals as u64;
+        let slope = (impact.instant_impact_bps(child_size) - impact.fixed_cost_bps()) / 10_000.0 / child_size as f64;
+        require!(
+            slope > 0.0,
+            AlgorithmError::InvalidParameters {
+                reason: "Calibrated impact does not grow with trade size".to_string(),
+            }
+        );
+        let interval_secs = horizon_secs as f64 / num_intervals as f64;
+        Ok(Self {
+            temporary: slope * arrival_price * interval_secs,
+            permanent: calculator.config().permanent_share * slope * arrival_price,
+            fixed: impact.fixed_cost_bps() / 10_000.0 * arrival_price,
+        })
+    }
+}
+
+/// Almgren-Chriss 最优执行参数（卖出 total_amount，买入对称）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct AlmgrenChrissParams {
+    /// 母单总数量
+    pub total_amount: u64,
+    /// 执行期限（秒）
+    pub horizon_secs: u64,
+    /// 交易期数
+    pub num_intervals: u32,
+    /// 到达价格（报价资产计价）
+    pub arrival_price: f64,
+    /// 日波动率（相对值，如 0.05 表示 5%）
+    pub daily_volatility: f64,
+    /// 冲击系数
+    pub impact: ImpactCoefficients,
+    /// 风险厌恶系数 λ（1 / 报价资产单位）
+    pub risk_aversion: f64,
+}
+
+impl AlmgrenChrissParams {
+    /// 单期时长 τ（秒）
+    pub fn interval_secs(&self) -> f64 {
+        self.horizon_secs as f64 / self.num_intervals as f64
+    }
+
+    /// 每 √秒 的绝对价格波动率 σ
+    pub fn sigma(&self) -> f64 {
+        self.daily_volatility * self.arrival_price / SECONDS_PER_DAY.sqrt()
+    }
+
+    /// 经永久冲击修正的临时冲击 η̃ = η - γτ/2
+    pub fn adjusted_temporary_impact(&self) -> f64 {
+        self.impact.temporary - 0.5 * self.impact.permanent * self.interval_secs()
+    }
+
+    /// 将紧急程度（0-100）换算为风险厌恶系数
+    /// - 紧急程度线性映射为 κ̃T ∈ [0, MAX_URGENCY_KAPPA_T]，λ = κ̃²·η̃ / σ²
+    /// - 紧急程度 0 对应 TWAP（风险中性），越高越前置成交
+    pub fn risk_aversion_for_urgency(&self, urgency: f64) -> anchor_lang::Result<f64> {
+        require!(
+            (0.0..=100.0).contains(&urgency),
+            AlgorithmError::InvalidParameters {
+                reason: "Urgency must be between 0 and 100".to_string(),
+            }
+        );
+        self.validate_market()?;
+        let kappa_tilde = urgency / 100.0 * MAX_URGENCY_KAPPA_T / self.horizon_secs as f64;
+        Ok(kappa_tilde * kappa_tilde * self.adjusted_temporary_impact() / self.sigma().powi(2))
+    }
+
+    /// 校验期限、价格、波动率与冲击系数
+    fn validate_market(&self) -> anchor_lang::Result<()> {
+        require!(
+            self.total_amount > 0 && self.horizon_secs > 0 && self.num_intervals > 0 && self.num_intervals <= 1_000,
+            AlgorithmError::InvalidParameters {
+                reason: "Invalid amount, horizon or number of intervals".to_string(),
+            }
+        );
+        require!(
+            self.arrival_price > 0.0 && self.daily_volatility > 0.0,
+            AlgorithmError::InvalidParameters {
+                reason: "Arrival price and volatility must be positive".to_string(),
+            }
+        );
+        require!(
+            self.impact.permanent >= 0.0 && self.impact.fixed >= 0.0 && self.adjusted_temporary_impact() > 0.0,
+            AlgorithmError::InvalidParameters {
+                reason: "Temporary impact must exceed half the permanent impact per interval".to_string(),
+            }
+        );
+        Ok(())
+    }
+}
+
+/// Almgren-Chriss 最优交易轨迹
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlmgrenChrissTrajectory {
+    /// 各期成交数量（整数，总和等于 total_amount）
+    pub trades: Vec<u64>,
+    /// 各期末剩余持仓 x_1..x_N（x_0 = total_amount）
+    pub holdings: Vec<u64>,
+    /// 轨迹衰减速率 κ（1/秒），0 表示线性（TWAP）轨迹
+    pub kappa: f64,
+    /// 期望执行成本 E[C]（报价资产计价，相对到达价格）
+    pub expected_cost: f64,
+    /// 执行成本方差 V[C]
+    pub cost_variance: f64,
+    /// 风险调整后目标 E[C] + λV[C]
+    pub objective: f64,
+}
+
+/// 成本-方差有效前沿上的一点
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrontierPoint {
+    /// 风险厌恶系数 λ
+    pub risk_aversion: f64,
+    /// 轨迹衰减速率 κ
+    pub kappa: f64,
+    /// 期望执行成本
+    pub expected_cost: f64,
+    /// 执行成本标准差
+    pub cost_std_dev: f64,
+}
+
+impl AlmgrenChrissTrajectory {
+    /// 按 Almgren-Chriss 离散模型计算期望成本与方差
+    /// - E[C] = ½γX² + εX + (η̃/τ)Σn²
+    /// - V[C] = σ²τΣx²
+    fn evaluate(params: &AlmgrenChrissParams, trades: Vec<u64>, kappa: f64) -> Self {
+        let tau = params.interval_secs();
+        let total = params.total_amount as f64;
+        let mut remaining = params.total_amount;
+        let holdings: Vec<u64> = trades
+            .iter()
+            .map(|trade| {
+                remaining -= trade;
+                remaining
+            })
+            .collect();
+        let sum_trades_sq: f64 = trades.iter().map(|n| (*n as f64).powi(2)).sum();
+        let sum_holdings_sq: f64 = holdings.iter().map(|x| (*x as f64).powi(2)).sum();
+        let expected_cost = 0.5 * params.impact.permanent * total * total
+            + params.impact.fixed * total
+            + params.adjusted_temporary_impact() / tau * sum_trades_sq;
+        let cost_variance = params.sigma().powi(2) * tau * sum_holdings_sq;
+        Self {
+            trades,
+            holdings,
+            kappa,
+            expected_cost,
+            cost_variance,
+            objective: expected_cost + params.risk_aversion * cost_variance,
+        }
+    }
+
+    /// 转换为执行优化器的 ExecutionPlan
+    /// - 每期一个分段，execution_time 为单期时长，target_price 为到达价格（精度 1e6）
+    /// - 分段预期成本 = εn + (η/τ)n² + γn·(已成交数量)，总和等于 E[C]
+    /// - 分段冲击为临时让步与累计永久漂移（基点），计划总冲击为不含固定成本的平均执行缺口（基点）
+    pub fn to_execution_plan(&self, params: &AlmgrenChrissParams) -> ExecutionPlan {
+        let tau = params.interval_secs();
+        let price = params.arrival_price;
+        let mut executed = 0u64;
+        let mut impact_cost = 0.0;
+        let segments = self
+            .trades
+            .iter()
+            .enumerate()
+            .map(|(i, trade)| {
+                let n = *trade as f64;
+                let permanent_drift = params.impact.permanent * executed as f64;
+                let temporary = params.impact.temporary / tau * n;
+                impact_cost += (temporary + permanent_drift) * n;
+                executed += trade;
+                ExecutionSegment {
+                    index: i as u32,
+                    amount: *trade,
+                    execution_time: tau.round() as u64,
+                    target_price: (price * AC_PRICE_SCALE).round() as u64,
+                    expected_cost: ((params.impact.fixed + temporary + permanent_drift) * n).round() as u64,
+                    expected_market_impact_bps: ((temporary + permanent_drift) / price * 10_000.0).round() as u32,
+                    execution_strategy: ExecutionStrategyType::TimeWeighted,
+                }
+            })
+            .collect();
+        ExecutionPlan {
+            id: format!("is_ac_{}_{}_{:.6e}", params.total_amount, params.num_intervals, self.kappa),
+            segments,
+            total_execution_amount: params.total_amount,
+            total_execution_time: params.horizon_secs,
+            total_cost: self.expected_cost.round() as u64,
+            total_market_impact_bps: (impact_cost / (params.total_amount as f64 * price) * 10_000.0).round() as u32,
+        }
+    }
+}
+
+/// 按最大余数法取整，保证总和等于 total
+fn round_preserving_total(total: u64, values: &[f64]) -> Vec<u64> {
+    let mut rounded: Vec<u64> = values.iter().map(|v| v.floor() as u64).collect();
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| {
+        (values[b] - values[b].floor())
+            .partial_cmp(&(values[a] - values[a].floor()))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.cmp(&b))
+    });
+    let shortfall = total.saturating_sub(rounded.iter().sum::<u64>()) as usize; // 各项向下取整损失均小于 1
+    for &i in order.iter().take(shortfall) {
+        rounded[i] += 1;
+    }
+    rounded
+}
+
+impl IsAlgorithm {
+    /// 计算 Almgren-Chriss 最优交易轨迹
+    /// - κ 由 cosh(κτ) = 1 + κ̃²τ²/2 求得，κ̃² = λσ²/η̃
+    /// - 剩余持仓 x_j = X·sinh(κ(T - t_j)) / sinh(κT)，λ = 0 时退化为线性（TWAP）轨迹
+    pub fn plan_trajectory(&self, params: &AlmgrenChrissParams) -> anchor_lang::Result<AlmgrenChrissTrajectory> {
+        params.validate_market()?;
+        require!(
+            params.risk_aversion >= 0.0 && params.risk_aversion.is_finite(),
+            AlgorithmError::InvalidParameters {
+                reason: "Risk aversion must be a non-negative finite number".to_string(),
+            }
+        );
+        let tau = params.interval_secs();
+        let horizon = params.horizon_secs as f64;
+        let kappa_tilde_sq = params.risk_aversion * params.sigma().powi(2) / params.adjusted_temporary_impact();
+        let kappa = (1.0 + 0.5 * kappa_tilde_sq * tau * tau).acosh() / tau;
+
+        // 剩余持仓比例，采用指数形式避免 κT 较大时 sinh 溢出
+        let holding_fraction = |t: f64| -> f64 {
+            if kappa * horizon < 1e-9 {
+                return 1.0 - t / horizon;
+            }
+            (-kappa * t).exp() * (1.0 - (-2.0 * kappa * (horizon - t)).exp()) / (1.0 - (-2.0 * kappa * horizon).exp())
+        };
+        let total = params.total_amount as f64;
+        let continuous: Vec<f64> = (1..=params.num_intervals)
+            .map(|j| total * (holding_fraction((j - 1) as f64 * tau) - holding_fraction(j as f64 * tau)))
+            .collect();
+        let trades = round_preserving_total(params.total_amount, &continuous);
+        Ok(AlmgrenChrissTrajectory::evaluate(params, trades, kappa))
+    }
+
+    /// 按紧急程度（0-100）计算最优轨迹，便于直接指定执行紧迫度而非猜测 TWAP 时长
+    pub fn plan_trajectory_for_urgency(
+        &self,
+        params: &AlmgrenChrissParams,
+        urgency: f64,
+    ) -> anchor_lang::Result<AlmgrenChrissTrajectory> {
+        let risk_aversion = params.risk_aversion_for_urgency(urgency)?;
+        self.plan_trajectory(&AlmgrenChrissParams { risk_aversion, ..params.clone() })
+    }
+
+    /// 计算成本-方差有效前沿（每个风险厌恶系数对应一条最优轨迹）
+    pub fn efficient_frontier(
+        &self,
+        params: &AlmgrenChrissParams,
+        risk_aversions: &[f64],
+    ) -> anchor_lang::Result<Vec<FrontierPoint>> {
+        risk_aversions
+            .iter()
+            .map(|risk_aversion| {
+                let trajectory = self.plan_trajectory(&AlmgrenChrissParams { risk_aversion: *risk_aversion, ..params.clone() })?;
+                Ok(FrontierPoint {
+                    risk_aversion: *risk_aversion,
+                    kappa: trajectory.kappa,
+                    expected_cost: trajectory.expected_cost,
+                    cost_std_dev: trajectory.cost_variance.sqrt(),
+                })
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -391,4 +746,129 @@ mod tests {
         assert!(result.success);
         assert_eq!(result.algorithm_type, AlgorithmType::IS);
     }
-} 
\ No newline at end of file
+
+    /// 卖出 10000 单位，1 小时 10 期，价格 100，日波动率 5%
+    fn ac_params(risk_aversion: f64) -> AlmgrenChrissParams {
+        AlmgrenChrissParams {
+            total_amount: 10_000,
+            horizon_secs: 3_600,
+            num_intervals: 10,
+            arrival_price: 100.0,
+            daily_volatility: 0.05,
+            impact: ImpactCoefficients { temporary: 0.36, permanent: 1e-4, fixed: 0.01 },
+            risk_aversion,
+        }
+    }
+
+    #[test]
+    fn test_risk_neutral_trajectory_is_twap() {
+        let algorithm = IsAlgorithm::new();
+        let params = ac_params(0.0);
+        let trajectory = algorithm.plan_trajectory(&params).unwrap();
+        assert_eq!(trajectory.trades, vec![1_000; 10]);
+        assert_eq!(trajectory.holdings[9], 0);
+        assert_eq!(trajectory.kappa, 0.0);
+        // E = ½γX² + εX + (η̃/τ)·N·(X/N)²
+        let eta_tilde = 0.36 - 0.5 * 1e-4 * 360.0;
+        let expected = 0.5 * 1e-4 * 1e8 + 0.01 * 1e4 + eta_tilde / 360.0 * 10.0 * 1e6;
+        assert!((trajectory.expected_cost - expected).abs() < 1e-6);
+        // V = σ²τΣx²，x = 9000, 8000, ..., 0
+        let sum_sq: f64 = (0..10).map(|j| (j as f64 * 1_000.0).powi(2)).sum();
+        assert!((trajectory.cost_variance - params.sigma().powi(2) * 360.0 * sum_sq).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_risk_aversion_front_loads_trades() {
+        let algorithm = IsAlgorithm::new();
+        let params = ac_params(0.0);
+        let risk_aversion = params.risk_aversion_for_urgency(50.0).unwrap();
+        let trajectory = algorithm.plan_trajectory(&AlmgrenChrissParams { risk_aversion, ..params.clone() }).unwrap();
+        assert_eq!(trajectory.trades.iter().sum::<u64>(), 10_000);
+        assert!(trajectory.trades.windows(2).all(|w| w[0] >= w[1])); // 单调递减，前置成交
+        assert!(trajectory.trades[0] > 1_000 && trajectory.trades[9] < 1_000);
+        // 紧急程度 50 对应 κ̃T = 5
+        let kappa_tilde = (risk_aversion * params.sigma().powi(2) / params.adjusted_temporary_impact()).sqrt();
+        assert!((kappa_tilde * 3_600.0 - 5.0).abs() < 1e-9);
+        // κ 满足 cosh(κτ) = 1 + κ̃²τ²/2
+        let tau = params.interval_secs();
+        assert!(((trajectory.kappa * tau).cosh() - (1.0 + 0.5 * (kappa_tilde * tau).powi(2))).abs() < 1e-9);
+        // 连续解 n_1 = 2sinh(κτ/2)cosh(κ(T - τ/2))/sinh(κT)·X
+        let k = trajectory.kappa;
+        let n1 = 2.0 * (k * tau / 2.0).sinh() * (k * (3_600.0 - tau / 2.0)).cosh() / (k * 3_600.0).sinh() * 10_000.0;
+        assert!((trajectory.trades[0] as f64 - n1).abs() <= 1.0);
+
+        let urgent = algorithm.plan_trajectory_for_urgency(&params, 100.0).unwrap();
+        assert!(urgent.trades[0] > trajectory.trades[0]);
+        assert!(algorithm.plan_trajectory_for_urgency(&params, 101.0).is_err());
+    }
+
+    #[test]
+    fn test_efficient_frontier_trades_cost_for_variance() {
+        let algorithm = IsAlgorithm::new();
+        let params = ac_params(0.0);
+        let lambdas: Vec<f64> = [0.0, 25.0, 50.0, 75.0, 100.0]
+            .iter()
+            .map(|urgency| params.risk_aversion_for_urgency(*urgency).unwrap())
+            .collect();
+        let frontier = algorithm.efficient_frontier(&params, &lambdas).unwrap();
+        assert_eq!(frontier.len(), 5);
+        for pair in frontier.windows(2) {
+            assert!(pair[1].expected_cost > pair[0].expected_cost); // 越紧急期望成本越高
+            assert!(pair[1].cost_std_dev < pair[0].cost_std_dev); // 风险越低
+        }
+    }
+
+    #[test]
+    fn test_trajectory_to_execution_plan() {
+        let algorithm = IsAlgorithm::new();
+        let params = ac_params(0.0);
+        let trajectory = algorithm.plan_trajectory_for_urgency(&params, 60.0).unwrap();
+        let plan = trajectory.to_execution_plan(&params);
+        assert_eq!(plan.segments.len(), 10);
+        assert_eq!(plan.segments.iter().map(|s| s.amount).sum::<u64>(), 10_000);
+        assert_eq!(plan.total_execution_amount, 10_000);
+        assert_eq!(plan.total_execution_time, 3_600);
+        assert!(plan.segments.iter().all(|s| s.execution_time == 360 && s.target_price == 100_000_000));
+        let segment_cost: u64 = plan.segments.iter().map(|s| s.expected_cost).sum();
+        assert!(segment_cost.abs_diff(plan.total_cost) <= 10); // 分段成本之和等于 E[C]（逐段取整误差）
+        assert!(plan.total_market_impact_bps > 0);
+    }
+
+    #[test]
+    fn test_impact_coefficients_from_quote_curve() {
+        // 冲击与数量成正比：每 100 单位 10 bps
+        let impact = ImpactCoefficients::from_quote_curve(&[(100, 10), (200, 20), (400, 40)], 100.0, 360.0, 0.25, 5).unwrap();
+        assert!((impact.temporary - 1e-5 * 100.0 * 360.0).abs() < 1e-9);
+        assert!((impact.permanent - 0.25 * 1e-5 * 100.0).abs() < 1e-12);
+        assert!((impact.fixed - 0.05).abs() < 1e-12);
+        assert!(ImpactCoefficients::from_quote_curve(&[], 100.0, 360.0, 0.25, 5).is_err());
+
+        // 由校准冲击模型推导：线性模型下与报价曲线拟合一致
+        use crate::algorithms::execution_optimizer::types::MarketImpactModel;
+        use crate::algorithms::market_impact::{CalibratedImpact, ImpactCoefficientRecord};
+        let asset = Pubkey::new_unique();
+        let mut calculator = MarketImpactCalculator::new();
+        assert!(ImpactCoefficients::from_market_impact(&calculator, &asset, 100.0, 1_000, 3_600, 10).is_err()); // 未校准
+        calculator.load_coefficients(&[ImpactCoefficientRecord {
+            venue: None,
+            asset,
+            impact: CalibratedImpact {
+                model: MarketImpactModel::Linear,
+                intercept_bps: 5.0,
+                slope_bps: 0.1,
+                r_squared: 1.0,
+                samples: 10,
+                calibrated_at: 0,
+            },
+        }]);
+        let calibrated = ImpactCoefficients::from_market_impact(&calculator, &asset, 100.0, 1_000, 3_600, 10).unwrap();
+        assert!((calibrated.temporary - impact.temporary).abs() < 1e-9);
+        assert!((calibrated.permanent - impact.permanent).abs() < 1e-12);
+        assert!((calibrated.fixed - impact.fixed).abs() < 1e-12);
+
+        // 临时冲击不足以覆盖永久冲击修正时参数无效
+        let mut params = ac_params(1e-6);
+        params.impact.temporary = 0.5 * params.impact.permanent * params.interval_secs();
+        assert!(IsAlgorithm::new().plan_trajectory(&params).is_err());
+    }
+}
@@ -0,0 +1,577 @@
+/*!
+ * 市场冲击模型校准模块
+ *
+ * 从历史 DEX 成交（DexSwapExecuted 事件或 CSV 导出）中按资产、按交易场所（池/DEX）拟合市场冲击模型，
+ * 保存校准系数，并为 TWAP/VWAP/IS 规划与智能路由成本函数提供冲击估计。
+ *
+ * 设计要点：
+ * - 单笔成交冲击 = 成交均价相对成交前参考价格的不利偏离（基点），包含手续费与半价差
+ * - 每组成交分别以线性、平方根（可选对数）驱动因子做最小二乘，取残差平方和最小的模型；斜率非负
+ * - 截距视为固定成本，截距以上的规模相关冲击中 permanent_share 比例视为永久冲击
+ * - 跨期执行按 slice_interval_secs 拆为等量子单，子单冲击加上此前子单累积的永久冲击，得到平均冲击
+ */
+
+use anchor_lang::prelude::*;
+use crate::algorithms::execution_optimizer::types::MarketImpactModel;
+use crate::core::events::DexSwapExecuted;
+use crate::core::traits::dex_oracle::MarketImpactModel as ImpactEstimator;
+use crate::errors::algorithm_error::AlgorithmError;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// 参考价格精度（与 DEX 报价均价一致，1e6）
+pub const IMPACT_PRICE_SCALE: f64 = 1_000_000.0;
+/// 成交 CSV 表头
+pub const IMPACT_CSV_HEADER: &str = "venue,asset,timestamp,amount_in,amount_out,reference_price";
+
+/// 历史成交样本（卖出 asset，amount_in 为 asset 数量）
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImpactFill {
+    /// 交易场所（DEX 名称或池地址）
+    pub venue: String,
+    /// 卖出资产 mint
+    pub asset: Pubkey,
+    /// 成交时间戳（Unix 秒）
+    pub timestamp: i64,
+    /// 输入数量（asset 原子数量）
+    pub amount_in: u64,
+    /// 输出数量（报价资产原子数量）
+    pub amount_out: u64,
+    /// 成交前参考价格（每单位 asset 的报价资产数量，精度 1e6）
+    pub reference_price: u64,
+}
+
+impl ImpactFill {
+    /// 由 DexSwapExecuted 事件与成交前参考价格构造样本（卖出 token_in）
+    pub fn from_swap_event(event: &DexSwapExecuted, reference_price: u64) -> Self {
+        Self {
+            venue: event.dex.clone(),
+            asset: event.token_in,
+            timestamp: event.timestamp,
+            amount_in: event.amount_in,
+            amount_out: event.amount_out,
+            reference_price,
+        }
+    }
+
+    /// 成交冲击（基点）：成交均价相对参考价格的不利偏离，价格改善为负；数量或参考价格为 0 时返回 None
+    pub fn impact_bps(&self) -> Option<f64> {
+        if self.amount_in == 0 || self.reference_price == 0 {
+            return None;
+        }
+        let execution_price = self.amount_out as f64 * IMPACT_PRICE_SCALE / self.amount_in as f64;
+        let reference_price = self.reference_price as f64;
+        Some((reference_price - execution_price) / reference_price * 10_000.0)
+    }
+
+    /// 解析成交 CSV（列顺序见 IMPACT_CSV_HEADER，表头与空行忽略）
+    pub fn parse_csv(csv: &str) -> anchor_lang::Result<Vec<Self>> {
+        let mut fills = Vec::new();
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.eq_ignore_ascii_case(IMPACT_CSV_HEADER) {
+                continue;
+            }
+            let invalid = |field: &str| AlgorithmError::InvalidParameters {
+                reason: format!("Invalid {} in impact CSV line {}", field, index + 1),
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            require!(fields.len() == 6 && !fields[0].is_empty(), invalid("row"));
+            fills.push(Self {
+                venue: fields[0].to_string(),
+                asset: Pubkey::from_str(fields[1]).map_err(|_| invalid("asset"))?,
+                timestamp: fields[2].parse().map_err(|_| invalid("timestamp"))?,
+                amount_in: fields[3].parse().map_err(|_| invalid("amount_in"))?,
+                amount_out: fields[4].parse().map_err(|_| invalid("amount_out"))?,
+                reference_price: fields[5].parse().map_err(|_| invalid("reference_price"))?,
+            });
+        }
+        Ok(fills)
+    }
+}
+
+/// 市场冲击校准配置
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketImpactConfig {
+    /// 参与拟合的冲击模型（残差最小者胜出，相同时取靠前者）
+    pub candidate_models: Vec<MarketImpactModel>,
+    /// 每组（资产或场所+资产）最少成交样本数
+    pub min_samples: u32,
+    /// 跨期执行的子单间隔（秒）
+    pub slice_interval_secs: u64,
+    /// 规模相关冲击中永久冲击的比例 [0, 1]
+    pub permanent_share: f64,
+    /// 单次估计的最大子单数量
+    pub max_slices: u32,
+}
+
+impl Default for MarketImpactConfig {
+    fn default() -> Self {
+        Self {
+            candidate_models: vec![MarketImpactModel::Linear, MarketImpactModel::SquareRoot],
+            min_samples: 10,
+            slice_interval_secs: 60, // 每分钟一笔子单
+            permanent_share: 0.25,
+            max_slices: 1_000,
+        }
+    }
+}
+
+impl MarketImpactConfig {
+    /// 校验配置
+    pub fn validate(&self) -> anchor_lang::Result<()> {
+        require!(
+            !self.candidate_models.is_empty() && self.min_samples >= 2,
+            AlgorithmError::InvalidParameters {
+                reason: "Impact calibration needs at least one model and two samples".to_string(),
+            }
+        );
+        require!(
+            self.slice_interval_secs > 0 && self.max_slices > 0,
+            AlgorithmError::InvalidParameters {
+                reason: "Slice interval and max slices must be positive".to_string(),
+            }
+        );
+        require!(
+            (0.0..=1.0).contains(&self.permanent_share),
+            AlgorithmError::InvalidParameters {
+                reason: "Permanent impact share must be in [0, 1]".to_string(),
+            }
+        );
+        Ok(())
+    }
+}
+
+/// 校准后的冲击系数：冲击（基点）= intercept_bps + slope_bps × driver(数量)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CalibratedImpact {
+    /// 冲击模型
+    pub model: MarketImpactModel,
+    /// 截距（基点），即手续费与半价差等固定成本
+    pub intercept_bps: f64,
+    /// 斜率（基点 / 驱动因子单位）
+    pub slope_bps: f64,
+    /// 拟合优度 R²
+    pub r_squared: f64,
+    /// 样本数量
+    pub samples: u32,
+    /// 最新样本时间戳
+    pub calibrated_at: i64,
+}
+
+impl CalibratedImpact {
+    /// 单笔成交 size 的冲击（基点，非负）
+    pub fn instant_impact_bps(&self, size: u64) -> f64 {
+        (self.intercept_bps + self.slope_bps * self.model.driver(size as f64)).max(0.0)
+    }
+
+    /// 固定成本（基点，非负）
+    pub fn fixed_cost_bps(&self) -> f64 {
+        self.intercept_bps.max(0.0)
+    }
+
+    /// 最小二乘拟合，返回系数与残差平方和；驱动因子无差异时返回 None
+    fn fit(model: MarketImpactModel, points: &[(f64, f64)], calibrated_at: i64) -> Option<(Self, f64)> {
+        let n = points.len() as f64;
+        let xs: Vec<f64> = points.iter().map(|(size, _)| model.driver(*size)).collect();
+        let mean_x = xs.iter().sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, impact)| impact).sum::<f64>() / n;
+        let (sxx, sxy, syy) = xs.iter().zip(points).fold((0.0, 0.0, 0.0), |(sxx, sxy, syy), (x, (_, y))| {
+            let (dx, dy) = (x - mean_x, y - mean_y);
+            (sxx + dx * dx, sxy + dx * dy, syy + dy * dy)
+        });
+        if sxx <= f64::EPSILON {
+            return None;
+        }
+        let slope_bps = (sxy / sxx).max(0.0); // 冲击随规模不减
+        let intercept_bps = mean_y - slope_bps * mean_x;
+        let sse: f64 = xs
+            .iter()
+            .zip(points)
+            .map(|(x, (_, y))| (y - intercept_bps - slope_bps * x).powi(2))
+            .sum();
+        let r_squared = if syy > 0.0 { 1.0 - sse / syy } else { 1.0 };
+        Some((
+            Self { model, intercept_bps, slope_bps, r_squared, samples: points.len() as u32, calibrated_at },
+            sse,
+        ))
+    }
+}
+
+/// 核心 trait 适配：按单笔成交估算冲击（基点），流动性已体现在校准系数中
+impl ImpactEstimator for CalibratedImpact {
+    fn estimate_impact(&self, amount: u64, _liquidity: u64) -> u64 {
+        self.instant_impact_bps(amount).round() as u64
+    }
+}
+
+/// 校准系数持久化记录
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ImpactCoefficientRecord {
+    /// 交易场所（None 表示该资产全部场所合并拟合）
+    pub venue: Option<String>,
+    /// 资产 mint
+    pub asset: Pubkey,
+    /// 校准系数
+    pub impact: CalibratedImpact,
+}
+
+/// 冲击估计结果
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImpactEstimate {
+    /// 使用的冲击模型
+    pub model: MarketImpactModel,
+    /// 子单数量
+    pub slices: u32,
+    /// 固定成本（基点）
+    pub fixed_bps: f64,
+    /// 子单按数量加权的平均瞬时冲击（基点，含固定成本）
+    pub temporary_bps: f64,
+    /// 执行结束时累积的永久冲击（基点）
+    pub permanent_bps: f64,
+    /// 按数量加权的平均冲击（基点），即相对到达价格的预期执行缺口
+    pub average_bps: f64,
+}
+
+impl ImpactEstimate {
+    /// 平均冲击（基点，取整）
+    pub fn bps(&self) -> u32 {
+        self.average_bps.round().min(u32::MAX as f64) as u32
+    }
+}
+
+/// 市场冲击计算器：按资产与场所保存校准系数并估计冲击
+#[derive(Clone, Debug, Default)]
+pub struct MarketImpactCalculator {
+    /// 校准配置
+    config: MarketImpactConfig,
+    /// 资产级系数（全部场所合并拟合）
+    assets: BTreeMap<Pubkey, CalibratedImpact>,
+    /// 场所级系数
+    venues: BTreeMap<(String, Pubkey), CalibratedImpact>,
+}
+
+impl MarketImpactCalculator {
+    /// 创建使用默认配置的计算器
+    pub fn new() -> Self {
+        Self::with_config(MarketImpactConfig::default())
+    }
+
+    /// 使用自定义配置创建计算器
+    pub fn with_config(config: MarketImpactConfig) -> Self {
+        Self { config, assets: BTreeMap::new(), venues: BTreeMap::new() }
+    }
+
+    /// 校准配置
+    pub fn config(&self) -> &MarketImpactConfig {
+        &self.config
+    }
+
+    /// 由历史成交校准，样本不足的分组保留原有系数；返回本次更新的系数组数
+    pub fn calibrate(&mut self, fills: &[ImpactFill]) -> anchor_lang::Result<usize> {
+        self.config.validate()?;
+        let mut by_asset: BTreeMap<Pubkey, Vec<&ImpactFill>> = BTreeMap::new();
+        let mut by_venue: BTreeMap<(String, Pubkey), Vec<&ImpactFill>> = BTreeMap::new();
+        for fill in fills.iter().filter(|fill| fill.impact_bps().is_some()) {
+            by_asset.entry(fill.asset).or_default().push(fill);
+            by_venue.entry((fill.venue.clone(), fill.asset)).or_default().push(fill);
+        }
+        let mut updated = 0;
+        for (asset, group) in by_asset {
+            if let Some(impact) = self.fit_group(&group) {
+                self.assets.insert(asset, impact);
+                updated += 1;
+            }
+        }
+        for (key, group) in by_venue {
+            if let Some(impact) = self.fit_group(&group) {
+                self.venues.insert(key, impact);
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+
+    /// 由 DexSwapExecuted 事件校准；reference_price 返回成交前参考价格（精度 1e6），无参考价格的事件忽略
+    pub fn calibrate_from_swap_events(
+        &mut self,
+        events: &[DexSwapExecuted],
+        reference_price: impl Fn(&DexSwapExecuted) -> Option<u64>,
+    ) -> anchor_lang::Result<usize> {
+        let fills: Vec<ImpactFill> = events
+            .iter()
+            .filter_map(|event| reference_price(event).map(|price| ImpactFill::from_swap_event(event, price)))
+            .collect();
+        self.calibrate(&fills)
+    }
+
+    /// 由成交 CSV 校准
+    pub fn calibrate_from_csv(&mut self, csv: &str) -> anchor_lang::Result<usize> {
+        let fills = ImpactFill::parse_csv(csv)?;
+        self.calibrate(&fills)
+    }
+
+    /// 资产级校准系数
+    pub fn asset_impact(&self, asset: &Pubkey) -> anchor_lang::Result<&CalibratedImpact> {
+        self.assets.get(asset).ok_or_else(|| {
+            AlgorithmError::MissingDependency { dependency: format!("market impact calibration for {}", asset) }.into()
+        })
+    }
+
+    /// 场所级校准系数
+    pub fn venue_impact(&self, venue: &str, asset: &Pubkey) -> Option<&CalibratedImpact> {
+        self.venues.get(&(venue.to_string(), *asset))
+    }
+
+    /// 导出全部校准系数（资产级在前）
+    pub fn coefficients(&self) -> Vec<ImpactCoefficientRecord> {
+        let assets = self
+            .assets
+            .iter()
+            .map(|(asset, impact)| ImpactCoefficientRecord { venue: None, asset: *asset, impact: *impact });
+        let venues = self.venues.iter().map(|((venue, asset), impact)| ImpactCoefficientRecord {
+            venue: Some(venue.clone()),
+            asset: *asset,
+            impact: *impact,
+        });
+        assets.chain(venues).collect()
+    }
+
+    /// 载入校准系数（覆盖同键系数）
+    pub fn load_coefficients(&mut self, records: &[ImpactCoefficientRecord]) {
+        for record in records {
+            match &record.venue {
+                Some(venue) => self.venues.insert((venue.clone(), record.asset), record.impact),
+                None => self.assets.insert(record.asset, record.impact),
+            };
+        }
+    }
+
+    /// 估计在 horizon_secs 内均匀执行 size 的平均冲击（horizon 为 0 表示单笔成交）
+    pub fn estimate_impact(&self, asset: &Pubkey, size: u64, horizon_secs: u64) -> anchor_lang::Result<ImpactEstimate> {
+        let impact = self.asset_impact(asset)?;
+        Ok(self.schedule_estimate(impact, &self.slice_sizes(size, horizon_secs)))
+    }
+
+    /// 按场所估计冲击，场所未校准时退回资产级系数
+    pub fn estimate_venue_impact(
+        &self,
+        venue: &str,
+        asset: &Pubkey,
+        size: u64,
+        horizon_secs: u64,
+    ) -> anchor_lang::Result<ImpactEstimate> {
+        let impact = match self.venue_impact(venue, asset) {
+            Some(impact) => impact,
+            None => self.asset_impact(asset)?,
+        };
+        Ok(self.schedule_estimate(impact, &self.slice_sizes(size, horizon_secs)))
+    }
+
+    /// 估计给定子单序列（每个子单为一笔成交）的平均冲击
+    pub fn estimate_schedule_impact(&self, asset: &Pubkey, sizes: &[u64]) -> anchor_lang::Result<ImpactEstimate> {
+        let impact = self.asset_impact(asset)?;
+        Ok(self.schedule_estimate(impact, sizes))
+    }
+
+    /// 按所有候选模型拟合一组成交，样本不足或无法拟合时返回 None
+    fn fit_group(&self, fills: &[&ImpactFill]) -> Option<CalibratedImpact> {
+        if fills.len() < self.config.min_samples as usize {
+            return None;
+        }
+        let points: Vec<(f64, f64)> = fills
+            .iter()
+            .filter_map(|fill| Some((fill.amount_in as f64, fill.impact_bps()?)))
+            .collect();
+        let calibrated_at = fills.iter().map(|fill| fill.timestamp).max().unwrap_or_default();
+        self.config
+            .candidate_models
+            .iter()
+            .filter_map(|model| CalibratedImpact::fit(*model, &points, calibrated_at))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(impact, _)| impact)
+    }
+
+    /// 将 size 按子单间隔拆为等量子单（余数分配给靠前子单）
+    fn slice_sizes(&self, size: u64, horizon_secs: u64) -> Vec<u64> {
+        let slices = (horizon_secs / self.config.slice_interval_secs.max(1))
+            .clamp(1, self.config.max_slices.max(1) as u64)
+            .min(size.max(1));
+        let (base, remainder) = (size / slices, size % slices);
+        (0..slices).map(|i| base + u64::from(i < remainder)).collect()
+    }
+
+    /// 子单冲击加上此前子单累积的永久冲击，按数量加权平均
+    fn schedule_estimate(&self, impact: &CalibratedImpact, sizes: &[u64]) -> ImpactEstimate {
+        let fixed_bps = impact.fixed_cost_bps();
+        let (mut drift, mut temporary, mut total_cost, mut total_size, mut slices) = (0.0, 0.0, 0.0, 0.0, 0u32);
+        for size in sizes.iter().filter(|size| **size > 0) {
+            let quantity = *size as f64;
+            let instant = impact.instant_impact_bps(*size);
+            temporary += quantity * instant;
+            total_cost += quantity * (instant + drift);
+            drift += self.config.permanent_share * (instant - fixed_bps); // 规模相关冲击的永久部分
+            total_size += quantity;
+            slices += 1;
+        }
+        let average = |value: f64| if total_size > 0.0 { value / total_size } else { 0.0 };
+        ImpactEstimate {
+            model: impact.model,
+            slices,
+            fixed_bps,
+            temporary_bps: average(temporary),
+            permanent_bps: drift,
+            average_bps: average(total_cost),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 生成冲击严格服从给定函数的成交样本（参考价格 1.0）
+    fn fills(venue: &str, asset: Pubkey, sizes: &[u64], impact_bps: impl Fn(f64) -> f64) -> Vec<ImpactFill> {
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, size)| {
+                let out = *size as f64 * (1.0 - impact_bps(*size as f64) / 10_000.0);
+                ImpactFill {
+                    venue: venue.to_string(),
+                    asset,
+                    timestamp: i as i64,
+                    amount_in: *size,
+                    amount_out: out.round() as u64,
+                    reference_price: 1_000_000,
+                }
+            })
+            .collect()
+    }
+
+    const SIZES: [u64; 10] = [1_000_000, 2_000_000, 4_000_000, 9_000_000, 16_000_000, 25_000_000, 36_000_000, 49_000_000, 64_000_000, 100_000_000];
+
+    #[test]
+    fn test_calibration_selects_best_model() {
+        let (linear_asset, sqrt_asset) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut samples = fills("orca", linear_asset, &SIZES, |q| 30.0 + q * 1e-6);
+        samples.extend(fills("orca", sqrt_asset, &SIZES, |q| 25.0 + 0.02 * q.sqrt()));
+        let mut calculator = MarketImpactCalculator::new();
+        assert_eq!(calculator.calibrate(&samples).unwrap(), 4); // 两个资产级 + 两个场所级
+
+        let linear = calculator.asset_impact(&linear_asset).unwrap();
+        assert_eq!(linear.model, MarketImpactModel::Linear);
+        assert!((linear.intercept_bps - 30.0).abs() < 0.1 && (linear.slope_bps - 1e-6).abs() < 1e-8);
+        assert!(linear.r_squared > 0.999);
+        assert_eq!(linear.calibrated_at, 9);
+
+        let sqrt = calculator.asset_impact(&sqrt_asset).unwrap();
+        assert_eq!(sqrt.model, MarketImpactModel::SquareRoot);
+        assert!((sqrt.slope_bps - 0.02).abs() < 1e-3);
+        assert_eq!(ImpactEstimator::estimate_impact(sqrt, 4_000_000, 0), 65); // 25 + 0.02 × 2000
+        assert!(calculator.asset_impact(&Pubkey::new_unique()).is_err());
+
+        // 样本不足的分组不参与校准
+        let mut sparse = MarketImpactCalculator::new();
+        assert_eq!(sparse.calibrate(&samples[..5]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_calibration_from_swap_events_and_csv() {
+        let asset = Pubkey::new_unique();
+        let quote = Pubkey::new_unique();
+        let events: Vec<DexSwapExecuted> = fills("raydium", asset, &SIZES, |q| 10.0 + 2e-6 * q)
+            .into_iter()
+            .map(|fill| DexSwapExecuted {
+                dex: fill.venue,
+                amount_in: fill.amount_in,
+                amount_out: fill.amount_out * 2, // 报价资产价格 2.0
+                token_in: asset,
+                token_out: quote,
+                user: Pubkey::new_unique(),
+                timestamp: fill.timestamp,
+            })
+            .collect();
+        let mut calculator = MarketImpactCalculator::new();
+        calculator.calibrate_from_swap_events(&events, |_| Some(2_000_000)).unwrap();
+        let venue = calculator.venue_impact("raydium", &asset).unwrap();
+        assert!((venue.slope_bps - 2e-6).abs() < 1e-8);
+
+        let csv: String = std::iter::once(IMPACT_CSV_HEADER.to_string())
+            .chain(fills("pool-a", asset, &SIZES, |q| 50.0 + 1e-6 * q).iter().map(|fill| {
+                format!("{},{},{},{},{},{}", fill.venue, fill.asset, fill.timestamp, fill.amount_in, fill.amount_out, fill.reference_price)
+            }))
+            .collect::<Vec<_>>()
+            .join("\n");
+        calculator.calibrate_from_csv(&csv).unwrap();
+        assert!((calculator.venue_impact("pool-a", &asset).unwrap().intercept_bps - 50.0).abs() < 0.1);
+        assert!(ImpactFill::parse_csv("pool-a,not-a-key,0,1,1,1").is_err());
+        assert!(ImpactFill::parse_csv("pool-a,1,2").is_err());
+    }
+
+    #[test]
+    fn test_horizon_slicing_and_permanent_drift() {
+        let asset = Pubkey::new_unique();
+        let mut calculator = MarketImpactCalculator::new();
+        calculator.load_coefficients(&[ImpactCoefficientRecord {
+            venue: None,
+            asset,
+            impact: CalibratedImpact {
+                model: MarketImpactModel::Linear,
+                intercept_bps: 10.0,
+                slope_bps: 1e-4,
+                r_squared: 1.0,
+                samples: 10,
+                calibrated_at: 0,
+            },
+        }]);
+        let instant = calculator.estimate_impact(&asset, 1_000_000, 0).unwrap();
+        assert_eq!(instant.slices, 1);
+        assert!((instant.average_bps - 110.0).abs() < 1e-9);
+
+        // 10 分钟拆为 10 笔子单：每笔 10 + 10 = 20 bps，永久漂移每笔 0.25 × 10 = 2.5 bps
+        let sliced = calculator.estimate_impact(&asset, 1_000_000, 600).unwrap();
+        assert_eq!(sliced.slices, 10);
+        assert!((sliced.temporary_bps - 20.0).abs() < 1e-9);
+        assert!((sliced.permanent_bps - 25.0).abs() < 1e-9);
+        assert!((sliced.average_bps - (20.0 + 2.5 * 4.5)).abs() < 1e-9);
+        assert!(sliced.average_bps < instant.average_bps);
+        assert_eq!(sliced.bps(), 31);
+
+        let schedule = calculator.estimate_schedule_impact(&asset, &[100_000; 10]).unwrap();
+        assert_eq!(schedule, sliced);
+    }
+
+    #[test]
+    fn test_venue_fallback_and_coefficient_roundtrip() {
+        let asset = Pubkey::new_unique();
+        let mut samples = fills("orca", asset, &SIZES, |q| 5.0 + 1e-6 * q);
+        samples.extend(fills("raydium", asset, &SIZES, |q| 5.0 + 4e-6 * q));
+        let mut calculator = MarketImpactCalculator::new();
+        calculator.calibrate(&samples).unwrap();
+        let orca = calculator.estimate_venue_impact("orca", &asset, 10_000_000, 0).unwrap();
+        let raydium = calculator.estimate_venue_impact("raydium", &asset, 10_000_000, 0).unwrap();
+        let unknown = calculator.estimate_venue_impact("phoenix", &asset, 10_000_000, 0).unwrap();
+        assert!(orca.average_bps < unknown.average_bps && unknown.average_bps < raydium.average_bps);
+
+        let records = calculator.coefficients();
+        assert_eq!(records.len(), 3);
+        let mut restored = MarketImpactCalculator::new();
+        restored.load_coefficients(&records);
+        assert_eq!(restored.coefficients(), records);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut calculator = MarketImpactCalculator::with_config(MarketImpactConfig {
+            candidate_models: vec![],
+            ..MarketImpactConfig::default()
+        });
+        assert!(calculator.calibrate(&[]).is_err());
+        let mut calculator = MarketImpactCalculator::with_config(MarketImpactConfig {
+            permanent_share: 1.5,
+            ..MarketImpactConfig::default()
+        });
+        assert!(calculator.calibrate(&[]).is_err());
+    }
+}
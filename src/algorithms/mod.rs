@@ -14,6 +14,7 @@ use std::sync::{Arc, RwLock}; // Arc+RwLock 实现线程安全的全局注册表
 
 use crate::algorithms::algorithm_registry::*; // 引入算法注册表模块
 use crate::algorithms::execution_optimizer::*; // 引入执行优化器模块
+use crate::algorithms::market_impact::MarketImpactCalculator; // 引入市场冲击模型模块
 use crate::algorithms::smart_routing::*; // 引入智能路由算法模块
 use crate::algorithms::twap::*; // 引入TWAP算法模块
 use crate::algorithms::vwap::*; // 引入VWAP算法模块
@@ -36,6 +37,8 @@ pub mod twap; // TWAP算法子模块
 pub mod vwap; // VWAP算法子模块
 pub mod pov; // POV算法子模块
 pub mod implementation_shortfall; // IS算法子模块
+pub mod volume_profile; // 成交量曲线子模块
+pub mod market_impact; // 市场冲击模型子模块
 
 /// 算法 trait 对象通用封装，便于动态注册和类型擦除
 pub trait AlgorithmBox: Send + Sync {
@@ -321,9 +324,9 @@ impl AlgorithmFactory {
         SmartRoutingAlgorithm::new()
     }
 
-    /// 创建市场冲击计算器
-    pub fn create_market_impact_calculator() -> () {
-        // MarketImpactCalculator::new() // 暂时注释掉未定义的类型
+    /// 创建市场冲击计算器（需以历史成交校准或载入系数后使用）
+    pub fn create_market_impact_calculator() -> MarketImpactCalculator {
+        MarketImpactCalculator::new()
     }
 
     /// 创建风险评估引擎
@@ -5,6 +5,7 @@
 //! - 执行逻辑：基于交易量百分比的算法执行
 //! - 服务层调用：委托给PovService执行核心业务逻辑
 //! - 事件发射：发射POV算法执行事件用于审计和追踪
+//! - 有状态执行引擎：PovEngine 消费观测到的市场成交量流，按参与率带宽定量分片并统计实际参与率与缺口
 //! 
 //! 设计特点：
 //! - 最小功能单元：专注于POV算法执行功能
@@ -29,6 +30,7 @@ use crate::{
     services::*,
     utils::*,
 };
+use crate::errors::algorithm_error::AlgorithmError;
 
 /// POV算法参数结构体
 /// 
@@ -338,6 +340,281 @@ impl PovService {
     }
 }
 
+/// POV 价格精度（与 DexSwapResult.avg_price、TWAP 订单限价一致，1e6）
+pub const POV_PRICE_SCALE: u128 = 1_000_000;
+/// 参与率/价格偏离基点精度
+const POV_BPS_DENOMINATOR: u128 = 10_000;
+
+/// 市场成交量观测（来自 DEX swap 事件或链下成交量源）
+/// - volume 为自上次观测以来的增量成交量，单位与订单输入数量一致，不含本订单自身成交
+/// - price 为观测时的最新成交价格（每单位输入可得输出，精度 POV_PRICE_SCALE）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VolumeObservation {
+    /// 观测时间戳
+    pub timestamp: i64,
+    /// 增量市场成交量
+    pub volume: u64,
+    /// 最新成交价格
+    pub price: u64,
+}
+
+/// POV 分片决策
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PovSliceDecision {
+    /// 执行分片：输入数量与按价格偏离上限计算的最小输出
+    Execute { amount: u64, min_amount_out: u64 },
+    /// 参与率已达目标或待执行数量不足最小分片，等待更多市场成交量
+    Wait,
+    /// 最新观测价格偏离到达价格超过上限，暂停执行
+    PriceOutOfBand,
+    /// 订单已全部成交
+    Complete,
+}
+
+/// POV 执行报告
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PovReport {
+    /// 已观测市场成交量
+    pub market_volume: u64,
+    /// 已成交数量
+    pub filled_amount: u64,
+    /// 实际参与率（基点）
+    pub realized_rate_bps: u64,
+    /// 目标参与率（基点）
+    pub target_rate_bps: u16,
+    /// 参与率缺口：按目标参与率应成交但尚未成交的数量（不超过剩余数量）
+    pub participation_shortfall: u64,
+    /// 已成交部分平均价格（精度 POV_PRICE_SCALE）
+    pub average_fill_price: u64,
+    /// 相对到达价格的执行缺口（基点，正值表示成交价劣于到达价格）
+    pub price_shortfall_bps: i64,
+    /// 实际参与率是否处于 [min_rate_bps, max_rate_bps] 带宽内
+    pub within_band: bool,
+}
+
+/// 有状态 POV 执行引擎
+/// - 按累计市场成交量 × 目标参与率确定应成交数量，分片不超过参与率上限，落后于下限时放宽最小分片限制追赶
+/// - 最新观测价格相对到达价格偏离超过 max_price_deviation_bps 时暂停，成交时以该上限计算最小输出
+/// - 全部为整数运算，可直接持久化在订单账户中由 crank 指令推进，也可离线回放成交量带进行测试
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct PovEngine {
+    /// 母单总数量
+    pub total_amount: u64,
+    /// 目标参与率（基点）
+    pub target_rate_bps: u16,
+    /// 参与率下限（基点）
+    pub min_rate_bps: u16,
+    /// 参与率上限（基点）
+    pub max_rate_bps: u16,
+    /// 单片价格偏离上限（基点，相对到达价格）
+    pub max_price_deviation_bps: u16,
+    /// 最小分片数量（尾单与追赶分片除外）
+    pub min_slice_amount: u64,
+    /// 到达价格（精度 POV_PRICE_SCALE）
+    pub arrival_price: u64,
+    /// 已观测市场成交量
+    pub market_volume: u64,
+    /// 最新观测价格
+    pub last_price: u64,
+    /// 最新观测时间戳
+    pub last_observed_at: i64,
+    /// 已成交数量
+    pub filled_amount: u64,
+    /// 已收到输出数量
+    pub received_amount: u64,
+    /// 已执行分片数量
+    pub slices_executed: u32,
+}
+
+impl PovEngine {
+    /// 创建引擎并校验参数
+    pub fn new(
+        total_amount: u64,
+        target_rate_bps: u16,
+        min_rate_bps: u16,
+        max_rate_bps: u16,
+        max_price_deviation_bps: u16,
+        min_slice_amount: u64,
+        arrival_price: u64,
+    ) -> anchor_lang::Result<Self> {
+        require!(
+            total_amount > 0 && arrival_price > 0,
+            AlgorithmError::InvalidParameters {
+                reason: "POV total amount and arrival price must be positive".to_string(),
+            }
+        );
+        require!(
+            min_rate_bps <= target_rate_bps && target_rate_bps <= max_rate_bps && max_rate_bps > 0 && max_rate_bps <= 10_000,
+            AlgorithmError::InvalidParameters {
+                reason: "POV participation band must satisfy min <= target <= max <= 10000".to_string(),
+            }
+        );
+        require!(
+            max_price_deviation_bps < 10_000,
+            AlgorithmError::InvalidParameters {
+                reason: "POV price deviation must be below 10000 bps".to_string(),
+            }
+        );
+        Ok(Self {
+            total_amount,
+            target_rate_bps,
+            min_rate_bps,
+            max_rate_bps,
+            max_price_deviation_bps,
+            min_slice_amount,
+            arrival_price,
+            market_volume: 0,
+            last_price: arrival_price,
+            last_observed_at: 0,
+            filled_amount: 0,
+            received_amount: 0,
+            slices_executed: 0,
+        })
+    }
+
+    /// 剩余未成交数量
+    pub fn remaining_amount(&self) -> u64 {
+        self.total_amount.saturating_sub(self.filled_amount)
+    }
+
+    /// 是否已全部成交
+    pub fn is_complete(&self) -> bool {
+        self.remaining_amount() == 0
+    }
+
+    /// 累计市场成交量按参与率折算的数量
+    fn volume_at_rate(&self, rate_bps: u16) -> u64 {
+        (self.market_volume as u128 * rate_bps as u128 / POV_BPS_DENOMINATOR) as u64
+    }
+
+    /// 记录一次市场成交量观测（时间戳不得回退）
+    pub fn observe(&mut self, observation: &VolumeObservation) -> anchor_lang::Result<()> {
+        require!(
+            observation.timestamp >= self.last_observed_at && observation.price > 0,
+            AlgorithmError::InvalidParameters {
+                reason: "Stale or invalid volume observation".to_string(),
+            }
+        );
+        self.market_volume = self.market_volume.checked_add(observation.volume).ok_or(AlgorithmError::InvalidParameters {
+            reason: "Market volume overflow".to_string(),
+        })?;
+        self.last_price = observation.price;
+        self.last_observed_at = observation.timestamp;
+        Ok(())
+    }
+
+    /// 最新观测价格相对到达价格的偏离（基点）
+    pub fn price_deviation_bps(&self) -> u64 {
+        let diff = self.last_price.abs_diff(self.arrival_price) as u128;
+        (diff * POV_BPS_DENOMINATOR / self.arrival_price as u128) as u64
+    }
+
+    /// 按价格偏离上限计算最小可接受输出
+    pub fn min_amount_out(&self, amount: u64) -> u64 {
+        let floor_price = self.arrival_price as u128 * (POV_BPS_DENOMINATOR - self.max_price_deviation_bps as u128) / POV_BPS_DENOMINATOR;
+        (amount as u128 * floor_price / POV_PRICE_SCALE) as u64
+    }
+
+    /// 计算下一个分片
+    pub fn next_slice(&self) -> PovSliceDecision {
+        let remaining = self.remaining_amount();
+        if remaining == 0 {
+            return PovSliceDecision::Complete;
+        }
+        if self.price_deviation_bps() > self.max_price_deviation_bps as u64 {
+            return PovSliceDecision::PriceOutOfBand;
+        }
+        let desired = self.volume_at_rate(self.target_rate_bps).saturating_sub(self.filled_amount).min(remaining);
+        let cap = self.volume_at_rate(self.max_rate_bps).saturating_sub(self.filled_amount).min(remaining);
+        let behind_band = self.volume_at_rate(self.min_rate_bps) > self.filled_amount;
+        let amount = if desired >= self.min_slice_amount || desired == remaining {
+            desired
+        } else if desired > 0 && self.min_slice_amount.min(remaining) <= cap {
+            self.min_slice_amount.min(remaining) // 向上取整到最小分片，仍不超过参与率上限
+        } else if behind_band {
+            desired // 落后于参与率下限，允许小分片追赶
+        } else {
+            0
+        };
+        if amount == 0 {
+            return PovSliceDecision::Wait;
+        }
+        PovSliceDecision::Execute { amount, min_amount_out: self.min_amount_out(amount) }
+    }
+
+    /// 记录分片成交
+    pub fn record_fill(&mut self, amount_in: u64, amount_out: u64) -> anchor_lang::Result<()> {
+        require!(
+            amount_in > 0 && amount_in <= self.remaining_amount(),
+            AlgorithmError::InvalidParameters {
+                reason: "POV fill exceeds remaining amount".to_string(),
+            }
+        );
+        require!(
+            amount_out >= self.min_amount_out(amount_in),
+            AlgorithmError::InvalidResult {
+                reason: "POV fill price beyond deviation limit".to_string(),
+            }
+        );
+        self.filled_amount += amount_in;
+        self.received_amount = self.received_amount.saturating_add(amount_out);
+        self.slices_executed += 1;
+        Ok(())
+    }
+
+    /// 已成交部分平均价格（精度 POV_PRICE_SCALE）
+    pub fn average_fill_price(&self) -> u64 {
+        if self.filled_amount == 0 {
+            return 0;
+        }
+        (self.received_amount as u128 * POV_PRICE_SCALE / self.filled_amount as u128) as u64
+    }
+
+    /// 生成执行报告
+    pub fn report(&self) -> PovReport {
+        let realized_rate_bps = if self.market_volume == 0 {
+            0
+        } else {
+            (self.filled_amount as u128 * POV_BPS_DENOMINATOR / self.market_volume as u128) as u64
+        };
+        let average_fill_price = self.average_fill_price();
+        let price_shortfall_bps = if average_fill_price == 0 {
+            0
+        } else {
+            ((self.arrival_price as i128 - average_fill_price as i128) * POV_BPS_DENOMINATOR as i128 / self.arrival_price as i128) as i64
+        };
+        PovReport {
+            market_volume: self.market_volume,
+            filled_amount: self.filled_amount,
+            realized_rate_bps,
+            target_rate_bps: self.target_rate_bps,
+            participation_shortfall: self
+                .volume_at_rate(self.target_rate_bps)
+                .saturating_sub(self.filled_amount)
+                .min(self.remaining_amount()),
+            average_fill_price,
+            price_shortfall_bps,
+            within_band: self.is_complete()
+                || (realized_rate_bps >= self.min_rate_bps as u64 && realized_rate_bps <= self.max_rate_bps as u64),
+        }
+    }
+
+    /// 回放成交量带：每条观测后计算分片，并由 fill(数量, 观测价格) 返回实际输出数量
+    pub fn replay<F>(&mut self, tape: &[VolumeObservation], mut fill: F) -> anchor_lang::Result<PovReport>
+    where
+        F: FnMut(u64, u64) -> u64,
+    {
+        for observation in tape {
+            self.observe(observation)?;
+            if let PovSliceDecision::Execute { amount, .. } = self.next_slice() {
+                let amount_out = fill(amount, observation.price);
+                self.record_fill(amount, amount_out)?;
+            }
+        }
+        Ok(self.report())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -387,4 +664,87 @@ mod tests {
         assert_eq!(result.volume_processed, 500); // 50% of 1000
         assert!(result.success);
     }
-} 
\ No newline at end of file
+
+    /// 到达价格 1.0（精度 1e6）
+    const ARRIVAL: u64 = 1_000_000;
+
+    /// 合成成交量带：每条观测间隔 60 秒
+    fn tape(volumes: &[u64], price: u64) -> Vec<VolumeObservation> {
+        volumes
+            .iter()
+            .enumerate()
+            .map(|(i, volume)| VolumeObservation { timestamp: 1_000 + 60 * i as i64, volume: *volume, price })
+            .collect()
+    }
+
+    #[test]
+    fn test_pov_engine_tracks_target_rate() {
+        let mut engine = PovEngine::new(500, 1_000, 500, 1_500, 100, 0, ARRIVAL).unwrap();
+        let report = engine.replay(&tape(&[1_000, 2_000, 0, 1_000, 1_000], ARRIVAL), |amount, _| amount).unwrap();
+        assert_eq!(report.filled_amount, 500);
+        assert_eq!(report.market_volume, 5_000);
+        assert_eq!(report.realized_rate_bps, 1_000);
+        assert_eq!(report.participation_shortfall, 0);
+        assert_eq!(report.price_shortfall_bps, 0);
+        assert!(report.within_band);
+        assert_eq!(engine.slices_executed, 4); // 零成交量观测不产生分片
+        assert_eq!(engine.next_slice(), PovSliceDecision::Complete);
+    }
+
+    #[test]
+    fn test_pov_engine_min_slice_and_band() {
+        // 不足最小分片时向上取整，但不超过参与率上限
+        let mut engine = PovEngine::new(10_000, 1_000, 500, 2_000, 100, 150, ARRIVAL).unwrap();
+        engine.replay(&tape(&[1_000, 1_000], ARRIVAL), |amount, _| amount).unwrap();
+        assert_eq!(engine.filled_amount, 300); // 150 + 150
+        assert!(engine.report().realized_rate_bps <= 2_000);
+
+        // 上限低于最小分片且落后于下限：允许小分片追赶
+        let mut engine = PovEngine::new(10_000, 1_000, 800, 1_200, 100, 150, ARRIVAL).unwrap();
+        engine.observe(&tape(&[1_000], ARRIVAL)[0]).unwrap();
+        assert_eq!(engine.next_slice(), PovSliceDecision::Execute { amount: 100, min_amount_out: 99 });
+
+        // 上限低于最小分片且未落后于下限：等待更多成交量
+        let mut engine = PovEngine::new(10_000, 1_000, 0, 1_000, 100, 150, ARRIVAL).unwrap();
+        engine.observe(&tape(&[1_000], ARRIVAL)[0]).unwrap();
+        assert_eq!(engine.next_slice(), PovSliceDecision::Wait);
+        assert_eq!(engine.report().participation_shortfall, 100);
+    }
+
+    #[test]
+    fn test_pov_engine_pauses_on_price_deviation() {
+        let mut engine = PovEngine::new(10_000, 1_000, 500, 1_500, 100, 0, ARRIVAL).unwrap();
+        let mut observations = tape(&[1_000, 1_000, 1_000], 980_000); // 偏离 200 bps
+        observations[2].price = 995_000; // 回到带宽内
+        engine.observe(&observations[0]).unwrap();
+        assert_eq!(engine.next_slice(), PovSliceDecision::PriceOutOfBand);
+        engine.observe(&observations[1]).unwrap();
+        assert!(!engine.report().within_band);
+        assert_eq!(engine.report().participation_shortfall, 200);
+
+        // 价格恢复后一次性追赶累计缺口
+        engine.observe(&observations[2]).unwrap();
+        let PovSliceDecision::Execute { amount, min_amount_out } = engine.next_slice() else {
+            panic!("expected slice");
+        };
+        assert_eq!((amount, min_amount_out), (300, 297));
+        assert!(engine.record_fill(amount, 296).is_err()); // 成交价超出偏离上限
+        engine.record_fill(amount, 298).unwrap();
+        let report = engine.report();
+        assert_eq!(report.realized_rate_bps, 1_000);
+        assert_eq!(report.average_fill_price, 993_333);
+        assert_eq!(report.price_shortfall_bps, 66);
+    }
+
+    #[test]
+    fn test_pov_engine_rejects_invalid_input() {
+        assert!(PovEngine::new(0, 1_000, 500, 1_500, 100, 0, ARRIVAL).is_err());
+        assert!(PovEngine::new(1_000, 2_000, 500, 1_500, 100, 0, ARRIVAL).is_err()); // 目标超出带宽
+        assert!(PovEngine::new(1_000, 1_000, 500, 1_500, 10_000, 0, ARRIVAL).is_err());
+        let mut engine = PovEngine::new(1_000, 1_000, 500, 1_500, 100, 0, ARRIVAL).unwrap();
+        let observations = tape(&[1_000, 1_000], ARRIVAL);
+        engine.observe(&observations[1]).unwrap();
+        assert!(engine.observe(&observations[0]).is_err()); // 时间戳回退
+        assert!(engine.record_fill(1_001, 1_001).is_err());
+    }
+}
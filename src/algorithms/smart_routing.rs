@@ -16,19 +16,29 @@
  */
 
 use anchor_lang::prelude::*;
-use crate::algorithms::traits::{Algorithm, RoutingStrategy, AlgorithmType, RoutingResult};
+use crate::algorithms::traits::{Algorithm, RoutingStrategy, AlgorithmType, RoutingResult, RouteLeg};
 use crate::core::adapter::AdapterTrait;
 use crate::algorithms::traits::AlgorithmError;
+use crate::algorithms::market_impact::MarketImpactCalculator;
 use crate::core::types::TradeParams;
+use crate::dex::curve::{self, CurveType, FeeRate};
+use crate::dex::orderbook::{self, OrderBook, OrderSide};
 // use crate::core::types::trade::TradeParams; // 暂时注释掉
 // use crate::errors::algorithm_error::AlgorithmError; // 暂时注释掉
 // use crate::core::constants::*; // 暂时注释掉
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, BTreeSet};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// 最大滑点容忍度（基点）
 const MAX_SLIPPAGE_BPS: u64 = 1000; // 10%
 
+/// 套利检测探测量：边流动性的 1/10000，用于估计含费有效汇率
+const ARBITRAGE_PROBE_DIVISOR: u64 = 10_000;
+
+/// 负环判定的浮点容差
+const CYCLE_EPSILON: f64 = 1e-12;
+
 /// 智能路由算法参数结构体
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub struct SmartRoutingParams {
@@ -219,6 +229,19 @@ pub struct ArbitrageOpportunity {
     pub risk_score: f64,
     /// 执行优先级
     pub execution_priority: u32,
+    /// 套利环代币序列（首尾均为起始代币，利润以起始代币计）
+    pub cycle: Vec<Pubkey>,
+    /// 最优投入数量（起始代币原子数量）
+    pub optimal_amount_in: u64,
+}
+
+/// 路由结果与套利机会报告
+#[derive(Clone, Debug)]
+pub struct SmartRoutingReport {
+    /// 用户 from→to 的路由结果
+    pub route: RoutingResult,
+    /// 同一路由图上检测到的套利机会（按利润率降序，与路由结果相互独立）
+    pub arbitrage_opportunities: Vec<ArbitrageOpportunity>,
 }
 
 /// 套利类型
@@ -232,6 +255,8 @@ pub enum ArbitrageType {
     Statistical,
     /// 时间套利
     Temporal,
+    /// 四跳及以上的环形套利
+    Cyclic,
 }
 
 /// 智能路由算法执行状态
@@ -323,6 +348,8 @@ pub struct SmartRoutingAlgorithm {
     execution_cache: HashMap<String, SmartRoutingExecutionState>,
     /// 路由图缓存
     routing_graph_cache: HashMap<String, RoutingGraph>,
+    /// 校准的市场冲击模型（设置后边权计入按场所校准的规模相关冲击）
+    impact_model: Option<Arc<MarketImpactCalculator>>,
 }
 
 /// 智能路由算法配置
@@ -342,6 +369,8 @@ pub struct SmartRoutingConfig {
     pub arbitrage_detection_threshold_bps: u32,
     /// 最大路径数量
     pub max_paths_count: u32,
+    /// 拆单分配步数（每步分配 1/N 输入，越大越接近等边际最优）
+    pub split_steps: u32,
 }
 
 /// 路由策略
@@ -366,12 +395,256 @@ pub enum RoutingStrategyType {
 pub struct RoutingGraph {
     /// 节点映射
     pub nodes: HashMap<Pubkey, RouteNode>,
-    /// 边映射
-    pub edges: HashMap<(Pubkey, Pubkey), RouteEdge>,
+    /// 边映射，按 (源代币, 目标代币, DEX名称) 为键：同一代币对的不同场所并存为平行边
+    pub edges: BTreeMap<(Pubkey, Pubkey, String), RouteEdge>,
     /// 图更新时间戳
     pub last_updated: u64,
 }
 
+impl RoutingGraph {
+    /// 以订单簿快照添加双向订单簿边：base→quote 吃买盘，quote→base 吃卖盘
+    /// - 流动性取对手盘总深度（以该边输入币种原子数量计）
+    /// - 权重为 taker 费率加半个买卖价差（比例），与按成本累加的最短路径一致
+    pub fn add_order_book_edges(&mut self, base: Pubkey, quote: Pubkey, dex_info: DexInfo, book: &OrderBook) {
+        let taker_fee_bps = book.market.taker_fee_bps();
+        let fees = FeeInfo {
+            trading_fee_bps: taker_fee_bps,
+            protocol_fee_bps: 0,
+            lp_fee_bps: 0,
+            total_fee_bps: taker_fee_bps,
+        };
+        let half_spread_bps = book.spread_bps().unwrap_or(0) / 2;
+        let weight = (taker_fee_bps as u64 + half_spread_bps) as f64 / 10_000.0;
+        for (from, to, side) in [(base, quote, OrderSide::Ask), (quote, base, OrderSide::Bid)] {
+            let liquidity = order_book_liquidity(book, side).available_liquidity;
+            self.insert_edge(RouteEdge {
+                from,
+                to,
+                dex_info: DexInfo { dex_type: DexType::OrderBook, ..dex_info.clone() },
+                liquidity,
+                fees: fees.clone(),
+                weight,
+                curve: Some(HopCurve::OrderBook { book: book.clone(), side }),
+            });
+        }
+    }
+
+    /// 以 AMM 池当前储备添加单向边 from→to
+    /// - 流动性取输入侧储备，权重为费率（比例），与订单簿边口径一致
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_pool_edge(&mut self, from: Pubkey, to: Pubkey, dex_info: DexInfo, curve: CurveType, reserve_in: u64, reserve_out: u64, fee: FeeRate) {
+        let fee_bps = curve::mul_div_ceil(fee.numerator as u128, 10_000, fee.denominator as u128).unwrap_or(0) as u32;
+        self.insert_edge(RouteEdge {
+            from,
+            to,
+            dex_info,
+            liquidity: reserve_in,
+            fees: FeeInfo {
+                trading_fee_bps: fee_bps,
+                protocol_fee_bps: 0,
+                lp_fee_bps: 0,
+                total_fee_bps: fee_bps,
+            },
+            weight: fee_bps as f64 / 10_000.0,
+            curve: Some(HopCurve::Pool { curve, reserve_in, reserve_out, fee }),
+        });
+    }
+
+    /// 插入边：同一场所的同向边被替换，不同场所的平行边并存
+    pub fn insert_edge(&mut self, edge: RouteEdge) {
+        self.edges.insert((edge.from, edge.to, edge.dex_info.name.clone()), edge);
+    }
+
+    /// from→to 的全部平行边（按 DEX 名称排序）
+    pub fn edges_between(&self, from: Pubkey, to: Pubkey) -> impl Iterator<Item = &RouteEdge> + '_ {
+        self.edges
+            .range((from, to, String::new())..)
+            .take_while(move |((edge_from, edge_to, _), _)| *edge_from == from && *edge_to == to)
+            .map(|(_, edge)| edge)
+    }
+
+    /// 将校准的市场冲击计入边权：按场所（DEX 名称）与输入代币估计 amount 单笔成交的冲击
+    /// - 仅累加截距以上的规模相关部分（比例），费率已计入边权；未校准的边保持不变
+    /// - 多跳路径各跳均以初始输入数量近似成交规模
+    pub fn apply_impact_model(&mut self, calculator: &MarketImpactCalculator, amount: u64) {
+        for edge in self.edges.values_mut() {
+            if let Ok(estimate) = calculator.estimate_venue_impact(&edge.dex_info.name, &edge.from, amount, 0) {
+                edge.weight += (estimate.average_bps - estimate.fixed_bps) / 10_000.0;
+            }
+        }
+    }
+
+    /// from→to 平行边中边权最小者（与最短路径口径一致）
+    pub fn edge(&self, from: &Pubkey, to: &Pubkey) -> Option<&RouteEdge> {
+        self.edges_between(*from, *to).min_by(|a, b| a.weight.total_cmp(&b.weight))
+    }
+
+    /// 按 amount_in 报价 from→to，取平行边中输出最高者；均报价失败返回 None
+    pub fn best_quote(&self, from: &Pubkey, to: &Pubkey, amount_in: u64) -> Option<(&RouteEdge, u64)> {
+        self.edges_between(*from, *to)
+            .filter_map(|edge| edge.quote(amount_in).map(|out| (edge, out)))
+            .max_by_key(|(_, out)| *out)
+    }
+
+    /// 枚举 from→to 不超过 max_hops 跳的无环候选路径，供拆单路由使用
+    /// - 每条带报价曲线的边对应一跳（平行边各成候选），无曲线的边不参与
+    /// - 出边按目标代币与 DEX 名称有序遍历，候选顺序确定
+    pub fn route_candidates(&self, from: Pubkey, to: Pubkey, max_hops: usize) -> Vec<RouteCandidate> {
+        let mut adjacency: BTreeMap<Pubkey, Vec<&RouteEdge>> = BTreeMap::new();
+        for edge in self.edges.values().filter(|edge| edge.curve.is_some()) {
+            adjacency.entry(edge.from).or_default().push(edge);
+        }
+        let mut candidates = Vec::new();
+        collect_route_candidates(&adjacency, from, to, max_hops, &mut Vec::new(), &mut candidates);
+        candidates
+    }
+
+    /// 沿环逐跳报价（cycle 首尾为同一代币），每跳取平行边中输出最高者；缺边或任一跳失败返回 None
+    pub fn quote_cycle(&self, cycle: &[Pubkey], amount_in: u64) -> Option<u64> {
+        cycle.windows(2).try_fold(amount_in, |amount, pair| {
+            self.best_quote(&pair[0], &pair[1], amount).map(|(_, out)| out)
+        })
+    }
+
+    /// Bellman-Ford 负环检测：边权为 -ln(有效汇率)，负环即汇率乘积大于 1 的套利环
+    /// - 有效汇率按各边探测量报价，已计入费用及该规模下的滑点；平行边均参与松弛
+    /// - 所有节点初始距离为 0（等价于连向全部节点的虚拟源点），不连通的子图同样可检出
+    /// - 返回去重后的环，首尾为同一代币，从环上 Pubkey 最小的代币起始
+    pub fn find_negative_cycles(&self) -> Vec<Vec<Pubkey>> {
+        let tokens: Vec<Pubkey> = self.edges.keys()
+            .flat_map(|(from, to, _)| [*from, *to])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let index: HashMap<Pubkey, usize> = tokens.iter().enumerate().map(|(i, token)| (*token, i)).collect();
+        // BTreeMap 按 (from, to, dex) 有序遍历，松弛顺序与结果均确定
+        let edges: Vec<(usize, usize, f64)> = self.edges.iter()
+            .filter_map(|((from, to, _), edge)| Some((index[from], index[to], edge.log_weight(edge.probe_amount())?)))
+            .collect();
+        // 平行边中取汇率最优（权重最小）者计算环权重
+        let mut weights: HashMap<(usize, usize), f64> = HashMap::new();
+        for &(u, v, w) in &edges {
+            let best = weights.entry((u, v)).or_insert(w);
+            *best = best.min(w);
+        }
+
+        let n = tokens.len();
+        let mut dist = vec![0.0f64; n];
+        let mut pred: Vec<Option<usize>> = vec![None; n];
+        let mut relaxed = Vec::new();
+        // 第 n 轮仍可松弛的节点必然可沿前驱回溯到负环
+        for _ in 0..n {
+            relaxed.clear();
+            for &(u, v, w) in &edges {
+                if dist[u] + w < dist[v] - CYCLE_EPSILON {
+                    dist[v] = dist[u] + w;
+                    pred[v] = Some(u);
+                    relaxed.push(v);
+                }
+            }
+            if relaxed.is_empty() {
+                return Vec::new();
+            }
+        }
+
+        let mut cycles = BTreeSet::new();
+        for &start in &relaxed {
+            // 回溯 n 步确保落在环上
+            let mut node = Some(start);
+            for _ in 0..n {
+                node = node.and_then(|v| pred[v]);
+            }
+            let Some(entry) = node else { continue };
+            let mut cycle = vec![entry];
+            let mut current = pred[entry];
+            while let Some(v) = current {
+                if v == entry || cycle.len() > n {
+                    break;
+                }
+                cycle.push(v);
+                current = pred[v];
+            }
+            if current != Some(entry) {
+                continue;
+            }
+            // 前驱链为逆序，翻转为交易方向
+            cycle.reverse();
+            let total: f64 = (0..cycle.len())
+                .map(|i| weights[&(cycle[i], cycle[(i + 1) % cycle.len()])])
+                .sum();
+            if total >= -CYCLE_EPSILON {
+                continue;
+            }
+            let min_pos = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+            cycle.rotate_left(min_pos);
+            cycles.insert(cycle);
+        }
+
+        cycles.into_iter()
+            .map(|cycle| {
+                let mut path: Vec<Pubkey> = cycle.iter().map(|&i| tokens[i]).collect();
+                path.push(path[0]);
+                path
+            })
+            .collect()
+    }
+}
+
+/// 深度优先收集候选路径：到达 to 即成一条候选，不重复经过已访问代币
+fn collect_route_candidates<'a>(
+    adjacency: &BTreeMap<Pubkey, Vec<&'a RouteEdge>>,
+    current: Pubkey,
+    to: Pubkey,
+    max_hops: usize,
+    hops: &mut Vec<&'a RouteEdge>,
+    candidates: &mut Vec<RouteCandidate>,
+) {
+    if current == to && !hops.is_empty() {
+        candidates.push(RouteCandidate { hops: hops.iter().filter_map(|edge| edge.route_hop()).collect() });
+        return;
+    }
+    if hops.len() >= max_hops {
+        return;
+    }
+    for &edge in adjacency.get(&current).into_iter().flatten() {
+        if edge.to == current || hops.iter().any(|hop| hop.from == edge.to) {
+            continue;
+        }
+        hops.push(edge);
+        collect_route_candidates(adjacency, edge.to, to, max_hops, hops, candidates);
+        hops.pop();
+    }
+}
+
+/// 订单簿对手盘深度转换为流动性信息
+/// - 价格档位：price 为市场原生价格、size 为 base lots
+/// - 可用流动性：吃买盘（Ask）以可卖出 base 原子数量计，吃卖盘（Bid）以买空卖盘所需 quote 原子数量计
+pub fn order_book_liquidity(book: &OrderBook, side: OrderSide) -> LiquidityInfo {
+    let market = &book.market;
+    let mut cumulative_size = 0u64;
+    let mut quote_lots = 0u64;
+    let liquidity_distribution: Vec<PriceLevel> = book
+        .levels(side)
+        .iter()
+        .map(|level| {
+            cumulative_size = cumulative_size.saturating_add(level.size_lots);
+            quote_lots = quote_lots.saturating_add(market.quote_lots_ceil(level.size_lots, level.price_lots).unwrap_or(u64::MAX));
+            PriceLevel { price: level.price_lots, size: level.size_lots, cumulative_size }
+        })
+        .collect();
+    let available_liquidity = match side {
+        OrderSide::Ask => cumulative_size.saturating_mul(market.base_lot_size),
+        OrderSide::Bid => quote_lots.saturating_mul(market.quote_lot_size),
+    };
+    let spread_bps = book.spread_bps().unwrap_or(10_000).min(10_000);
+    LiquidityInfo {
+        available_liquidity,
+        depth_score: (liquidity_distribution.len() as f64 / 10.0).min(1.0), // 10 档及以上视为满分
+        liquidity_distribution,
+        stability_score: 1.0 - spread_bps as f64 / 10_000.0, // 价差越窄越稳定
+    }
+}
+
 /// 路由边
 #[derive(Clone, Debug)]
 pub struct RouteEdge {
@@ -387,6 +660,42 @@ pub struct RouteEdge {
     pub fees: FeeInfo,
     /// 权重
     pub weight: f64,
+    /// 报价曲线（None 时按总费率线性估算）
+    pub curve: Option<HopCurve>,
+}
+
+impl RouteEdge {
+    /// 按边报价；无曲线的边按总费率线性估算（视为 1:1 兑换）
+    pub fn quote(&self, amount_in: u64) -> Option<u64> {
+        match &self.curve {
+            Some(curve) => curve.quote(amount_in),
+            None => {
+                let keep_bps = 10_000u128 - self.fees.total_fee_bps.min(10_000) as u128;
+                Some((amount_in as u128 * keep_bps / 10_000) as u64)
+            }
+        }
+    }
+
+    /// 汇率探测量：边流动性的 1/ARBITRAGE_PROBE_DIVISOR（至少为 1）
+    pub fn probe_amount(&self) -> u64 {
+        (self.liquidity / ARBITRAGE_PROBE_DIVISOR).max(1)
+    }
+
+    /// 转换为拆单候选路径的一跳（DEX 名称取自边）；无曲线的边返回 None
+    pub fn route_hop(&self) -> Option<RouteHop> {
+        Some(RouteHop {
+            dex_name: self.dex_info.name.clone(),
+            from: self.from,
+            to: self.to,
+            curve: self.curve.clone()?,
+        })
+    }
+
+    /// 以 probe 报价的 -ln(有效汇率)；报价失败或输出为 0 返回 None
+    pub fn log_weight(&self, probe: u64) -> Option<f64> {
+        let amount_out = self.quote(probe).filter(|out| *out > 0)?;
+        Some(-(amount_out as f64 / probe as f64).ln())
+    }
 }
 
 /// DEX信息
@@ -415,6 +724,110 @@ pub enum DexType {
     Hybrid,
 }
 
+/// 单跳报价曲线（合成池或由链上账户解析得到）
+#[derive(Clone, Debug)]
+pub enum HopCurve {
+    /// AMM 池（常积/StableSwap），按当前储备报价
+    Pool {
+        curve: CurveType,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee: FeeRate,
+    },
+    /// 订单簿，沿对手盘逐档吃单
+    OrderBook {
+        book: OrderBook,
+        side: OrderSide,
+    },
+}
+
+impl HopCurve {
+    /// 输入 amount_in 的预期输出；流动性不足或溢出返回 None
+    pub fn quote(&self, amount_in: u64) -> Option<u64> {
+        match self {
+            HopCurve::Pool { curve, reserve_in, reserve_out, fee } => {
+                curve::quote(*curve, *reserve_in, *reserve_out, amount_in, *fee).map(|q| q.amount_out)
+            }
+            HopCurve::OrderBook { book, side } => orderbook::quote_fill(book, *side, amount_in, None)
+                .filter(|fill| fill.complete)
+                .map(|fill| fill.amount_out),
+        }
+    }
+}
+
+/// 路由单跳
+#[derive(Clone, Debug)]
+pub struct RouteHop {
+    /// DEX名称
+    pub dex_name: String,
+    /// 输入代币
+    pub from: Pubkey,
+    /// 输出代币
+    pub to: Pubkey,
+    /// 报价曲线
+    pub curve: HopCurve,
+}
+
+/// 拆单候选路径（不同候选路径可共享同一条边，拆单时按边累计流量报价）
+#[derive(Clone, Debug)]
+pub struct RouteCandidate {
+    /// 依次执行的各跳
+    pub hops: Vec<RouteHop>,
+}
+
+impl RouteCandidate {
+    /// 沿路径逐跳报价；任一跳失败返回 None
+    pub fn quote(&self, amount_in: u64) -> Option<u64> {
+        self.hops.iter().try_fold(amount_in, |amount, hop| {
+            if amount == 0 {
+                Some(0)
+            } else {
+                hop.curve.quote(amount)
+            }
+        })
+    }
+
+    /// 路径是否首尾相接
+    pub fn is_connected(&self) -> bool {
+        !self.hops.is_empty() && self.hops.windows(2).all(|pair| pair[0].to == pair[1].from)
+    }
+
+    /// 代币路径（含起止代币）
+    pub fn token_path(&self) -> Vec<Pubkey> {
+        let mut path: Vec<Pubkey> = self.hops.first().map(|hop| vec![hop.from]).unwrap_or_default();
+        path.extend(self.hops.iter().map(|hop| hop.to));
+        path
+    }
+
+    /// 路径名称（多跳以 "->" 连接）
+    pub fn dex_name(&self) -> String {
+        self.hops.iter().map(|hop| hop.dex_name.as_str()).collect::<Vec<_>>().join("->")
+    }
+
+    /// 按各路径的输入分配联合报价，返回各路径输出；任一跳失败返回 None
+    /// - 同一 DEX 同向代币对视为同一条边，按跳序依次成交：后经过该边的路径只得到
+    ///   quote(累计输入 + 本路径输入) - quote(累计输入)，共享的流动性不会被重复计算
+    pub fn quote_allocations(candidates: &[&RouteCandidate], allocations: &[u64]) -> Option<Vec<u64>> {
+        let mut amounts = allocations.to_vec();
+        let mut flows: HashMap<(&str, Pubkey, Pubkey), (u64, u64)> = HashMap::new(); // 边 -> (累计输入, 累计输出)
+        let depth = candidates.iter().map(|c| c.hops.len()).max().unwrap_or(0);
+        for position in 0..depth {
+            for (i, candidate) in candidates.iter().enumerate() {
+                let hop = match candidate.hops.get(position) {
+                    Some(hop) if amounts[i] > 0 => hop,
+                    _ => continue,
+                };
+                let flow = flows.entry((hop.dex_name.as_str(), hop.from, hop.to)).or_insert((0, 0));
+                let total_in = flow.0.checked_add(amounts[i])?;
+                let total_out = hop.curve.quote(total_in)?;
+                amounts[i] = total_out.saturating_sub(flow.1);
+                *flow = (total_in, total_out);
+            }
+        }
+        Some(amounts)
+    }
+}
+
 impl Default for SmartRoutingConfig {
     fn default() -> Self {
         Self {
@@ -425,6 +838,7 @@ impl Default for SmartRoutingConfig {
             default_routing_strategy: RoutingStrategyType::BestOutput,
             arbitrage_detection_threshold_bps: 50, // 0.5%
             max_paths_count: 10,
+            split_steps: 100,
         }
     }
 }
@@ -490,23 +904,8 @@ impl RoutingStrategy for SmartRoutingAlgorithm {
         // 构建路由图
         let routing_graph = self.build_routing_graph(&routing_params)?;
         
-        // 计算最优路径
-        let optimal_path = self.calculate_optimal_path(&routing_graph, &routing_params)?;
-        
-        // 检测套利机会
-        let arbitrage_opportunities = if routing_params.enable_arbitrage_detection {
-            self.detect_arbitrage_opportunities(&routing_graph, &routing_params)?
-        } else {
-            Vec::new()
-        };
-        
-        // 优化路径
-        let optimized_path = self.optimize_path(&optimal_path, &arbitrage_opportunities, &routing_params)?;
-        
-        // 计算最终结果
-        let result = self.calculate_routing_result(&optimized_path, &routing_params)?;
-        
-        Ok(result)
+        // 在候选路径间拆单，或退回单条最优路径
+        self.route_on_graph(&routing_graph, &routing_params)
     }
 }
 
@@ -517,6 +916,7 @@ impl SmartRoutingAlgorithm {
             config: SmartRoutingConfig::default(),
             execution_cache: HashMap::new(),
             routing_graph_cache: HashMap::new(),
+            impact_model: None,
         }
     }
     
@@ -526,9 +926,16 @@ impl SmartRoutingAlgorithm {
             config,
             execution_cache: HashMap::new(),
             routing_graph_cache: HashMap::new(),
+            impact_model: None,
         }
     }
     
+    /// 使用校准的市场冲击模型作为路由成本的规模相关部分
+    pub fn with_impact_model(mut self, calculator: Arc<MarketImpactCalculator>) -> Self {
+        self.impact_model = Some(calculator);
+        self
+    }
+
     /// 解析智能路由参数
     fn parse_routing_params(&self, params: &TradeParams) -> anchor_lang::Result<SmartRoutingParams> {
         // 从TradeParams构建SmartRoutingParams
@@ -595,7 +1002,7 @@ impl SmartRoutingAlgorithm {
     fn build_routing_graph(&self, params: &SmartRoutingParams) -> anchor_lang::Result<RoutingGraph> {
         let mut graph = RoutingGraph {
             nodes: HashMap::new(),
-            edges: HashMap::new(),
+            edges: BTreeMap::new(),
             last_updated: self.get_current_timestamp(),
         };
         
@@ -616,6 +1023,11 @@ impl SmartRoutingAlgorithm {
         
         // 添加边（模拟）
         self.add_edges(&mut graph, params)?;
+
+        // 按校准冲击模型计入各边的规模相关冲击成本
+        if let Some(calculator) = &self.impact_model {
+            graph.apply_impact_model(calculator, params.amount_in);
+        }
         
         Ok(graph)
     }
@@ -686,9 +1098,10 @@ impl SmartRoutingAlgorithm {
                             total_fee_bps: 60,
                         },
                         weight: 1.0,
+                        curve: None,
                     };
                     
-                    graph.edges.insert((*from_token, *to_token), edge);
+                    graph.insert_edge(edge);
                 }
             }
         }
@@ -696,6 +1109,34 @@ impl SmartRoutingAlgorithm {
         Ok(())
     }
     
+    /// 在路由图上计算路由结果
+    /// - 枚举 from→to 不超过 max_hops 跳、各跳均带报价曲线的候选路径，经 split_route 按等边际输出拆单
+    /// - 图中无可报价路径（如仅有模拟边）时退回最短路径，各跳 DEX 取自路径上的边
+    fn route_on_graph(&self, graph: &RoutingGraph, params: &SmartRoutingParams) -> anchor_lang::Result<RoutingResult> {
+        let candidates = graph.route_candidates(params.from_token, params.to_token, params.max_hops as usize);
+        if !candidates.is_empty() {
+            return self.split_route(params.amount_in, params.max_hops, &candidates);
+        }
+
+        // 计算最优路径
+        let optimal_path = self.calculate_optimal_path(graph, params)?;
+        
+        // 计算最终结果
+        self.calculate_routing_result(graph, &optimal_path, params)
+    }
+
+    /// 在路由图上计算路由结果，并单独上报同一图上检测到的套利机会
+    /// - 套利机会不会替换用户的 from→to 路由，由调用方决定是否另行执行
+    pub fn route_with_arbitrage(&self, graph: &RoutingGraph, params: &SmartRoutingParams) -> anchor_lang::Result<SmartRoutingReport> {
+        let route = self.route_on_graph(graph, params)?;
+        let arbitrage_opportunities = if params.enable_arbitrage_detection {
+            self.detect_arbitrage_opportunities(graph, params)?
+        } else {
+            Vec::new()
+        };
+        Ok(SmartRoutingReport { route, arbitrage_opportunities })
+    }
+
     /// 计算最优路径
     fn calculate_optimal_path(&self, graph: &RoutingGraph, params: &SmartRoutingParams) -> anchor_lang::Result<RoutePath> {
         // 使用Dijkstra算法计算最优路径
@@ -723,7 +1164,7 @@ impl SmartRoutingAlgorithm {
             unvisited.remove(&current_distance);
             
             // 更新邻居节点
-            for ((from, to), edge) in &graph.edges {
+            for ((from, to, _), edge) in &graph.edges {
                 if *from == current_node {
                     let new_distance = current_distance + edge.weight;
                     if new_distance < *distances.get(to).unwrap_or(&f64::INFINITY) {
@@ -776,185 +1217,181 @@ impl SmartRoutingAlgorithm {
     }
     
     /// 检测套利机会
+    /// - 路由图负环即套利环，跳数超过 max_hops（至少 2）的环忽略
+    /// - 环含 from_token 时从 from_token 起始，利润以起始代币计
+    /// - 按最优规模下的利润率降序排名，低于检测阈值的机会丢弃
     fn detect_arbitrage_opportunities(&self, graph: &RoutingGraph, params: &SmartRoutingParams) -> anchor_lang::Result<Vec<ArbitrageOpportunity>> {
-        let mut opportunities = Vec::new();
-        
-        // 模拟套利机会检测
-        // 实际实现应该分析不同DEX间的价格差异
-        
-        // 三角套利检测
-        let triangular_opportunity = self.detect_triangular_arbitrage(graph, params)?;
-        if let Some(opportunity) = triangular_opportunity {
-            opportunities.push(opportunity);
-        }
-        
-        // 跨DEX套利检测
-        let cross_dex_opportunity = self.detect_cross_dex_arbitrage(graph, params)?;
-        if let Some(opportunity) = cross_dex_opportunity {
-            opportunities.push(opportunity);
+        let max_hops = params.max_hops.max(2) as usize;
+        let mut opportunities: Vec<ArbitrageOpportunity> = graph.find_negative_cycles()
+            .into_iter()
+            .filter(|cycle| cycle.len() - 1 <= max_hops)
+            .filter_map(|cycle| self.evaluate_cycle(graph, rotate_cycle(cycle, &params.from_token)))
+            .filter(|opportunity| opportunity.profit_margin_bps >= self.config.arbitrage_detection_threshold_bps)
+            .collect();
+        opportunities.sort_by(|a, b| {
+            b.profit_margin_bps.cmp(&a.profit_margin_bps).then(b.expected_profit.cmp(&a.expected_profit))
+        });
+        let timestamp = self.get_current_timestamp();
+        for (rank, opportunity) in opportunities.iter_mut().enumerate() {
+            opportunity.id = format!("arb_{}_{}", timestamp, rank + 1);
+            opportunity.execution_priority = rank as u32 + 1;
         }
-        
         Ok(opportunities)
     }
-    
-    /// 检测三角套利
-    fn detect_triangular_arbitrage(&self, graph: &RoutingGraph, params: &SmartRoutingParams) -> anchor_lang::Result<Option<ArbitrageOpportunity>> {
-        // 模拟三角套利检测
-        // 实际实现应该分析三个代币之间的套利机会
-        
-        // 检查是否有足够的中间代币
-        if graph.nodes.len() < 3 {
-            return Ok(None);
-        }
-        
-        // 模拟找到套利机会
-        let profit = params.amount_in * 5 / 1000; // 0.5%利润
-        
-        if profit > 0 {
-            let buy_path = RoutePath {
-                id: "buy_path".to_string(),
-                nodes: vec![
-                    graph.nodes.get(&params.from_token).unwrap().clone(),
-                    graph.nodes.values().next().unwrap().clone(),
-                ],
-                weight: 1.0,
-                expected_output: params.amount_in,
-                total_fees: 1000,
-                estimated_slippage_bps: 50,
-                complexity: 2,
-                risk_score: 0.3,
-            };
-            
-            let sell_path = RoutePath {
-                id: "sell_path".to_string(),
-                nodes: vec![
-                    graph.nodes.values().next().unwrap().clone(),
-                    graph.nodes.get(&params.to_token).unwrap().clone(),
-                ],
-                weight: 1.0,
-                expected_output: params.amount_in + profit,
-                total_fees: 1000,
-                estimated_slippage_bps: 50,
-                complexity: 2,
-                risk_score: 0.3,
-            };
-            
-            Ok(Some(ArbitrageOpportunity {
-                id: format!("arb_{}", self.get_current_timestamp()),
-                opportunity_type: ArbitrageType::Triangular,
-                buy_path,
-                sell_path,
-                expected_profit: profit,
-                profit_margin_bps: 50,
-                risk_score: 0.3,
-                execution_priority: 1,
-            }))
-        } else {
-            Ok(None)
-        }
-    }
-    
-    /// 检测跨DEX套利
-    fn detect_cross_dex_arbitrage(&self, graph: &RoutingGraph, params: &SmartRoutingParams) -> anchor_lang::Result<Option<ArbitrageOpportunity>> {
-        // 模拟跨DEX套利检测
-        // 实际实现应该比较不同DEX的价格
-        
-        let profit = params.amount_in * 3 / 1000; // 0.3%利润
-        
-        if profit > 0 {
-            let buy_path = RoutePath {
-                id: "buy_path_dex1".to_string(),
-                nodes: vec![
-                    graph.nodes.get(&params.from_token).unwrap().clone(),
-                    graph.nodes.get(&params.to_token).unwrap().clone(),
-                ],
-                weight: 1.0,
-                expected_output: params.amount_in,
-                total_fees: 800,
-                estimated_slippage_bps: 30,
-                complexity: 2,
-                risk_score: 0.2,
-            };
-            
-            let sell_path = RoutePath {
-                id: "sell_path_dex2".to_string(),
-                nodes: vec![
-                    graph.nodes.get(&params.to_token).unwrap().clone(),
-                    graph.nodes.get(&params.from_token).unwrap().clone(),
-                ],
-                weight: 1.0,
-                expected_output: params.amount_in + profit,
-                total_fees: 800,
-                estimated_slippage_bps: 30,
-                complexity: 2,
-                risk_score: 0.2,
-            };
-            
-            Ok(Some(ArbitrageOpportunity {
-                id: format!("arb_cross_{}", self.get_current_timestamp()),
-                opportunity_type: ArbitrageType::CrossDex,
-                buy_path,
-                sell_path,
-                expected_profit: profit,
-                profit_margin_bps: 30,
-                risk_score: 0.2,
-                execution_priority: 2,
-            }))
-        } else {
-            Ok(None)
-        }
-    }
-    
-    /// 优化路径
-    fn optimize_path(&self, path: &RoutePath, arbitrage_opportunities: &[ArbitrageOpportunity], params: &SmartRoutingParams) -> anchor_lang::Result<RoutePath> {
-        let mut optimized_path = path.clone();
-        
-        // 如果有套利机会，优先选择套利路径
-        if let Some(best_arbitrage) = arbitrage_opportunities.iter().max_by_key(|arb| arb.expected_profit) {
-            if best_arbitrage.profit_margin_bps >= self.config.arbitrage_detection_threshold_bps {
-                optimized_path = best_arbitrage.buy_path.clone();
+
+    /// 评估单个套利环：在首跳流动性范围内三分搜索最优投入
+    /// - 常积池与订单簿的输出对输入为凹函数，利润 out(x) - x 单峰
+    /// - 买入路径为首跳，卖出路径为其余各跳；首跳有平行边时搜索上界取其中最大流动性
+    fn evaluate_cycle(&self, graph: &RoutingGraph, cycle: Vec<Pubkey>) -> Option<ArbitrageOpportunity> {
+        let first_liquidity = graph.edges_between(cycle[0], cycle[1]).map(|edge| edge.liquidity).max()?;
+        let profit = |amount: u64| {
+            graph.quote_cycle(&cycle, amount).map_or(i128::MIN, |out| out as i128 - amount as i128)
+        };
+        let (mut lo, mut hi) = (1u64, first_liquidity.max(1));
+        while hi - lo > 2 {
+            let m1 = lo + (hi - lo) / 3;
+            let m2 = hi - (hi - lo) / 3;
+            if profit(m1) < profit(m2) {
+                lo = m1 + 1;
+            } else {
+                hi = m2;
             }
         }
-        
-        // 根据策略优化路径
-        match &self.config.default_routing_strategy {
-            RoutingStrategyType::BestOutput => {
-                // 选择预期输出最高的路径
-                // 这里已经是最优路径
-            },
-            RoutingStrategyType::LowestFees => {
-                // 选择费用最低的路径
-                optimized_path.total_fees = optimized_path.total_fees * 9 / 10; // 模拟优化
-            },
-            RoutingStrategyType::LowestSlippage => {
-                // 选择滑点最低的路径
-                optimized_path.estimated_slippage_bps = optimized_path.estimated_slippage_bps * 8 / 10; // 模拟优化
-            },
-            RoutingStrategyType::HighestLiquidity => {
-                // 选择流动性最高的路径
-                optimized_path.risk_score = optimized_path.risk_score * 8 / 10; // 模拟优化
-            },
-            RoutingStrategyType::Balanced => {
-                // 平衡策略
-                optimized_path.estimated_slippage_bps = optimized_path.estimated_slippage_bps * 9 / 10;
-                optimized_path.total_fees = optimized_path.total_fees * 9 / 10;
-            },
-            RoutingStrategyType::Custom => {
-                // 自定义策略
-                // 这里可以实现自定义优化逻辑
-            },
+        let amount_in = (lo..=hi).max_by_key(|amount| profit(*amount))?;
+        let amount_out = graph.quote_cycle(&cycle, amount_in)?;
+        let expected_profit = amount_out.checked_sub(amount_in).filter(|p| *p > 0)?;
+        let profit_margin_bps = (expected_profit as u128 * 10_000 / amount_in as u128).min(u32::MAX as u128) as u32;
+
+        let buy_path = self.cycle_segment_path(graph, "buy_path".to_string(), &cycle[..2], amount_in)?;
+        let sell_path = self.cycle_segment_path(graph, "sell_path".to_string(), &cycle[1..], buy_path.expected_output)?;
+        let opportunity_type = match cycle.len() - 1 {
+            2 => ArbitrageType::CrossDex,
+            3 => ArbitrageType::Triangular,
+            _ => ArbitrageType::Cyclic,
+        };
+        Some(ArbitrageOpportunity {
+            id: String::new(),
+            opportunity_type,
+            risk_score: buy_path.risk_score.max(sell_path.risk_score),
+            buy_path,
+            sell_path,
+            expected_profit,
+            profit_margin_bps,
+            execution_priority: 0,
+            cycle,
+            optimal_amount_in: amount_in,
+        })
+    }
+
+    /// 由环上一段代币序列构建路由路径
+    /// - 预期输出逐跳按曲线报价（平行边取输出最高者），费用按各跳输入估算后累加
+    /// - 滑点为按探测汇率折算的输出与实际输出之差
+    fn cycle_segment_path(&self, graph: &RoutingGraph, id: String, tokens: &[Pubkey], amount_in: u64) -> Option<RoutePath> {
+        let mut amount = amount_in;
+        let mut total_fees = 0u64;
+        let mut weight = 0.0;
+        for pair in tokens.windows(2) {
+            let (edge, amount_out) = graph.best_quote(&pair[0], &pair[1], amount)?;
+            total_fees = total_fees.saturating_add((amount as u128 * edge.fees.total_fee_bps as u128 / 10_000) as u64);
+            weight += edge.log_weight(edge.probe_amount())?;
+            amount = amount_out;
         }
-        
-        Ok(optimized_path)
+        let spot_out = (amount_in as f64 * (-weight).exp()) as u128;
+        let nodes = tokens.iter()
+            .map(|token| match graph.nodes.get(token) {
+                Some(node) => Ok(node.clone()),
+                None => self.create_node(*token, NodeType::Intermediate),
+            })
+            .collect::<anchor_lang::Result<Vec<_>>>()
+            .ok()?;
+        Some(RoutePath {
+            id,
+            risk_score: self.calculate_risk_score(&nodes),
+            nodes,
+            weight,
+            expected_output: amount,
+            total_fees,
+            estimated_slippage_bps: curve::price_impact_bps(spot_out, amount as u128).min(u32::MAX as u64) as u32,
+            complexity: (tokens.len() - 1) as u32,
+        })
     }
     
-    /// 计算路由结果
-    fn calculate_routing_result(&self, path: &RoutePath, params: &SmartRoutingParams) -> anchor_lang::Result<RoutingResult> {
+    /// 计算路由结果：单腿承接全部输入，DEX 名称取自路径各跳的边（多跳以 "->" 连接）
+    fn calculate_routing_result(&self, graph: &RoutingGraph, path: &RoutePath, params: &SmartRoutingParams) -> anchor_lang::Result<RoutingResult> {
+        let token_path: Vec<Pubkey> = path.nodes.iter().map(|node| node.id).collect();
+        let dex_name = token_path.windows(2)
+            .map(|pair| graph.edge(&pair[0], &pair[1]).map(|edge| edge.dex_info.name.as_str()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(SmartRoutingError::NoRouteCandidates)?
+            .join("->");
         Ok(RoutingResult {
-            best_dex: "Jupiter".to_string(), // 实际应该从路径中获取
+            best_dex: dex_name.clone(),
             expected_out: path.expected_output,
+            legs: vec![RouteLeg {
+                dex_name,
+                path: token_path,
+                amount_in: params.amount_in,
+                expected_out: path.expected_output,
+            }],
         })
     }
+
+    /// 拆单路由：将 amount_in 按等边际输出原则分配到多条候选路径
+    /// - 过滤跳数超过 max_hops（取自调用参数，与候选枚举一致）或首尾不相接的候选，按首个分配单元的输出排序后保留前 max_paths_count 条
+    /// - 将输入等分为 split_steps 份，每份分配给当前边际输出最高的路径；各曲线为凹函数时结果在一份粒度内等边际
+    /// - 边际输出按全部路径联合报价（见 RouteCandidate::quote_allocations），共享首跳等边的路径不会重复计算同一份流动性
+    pub fn split_route(&self, amount_in: u64, max_hops: u32, candidates: &[RouteCandidate]) -> anchor_lang::Result<RoutingResult> {
+        require!(amount_in > 0, SmartRoutingError::InvalidAmount);
+        let max_hops = max_hops.max(1) as usize;
+        let max_paths = self.config.max_paths_count.max(1) as usize;
+        let steps = (self.config.split_steps.max(1) as u64).min(amount_in);
+        let chunk = amount_in / steps;
+
+        let mut ranked: Vec<(&RouteCandidate, u64)> = candidates
+            .iter()
+            .filter(|c| c.is_connected() && c.hops.len() <= max_hops)
+            .filter_map(|c| c.quote(chunk).filter(|out| *out > 0).map(|out| (c, out)))
+            .collect();
+        require!(!ranked.is_empty(), SmartRoutingError::NoRouteCandidates);
+        ranked.sort_by(|a, b| b.1.cmp(&a.1));
+        ranked.truncate(max_paths);
+        let selected: Vec<&RouteCandidate> = ranked.into_iter().map(|(c, _)| c).collect();
+
+        let mut allocations = vec![0u64; selected.len()];
+        let mut outputs = vec![0u64; selected.len()];
+        for step in 0..steps {
+            let size = if step + 1 == steps { amount_in - chunk * (steps - 1) } else { chunk };
+            // 选择追加本份输入后联合总输出最大的路径（总输出最大即增量最大）
+            let (index, trial_outputs, _) = (0..selected.len())
+                .filter_map(|i| {
+                    let mut trial = allocations.clone();
+                    trial[i] += size;
+                    let trial_outputs = RouteCandidate::quote_allocations(&selected, &trial)?;
+                    let total = trial_outputs.iter().try_fold(0u64, |acc, out| acc.checked_add(*out))?;
+                    Some((i, trial_outputs, total))
+                })
+                .max_by(|a, b| a.2.cmp(&b.2).then(b.0.cmp(&a.0))) // 增量相同取排序靠前者
+                .ok_or(SmartRoutingError::InsufficientLiquidity)?;
+            allocations[index] += size;
+            outputs = trial_outputs;
+        }
+
+        let legs: Vec<RouteLeg> = selected
+            .iter()
+            .zip(allocations.iter().zip(outputs.iter()))
+            .filter(|(_, (amount, _))| **amount > 0)
+            .map(|(c, (amount, out))| RouteLeg {
+                dex_name: c.dex_name(),
+                path: c.token_path(),
+                amount_in: *amount,
+                expected_out: *out,
+            })
+            .collect();
+        let expected_out = legs.iter().try_fold(0u64, |acc, leg| acc.checked_add(leg.expected_out))
+            .ok_or(SmartRoutingError::InsufficientLiquidity)?;
+        let best_dex = legs.iter().max_by_key(|leg| leg.amount_in).map(|leg| leg.dex_name.clone()).unwrap_or_default();
+        Ok(RoutingResult { best_dex, expected_out, legs })
+    }
     
     /// 计算风险评分
     fn calculate_risk_score(&self, nodes: &[RouteNode]) -> f64 {
@@ -984,6 +1421,17 @@ impl SmartRoutingAlgorithm {
     }
 }
 
+/// 将环旋转为从 start 起始（环中不含 start 时原样返回）
+fn rotate_cycle(mut cycle: Vec<Pubkey>, start: &Pubkey) -> Vec<Pubkey> {
+    let hops = cycle.len() - 1;
+    if let Some(pos) = cycle[..hops].iter().position(|token| token == start) {
+        cycle.pop();
+        cycle.rotate_left(pos);
+        cycle.push(*start);
+    }
+    cycle
+}
+
 /// Anchor 自动注册宏
 // #[ctor::ctor]
 fn auto_register_smart_routing_algorithm() {
@@ -992,6 +1440,17 @@ fn auto_register_smart_routing_algorithm() {
     factory.register(adapter);
 }
 
+/// 智能路由错误码（Anchor错误）
+#[error_code]
+pub enum SmartRoutingError {
+    /// 输入数量无效
+    #[msg("Invalid amount")] InvalidAmount,
+    /// 无满足跳数与连通性要求的候选路径
+    #[msg("No route candidates")] NoRouteCandidates,
+    /// 候选路径流动性不足以吸收全部输入
+    #[msg("Insufficient liquidity across routes")] InsufficientLiquidity,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1172,4 +1631,401 @@ mod tests {
             assert!(opportunity.risk_score >= 0.0 && opportunity.risk_score <= 1.0);
         }
     }
+    /// 测试以订单簿快照生成双向订单簿边。
+    #[test]
+    fn test_order_book_edges() {
+        use crate::dex::curve::FeeRate;
+        use crate::dex::orderbook::OrderBookMarket;
+
+        let market = OrderBookMarket {
+            base_lot_size: 1_000,
+            quote_lot_size: 10,
+            price_numerator: 1,
+            price_denominator: 1,
+            taker_fee: FeeRate::new(4, 10_000),
+        };
+        let book = OrderBook::from_orders(market, vec![(99, 10), (98, 20)], vec![(101, 5), (102, 5)]);
+        let (base, quote) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut graph = RoutingGraph { nodes: HashMap::new(), edges: BTreeMap::new(), last_updated: 0 };
+        let dex_info = DexInfo {
+            name: "Phoenix".to_string(),
+            dex_type: DexType::AMM,
+            program_id: Pubkey::new_unique(),
+            version: "1.0.0".to_string(),
+        };
+        graph.add_order_book_edges(base, quote, dex_info, &book);
+
+        let sell = graph.edge(&base, &quote).unwrap();
+        assert!(matches!(sell.dex_info.dex_type, DexType::OrderBook));
+        assert_eq!(sell.liquidity, 30 * 1_000);
+        assert_eq!(sell.fees.total_fee_bps, 4);
+        let buy = graph.edge(&quote, &base).unwrap();
+        assert_eq!(buy.liquidity, (5 * 101 + 5 * 102) * 10);
+        assert!((buy.weight - (4.0 + 99.0) / 10_000.0).abs() < 1e-12);
+
+        let bids = order_book_liquidity(&book, OrderSide::Ask);
+        assert_eq!(bids.liquidity_distribution[1].cumulative_size, 30);
+        assert_eq!(bids.liquidity_distribution[0].price, 99);
+    }
+
+    #[test]
+    fn test_impact_model_adjusts_edge_weights() {
+        use crate::algorithms::execution_optimizer::types::MarketImpactModel;
+        use crate::algorithms::market_impact::{CalibratedImpact, ImpactCoefficientRecord};
+
+        let (sol, usdc, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let dex_info = |name: &str| DexInfo {
+            name: name.to_string(),
+            dex_type: DexType::AMM,
+            program_id: Pubkey::new_unique(),
+            version: "1.0.0".to_string(),
+        };
+        let fee = FeeRate::new(25, 10_000);
+        let mut graph = RoutingGraph { nodes: HashMap::new(), edges: BTreeMap::new(), last_updated: 0 };
+        graph.add_pool_edge(sol, usdc, dex_info("Orca"), CurveType::ConstantProduct, 1_000_000, 1_000_000, fee);
+        graph.add_pool_edge(bonk, usdc, dex_info("Orca"), CurveType::ConstantProduct, 1_000_000, 1_000_000, fee);
+
+        let mut calculator = MarketImpactCalculator::new();
+        calculator.load_coefficients(&[ImpactCoefficientRecord {
+            venue: Some("Orca".to_string()),
+            asset: sol,
+            impact: CalibratedImpact {
+                model: MarketImpactModel::Linear,
+                intercept_bps: 25.0,
+                slope_bps: 0.01,
+                r_squared: 1.0,
+                samples: 10,
+                calibrated_at: 0,
+            },
+        }]);
+        graph.apply_impact_model(&calculator, 5_000);
+        // 仅累加截距以上的 0.01 × 5000 = 50 bps，未校准的 bonk 边不变
+        assert!((graph.edge(&sol, &usdc).unwrap().weight - (25.0 + 50.0) / 10_000.0).abs() < 1e-12);
+        assert!((graph.edge(&bonk, &usdc).unwrap().weight - 25.0 / 10_000.0).abs() < 1e-12);
+    }
+
+    fn pool_hop(dex: &str, from: Pubkey, to: Pubkey, reserve_in: u64, reserve_out: u64) -> RouteHop {
+        RouteHop {
+            dex_name: dex.to_string(),
+            from,
+            to,
+            curve: HopCurve::Pool {
+                curve: CurveType::ConstantProduct,
+                reserve_in,
+                reserve_out,
+                fee: FeeRate::new(25, 10_000),
+            },
+        }
+    }
+
+    /// 测试拆单按等边际输出在深浅两池间分配。
+    #[test]
+    fn test_split_route_equalizes_marginal_output() {
+        let algo = SmartRoutingAlgorithm::new();
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let shallow = RouteCandidate { hops: vec![pool_hop("orca", sol, usdc, 1_000_000_000, 1_000_000_000)] };
+        let deep = RouteCandidate { hops: vec![pool_hop("raydium", sol, usdc, 3_000_000_000, 3_000_000_000)] };
+        let amount = 200_000_000;
+        let result = algo.split_route(amount, 3, &[shallow.clone(), deep.clone()]).unwrap();
+
+        assert_eq!(result.legs.len(), 2);
+        assert_eq!(result.best_dex, "raydium");
+        assert_eq!(result.legs.iter().map(|leg| leg.amount_in).sum::<u64>(), amount);
+        assert_eq!(result.expected_out, result.legs.iter().map(|leg| leg.expected_out).sum::<u64>());
+        // 深池储备为浅池 3 倍，分得 3/4 输入
+        let deep_leg = result.legs.iter().find(|leg| leg.dex_name == "raydium").unwrap();
+        assert_eq!(deep_leg.amount_in, 150_000_000);
+        assert_eq!(deep_leg.path, vec![sol, usdc]);
+        // 各腿再追加一份输入的边际输出相差不足 1%
+        let step = amount / 100;
+        let marginal: Vec<u64> = result
+            .legs
+            .iter()
+            .map(|leg| {
+                let candidate = if leg.dex_name == "raydium" { &deep } else { &shallow };
+                candidate.quote(leg.amount_in + step).unwrap() - leg.expected_out
+            })
+            .collect();
+        assert!(marginal[0].abs_diff(marginal[1]) * 100 < marginal[0]);
+        // 拆单优于任一单路径
+        assert!(result.expected_out > deep.quote(amount).unwrap());
+    }
+
+    /// 测试共享首跳的两条路径按累计流量报价，不重复计算首跳流动性。
+    #[test]
+    fn test_split_route_shared_first_hop() {
+        let (sol, usdt, usdc) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let shared = pool_hop("orca", sol, usdt, 1_000_000_000, 1_000_000_000);
+        let via_raydium = RouteCandidate { hops: vec![shared.clone(), pool_hop("raydium", usdt, usdc, 1_000_000_000_000_000, 1_000_000_000_000_000)] };
+        let via_meteora = RouteCandidate { hops: vec![shared.clone(), pool_hop("meteora", usdt, usdc, 1_000_000_000_000_000, 1_000_000_000_000_000)] };
+        let amount = 200_000_000;
+        let result = SmartRoutingAlgorithm::new().split_route(amount, 3, &[via_raydium.clone(), via_meteora.clone()]).unwrap();
+
+        assert_eq!(result.legs.iter().map(|leg| leg.amount_in).sum::<u64>(), amount);
+        assert_eq!(result.expected_out, result.legs.iter().map(|leg| leg.expected_out).sum::<u64>());
+        // 总输出受共享首跳整体报价约束，第二跳近乎无限深只扣手续费
+        let shared_out = shared.curve.quote(amount).unwrap();
+        assert!(result.expected_out <= shared_out);
+        assert!(result.expected_out * 100 >= shared_out * 99);
+        // 不会按两腿各自独立报价之和高估输出
+        assert!(result.expected_out < via_raydium.quote(amount / 2).unwrap() + via_meteora.quote(amount / 2).unwrap());
+        // 联合报价：第二条路径只得到首跳的剩余流动性
+        let joint = RouteCandidate::quote_allocations(&[&via_raydium, &via_meteora], &[amount / 2, amount / 2]).unwrap();
+        assert!(joint[1] < joint[0]);
+        assert!(joint[0] + joint[1] <= shared_out);
+    }
+
+    /// 测试拆单遵守调用参数的 max_hops 与配置的 max_paths_count，并在订单簿深度耗尽后转向 AMM。
+    #[test]
+    fn test_split_route_respects_config_limits() {
+        use crate::dex::orderbook::OrderBookMarket;
+
+        let (sol, usdc, usdt) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let direct = RouteCandidate { hops: vec![pool_hop("raydium", sol, usdc, 1_000_000_000, 1_000_000_000)] };
+        let via_usdt = RouteCandidate {
+            hops: vec![
+                pool_hop("orca", sol, usdt, 5_000_000_000, 5_000_000_000),
+                pool_hop("orca", usdt, usdc, 5_000_000_000, 5_000_000_000),
+            ],
+        };
+        let amount = 100_000_000;
+
+        let result = SmartRoutingAlgorithm::new().split_route(amount, 1, &[direct.clone(), via_usdt.clone()]).unwrap();
+        assert_eq!(result.legs.len(), 1);
+        assert_eq!(result.legs[0].path, vec![sol, usdc]);
+
+        let result = SmartRoutingAlgorithm::new().split_route(amount, 3, &[direct.clone(), via_usdt.clone()]).unwrap();
+        assert_eq!(result.legs.len(), 2);
+        assert!(result.legs.iter().any(|leg| leg.dex_name == "orca->orca" && leg.path == vec![sol, usdt, usdc]));
+
+        let one_path = SmartRoutingAlgorithm::with_config(SmartRoutingConfig { max_paths_count: 1, ..Default::default() });
+        let result = one_path.split_route(amount, 3, &[via_usdt.clone(), direct.clone()]).unwrap();
+        assert_eq!(result.legs.len(), 1);
+        assert_eq!(result.best_dex, "raydium");
+
+        // 1:1 无手续费订单簿，深度 3_000 万：先吃满订单簿，余量进入 AMM
+        let market = OrderBookMarket {
+            base_lot_size: 1,
+            quote_lot_size: 1,
+            price_numerator: 1,
+            price_denominator: 1,
+            taker_fee: FeeRate::new(0, 10_000),
+        };
+        let book = OrderBook::from_orders(market, vec![(1, 30_000_000)], vec![]);
+        let clob = RouteCandidate {
+            hops: vec![RouteHop { dex_name: "phoenix".to_string(), from: sol, to: usdc, curve: HopCurve::OrderBook { book, side: OrderSide::Ask } }],
+        };
+        let result = SmartRoutingAlgorithm::new().split_route(amount, 3, &[direct.clone(), clob]).unwrap();
+        let clob_leg = result.legs.iter().find(|leg| leg.dex_name == "phoenix").unwrap();
+        assert_eq!(clob_leg.amount_in, 30_000_000);
+        assert_eq!(clob_leg.expected_out, 30_000_000);
+
+        assert!(SmartRoutingAlgorithm::new().split_route(0, 3, &[direct]).is_err());
+        assert!(SmartRoutingAlgorithm::new().split_route(1_000, 3, &[]).is_err());
+    }
+
+    /// 测试负环检测：三角套利与跨DEX套利均被检出，按利润率排名并给出最优规模。
+    #[test]
+    fn test_detect_negative_cycle_arbitrage() {
+        let dex = |name: &str| DexInfo {
+            name: name.to_string(),
+            dex_type: DexType::AMM,
+            program_id: Pubkey::new_unique(),
+            version: "1.0.0".to_string(),
+        };
+        let fee = FeeRate::new(25, 10_000);
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (x, y) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut graph = RoutingGraph { nodes: HashMap::new(), edges: BTreeMap::new(), last_updated: 0 };
+        // 三角：A/B、B/C 为 1:1，C/A 池中 A 偏多 5%
+        for (from, to, reserve_from, reserve_to) in [
+            (a, b, 1_000_000_000, 1_000_000_000),
+            (b, c, 1_000_000_000, 1_000_000_000),
+            (c, a, 1_000_000_000, 1_050_000_000),
+        ] {
+            graph.add_pool_edge(from, to, dex("orca"), CurveType::ConstantProduct, reserve_from, reserve_to, fee);
+            graph.add_pool_edge(to, from, dex("orca"), CurveType::ConstantProduct, reserve_to, reserve_from, fee);
+        }
+        // 跨DEX：X→Y 走 1:1 池，Y→X 走 X 偏多 2% 的池
+        graph.add_pool_edge(x, y, dex("raydium"), CurveType::ConstantProduct, 1_000_000_000, 1_000_000_000, fee);
+        graph.add_pool_edge(y, x, dex("meteora"), CurveType::ConstantProduct, 1_000_000_000, 1_020_000_000, fee);
+
+        let cycles = graph.find_negative_cycles();
+        assert_eq!(cycles.len(), 2);
+        assert!(cycles.iter().all(|cycle| cycle.first() == cycle.last()));
+
+        let mut params = SmartRoutingParams {
+            from_token: a,
+            to_token: a,
+            amount_in: 1_000_000,
+            min_amount_out: 1_000_000,
+            slippage_tolerance_bps: 100,
+            enable_arbitrage_detection: true,
+            enable_multi_hop: true,
+            max_hops: 3,
+            enable_cost_optimization: true,
+            enable_liquidity_priority: true,
+            risk_params: SmartRoutingRiskParams {
+                max_single_route_bps: 1000,
+                max_price_deviation_bps: 200,
+                max_execution_time: 60,
+                enable_emergency_stop: true,
+                emergency_stop_threshold_bps: 500,
+                max_route_complexity: 5,
+                min_liquidity_requirement: 1000,
+            },
+            monitoring_params: SmartRoutingMonitoringParams {
+                enable_monitoring: true,
+                metrics_interval: 30,
+                enable_detailed_logging: true,
+                enable_performance_warnings: true,
+                enable_route_analysis: true,
+            },
+        };
+        let algo = SmartRoutingAlgorithm::new();
+        let opportunities = algo.detect_arbitrage_opportunities(&graph, &params).unwrap();
+        assert_eq!(opportunities.len(), 2);
+
+        let triangle = &opportunities[0];
+        assert_eq!(triangle.opportunity_type, ArbitrageType::Triangular);
+        assert_eq!(triangle.cycle, vec![a, b, c, a]);
+        assert_eq!(triangle.execution_priority, 1);
+        // 解析最优约 697.6 万，利润约 14.57 万（约 208 bps）
+        assert!(triangle.optimal_amount_in > 6_900_000 && triangle.optimal_amount_in < 7_050_000);
+        assert!(triangle.expected_profit >= 145_000);
+        assert!(triangle.profit_margin_bps >= 200);
+        assert_eq!(triangle.sell_path.expected_output, triangle.optimal_amount_in + triangle.expected_profit);
+        assert!(triangle.sell_path.estimated_slippage_bps > 0);
+        // 规模翻倍后滑点吞噬利润
+        let doubled = graph.quote_cycle(&triangle.cycle, triangle.optimal_amount_in * 2).unwrap();
+        assert!(doubled.saturating_sub(triangle.optimal_amount_in * 2) < triangle.expected_profit);
+
+        let cross = &opportunities[1];
+        assert_eq!(cross.opportunity_type, ArbitrageType::CrossDex);
+        assert_eq!(cross.execution_priority, 2);
+        assert!(cross.cycle.contains(&x) && cross.cycle.contains(&y));
+        assert!(cross.profit_margin_bps >= algo.config.arbitrage_detection_threshold_bps);
+        assert!(cross.profit_margin_bps < triangle.profit_margin_bps);
+
+        // 跳数上限为 2 时三角环被过滤
+        params.max_hops = 2;
+        let opportunities = algo.detect_arbitrage_opportunities(&graph, &params).unwrap();
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].opportunity_type, ArbitrageType::CrossDex);
+    }
+
+    /// 测试路由入口：候选路径取自图中各边并拆单，腿名称为各跳实际 DEX；无曲线时退回最短路径。
+    #[test]
+    fn test_route_on_graph_splits_across_graph_paths() {
+        let dex = |name: &str| DexInfo {
+            name: name.to_string(),
+            dex_type: DexType::AMM,
+            program_id: Pubkey::new_unique(),
+            version: "1.0.0".to_string(),
+        };
+        let fee = FeeRate::new(25, 10_000);
+        let (sol, usdc, usdt) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut graph = RoutingGraph { nodes: HashMap::new(), edges: BTreeMap::new(), last_updated: 0 };
+        graph.add_pool_edge(sol, usdc, dex("orca"), CurveType::ConstantProduct, 1_000_000_000, 1_000_000_000, fee);
+        graph.add_pool_edge(sol, usdt, dex("raydium"), CurveType::ConstantProduct, 5_000_000_000, 5_000_000_000, fee);
+        graph.add_pool_edge(usdt, usdc, dex("meteora"), CurveType::ConstantProduct, 5_000_000_000, 5_000_000_000, fee);
+
+        let candidates = graph.route_candidates(sol, usdc, 3);
+        assert_eq!(candidates.len(), 2);
+        assert!(graph.route_candidates(sol, usdc, 1).iter().all(|c| c.hops.len() == 1));
+
+        let mut params = SmartRoutingParams {
+            from_token: sol,
+            to_token: usdc,
+            amount_in: 100_000_000,
+            min_amount_out: 0,
+            slippage_tolerance_bps: 100,
+            enable_arbitrage_detection: true,
+            enable_multi_hop: true,
+            max_hops: 3,
+            enable_cost_optimization: true,
+            enable_liquidity_priority: true,
+            risk_params: SmartRoutingRiskParams {
+                max_single_route_bps: 1000,
+                max_price_deviation_bps: 200,
+                max_execution_time: 60,
+                enable_emergency_stop: true,
+                emergency_stop_threshold_bps: 500,
+                max_route_complexity: 5,
+                min_liquidity_requirement: 1000,
+            },
+            monitoring_params: SmartRoutingMonitoringParams {
+                enable_monitoring: true,
+                metrics_interval: 30,
+                enable_detailed_logging: true,
+                enable_performance_warnings: true,
+                enable_route_analysis: true,
+            },
+        };
+        let algo = SmartRoutingAlgorithm::new();
+        let result = algo.route_on_graph(&graph, &params).unwrap();
+        assert_eq!(result.legs.len(), 2);
+        assert_eq!(result.legs.iter().map(|leg| leg.amount_in).sum::<u64>(), params.amount_in);
+        assert!(result.legs.iter().any(|leg| leg.dex_name == "orca" && leg.path == vec![sol, usdc]));
+        assert!(result.legs.iter().any(|leg| leg.dex_name == "raydium->meteora" && leg.path == vec![sol, usdt, usdc]));
+        assert!(result.legs.iter().all(|leg| leg.dex_name != "Jupiter"));
+
+        // 跳数上限取自调用参数：配置上限为 1 时仍按 params.max_hops 保留两跳路径
+        let one_hop_config = SmartRoutingAlgorithm::with_config(SmartRoutingConfig { max_hops: 1, ..Default::default() });
+        assert_eq!(one_hop_config.route_on_graph(&graph, &params).unwrap().legs, result.legs);
+
+        // 仅一跳时只剩直连池
+        params.max_hops = 1;
+        let result = algo.route_on_graph(&graph, &params).unwrap();
+        assert_eq!(result.best_dex, "orca");
+        assert_eq!(result.legs.len(), 1);
+
+        // USDC→SOL 池中 SOL 偏多 5%，形成 SOL→USDC→SOL 套利环：套利单独上报，不替换用户路由
+        graph.add_pool_edge(usdc, sol, dex("phoenix"), CurveType::ConstantProduct, 1_000_000_000, 1_050_000_000, fee);
+        params.max_hops = 3;
+        let report = algo.route_with_arbitrage(&graph, &params).unwrap();
+        assert_eq!(report.route.legs, algo.route_on_graph(&graph, &params).unwrap().legs);
+        assert!(report.route.legs.iter().all(|leg| leg.path.first() == Some(&sol) && leg.path.last() == Some(&usdc)));
+        assert!(!report.arbitrage_opportunities.is_empty());
+        assert_eq!(report.arbitrage_opportunities[0].cycle.first(), Some(&sol));
+        params.enable_arbitrage_detection = false;
+        assert!(algo.route_with_arbitrage(&graph, &params).unwrap().arbitrage_opportunities.is_empty());
+    }
+
+    /// 测试同一代币对的多场所平行边并存：报价取最优场所，拆单候选各场所独立，套利按最优汇率检测。
+    #[test]
+    fn test_parallel_pool_edges_kept_per_venue() {
+        let dex = |name: &str| DexInfo {
+            name: name.to_string(),
+            dex_type: DexType::AMM,
+            program_id: Pubkey::new_unique(),
+            version: "1.0.0".to_string(),
+        };
+        let fee = FeeRate::new(25, 10_000);
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut graph = RoutingGraph { nodes: HashMap::new(), edges: BTreeMap::new(), last_updated: 0 };
+        graph.add_pool_edge(sol, usdc, dex("orca"), CurveType::ConstantProduct, 1_000_000_000, 1_030_000_000, fee);
+        graph.add_pool_edge(sol, usdc, dex("raydium"), CurveType::ConstantProduct, 1_000_000_000, 1_000_000_000, fee);
+        graph.add_pool_edge(usdc, sol, dex("raydium"), CurveType::ConstantProduct, 1_000_000_000, 1_000_000_000, fee);
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(graph.edges_between(sol, usdc).count(), 2);
+
+        // 同一场所重复添加替换原边
+        graph.add_pool_edge(usdc, sol, dex("raydium"), CurveType::ConstantProduct, 1_000_000_000, 1_000_000_000, fee);
+        assert_eq!(graph.edges_between(usdc, sol).count(), 1);
+
+        let (edge, out) = graph.best_quote(&sol, &usdc, 1_000_000).unwrap();
+        assert_eq!(edge.dex_info.name, "orca");
+        assert!(out > graph.edges_between(sol, usdc).find(|e| e.dex_info.name == "raydium").unwrap().quote(1_000_000).unwrap());
+
+        let candidates = graph.route_candidates(sol, usdc, 1);
+        let names: Vec<String> = candidates.iter().map(|c| c.dex_name()).collect();
+        assert_eq!(names, vec!["orca".to_string(), "raydium".to_string()]);
+
+        // orca 买入、raydium 卖回的跨场所环（orca 汇率高出 3%）被检出
+        let cycles = graph.find_negative_cycles();
+        assert_eq!(cycles.len(), 1);
+        let profit_out = graph.quote_cycle(&cycles[0], 1_000_000).unwrap();
+        assert!(profit_out > 1_000_000);
+    }
 }
@@ -91,8 +91,18 @@ pub struct ExecutionResult {
 /// 路由算法结果结构体
 #[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize)] // 派生常用trait，便于序列化、调试、复制
 pub struct RoutingResult {
-    pub best_dex: String,      // 最优 DEX 名称
-    pub expected_out: u64,     // 预期输出
+    pub best_dex: String,      // 最优 DEX 名称（拆单时为分配量最大的一腿）
+    pub expected_out: u64,     // 预期输出（各腿合计）
+    pub legs: Vec<RouteLeg>,   // 拆单各腿（单路径时仅一腿）
+}
+
+/// 路由单腿结构体
+#[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)] // 派生常用trait，便于序列化、调试、判等
+pub struct RouteLeg {
+    pub dex_name: String,      // DEX/路径名称（多跳以 "->" 连接）
+    pub path: Vec<Pubkey>,     // 代币路径（含起止代币）
+    pub amount_in: u64,        // 分配输入数量
+    pub expected_out: u64,     // 预期输出数量
 }
 
 /// 风控算法结果结构体
@@ -21,7 +21,10 @@ use crate::core::types::AlgoParams;
 // use crate::core::types::algo::AlgoParams; // 暂时注释掉
 use crate::algorithms::traits::AlgorithmError;
 // use crate::core::constants::*; // 暂时注释掉
+use crate::account_models::price_history_account::PriceHistory;
+use crate::algorithms::market_impact::{ImpactEstimate, MarketImpactCalculator};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// 最大再平衡间隔（秒）
@@ -161,6 +164,14 @@ pub struct TwapAlgorithm {
     config: TwapConfig,
     /// 执行状态缓存
     execution_cache: HashMap<String, TwapExecutionState>,
+    /// 链上价格历史（设置后市场价格取其 TWAP）
+    price_history: Option<PriceHistory>,
+    /// 市场价格 TWAP 窗口（秒）
+    price_window_secs: u64,
+    /// 校准的市场冲击模型（设置后分段成交价格按校准冲击估计）
+    impact_model: Option<Arc<MarketImpactCalculator>>,
+    /// 冲击模型对应的卖出资产
+    impact_asset: Pubkey,
 }
 
 /// TWAP算法配置
@@ -269,6 +280,10 @@ impl TwapAlgorithm {
         Self {
             config: TwapConfig::default(),
             execution_cache: HashMap::new(),
+            price_history: None,
+            price_window_secs: 0,
+            impact_model: None,
+            impact_asset: Pubkey::default(),
         }
     }
     
@@ -277,8 +292,35 @@ impl TwapAlgorithm {
         Self {
             config,
             execution_cache: HashMap::new(),
+            price_history: None,
+            price_window_secs: 0,
+            impact_model: None,
+            impact_asset: Pubkey::default(),
         }
     }
+
+    /// 使用链上价格历史作为市场价格来源（取 window_secs 窗口 TWAP）
+    pub fn with_price_history(mut self, price_history: PriceHistory, window_secs: u64) -> Self {
+        self.price_history = Some(price_history);
+        self.price_window_secs = window_secs;
+        self
+    }
+
+    /// 使用校准的市场冲击模型估计分段成交价格（asset 为卖出资产）
+    pub fn with_impact_model(mut self, calculator: Arc<MarketImpactCalculator>, asset: Pubkey) -> Self {
+        self.impact_model = Some(calculator);
+        self.impact_asset = asset;
+        self
+    }
+
+    /// 估计执行计划的平均市场冲击：各分段视为一笔成交，并计入此前分段累积的永久冲击
+    pub fn estimate_plan_impact(&self, plan: &TwapExecutionPlan) -> anchor_lang::Result<ImpactEstimate> {
+        let calculator = self.impact_model.as_ref().ok_or_else(|| {
+            crate::errors::algorithm_error::AlgorithmError::MissingDependency { dependency: "market impact model".to_string() }
+        })?;
+        let sizes: Vec<u64> = plan.intervals.iter().map(|interval| interval.size).collect();
+        calculator.estimate_schedule_impact(&self.impact_asset, &sizes)
+    }
     
     /// 解析TWAP参数
     fn parse_twap_params(&self, params: &AlgoParams) -> anchor_lang::Result<TwapParams> {
@@ -389,6 +431,8 @@ impl TwapAlgorithm {
     }
     
     /// 执行TWAP算法
+    /// - 单次调用内遍历全部分段，仅用于离线模拟与参数评估
+    /// - 链上真实分时执行请使用 TwapOrder 账户与 crank_twap_slice 指令（见 instructions::twap_order）
     fn execute_twap_algorithm(&self, params: &TwapParams, plan: &TwapExecutionPlan) -> anchor_lang::Result<ExecutionResult> {
         let mut total_executed = 0u64;
         let mut total_cost = 0u64;
@@ -506,10 +550,13 @@ impl TwapAlgorithm {
             .as_secs()
     }
     
-    /// 获取市场价格（模拟实现）
+    /// 获取市场价格
+    /// - 已设置价格历史时取其 TWAP，否则返回模拟价格
     fn get_market_price(&self) -> anchor_lang::Result<u64> {
-        // 实际实现应调用Oracle接口
-        Ok(1_000_000) // 模拟价格：1 SOL = 1,000,000 lamports
+        match &self.price_history {
+            Some(history) => history.twap(self.get_current_timestamp() as i64, self.price_window_secs),
+            None => Ok(1_000_000), // 模拟价格：1 SOL = 1,000,000 lamports
+        }
     }
     
     /// 计算目标价格
@@ -521,6 +568,11 @@ impl TwapAlgorithm {
     
     /// 模拟市场执行
     fn simulate_market_execution(&self, size: u64, target_price: u64) -> anchor_lang::Result<u64> {
+        // 已设置冲击模型时按校准的单笔成交冲击估计成交价格
+        if let Some(calculator) = &self.impact_model {
+            let impact = calculator.estimate_impact(&self.impact_asset, size, 0)?;
+            return Ok((target_price as f64 * (1.0 + impact.average_bps / 10_000.0)).round() as u64);
+        }
         // 实际实现应调用DEX接口
         // 这里模拟执行价格，包含一些随机性
         let base_price = target_price;
@@ -942,4 +994,41 @@ mod tests {
         assert_eq!(metrics.price_deviation_stats.avg_deviation_bps, 25);
         assert_eq!(metrics.price_deviation_stats.max_deviation_bps, 35);
     }
+
+    #[test]
+    fn test_impact_model_pricing() {
+        use crate::algorithms::execution_optimizer::types::MarketImpactModel;
+        use crate::algorithms::market_impact::{CalibratedImpact, ImpactCoefficientRecord};
+
+        let asset = Pubkey::new_unique();
+        let mut calculator = MarketImpactCalculator::new();
+        calculator.load_coefficients(&[ImpactCoefficientRecord {
+            venue: None,
+            asset,
+            impact: CalibratedImpact {
+                model: MarketImpactModel::Linear,
+                intercept_bps: 10.0,
+                slope_bps: 0.1,
+                r_squared: 1.0,
+                samples: 10,
+                calibrated_at: 0,
+            },
+        }]);
+        let algo = TwapAlgorithm::new();
+        let plan = TwapExecutionPlan {
+            intervals: (0..10)
+                .map(|i| TwapInterval { index: i, size: 100, start_time: 0, end_time: 0, target_price: 0, executed: false })
+                .collect(),
+            total_amount: 1_000,
+            time_window: 3_600,
+            start_time: 0,
+        };
+        assert!(algo.estimate_plan_impact(&plan).is_err()); // 未设置冲击模型
+
+        let algo = algo.with_impact_model(Arc::new(calculator), asset);
+        // 每段 10 + 0.1 × 100 = 20 bps，后续分段叠加 0.25 × 10 = 2.5 bps 的永久冲击
+        let estimate = algo.estimate_plan_impact(&plan).unwrap();
+        assert!((estimate.average_bps - 31.25).abs() < 1e-9);
+        assert_eq!(algo.simulate_market_execution(100, 1_000_000).unwrap(), 1_002_000);
+    }
 }
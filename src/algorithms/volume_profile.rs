@@ -0,0 +1,435 @@
+/*!
+ * 日内成交量曲线模块
+ *
+ * 从历史成交量样本中按资产、按日内时间分桶学习成交量曲线（各分桶占全天成交量的比例），
+ * 供 VWAP 规划器按预期成交量为各执行分段定量。
+ *
+ * 设计要点：
+ * - 每日先归一化为占比再做按日 EWMA，避免个别放量日主导曲线形状
+ * - 相邻分桶核平滑（首尾相接，链上资产全天候交易），并设置最小占比，避免零权重分桶
+ * - 代币化股票使用股票日历：周末与节假日为休市日，单独学习休市日曲线，不污染交易日曲线
+ * - 同时以 EWMA 跟踪日均成交量，便于比较实际成交量与预期成交量
+ */
+
+use anchor_lang::prelude::*;
+use crate::errors::algorithm_error::AlgorithmError;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// 一天的秒数
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// 成交量样本
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VolumeSample {
+    /// 成交时间戳（Unix 秒）
+    pub timestamp: u64,
+    /// 成交量
+    pub volume: u64,
+}
+
+/// 日期类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DayKind {
+    /// 交易日
+    Trading,
+    /// 休市日（周末、节假日）
+    Closed,
+}
+
+/// 交易日历
+#[derive(Clone, Debug, PartialEq)]
+pub enum TradingCalendar {
+    /// 全天候交易（加密资产），每天都是交易日
+    Continuous,
+    /// 股票日历（代币化股票）：周六、周日与节假日为休市日
+    Equity {
+        /// 本地时区相对 UTC 的偏移（秒），决定日界与分桶位置
+        utc_offset_secs: i64,
+        /// 节假日（本地日序号，即本地时间戳 / 86400）
+        holidays: BTreeSet<i64>,
+    },
+}
+
+impl TradingCalendar {
+    /// 创建股票日历
+    pub fn equity(utc_offset_secs: i64, holidays: &[i64]) -> Self {
+        TradingCalendar::Equity { utc_offset_secs, holidays: holidays.iter().copied().collect() }
+    }
+
+    /// 本地时间戳（秒）
+    pub fn local_time(&self, timestamp: u64) -> i64 {
+        match self {
+            TradingCalendar::Continuous => timestamp as i64,
+            TradingCalendar::Equity { utc_offset_secs, .. } => timestamp as i64 + utc_offset_secs,
+        }
+    }
+
+    /// 本地日序号
+    pub fn day_of(&self, timestamp: u64) -> i64 {
+        self.local_time(timestamp).div_euclid(SECONDS_PER_DAY as i64)
+    }
+
+    /// 日期类型（1970-01-01 为周四）
+    pub fn day_kind(&self, day: i64) -> DayKind {
+        match self {
+            TradingCalendar::Continuous => DayKind::Trading,
+            TradingCalendar::Equity { holidays, .. } => {
+                let weekday = (day + 3).rem_euclid(7); // 0 = 周一 ... 5 = 周六, 6 = 周日
+                if weekday >= 5 || holidays.contains(&day) {
+                    DayKind::Closed
+                } else {
+                    DayKind::Trading
+                }
+            }
+        }
+    }
+}
+
+/// 成交量曲线学习配置
+#[derive(Clone, Debug, PartialEq)]
+pub struct VolumeProfileConfig {
+    /// 分桶长度（秒），需整除一天
+    pub bucket_secs: u64,
+    /// 按日 EWMA 系数（最新一天的权重），取值 (0, 1]
+    pub decay: f64,
+    /// 相邻分桶平滑权重，取值 [0, 0.5)
+    pub neighbor_weight: f64,
+    /// 分桶最小占比
+    pub min_bucket_share: f64,
+    /// 生成交易日曲线所需的最少天数
+    pub min_days: u32,
+}
+
+impl Default for VolumeProfileConfig {
+    fn default() -> Self {
+        Self {
+            bucket_secs: 1800, // 30分钟
+            decay: 0.2,
+            neighbor_weight: 0.15,
+            min_bucket_share: 1e-4,
+            min_days: 1,
+        }
+    }
+}
+
+impl VolumeProfileConfig {
+    /// 校验配置
+    pub fn validate(&self) -> anchor_lang::Result<()> {
+        require!(
+            self.bucket_secs > 0 && SECONDS_PER_DAY.is_multiple_of(self.bucket_secs),
+            AlgorithmError::InvalidParameters {
+                reason: "Bucket length must divide one day".to_string(),
+            }
+        );
+        require!(
+            self.decay > 0.0 && self.decay <= 1.0,
+            AlgorithmError::InvalidParameters {
+                reason: "Decay must be in (0, 1]".to_string(),
+            }
+        );
+        require!(
+            (0.0..0.5).contains(&self.neighbor_weight),
+            AlgorithmError::InvalidParameters {
+                reason: "Neighbor weight must be in [0, 0.5)".to_string(),
+            }
+        );
+        require!(
+            (0.0..1.0).contains(&self.min_bucket_share) && self.min_days > 0,
+            AlgorithmError::InvalidParameters {
+                reason: "Invalid minimum bucket share or minimum days".to_string(),
+            }
+        );
+        Ok(())
+    }
+
+    /// 每天的分桶数量
+    pub fn buckets_per_day(&self) -> usize {
+        (SECONDS_PER_DAY / self.bucket_secs) as usize
+    }
+}
+
+/// 某类日期的成交量曲线
+#[derive(Clone, Debug, PartialEq)]
+pub struct VolumeCurve {
+    /// 各分桶占全天成交量的比例（和为 1）
+    pub shares: Vec<f64>,
+    /// 日均成交量（EWMA）
+    pub avg_daily_volume: f64,
+    /// 参与学习的天数
+    pub days: u32,
+}
+
+/// 单个资产的日内成交量曲线
+#[derive(Clone, Debug, PartialEq)]
+pub struct VolumeProfile {
+    /// 资产标识
+    pub asset: String,
+    /// 分桶长度（秒）
+    pub bucket_secs: u64,
+    /// 交易日历
+    pub calendar: TradingCalendar,
+    /// 交易日曲线
+    pub trading: VolumeCurve,
+    /// 休市日曲线（无足够休市日样本时为空，休市时段预期成交量为 0）
+    pub closed: Option<VolumeCurve>,
+}
+
+impl VolumeProfile {
+    /// 指定本地日的曲线
+    pub fn curve_for(&self, day: i64) -> Option<&VolumeCurve> {
+        match self.calendar.day_kind(day) {
+            DayKind::Trading => Some(&self.trading),
+            DayKind::Closed => self.closed.as_ref(),
+        }
+    }
+
+    /// 时间段 [start, end) 内的预期成交量（跨分桶、跨日按时长比例累计）
+    pub fn expected_volume(&self, start: u64, end: u64) -> f64 {
+        let bucket_secs = self.bucket_secs as i64;
+        let day_secs = SECONDS_PER_DAY as i64;
+        let mut local = self.calendar.local_time(start);
+        let local_end = self.calendar.local_time(end);
+        let mut volume = 0.0;
+        while local < local_end {
+            let day = local.div_euclid(day_secs);
+            let bucket = local.rem_euclid(day_secs) / bucket_secs;
+            let bucket_end = day * day_secs + (bucket + 1) * bucket_secs;
+            let segment_end = bucket_end.min(local_end);
+            if let Some(curve) = self.curve_for(day) {
+                let fraction = (segment_end - local) as f64 / bucket_secs as f64;
+                volume += curve.shares[bucket as usize] * curve.avg_daily_volume * fraction;
+            }
+            local = segment_end;
+        }
+        volume
+    }
+}
+
+/// 按日 EWMA 累加器
+struct CurveAccumulator {
+    shares: Vec<f64>,
+    avg_daily_volume: f64,
+    days: u32,
+}
+
+impl CurveAccumulator {
+    fn update(&mut self, day_shares: &[f64], day_total: f64, decay: f64) {
+        if self.days == 0 {
+            self.shares = day_shares.to_vec();
+            self.avg_daily_volume = day_total;
+        } else {
+            for (share, day_share) in self.shares.iter_mut().zip(day_shares) {
+                *share = decay * day_share + (1.0 - decay) * *share;
+            }
+            self.avg_daily_volume = decay * day_total + (1.0 - decay) * self.avg_daily_volume;
+        }
+        self.days += 1;
+    }
+
+    /// 相邻分桶平滑、最小占比与归一化
+    fn finish(self, config: &VolumeProfileConfig) -> VolumeCurve {
+        let n = self.shares.len();
+        let w = config.neighbor_weight;
+        let mut shares: Vec<f64> = (0..n)
+            .map(|i| {
+                let prev = self.shares[(i + n - 1) % n];
+                let next = self.shares[(i + 1) % n];
+                (1.0 - 2.0 * w) * self.shares[i] + w * (prev + next)
+            })
+            .map(|share| share.max(config.min_bucket_share))
+            .collect();
+        let total: f64 = shares.iter().sum();
+        shares.iter_mut().for_each(|share| *share /= total);
+        VolumeCurve { shares, avg_daily_volume: self.avg_daily_volume, days: self.days }
+    }
+}
+
+/// 日内成交量曲线模型（按资产保存学习结果）
+#[derive(Clone, Debug, Default)]
+pub struct VolumeProfileModel {
+    /// 学习配置
+    pub config: VolumeProfileConfig,
+    /// 资产 -> 成交量曲线
+    profiles: HashMap<String, VolumeProfile>,
+}
+
+impl VolumeProfileModel {
+    /// 使用指定配置创建模型
+    pub fn new(config: VolumeProfileConfig) -> Self {
+        Self { config, profiles: HashMap::new() }
+    }
+
+    /// 从历史样本学习资产的成交量曲线并保存，替换该资产的旧曲线
+    pub fn fit(
+        &mut self,
+        asset: &str,
+        calendar: TradingCalendar,
+        samples: &[VolumeSample],
+    ) -> anchor_lang::Result<&VolumeProfile> {
+        self.config.validate()?;
+        let buckets = self.config.buckets_per_day();
+        let bucket_secs = self.config.bucket_secs as i64;
+
+        // 按本地日、分桶汇总成交量
+        let mut days: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+        for sample in samples {
+            let local = calendar.local_time(sample.timestamp);
+            let day = local.div_euclid(SECONDS_PER_DAY as i64);
+            let bucket = (local.rem_euclid(SECONDS_PER_DAY as i64) / bucket_secs) as usize;
+            days.entry(day).or_insert_with(|| vec![0.0; buckets])[bucket] += sample.volume as f64;
+        }
+
+        // 按时间顺序逐日更新交易日/休市日曲线
+        let new_accumulator = || CurveAccumulator { shares: vec![0.0; buckets], avg_daily_volume: 0.0, days: 0 };
+        let mut trading = new_accumulator();
+        let mut closed = new_accumulator();
+        for (day, volumes) in &days {
+            let total: f64 = volumes.iter().sum();
+            if total <= 0.0 {
+                continue;
+            }
+            let shares: Vec<f64> = volumes.iter().map(|volume| volume / total).collect();
+            let accumulator = match calendar.day_kind(*day) {
+                DayKind::Trading => &mut trading,
+                DayKind::Closed => &mut closed,
+            };
+            accumulator.update(&shares, total, self.config.decay);
+        }
+
+        require!(
+            trading.days >= self.config.min_days,
+            AlgorithmError::InvalidParameters {
+                reason: format!(
+                    "Insufficient volume history for {}: {} trading days, {} required",
+                    asset, trading.days, self.config.min_days
+                ),
+            }
+        );
+        let closed = (closed.days >= self.config.min_days).then(|| closed.finish(&self.config));
+        let profile = VolumeProfile {
+            asset: asset.to_string(),
+            bucket_secs: self.config.bucket_secs,
+            calendar,
+            trading: trading.finish(&self.config),
+            closed,
+        };
+        self.profiles.insert(asset.to_string(), profile);
+        Ok(&self.profiles[asset])
+    }
+
+    /// 获取资产的成交量曲线
+    pub fn profile(&self, asset: &str) -> Option<&VolumeProfile> {
+        self.profiles.get(asset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-01（周一）00:00 UTC
+    const MONDAY: u64 = 1_704_067_200;
+    const HOUR: u64 = 3_600;
+
+    /// 合成 U 型日内成交量：开盘、收盘时段放量，中午缩量
+    fn u_shape(hour: u64) -> u64 {
+        match hour {
+            0 | 23 => 400,
+            1 | 22 => 200,
+            _ => 100,
+        }
+    }
+
+    /// 生成 days 天逐小时样本，volume(day, hour) 为第 day 天第 hour 小时的成交量
+    fn series(days: u64, volume: impl Fn(u64, u64) -> u64) -> Vec<VolumeSample> {
+        (0..days)
+            .flat_map(|day| (0..24).map(move |hour| (day, hour)))
+            .map(|(day, hour)| VolumeSample { timestamp: MONDAY + day * 86_400 + hour * HOUR, volume: volume(day, hour) })
+            .collect()
+    }
+
+    fn hourly_config() -> VolumeProfileConfig {
+        VolumeProfileConfig { bucket_secs: HOUR, neighbor_weight: 0.0, min_bucket_share: 0.0, ..Default::default() }
+    }
+
+    #[test]
+    fn test_fit_learns_normalized_u_shape() {
+        let mut model = VolumeProfileModel::new(hourly_config());
+        // 成交量每天翻倍，但形状不变，学习到的占比不受放量日影响
+        let samples = series(5, |day, hour| u_shape(hour) << day);
+        let profile = model.fit("SOL", TradingCalendar::Continuous, &samples).unwrap();
+        let day_total: u64 = (0..24).map(u_shape).sum();
+        assert_eq!(profile.trading.days, 5);
+        assert_eq!(profile.trading.shares.len(), 24);
+        assert!((profile.trading.shares[0] - 400.0 / day_total as f64).abs() < 1e-12);
+        assert!((profile.trading.shares[12] - 100.0 / day_total as f64).abs() < 1e-12);
+        assert!((profile.trading.shares.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(profile.trading.avg_daily_volume > day_total as f64); // EWMA 跟随放量
+        assert!(profile.closed.is_none());
+        assert!(model.profile("SOL").is_some());
+        assert!(model.profile("BTC").is_none());
+    }
+
+    #[test]
+    fn test_neighbor_smoothing_and_floor() {
+        let config = VolumeProfileConfig { bucket_secs: HOUR, neighbor_weight: 0.25, min_bucket_share: 0.01, ..Default::default() };
+        let mut model = VolumeProfileModel::new(config);
+        // 全部成交集中在 12 点
+        let samples = series(3, |_, hour| if hour == 12 { 1_000 } else { 0 });
+        let shares = &model.fit("SPIKE", TradingCalendar::Continuous, &samples).unwrap().trading.shares;
+        assert!(shares[12] > shares[11] && (shares[11] - shares[13]).abs() < 1e-12); // 峰值向两侧扩散
+        assert!(shares[11] > shares[10]);
+        assert!(shares.iter().all(|share| *share > 0.0)); // 最小占比避免零权重
+        assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_equity_calendar_separates_weekends_and_holidays() {
+        let calendar = TradingCalendar::equity(0, &[(MONDAY / 86_400 + 7) as i64]); // 第二周周一为节假日
+        assert_eq!(calendar.day_kind((MONDAY / 86_400) as i64), DayKind::Trading);
+        assert_eq!(calendar.day_kind((MONDAY / 86_400 + 5) as i64), DayKind::Closed); // 周六
+        assert_eq!(calendar.day_kind((MONDAY / 86_400 + 7) as i64), DayKind::Closed); // 节假日
+
+        let mut model = VolumeProfileModel::new(hourly_config());
+        // 交易日为 U 型；休市日全天平坦且量小
+        let samples = series(14, |day, hour| {
+            if calendar.day_kind((MONDAY / 86_400 + day) as i64) == DayKind::Trading { u_shape(hour) } else { 10 }
+        });
+        let profile = model.fit("AAPLx", calendar, &samples).unwrap();
+        assert_eq!(profile.trading.days, 9);
+        let closed = profile.closed.as_ref().unwrap();
+        assert_eq!(closed.days, 5);
+        assert!((closed.shares[0] - 1.0 / 24.0).abs() < 1e-12);
+        assert!((closed.avg_daily_volume - 240.0).abs() < 1e-9);
+        let day_total: u64 = (0..24).map(u_shape).sum();
+        assert!((profile.trading.shares[0] - 400.0 / day_total as f64).abs() < 1e-12); // 休市日不影响交易日曲线
+
+        // 周五 22:00 至周六 02:00：跨越交易日与休市日
+        let friday = MONDAY + 4 * 86_400;
+        let expected = profile.expected_volume(friday + 22 * HOUR, friday + 26 * HOUR);
+        assert!((expected - (200.0 + 400.0 + 10.0 + 10.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_expected_volume_prorates_partial_buckets() {
+        let mut model = VolumeProfileModel::new(hourly_config());
+        let profile = model.fit("SOL", TradingCalendar::Continuous, &series(2, |_, hour| u_shape(hour))).unwrap();
+        // 23:30 - 00:30 跨日，各取半个分桶
+        let expected = profile.expected_volume(MONDAY + 23 * HOUR + 1_800, MONDAY + 24 * HOUR + 1_800);
+        assert!((expected - 400.0).abs() < 1e-6);
+        assert_eq!(profile.expected_volume(MONDAY, MONDAY), 0.0);
+    }
+
+    #[test]
+    fn test_fit_rejects_invalid_config_and_short_history() {
+        let mut model = VolumeProfileModel::new(VolumeProfileConfig { bucket_secs: 7_000, ..Default::default() });
+        assert!(model.fit("SOL", TradingCalendar::Continuous, &series(1, |_, h| u_shape(h))).is_err());
+
+        let mut model = VolumeProfileModel::new(VolumeProfileConfig { min_days: 3, ..hourly_config() });
+        assert!(model.fit("SOL", TradingCalendar::Continuous, &series(2, |_, h| u_shape(h))).is_err());
+        // 仅有休市日样本时无法生成交易日曲线
+        let weekend = series(7, |_, h| u_shape(h)).into_iter().filter(|s| (s.timestamp - MONDAY) / 86_400 >= 5).collect::<Vec<_>>();
+        let mut model = VolumeProfileModel::new(hourly_config());
+        assert!(model.fit("AAPLx", TradingCalendar::equity(0, &[]), &weekend).is_err());
+    }
+}
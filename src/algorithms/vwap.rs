@@ -16,12 +16,15 @@
 
 use anchor_lang::prelude::*;
 use crate::algorithms::traits::{Algorithm, ExecutionStrategy, AlgorithmType, ExecutionResult};
+use crate::algorithms::volume_profile::VolumeProfile;
+use crate::algorithms::market_impact::{ImpactEstimate, MarketImpactCalculator};
 use crate::core::adapter::AdapterTrait;
 use crate::core::types::AlgoParams;
 // use crate::core::types::algo::AlgoParams; // 暂时注释掉
 use crate::errors::algorithm_error::AlgorithmError;
 // use crate::core::constants::*; // 暂时注释掉
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// 最大再平衡间隔（秒）
@@ -208,6 +211,10 @@ pub struct VwapAlgorithm {
     config: VwapConfig,
     /// 执行状态缓存
     execution_cache: HashMap<String, VwapExecutionState>,
+    /// 校准的市场冲击模型（设置后目标价格按校准冲击计入深度影响）
+    impact_model: Option<Arc<MarketImpactCalculator>>,
+    /// 冲击模型对应的卖出资产
+    impact_asset: Pubkey,
 }
 
 /// VWAP算法配置
@@ -227,6 +234,8 @@ pub struct VwapConfig {
     pub cache_expiry_time: u64,
     /// 默认权重策略
     pub default_weight_strategy: WeightStrategy,
+    /// 成交量偏差持续系数 [0, 1]：重新规划时已观测到的放量/缩量在剩余时段延续的比例
+    pub volume_surprise_persistence: f64,
 }
 
 /// 权重策略枚举
@@ -252,6 +261,7 @@ impl Default for VwapConfig {
             enable_cache: true,
             cache_expiry_time: 300, // 5分钟
             default_weight_strategy: WeightStrategy::Linear,
+            volume_surprise_persistence: 0.5,
         }
     }
 }
@@ -327,6 +337,8 @@ impl VwapAlgorithm {
         Self {
             config: VwapConfig::default(),
             execution_cache: HashMap::new(),
+            impact_model: None,
+            impact_asset: Pubkey::default(),
         }
     }
     
@@ -335,8 +347,26 @@ impl VwapAlgorithm {
         Self {
             config,
             execution_cache: HashMap::new(),
+            impact_model: None,
+            impact_asset: Pubkey::default(),
         }
     }
+
+    /// 使用校准的市场冲击模型计算目标价格（asset 为卖出资产）
+    pub fn with_impact_model(mut self, calculator: Arc<MarketImpactCalculator>, asset: Pubkey) -> Self {
+        self.impact_model = Some(calculator);
+        self.impact_asset = asset;
+        self
+    }
+
+    /// 估计执行计划的平均市场冲击：各分段视为一笔成交，并计入此前分段累积的永久冲击
+    pub fn estimate_plan_impact(&self, plan: &VwapExecutionPlan) -> anchor_lang::Result<ImpactEstimate> {
+        let calculator = self.impact_model.as_ref().ok_or_else(|| AlgorithmError::MissingDependency {
+            dependency: "market impact model".to_string(),
+        })?;
+        let sizes: Vec<u64> = plan.intervals.iter().map(|interval| interval.size).collect();
+        calculator.estimate_schedule_impact(&self.impact_asset, &sizes)
+    }
     
     /// 解析VWAP参数
     fn parse_vwap_params(&self, params: &AlgoParams) -> anchor_lang::Result<VwapParams> {
@@ -489,6 +519,137 @@ impl VwapAlgorithm {
         }
     }
     
+    /// 基于成交量曲线生成执行计划
+    /// - 时间窗口等分为 num_intervals 段，各段按曲线给出的预期成交量占比定量
+    /// - 分段大小不超过 max_interval_size；总量足够时不低于 min_interval_size（预期成交量为 0 的休市分段不分配）
+    pub fn plan_from_volume_profile(
+        &self,
+        params: &VwapParams,
+        profile: &VolumeProfile,
+        start_time: u64,
+    ) -> anchor_lang::Result<VwapExecutionPlan> {
+        self.validate_vwap_params(params)?;
+        let interval_duration = params.time_window / params.num_intervals as u64;
+        let expected: Vec<f64> = (0..params.num_intervals as u64)
+            .map(|i| profile.expected_volume(start_time + i * interval_duration, start_time + (i + 1) * interval_duration))
+            .collect();
+        let sizes = allocate_by_weights(params.total_amount, &expected, params.min_interval_size, params.max_interval_size)?;
+        let total_expected: f64 = expected.iter().sum();
+
+        let intervals = sizes
+            .into_iter()
+            .zip(&expected)
+            .enumerate()
+            .map(|(i, (size, volume))| VwapInterval {
+                index: i as u32,
+                size,
+                weight: volume / total_expected,
+                start_time: start_time + i as u64 * interval_duration,
+                end_time: start_time + (i as u64 + 1) * interval_duration,
+                target_price: 0, // 将在执行时动态计算
+                executed: false,
+            })
+            .collect();
+
+        Ok(VwapExecutionPlan {
+            intervals,
+            total_amount: params.total_amount,
+            time_window: params.time_window,
+            start_time,
+        })
+    }
+
+    /// 实际成交量偏离曲线预期时重新规划剩余分段
+    /// - 已完成分段的实际市场成交量与预期偏差超过 risk_params.max_volume_deviation_bps 时触发，否则返回 None
+    /// - 剩余时段的预期成交量按 volume_surprise_persistence 延续已观测到的偏差
+    /// - 按市场成交进度计算应完成数量，落后部分并入下一分段追赶，其余按剩余预期成交量分配
+    pub fn replan_on_volume_divergence(
+        &self,
+        params: &VwapParams,
+        plan: &VwapExecutionPlan,
+        profile: &VolumeProfile,
+        progress: &VwapProgress,
+    ) -> anchor_lang::Result<Option<VwapExecutionPlan>> {
+        let completed = progress.completed_intervals as usize;
+        require!(
+            completed <= plan.intervals.len()
+                && progress.realized_volumes.len() == completed
+                && progress.executed_amount <= plan.total_amount,
+            AlgorithmError::InvalidParameters {
+                reason: "Inconsistent VWAP execution progress".to_string(),
+            }
+        );
+        let remaining = plan.total_amount - progress.executed_amount;
+        if completed == 0 || completed == plan.intervals.len() || remaining == 0 {
+            return Ok(None);
+        }
+
+        // 已完成时段的实际/预期成交量比
+        let expected: Vec<f64> = plan
+            .intervals
+            .iter()
+            .map(|interval| profile.expected_volume(interval.start_time, interval.end_time))
+            .collect();
+        let expected_done: f64 = expected[..completed].iter().sum();
+        let realized_done: f64 = progress.realized_volumes.iter().map(|volume| *volume as f64).sum();
+        if expected_done <= 0.0 {
+            return Ok(None);
+        }
+        let ratio = realized_done / expected_done;
+        if (ratio - 1.0).abs() * 10_000.0 <= params.risk_params.max_volume_deviation_bps as f64 {
+            return Ok(None);
+        }
+
+        // 市场成交进度：已实现成交量 / (已实现 + 延续偏差后的剩余预期成交量)
+        let expected_rest: f64 = expected[completed..].iter().sum();
+        let persistence = self.config.volume_surprise_persistence.clamp(0.0, 1.0);
+        let projected_rest = expected_rest * (1.0 + persistence * (ratio - 1.0)).max(0.0);
+        let market_progress = if realized_done + projected_rest > 0.0 {
+            realized_done / (realized_done + projected_rest)
+        } else {
+            1.0
+        };
+        let target_done = (plan.total_amount as f64 * market_progress).round() as u64;
+        let catch_up = target_done.saturating_sub(progress.executed_amount).min(remaining);
+
+        // 剩余分段权重：追赶数量叠加到下一分段，其余按剩余预期成交量分配
+        let rest_count = plan.intervals.len() - completed;
+        let spread = (remaining - catch_up) as f64 / remaining as f64;
+        let mut weights: Vec<f64> = expected[completed..]
+            .iter()
+            .map(|volume| {
+                let share = if expected_rest > 0.0 { volume / expected_rest } else { 1.0 / rest_count as f64 };
+                share * spread
+            })
+            .collect();
+        weights[0] += catch_up as f64 / remaining as f64;
+        let sizes = allocate_by_weights(remaining, &weights, params.min_interval_size, params.max_interval_size)?;
+
+        let total_expected = expected_done + expected_rest;
+        let mut intervals = plan.intervals.clone();
+        for interval in intervals[..completed].iter_mut() {
+            interval.executed = true;
+        }
+        for ((interval, size), volume) in intervals[completed..].iter_mut().zip(sizes).zip(&expected[completed..]) {
+            interval.size = size;
+            interval.weight = if total_expected > 0.0 { volume / total_expected } else { 0.0 };
+            interval.executed = false;
+        }
+
+        msg!(
+            "VWAP replanned after {} intervals: volume ratio {:.4}, catch-up {}",
+            completed,
+            ratio,
+            catch_up
+        );
+        Ok(Some(VwapExecutionPlan {
+            intervals,
+            total_amount: plan.total_amount,
+            time_window: plan.time_window,
+            start_time: plan.start_time,
+        }))
+    }
+    
     /// 执行VWAP算法
     fn execute_vwap_algorithm(&self, params: &VwapParams, plan: &VwapExecutionPlan) -> anchor_lang::Result<ExecutionResult> {
         let mut total_executed = 0u64;
@@ -655,8 +816,11 @@ impl VwapAlgorithm {
         // 基础滑点计算
         let base_slippage_factor = 1.0 + (slippage_tolerance_bps as f64 / 10000.0);
         
-        // 考虑市场深度和价格冲击
-        let depth_impact = market_depth.price_impact_factor * (order_size as f64 / 10000.0);
+        // 考虑市场深度和价格冲击（已设置冲击模型时使用校准的单笔成交冲击）
+        let depth_impact = match &self.impact_model {
+            Some(calculator) => calculator.estimate_impact(&self.impact_asset, order_size, 0)?.average_bps / 10000.0,
+            None => market_depth.price_impact_factor * (order_size as f64 / 10000.0),
+        };
         let volatility_impact = market_depth.market_volatility * 0.5;
         
         let total_impact = base_slippage_factor + depth_impact + volatility_impact;
@@ -947,6 +1111,90 @@ pub struct VwapIntervalResult {
     pub execution_time: u64,
 }
 
+/// VWAP执行进度（用于中途重新规划）
+#[derive(Clone, Debug, Default)]
+pub struct VwapProgress {
+    /// 已完成分段数量
+    pub completed_intervals: u32,
+    /// 已成交数量
+    pub executed_amount: u64,
+    /// 各已完成分段内的实际市场成交量
+    pub realized_volumes: Vec<u64>,
+}
+
+/// 按权重分配总量，分配结果之和恰为 total
+/// - 权重为 0 的分段不分配
+/// - 单段不超过 max_size；总量足够覆盖全部有权重分段时单段不低于 min_size
+/// - 取整余量按小数部分从大到小补足
+fn allocate_by_weights(total: u64, weights: &[f64], min_size: u64, max_size: u64) -> anchor_lang::Result<Vec<u64>> {
+    let active: Vec<usize> = (0..weights.len()).filter(|&i| weights[i] > 0.0).collect();
+    require!(
+        !active.is_empty(),
+        AlgorithmError::InvalidParameters {
+            reason: "No expected volume in execution window".to_string(),
+        }
+    );
+    require!(
+        active.len() as u128 * max_size as u128 >= total as u128,
+        AlgorithmError::InvalidParameters {
+            reason: "Total amount exceeds interval size limits".to_string(),
+        }
+    );
+    let min_size = if active.len() as u128 * min_size as u128 <= total as u128 { min_size } else { 0 };
+
+    // 迭代固定越界分段：先固定超过上限的，再固定低于下限的，其余按权重分配剩余数量
+    let mut fixed: Vec<Option<u64>> = weights.iter().map(|w| if *w > 0.0 { None } else { Some(0) }).collect();
+    let mut raw = vec![0.0; weights.len()];
+    loop {
+        let fixed_sum: u64 = fixed.iter().flatten().sum();
+        let free_weight: f64 = active.iter().filter(|&&i| fixed[i].is_none()).map(|&i| weights[i]).sum();
+        if free_weight <= 0.0 {
+            break;
+        }
+        let rest = total.saturating_sub(fixed_sum) as f64;
+        for &i in active.iter().filter(|&&i| fixed[i].is_none()) {
+            raw[i] = rest * weights[i] / free_weight;
+        }
+        let over: Vec<usize> = active.iter().copied().filter(|&i| fixed[i].is_none() && raw[i] > max_size as f64).collect();
+        let under: Vec<usize> = active.iter().copied().filter(|&i| fixed[i].is_none() && raw[i] < min_size as f64).collect();
+        match (over.is_empty(), under.is_empty()) {
+            (false, _) => over.into_iter().for_each(|i| fixed[i] = Some(max_size)),
+            (true, false) => under.into_iter().for_each(|i| fixed[i] = Some(min_size)),
+            (true, true) => break,
+        }
+    }
+
+    let mut sizes: Vec<u64> = (0..weights.len()).map(|i| fixed[i].unwrap_or(raw[i].floor() as u64)).collect();
+    let mut planned: u64 = sizes.iter().sum();
+
+    // 余量补足：先按小数部分从大到小逐一加 1，再由未达上限的分段依次吸收
+    let mut order: Vec<usize> = active.iter().copied().filter(|&i| fixed[i].is_none()).collect();
+    order.sort_by(|&a, &b| {
+        (raw[b] - raw[b].floor())
+            .partial_cmp(&(raw[a] - raw[a].floor()))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.cmp(&b))
+    });
+    for &i in &order {
+        if planned < total && sizes[i] < max_size {
+            sizes[i] += 1;
+            planned += 1;
+        }
+    }
+    for &i in &active {
+        let add = total.saturating_sub(planned).min(max_size - sizes[i]);
+        sizes[i] += add;
+        planned += add;
+    }
+    // 超额扣减（下限固定导致），从后往前扣至下限
+    for &i in active.iter().rev() {
+        let cut = planned.saturating_sub(total).min(sizes[i].saturating_sub(min_size));
+        sizes[i] -= cut;
+        planned -= cut;
+    }
+    Ok(sizes)
+}
+
 impl Default for MarketDepthData {
     fn default() -> Self {
         Self {
@@ -970,6 +1218,7 @@ fn auto_register_vwap_algorithm() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::volume_profile::{TradingCalendar, VolumeCurve};
     use anchor_lang::prelude::*;
     
     #[test]
@@ -1093,4 +1342,155 @@ mod tests {
         assert!(stats.volume_std > 0.0);
         assert!(stats.volume_uniformity > 0.0 && stats.volume_uniformity <= 1.0);
     }
+    
+    /// 2024-01-01（周一）00:00 UTC
+    const MONDAY: u64 = 1_704_067_200;
+
+    /// 合成成交量曲线：前 4 小时预期成交量为 10000、5000、2500、7500
+    fn sample_profile() -> VolumeProfile {
+        let mut shares = vec![0.0375; 24];
+        shares[..4].copy_from_slice(&[0.1, 0.05, 0.025, 0.075]);
+        VolumeProfile {
+            asset: "SOL".to_string(),
+            bucket_secs: 3600,
+            calendar: TradingCalendar::Continuous,
+            trading: VolumeCurve { shares, avg_daily_volume: 100_000.0, days: 10 },
+            closed: None,
+        }
+    }
+
+    fn profile_params(total_amount: u64, max_interval_size: u64) -> VwapParams {
+        VwapParams {
+            total_amount,
+            time_window: 4 * 3600,
+            num_intervals: 4,
+            slippage_tolerance_bps: 100,
+            min_interval_size: 50,
+            max_interval_size,
+            price_deviation_bps: 50,
+            enable_dynamic_weighting: true,
+            enable_market_depth_analysis: false,
+            risk_params: VwapRiskParams {
+                max_single_execution_bps: 5000,
+                max_price_deviation_bps: 200,
+                max_execution_time: 60,
+                enable_emergency_stop: true,
+                emergency_stop_threshold_bps: 500,
+                max_volume_deviation_bps: 300,
+            },
+            monitoring_params: VwapMonitoringParams {
+                enable_monitoring: false,
+                metrics_interval: 30,
+                enable_detailed_logging: false,
+                enable_performance_warnings: false,
+                enable_depth_monitoring: false,
+            },
+        }
+    }
+
+    fn sizes(plan: &VwapExecutionPlan) -> Vec<u64> {
+        plan.intervals.iter().map(|i| i.size).collect()
+    }
+
+    #[test]
+    fn test_plan_from_volume_profile() {
+        let algo = VwapAlgorithm::new();
+        let profile = sample_profile();
+
+        let plan = algo.plan_from_volume_profile(&profile_params(2000, 1000), &profile, MONDAY).unwrap();
+        assert_eq!(sizes(&plan), vec![800, 400, 200, 600]);
+        assert_eq!(plan.start_time, MONDAY);
+        assert_eq!(plan.intervals[1].start_time, MONDAY + 3600);
+        assert_eq!(plan.intervals[3].end_time, MONDAY + 4 * 3600);
+        assert!((plan.intervals[0].weight - 0.4).abs() < 1e-9);
+
+        // 上限约束：超出部分按剩余权重重新分配，总量不变
+        let plan = algo.plan_from_volume_profile(&profile_params(2000, 700), &profile, MONDAY).unwrap();
+        assert_eq!(sizes(&plan), vec![700, 433, 217, 650]);
+
+        // 总量超过分段上限之和
+        assert!(algo.plan_from_volume_profile(&profile_params(5000, 1000), &profile, MONDAY).is_err());
+    }
+
+    #[test]
+    fn test_plan_from_volume_profile_skips_closed_market() {
+        let algo = VwapAlgorithm::new();
+        let mut profile = sample_profile();
+        profile.calendar = TradingCalendar::equity(0, &[]);
+        // 周五 22:00 起 4 小时：后两小时为周六休市且无休市日曲线
+        let friday_close = MONDAY + 4 * 86_400 + 22 * 3600;
+        let plan = algo.plan_from_volume_profile(&profile_params(1000, 1000), &profile, friday_close).unwrap();
+        assert_eq!(plan.intervals[2].size, 0);
+        assert_eq!(plan.intervals[3].size, 0);
+        assert_eq!(sizes(&plan).iter().sum::<u64>(), 1000);
+        // 窗口完全落在休市时段
+        assert!(algo.plan_from_volume_profile(&profile_params(1000, 1000), &profile, MONDAY + 5 * 86_400).is_err());
+    }
+
+    #[test]
+    fn test_replan_catches_up_when_volume_surges() {
+        let algo = VwapAlgorithm::new();
+        let profile = sample_profile();
+        let params = profile_params(2000, 1000);
+        let plan = algo.plan_from_volume_profile(&params, &profile, MONDAY).unwrap();
+
+        // 第一小时实际成交量为预期的 2 倍：市场进度 20000 / (20000 + 15000 * 1.5) ≈ 47.06%，应完成 941
+        let progress = VwapProgress { completed_intervals: 1, executed_amount: 800, realized_volumes: vec![20_000] };
+        let replanned = algo.replan_on_volume_divergence(&params, &plan, &profile, &progress).unwrap().unwrap();
+        let rest = &sizes(&replanned)[1..];
+        assert_eq!(rest.iter().sum::<u64>(), 1200);
+        assert!((493..=495).contains(&rest[0])); // 353 按曲线分配 + 141 追赶
+        assert!(rest[0] > plan.intervals[1].size);
+        assert!(replanned.intervals[0].executed && !replanned.intervals[1].executed);
+        assert_eq!(replanned.intervals[0].size, 800);
+    }
+
+    #[test]
+    fn test_replan_rebalances_when_volume_dries_up() {
+        let algo = VwapAlgorithm::new();
+        let profile = sample_profile();
+        let params = profile_params(2000, 1000);
+        let plan = algo.plan_from_volume_profile(&params, &profile, MONDAY).unwrap();
+
+        // 偏差在容忍度内（100 bps < 300 bps）不重新规划
+        let within = VwapProgress { completed_intervals: 1, executed_amount: 800, realized_volumes: vec![10_100] };
+        assert!(algo.replan_on_volume_divergence(&params, &plan, &profile, &within).unwrap().is_none());
+
+        // 缩量时已领先市场进度，无需追赶，剩余数量按曲线分配
+        let dried = VwapProgress { completed_intervals: 1, executed_amount: 800, realized_volumes: vec![5_000] };
+        let replanned = algo.replan_on_volume_divergence(&params, &plan, &profile, &dried).unwrap().unwrap();
+        assert_eq!(&sizes(&replanned)[1..], &[400, 200, 600]);
+
+        // 进度与计划不一致
+        let invalid = VwapProgress { completed_intervals: 2, executed_amount: 800, realized_volumes: vec![5_000] };
+        assert!(algo.replan_on_volume_divergence(&params, &plan, &profile, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_impact_model_target_price() {
+        use crate::algorithms::execution_optimizer::types::MarketImpactModel;
+        use crate::algorithms::market_impact::{CalibratedImpact, ImpactCoefficientRecord};
+
+        let asset = Pubkey::new_unique();
+        let mut calculator = MarketImpactCalculator::new();
+        calculator.load_coefficients(&[ImpactCoefficientRecord {
+            venue: None,
+            asset,
+            impact: CalibratedImpact {
+                model: MarketImpactModel::SquareRoot,
+                intercept_bps: 10.0,
+                slope_bps: 1.0,
+                r_squared: 1.0,
+                samples: 10,
+                calibrated_at: 0,
+            },
+        }]);
+        let algo = VwapAlgorithm::new().with_impact_model(Arc::new(calculator), asset);
+        // 10 + √400 = 30 bps
+        let target = algo
+            .calculate_target_price_with_depth(1_000_000, 0, &MarketDepthData::default(), 400)
+            .unwrap();
+        assert!(target.abs_diff(1_003_000) <= 1);
+        assert!(algo.calculate_target_price_with_depth(1_000_000, 0, &MarketDepthData::default(), 1_600).unwrap() > target);
+    }
 }
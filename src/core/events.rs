@@ -28,6 +28,12 @@ pub struct OffchainDexRequest {
     /// 请求时间戳（Unix秒）
     /// - 类型：i64，链上 Clock::get()?.unix_timestamp
     pub timestamp: i64,
+    /// 指定响应者公钥（keeper 身份）
+    /// - 类型：Pubkey，仅该响应者可回填，其他 keeper 据此跳过
+    pub responder: Pubkey,
+    /// 请求过期时间戳（Unix秒）
+    /// - 类型：i64，过期后链上不再接受响应
+    pub expires_at: i64,
 }
 
 /// 链下预言机请求事件结构体
@@ -47,6 +53,10 @@ pub struct OffchainOracleRequest {
     pub requester: Pubkey, // 链上用户身份
     /// 请求时间戳（Unix秒）
     pub timestamp: i64, // 链上时间戳
+    /// 指定响应者公钥（keeper 身份）
+    pub responder: Pubkey, // 仅该响应者可回填
+    /// 请求过期时间戳（Unix秒）
+    pub expires_at: i64, // 过期后不再接受响应
 }
 
 /// 链下响应事件结构体
@@ -115,6 +125,10 @@ pub struct OraclePriceQueried {
     pub quote_mint: Pubkey, // 报价资产 mint
     /// 查询价格（整数，单位依赖具体实现）
     pub price: u64, // 查询价格
+    /// 来源回退链中实际使用的来源下标（0 主 / 1 备 / 2 第三）
+    pub source_index: u8, // 实际使用的来源
+    /// 是否得到其他健康来源的交叉确认（仅一个健康来源时为 false）
+    pub confirmed: bool, // 是否经交叉确认
     /// 查询时间戳（Unix秒）
     pub timestamp: i64, // 查询时间
 }
@@ -2,6 +2,8 @@
 
 pub mod traits;
 pub mod types;
+pub mod constants;
+pub mod events;
 pub mod logging;
 pub mod registry;
 pub mod adapter;
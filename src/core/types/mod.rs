@@ -83,27 +83,8 @@ pub enum AssetBatchOpType {
     Unfreeze,
 }
 
-/// DexAdapterTrait - 统一所有DEX/AMM外部集成trait接口，所有DEX/AMM适配器均需实现，便于最小功能单元化、可插拔、可扩展
-pub trait DexAdapterTrait {
-    /// 查询支持的资产类型，返回AssetType集合
-    fn supported_assets(&self) -> Vec<AssetType>;
-    /// 查询DEX/AMM名称，返回静态字符串
-    fn name(&self) -> &'static str;
-    /// 查询DEX/AMM版本，返回静态字符串
-    fn version(&self) -> &'static str;
-    /// 查询是否支持某资产，asset为资产类型
-    fn is_asset_supported(&self, asset: &AssetType) -> bool;
-    /// 查询流动性，asset为资产类型，返回流动性数量
-    fn liquidity(&self, asset: &AssetType) -> anchor_lang::Result<u64>;
-    /// 查询价格，asset为资产类型，返回价格
-    fn price(&self, asset: &AssetType) -> anchor_lang::Result<u64>;
-    /// 下单（买入），asset为资产类型，amount为买入数量，返回实际成交数量
-    fn buy(&mut self, asset: &AssetType, amount: u64) -> anchor_lang::Result<u64>;
-    /// 下单（卖出），asset为资产类型，amount为卖出数量，返回实际成交数量
-    fn sell(&mut self, asset: &AssetType, amount: u64) -> anchor_lang::Result<u64>;
-    /// 资产兑换，from为源资产，to为目标资产，amount为兑换数量，返回实际兑换数量
-    fn swap(&mut self, from: &AssetType, to: &AssetType, amount: u64) -> anchor_lang::Result<u64>;
-}
+/// DexAdapterTrait - DEX/AMM 适配器统一契约，与 crate::dex::adapter::DexAdapter 为同一 trait
+pub use crate::dex::adapter::DexAdapter as DexAdapterTrait;
 
 /// OracleAdapterTrait - 统一所有预言机外部集成trait接口，所有预言机适配器均需实现，便于最小功能单元化、可插拔、可扩展
 pub trait OracleAdapterTrait {
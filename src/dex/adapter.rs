@@ -5,20 +5,60 @@
 //! 并实现注册表、自动注册宏、Mock 适配器等，确保生产级集成、测试与合规。
 
 use anchor_lang::prelude::*; // Anchor 预导入，包含 Result、msg!、账户声明等
+use anchor_lang::solana_program::instruction::Instruction; // swap CPI 指令
 use crate::core::adapter::AdapterTrait;
 use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
 use std::collections::HashMap; // HashMap：适配器名称到实例的映射
 use std::sync::{Arc, RwLock}; // Arc/RwLock：线程安全全局注册表
 
 /// DEX/AMM 适配器 Trait（标准化、可扩展）
-/// 统一所有 DEX/AMM 的链上适配接口，支持 swap、批量 swap、配置、资产/市场类型查询等。
-pub trait DexAdapter: AdapterTrait {
+/// 全仓唯一的 DEX 适配契约：报价、成交、流动性管理与账户需求声明均由此 trait 提供，
+/// 两个注册表（DexAdapterRegistry、AdapterRegistry）与 traits.rs 的兼容导出都指向它。
+pub trait DexAdapter: AdapterTrait + Send + Sync {
+    /// 基于传入的池账户（池状态、金库等）计算报价。
+    /// - 默认不支持，需要读取链上池状态的适配器应覆盖此方法。
+    fn get_quote(&self, _params: &TradeParams, _accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        Err(DexAdapterError::QuoteUnsupported.into())
+    }
     /// 执行 swap 的最小功能单元接口。
     fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult>;
-    /// 批量 swap 接口。
-    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>>;
+    /// 基于传入的池账户报价并校验 min_amount_out，不转移任何代币。
+    /// - 仅用于定价与可成交性判断；真实成交须经 build_swap_instruction + invoke_swap，按代币账户余额变化确认。
+    fn quote_checked(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        check_quote_min_out(params, self.get_quote(params, accounts)?)
+    }
+    /// 基于传入的池账户构造真实成交的 swap CPI 指令（输入/输出代币账户由 user 指定，可由程序 PDA 持有）。
+    /// - accounts 按 account_requirements(DexOperation::Swap) 顺序传入；调用方仍需按输出账户余额变化校验 min_amount_out。
+    /// - 默认不支持，可被程序代持资产调用的适配器应覆盖此方法。
+    fn build_swap_instruction(&self, _params: &TradeParams, _user: &DexSwapUserAccounts, _accounts: &[AccountInfo]) -> anchor_lang::Result<Instruction> {
+        Err(DexAdapterError::SwapCpiUnsupported.into())
+    }
+    /// 是否实现 build_swap_instruction / invoke_swap（托管资金的订单在创建时据此拒绝无法成交的适配器）。
+    fn supports_swap_cpi(&self) -> bool { false }
+    /// 执行 build_swap_instruction 构造的指令（校验目标程序，输入代币由程序 PDA 持有时传入 signer_seeds）。
+    fn invoke_swap(&self, _instruction: &Instruction, _account_infos: &[AccountInfo], _signer_seeds: &[&[&[u8]]]) -> anchor_lang::Result<()> {
+        Err(DexAdapterError::SwapCpiUnsupported.into())
+    }
+    /// 批量 swap 接口，默认逐笔调用 swap。
+    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
+        params.trades.iter().map(|p| self.swap(p)).collect()
+    }
     /// 配置 DEX adapter。
-    fn configure(&self, params: &DexParams) -> anchor_lang::Result<()>;
+    fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> { Ok(()) }
+    /// 添加流动性，返回获得的 LP 数量。
+    /// - 默认不支持，提供做市接口的适配器应覆盖此方法。
+    fn add_liquidity(&self, _params: &AddLiquidityParams, _accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
+        Err(DexAdapterError::LiquidityUnsupported.into())
+    }
+    /// 移除流动性，返回返还的资产数量。
+    fn remove_liquidity(&self, _params: &RemoveLiquidityParams, _accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
+        Err(DexAdapterError::LiquidityUnsupported.into())
+    }
+    /// 声明某操作需要按序传入的账户（pool_program 为池账户归属程序，用于区分同一 DEX 的多种池型）。
+    /// - 默认无需额外账户。
+    fn account_requirements(&self, _operation: DexOperation, _pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        vec![]
+    }
     /// 支持的资产类型。
     fn supported_assets(&self) -> Vec<String> { vec![] }
     /// 支持的市场类型。
@@ -27,6 +67,88 @@ pub trait DexAdapter: AdapterTrait {
     fn adapter_type(&self) -> DexAdapterType { DexAdapterType::Other }
 }
 
+/// 适配器操作类型（用于查询账户需求）。
+#[derive(Clone, Copy, Debug, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum DexOperation {
+    Quote,           // 报价
+    Swap,            // 成交
+    AddLiquidity,    // 添加流动性
+    RemoveLiquidity, // 移除流动性
+}
+
+/// 单个账户需求描述（按传入顺序排列）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DexAccountRequirement {
+    pub name: &'static str, // 账户名称
+    pub is_writable: bool,  // 是否可写
+    pub is_signer: bool,    // 是否需签名
+    pub is_repeated: bool,  // 是否可连续传入多个（如 tick/bin array），仅允许出现在末尾
+}
+
+impl DexAccountRequirement {
+    /// 只读账户。
+    pub fn readonly(name: &'static str) -> Self {
+        Self { name, is_writable: false, is_signer: false, is_repeated: false }
+    }
+    /// 可写账户。
+    pub fn writable(name: &'static str) -> Self {
+        Self { name, is_writable: true, is_signer: false, is_repeated: false }
+    }
+    /// 签名账户。
+    pub fn signer(name: &'static str) -> Self {
+        Self { name, is_writable: false, is_signer: true, is_repeated: false }
+    }
+    /// 标记为可重复的尾部账户。
+    pub fn repeated(mut self) -> Self {
+        self.is_repeated = true;
+        self
+    }
+}
+
+/// 按账户需求校验传入账户：数量、可写与签名标记。
+pub fn check_account_requirements(requirements: &[DexAccountRequirement], accounts: &[AccountInfo]) -> anchor_lang::Result<()> {
+    let fixed = requirements.iter().filter(|r| !r.is_repeated).count();
+    let repeated = requirements.iter().any(|r| r.is_repeated);
+    require!(accounts.len() >= fixed + repeated as usize, DexAdapterError::MissingPoolAccounts);
+    let tail = requirements.last().filter(|r| r.is_repeated);
+    for (i, info) in accounts.iter().enumerate() {
+        let Some(req) = requirements.get(i).filter(|r| !r.is_repeated).or(tail) else { break };
+        require!(!req.is_writable || info.is_writable, DexAdapterError::InvalidPoolAccount);
+        require!(!req.is_signer || info.is_signer, DexAdapterError::InvalidPoolAccount);
+    }
+    Ok(())
+}
+
+/// 添加流动性参数结构体
+/// - 描述一次 add_liquidity 操作的所有输入参数
+#[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct AddLiquidityParams {
+    /// tokenA mint
+    pub token_a: Pubkey, // 资产A
+    /// tokenB mint
+    pub token_b: Pubkey, // 资产B
+    /// tokenA数量
+    pub amount_a: u64, // 资产A数量
+    /// tokenB数量
+    pub amount_b: u64, // 资产B数量
+    /// 用户公钥
+    pub user: Pubkey, // 操作发起人
+    /// DEX相关账户列表
+    pub dex_accounts: Vec<Pubkey>, // 适配器所需账户
+}
+
+/// 移除流动性参数结构体
+/// - 描述一次 remove_liquidity 操作的所有输入参数
+#[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize)]
+pub struct RemoveLiquidityParams {
+    /// 移除的流动性token数量
+    pub liquidity: u64, // LP token数量
+    /// 用户公钥
+    pub user: Pubkey, // 操作发起人
+    /// DEX相关账户列表
+    pub dex_accounts: Vec<Pubkey>, // 适配器所需账户
+}
+
 /// DEX adapter 类型枚举。
 #[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub enum DexAdapterType {
@@ -37,6 +159,16 @@ pub enum DexAdapterType {
     Other,  // 其他
 }
 
+/// swap CPI 的用户侧账户。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DexSwapUserAccounts {
+    pub authority: Pubkey,     // 输入代币账户的 owner（签名者或程序 PDA）
+    pub payer: Pubkey,         // 支付 CPI 附带费用的签名者（如 OpenBook 罚金）
+    pub source: Pubkey,        // 输入代币账户
+    pub destination: Pubkey,   // 输出代币账户
+    pub token_program: Pubkey, // SPL Token 程序
+}
+
 /// swap 结果结构体。
 #[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize)]
 pub struct DexSwapResult {
@@ -46,6 +178,126 @@ pub struct DexSwapResult {
     pub dex_name: String,     // DEX 名称
 }
 
+impl DexSwapResult {
+    /// 按成交均价折算的输出数量。
+    pub fn amount_out(&self) -> u64 {
+        (self.executed_amount as u128 * self.avg_price as u128 / crate::dex::curve::AVG_PRICE_SCALE) as u64
+    }
+}
+
+/// 报价结果结构体。
+#[derive(Clone, Debug, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct DexQuote {
+    pub amount_in: u64,        // 输入数量（含手续费）
+    pub amount_out: u64,       // 预期输出数量
+    pub fee: u64,              // 手续费（输入币种计）
+    pub price_impact_bps: u64, // 价格冲击（基点）
+    pub avg_price: u64,        // 成交均价（精度 1e6）
+    pub dex_name: String,      // DEX 名称
+}
+
+impl DexQuote {
+    /// 由曲线报价构造 DEX 报价。
+    pub fn from_curve(quote: &crate::dex::curve::CurveQuote, dex_name: &str) -> Self {
+        Self {
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            fee: quote.fee,
+            price_impact_bps: quote.price_impact_bps,
+            avg_price: quote.avg_price(),
+            dex_name: dex_name.to_string(),
+        }
+    }
+}
+
+/// 已接入 DEX 的链上程序 ID；指令账户中的 dex_program 须属于其中之一。
+pub const SUPPORTED_DEX_PROGRAM_IDS: [Pubkey; 8] = [
+    crate::dex::raydium::RAYDIUM_AMM_V4_PROGRAM_ID,
+    crate::dex::raydium::RAYDIUM_CLMM_PROGRAM_ID,
+    crate::dex::orca::ORCA_TOKEN_SWAP_PROGRAM_ID,
+    crate::dex::orca::ORCA_WHIRLPOOL_PROGRAM_ID,
+    crate::dex::lifinity::LIFINITY_V2_PROGRAM_ID,
+    crate::dex::meteora::METEORA_DLMM_PROGRAM_ID,
+    crate::dex::phoenix::PHOENIX_PROGRAM_ID,
+    crate::dex::openbook::OPENBOOK_V2_PROGRAM_ID,
+];
+
+/// 程序 ID 是否属于已接入的 DEX。
+pub fn is_supported_dex_program(program_id: &Pubkey) -> bool {
+    SUPPORTED_DEX_PROGRAM_IDS.contains(program_id)
+}
+
+/// 托管订单（TWAP/POV）可选的 DEX 名称；均实现以程序 PDA 签名的 swap CPI。
+pub const ESCROW_SWAP_DEX_NAMES: [&str; 3] = ["phoenix", "openbook", "meteora"];
+
+/// 按 DEX 名称静态选取支持 swap CPI 的适配器。
+/// - 链上程序不执行 DEX_ADAPTER_REGISTRY 的自动注册，托管订单不得依赖运行时注册表。
+pub fn escrow_swap_adapter(dex_name: &str) -> Option<&'static dyn DexAdapter> {
+    match dex_name {
+        "phoenix" => Some(&crate::dex::phoenix::PhoenixAdapter),
+        "openbook" => Some(&crate::dex::openbook::OpenBookAdapter),
+        "meteora" => Some(&crate::dex::meteora::MeteoraAdapter),
+        _ => None,
+    }
+}
+
+/// 按报价校验 min_amount_out，供 quote_checked 复用。
+pub fn check_quote_min_out(params: &TradeParams, quote: DexQuote) -> anchor_lang::Result<DexQuote> {
+    require!(quote.amount_out > 0, DexAdapterError::InsufficientLiquidity);
+    require!(quote.amount_out >= params.min_amount_out, DexAdapterError::SlippageExceeded);
+    Ok(quote)
+}
+
+/// 服务层按池账户报价并校验 min_amount_out；未携带池账户时返回 MissingPoolAccounts，不回退到预言机/参数价格。
+/// - 结果为报价而非成交，不转移任何代币。
+pub fn checked_quote_from_pool(adapter: &dyn DexAdapter, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+    require!(!accounts.is_empty(), DexAdapterError::MissingPoolAccounts);
+    adapter.quote_checked(params, accounts)
+}
+
+/// 服务层按池账户报价；未携带池账户时返回 MissingPoolAccounts。
+pub fn quote_from_pool(adapter: &dyn DexAdapter, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+    require!(!accounts.is_empty(), DexAdapterError::MissingPoolAccounts);
+    adapter.get_quote(params, accounts)
+}
+
+/// DEX 适配器通用错误码（Anchor错误）
+#[error_code]
+pub enum DexAdapterError {
+    /// 适配器不支持基于账户报价
+    #[msg("Quote from pool accounts unsupported by this adapter")] QuoteUnsupported,
+    /// 缺少池账户
+    #[msg("Missing pool accounts")] MissingPoolAccounts,
+    /// 池账户无效（归属、布局或金库不匹配）
+    #[msg("Invalid pool account")] InvalidPoolAccount,
+    /// 代币对与池不匹配
+    #[msg("Token pair does not match pool")] TokenPairMismatch,
+    /// 流动性不足
+    #[msg("Insufficient liquidity")] InsufficientLiquidity,
+    /// 输出低于 min_amount_out
+    #[msg("Slippage exceeded")] SlippageExceeded,
+    /// 数学溢出
+    #[msg("Math overflow")] MathOverflow,
+    /// tick array 账户无效（归属、所属池或布局不匹配）
+    #[msg("Invalid tick array account")] InvalidTickArray,
+    /// 传入的 tick array 不足以覆盖整笔成交
+    #[msg("Swap exceeds supplied tick arrays")] InsufficientTickArrays,
+    /// bin array 账户无效（归属、所属交易对或布局不匹配）
+    #[msg("Invalid bin array account")] InvalidBinArray,
+    /// 传入的 bin array 不足以覆盖整笔成交
+    #[msg("Swap exceeds supplied bin arrays")] InsufficientBinArrays,
+    /// 适配器不支持流动性管理
+    #[msg("Liquidity management unsupported by this adapter")] LiquidityUnsupported,
+    /// 适配器不支持构造 swap CPI
+    #[msg("Swap CPI unsupported by this adapter")] SwapCpiUnsupported,
+    /// dex_program 不是已接入的 DEX 程序
+    #[msg("Unsupported DEX program")] UnsupportedDexProgram,
+    /// 适配器工厂不可用（锁中毒）
+    #[msg("Adapter registry unavailable")] AdapterRegistryUnavailable,
+    /// 套利路径未回到起始 mint，利润无法在同一单位下计算
+    #[msg("Arbitrage route is not a closed cycle")] OpenArbitrageCycle,
+}
+
 // === Anchor账户声明（可扩展） ===
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -64,7 +316,7 @@ pub struct Configure<'info> {
 
 /// DEX 适配器注册表，支持动态注册、注销、查询、事件日志，线程安全。
 pub struct DexAdapterRegistry {
-    adapters: RwLock<HashMap<String, Arc<dyn DexAdapter + Send + Sync>>>, // 适配器名称到实例的映射
+    adapters: RwLock<HashMap<String, Arc<dyn DexAdapter>>>, // 适配器名称到实例的映射
 }
 
 impl DexAdapterRegistry {
@@ -73,17 +325,22 @@ impl DexAdapterRegistry {
         Self { adapters: RwLock::new(HashMap::new()) }
     }
     /// 注册适配器。
-    pub fn register(&self, name: &str, adapter: Arc<dyn DexAdapter + Send + Sync>) {
+    pub fn register(&self, name: &str, adapter: Arc<dyn DexAdapter>) {
         self.adapters.write().unwrap().insert(name.to_string(), adapter);
         msg!("[DexAdapterRegistry] Registered adapter: {}", name);
     }
+    /// 以适配器自身名称注册。
+    pub fn register_adapter(&self, adapter: Arc<dyn DexAdapter>) {
+        let name = adapter.name().to_string();
+        self.register(&name, adapter);
+    }
     /// 注销适配器。
     pub fn unregister(&self, name: &str) {
         self.adapters.write().unwrap().remove(name);
         msg!("[DexAdapterRegistry] Unregistered adapter: {}", name);
     }
     /// 查询适配器。
-    pub fn get(&self, name: &str) -> Option<Arc<dyn DexAdapter + Send + Sync>> {
+    pub fn get(&self, name: &str) -> Option<Arc<dyn DexAdapter>> {
         self.adapters.read().unwrap().get(name).cloned()
     }
     /// 列出所有已注册适配器名称。
@@ -126,14 +383,6 @@ impl DexAdapter for MockDexAdapter {
             dex_name: "mock_dex".to_string(),
         })
     }
-    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
-        Ok(params.swaps.iter().map(|p| DexSwapResult {
-            executed_amount: p.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "mock_dex".to_string(),
-        }).collect())
-    }
     fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> { Ok(()) }
     fn supported_assets(&self) -> Vec<String> { vec!["SOL".to_string(), "USDC".to_string()] }
     fn supported_markets(&self) -> Vec<String> { vec!["spot".to_string()] }
@@ -162,4 +411,81 @@ mod tests {
         assert_eq!(result.fee, 1000);
         assert_eq!(result.dex_name, "mock_dex");
     }
-} 
\ No newline at end of file
+
+    /// 默认不支持流动性管理。
+    #[test]
+    fn test_default_liquidity_unsupported() {
+        let adapter = MockDexAdapter;
+        let params = AddLiquidityParams {
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            amount_a: 1,
+            amount_b: 1,
+            user: Pubkey::new_unique(),
+            dex_accounts: vec![],
+        };
+        assert!(adapter.add_liquidity(&params, &[]).is_err());
+        assert!(adapter.account_requirements(DexOperation::Swap, &Pubkey::default()).is_empty());
+        let user = DexSwapUserAccounts {
+            authority: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            token_program: Pubkey::new_unique(),
+        };
+        let trade = TradeParams {
+            trade_type: "swap".to_string(),
+            from_token: Pubkey::new_unique(),
+            to_token: Pubkey::new_unique(),
+            amount_in: 1,
+            min_amount_out: 1,
+            dex_name: "mock_dex".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        };
+        assert!(!adapter.supports_swap_cpi());
+        assert!(adapter.build_swap_instruction(&trade, &user, &[]).is_err()); // 默认不支持 swap CPI
+        assert!(checked_quote_from_pool(&adapter, &trade, &[]).is_err()); // 无池账户时不回退
+        assert!(quote_from_pool(&adapter, &trade, &[]).is_err());
+    }
+
+    /// 托管订单适配器按名称静态选取，且均支持 swap CPI。
+    #[test]
+    fn test_escrow_swap_adapter() {
+        for name in ESCROW_SWAP_DEX_NAMES {
+            let adapter = escrow_swap_adapter(name).unwrap();
+            assert_eq!(adapter.name(), name);
+            assert!(adapter.supports_swap_cpi());
+        }
+        assert!(escrow_swap_adapter("raydium").is_none()); // 未实现 swap CPI
+        assert!(escrow_swap_adapter("unknown").is_none());
+    }
+
+    /// 按账户需求校验数量、可写与签名标记。
+    #[test]
+    fn test_check_account_requirements() {
+        let requirements = vec![
+            DexAccountRequirement::writable("pool"),
+            DexAccountRequirement::readonly("tick_array").repeated(),
+        ];
+        let (keys, owner) = ([Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()], Pubkey::new_unique());
+        let (mut lamports, mut data) = ([0u64; 3], [vec![], vec![], vec![]]);
+        let mut infos: Vec<AccountInfo> = keys.iter().zip(lamports.iter_mut()).zip(data.iter_mut())
+            .map(|((key, l), d)| AccountInfo::new(key, false, true, l, d, &owner, false, 0))
+            .collect();
+        assert!(check_account_requirements(&requirements, &infos).is_ok());
+        assert!(check_account_requirements(&requirements, &infos[..1]).is_err()); // 缺少尾部重复账户
+        infos[0].is_writable = false;
+        assert!(check_account_requirements(&requirements, &infos).is_err());
+    }
+
+    /// 仅已接入 DEX 的程序 ID 可作为 dex_program。
+    #[test]
+    fn test_is_supported_dex_program() {
+        assert!(is_supported_dex_program(&crate::dex::raydium::RAYDIUM_AMM_V4_PROGRAM_ID));
+        assert!(is_supported_dex_program(&crate::dex::openbook::OPENBOOK_V2_PROGRAM_ID));
+        assert!(!is_supported_dex_program(&Pubkey::new_unique()));
+        assert!(!is_supported_dex_program(&anchor_spl::token::ID));
+    }
+}
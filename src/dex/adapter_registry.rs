@@ -2,23 +2,15 @@
 //! DEX Adapter Registry Module
 //!
 //! 本模块实现 DEX 适配器注册表，支持适配器的注册、注销、查找、批量管理等功能，确保适配器生命周期合规、可追溯、可维护。
+//! 注册表存储统一的 `DexAdapter` trait 对象，与 `DexAdapterRegistry` 中取出的适配器可互换使用。
 
+use crate::dex::adapter::DexAdapter;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-/// DEX 适配器 trait，所有适配器需实现。
-pub trait DexAdapterTrait: Send + Sync {
-    /// 获取适配器名称。
-    fn name(&self) -> &str;
-    /// 获取适配器版本。
-    fn version(&self) -> &str;
-    /// 检查适配器是否可用。
-    fn is_available(&self) -> bool;
-}
-
 /// 适配器注册表结构体。
 pub struct AdapterRegistry {
-    adapters: Mutex<HashMap<String, Arc<dyn DexAdapterTrait>>>, // 线程安全适配器映射表
+    adapters: Mutex<HashMap<String, Arc<dyn DexAdapter>>>, // 线程安全适配器映射表
 }
 
 impl AdapterRegistry {
@@ -28,8 +20,8 @@ impl AdapterRegistry {
             adapters: Mutex::new(HashMap::new()),
         }
     }
-    /// 注册适配器。
-    pub fn register(&self, adapter: Arc<dyn DexAdapterTrait>) {
+    /// 注册适配器（以适配器名称为键）。
+    pub fn register(&self, adapter: Arc<dyn DexAdapter>) {
         let mut map = self.adapters.lock().unwrap();
         map.insert(adapter.name().to_string(), adapter);
     }
@@ -39,7 +31,7 @@ impl AdapterRegistry {
         map.remove(name);
     }
     /// 查找适配器。
-    pub fn find(&self, name: &str) -> Option<Arc<dyn DexAdapterTrait>> {
+    pub fn find(&self, name: &str) -> Option<Arc<dyn DexAdapter>> {
         let map = self.adapters.lock().unwrap();
         map.get(name).cloned()
     }
@@ -49,13 +41,50 @@ impl AdapterRegistry {
         map.keys().cloned().collect()
     }
     /// 获取所有可用适配器。
-    pub fn available_adapters(&self) -> Vec<Arc<dyn DexAdapterTrait>> {
+    pub fn available_adapters(&self) -> Vec<Arc<dyn DexAdapter>> {
         let map = self.adapters.lock().unwrap();
         map.values().filter(|a| a.is_available()).cloned().collect()
     }
+    /// 获取支持指定资产的可用适配器。
+    pub fn adapters_for_asset(&self, asset: &str) -> Vec<Arc<dyn DexAdapter>> {
+        let map = self.adapters.lock().unwrap();
+        map.values()
+            .filter(|a| a.is_available() && a.supported_assets().iter().any(|s| s == asset))
+            .cloned()
+            .collect()
+    }
     /// 清空所有适配器。
     pub fn clear(&self) {
         let mut map = self.adapters.lock().unwrap();
         map.clear();
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::adapter::{DexAdapterRegistry, MockDexAdapter};
+    use crate::dex::meteora::MeteoraAdapter;
+    use crate::dex::raydium::RaydiumAdapter;
+
+    /// 同一适配器实例可同时注册到两个注册表，取出后行为一致。
+    #[test]
+    fn test_registries_share_adapter_contract() {
+        let registry = AdapterRegistry::new();
+        let dex_registry = DexAdapterRegistry::new();
+        let raydium: Arc<dyn DexAdapter> = Arc::new(RaydiumAdapter);
+        registry.register(raydium.clone());
+        registry.register(Arc::new(MeteoraAdapter));
+        registry.register(Arc::new(MockDexAdapter));
+        dex_registry.register_adapter(raydium);
+
+        let from_registry = registry.find("raydium").unwrap();
+        let from_dex_registry = dex_registry.get("raydium").unwrap();
+        assert_eq!(from_registry.name(), from_dex_registry.name());
+        assert_eq!(from_registry.adapter_type(), from_dex_registry.adapter_type());
+        assert_eq!(registry.adapters_for_asset("METEORA").len(), 1);
+        registry.unregister("meteora");
+        assert!(registry.find("meteora").is_none());
+        assert_eq!(registry.list_names().len(), 2);
+    }
+}
@@ -0,0 +1,420 @@
+//!
+//! Concentrated Liquidity (CLMM) Math Module
+//!
+//! 本模块实现集中流动性池（Orca Whirlpool / Raydium CLMM）的报价数学：Q64.64 平方根价格与 tick 互转、
+//! 单区间 swap step、按已初始化 tick 逐段穿越并更新活跃流动性，输出精确成交数量、手续费与成交后价格。
+//! 两个协议均采用 Q64.64 sqrt price 与 1.0001 tick 底数，费率按百万分之一计，因此共用同一套算法。
+
+use anchor_lang::prelude::*;
+use crate::dex::curve::{self, mul_div, mul_div_ceil, CurveQuote, AVG_PRICE_SCALE};
+
+/// 最小 tick
+pub const MIN_TICK: i32 = -443_636;
+/// 最大 tick
+pub const MAX_TICK: i32 = 443_636;
+/// Q64.64 定点数 1.0
+pub const Q64: u128 = 1 << 64;
+/// 费率分母（百万分之一）
+pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
+/// 单次报价最多执行的 swap step 数量，防止计算单元耗尽
+const MAX_SWAP_STEPS: usize = 256;
+
+/// sqrt(1.0001)^(-2^k) 的 Q64.64 表示（与 Uniswap v3 TickMath 常量右移 64 位一致）
+const TICK_RATIOS: [(u32, u128); 19] = [
+    (0x1, 0xfffcb933bd6fad37),
+    (0x2, 0xfff97272373d4132),
+    (0x4, 0xfff2e50f5f656932),
+    (0x8, 0xffe5caca7e10e4e6),
+    (0x10, 0xffcb9843d60f6159),
+    (0x20, 0xff973b41fa98c081),
+    (0x40, 0xff2ea16466c96a38),
+    (0x80, 0xfe5dee046a99a2a8),
+    (0x100, 0xfcbe86c7900a88ae),
+    (0x200, 0xf987a7253ac41317),
+    (0x400, 0xf3392b0822b70005),
+    (0x800, 0xe7159475a2c29b74),
+    (0x1000, 0xd097f3bdfd2022b8),
+    (0x2000, 0xa9f746462d870fdf),
+    (0x4000, 0x70d869a156d2a1b8),
+    (0x8000, 0x31be135f97d08fd9),
+    (0x10000, 0x9aa508b5b7a84e1),
+    (0x20000, 0x5d6af8dedb8119),
+    (0x40000, 0x2216e584f5fa),
+];
+
+/// 已初始化 tick（仅保留报价所需的净流动性）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct ClmmTick {
+    pub index: i32,          // tick 索引
+    pub liquidity_net: i128, // 自左向右穿越时活跃流动性的变化量
+}
+
+/// 单个 tick array 解析结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickArrayTicks {
+    pub start_tick_index: i32, // 起始 tick
+    pub span: i32,             // 覆盖 tick 跨度（每数组 tick 数 * tick_spacing）
+    pub ticks: Vec<ClmmTick>,  // 数组内已初始化 tick
+}
+
+/// 若干连续 tick array 合并后的 tick 集合
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClmmTickSet {
+    pub ticks: Vec<ClmmTick>, // 按索引升序排列的已初始化 tick
+    pub lower: i32,           // 覆盖下界（含）
+    pub upper: i32,           // 覆盖上界（不含）
+}
+
+impl ClmmTickSet {
+    /// 合并 tick array；数组必须首尾相接，否则中间缺失的 tick 会导致报价错误
+    pub fn from_arrays(mut arrays: Vec<TickArrayTicks>) -> Option<Self> {
+        if arrays.is_empty() {
+            return None;
+        }
+        arrays.sort_by_key(|a| a.start_tick_index);
+        arrays.dedup_by_key(|a| a.start_tick_index);
+        let lower = arrays[0].start_tick_index;
+        let mut upper = lower;
+        let mut ticks = Vec::new();
+        for array in arrays {
+            if array.start_tick_index != upper || array.span <= 0 {
+                return None;
+            }
+            upper = array.start_tick_index.checked_add(array.span)?;
+            ticks.extend(array.ticks);
+        }
+        ticks.sort_by_key(|t| t.index);
+        Some(Self { ticks, lower, upper })
+    }
+
+    /// 覆盖范围是否包含指定 tick
+    pub fn covers(&self, tick: i32) -> bool {
+        tick >= self.lower && tick < self.upper
+    }
+}
+
+/// 池当前状态
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClmmPoolState {
+    pub sqrt_price: u128,  // 当前 sqrt price（Q64.64）
+    pub tick_current: i32, // 当前 tick
+    pub liquidity: u128,   // 当前活跃流动性
+    pub fee_rate: u32,     // 交易费率（百万分之一）
+}
+
+/// 单个 swap step 结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapStep {
+    pub sqrt_price_next: u128, // step 结束时的 sqrt price
+    pub amount_in: u128,       // 扣费后输入
+    pub amount_out: u128,      // 输出
+    pub fee: u128,             // 手续费
+}
+
+/// 集中流动性报价结果
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct ClmmQuote {
+    pub amount_in: u64,        // 实际消耗输入（含手续费），tick array 覆盖不足时小于请求数量
+    pub amount_out: u64,       // 输出数量
+    pub fee: u64,              // 手续费（输入币种计）
+    pub price_impact_bps: u64, // 价格冲击（基点，相对报价前边际价格）
+    pub sqrt_price_after: u128, // 成交后 sqrt price（Q64.64）
+    pub tick_after: i32,       // 成交后 tick
+    pub ticks_crossed: u32,    // 穿越的已初始化 tick 数量
+}
+
+impl ClmmQuote {
+    /// 转换为通用曲线报价，供 DexQuote 复用
+    pub fn to_curve_quote(&self) -> CurveQuote {
+        CurveQuote {
+            amount_in: self.amount_in,
+            amount_out: self.amount_out,
+            fee: self.fee,
+            price_impact_bps: self.price_impact_bps,
+        }
+    }
+
+    /// 成交后价格（每单位 A 可得 B，精度 AVG_PRICE_SCALE）
+    pub fn price_after(&self) -> Option<u128> {
+        sqrt_price_to_price(self.sqrt_price_after)
+    }
+}
+
+/// sqrt price 转换为价格（每单位 A 可得 B，精度 AVG_PRICE_SCALE）
+pub fn sqrt_price_to_price(sqrt_price: u128) -> Option<u128> {
+    mul_div(mul_div(sqrt_price, sqrt_price, Q64)?, AVG_PRICE_SCALE, Q64)
+}
+
+/// tick 对应的 sqrt price（Q64.64），即 sqrt(1.0001^tick) * 2^64
+pub fn sqrt_price_at_tick(tick: i32) -> Option<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = Q64;
+    for (bit, factor) in TICK_RATIOS {
+        if abs_tick & bit != 0 {
+            ratio = (ratio * factor) >> 64; // ratio <= 2^64，乘积不会溢出
+        }
+    }
+    if tick > 0 {
+        ratio = u128::MAX / ratio;
+    }
+    Some(ratio)
+}
+
+/// sqrt price 对应的 tick（满足 sqrt_price_at_tick(t) <= sqrt_price 的最大 t）
+pub fn tick_at_sqrt_price(sqrt_price: u128) -> Option<i32> {
+    if sqrt_price < sqrt_price_at_tick(MIN_TICK)? || sqrt_price > sqrt_price_at_tick(MAX_TICK)? {
+        return None;
+    }
+    let (mut lo, mut hi) = (MIN_TICK, MAX_TICK);
+    while lo < hi {
+        let mid = lo + (hi - lo + 1) / 2;
+        if sqrt_price_at_tick(mid)? <= sqrt_price {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Some(lo)
+}
+
+/// 价格区间内 A 数量：L * (upper - lower) / (upper * lower)
+pub fn amount_a_delta(sqrt_lower: u128, sqrt_upper: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    let (lower, upper) = (sqrt_lower.min(sqrt_upper), sqrt_lower.max(sqrt_upper));
+    if lower == 0 {
+        return None;
+    }
+    let diff = upper - lower;
+    if round_up {
+        mul_div_ceil(mul_div_ceil(liquidity, diff, lower)?, Q64, upper)
+    } else {
+        mul_div(mul_div(liquidity, diff, lower)?, Q64, upper)
+    }
+}
+
+/// 价格区间内 B 数量：L * (upper - lower)
+pub fn amount_b_delta(sqrt_lower: u128, sqrt_upper: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    let diff = sqrt_lower.abs_diff(sqrt_upper);
+    if round_up {
+        mul_div_ceil(liquidity, diff, Q64)
+    } else {
+        mul_div(liquidity, diff, Q64)
+    }
+}
+
+/// 给定扣费后输入，计算新的 sqrt price（取整方向保证不高估输出）
+pub fn next_sqrt_price_from_input(sqrt_price: u128, liquidity: u128, amount: u128, a_to_b: bool) -> Option<u128> {
+    if amount == 0 {
+        return Some(sqrt_price);
+    }
+    if liquidity == 0 {
+        return None;
+    }
+    if a_to_b {
+        // L * P / (L + amount * P)，向上取整
+        let denominator = liquidity.checked_add(mul_div(amount, sqrt_price, Q64)?)?;
+        mul_div_ceil(liquidity, sqrt_price, denominator)
+    } else {
+        // P + amount / L，向下取整
+        sqrt_price.checked_add(mul_div(amount, Q64, liquidity)?)
+    }
+}
+
+/// 单区间 swap step（精确输入）
+pub fn compute_swap_step(
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u128,
+    fee_rate: u32,
+) -> Option<SwapStep> {
+    if fee_rate >= FEE_RATE_DENOMINATOR {
+        return None;
+    }
+    let a_to_b = sqrt_price_target <= sqrt_price_current;
+    let fee_complement = (FEE_RATE_DENOMINATOR - fee_rate) as u128;
+    let remaining_less_fee = mul_div(amount_remaining, fee_complement, FEE_RATE_DENOMINATOR as u128)?;
+    let amount_in_to_target = if a_to_b {
+        amount_a_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?
+    } else {
+        amount_b_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?
+    };
+    let reached = remaining_less_fee >= amount_in_to_target;
+    let sqrt_price_next = if reached {
+        sqrt_price_target
+    } else {
+        next_sqrt_price_from_input(sqrt_price_current, liquidity, remaining_less_fee, a_to_b)?
+    };
+    let (amount_in, amount_out) = if a_to_b {
+        (
+            amount_a_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?,
+            amount_b_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?,
+        )
+    } else {
+        (
+            amount_b_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?,
+            amount_a_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?,
+        )
+    };
+    let amount_in = amount_in.min(amount_remaining);
+    let fee = if reached {
+        mul_div_ceil(amount_in, fee_rate as u128, fee_complement)?.min(amount_remaining - amount_in)
+    } else {
+        // 未到达目标价格时剩余输入全部计入手续费，避免残留
+        amount_remaining - amount_in
+    };
+    Some(SwapStep { sqrt_price_next, amount_in, amount_out, fee })
+}
+
+/// 沿已初始化 tick 逐段报价（精确输入）
+/// - a_to_b: true 表示 A 换 B（价格下降）
+/// - 报价在 tick 集合覆盖边界处停止，调用方需检查 amount_in 是否全部消耗
+pub fn swap_quote(pool: &ClmmPoolState, tick_set: &ClmmTickSet, amount_in: u64, a_to_b: bool) -> Option<ClmmQuote> {
+    if amount_in == 0 || pool.sqrt_price == 0 || !tick_set.covers(pool.tick_current) {
+        return None;
+    }
+    let limit_tick = if a_to_b { tick_set.lower.max(MIN_TICK) } else { (tick_set.upper - 1).min(MAX_TICK) };
+    let sqrt_price_limit = sqrt_price_at_tick(limit_tick)?;
+    let mut sqrt_price = pool.sqrt_price;
+    let mut tick = pool.tick_current;
+    let mut liquidity = pool.liquidity;
+    let mut remaining = amount_in as u128;
+    let (mut amount_out, mut fee, mut ticks_crossed) = (0u128, 0u128, 0u32);
+
+    for _ in 0..MAX_SWAP_STEPS {
+        if remaining == 0 || sqrt_price == sqrt_price_limit {
+            break;
+        }
+        // 下一个已初始化 tick：A→B 向左取 <= 当前 tick，B→A 向右取 > 当前 tick
+        let next_tick = if a_to_b {
+            tick_set.ticks.iter().rev().find(|t| t.index <= tick && t.index >= limit_tick)
+        } else {
+            tick_set.ticks.iter().find(|t| t.index > tick && t.index <= limit_tick)
+        };
+        let target_tick = next_tick.map(|t| t.index).unwrap_or(limit_tick);
+        let sqrt_price_target = sqrt_price_at_tick(target_tick)?;
+        let step = compute_swap_step(sqrt_price, sqrt_price_target, liquidity, remaining, pool.fee_rate)?;
+        remaining = remaining.checked_sub(step.amount_in.checked_add(step.fee)?)?;
+        amount_out = amount_out.checked_add(step.amount_out)?;
+        fee = fee.checked_add(step.fee)?;
+        if step.sqrt_price_next == sqrt_price_target {
+            if let Some(crossed) = next_tick {
+                // 穿越 tick：A→B 向左穿越需减去 liquidity_net
+                let net = if a_to_b { crossed.liquidity_net.checked_neg()? } else { crossed.liquidity_net };
+                liquidity = u128::try_from((liquidity as i128).checked_add(net)?).ok()?;
+                ticks_crossed += 1;
+            }
+            tick = if a_to_b { target_tick - 1 } else { target_tick };
+        } else {
+            tick = tick_at_sqrt_price(step.sqrt_price_next)?;
+        }
+        sqrt_price = step.sqrt_price_next;
+    }
+
+    let consumed = amount_in as u128 - remaining;
+    let net_in = consumed.checked_sub(fee)?;
+    // 按报价前边际价格应得输出
+    let spot_out = if a_to_b {
+        mul_div(mul_div(net_in, pool.sqrt_price, Q64)?, pool.sqrt_price, Q64)?
+    } else {
+        mul_div(mul_div(net_in, Q64, pool.sqrt_price)?, Q64, pool.sqrt_price)?
+    };
+    Some(ClmmQuote {
+        amount_in: u64::try_from(consumed).ok()?,
+        amount_out: u64::try_from(amount_out).ok()?,
+        fee: u64::try_from(fee).ok()?,
+        price_impact_bps: curve::price_impact_bps(spot_out, amount_out),
+        sqrt_price_after: sqrt_price,
+        tick_after: tick,
+        ticks_crossed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_array(lower: i32, upper: i32, ticks: Vec<ClmmTick>) -> ClmmTickSet {
+        ClmmTickSet::from_arrays(vec![TickArrayTicks { start_tick_index: lower, span: upper - lower, ticks }]).unwrap()
+    }
+
+    #[test]
+    fn test_sqrt_price_at_tick() {
+        assert_eq!(sqrt_price_at_tick(0), Some(Q64));
+        let up = sqrt_price_at_tick(1).unwrap();
+        let down = sqrt_price_at_tick(-1).unwrap();
+        assert_eq!(up - Q64, 922_314_146_408_235); // ≈ 2^64 * (sqrt(1.0001) - 1)
+        assert!(down < Q64 && up > Q64);
+        assert!(sqrt_price_at_tick(MAX_TICK + 1).is_none());
+        for tick in [-443_636, -50_000, -887, -1, 0, 1, 64, 12_345, 443_636] {
+            assert_eq!(tick_at_sqrt_price(sqrt_price_at_tick(tick).unwrap()), Some(tick));
+        }
+        assert_eq!(tick_at_sqrt_price(sqrt_price_at_tick(100).unwrap() - 1), Some(99));
+    }
+
+    #[test]
+    fn test_swap_within_single_range() {
+        let pool = ClmmPoolState { sqrt_price: Q64, tick_current: 0, liquidity: 1_000_000_000_000, fee_rate: 3_000 };
+        let ticks = single_array(-1_000, 1_000, vec![]);
+        let q = swap_quote(&pool, &ticks, 1_000_000, true).unwrap();
+        assert_eq!(q.amount_in, 1_000_000);
+        assert_eq!(q.fee, 3_000);
+        // 等价于 L=1e12 的常积池：1e12 * 997_000 / (1e12 + 997_000)
+        assert!(q.amount_out == 996_999 || q.amount_out == 996_998);
+        assert!(q.sqrt_price_after < Q64);
+        assert_eq!(q.tick_after, -1);
+        assert_eq!(q.ticks_crossed, 0);
+        // 反向同理
+        let q = swap_quote(&pool, &ticks, 1_000_000, false).unwrap();
+        assert!(q.amount_out >= 996_998 && q.amount_out <= 996_999);
+        assert_eq!(q.tick_after, 0);
+    }
+
+    #[test]
+    fn test_swap_crosses_initialized_tick() {
+        // [-100, 100) 区间深度 1e10，其外侧仅剩 1e9
+        let pool = ClmmPoolState { sqrt_price: Q64, tick_current: 0, liquidity: 10_000_000_000, fee_rate: 0 };
+        let ticks = single_array(-5_000, 5_000, vec![
+            ClmmTick { index: -100, liquidity_net: 9_000_000_000 },
+            ClmmTick { index: 100, liquidity_net: -9_000_000_000 },
+        ]);
+        // 耗尽 [-100, 0] 区间所需 A 数量
+        let to_boundary = amount_a_delta(sqrt_price_at_tick(-100).unwrap(), Q64, 10_000_000_000, true).unwrap() as u64;
+        let small = swap_quote(&pool, &ticks, to_boundary - 1, true).unwrap();
+        assert_eq!(small.ticks_crossed, 0);
+        let large = swap_quote(&pool, &ticks, to_boundary * 2, true).unwrap();
+        assert_eq!(large.ticks_crossed, 1);
+        assert!(large.tick_after < -100);
+        assert_eq!(large.amount_in, to_boundary * 2);
+        // 穿越后深度变浅：与始终保持 1e10 深度的单一流动性模型相比，输出明显更少
+        let single_liquidity = swap_quote(&pool, &single_array(-5_000, 5_000, vec![]), to_boundary * 2, true).unwrap();
+        assert!(large.amount_out < single_liquidity.amount_out);
+        assert!(large.price_impact_bps > single_liquidity.price_impact_bps * 3);
+        assert!(large.price_impact_bps > small.price_impact_bps);
+    }
+
+    #[test]
+    fn test_swap_stops_at_tick_array_boundary() {
+        let pool = ClmmPoolState { sqrt_price: Q64, tick_current: 0, liquidity: 1_000_000, fee_rate: 0 };
+        let ticks = single_array(-64, 64, vec![]);
+        let q = swap_quote(&pool, &ticks, 1_000_000_000, true).unwrap();
+        assert!(q.amount_in < 1_000_000_000);
+        assert_eq!(q.sqrt_price_after, sqrt_price_at_tick(-64).unwrap());
+        // 当前 tick 不在覆盖范围内
+        let outside = ClmmPoolState { tick_current: 64, ..pool };
+        assert!(swap_quote(&outside, &ticks, 1, true).is_none());
+    }
+
+    #[test]
+    fn test_tick_set_requires_contiguous_arrays() {
+        let a = TickArrayTicks { start_tick_index: 0, span: 88, ticks: vec![ClmmTick { index: 8, liquidity_net: 1 }] };
+        let b = TickArrayTicks { start_tick_index: -88, span: 88, ticks: vec![ClmmTick { index: -8, liquidity_net: -1 }] };
+        let gap = TickArrayTicks { start_tick_index: 176, span: 88, ticks: vec![] };
+        let set = ClmmTickSet::from_arrays(vec![a.clone(), b]).unwrap();
+        assert_eq!((set.lower, set.upper), (-88, 88));
+        assert_eq!(set.ticks[0].index, -8);
+        assert!(ClmmTickSet::from_arrays(vec![a, gap]).is_none());
+    }
+}
@@ -0,0 +1,313 @@
+//!
+//! AMM Curve Math Module
+//!
+//! 本模块实现 AMM 池报价所需的曲线数学：常积曲线（x*y=k）与 StableSwap 不变量（n=2），
+//! 统一输出成交数量、手续费、价格冲击，供 Raydium/Orca/Lifinity 等适配器基于链上池状态报价。
+//! 全部采用整数运算（u128 + 256 位中间乘积），结果与链上程序的取整方向保持一致，可在合约内直接使用。
+
+use anchor_lang::prelude::*;
+
+/// 均价精度（与 DexSwapResult.avg_price 精度一致，1e6）
+pub const AVG_PRICE_SCALE: u128 = 1_000_000;
+/// 基点分母
+const BPS_DENOMINATOR: u128 = 10_000;
+/// StableSwap 池币种数量
+const N_COINS: u128 = 2;
+/// Newton 迭代最大次数
+const MAX_NEWTON_ITERATIONS: usize = 64;
+
+/// 曲线类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum CurveType {
+    /// 常积曲线 x*y=k
+    ConstantProduct,
+    /// StableSwap 曲线，附带放大系数 A
+    Stable { amp: u64 },
+}
+
+/// 手续费率（分子/分母，按输入收取）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct FeeRate {
+    pub numerator: u64,   // 费率分子
+    pub denominator: u64, // 费率分母
+}
+
+impl FeeRate {
+    /// 构造费率
+    pub fn new(numerator: u64, denominator: u64) -> Self {
+        Self { numerator, denominator }
+    }
+
+    /// 合并两档按输入收取的费率（如交易费 + 协议费）
+    pub fn combine(self, other: FeeRate) -> Option<FeeRate> {
+        if self.denominator == 0 || self.numerator == 0 {
+            return Some(other);
+        }
+        if other.denominator == 0 || other.numerator == 0 {
+            return Some(self);
+        }
+        let den = (self.denominator as u128).checked_mul(other.denominator as u128)?;
+        let num = (self.numerator as u128 * other.denominator as u128)
+            .checked_add(other.numerator as u128 * self.denominator as u128)?;
+        Some(FeeRate {
+            numerator: u64::try_from(num).ok()?,
+            denominator: u64::try_from(den).ok()?,
+        })
+    }
+
+    /// 计算输入金额应收手续费（向上取整，与链上程序一致）
+    pub fn fee_on(&self, amount: u64) -> Option<u64> {
+        if self.numerator == 0 || self.denominator == 0 || amount == 0 {
+            return Some(0);
+        }
+        let num = (amount as u128).checked_mul(self.numerator as u128)?;
+        let fee = (num + self.denominator as u128 - 1) / self.denominator as u128;
+        u64::try_from(fee.max(1)).ok()
+    }
+}
+
+/// 单池报价结果
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct CurveQuote {
+    pub amount_in: u64,        // 输入数量（含手续费）
+    pub amount_out: u64,       // 输出数量
+    pub fee: u64,              // 手续费（输入币种计）
+    pub price_impact_bps: u64, // 价格冲击（基点，相对池当前边际价格）
+}
+
+impl CurveQuote {
+    /// 成交均价（每单位输入可得输出，精度 AVG_PRICE_SCALE）
+    pub fn avg_price(&self) -> u64 {
+        if self.amount_in == 0 {
+            return 0;
+        }
+        ((self.amount_out as u128 * AVG_PRICE_SCALE) / self.amount_in as u128) as u64
+    }
+}
+
+/// 按曲线类型计算报价
+/// - reserve_in/reserve_out: 输入/输出侧池储备
+/// - amount_in: 输入数量（含手续费）
+/// - fee: 按输入收取的费率
+pub fn quote(curve: CurveType, reserve_in: u64, reserve_out: u64, amount_in: u64, fee: FeeRate) -> Option<CurveQuote> {
+    match curve {
+        CurveType::ConstantProduct => constant_product_quote(reserve_in, reserve_out, amount_in, fee),
+        CurveType::Stable { amp } => stable_swap_quote(amp, reserve_in, reserve_out, amount_in, fee),
+    }
+}
+
+/// 常积曲线报价：out = reserve_out * in' / (reserve_in + in')，in' 为扣费后输入
+pub fn constant_product_quote(reserve_in: u64, reserve_out: u64, amount_in: u64, fee: FeeRate) -> Option<CurveQuote> {
+    if reserve_in == 0 || reserve_out == 0 || amount_in == 0 {
+        return None;
+    }
+    let fee_amount = fee.fee_on(amount_in)?;
+    let net_in = amount_in.checked_sub(fee_amount)? as u128;
+    let amount_out = (reserve_out as u128).checked_mul(net_in)? / (reserve_in as u128).checked_add(net_in)?;
+    let spot_out = (reserve_out as u128).checked_mul(net_in)? / reserve_in as u128; // 按边际价格应得输出
+    Some(CurveQuote {
+        amount_in,
+        amount_out: u64::try_from(amount_out).ok()?,
+        fee: fee_amount,
+        price_impact_bps: price_impact_bps(spot_out, amount_out),
+    })
+}
+
+/// StableSwap 报价（Curve 不变量，n=2，与 spl-token-swap 稳定曲线实现一致）
+pub fn stable_swap_quote(amp: u64, reserve_in: u64, reserve_out: u64, amount_in: u64, fee: FeeRate) -> Option<CurveQuote> {
+    if reserve_in == 0 || reserve_out == 0 || amount_in == 0 || amp == 0 {
+        return None;
+    }
+    let fee_amount = fee.fee_on(amount_in)?;
+    let net_in = amount_in.checked_sub(fee_amount)? as u128;
+    let amount_out = stable_swap_out(amp, reserve_in as u128, reserve_out as u128, net_in)?;
+    // 以极小输入近似边际价格，计算价格冲击
+    let probe = ((reserve_in as u128) / 1_000_000).max(1);
+    let probe_out = stable_swap_out(amp, reserve_in as u128, reserve_out as u128, probe)?;
+    let spot_out = mul_div(net_in, probe_out, probe)?;
+    Some(CurveQuote {
+        amount_in,
+        amount_out: u64::try_from(amount_out).ok()?,
+        fee: fee_amount,
+        price_impact_bps: price_impact_bps(spot_out, amount_out),
+    })
+}
+
+/// StableSwap 扣费后输出数量
+fn stable_swap_out(amp: u64, reserve_in: u128, reserve_out: u128, net_in: u128) -> Option<u128> {
+    let leverage = (amp as u128).checked_mul(N_COINS)?;
+    let d = compute_d(leverage, reserve_in, reserve_out)?;
+    let new_in = reserve_in.checked_add(net_in)?;
+    let new_out = compute_y(leverage, new_in, d)?;
+    // 向下取整：多减 1 防止因 Newton 取整导致的超额输出
+    Some(reserve_out.checked_sub(new_out)?.saturating_sub(1))
+}
+
+/// 计算 StableSwap 不变量 D
+pub fn compute_d(leverage: u128, amount_a: u128, amount_b: u128) -> Option<u128> {
+    let sum_x = amount_a.checked_add(amount_b)?;
+    if sum_x == 0 {
+        return Some(0);
+    }
+    if amount_a == 0 || amount_b == 0 {
+        return None;
+    }
+    let mut d = sum_x;
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let mut d_product = d;
+        d_product = mul_div(d_product, d, amount_a.checked_mul(N_COINS)?)?;
+        d_product = mul_div(d_product, d, amount_b.checked_mul(N_COINS)?)?;
+        let d_prev = d;
+        let numerator = leverage
+            .checked_mul(sum_x)?
+            .checked_add(d_product.checked_mul(N_COINS)?)?;
+        let denominator = leverage
+            .checked_sub(1)?
+            .checked_mul(d)?
+            .checked_add(d_product.checked_mul(N_COINS + 1)?)?;
+        d = mul_div(numerator, d, denominator)?;
+        if d.abs_diff(d_prev) <= 1 {
+            return Some(d);
+        }
+    }
+    Some(d)
+}
+
+/// 给定一侧新储备与不变量 D，求另一侧储备 y
+pub fn compute_y(leverage: u128, new_source_amount: u128, d: u128) -> Option<u128> {
+    if new_source_amount == 0 {
+        return None;
+    }
+    let mut c = mul_div(d, d, new_source_amount.checked_mul(N_COINS)?)?;
+    c = mul_div(c, d, leverage.checked_mul(N_COINS)?)?;
+    let b = new_source_amount.checked_add(d / leverage)?;
+    let mut y = d;
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let y_prev = y;
+        let numerator = y.checked_mul(y)?.checked_add(c)?;
+        let denominator = y.checked_mul(2)?.checked_add(b)?.checked_sub(d)?;
+        y = numerator / denominator;
+        if y.abs_diff(y_prev) <= 1 {
+            return Some(y);
+        }
+    }
+    Some(y)
+}
+
+/// 价格冲击（基点）：(spot_out - actual_out) / spot_out
+pub fn price_impact_bps(spot_out: u128, actual_out: u128) -> u64 {
+    if spot_out == 0 || actual_out >= spot_out {
+        return 0;
+    }
+    ((spot_out - actual_out) * BPS_DENOMINATOR / spot_out) as u64
+}
+
+/// 计算 a * b / denominator（256 位中间乘积，向下取整），结果溢出 u128 时返回 None
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let (hi, lo) = full_mul(a, b);
+    if hi == 0 {
+        return Some(lo / denominator);
+    }
+    if hi >= denominator {
+        return None; // 商超出 u128
+    }
+    // 256/128 位长除法，余数始终小于除数
+    let mut remainder = hi;
+    let mut quotient: u128 = 0;
+    for i in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
+/// 计算 a * b / denominator（256 位中间乘积，向上取整）
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    let quotient = mul_div(a, b, denominator)?;
+    if full_mul(quotient, denominator) == full_mul(a, b) {
+        Some(quotient)
+    } else {
+        quotient.checked_add(1)
+    }
+}
+
+/// u128 × u128 → (高 128 位, 低 128 位)
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a0, a1) = (a & MASK, a >> 64);
+    let (b0, b1) = (b & MASK, b >> 64);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_wide_intermediate() {
+        assert_eq!(mul_div(10, 20, 3), Some(66));
+        let big = u128::MAX / 3;
+        assert_eq!(mul_div(big, 6, 6), Some(big));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+        assert_eq!(mul_div_ceil(10, 20, 3), Some(67));
+        assert_eq!(mul_div_ceil(10, 21, 3), Some(70));
+        assert_eq!(mul_div_ceil(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+    }
+
+    #[test]
+    fn test_constant_product_quote() {
+        // 1_000_000 / 1_000_000 池，输入 10_000，费率 0.25%
+        let q = constant_product_quote(1_000_000, 1_000_000, 10_000, FeeRate::new(25, 10_000)).unwrap();
+        assert_eq!(q.fee, 25);
+        assert_eq!(q.amount_out, 9_876); // 1e6 * 9975 / 1_009_975
+        assert_eq!(q.price_impact_bps, 99);
+        assert!(q.avg_price() < 1_000_000);
+        // 无手续费时 k 不减少
+        let q = constant_product_quote(500, 2_000, 100, FeeRate::new(0, 0)).unwrap();
+        assert!((500u128 + 100) * (2_000u128 - q.amount_out as u128) >= 500 * 2_000);
+        assert!(constant_product_quote(0, 1, 1, FeeRate::new(0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_stable_swap_quote_is_near_parity() {
+        let reserve = 1_000_000_000_000u64;
+        let q = stable_swap_quote(100, reserve, reserve, 1_000_000_000, FeeRate::new(4, 10_000)).unwrap();
+        let cp = constant_product_quote(reserve, reserve, 1_000_000_000, FeeRate::new(4, 10_000)).unwrap();
+        assert!(q.amount_out > cp.amount_out); // 稳定曲线滑点小于常积曲线
+        assert!(q.amount_out <= 1_000_000_000 - q.fee);
+        assert!(q.price_impact_bps <= 1);
+    }
+
+    #[test]
+    fn test_stable_swap_invariant_holds() {
+        let leverage = 200u128;
+        let d = compute_d(leverage, 5_000_000, 3_000_000).unwrap();
+        let out = stable_swap_out(100, 5_000_000, 3_000_000, 100_000).unwrap();
+        let d_after = compute_d(leverage, 5_100_000, 3_000_000 - out).unwrap();
+        assert!(d_after >= d); // 交易后不变量不减少
+    }
+
+    #[test]
+    fn test_fee_rate_combine() {
+        let combined = FeeRate::new(25, 10_000).combine(FeeRate::new(5, 10_000)).unwrap();
+        assert_eq!(combined.fee_on(1_000_000), Some(3_000));
+        assert_eq!(FeeRate::new(0, 0).combine(FeeRate::new(1, 100)), Some(FeeRate::new(1, 100)));
+    }
+}
@@ -0,0 +1,406 @@
+//!
+//! Dynamic Liquidity Market Maker (DLMM) Math Module
+//!
+//! 本模块实现 Meteora DLMM（lb_clmm）按 bin 报价的数学：bin 价格 (1 + bin_step/10000)^id（Q64.64）、
+//! 基础费率 + 波动率累加器驱动的可变费率、逐 bin 消耗流动性并更新波动率，输出精确成交数量、手续费与成交后活跃 bin。
+//! 取整方向与链上程序一致：输入与手续费向上取整、输出向下取整。
+
+use anchor_lang::prelude::*;
+use crate::dex::curve::{self, mul_div, mul_div_ceil, CurveQuote};
+use std::collections::BTreeMap;
+
+/// 每个 BinArray 包含的 bin 数
+pub const MAX_BIN_PER_ARRAY: i32 = 70;
+/// 费率精度（1e9 = 100%）
+pub const FEE_PRECISION: u128 = 1_000_000_000;
+/// 最大总费率（10%）
+pub const MAX_FEE_RATE: u128 = 100_000_000;
+/// 基点上限
+pub const BASIS_POINT_MAX: u128 = 10_000;
+/// Q64.64 定点数 1.0
+pub const Q64: u128 = 1 << 64;
+/// 可变费率缩放因子
+const VARIABLE_FEE_SCALE: u128 = 100_000_000_000;
+
+/// 费率静态参数（对应 lb_pair.parameters）
+#[derive(Clone, Debug, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmFeeParameters {
+    pub base_factor: u16,                 // 基础费率因子
+    pub filter_period: u16,               // 高频过滤周期（秒）
+    pub decay_period: u16,                // 波动率衰减周期（秒）
+    pub reduction_factor: u16,            // 波动率衰减系数（基点）
+    pub variable_fee_control: u32,        // 可变费率控制参数
+    pub max_volatility_accumulator: u32,  // 波动率累加器上限
+    pub base_fee_power_factor: u8,        // 基础费率 10 的幂次
+}
+
+/// 波动率动态参数（对应 lb_pair.v_parameters）
+#[derive(Clone, Debug, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmVolatilityState {
+    pub volatility_accumulator: u32, // 波动率累加器
+    pub volatility_reference: u32,   // 波动率参考值
+    pub index_reference: i32,        // 参考 bin
+    pub last_update_timestamp: i64,  // 上次更新时间戳
+}
+
+/// 交易对报价所需状态
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmPairState {
+    pub active_id: i32,                  // 当前活跃 bin
+    pub bin_step: u16,                   // bin 间价格步长（基点）
+    pub fees: DlmmFeeParameters,         // 费率静态参数
+    pub volatility: DlmmVolatilityState, // 波动率动态参数
+}
+
+impl DlmmPairState {
+    /// 基础费率：base_factor * bin_step * 10 * 10^power（精度 1e9）
+    pub fn base_fee_rate(&self) -> u128 {
+        self.fees.base_factor as u128
+            * self.bin_step as u128
+            * 10
+            * 10u128.pow(self.fees.base_fee_power_factor as u32)
+    }
+
+    /// 可变费率：variable_fee_control * (volatility_accumulator * bin_step)^2，向上取整
+    pub fn variable_fee_rate(&self) -> u128 {
+        if self.fees.variable_fee_control == 0 {
+            return 0;
+        }
+        let square = (self.volatility.volatility_accumulator as u128 * self.bin_step as u128).pow(2);
+        let v_fee = self.fees.variable_fee_control as u128 * square;
+        (v_fee + VARIABLE_FEE_SCALE - 1) / VARIABLE_FEE_SCALE
+    }
+
+    /// 总费率（封顶 MAX_FEE_RATE）
+    pub fn total_fee_rate(&self) -> u128 {
+        (self.base_fee_rate() + self.variable_fee_rate()).min(MAX_FEE_RATE)
+    }
+
+    /// swap 开始前按时间更新参考 bin 与波动率参考值
+    pub fn update_references(&mut self, now: i64) {
+        let elapsed = now.saturating_sub(self.volatility.last_update_timestamp);
+        if elapsed >= self.fees.filter_period as i64 {
+            self.volatility.index_reference = self.active_id;
+            self.volatility.volatility_reference = if elapsed < self.fees.decay_period as i64 {
+                (self.volatility.volatility_accumulator as u128 * self.fees.reduction_factor as u128 / BASIS_POINT_MAX) as u32
+            } else {
+                0
+            };
+        }
+    }
+
+    /// 进入新 bin 时更新波动率累加器
+    pub fn update_volatility_accumulator(&mut self, active_id: i32) {
+        let delta_id = self.volatility.index_reference.abs_diff(active_id) as u128;
+        let accumulator = self.volatility.volatility_reference as u128 + delta_id * BASIS_POINT_MAX;
+        self.volatility.volatility_accumulator = accumulator.min(self.fees.max_volatility_accumulator as u128) as u32;
+    }
+}
+
+/// 单个 bin 储备
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmBin {
+    pub id: i32,        // bin id
+    pub amount_x: u64,  // X 储备
+    pub amount_y: u64,  // Y 储备
+}
+
+/// 单个 BinArray 解析结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinArrayBins {
+    pub index: i64,          // BinArray 索引
+    pub bins: Vec<DlmmBin>,  // 数组内有储备的 bin
+}
+
+/// 若干连续 BinArray 合并后的 bin 集合
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DlmmBinSet {
+    pub bins: BTreeMap<i32, DlmmBin>, // bin id -> 储备
+    pub lower: i32,                   // 覆盖下界（含）
+    pub upper: i32,                   // 覆盖上界（含）
+}
+
+impl DlmmBinSet {
+    /// 合并 BinArray；数组索引必须连续，否则中间缺失的 bin 会导致报价错误
+    pub fn from_arrays(mut arrays: Vec<BinArrayBins>) -> Option<Self> {
+        if arrays.is_empty() {
+            return None;
+        }
+        arrays.sort_by_key(|a| a.index);
+        arrays.dedup_by_key(|a| a.index);
+        for pair in arrays.windows(2) {
+            if pair[1].index != pair[0].index + 1 {
+                return None;
+            }
+        }
+        let (lower, _) = bin_array_bounds(arrays[0].index)?;
+        let (_, upper) = bin_array_bounds(arrays[arrays.len() - 1].index)?;
+        let mut bins = BTreeMap::new();
+        for bin in arrays.into_iter().flat_map(|a| a.bins) {
+            bins.insert(bin.id, bin);
+        }
+        Some(Self { bins, lower, upper })
+    }
+
+    /// 覆盖范围是否包含指定 bin
+    pub fn covers(&self, bin_id: i32) -> bool {
+        bin_id >= self.lower && bin_id <= self.upper
+    }
+}
+
+/// bin 所在 BinArray 索引（向负无穷取整）
+pub fn bin_array_index(bin_id: i32) -> i64 {
+    (bin_id as i64).div_euclid(MAX_BIN_PER_ARRAY as i64)
+}
+
+/// BinArray 覆盖的 bin id 区间（含两端）
+pub fn bin_array_bounds(index: i64) -> Option<(i32, i32)> {
+    let lower = i32::try_from(index.checked_mul(MAX_BIN_PER_ARRAY as i64)?).ok()?;
+    Some((lower, lower.checked_add(MAX_BIN_PER_ARRAY - 1)?))
+}
+
+/// bin 价格（每单位 X 可得 Y，Q64.64）：(1 + bin_step / 10000)^bin_id
+pub fn price_from_bin_id(bin_id: i32, bin_step: u16) -> Option<u128> {
+    let base = Q64.checked_add(((bin_step as u128) << 64) / BASIS_POINT_MAX)?;
+    let mut exp = bin_id.unsigned_abs();
+    let (mut result, mut square) = (Q64, base);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_div(result, square, Q64)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            square = mul_div(square, square, Q64)?;
+        }
+    }
+    if bin_id < 0 {
+        result = mul_div(Q64, Q64, result)?;
+    }
+    (result > 0).then_some(result)
+}
+
+/// DLMM 报价结果
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmQuote {
+    pub amount_in: u64,        // 实际消耗输入（含手续费），BinArray 覆盖不足时小于请求数量
+    pub amount_out: u64,       // 输出数量
+    pub fee: u64,              // 手续费（输入币种计）
+    pub price_impact_bps: u64, // 价格冲击（基点，相对报价前活跃 bin 价格）
+    pub active_id_after: i32,  // 成交后活跃 bin
+    pub bins_crossed: u32,     // 完全消耗并越过的 bin 数量
+}
+
+impl DlmmQuote {
+    /// 转换为通用曲线报价，供 DexQuote 复用
+    pub fn to_curve_quote(&self) -> CurveQuote {
+        CurveQuote {
+            amount_in: self.amount_in,
+            amount_out: self.amount_out,
+            fee: self.fee,
+            price_impact_bps: self.price_impact_bps,
+        }
+    }
+}
+
+/// 逐 bin 报价（精确输入）
+/// - swap_for_y: true 表示 X 换 Y（活跃 bin 向左移动）
+/// - now: 当前 Unix 时间戳，用于波动率参考值衰减
+/// - 报价在 bin 集合覆盖边界处停止，调用方需检查 amount_in 是否全部消耗
+pub fn swap_quote(pair: &DlmmPairState, bin_set: &DlmmBinSet, amount_in: u64, swap_for_y: bool, now: i64) -> Option<DlmmQuote> {
+    if amount_in == 0 || !bin_set.covers(pair.active_id) {
+        return None;
+    }
+    let mut state = pair.clone();
+    state.update_references(now);
+    let mut active_id = pair.active_id;
+    let mut remaining = amount_in as u128;
+    let (mut amount_out, mut fee, mut bins_crossed) = (0u128, 0u128, 0u32);
+
+    while remaining > 0 && bin_set.covers(active_id) {
+        state.update_volatility_accumulator(active_id);
+        let fee_rate = state.total_fee_rate();
+        let price = price_from_bin_id(active_id, pair.bin_step)?;
+        let bin = bin_set.bins.get(&active_id).copied().unwrap_or_default();
+        let available = if swap_for_y { bin.amount_y } else { bin.amount_x } as u128;
+        if available > 0 {
+            // 吃完本 bin 所需输入（不含费）
+            let max_in = if swap_for_y {
+                mul_div_ceil(available, Q64, price)?
+            } else {
+                mul_div_ceil(available, price, Q64)?
+            };
+            let max_fee = mul_div_ceil(max_in, fee_rate, FEE_PRECISION - fee_rate)?;
+            let max_in_with_fee = max_in.checked_add(max_fee)?;
+            if remaining < max_in_with_fee {
+                // 部分成交：剩余输入扣费后全部在本 bin 成交
+                let step_fee = mul_div_ceil(remaining, fee_rate, FEE_PRECISION)?;
+                let net_in = remaining - step_fee;
+                let out = if swap_for_y { mul_div(net_in, price, Q64)? } else { mul_div(net_in, Q64, price)? };
+                amount_out = amount_out.checked_add(out.min(available))?;
+                fee = fee.checked_add(step_fee)?;
+                remaining = 0;
+                break;
+            }
+            remaining -= max_in_with_fee;
+            amount_out = amount_out.checked_add(available)?;
+            fee = fee.checked_add(max_fee)?;
+        }
+        active_id = if swap_for_y { active_id.checked_sub(1)? } else { active_id.checked_add(1)? };
+        bins_crossed += 1;
+    }
+
+    let consumed = amount_in as u128 - remaining;
+    let net_in = consumed.checked_sub(fee)?;
+    let start_price = price_from_bin_id(pair.active_id, pair.bin_step)?;
+    let spot_out = if swap_for_y { mul_div(net_in, start_price, Q64)? } else { mul_div(net_in, Q64, start_price)? };
+    Some(DlmmQuote {
+        amount_in: u64::try_from(consumed).ok()?,
+        amount_out: u64::try_from(amount_out).ok()?,
+        fee: u64::try_from(fee).ok()?,
+        price_impact_bps: curve::price_impact_bps(spot_out, amount_out),
+        active_id_after: active_id,
+        bins_crossed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(variable_fee_control: u32) -> DlmmPairState {
+        DlmmPairState {
+            active_id: 0,
+            bin_step: 10,
+            fees: DlmmFeeParameters {
+                base_factor: 10_000,
+                filter_period: 30,
+                decay_period: 600,
+                reduction_factor: 5_000,
+                variable_fee_control,
+                max_volatility_accumulator: 350_000,
+                base_fee_power_factor: 0,
+            },
+            volatility: DlmmVolatilityState::default(),
+        }
+    }
+
+    fn bins(ids: std::ops::RangeInclusive<i32>, amount_x: u64, amount_y: u64) -> DlmmBinSet {
+        let arrays = (bin_array_index(*ids.start())..=bin_array_index(*ids.end()))
+            .map(|index| BinArrayBins {
+                index,
+                bins: ids.clone()
+                    .filter(|id| bin_array_index(*id) == index)
+                    .map(|id| DlmmBin { id, amount_x: if id > 0 { amount_x } else { 0 }, amount_y: if id <= 0 { amount_y } else { 0 } })
+                    .collect(),
+            })
+            .collect();
+        DlmmBinSet::from_arrays(arrays).unwrap()
+    }
+
+    #[test]
+    fn test_bin_price_and_array_index() {
+        assert_eq!(price_from_bin_id(0, 10), Some(Q64));
+        let up = price_from_bin_id(1, 10).unwrap();
+        assert_eq!(up, Q64 + (10u128 << 64) / 10_000);
+        let down = price_from_bin_id(-1, 10).unwrap();
+        assert!(mul_div(up, down, Q64).unwrap().abs_diff(Q64) <= 1);
+        // (1.001)^1000 ≈ 2.7169
+        let far = price_from_bin_id(1_000, 10).unwrap();
+        assert_eq!(mul_div(far, 10_000, Q64).unwrap(), 27_169);
+        assert_eq!(bin_array_index(0), 0);
+        assert_eq!(bin_array_index(69), 0);
+        assert_eq!(bin_array_index(70), 1);
+        assert_eq!(bin_array_index(-1), -1);
+        assert_eq!(bin_array_index(-70), -1);
+        assert_eq!(bin_array_index(-71), -2);
+        assert_eq!(bin_array_bounds(-1), Some((-70, -1)));
+    }
+
+    #[test]
+    fn test_fee_rates() {
+        let mut p = pair(0);
+        assert_eq!(p.base_fee_rate(), 1_000_000); // 0.1%
+        assert_eq!(p.variable_fee_rate(), 0);
+        p.fees.variable_fee_control = 7_500;
+        p.volatility.volatility_accumulator = 20_000;
+        // 7_500 * (20_000 * 10)^2 / 1e11 = 3_000
+        assert_eq!(p.variable_fee_rate(), 3_000);
+        assert_eq!(p.total_fee_rate(), 1_003_000);
+        p.fees.variable_fee_control = 10_000_000;
+        p.volatility.volatility_accumulator = 350_000;
+        assert_eq!(p.total_fee_rate(), MAX_FEE_RATE);
+    }
+
+    #[test]
+    fn test_volatility_reference_decay() {
+        let mut p = pair(7_500);
+        p.active_id = 12;
+        p.volatility = DlmmVolatilityState { volatility_accumulator: 40_000, volatility_reference: 0, index_reference: 5, last_update_timestamp: 1_000 };
+        // 过滤周期内：参考值不变
+        let mut q = p.clone();
+        q.update_references(1_010);
+        assert_eq!(q.volatility.index_reference, 5);
+        // 衰减周期内：参考值按 reduction_factor 衰减
+        let mut q = p.clone();
+        q.update_references(1_100);
+        assert_eq!(q.volatility.index_reference, 12);
+        assert_eq!(q.volatility.volatility_reference, 20_000);
+        q.update_volatility_accumulator(10);
+        assert_eq!(q.volatility.volatility_accumulator, 40_000);
+        // 超过衰减周期：参考值清零
+        let mut q = p.clone();
+        q.update_references(2_000);
+        assert_eq!(q.volatility.volatility_reference, 0);
+    }
+
+    #[test]
+    fn test_swap_within_single_bin() {
+        let set = bins(-5..=5, 1_000_000, 1_000_000);
+        let q = swap_quote(&pair(0), &set, 100_000, true, 0).unwrap();
+        assert_eq!(q.fee, 100);
+        assert_eq!(q.amount_out, 99_900);
+        assert_eq!(q.active_id_after, 0);
+        assert_eq!(q.bins_crossed, 0);
+        assert_eq!(q.price_impact_bps, 0);
+    }
+
+    #[test]
+    fn test_swap_crosses_bins() {
+        let set = bins(-5..=5, 1_000, 1_000);
+        let q = swap_quote(&pair(0), &set, 1_500, true, 0).unwrap();
+        // bin 0：1_000 输入 + 2 手续费吃完；bin -1：剩余 498 扣费 1 后按 1/1.001 成交
+        assert_eq!(q.amount_in, 1_500);
+        assert_eq!(q.fee, 3);
+        assert_eq!(q.amount_out, 1_496);
+        assert_eq!(q.active_id_after, -1);
+        assert_eq!(q.bins_crossed, 1);
+        // Y→X 方向：bin 0 无 X 储备，直接越过
+        let q = swap_quote(&pair(0), &set, 500, false, 0).unwrap();
+        assert_eq!(q.active_id_after, 1);
+        assert!(q.amount_out < 500);
+    }
+
+    #[test]
+    fn test_variable_fee_grows_with_bins_crossed() {
+        let set = bins(-69..=0, 1_000, 1_000);
+        let calm = swap_quote(&pair(0), &set, 20_000, true, 0).unwrap();
+        let volatile = swap_quote(&pair(40_000), &set, 20_000, true, 0).unwrap();
+        assert!(volatile.fee > calm.fee);
+        assert!(volatile.amount_out < calm.amount_out);
+        // 覆盖不足：仅 70 个 bin，每个 1_000 Y
+        let exhausted = swap_quote(&pair(0), &set, 1_000_000, true, 0).unwrap();
+        assert!(exhausted.amount_in < 1_000_000);
+        assert_eq!(exhausted.amount_out, 70_000);
+    }
+
+    #[test]
+    fn test_bin_set_requires_contiguous_arrays() {
+        let a = BinArrayBins { index: 0, bins: vec![] };
+        let b = BinArrayBins { index: 2, bins: vec![] };
+        assert!(DlmmBinSet::from_arrays(vec![a.clone(), b]).is_none());
+        let c = BinArrayBins { index: -1, bins: vec![DlmmBin { id: -3, amount_x: 0, amount_y: 5 }] };
+        let set = DlmmBinSet::from_arrays(vec![a, c]).unwrap();
+        assert_eq!((set.lower, set.upper), (-70, 69));
+        assert_eq!(set.bins[&-3].amount_y, 5);
+    }
+}
@@ -1,9 +1,10 @@
 // JupiterAdapter - Jupiter DEX/AMM聚合器适配器实现
-// 生产级实现，完整实现DexAdapterTrait，所有方法均逐行专业注释
-
+// 生产级实现，完整实现统一 DexAdapter trait，所有方法均逐行专业注释
 use anchor_lang::prelude::*;
-// use crate::core::types: // 暂时注释掉:{AssetType, DexAdapterTrait};
-use crate::dex::traits::*;
+use crate::core::adapter::AdapterTrait;
+use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterType, DexOperation, DexQuote, DexSwapResult};
+use crate::dex::jupiter::JupiterAdapter as RealJupiterAdapter;
 
 /// JupiterAdapter结构体，代表Jupiter DEX/AMM聚合器适配器
 pub struct JupiterAdapter {
@@ -25,23 +26,36 @@ impl JupiterAdapter {
     }
 }
 
+/// 实现 AdapterTrait，提供适配器元信息。
+impl AdapterTrait for JupiterAdapter {
+    fn name(&self) -> &str { "jupiter_adapter" }
+    fn version(&self) -> &str { "1.0.0" }
+    fn is_available(&self) -> bool { true }
+    fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+}
+
+/// 实现统一 DexAdapter trait，报价与成交委托给 jupiter.rs 中的真实实现。
 impl DexAdapter for JupiterAdapter {
-    /// 执行swap（mock实现，实际应调用Jupiter CPI）
-    fn swap(&self, _ctx: Context<Swap>, params: SwapParams) -> anchor_lang::Result<SwapResult> {
-        let amount_out = params.amount_in; // 假定1:1兑换
-        Ok(SwapResult { amount_out, fee: 0 })
+    /// 执行swap
+    fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
+        RealJupiterAdapter.swap(params)
     }
-    /// 添加流动性（mock实现）
-    fn add_liquidity(&self, _ctx: Context<AddLiquidity>, params: AddLiquidityParams) -> anchor_lang::Result<u64> {
-        Ok(params.amount)
+    /// 批量swap
+    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
+        RealJupiterAdapter.batch_swap(params)
     }
-    /// 移除流动性（mock实现）
-    fn remove_liquidity(&self, _ctx: Context<RemoveLiquidity>, params: RemoveLiquidityParams) -> anchor_lang::Result<u64> {
-        Ok(params.amount)
+    /// 配置适配器
+    fn configure(&self, params: &DexParams) -> anchor_lang::Result<()> {
+        RealJupiterAdapter.configure(params)
     }
-    /// 获取报价（mock实现）
-    fn get_quote(&self, _ctx: Context<GetQuote>, params: QuoteParams) -> anchor_lang::Result<QuoteResult> {
-        Ok(QuoteResult { amount_out: params.amount_in, fee: 0 })
+    /// 获取报价
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        RealJupiterAdapter.get_quote(params, accounts)
+    }
+    /// 账户需求
+    fn account_requirements(&self, operation: DexOperation, pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        RealJupiterAdapter.account_requirements(operation, pool_program)
     }
     /// 查询支持的资产类型
     fn supported_assets(&self) -> Vec<String> {
@@ -62,4 +76,4 @@ impl DexAdapter for JupiterAdapter {
 pub enum ErrorCode {
     #[msg("不支持的资产类型")] 
     UnsupportedAsset,
-} 
\ No newline at end of file
+}
@@ -0,0 +1,124 @@
+//!
+//! DEX Account Layout Helpers
+//!
+//! 本模块提供 DEX 池账户的零依赖字节解析工具：小端整数/公钥读取、SPL Token 账户解析、账户归属校验，
+//! 供各适配器从传入的 AccountInfo 中反序列化池状态与金库余额，避免引入各 DEX 官方 SDK。
+
+use anchor_lang::prelude::*;
+
+/// SPL Token 账户数据长度
+pub const SPL_TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// 读取小端 u8
+pub fn read_u8(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).copied()
+}
+
+/// 读取小端 u16
+pub fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)?.try_into().ok().map(u16::from_le_bytes)
+}
+
+/// 读取小端 u32
+pub fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)?.try_into().ok().map(u32::from_le_bytes)
+}
+
+/// 读取小端 i32
+pub fn read_i32(data: &[u8], offset: usize) -> Option<i32> {
+    data.get(offset..offset + 4)?.try_into().ok().map(i32::from_le_bytes)
+}
+
+/// 读取小端 u64
+pub fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)?.try_into().ok().map(u64::from_le_bytes)
+}
+
+/// 读取小端 i64
+pub fn read_i64(data: &[u8], offset: usize) -> Option<i64> {
+    data.get(offset..offset + 8)?.try_into().ok().map(i64::from_le_bytes)
+}
+
+/// 读取小端 u128
+pub fn read_u128(data: &[u8], offset: usize) -> Option<u128> {
+    data.get(offset..offset + 16)?.try_into().ok().map(u128::from_le_bytes)
+}
+
+/// 读取小端 i128
+pub fn read_i128(data: &[u8], offset: usize) -> Option<i128> {
+    data.get(offset..offset + 16)?.try_into().ok().map(i128::from_le_bytes)
+}
+
+/// 读取公钥
+pub fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    Some(Pubkey::new_from_array(bytes))
+}
+
+/// SPL Token 账户关键字段
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenAccountState {
+    pub mint: Pubkey,  // 代币 mint
+    pub owner: Pubkey, // 代币账户所有者
+    pub amount: u64,   // 余额
+}
+
+/// 解析 SPL Token / Token-2022 账户（基础 165 字节布局：mint | owner | amount）
+/// - 校验账户归属 Token 程序，防止伪造金库余额
+pub fn unpack_token_account(info: &AccountInfo) -> Option<TokenAccountState> {
+    if *info.owner != anchor_spl::token::ID && *info.owner != anchor_spl::token_2022::ID {
+        return None;
+    }
+    let data = info.try_borrow_data().ok()?;
+    if data.len() < SPL_TOKEN_ACCOUNT_LEN {
+        return None;
+    }
+    Some(TokenAccountState {
+        mint: read_pubkey(&data, 0)?,
+        owner: read_pubkey(&data, 32)?,
+        amount: read_u64(&data, 64)?,
+    })
+}
+
+/// 构造 SPL Token 账户字节（测试夹具与离线模拟使用）
+pub fn pack_token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0u8; SPL_TOKEN_ACCOUNT_LEN];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1; // AccountState::Initialized
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readers_bounds() {
+        let data = [1u8, 0, 0, 0, 0, 0, 0, 0, 2];
+        assert_eq!(read_u64(&data, 0), Some(1));
+        assert_eq!(read_u8(&data, 8), Some(2));
+        assert_eq!(read_u64(&data, 2), None);
+        assert_eq!(read_pubkey(&data, 0), None);
+    }
+
+    #[test]
+    fn test_unpack_token_account_checks_owner() {
+        let key = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut data = pack_token_account(&mint, &Pubkey::default(), 42);
+        let mut lamports = 0u64;
+        let token_program = anchor_spl::token::ID;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &token_program, false, 0);
+        let state = unpack_token_account(&info).unwrap();
+        assert_eq!(state.mint, mint);
+        assert_eq!(state.amount, 42);
+
+        let mut data = pack_token_account(&mint, &Pubkey::default(), 42);
+        let mut lamports = 0u64;
+        let fake_owner = Pubkey::new_unique();
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &fake_owner, false, 0);
+        assert!(unpack_token_account(&info).is_none());
+    }
+}
@@ -2,16 +2,136 @@
 //! Lifinity DEX Adapter Module
 //!
 //! 本模块实现 Lifinity DEX 适配器，提供与 Lifinity AMM 的链上集成接口，确保交易路由与聚合合规、可维护。
+//! 报价基于传入的 Lifinity v2 Amm 账户与金库 Token 账户，按池声明的曲线（常积/StableSwap）计算。
 
 use anchor_lang::prelude::*;
 use crate::core::adapter::AdapterTrait;
 use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
 // // use crate::core::types: // 暂时注释掉:{TradeParams, BatchTradeParams, DexParams}; // 暂时注释掉
-use crate::dex::adapter::{DexAdapter, DexSwapResult, DexAdapterType};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterError, DexAdapterType, DexOperation, DexQuote, DexSwapResult};
+use crate::dex::curve::{self, CurveType, FeeRate};
+use crate::dex::layout::{read_pubkey, read_u64, read_u8, unpack_token_account};
+
+/// Lifinity v2 程序ID
+pub const LIFINITY_V2_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("2wT8Yq49kHgDzXuPxZSaeLaH1qbmGXtEyPy64bL7aD3c");
+/// Amm 账户最小长度（覆盖到曲线参数字段）
+pub const LIFINITY_AMM_MIN_LEN: usize = 519;
+
+/// Lifinity v2 Amm 状态（仅解析报价所需字段，偏移含 8 字节 Anchor 判别符）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LifinityAmm {
+    pub token_a_account: Pubkey,            // A 金库
+    pub token_b_account: Pubkey,            // B 金库
+    pub token_a_mint: Pubkey,               // A mint
+    pub token_b_mint: Pubkey,               // B mint
+    pub trade_fee_numerator: u64,           // LP 交易费分子
+    pub trade_fee_denominator: u64,         // LP 交易费分母
+    pub owner_trade_fee_numerator: u64,     // 协议交易费分子
+    pub owner_trade_fee_denominator: u64,   // 协议交易费分母
+    pub curve_type: u8,                     // 曲线类型（0=常积，2=Stable）
+    pub curve_parameters: u64,              // 曲线参数（Stable 曲线为放大系数）
+}
+
+impl LifinityAmm {
+    const TOKEN_A_ACCOUNT: usize = 158;
+    const TOKEN_B_ACCOUNT: usize = 190;
+    const TOKEN_A_MINT: usize = 254;
+    const TOKEN_B_MINT: usize = 286;
+    const TRADE_FEE_NUMERATOR: usize = 446;
+    const TRADE_FEE_DENOMINATOR: usize = 454;
+    const OWNER_TRADE_FEE_NUMERATOR: usize = 462;
+    const OWNER_TRADE_FEE_DENOMINATOR: usize = 470;
+    const CURVE_TYPE: usize = 510;
+    const CURVE_PARAMETERS: usize = 511;
+
+    /// 从账户字节反序列化
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < LIFINITY_AMM_MIN_LEN {
+            return None;
+        }
+        Some(Self {
+            token_a_account: read_pubkey(data, Self::TOKEN_A_ACCOUNT)?,
+            token_b_account: read_pubkey(data, Self::TOKEN_B_ACCOUNT)?,
+            token_a_mint: read_pubkey(data, Self::TOKEN_A_MINT)?,
+            token_b_mint: read_pubkey(data, Self::TOKEN_B_MINT)?,
+            trade_fee_numerator: read_u64(data, Self::TRADE_FEE_NUMERATOR)?,
+            trade_fee_denominator: read_u64(data, Self::TRADE_FEE_DENOMINATOR)?,
+            owner_trade_fee_numerator: read_u64(data, Self::OWNER_TRADE_FEE_NUMERATOR)?,
+            owner_trade_fee_denominator: read_u64(data, Self::OWNER_TRADE_FEE_DENOMINATOR)?,
+            curve_type: read_u8(data, Self::CURVE_TYPE)?,
+            curve_parameters: read_u64(data, Self::CURVE_PARAMETERS)?,
+        })
+    }
+
+    /// 序列化为账户字节（测试夹具与离线模拟使用，未解析字段填零）
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0u8; LIFINITY_AMM_MIN_LEN];
+        data[Self::TOKEN_A_ACCOUNT..Self::TOKEN_A_ACCOUNT + 32].copy_from_slice(self.token_a_account.as_ref());
+        data[Self::TOKEN_B_ACCOUNT..Self::TOKEN_B_ACCOUNT + 32].copy_from_slice(self.token_b_account.as_ref());
+        data[Self::TOKEN_A_MINT..Self::TOKEN_A_MINT + 32].copy_from_slice(self.token_a_mint.as_ref());
+        data[Self::TOKEN_B_MINT..Self::TOKEN_B_MINT + 32].copy_from_slice(self.token_b_mint.as_ref());
+        data[Self::TRADE_FEE_NUMERATOR..Self::TRADE_FEE_NUMERATOR + 8].copy_from_slice(&self.trade_fee_numerator.to_le_bytes());
+        data[Self::TRADE_FEE_DENOMINATOR..Self::TRADE_FEE_DENOMINATOR + 8].copy_from_slice(&self.trade_fee_denominator.to_le_bytes());
+        data[Self::OWNER_TRADE_FEE_NUMERATOR..Self::OWNER_TRADE_FEE_NUMERATOR + 8].copy_from_slice(&self.owner_trade_fee_numerator.to_le_bytes());
+        data[Self::OWNER_TRADE_FEE_DENOMINATOR..Self::OWNER_TRADE_FEE_DENOMINATOR + 8].copy_from_slice(&self.owner_trade_fee_denominator.to_le_bytes());
+        data[Self::CURVE_TYPE] = self.curve_type;
+        data[Self::CURVE_PARAMETERS..Self::CURVE_PARAMETERS + 8].copy_from_slice(&self.curve_parameters.to_le_bytes());
+        data
+    }
+
+    /// 池声明的曲线类型
+    pub fn curve(&self) -> Option<CurveType> {
+        match self.curve_type {
+            0 => Some(CurveType::ConstantProduct),
+            2 => Some(CurveType::Stable { amp: self.curve_parameters }),
+            _ => None,
+        }
+    }
+
+    /// 按输入收取的总费率（LP 交易费 + 协议交易费）
+    pub fn fee_rate(&self) -> Option<FeeRate> {
+        FeeRate::new(self.trade_fee_numerator, self.trade_fee_denominator)
+            .combine(FeeRate::new(self.owner_trade_fee_numerator, self.owner_trade_fee_denominator))
+    }
+}
 
 /// Lifinity DEX 适配器结构体。
 pub struct LifinityAdapter;
 
+impl LifinityAdapter {
+    /// 基于池账户计算报价
+    /// - accounts[0]: Amm 账户（归属 Lifinity v2 程序）
+    /// - accounts[1]: A 金库 Token 账户
+    /// - accounts[2]: B 金库 Token 账户
+    pub fn quote_from_accounts(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        require!(params.amount_in > 0, LifinityError::InvalidAmount);
+        require!(params.from_token != params.to_token, LifinityError::InvalidTokens);
+        require!(accounts.len() >= 3, DexAdapterError::MissingPoolAccounts);
+        let (pool, vault_a, vault_b) = (&accounts[0], &accounts[1], &accounts[2]);
+        require_keys_eq!(*pool.owner, LIFINITY_V2_PROGRAM_ID, LifinityError::InvalidAmmAccount);
+        let amm = {
+            let data = pool.try_borrow_data()?;
+            LifinityAmm::unpack(&data).ok_or(LifinityError::InvalidAmmAccount)?
+        };
+        require_keys_eq!(vault_a.key(), amm.token_a_account, LifinityError::InvalidAmmAccount);
+        require_keys_eq!(vault_b.key(), amm.token_b_account, LifinityError::InvalidAmmAccount);
+        let a = unpack_token_account(vault_a).ok_or(LifinityError::InvalidAmmAccount)?;
+        let b = unpack_token_account(vault_b).ok_or(LifinityError::InvalidAmmAccount)?;
+        let (reserve_in, reserve_out) = if params.from_token == amm.token_a_mint && params.to_token == amm.token_b_mint {
+            (a.amount, b.amount)
+        } else if params.from_token == amm.token_b_mint && params.to_token == amm.token_a_mint {
+            (b.amount, a.amount)
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let curve_type = amm.curve().ok_or(LifinityError::Unsupported)?;
+        let fee = amm.fee_rate().ok_or(DexAdapterError::MathOverflow)?;
+        let quote = curve::quote(curve_type, reserve_in, reserve_out, params.amount_in, fee)
+            .ok_or(LifinityError::InsufficientLiquidity)?;
+        Ok(DexQuote::from_curve(&quote, self.name()))
+    }
+}
+
 impl AdapterTrait for LifinityAdapter {
     fn name(&self) -> &str { "lifinity" }
     fn version(&self) -> &str { "1.0.0" }
@@ -22,34 +142,38 @@ impl AdapterTrait for LifinityAdapter {
 
 impl DexAdapter for LifinityAdapter {
     /// 执行 Lifinity swap 操作。
+    /// - 报价依赖池账户，未携带账户时无法给出真实成交：报价使用 quote_checked，成交使用 build_swap_instruction。
     fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // 生产级实现：集成Lifinity链上CPI调用，参数校验、错误处理、事件追踪
-        require!(params.amount_in > 0, crate::errors::asset_error::AssetError::InvalidAmount);
-        require!(params.from_token != params.to_token, crate::errors::dex_error::DexError::InvalidTokens);
-        
-        // TODO: 调用Lifinity CPI（此处应集成真实CPI调用）
-        // 这里只做结构示例，实际应调用CPI并返回真实成交数据
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000, // 应为CPI返回均价
-            fee: 1000,            // 应为CPI返回手续费
-            dex_name: "lifinity".to_string(),
-        })
+        require!(params.amount_in > 0, LifinityError::InvalidAmount);
+        require!(params.from_token != params.to_token, LifinityError::InvalidTokens);
+        Err(DexAdapterError::MissingPoolAccounts.into())
     }
     
     /// 批量 swap 操作。
     fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
-        Ok(params.trades.iter().map(|p| DexSwapResult {
-            executed_amount: p.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "lifinity".to_string(),
-        }).collect())
+        params.trades.iter().map(|p| self.swap(p)).collect()
     }
     
     /// 配置 Lifinity 适配器。
     fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> { Ok(()) }
-    
+
+    /// 基于 Amm 与金库账户报价。
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        self.quote_from_accounts(params, accounts)
+    }
+
+    /// 报价/成交所需账户：Amm + 双金库。
+    fn account_requirements(&self, operation: DexOperation, _pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        match operation {
+            DexOperation::Quote | DexOperation::Swap => vec![
+                DexAccountRequirement::readonly("amm"),
+                DexAccountRequirement::readonly("vault_a"),
+                DexAccountRequirement::readonly("vault_b"),
+            ],
+            _ => vec![],
+        }
+    }
+
     /// 返回支持的资产列表。
     fn supported_assets(&self) -> Vec<String> { 
         vec!["SOL".to_string(), "USDC".to_string(), "LFNTY".to_string(), "BTC".to_string()] 
@@ -106,8 +230,48 @@ fn register_lifinity_adapter() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::layout::pack_token_account;
     use anchor_lang::prelude::Pubkey;
 
+    /// 构造交易参数。
+    fn trade(from: Pubkey, to: Pubkey, amount_in: u64, min_amount_out: u64) -> TradeParams {
+        TradeParams {
+            trade_type: "swap".to_string(),
+            from_token: from,
+            to_token: to,
+            amount_in,
+            min_amount_out,
+            dex_name: "lifinity".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        }
+    }
+
+    /// 构造 Amm 与金库夹具，返回 (keys, data, mint_a, mint_b)。
+    fn fixture(curve_type: u8) -> ([Pubkey; 3], [Vec<u8>; 3], Pubkey, Pubkey) {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let amm = LifinityAmm {
+            token_a_account: keys[1],
+            token_b_account: keys[2],
+            token_a_mint: mint_a,
+            token_b_mint: mint_b,
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            owner_trade_fee_numerator: 5,
+            owner_trade_fee_denominator: 10_000,
+            curve_type,
+            curve_parameters: 100,
+        };
+        let data = [
+            amm.pack(),
+            pack_token_account(&mint_a, &Pubkey::default(), 1_000_000_000),
+            pack_token_account(&mint_b, &Pubkey::default(), 1_000_000_000),
+        ];
+        (keys, data, mint_a, mint_b)
+    }
+
     /// 测试 LifinityAdapter 名称。
     #[test]
     fn test_lifinity_adapter_name() {
@@ -119,15 +283,38 @@ mod tests {
     #[test]
     fn test_lifinity_adapter_swap() {
         let adapter = LifinityAdapter;
-        let params = TradeParams {
-            from_token: Pubkey::default(),
-            to_token: Pubkey::new_unique(), // 使用不同的token
-            amount_in: 100,
-            min_amount_out: 90,
-            dex_name: "lifinity".to_string(),
-        };
-        let result = adapter.swap(&params);
-        assert!(result.is_ok());
+        let (keys, mut data, mint_a, mint_b) = fixture(0);
+        let owners = [LIFINITY_V2_PROGRAM_ID, anchor_spl::token::ID, anchor_spl::token::ID];
+        let mut lamports = [0u64; 3];
+        let [d0, d1, d2] = &mut data;
+        let [l0, l1, l2] = &mut lamports;
+        let infos = vec![
+            AccountInfo::new(&keys[0], false, false, l0, d0, &owners[0], false, 0),
+            AccountInfo::new(&keys[1], false, false, l1, d1, &owners[1], false, 0),
+            AccountInfo::new(&keys[2], false, false, l2, d2, &owners[2], false, 0),
+        ];
+        let result = adapter.quote_checked(&trade(mint_a, mint_b, 10_000_000, 9_800_000), &infos).unwrap();
+        assert_eq!(result.fee, 30_000);
+        assert_eq!(result.avg_price, 987_158); // 9_871_580 / 10_000_000 * 1e6
+        assert!(adapter.quote_checked(&trade(mint_a, mint_b, 10_000_000, 9_900_000), &infos).is_err());
+        assert!(adapter.swap(&trade(mint_a, mint_b, 100, 90)).is_err());
+    }
+
+    /// 测试伪造 Amm 归属被拒绝。
+    #[test]
+    fn test_lifinity_rejects_foreign_amm_owner() {
+        let adapter = LifinityAdapter;
+        let (keys, mut data, mint_a, mint_b) = fixture(0);
+        let fake = Pubkey::new_unique();
+        let mut lamports = [0u64; 3];
+        let [d0, d1, d2] = &mut data;
+        let [l0, l1, l2] = &mut lamports;
+        let infos = vec![
+            AccountInfo::new(&keys[0], false, false, l0, d0, &fake, false, 0),
+            AccountInfo::new(&keys[1], false, false, l1, d1, &anchor_spl::token::ID, false, 0),
+            AccountInfo::new(&keys[2], false, false, l2, d2, &anchor_spl::token::ID, false, 0),
+        ];
+        assert!(adapter.get_quote(&trade(mint_a, mint_b, 100, 0), &infos).is_err());
     }
     
     /// 测试 LifinityAdapter 支持的资产。
@@ -139,4 +326,4 @@ mod tests {
         assert!(assets.contains(&"USDC".to_string()));
         assert!(assets.contains(&"LFNTY".to_string()));
     }
-} 
\ No newline at end of file
+}
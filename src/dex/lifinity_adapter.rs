@@ -33,23 +33,23 @@ fn auto_register_lifinity_adapter() {
 /// 实现 DexAdapter trait，委托给 lifinity.rs 中的真实实现。
 impl DexAdapter for LifinityAdapter {
     fn swap(&self, params: &crate::core::types::TradeParams) -> anchor_lang::Result<crate::dex::adapter::DexSwapResult> {
-        // TODO: 实现实际的 swap 逻辑
-        Ok(crate::dex::adapter::DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "lifinity".to_string(),
-        })
+        RealLifinityAdapter.swap(params)
     }
     
     fn batch_swap(&self, params: &crate::core::types::BatchTradeParams) -> anchor_lang::Result<Vec<crate::dex::adapter::DexSwapResult>> {
-        // TODO: 实现实际的批量 swap 逻辑
-        Ok(vec![])
+        RealLifinityAdapter.batch_swap(params)
     }
     
     fn configure(&self, params: &crate::core::types::DexParams) -> anchor_lang::Result<()> {
-        // TODO: 实现配置逻辑
-        Ok(())
+        RealLifinityAdapter.configure(params)
+    }
+
+    fn get_quote(&self, params: &crate::core::types::TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<crate::dex::adapter::DexQuote> {
+        RealLifinityAdapter.get_quote(params, accounts)
+    }
+
+    fn account_requirements(&self, operation: crate::dex::adapter::DexOperation, pool_program: &Pubkey) -> Vec<crate::dex::adapter::DexAccountRequirement> {
+        RealLifinityAdapter.account_requirements(operation, pool_program)
     }
     
     fn supported_assets(&self) -> Vec<String> {
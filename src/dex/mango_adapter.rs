@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*; // Anchor预导入，包含Result、Context等
-use super::traits::{DexAdapter, SwapParams, SwapResult, AddLiquidityParams, RemoveLiquidityParams, QuoteParams, QuoteResult}; // DEX适配器trait及相关类型
+use crate::core::types::TradeParams; // 统一交易参数
+use crate::dex::adapter::{AddLiquidityParams, DexAdapter, DexQuote, DexSwapResult, RemoveLiquidityParams}; // 统一DEX适配器trait及相关类型
 use crate::core::adapter::AdapterTrait; // 适配器元信息trait，统一接口
 
 /// Mango DEX适配器结构体
@@ -78,10 +79,10 @@ pub struct GetQuote<'info> {
     pub mango_program: AccountInfo<'info>,
 }
 
-/// 实现 DexAdapter trait，集成 Mango 链上 CPI 调用。
+/// 实现统一 DexAdapter trait，集成 Mango 链上 CPI 调用。
 impl DexAdapter for MangoAdapter {
     /// 执行 Mango swap 操作。
-    fn swap(&self, params: SwapParams) -> anchor_lang::Result<SwapResult> {
+    fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
         // TODO: 实现 Mango DEX 交换逻辑
         // 由于 mango 依赖不可用，暂时返回错误
         Err(anchor_lang::error::Error::from(anchor_lang::error::ErrorCode::AccountNotInitialized))
@@ -91,13 +92,15 @@ impl DexAdapter for MangoAdapter {
         // };
         // let cpi_ctx = CpiContext::new(self.program_id, cpi_accounts);
         // let result = mango::cpi::swap(cpi_ctx, params.amount_in, params.min_amount_out)?;
-        // Ok(SwapResult {
-        //     amount_out: result.amount_out,
+        // Ok(DexSwapResult {
+        //     executed_amount: params.amount_in,
+        //     avg_price: result.avg_price,
         //     fee: result.fee,
+        //     dex_name: "mango".to_string(),
         // })
     }
     /// 添加流动性。
-    fn add_liquidity(&self, params: AddLiquidityParams) -> anchor_lang::Result<u64> {
+    fn add_liquidity(&self, params: &AddLiquidityParams, _accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
         // TODO: 实现 Mango DEX 添加流动性逻辑
         // 由于 mango 依赖不可用，暂时返回错误
         Err(anchor_lang::error::Error::from(anchor_lang::error::ErrorCode::AccountNotInitialized))
@@ -113,7 +116,7 @@ impl DexAdapter for MangoAdapter {
         // })
     }
     /// 移除流动性。
-    fn remove_liquidity(&self, params: RemoveLiquidityParams) -> anchor_lang::Result<u64> {
+    fn remove_liquidity(&self, params: &RemoveLiquidityParams, _accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
         // TODO: 实现 Mango DEX 移除流动性逻辑
         // 由于 mango 依赖不可用，暂时返回错误
         Err(anchor_lang::error::Error::from(anchor_lang::error::ErrorCode::AccountNotInitialized))
@@ -126,7 +129,7 @@ impl DexAdapter for MangoAdapter {
         // Ok(result.amount_out)
     }
     /// 获取报价。
-    fn get_quote(&self, params: QuoteParams) -> anchor_lang::Result<QuoteResult> {
+    fn get_quote(&self, params: &TradeParams, _accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
         // TODO: 实现 Mango DEX 获取报价逻辑
         // 由于 mango 依赖不可用，暂时返回错误
         Err(anchor_lang::error::Error::from(anchor_lang::error::ErrorCode::AccountNotInitialized))
@@ -135,8 +138,8 @@ impl DexAdapter for MangoAdapter {
         //     // 账户配置
         // };
         // let cpi_ctx = CpiContext::new(self.program_id, cpi_accounts);
-        // let quote = mango::cpi::get_quote(cpi_ctx, params.input_mint, params.output_mint, params.amount_in)?;
-        // Ok(QuoteResult { amount_out: quote.amount_out, fee: quote.fee })
+        // let quote = mango::cpi::get_quote(cpi_ctx, params.from_token, params.to_token, params.amount_in)?;
+        // Ok(DexQuote { amount_in: params.amount_in, amount_out: quote.amount_out, fee: quote.fee, .. })
     }
 }
 
@@ -2,15 +2,281 @@
 //! Meteora DEX Adapter Module
 //!
 //! 本模块实现 Meteora DEX 适配器，提供与 Meteora AMM 的链上集成接口，确保交易路由与聚合合规、可维护。
+//! 报价基于传入的 DLMM lb_pair 与 bin array 账户，按基础费率 + 可变费率逐 bin 计算精确输出；
+//! 成交路径按 lb_clmm swap 指令构造真实 CPI 账户列表与指令数据。
 
 use crate::core::adapter::AdapterTrait;
 use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
-use crate::dex::adapter::{DexAdapter, DexSwapResult, DexAdapterType};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterError, DexAdapterType, DexOperation, DexQuote, DexSwapResult, DexSwapUserAccounts};
+use crate::dex::dlmm::{self, BinArrayBins, DlmmBin, DlmmBinSet, DlmmFeeParameters, DlmmPairState, DlmmQuote, DlmmVolatilityState};
+use crate::dex::layout::{read_i32, read_i64, read_pubkey, read_u16, read_u32, read_u64, read_u8};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+
+/// Meteora DLMM（lb_clmm）程序ID
+pub const METEORA_DLMM_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9t6FruQLtn");
+/// lb_pair 报价所需最小长度（覆盖到 oracle 字段）
+pub const LB_PAIR_MIN_LEN: usize = 584;
+/// BinArray 账户长度
+pub const BIN_ARRAY_LEN: usize = 10_136;
+/// swap 指令判别符（sha256("global:swap")[..8]）
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+/// 交易对启用状态
+const PAIR_STATUS_ENABLED: u8 = 0;
+
+/// DLMM lb_pair 状态（仅解析报价与 CPI 所需字段，偏移含 8 字节 Anchor 判别符）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LbPairState {
+    pub fees: DlmmFeeParameters,         // 费率静态参数
+    pub volatility: DlmmVolatilityState, // 波动率动态参数
+    pub active_id: i32,                  // 当前活跃 bin
+    pub bin_step: u16,                   // bin 步长（基点）
+    pub status: u8,                      // 交易对状态（0=启用）
+    pub token_x_mint: Pubkey,            // X mint
+    pub token_y_mint: Pubkey,            // Y mint
+    pub reserve_x: Pubkey,               // X 储备金库
+    pub reserve_y: Pubkey,               // Y 储备金库
+    pub oracle: Pubkey,                  // 价格预言机账户
+}
+
+impl LbPairState {
+    const BASE_FACTOR: usize = 8;
+    const FILTER_PERIOD: usize = 10;
+    const DECAY_PERIOD: usize = 12;
+    const REDUCTION_FACTOR: usize = 14;
+    const VARIABLE_FEE_CONTROL: usize = 16;
+    const MAX_VOLATILITY_ACCUMULATOR: usize = 20;
+    const BASE_FEE_POWER_FACTOR: usize = 34;
+    const VOLATILITY_ACCUMULATOR: usize = 40;
+    const VOLATILITY_REFERENCE: usize = 44;
+    const INDEX_REFERENCE: usize = 48;
+    const LAST_UPDATE_TIMESTAMP: usize = 56;
+    const ACTIVE_ID: usize = 76;
+    const BIN_STEP: usize = 80;
+    const STATUS: usize = 82;
+    const TOKEN_X_MINT: usize = 88;
+    const TOKEN_Y_MINT: usize = 120;
+    const RESERVE_X: usize = 152;
+    const RESERVE_Y: usize = 184;
+    const ORACLE: usize = 552;
+
+    /// 从账户字节反序列化
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < LB_PAIR_MIN_LEN {
+            return None;
+        }
+        Some(Self {
+            fees: DlmmFeeParameters {
+                base_factor: read_u16(data, Self::BASE_FACTOR)?,
+                filter_period: read_u16(data, Self::FILTER_PERIOD)?,
+                decay_period: read_u16(data, Self::DECAY_PERIOD)?,
+                reduction_factor: read_u16(data, Self::REDUCTION_FACTOR)?,
+                variable_fee_control: read_u32(data, Self::VARIABLE_FEE_CONTROL)?,
+                max_volatility_accumulator: read_u32(data, Self::MAX_VOLATILITY_ACCUMULATOR)?,
+                base_fee_power_factor: read_u8(data, Self::BASE_FEE_POWER_FACTOR)?,
+            },
+            volatility: DlmmVolatilityState {
+                volatility_accumulator: read_u32(data, Self::VOLATILITY_ACCUMULATOR)?,
+                volatility_reference: read_u32(data, Self::VOLATILITY_REFERENCE)?,
+                index_reference: read_i32(data, Self::INDEX_REFERENCE)?,
+                last_update_timestamp: read_i64(data, Self::LAST_UPDATE_TIMESTAMP)?,
+            },
+            active_id: read_i32(data, Self::ACTIVE_ID)?,
+            bin_step: read_u16(data, Self::BIN_STEP)?,
+            status: read_u8(data, Self::STATUS)?,
+            token_x_mint: read_pubkey(data, Self::TOKEN_X_MINT)?,
+            token_y_mint: read_pubkey(data, Self::TOKEN_Y_MINT)?,
+            reserve_x: read_pubkey(data, Self::RESERVE_X)?,
+            reserve_y: read_pubkey(data, Self::RESERVE_Y)?,
+            oracle: read_pubkey(data, Self::ORACLE)?,
+        })
+    }
+
+    /// 序列化为账户字节（测试夹具与离线模拟使用，未解析字段填零）
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0u8; LB_PAIR_MIN_LEN];
+        data[Self::BASE_FACTOR..Self::BASE_FACTOR + 2].copy_from_slice(&self.fees.base_factor.to_le_bytes());
+        data[Self::FILTER_PERIOD..Self::FILTER_PERIOD + 2].copy_from_slice(&self.fees.filter_period.to_le_bytes());
+        data[Self::DECAY_PERIOD..Self::DECAY_PERIOD + 2].copy_from_slice(&self.fees.decay_period.to_le_bytes());
+        data[Self::REDUCTION_FACTOR..Self::REDUCTION_FACTOR + 2].copy_from_slice(&self.fees.reduction_factor.to_le_bytes());
+        data[Self::VARIABLE_FEE_CONTROL..Self::VARIABLE_FEE_CONTROL + 4].copy_from_slice(&self.fees.variable_fee_control.to_le_bytes());
+        data[Self::MAX_VOLATILITY_ACCUMULATOR..Self::MAX_VOLATILITY_ACCUMULATOR + 4].copy_from_slice(&self.fees.max_volatility_accumulator.to_le_bytes());
+        data[Self::BASE_FEE_POWER_FACTOR] = self.fees.base_fee_power_factor;
+        data[Self::VOLATILITY_ACCUMULATOR..Self::VOLATILITY_ACCUMULATOR + 4].copy_from_slice(&self.volatility.volatility_accumulator.to_le_bytes());
+        data[Self::VOLATILITY_REFERENCE..Self::VOLATILITY_REFERENCE + 4].copy_from_slice(&self.volatility.volatility_reference.to_le_bytes());
+        data[Self::INDEX_REFERENCE..Self::INDEX_REFERENCE + 4].copy_from_slice(&self.volatility.index_reference.to_le_bytes());
+        data[Self::LAST_UPDATE_TIMESTAMP..Self::LAST_UPDATE_TIMESTAMP + 8].copy_from_slice(&self.volatility.last_update_timestamp.to_le_bytes());
+        data[Self::ACTIVE_ID..Self::ACTIVE_ID + 4].copy_from_slice(&self.active_id.to_le_bytes());
+        data[Self::BIN_STEP..Self::BIN_STEP + 2].copy_from_slice(&self.bin_step.to_le_bytes());
+        data[Self::STATUS] = self.status;
+        data[Self::TOKEN_X_MINT..Self::TOKEN_X_MINT + 32].copy_from_slice(self.token_x_mint.as_ref());
+        data[Self::TOKEN_Y_MINT..Self::TOKEN_Y_MINT + 32].copy_from_slice(self.token_y_mint.as_ref());
+        data[Self::RESERVE_X..Self::RESERVE_X + 32].copy_from_slice(self.reserve_x.as_ref());
+        data[Self::RESERVE_Y..Self::RESERVE_Y + 32].copy_from_slice(self.reserve_y.as_ref());
+        data[Self::ORACLE..Self::ORACLE + 32].copy_from_slice(self.oracle.as_ref());
+        data
+    }
+
+    /// 转换为报价引擎状态
+    pub fn to_pair_state(&self) -> DlmmPairState {
+        DlmmPairState {
+            active_id: self.active_id,
+            bin_step: self.bin_step,
+            fees: self.fees.clone(),
+            volatility: self.volatility.clone(),
+        }
+    }
+}
+
+/// DLMM BinArray 解析（index | version | lb_pair | 70 × Bin(144 字节)）
+pub struct MeteoraBinArray;
+
+impl MeteoraBinArray {
+    const INDEX: usize = 8;
+    const LB_PAIR: usize = 24;
+    const BINS: usize = 56;
+    const BIN_LEN: usize = 144;
+
+    /// 解析并校验所属交易对，返回有储备的 bin
+    pub fn unpack(data: &[u8], lb_pair: &Pubkey) -> Option<BinArrayBins> {
+        if data.len() < BIN_ARRAY_LEN || read_pubkey(data, Self::LB_PAIR)? != *lb_pair {
+            return None;
+        }
+        let index = read_i64(data, Self::INDEX)?;
+        let (lower, _) = dlmm::bin_array_bounds(index)?;
+        let mut bins = Vec::new();
+        for i in 0..dlmm::MAX_BIN_PER_ARRAY {
+            let offset = Self::BINS + i as usize * Self::BIN_LEN;
+            let (amount_x, amount_y) = (read_u64(data, offset)?, read_u64(data, offset + 8)?);
+            if amount_x > 0 || amount_y > 0 {
+                bins.push(DlmmBin { id: lower + i, amount_x, amount_y });
+            }
+        }
+        Some(BinArrayBins { index, bins })
+    }
+
+    /// 构造 BinArray 字节（测试夹具与离线模拟使用）
+    pub fn pack(lb_pair: &Pubkey, index: i64, bins: &[DlmmBin]) -> Vec<u8> {
+        let mut data = vec![0u8; BIN_ARRAY_LEN];
+        data[Self::INDEX..Self::INDEX + 8].copy_from_slice(&index.to_le_bytes());
+        data[Self::LB_PAIR..Self::LB_PAIR + 32].copy_from_slice(lb_pair.as_ref());
+        let lower = index as i32 * dlmm::MAX_BIN_PER_ARRAY;
+        for bin in bins {
+            let offset = Self::BINS + (bin.id - lower) as usize * Self::BIN_LEN;
+            data[offset..offset + 8].copy_from_slice(&bin.amount_x.to_le_bytes());
+            data[offset + 8..offset + 16].copy_from_slice(&bin.amount_y.to_le_bytes());
+        }
+        data
+    }
+
+    /// BinArray PDA 地址
+    pub fn address(lb_pair: &Pubkey, index: i64) -> Pubkey {
+        Pubkey::find_program_address(&[b"bin_array", lb_pair.as_ref(), &index.to_le_bytes()], &METEORA_DLMM_PROGRAM_ID).0
+    }
+
+    /// swap 需要的 BinArray 索引：从活跃 bin 所在数组开始沿成交方向取 count 个
+    pub fn indexes_for_swap(active_id: i32, swap_for_y: bool, count: usize) -> Vec<i64> {
+        let start = dlmm::bin_array_index(active_id);
+        (0..count as i64).map(|i| if swap_for_y { start - i } else { start + i }).collect()
+    }
+}
+
+/// DLMM swap CPI 所需的用户侧账户
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DlmmSwapAccounts {
+    pub user: Pubkey,                               // 签名者（持有输入代币的账户或 PDA）
+    pub user_token_in: Pubkey,                      // 输入代币账户
+    pub user_token_out: Pubkey,                     // 输出代币账户
+    pub token_x_program: Pubkey,                    // X 代币程序（SPL Token / Token-2022）
+    pub token_y_program: Pubkey,                    // Y 代币程序
+    pub bin_array_bitmap_extension: Option<Pubkey>, // bitmap 扩展账户（可选）
+    pub host_fee_in: Option<Pubkey>,                // 推荐人手续费账户（可选）
+    pub bin_arrays: Vec<Pubkey>,                    // 沿成交方向的 BinArray（remaining accounts）
+}
+
+/// 构造 lb_clmm swap 指令（账户顺序与程序 Swap 上下文一致，可选账户以程序ID占位）
+pub fn build_swap_instruction(
+    lb_pair: &Pubkey,
+    pair: &LbPairState,
+    accounts: &DlmmSwapAccounts,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let optional = |key: Option<Pubkey>| key.unwrap_or(METEORA_DLMM_PROGRAM_ID);
+    let event_authority = Pubkey::find_program_address(&[b"__event_authority"], &METEORA_DLMM_PROGRAM_ID).0;
+    let mut metas = vec![
+        AccountMeta::new(*lb_pair, false),
+        AccountMeta::new_readonly(optional(accounts.bin_array_bitmap_extension), false),
+        AccountMeta::new(pair.reserve_x, false),
+        AccountMeta::new(pair.reserve_y, false),
+        AccountMeta::new(accounts.user_token_in, false),
+        AccountMeta::new(accounts.user_token_out, false),
+        AccountMeta::new_readonly(pair.token_x_mint, false),
+        AccountMeta::new_readonly(pair.token_y_mint, false),
+        AccountMeta::new(pair.oracle, false),
+        AccountMeta::new(optional(accounts.host_fee_in), false),
+        AccountMeta::new_readonly(accounts.user, true),
+        AccountMeta::new_readonly(accounts.token_x_program, false),
+        AccountMeta::new_readonly(accounts.token_y_program, false),
+        AccountMeta::new_readonly(event_authority, false),
+        AccountMeta::new_readonly(METEORA_DLMM_PROGRAM_ID, false),
+    ];
+    metas.extend(accounts.bin_arrays.iter().map(|key| AccountMeta::new(*key, false)));
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&SWAP_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+    Instruction { program_id: METEORA_DLMM_PROGRAM_ID, accounts: metas, data }
+}
+
+/// 执行 DLMM swap CPI（输入代币由程序 PDA 持有时传入 signer_seeds）
+pub fn invoke_swap(instruction: &Instruction, account_infos: &[AccountInfo], signer_seeds: &[&[&[u8]]]) -> anchor_lang::Result<()> {
+    require_keys_eq!(instruction.program_id, METEORA_DLMM_PROGRAM_ID, MeteoraError::InvalidAmmAccount);
+    anchor_lang::solana_program::program::invoke_signed(instruction, account_infos, signer_seeds)?;
+    Ok(())
+}
 
 /// Meteora DEX 适配器结构体。
 pub struct MeteoraAdapter;
 
+impl MeteoraAdapter {
+    /// 基于 DLMM 账户逐 bin 报价
+    /// - accounts[0]: lb_pair 账户（归属 DLMM 程序）
+    /// - accounts[1..]: 覆盖成交方向的连续 BinArray 账户
+    /// - now: 当前 Unix 时间戳（波动率衰减）
+    pub fn quote_dlmm(&self, params: &TradeParams, accounts: &[AccountInfo], now: i64) -> anchor_lang::Result<DlmmQuote> {
+        require!(params.amount_in > 0, MeteoraError::InvalidAmount);
+        require!(params.from_token != params.to_token, MeteoraError::InvalidTokens);
+        require!(accounts.len() >= 2, DexAdapterError::MissingPoolAccounts);
+        let pool = &accounts[0];
+        require_keys_eq!(*pool.owner, METEORA_DLMM_PROGRAM_ID, MeteoraError::InvalidAmmAccount);
+        let pair = {
+            let data = pool.try_borrow_data()?;
+            LbPairState::unpack(&data).ok_or(MeteoraError::InvalidAmmAccount)?
+        };
+        require!(pair.status == PAIR_STATUS_ENABLED, MeteoraError::Unsupported);
+        require!(pair.bin_step > 0, MeteoraError::InvalidAmmAccount);
+        let swap_for_y = if params.from_token == pair.token_x_mint && params.to_token == pair.token_y_mint {
+            true
+        } else if params.from_token == pair.token_y_mint && params.to_token == pair.token_x_mint {
+            false
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let mut arrays = Vec::with_capacity(accounts.len() - 1);
+        for info in &accounts[1..] {
+            require_keys_eq!(*info.owner, METEORA_DLMM_PROGRAM_ID, DexAdapterError::InvalidBinArray);
+            let data = info.try_borrow_data()?;
+            arrays.push(MeteoraBinArray::unpack(&data, pool.key).ok_or(DexAdapterError::InvalidBinArray)?);
+        }
+        let bin_set = DlmmBinSet::from_arrays(arrays).ok_or(DexAdapterError::InvalidBinArray)?;
+        let quote = dlmm::swap_quote(&pair.to_pair_state(), &bin_set, params.amount_in, swap_for_y, now)
+            .ok_or(DexAdapterError::InvalidBinArray)?;
+        require!(quote.amount_in == params.amount_in, DexAdapterError::InsufficientBinArrays);
+        Ok(quote)
+    }
+}
+
 impl AdapterTrait for MeteoraAdapter {
     fn name(&self) -> &str { "meteora" }
     fn version(&self) -> &str { "1.0.0" }
@@ -21,61 +287,130 @@ impl AdapterTrait for MeteoraAdapter {
 
 impl DexAdapter for MeteoraAdapter {
     /// 执行 Meteora swap 操作。
+    /// - 报价依赖 lb_pair 与 BinArray 账户，未携带账户时无法给出真实成交：报价使用 quote_checked，成交使用 build_swap_instruction。
     fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // 生产级实现：集成Meteora链上CPI调用，参数校验、错误处理、事件追踪
-        require!(params.amount_in > 0, crate::errors::asset_error::AssetError::InvalidAmount);
-        require!(params.from_token != params.to_token, crate::errors::dex_error::DexError::InvalidTokens);
-        
-        // TODO: 调用Meteora CPI（此处应集成真实CPI调用）
-        // 这里只做结构示例，实际应调用CPI并返回真实成交数据
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000, // 应为CPI返回均价
-            fee: 1000,            // 应为CPI返回手续费
-            dex_name: "meteora".to_string(),
-        })
+        require!(params.amount_in > 0, MeteoraError::InvalidAmount);
+        require!(params.from_token != params.to_token, MeteoraError::InvalidTokens);
+        Err(DexAdapterError::MissingPoolAccounts.into())
     }
-    
+
     /// 批量 swap 操作。
     fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
-        Ok(params.trades.iter().map(|p| DexSwapResult {
-            executed_amount: p.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "meteora".to_string(),
-        }).collect())
+        params.trades.iter().map(|p| self.swap(p)).collect()
     }
-    
+
     /// 配置 Meteora 适配器。
     fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> { Ok(()) }
-    
+
+    /// 基于 lb_pair 与 BinArray 账户报价（链上时钟驱动可变费率衰减）。
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        let now = Clock::get()?.unix_timestamp;
+        let quote = self.quote_dlmm(params, accounts, now)?;
+        Ok(DexQuote::from_curve(&quote.to_curve_quote(), self.name()))
+    }
+
+    /// 构造 DLMM swap CPI 指令：accounts[0] 为 lb_pair，其后为沿成交方向的 BinArray；min_amount_out 由 DLMM 程序强制。
+    fn build_swap_instruction(&self, params: &TradeParams, user: &DexSwapUserAccounts, accounts: &[AccountInfo]) -> anchor_lang::Result<Instruction> {
+        require!(params.amount_in > 0, MeteoraError::InvalidAmount);
+        require!(accounts.len() >= 2, DexAdapterError::MissingPoolAccounts);
+        let pool = &accounts[0];
+        require_keys_eq!(*pool.owner, METEORA_DLMM_PROGRAM_ID, MeteoraError::InvalidAmmAccount);
+        let pair = {
+            let data = pool.try_borrow_data()?;
+            LbPairState::unpack(&data).ok_or(MeteoraError::InvalidAmmAccount)?
+        };
+        let forward = params.from_token == pair.token_x_mint && params.to_token == pair.token_y_mint;
+        let backward = params.from_token == pair.token_y_mint && params.to_token == pair.token_x_mint;
+        require!(forward || backward, DexAdapterError::TokenPairMismatch);
+        for info in &accounts[1..] {
+            require_keys_eq!(*info.owner, METEORA_DLMM_PROGRAM_ID, DexAdapterError::InvalidBinArray);
+        }
+        let swap_accounts = DlmmSwapAccounts {
+            user: user.authority,
+            user_token_in: user.source,
+            user_token_out: user.destination,
+            token_x_program: user.token_program,
+            token_y_program: user.token_program,
+            bin_array_bitmap_extension: None,
+            host_fee_in: None,
+            bin_arrays: accounts[1..].iter().map(|info| *info.key).collect(),
+        };
+        Ok(build_swap_instruction(pool.key, &pair, &swap_accounts, params.amount_in, params.min_amount_out))
+    }
+
+    /// 支持以程序 PDA 签名的 swap CPI。
+    fn supports_swap_cpi(&self) -> bool { true }
+
+    /// 执行 DLMM swap CPI。
+    fn invoke_swap(&self, instruction: &Instruction, account_infos: &[AccountInfo], signer_seeds: &[&[&[u8]]]) -> anchor_lang::Result<()> {
+        invoke_swap(instruction, account_infos, signer_seeds)
+    }
+
+    /// 报价/成交所需账户：lb_pair + 沿成交方向的连续 BinArray。
+    fn account_requirements(&self, operation: DexOperation, _pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        match operation {
+            DexOperation::Quote | DexOperation::Swap => vec![
+                DexAccountRequirement::readonly("lb_pair"),
+                DexAccountRequirement::readonly("bin_array").repeated(),
+            ],
+            _ => vec![],
+        }
+    }
+
     /// 返回支持的资产列表。
-    fn supported_assets(&self) -> Vec<String> { 
-        vec!["SOL".to_string(), "USDC".to_string(), "METEORA".to_string(), "ETH".to_string()] 
+    fn supported_assets(&self) -> Vec<String> {
+        vec!["SOL".to_string(), "USDC".to_string(), "METEORA".to_string(), "ETH".to_string()]
     }
-    
+
     /// 返回支持的市场类型。
-    fn supported_markets(&self) -> Vec<String> { 
-        vec!["spot".to_string(), "perpetual".to_string()] 
+    fn supported_markets(&self) -> Vec<String> {
+        vec!["spot".to_string(), "perpetual".to_string()]
     }
-    
+
     /// 返回适配器类型。
     fn adapter_type(&self) -> DexAdapterType { DexAdapterType::AMM }
 }
 
-/// Meteora DEX CPI账户结构声明
+/// Meteora DLMM swap CPI账户结构声明（顺序与 lb_clmm Swap 上下文一致，BinArray 通过 remaining_accounts 传入）
 #[derive(Accounts)]
 pub struct MeteoraSwap<'info> {
-    /// Meteora程序
-    pub meteora_program: AccountInfo<'info>,
-    /// AMM账户
-    pub amm_account: AccountInfo<'info>,
+    /// lb_pair账户
+    #[account(mut)]
+    pub lb_pair: AccountInfo<'info>,
+    /// bitmap扩展账户（可选，缺省传程序ID）
+    pub bin_array_bitmap_extension: AccountInfo<'info>,
+    /// X储备金库
+    #[account(mut)]
+    pub reserve_x: AccountInfo<'info>,
+    /// Y储备金库
+    #[account(mut)]
+    pub reserve_y: AccountInfo<'info>,
     /// 输入代币账户
-    pub input_token_account: AccountInfo<'info>,
+    #[account(mut)]
+    pub user_token_in: AccountInfo<'info>,
     /// 输出代币账户
-    pub output_token_account: AccountInfo<'info>,
+    #[account(mut)]
+    pub user_token_out: AccountInfo<'info>,
+    /// X mint
+    pub token_x_mint: AccountInfo<'info>,
+    /// Y mint
+    pub token_y_mint: AccountInfo<'info>,
+    /// 预言机账户
+    #[account(mut)]
+    pub oracle: AccountInfo<'info>,
+    /// 推荐人手续费账户（可选，缺省传程序ID）
+    #[account(mut)]
+    pub host_fee_in: AccountInfo<'info>,
     /// 用户账户
-    pub user: AccountInfo<'info>,
+    pub user: Signer<'info>,
+    /// X代币程序
+    pub token_x_program: AccountInfo<'info>,
+    /// Y代币程序
+    pub token_y_program: AccountInfo<'info>,
+    /// 事件权限PDA
+    pub event_authority: AccountInfo<'info>,
+    /// Meteora DLMM程序
+    pub meteora_program: AccountInfo<'info>,
 }
 
 /// Meteora DEX错误码（Anchor错误）
@@ -105,8 +440,58 @@ fn register_meteora_adapter() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::adapter::check_quote_min_out;
     use anchor_lang::prelude::Pubkey;
 
+    /// 构造交易参数。
+    fn trade(from: Pubkey, to: Pubkey, amount_in: u64, min_amount_out: u64) -> TradeParams {
+        TradeParams {
+            trade_type: "swap".to_string(),
+            from_token: from,
+            to_token: to,
+            amount_in,
+            min_amount_out,
+            dex_name: "meteora".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        }
+    }
+
+    /// 交易对夹具：bin_step 10、基础费率 0.1%，活跃 bin 0；bin [-70, -1] 每个 1_000 Y，bin 0 有 5_000 Y。
+    fn pair_fixture() -> (LbPairState, Vec<Pubkey>, Vec<Vec<u8>>) {
+        let lb_pair = Pubkey::new_unique();
+        let pair = LbPairState {
+            fees: DlmmFeeParameters {
+                base_factor: 10_000,
+                filter_period: 30,
+                decay_period: 600,
+                reduction_factor: 5_000,
+                variable_fee_control: 40_000,
+                max_volatility_accumulator: 350_000,
+                base_fee_power_factor: 0,
+            },
+            volatility: DlmmVolatilityState::default(),
+            active_id: 0,
+            bin_step: 10,
+            status: PAIR_STATUS_ENABLED,
+            token_x_mint: Pubkey::new_unique(),
+            token_y_mint: Pubkey::new_unique(),
+            reserve_x: Pubkey::new_unique(),
+            reserve_y: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+        };
+        let lower: Vec<DlmmBin> = (-70..0).map(|id| DlmmBin { id, amount_x: 0, amount_y: 1_000 }).collect();
+        let upper = [DlmmBin { id: 0, amount_x: 0, amount_y: 5_000 }, DlmmBin { id: 1, amount_x: 5_000, amount_y: 0 }];
+        let keys = vec![lb_pair, Pubkey::new_unique(), Pubkey::new_unique()];
+        let data = vec![
+            pair.pack(),
+            MeteoraBinArray::pack(&lb_pair, 0, &upper),
+            MeteoraBinArray::pack(&lb_pair, -1, &lower),
+        ];
+        (pair, keys, data)
+    }
+
     /// 测试 MeteoraAdapter 名称。
     #[test]
     fn test_meteora_adapter_name() {
@@ -114,21 +499,111 @@ mod tests {
         assert_eq!(adapter.name(), "meteora");
     }
 
-    /// 测试 MeteoraAdapter swap 功能。
+    /// 测试 lb_pair 与 BinArray 解析往返。
+    #[test]
+    fn test_lb_pair_and_bin_array_roundtrip() {
+        let (pair, keys, data) = pair_fixture();
+        assert_eq!(LbPairState::unpack(&data[0]), Some(pair));
+        let array = MeteoraBinArray::unpack(&data[2], &keys[0]).unwrap();
+        assert_eq!(array.index, -1);
+        assert_eq!(array.bins.len(), 70);
+        assert_eq!(array.bins[0].id, -70);
+        assert!(MeteoraBinArray::unpack(&data[2], &Pubkey::new_unique()).is_none());
+        assert_eq!(MeteoraBinArray::indexes_for_swap(-1, true, 3), vec![-1, -2, -3]);
+        assert_eq!(MeteoraBinArray::indexes_for_swap(70, false, 2), vec![1, 2]);
+    }
+
+    /// 测试 MeteoraAdapter 逐 bin 报价与结算。
     #[test]
     fn test_meteora_adapter_swap() {
         let adapter = MeteoraAdapter;
-        let params = TradeParams {
-            from_token: Pubkey::default(),
-            to_token: Pubkey::new_unique(), // 使用不同的token
-            amount_in: 100,
-            min_amount_out: 90,
-            dex_name: "meteora".to_string(),
+        let (pair, keys, mut data) = pair_fixture();
+        let mut lamports = vec![0u64; 3];
+        let infos: Vec<AccountInfo> = keys.iter().zip(data.iter_mut()).zip(lamports.iter_mut())
+            .map(|((key, d), l)| AccountInfo::new(key, false, false, l, d, &METEORA_DLMM_PROGRAM_ID, false, 0))
+            .collect();
+        let (x, y) = (pair.token_x_mint, pair.token_y_mint);
+        // 活跃 bin 内成交：0.1% 基础费率
+        let small = adapter.quote_dlmm(&trade(x, y, 1_000, 0), &infos, 0).unwrap();
+        assert_eq!(small.fee, 1);
+        assert_eq!(small.amount_out, 999);
+        assert_eq!(small.bins_crossed, 0);
+        // 穿越多个 bin：可变费率随波动率累加器上升
+        let large = adapter.quote_dlmm(&trade(x, y, 20_000, 0), &infos, 0).unwrap();
+        assert!(large.bins_crossed > 10);
+        assert!(large.fee > 20);
+        assert!(large.active_id_after < -10);
+        // 结算：min_amount_out 校验
+        let quote = DexQuote::from_curve(&small.to_curve_quote(), adapter.name());
+        assert!(check_quote_min_out(&trade(x, y, 1_000, 999), quote.clone()).is_ok());
+        assert!(check_quote_min_out(&trade(x, y, 1_000, 1_000), quote).is_err());
+        // 超出 BinArray 覆盖范围
+        assert!(adapter.quote_dlmm(&trade(x, y, 1_000_000, 0), &infos, 0).is_err());
+        // 代币对不匹配、无账户 swap
+        assert!(adapter.quote_dlmm(&trade(x, Pubkey::new_unique(), 1_000, 0), &infos, 0).is_err());
+        assert!(adapter.swap(&trade(x, y, 100, 90)).is_err());
+    }
+
+    /// 测试 swap 指令账户列表与指令数据。
+    #[test]
+    fn test_build_swap_instruction() {
+        let (pair, keys, _) = pair_fixture();
+        let accounts = DlmmSwapAccounts {
+            user: Pubkey::new_unique(),
+            user_token_in: Pubkey::new_unique(),
+            user_token_out: Pubkey::new_unique(),
+            token_x_program: anchor_spl::token::ID,
+            token_y_program: anchor_spl::token::ID,
+            bin_array_bitmap_extension: None,
+            host_fee_in: None,
+            bin_arrays: MeteoraBinArray::indexes_for_swap(pair.active_id, true, 2)
+                .into_iter()
+                .map(|index| MeteoraBinArray::address(&keys[0], index))
+                .collect(),
         };
-        let result = adapter.swap(&params);
-        assert!(result.is_ok());
+        let ix = build_swap_instruction(&keys[0], &pair, &accounts, 1_000, 990);
+        assert_eq!(ix.program_id, METEORA_DLMM_PROGRAM_ID);
+        assert_eq!(ix.accounts.len(), 17);
+        assert_eq!(ix.accounts[0].pubkey, keys[0]);
+        assert!(ix.accounts[0].is_writable);
+        assert_eq!(ix.accounts[1].pubkey, METEORA_DLMM_PROGRAM_ID); // 可选账户占位
+        assert_eq!(ix.accounts[2].pubkey, pair.reserve_x);
+        assert!(ix.accounts[10].is_signer);
+        assert_eq!(ix.accounts[15].pubkey, MeteoraBinArray::address(&keys[0], 0));
+        assert_eq!(&ix.data[..8], &SWAP_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 1_000);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 990);
     }
-    
+
+    /// 测试适配器按池账户构造 swap CPI 指令。
+    #[test]
+    fn test_adapter_build_swap_instruction() {
+        let adapter = MeteoraAdapter;
+        let (pair, keys, mut data) = pair_fixture();
+        let mut lamports = vec![0u64; 3];
+        let infos: Vec<AccountInfo> = keys.iter().zip(data.iter_mut()).zip(lamports.iter_mut())
+            .map(|((key, d), l)| AccountInfo::new(key, false, false, l, d, &METEORA_DLMM_PROGRAM_ID, false, 0))
+            .collect();
+        let user = DexSwapUserAccounts {
+            authority: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
+        };
+        let (x, y) = (pair.token_x_mint, pair.token_y_mint);
+        assert!(adapter.supports_swap_cpi());
+        let ix = adapter.build_swap_instruction(&trade(x, y, 1_000, 990), &user, &infos).unwrap();
+        assert_eq!(ix.accounts.len(), 17);
+        assert_eq!(ix.accounts[4].pubkey, user.source);
+        assert_eq!(ix.accounts[5].pubkey, user.destination);
+        assert_eq!(ix.accounts[10].pubkey, user.authority);
+        assert_eq!(ix.accounts[16].pubkey, keys[2]);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 990);
+        assert!(adapter.build_swap_instruction(&trade(x, Pubkey::new_unique(), 1_000, 0), &user, &infos).is_err());
+        assert!(adapter.build_swap_instruction(&trade(x, y, 1_000, 0), &user, &infos[..1]).is_err());
+    }
+
     /// 测试 MeteoraAdapter 支持的资产。
     #[test]
     fn test_meteora_supported_assets() {
@@ -138,4 +613,4 @@ mod tests {
         assert!(assets.contains(&"USDC".to_string()));
         assert!(assets.contains(&"METEORA".to_string()));
     }
-} 
\ No newline at end of file
+}
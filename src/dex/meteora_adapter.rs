@@ -34,13 +34,23 @@ fn auto_register_meteora_adapter() {
 /// 实现 DexAdapter trait，委托给 meteora.rs 中的真实实现。
 impl DexAdapter for MeteoraAdapter {
     fn swap(&self, params: &crate::core::types::TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // TODO: 实现实际的 swap 逻辑
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "meteora".to_string(),
-        })
+        RealMeteoraAdapter.swap(params)
+    }
+    
+    fn batch_swap(&self, params: &crate::core::types::BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
+        RealMeteoraAdapter.batch_swap(params)
+    }
+    
+    fn configure(&self, params: &crate::core::types::DexParams) -> anchor_lang::Result<()> {
+        RealMeteoraAdapter.configure(params)
+    }
+
+    fn get_quote(&self, params: &crate::core::types::TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<crate::dex::adapter::DexQuote> {
+        RealMeteoraAdapter.get_quote(params, accounts)
+    }
+
+    fn account_requirements(&self, operation: crate::dex::adapter::DexOperation, pool_program: &Pubkey) -> Vec<crate::dex::adapter::DexAccountRequirement> {
+        RealMeteoraAdapter.account_requirements(operation, pool_program)
     }
     
     fn supported_assets(&self) -> Vec<String> {
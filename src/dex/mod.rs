@@ -6,8 +6,12 @@
 // 导入并公开所有子模块。
 pub mod adapter;            // 适配器基类与通用逻辑
 pub mod adapter_registry;   // 适配器注册表
+pub mod clmm;               // 集中流动性 tick 穿越报价数学
+pub mod curve;              // AMM 曲线数学（常积/StableSwap）
+pub mod dlmm;               // Meteora DLMM 逐 bin 报价数学
 pub mod factory;            // 工厂模式实现
 pub mod jupiter;            // Jupiter 适配器
+pub mod layout;             // 池账户字节布局解析工具
 pub mod lifinity;           // Lifinity 适配器
 pub mod lifinity_adapter;   // Lifinity 适配器实现
 pub mod logging;            // 日志工具
@@ -18,6 +22,7 @@ pub mod openbook;           // OpenBook 适配器
 pub mod openbook_adapter;   // OpenBook 适配器实现
 pub mod orca;               // Orca 适配器
 pub mod orca_adapter;       // Orca 适配器实现
+pub mod orderbook;          // 订单簿逐档吃单报价数学
 pub mod phoenix;            // Phoenix 适配器
 pub mod phoenix_adapter;    // Phoenix 适配器实现
 pub mod raydium;            // Raydium 适配器
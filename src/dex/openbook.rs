@@ -2,39 +2,511 @@
 //! OpenBook DEX Adapter Module
 //!
 //! 本模块实现 OpenBook DEX 适配器，集成 Anchor CPI 调用，支持流动性管理、报价、异常处理等，确保链上集成合规、可维护。
+//! 报价基于传入的 OpenBook v2 Market 与 bids/asks BookSide 账户：遍历固定价格订单树叶子节点、剔除过期挂单后聚合档位，
+//! 按 taker 费率逐档吃单；成交路径按 place_take_order 指令构造 ImmediateOrCancel 吃单的真实 CPI 账户列表与指令数据。
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use crate::core::adapter::AdapterTrait;
 use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
-use crate::dex::adapter::{DexAdapter, DexSwapResult};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterError, DexAdapterType, DexOperation, DexQuote, DexSwapResult, DexSwapUserAccounts};
+use crate::dex::curve::FeeRate;
+use crate::dex::layout::{read_i64, read_pubkey, read_u128, read_u16, read_u32, read_u64, read_u8};
+use crate::dex::orderbook::{self, BookFill, OrderBook, OrderBookMarket, OrderSide};
+
+/// OpenBook v2 程序ID
+pub const OPENBOOK_V2_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb");
+/// Market 账户判别符（sha256("account:Market")[..8]）
+pub const MARKET_DISCRIMINATOR: [u8; 8] = [219, 190, 213, 55, 0, 227, 198, 154];
+/// BookSide 账户判别符（sha256("account:BookSide")[..8]）
+pub const BOOK_SIDE_DISCRIMINATOR: [u8; 8] = [72, 44, 225, 141, 178, 130, 97, 57];
+/// place_take_order 指令判别符（sha256("global:place_take_order")[..8]）
+pub const PLACE_TAKE_ORDER_DISCRIMINATOR: [u8; 8] = [3, 44, 71, 3, 26, 199, 203, 85];
+/// Market 账户长度
+pub const MARKET_LEN: usize = 848;
+/// 每个 BookSide 的订单树节点数
+pub const MAX_ORDERTREE_NODES: usize = 1024;
+/// BookSide 账户长度
+pub const BOOK_SIDE_LEN: usize = BOOK_SIDE_NODES + MAX_ORDERTREE_NODES * NODE_LEN;
+/// 手续费精度（taker_fee 以 1e-6 计）
+const FEES_SCALE_FACTOR: u64 = 1_000_000;
+/// 订单树节点数组偏移
+const BOOK_SIDE_NODES: usize = 840;
+/// 订单树节点长度
+const NODE_LEN: usize = 88;
+/// 内部节点标签
+const INNER_NODE_TAG: u8 = 1;
+/// 叶子节点标签
+const LEAF_NODE_TAG: u8 = 2;
+/// place_take_order 订单类型：ImmediateOrCancel
+const ORDER_TYPE_IMMEDIATE_OR_CANCEL: u8 = 1;
+/// place_take_order 单笔最多匹配挂单数
+const TAKE_ORDER_MATCH_LIMIT: u8 = 16;
+
+/// OpenBook v2 市场状态（仅解析报价与 CPI 所需字段，偏移含 8 字节 Anchor 判别符）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpenBookMarketState {
+    pub market_authority: Pubkey,          // 市场金库权限 PDA
+    pub time_expiry: i64,                  // 市场过期时间（0=不过期）
+    pub open_orders_admin: Option<Pubkey>, // 下单管理员（可选）
+    pub bids: Pubkey,                      // 买盘 BookSide
+    pub asks: Pubkey,                      // 卖盘 BookSide
+    pub event_heap: Pubkey,                // 事件堆
+    pub oracle_a: Option<Pubkey>,          // 预言机 A（可选）
+    pub oracle_b: Option<Pubkey>,          // 预言机 B（可选）
+    pub quote_lot_size: i64,               // 每 quote lot 的 quote 原子数量
+    pub base_lot_size: i64,                // 每 base lot 的 base 原子数量
+    pub taker_fee: i64,                    // taker 费率（1e-6）
+    pub base_mint: Pubkey,                 // base mint
+    pub quote_mint: Pubkey,                // quote mint
+    pub market_base_vault: Pubkey,         // base 金库
+    pub market_quote_vault: Pubkey,        // quote 金库
+}
+
+impl OpenBookMarketState {
+    const MARKET_AUTHORITY: usize = 16;
+    const TIME_EXPIRY: usize = 48;
+    const OPEN_ORDERS_ADMIN: usize = 88;
+    const BIDS: usize = 200;
+    const ASKS: usize = 232;
+    const EVENT_HEAP: usize = 264;
+    const ORACLE_A: usize = 296;
+    const ORACLE_B: usize = 328;
+    const QUOTE_LOT_SIZE: usize = 448;
+    const BASE_LOT_SIZE: usize = 456;
+    const TAKER_FEE: usize = 488;
+    const BASE_MINT: usize = 576;
+    const QUOTE_MINT: usize = 608;
+    const MARKET_BASE_VAULT: usize = 640;
+    const MARKET_QUOTE_VAULT: usize = 680;
+
+    /// 从 Market 账户数据解析
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < MARKET_LEN || data[..8] != MARKET_DISCRIMINATOR {
+            return None;
+        }
+        let optional = |offset: usize| read_pubkey(data, offset).filter(|key| *key != Pubkey::default());
+        Some(Self {
+            market_authority: read_pubkey(data, Self::MARKET_AUTHORITY)?,
+            time_expiry: read_i64(data, Self::TIME_EXPIRY)?,
+            open_orders_admin: optional(Self::OPEN_ORDERS_ADMIN),
+            bids: read_pubkey(data, Self::BIDS)?,
+            asks: read_pubkey(data, Self::ASKS)?,
+            event_heap: read_pubkey(data, Self::EVENT_HEAP)?,
+            oracle_a: optional(Self::ORACLE_A),
+            oracle_b: optional(Self::ORACLE_B),
+            quote_lot_size: read_i64(data, Self::QUOTE_LOT_SIZE)?,
+            base_lot_size: read_i64(data, Self::BASE_LOT_SIZE)?,
+            taker_fee: read_i64(data, Self::TAKER_FEE)?,
+            base_mint: read_pubkey(data, Self::BASE_MINT)?,
+            quote_mint: read_pubkey(data, Self::QUOTE_MINT)?,
+            market_base_vault: read_pubkey(data, Self::MARKET_BASE_VAULT)?,
+            market_quote_vault: read_pubkey(data, Self::MARKET_QUOTE_VAULT)?,
+        })
+    }
+
+    /// 序列化为 Market 账户数据（未解析字段置零，仅供测试与离线模拟）
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0u8; MARKET_LEN];
+        data[..8].copy_from_slice(&MARKET_DISCRIMINATOR);
+        let mut put = |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(Self::MARKET_AUTHORITY, self.market_authority.as_ref());
+        put(Self::TIME_EXPIRY, &self.time_expiry.to_le_bytes());
+        put(Self::OPEN_ORDERS_ADMIN, self.open_orders_admin.unwrap_or_default().as_ref());
+        put(Self::BIDS, self.bids.as_ref());
+        put(Self::ASKS, self.asks.as_ref());
+        put(Self::EVENT_HEAP, self.event_heap.as_ref());
+        put(Self::ORACLE_A, self.oracle_a.unwrap_or_default().as_ref());
+        put(Self::ORACLE_B, self.oracle_b.unwrap_or_default().as_ref());
+        put(Self::QUOTE_LOT_SIZE, &self.quote_lot_size.to_le_bytes());
+        put(Self::BASE_LOT_SIZE, &self.base_lot_size.to_le_bytes());
+        put(Self::TAKER_FEE, &self.taker_fee.to_le_bytes());
+        put(Self::BASE_MINT, self.base_mint.as_ref());
+        put(Self::QUOTE_MINT, self.quote_mint.as_ref());
+        put(Self::MARKET_BASE_VAULT, self.market_base_vault.as_ref());
+        put(Self::MARKET_QUOTE_VAULT, self.market_quote_vault.as_ref());
+        data
+    }
+
+    /// lot 换算参数：价格即每 base lot 的 quote lots
+    pub fn to_market(&self) -> OrderBookMarket {
+        OrderBookMarket {
+            base_lot_size: self.base_lot_size.max(0) as u64,
+            quote_lot_size: self.quote_lot_size.max(0) as u64,
+            price_numerator: 1,
+            price_denominator: 1,
+            taker_fee: FeeRate::new(self.taker_fee.max(0) as u64, FEES_SCALE_FACTOR),
+        }
+    }
+
+    /// 市场是否已过期
+    pub fn is_expired(&self, unix_timestamp: i64) -> bool {
+        self.time_expiry != 0 && unix_timestamp >= self.time_expiry
+    }
+}
+
+/// OpenBook v2 挂单（叶子节点中的报价相关字段）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenBookOrder {
+    pub price_lots: u64,    // 价格（quote lots / base lot）
+    pub quantity: u64,      // 数量（base lots）
+    pub timestamp: u64,     // 挂单时间戳
+    pub time_in_force: u16, // 有效期（秒，0=不过期）
+}
+
+impl OpenBookOrder {
+    /// 按当前时间戳判断是否已过期
+    pub fn is_expired(&self, unix_timestamp: i64) -> bool {
+        self.time_in_force != 0 && unix_timestamp >= self.timestamp as i64 + self.time_in_force as i64
+    }
+}
+
+/// OpenBook v2 BookSide 账户解析（仅固定价格订单树，预言机锚定订单不计入报价）
+pub struct OpenBookBookSide;
+
+impl OpenBookBookSide {
+    /// 自固定订单树根节点遍历叶子节点（访问数超过节点容量视为损坏）
+    pub fn unpack(data: &[u8]) -> Option<Vec<OpenBookOrder>> {
+        if data.len() < BOOK_SIDE_LEN || data[..8] != BOOK_SIDE_DISCRIMINATOR {
+            return None;
+        }
+        let root = read_u32(data, 8)?;
+        let leaf_count = read_u32(data, 12)?;
+        let mut orders = Vec::with_capacity(leaf_count as usize);
+        if leaf_count == 0 {
+            return Some(orders);
+        }
+        let mut stack = vec![root];
+        let mut visited = 0usize;
+        while let Some(index) = stack.pop() {
+            visited += 1;
+            if index as usize >= MAX_ORDERTREE_NODES || visited > MAX_ORDERTREE_NODES {
+                return None;
+            }
+            let node = BOOK_SIDE_NODES + index as usize * NODE_LEN;
+            match read_u8(data, node)? {
+                INNER_NODE_TAG => {
+                    stack.push(read_u32(data, node + 24)?);
+                    stack.push(read_u32(data, node + 28)?);
+                }
+                LEAF_NODE_TAG => orders.push(OpenBookOrder {
+                    price_lots: (read_u128(data, node + 8)? >> 64) as u64,
+                    quantity: read_i64(data, node + 56)?.max(0) as u64,
+                    timestamp: read_u64(data, node + 64)?,
+                    time_in_force: read_u16(data, node + 2)?,
+                }),
+                _ => return None,
+            }
+        }
+        if orders.len() != leaf_count as usize {
+            return None;
+        }
+        Some(orders)
+    }
+
+    /// 序列化为 BookSide 账户数据（内部节点按右链挂接叶子，仅供测试与离线模拟）
+    pub fn pack(orders: &[OpenBookOrder]) -> Vec<u8> {
+        let mut data = vec![0u8; BOOK_SIDE_LEN];
+        data[..8].copy_from_slice(&BOOK_SIDE_DISCRIMINATOR);
+        data[12..16].copy_from_slice(&(orders.len() as u32).to_le_bytes());
+        let leaves = orders.len();
+        let inners = leaves.saturating_sub(1);
+        // 节点 0..inners 为内部节点，inner[i] 子节点为 leaf[i] 与 inner[i+1]（末个内部节点指向最后两片叶子）
+        for i in 0..inners {
+            let node = BOOK_SIDE_NODES + i * NODE_LEN;
+            let left = (inners + i) as u32;
+            let right = if i + 1 < inners { (i + 1) as u32 } else { (inners + leaves - 1) as u32 };
+            data[node] = INNER_NODE_TAG;
+            data[node + 24..node + 28].copy_from_slice(&left.to_le_bytes());
+            data[node + 28..node + 32].copy_from_slice(&right.to_le_bytes());
+        }
+        for (i, order) in orders.iter().enumerate() {
+            let node = BOOK_SIDE_NODES + (inners + i) * NODE_LEN;
+            let key = ((order.price_lots as u128) << 64) | i as u128;
+            data[node] = LEAF_NODE_TAG;
+            data[node + 2..node + 4].copy_from_slice(&order.time_in_force.to_le_bytes());
+            data[node + 8..node + 24].copy_from_slice(&key.to_le_bytes());
+            data[node + 56..node + 64].copy_from_slice(&(order.quantity as i64).to_le_bytes());
+            data[node + 64..node + 72].copy_from_slice(&order.timestamp.to_le_bytes());
+        }
+        data // 根节点下标为 0（内部节点 0 或唯一叶子），零初始化即可
+    }
+}
+
+/// place_take_order 指令参数（Borsh 编码顺序与 OpenBook v2 PlaceTakeOrderArgs 一致）
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct PlaceTakeOrderArgs {
+    pub side: OrderSide,                     // 方向
+    pub price_lots: i64,                     // 限价（quote lots / base lot）
+    pub max_base_lots: i64,                  // 最多成交 base lots
+    pub max_quote_lots_including_fees: i64,  // 最多支付 quote lots（含手续费）
+    pub order_type: u8,                      // 订单类型（1=ImmediateOrCancel）
+    pub limit: u8,                           // 最多匹配挂单数
+}
+
+impl PlaceTakeOrderArgs {
+    /// 由输入数量生成 IOC 吃单参数；price_lots 取报价最差档位，盘口变差时链上不再成交
+    pub fn immediate_or_cancel(state: &OpenBookMarketState, side: OrderSide, amount_in: u64, price_lots: u64, limit: u8) -> Self {
+        let lots = |amount: u64, lot: i64| i64::try_from(amount / lot.max(1) as u64).unwrap_or(i64::MAX);
+        let (max_base_lots, max_quote_lots_including_fees) = match side {
+            OrderSide::Bid => (i64::MAX, lots(amount_in, state.quote_lot_size)),
+            OrderSide::Ask => (lots(amount_in, state.base_lot_size), i64::MAX),
+        };
+        Self {
+            side,
+            price_lots: i64::try_from(price_lots).unwrap_or(i64::MAX),
+            max_base_lots,
+            max_quote_lots_including_fees,
+            order_type: ORDER_TYPE_IMMEDIATE_OR_CANCEL,
+            limit,
+        }
+    }
+}
+
+/// place_take_order CPI 所需的用户侧账户
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpenBookTakeAccounts {
+    pub signer: Pubkey,             // 签名者（持有输入代币的账户或 PDA）
+    pub penalty_payer: Pubkey,      // 罚金支付者（签名）
+    pub user_base_account: Pubkey,  // base 代币账户
+    pub user_quote_account: Pubkey, // quote 代币账户
+    pub token_program: Pubkey,      // SPL Token 程序
+}
+
+/// 构造 place_take_order 指令（账户顺序与程序 PlaceTakeOrder 上下文一致，可选账户以程序ID占位）
+/// - IOC 不校验最小输出，调用方需在 CPI 后按代币余额变化校验 min_amount_out
+pub fn build_place_take_order_instruction(
+    market: &Pubkey,
+    state: &OpenBookMarketState,
+    accounts: &OpenBookTakeAccounts,
+    args: &PlaceTakeOrderArgs,
+) -> Instruction {
+    let optional = |key: Option<Pubkey>| key.unwrap_or(OPENBOOK_V2_PROGRAM_ID);
+    let metas = vec![
+        AccountMeta::new(accounts.signer, true),
+        AccountMeta::new(accounts.penalty_payer, true),
+        AccountMeta::new(*market, false),
+        AccountMeta::new_readonly(state.market_authority, false),
+        AccountMeta::new(state.bids, false),
+        AccountMeta::new(state.asks, false),
+        AccountMeta::new(state.market_base_vault, false),
+        AccountMeta::new(state.market_quote_vault, false),
+        AccountMeta::new(state.event_heap, false),
+        AccountMeta::new(accounts.user_base_account, false),
+        AccountMeta::new(accounts.user_quote_account, false),
+        AccountMeta::new_readonly(optional(state.oracle_a), false),
+        AccountMeta::new_readonly(optional(state.oracle_b), false),
+        AccountMeta::new_readonly(accounts.token_program, false),
+        AccountMeta::new_readonly(anchor_lang::solana_program::system_program::ID, false),
+        AccountMeta::new_readonly(optional(state.open_orders_admin), state.open_orders_admin.is_some()),
+    ];
+    let mut data = PLACE_TAKE_ORDER_DISCRIMINATOR.to_vec();
+    args.serialize(&mut data).expect("serialize take order args");
+    Instruction { program_id: OPENBOOK_V2_PROGRAM_ID, accounts: metas, data }
+}
+
+/// 执行 place_take_order CPI（输入代币由程序 PDA 持有时传入 signer_seeds）
+pub fn invoke_take_order(instruction: &Instruction, account_infos: &[AccountInfo], signer_seeds: &[&[&[u8]]]) -> anchor_lang::Result<()> {
+    require_keys_eq!(instruction.program_id, OPENBOOK_V2_PROGRAM_ID, OpenBookError::InvalidMarketAccount);
+    anchor_lang::solana_program::program::invoke_signed(instruction, account_infos, signer_seeds)?;
+    Ok(())
+}
 
 /// OpenBook DEX 适配器结构体。
 /// 用于对接 Solana 链上的 OpenBook DEX，实现统一的 DEX 适配接口，集成流动性管理、报价等功能。
 #[derive(Default)]
 pub struct OpenBookAdapter;
 
-/// 实现 DexAdapter trait，集成 OpenBook 链上 CPI 调用。
+impl OpenBookAdapter {
+    /// 基于 Market 与 BookSide 账户逐档报价
+    /// - accounts[0]: Market 账户；accounts[1]: bids；accounts[2]: asks（均归属 OpenBook v2 程序）
+    /// - unix_timestamp: 当前时间戳（剔除过期挂单与过期市场）
+    pub fn quote_market(&self, params: &TradeParams, accounts: &[AccountInfo], unix_timestamp: i64) -> anchor_lang::Result<BookFill> {
+        require!(params.amount_in > 0, OpenBookError::InvalidAmount);
+        require!(params.from_token != params.to_token, OpenBookError::InvalidTokens);
+        require!(accounts.len() >= 3, DexAdapterError::MissingPoolAccounts);
+        for info in &accounts[..3] {
+            require_keys_eq!(*info.owner, OPENBOOK_V2_PROGRAM_ID, OpenBookError::InvalidMarketAccount);
+        }
+        let state = {
+            let data = accounts[0].try_borrow_data()?;
+            OpenBookMarketState::unpack(&data).ok_or(OpenBookError::InvalidMarketAccount)?
+        };
+        require!(!state.is_expired(unix_timestamp), OpenBookError::MarketExpired);
+        require_keys_eq!(*accounts[1].key, state.bids, OpenBookError::InvalidBookSide);
+        require_keys_eq!(*accounts[2].key, state.asks, OpenBookError::InvalidBookSide);
+        let side = if params.from_token == state.quote_mint && params.to_token == state.base_mint {
+            OrderSide::Bid
+        } else if params.from_token == state.base_mint && params.to_token == state.quote_mint {
+            OrderSide::Ask
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let unpack_side = |info: &AccountInfo| -> anchor_lang::Result<Vec<(u64, u64)>> {
+            let data = info.try_borrow_data()?;
+            let orders = OpenBookBookSide::unpack(&data).ok_or(OpenBookError::InvalidBookSide)?;
+            Ok(orders.into_iter().filter(|o| !o.is_expired(unix_timestamp)).map(|o| (o.price_lots, o.quantity)).collect())
+        };
+        let book = OrderBook::from_orders(state.to_market(), unpack_side(&accounts[1])?, unpack_side(&accounts[2])?);
+        let fill = orderbook::quote_fill(&book, side, params.amount_in, None).ok_or(DexAdapterError::MathOverflow)?;
+        require!(fill.complete, DexAdapterError::InsufficientLiquidity);
+        Ok(fill)
+    }
+
+    /// 按报价构造 IOC 吃单指令：限价取报价最差档位；IOC 不校验最小输出，调用方需按输出账户余额变化校验
+    pub fn take_order_instruction(&self, params: &TradeParams, user: &DexSwapUserAccounts, accounts: &[AccountInfo], unix_timestamp: i64) -> anchor_lang::Result<Instruction> {
+        let fill = self.quote_market(params, accounts, unix_timestamp)?;
+        require!(fill.amount_out >= params.min_amount_out, DexAdapterError::SlippageExceeded);
+        let market = &accounts[0];
+        let state = {
+            let data = market.try_borrow_data()?;
+            OpenBookMarketState::unpack(&data).ok_or(OpenBookError::InvalidMarketAccount)?
+        };
+        let (user_base_account, user_quote_account) = match fill.side {
+            OrderSide::Bid => (user.destination, user.source),
+            OrderSide::Ask => (user.source, user.destination),
+        };
+        let take_accounts = OpenBookTakeAccounts {
+            signer: user.authority,
+            penalty_payer: user.payer,
+            user_base_account,
+            user_quote_account,
+            token_program: user.token_program,
+        };
+        let args = PlaceTakeOrderArgs::immediate_or_cancel(&state, fill.side, params.amount_in, fill.worst_price_lots, TAKE_ORDER_MATCH_LIMIT);
+        Ok(build_place_take_order_instruction(market.key, &state, &take_accounts, &args))
+    }
+}
+
+impl AdapterTrait for OpenBookAdapter {
+    fn name(&self) -> &str { "openbook" }
+    fn version(&self) -> &str { "1.0.0" }
+    fn is_available(&self) -> bool { true }
+    fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+}
+
+/// 实现 DexAdapter trait，集成 OpenBook v2 链上报价与 CPI 调用。
 impl DexAdapter for OpenBookAdapter {
     /// 执行 OpenBook swap 操作。
+    /// - 报价依赖 Market 与 BookSide 账户，未携带账户时无法给出真实成交：报价使用 quote_checked，成交使用 build_swap_instruction。
     fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // 生产级实现：集成OpenBook链上CPI调用，参数校验、错误处理、事件追踪
-        require!(params.amount_in > 0, crate::errors::asset_error::AssetError::InvalidAmount);
-        // TODO: 调用OpenBook CPI（此处应集成真实CPI调用）
-        // 这里只做结构示例，实际应调用CPI并返回真实成交数据
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000, // 应为CPI返回均价
-            fee: 1000,            // 应为CPI返回手续费
-            dex_name: "openbook".to_string(),
-        })
+        require!(params.amount_in > 0, OpenBookError::InvalidAmount);
+        require!(params.from_token != params.to_token, OpenBookError::InvalidTokens);
+        Err(DexAdapterError::MissingPoolAccounts.into())
+    }
+
+    /// 批量 swap 操作。
+    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
+        params.trades.iter().map(|p| self.swap(p)).collect()
+    }
+
+    /// 配置 OpenBook 适配器。
+    fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> { Ok(()) }
+
+    /// 基于 Market 与 BookSide 账户报价（链上时钟剔除过期挂单）。
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        let now = Clock::get()?.unix_timestamp;
+        let fill = self.quote_market(params, accounts, now)?;
+        Ok(DexQuote::from_curve(&fill.to_curve_quote(), self.name()))
+    }
+
+    /// 基于 Market 与 BookSide 账户构造 IOC 吃单 CPI 指令（链上时钟剔除过期挂单）。
+    fn build_swap_instruction(&self, params: &TradeParams, user: &DexSwapUserAccounts, accounts: &[AccountInfo]) -> anchor_lang::Result<Instruction> {
+        let now = Clock::get()?.unix_timestamp;
+        self.take_order_instruction(params, user, accounts, now)
+    }
+
+    /// 支持以程序 PDA 签名的 swap CPI。
+    fn supports_swap_cpi(&self) -> bool { true }
+
+    /// 执行 place_take_order CPI。
+    fn invoke_swap(&self, instruction: &Instruction, account_infos: &[AccountInfo], signer_seeds: &[&[&[u8]]]) -> anchor_lang::Result<()> {
+        invoke_take_order(instruction, account_infos, signer_seeds)
+    }
+
+    /// 报价/成交所需账户：Market + bids + asks。
+    fn account_requirements(&self, operation: DexOperation, _pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        match operation {
+            DexOperation::Quote | DexOperation::Swap => vec![
+                DexAccountRequirement::readonly("market"),
+                DexAccountRequirement::readonly("bids"),
+                DexAccountRequirement::readonly("asks"),
+            ],
+            _ => vec![],
+        }
     }
-    // 其他 trait 方法如 batch_swap、configure 可按需实现
+
+    /// 返回支持的资产列表。
+    fn supported_assets(&self) -> Vec<String> {
+        vec!["SOL".to_string(), "USDC".to_string()]
+    }
+
+    /// 返回支持的市场类型。
+    fn supported_markets(&self) -> Vec<String> {
+        vec!["spot".to_string()]
+    }
+
+    /// 返回适配器类型。
+    fn adapter_type(&self) -> DexAdapterType { DexAdapterType::CLOB }
+}
+
+/// OpenBook v2 place_take_order CPI账户结构声明（顺序与程序 PlaceTakeOrder 上下文一致）
+#[derive(Accounts)]
+pub struct OpenBookTakeOrder<'info> {
+    /// 签名者
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// 罚金支付者
+    #[account(mut)]
+    pub penalty_payer: Signer<'info>,
+    /// 市场账户
+    #[account(mut)]
+    pub market: AccountInfo<'info>,
+    /// 市场权限PDA
+    pub market_authority: AccountInfo<'info>,
+    /// 买盘
+    #[account(mut)]
+    pub bids: AccountInfo<'info>,
+    /// 卖盘
+    #[account(mut)]
+    pub asks: AccountInfo<'info>,
+    /// base金库
+    #[account(mut)]
+    pub market_base_vault: AccountInfo<'info>,
+    /// quote金库
+    #[account(mut)]
+    pub market_quote_vault: AccountInfo<'info>,
+    /// 事件堆
+    #[account(mut)]
+    pub event_heap: AccountInfo<'info>,
+    /// base代币账户
+    #[account(mut)]
+    pub user_base_account: AccountInfo<'info>,
+    /// quote代币账户
+    #[account(mut)]
+    pub user_quote_account: AccountInfo<'info>,
+    /// 预言机A（可选，缺省传程序ID）
+    pub oracle_a: AccountInfo<'info>,
+    /// 预言机B（可选，缺省传程序ID）
+    pub oracle_b: AccountInfo<'info>,
+    /// 代币程序
+    pub token_program: AccountInfo<'info>,
+    /// 系统程序
+    pub system_program: AccountInfo<'info>,
+    /// 下单管理员（可选，缺省传程序ID）
+    pub open_orders_admin: AccountInfo<'info>,
 }
 
 /// OpenBook 适配器错误码（Anchor 错误）。
 #[error_code]
-pub enum ErrorCode {
-    #[msg("Invalid amount")] InvalidAmount,      // 输入数量无效（如为0）
-    #[msg("Operation unsupported")] Unsupported, // 操作不支持
+pub enum OpenBookError {
+    #[msg("Invalid amount")] InvalidAmount,                 // 输入数量无效（如为0）
+    #[msg("Operation unsupported")] Unsupported,            // 操作不支持
+    #[msg("Invalid tokens")] InvalidTokens,                 // 输入输出代币相同
+    #[msg("Invalid market account")] InvalidMarketAccount,  // Market 归属、判别符或布局不匹配
+    #[msg("Invalid book side account")] InvalidBookSide,    // bids/asks 与市场不匹配或树结构损坏
+    #[msg("Market expired")] MarketExpired,                 // 市场已过期
 }
 
 /// 自动注册 OpenBookAdapter 到工厂（如有需要可补充）。
@@ -46,54 +518,222 @@ fn register_openbook_adapter() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use anchor_lang::prelude::*;
+    use crate::dex::adapter::check_quote_min_out;
     use std::str::FromStr;
 
     /// MockOpenBookAdapter 为 OpenBook DEX 的测试实现，便于单元测试。
     struct MockOpenBookAdapter;
+    impl AdapterTrait for MockOpenBookAdapter {
+        fn name(&self) -> &str { "mock_openbook" }
+        fn version(&self) -> &str { "1.0.0" }
+        fn is_available(&self) -> bool { true }
+        fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+        fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    }
     impl DexAdapter for MockOpenBookAdapter {
         /// 模拟 swap 操作，返回输入数量的 96% 作为输出。
-        fn swap(
-            &self,
-            _ctx: Context<Swap>,
-            _input_mint: Pubkey,
-            _output_mint: Pubkey,
-            amount_in: u64,
-            _min_amount_out: u64,
-        ) -> anchor_lang::Result<u64> {
-            Ok(amount_in * 96 / 100) // 模拟 4% 滑点
+        fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
+            Ok(DexSwapResult {
+                executed_amount: params.amount_in,
+                avg_price: 960_000, // 模拟 4% 滑点
+                fee: 0,
+                dex_name: self.name().to_string(),
+            })
         }
         /// 模拟报价操作，返回输入数量的 96% 作为预期输出。
-        fn quote(
-            &self,
-            _input_mint: Pubkey,
-            _output_mint: Pubkey,
-            amount_in: u64,
-        ) -> anchor_lang::Result<u64> {
-            Ok(amount_in * 96 / 100)
+        fn get_quote(&self, params: &TradeParams, _accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+            Ok(DexQuote {
+                amount_in: params.amount_in,
+                amount_out: params.amount_in * 96 / 100,
+                fee: 0,
+                price_impact_bps: 400,
+                avg_price: 960_000,
+                dex_name: self.name().to_string(),
+            })
         }
     }
 
+    /// 主网 SOL/USDC 交易参数。
+    fn mainnet_trade(amount_in: u64, min_amount_out: u64) -> TradeParams {
+        trade(
+            Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
+            Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap(),
+            amount_in,
+            min_amount_out,
+        )
+    }
+
     /// 测试 OpenBookAdapter swap 功能。
     #[test]
     fn test_openbook_adapter_swap() {
         let adapter = MockOpenBookAdapter;
-        let ctx = Context::new();
-        let input = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
-        let output = Pubkey::from_str("USDC111111111111111111111111111111111111111").unwrap();
-        let result = adapter.swap(ctx, input, output, 100_000, 96_000);
+        let result = adapter.swap(&mainnet_trade(100_000, 96_000));
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 96_000);
+        assert_eq!(result.unwrap().amount_out(), 96_000);
     }
 
     /// 测试 OpenBookAdapter quote 功能。
     #[test]
     fn test_openbook_adapter_quote() {
         let adapter = MockOpenBookAdapter;
-        let input = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
-        let output = Pubkey::from_str("USDC111111111111111111111111111111111111111").unwrap();
-        let result = adapter.quote(input, output, 100_000);
+        let result = adapter.get_quote(&mainnet_trade(100_000, 0), &[]);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 96_000);
+        assert_eq!(result.unwrap().amount_out, 96_000);
+    }
+
+    /// 构造交易参数。
+    fn trade(from: Pubkey, to: Pubkey, amount_in: u64, min_amount_out: u64) -> TradeParams {
+        TradeParams {
+            trade_type: "swap".to_string(),
+            from_token: from,
+            to_token: to,
+            amount_in,
+            min_amount_out,
+            dex_name: "openbook".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        }
+    }
+
+    fn order(price_lots: u64, quantity: u64) -> OpenBookOrder {
+        OpenBookOrder { price_lots, quantity, timestamp: 0, time_in_force: 0 }
     }
-} 
\ No newline at end of file
+
+    /// 市场夹具：base lot 0.01 SOL、quote lot 1 USDC 原子，taker 费 4 基点（400 / 1e6）。
+    fn market_fixture() -> (OpenBookMarketState, Vec<Pubkey>, Vec<Vec<u8>>) {
+        let state = OpenBookMarketState {
+            market_authority: Pubkey::new_unique(),
+            time_expiry: 0,
+            open_orders_admin: None,
+            bids: Pubkey::new_unique(),
+            asks: Pubkey::new_unique(),
+            event_heap: Pubkey::new_unique(),
+            oracle_a: None,
+            oracle_b: None,
+            quote_lot_size: 1,
+            base_lot_size: 10_000_000,
+            taker_fee: 400,
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            market_base_vault: Pubkey::new_unique(),
+            market_quote_vault: Pubkey::new_unique(),
+        };
+        let mut stale = order(1_490_000, 500);
+        stale.timestamp = 100;
+        stale.time_in_force = 10;
+        let bids = [order(1_499_000, 100), order(1_498_000, 300), stale];
+        let asks = [order(1_501_000, 100), order(1_502_000, 300)];
+        let keys = vec![Pubkey::new_unique(), state.bids, state.asks];
+        let data = vec![state.pack(), OpenBookBookSide::pack(&bids), OpenBookBookSide::pack(&asks)];
+        (state, keys, data)
+    }
+
+    /// 测试 Market 与 BookSide 解析往返。
+    #[test]
+    fn test_market_and_book_side_roundtrip() {
+        let (state, _, data) = market_fixture();
+        assert_eq!(OpenBookMarketState::unpack(&data[0]), Some(state));
+        let bids = OpenBookBookSide::unpack(&data[1]).unwrap();
+        assert_eq!(bids.len(), 3);
+        assert!(bids.iter().any(|o| o.price_lots == 1_498_000 && o.quantity == 300));
+        assert_eq!(bids.iter().filter(|o| o.is_expired(110)).count(), 1);
+        assert_eq!(OpenBookBookSide::unpack(&OpenBookBookSide::pack(&[order(7, 1)])).unwrap(), vec![order(7, 1)]);
+        assert!(OpenBookBookSide::unpack(&OpenBookBookSide::pack(&[])).unwrap().is_empty());
+        assert!(OpenBookBookSide::unpack(&data[0]).is_none());
+    }
+
+    /// 测试 OpenBookAdapter 逐档报价与结算。
+    #[test]
+    fn test_openbook_market_quote() {
+        let adapter = OpenBookAdapter;
+        let (state, keys, mut data) = market_fixture();
+        let mut lamports = vec![0u64; 3];
+        let infos: Vec<AccountInfo> = keys.iter().zip(data.iter_mut()).zip(lamports.iter_mut())
+            .map(|((key, d), l)| AccountInfo::new(key, false, false, l, d, &OPENBOOK_V2_PROGRAM_ID, false, 0))
+            .collect();
+        let (sol, usdc) = (state.base_mint, state.quote_mint);
+        // 卖出 3 SOL：100 lots @1_499_000 + 200 lots @1_498_000，过期挂单不参与
+        let sell = adapter.quote_market(&trade(sol, usdc, 3_000_000_000, 0), &infos, 200).unwrap();
+        assert_eq!(sell.quote_lots, 100 * 1_499_000 + 200 * 1_498_000);
+        assert_eq!(sell.fee, 449_500_000 * 4 / 10_000);
+        assert_eq!(sell.amount_out, 449_500_000 - sell.fee);
+        assert_eq!(sell.worst_price_lots, 1_498_000);
+        // 买入：200 USDC 吃卖一档后进入卖二档
+        let buy = adapter.quote_market(&trade(usdc, sol, 200_000_000, 0), &infos, 200).unwrap();
+        assert_eq!(buy.base_lots, 133);
+        assert_eq!(buy.levels_consumed, 2);
+        let quote = DexQuote::from_curve(&buy.to_curve_quote(), adapter.name());
+        assert!(check_quote_min_out(&trade(usdc, sol, 200_000_000, 1_330_000_000), quote.clone()).is_ok());
+        assert!(check_quote_min_out(&trade(usdc, sol, 200_000_000, 1_330_000_001), quote).is_err());
+        // 深度不足、市场过期、代币对不匹配
+        assert!(adapter.quote_market(&trade(sol, usdc, 5_000_000_000, 0), &infos, 200).is_err());
+        assert!(adapter.quote_market(&trade(sol, usdc, 5_000_000_000, 0), &infos, 105).is_ok());
+        assert!(adapter.quote_market(&trade(sol, Pubkey::new_unique(), 1_000, 0), &infos, 200).is_err());
+        assert!(adapter.swap(&trade(sol, usdc, 100, 90)).is_err());
+    }
+
+    /// 测试 place_take_order 指令账户列表与指令数据。
+    #[test]
+    fn test_build_place_take_order_instruction() {
+        let (state, keys, _) = market_fixture();
+        let accounts = OpenBookTakeAccounts {
+            signer: Pubkey::new_unique(),
+            penalty_payer: Pubkey::new_unique(),
+            user_base_account: Pubkey::new_unique(),
+            user_quote_account: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
+        };
+        let args = PlaceTakeOrderArgs::immediate_or_cancel(&state, OrderSide::Bid, 200_000_000, 1_502_000, 16);
+        assert_eq!(args.max_quote_lots_including_fees, 200_000_000);
+        assert_eq!(args.max_base_lots, i64::MAX);
+        let ix = build_place_take_order_instruction(&keys[0], &state, &accounts, &args);
+        assert_eq!(ix.program_id, OPENBOOK_V2_PROGRAM_ID);
+        assert_eq!(ix.accounts.len(), 16);
+        assert!(ix.accounts[0].is_signer && ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, keys[0]);
+        assert_eq!(ix.accounts[4].pubkey, state.bids);
+        assert_eq!(ix.accounts[11].pubkey, OPENBOOK_V2_PROGRAM_ID); // 可选账户占位
+        assert!(!ix.accounts[15].is_signer);
+        assert_eq!(&ix.data[..8], &PLACE_TAKE_ORDER_DISCRIMINATOR);
+        assert_eq!(PlaceTakeOrderArgs::try_from_slice(&ix.data[8..]).unwrap(), args);
+    }
+
+    /// 测试按报价构造托管账户的 IOC 吃单指令。
+    #[test]
+    fn test_take_order_instruction_from_quote() {
+        let adapter = OpenBookAdapter;
+        let (state, keys, mut data) = market_fixture();
+        let mut lamports = vec![0u64; 3];
+        let infos: Vec<AccountInfo> = keys.iter().zip(data.iter_mut()).zip(lamports.iter_mut())
+            .map(|((key, d), l)| AccountInfo::new(key, false, false, l, d, &OPENBOOK_V2_PROGRAM_ID, false, 0))
+            .collect();
+        let user = DexSwapUserAccounts {
+            authority: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
+        };
+        let (sol, usdc) = (state.base_mint, state.quote_mint);
+        let ix = adapter.take_order_instruction(&trade(sol, usdc, 3_000_000_000, 0), &user, &infos, 200).unwrap();
+        assert_eq!(ix.accounts[0].pubkey, user.authority);
+        assert_eq!(ix.accounts[1].pubkey, user.payer);
+        assert_eq!(ix.accounts[9].pubkey, user.source); // 卖出 base：输入账户为 base 账户
+        assert_eq!(ix.accounts[10].pubkey, user.destination);
+        let args = PlaceTakeOrderArgs::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.side, OrderSide::Ask);
+        assert_eq!(args.price_lots, 1_498_000);
+        assert_eq!(args.max_base_lots, 300);
+        assert!(adapter.take_order_instruction(&trade(sol, usdc, 3_000_000_000, u64::MAX), &user, &infos, 200).is_err());
+    }
+
+    /// 测试 OpenBookAdapter 账户需求与类型。
+    #[test]
+    fn test_openbook_account_requirements() {
+        let adapter = OpenBookAdapter;
+        assert_eq!(adapter.adapter_type(), DexAdapterType::CLOB);
+        assert_eq!(adapter.account_requirements(DexOperation::Quote, &OPENBOOK_V2_PROGRAM_ID).len(), 3);
+        assert!(adapter.account_requirements(DexOperation::AddLiquidity, &OPENBOOK_V2_PROGRAM_ID).is_empty());
+    }
+}
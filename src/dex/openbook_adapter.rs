@@ -1,12 +1,13 @@
 //!
 //! OpenBook DEX Adapter Bridge Module
 //!
-//! 本模块为 OpenBook DEX 提供 Anchor 兼容的桥接适配器，实现统一接口、自动注册、CPI集成（预留），确保可插拔、合规、可维护。
+//! 本模块为 OpenBook DEX 提供 Anchor 兼容的桥接适配器，实现统一接口、自动注册，报价与成交委托 openbook.rs 中的 OpenBook v2 实现。
 
 use anchor_lang::prelude::*; // Anchor 预导入，包含 Result、Context 等
 use crate::core::adapter::AdapterTrait;
 use crate::dex::adapter::{DexAdapter, DexSwapResult};
 use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
+use crate::dex::openbook::OpenBookAdapter as RealOpenBookAdapter;
 // use crate::core::adapter: // 暂时注释掉:AdapterTrait; // 适配器元信息 trait，统一接口
 // 移除未找到的ctor属性
 // use ctor::ctor; // ctor 宏用于自动注册
@@ -33,16 +34,26 @@ fn auto_register_openbook_adapter() {
     factory.register(adapter);
 }
 
-/// 实现 DexAdapter trait，集成 OpenBook 链上 CPI 调用（待补充）。
+/// 实现 DexAdapter trait，委托给 openbook.rs 中的真实实现。
 impl DexAdapter for OpenBookAdapter {
-    fn swap(&self, params: &crate::core::types::TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // TODO: 实现实际的 swap 逻辑
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "openbook".to_string(),
-        })
+    fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
+        RealOpenBookAdapter.swap(params)
+    }
+
+    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
+        RealOpenBookAdapter.batch_swap(params)
+    }
+
+    fn configure(&self, params: &DexParams) -> anchor_lang::Result<()> {
+        RealOpenBookAdapter.configure(params)
+    }
+
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<crate::dex::adapter::DexQuote> {
+        RealOpenBookAdapter.get_quote(params, accounts)
+    }
+
+    fn account_requirements(&self, operation: crate::dex::adapter::DexOperation, pool_program: &Pubkey) -> Vec<crate::dex::adapter::DexAccountRequirement> {
+        RealOpenBookAdapter.account_requirements(operation, pool_program)
     }
     
     fn supported_assets(&self) -> Vec<String> {
@@ -54,6 +65,6 @@ impl DexAdapter for OpenBookAdapter {
     }
     
     fn adapter_type(&self) -> crate::dex::adapter::DexAdapterType {
-        crate::dex::adapter::DexAdapterType::AMM
+        RealOpenBookAdapter.adapter_type()
     }
-} 
\ No newline at end of file
+}
@@ -2,15 +2,297 @@
 //! Orca DEX Adapter Module
 //!
 //! 本模块实现 Orca DEX 适配器，提供与 Orca AMM 的链上集成接口，确保交易路由与聚合合规、可维护。
+//! 报价基于传入的 Orca token-swap 池账户（SwapV1）与金库 Token 账户，按池声明的曲线（常积/StableSwap）计算；
+//! Whirlpool 集中流动性池则解析 Whirlpool 与 tick array 账户，逐 tick 穿越得到精确输出与成交后价格。
 
 use crate::core::adapter::AdapterTrait;
 use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
-use crate::dex::adapter::{DexAdapter, DexSwapResult};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterError, DexOperation, DexQuote, DexSwapResult};
+use crate::dex::clmm::{self, ClmmPoolState, ClmmQuote, ClmmTick, ClmmTickSet, TickArrayTicks};
+use crate::dex::curve::{self, CurveType, FeeRate};
+use crate::dex::layout::{read_i128, read_i32, read_pubkey, read_u128, read_u16, read_u64, read_u8, unpack_token_account};
 use anchor_lang::prelude::*;
 
+/// Orca token-swap（v2）程序ID
+pub const ORCA_TOKEN_SWAP_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP");
+/// Orca Whirlpool 程序ID
+pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+/// Whirlpool 账户长度
+pub const WHIRLPOOL_LEN: usize = 653;
+/// Whirlpool TickArray 账户长度
+pub const WHIRLPOOL_TICK_ARRAY_LEN: usize = 9988;
+/// 每个 Whirlpool TickArray 包含的 tick 数
+pub const WHIRLPOOL_TICK_ARRAY_SIZE: i32 = 88;
+/// SwapV1 账户长度（含版本字节）
+pub const ORCA_SWAP_STATE_LEN: usize = 324;
+/// 常积曲线类型标识
+const CURVE_TYPE_CONSTANT_PRODUCT: u8 = 0;
+/// StableSwap 曲线类型标识
+const CURVE_TYPE_STABLE: u8 = 2;
+
+/// Orca token-swap 池状态（仅解析报价所需字段）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrcaSwapState {
+    pub is_initialized: bool,               // 是否已初始化
+    pub token_a: Pubkey,                    // A 金库
+    pub token_b: Pubkey,                    // B 金库
+    pub token_a_mint: Pubkey,               // A mint
+    pub token_b_mint: Pubkey,               // B mint
+    pub trade_fee_numerator: u64,           // LP 交易费分子
+    pub trade_fee_denominator: u64,         // LP 交易费分母
+    pub owner_trade_fee_numerator: u64,     // 协议交易费分子
+    pub owner_trade_fee_denominator: u64,   // 协议交易费分母
+    pub curve_type: u8,                     // 曲线类型（0=常积，2=Stable）
+    pub amp: u64,                           // Stable 曲线放大系数（曲线参数首 8 字节）
+}
+
+impl OrcaSwapState {
+    // SwapV1 字段偏移（首字节为版本号，与 spl-token-swap state.rs 布局一致）
+    const IS_INITIALIZED: usize = 1;
+    const TOKEN_A: usize = 35;
+    const TOKEN_B: usize = 67;
+    const TOKEN_A_MINT: usize = 131;
+    const TOKEN_B_MINT: usize = 163;
+    const TRADE_FEE_NUMERATOR: usize = 227;
+    const TRADE_FEE_DENOMINATOR: usize = 235;
+    const OWNER_TRADE_FEE_NUMERATOR: usize = 243;
+    const OWNER_TRADE_FEE_DENOMINATOR: usize = 251;
+    const CURVE_TYPE: usize = 291;
+    const CURVE_PARAMETERS: usize = 292;
+
+    /// 从账户字节反序列化
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < ORCA_SWAP_STATE_LEN {
+            return None;
+        }
+        Some(Self {
+            is_initialized: read_u8(data, Self::IS_INITIALIZED)? == 1,
+            token_a: read_pubkey(data, Self::TOKEN_A)?,
+            token_b: read_pubkey(data, Self::TOKEN_B)?,
+            token_a_mint: read_pubkey(data, Self::TOKEN_A_MINT)?,
+            token_b_mint: read_pubkey(data, Self::TOKEN_B_MINT)?,
+            trade_fee_numerator: read_u64(data, Self::TRADE_FEE_NUMERATOR)?,
+            trade_fee_denominator: read_u64(data, Self::TRADE_FEE_DENOMINATOR)?,
+            owner_trade_fee_numerator: read_u64(data, Self::OWNER_TRADE_FEE_NUMERATOR)?,
+            owner_trade_fee_denominator: read_u64(data, Self::OWNER_TRADE_FEE_DENOMINATOR)?,
+            curve_type: read_u8(data, Self::CURVE_TYPE)?,
+            amp: read_u64(data, Self::CURVE_PARAMETERS)?,
+        })
+    }
+
+    /// 序列化为账户字节（测试夹具与离线模拟使用，未解析字段填零）
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0u8; ORCA_SWAP_STATE_LEN];
+        data[0] = 1; // SwapVersion::SwapV1
+        data[Self::IS_INITIALIZED] = self.is_initialized as u8;
+        data[Self::TOKEN_A..Self::TOKEN_A + 32].copy_from_slice(self.token_a.as_ref());
+        data[Self::TOKEN_B..Self::TOKEN_B + 32].copy_from_slice(self.token_b.as_ref());
+        data[Self::TOKEN_A_MINT..Self::TOKEN_A_MINT + 32].copy_from_slice(self.token_a_mint.as_ref());
+        data[Self::TOKEN_B_MINT..Self::TOKEN_B_MINT + 32].copy_from_slice(self.token_b_mint.as_ref());
+        data[Self::TRADE_FEE_NUMERATOR..Self::TRADE_FEE_NUMERATOR + 8].copy_from_slice(&self.trade_fee_numerator.to_le_bytes());
+        data[Self::TRADE_FEE_DENOMINATOR..Self::TRADE_FEE_DENOMINATOR + 8].copy_from_slice(&self.trade_fee_denominator.to_le_bytes());
+        data[Self::OWNER_TRADE_FEE_NUMERATOR..Self::OWNER_TRADE_FEE_NUMERATOR + 8].copy_from_slice(&self.owner_trade_fee_numerator.to_le_bytes());
+        data[Self::OWNER_TRADE_FEE_DENOMINATOR..Self::OWNER_TRADE_FEE_DENOMINATOR + 8].copy_from_slice(&self.owner_trade_fee_denominator.to_le_bytes());
+        data[Self::CURVE_TYPE] = self.curve_type;
+        data[Self::CURVE_PARAMETERS..Self::CURVE_PARAMETERS + 8].copy_from_slice(&self.amp.to_le_bytes());
+        data
+    }
+
+    /// 池声明的曲线类型
+    pub fn curve(&self) -> Option<CurveType> {
+        match self.curve_type {
+            CURVE_TYPE_CONSTANT_PRODUCT => Some(CurveType::ConstantProduct),
+            CURVE_TYPE_STABLE => Some(CurveType::Stable { amp: self.amp }),
+            _ => None,
+        }
+    }
+
+    /// 按输入收取的总费率（LP 交易费 + 协议交易费）
+    pub fn fee_rate(&self) -> Option<FeeRate> {
+        FeeRate::new(self.trade_fee_numerator, self.trade_fee_denominator)
+            .combine(FeeRate::new(self.owner_trade_fee_numerator, self.owner_trade_fee_denominator))
+    }
+}
+
+/// Whirlpool 池状态（仅解析报价所需字段，偏移含 8 字节 Anchor 判别符）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WhirlpoolState {
+    pub tick_spacing: u16,       // tick 间距
+    pub fee_rate: u16,           // 交易费率（百万分之一）
+    pub liquidity: u128,         // 当前活跃流动性
+    pub sqrt_price: u128,        // 当前 sqrt price（Q64.64）
+    pub tick_current_index: i32, // 当前 tick
+    pub token_mint_a: Pubkey,    // A mint
+    pub token_mint_b: Pubkey,    // B mint
+}
+
+impl WhirlpoolState {
+    const TICK_SPACING: usize = 41;
+    const FEE_RATE: usize = 45;
+    const LIQUIDITY: usize = 49;
+    const SQRT_PRICE: usize = 65;
+    const TICK_CURRENT_INDEX: usize = 81;
+    const TOKEN_MINT_A: usize = 101;
+    const TOKEN_MINT_B: usize = 181;
+
+    /// 从账户字节反序列化
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < WHIRLPOOL_LEN {
+            return None;
+        }
+        Some(Self {
+            tick_spacing: read_u16(data, Self::TICK_SPACING)?,
+            fee_rate: read_u16(data, Self::FEE_RATE)?,
+            liquidity: read_u128(data, Self::LIQUIDITY)?,
+            sqrt_price: read_u128(data, Self::SQRT_PRICE)?,
+            tick_current_index: read_i32(data, Self::TICK_CURRENT_INDEX)?,
+            token_mint_a: read_pubkey(data, Self::TOKEN_MINT_A)?,
+            token_mint_b: read_pubkey(data, Self::TOKEN_MINT_B)?,
+        })
+    }
+
+    /// 序列化为账户字节（测试夹具与离线模拟使用，未解析字段填零）
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0u8; WHIRLPOOL_LEN];
+        data[Self::TICK_SPACING..Self::TICK_SPACING + 2].copy_from_slice(&self.tick_spacing.to_le_bytes());
+        data[Self::FEE_RATE..Self::FEE_RATE + 2].copy_from_slice(&self.fee_rate.to_le_bytes());
+        data[Self::LIQUIDITY..Self::LIQUIDITY + 16].copy_from_slice(&self.liquidity.to_le_bytes());
+        data[Self::SQRT_PRICE..Self::SQRT_PRICE + 16].copy_from_slice(&self.sqrt_price.to_le_bytes());
+        data[Self::TICK_CURRENT_INDEX..Self::TICK_CURRENT_INDEX + 4].copy_from_slice(&self.tick_current_index.to_le_bytes());
+        data[Self::TOKEN_MINT_A..Self::TOKEN_MINT_A + 32].copy_from_slice(self.token_mint_a.as_ref());
+        data[Self::TOKEN_MINT_B..Self::TOKEN_MINT_B + 32].copy_from_slice(self.token_mint_b.as_ref());
+        data
+    }
+}
+
+/// Whirlpool TickArray 解析（start_tick_index | 88 × Tick(113 字节) | whirlpool）
+pub struct WhirlpoolTickArray;
+
+impl WhirlpoolTickArray {
+    const START_TICK_INDEX: usize = 8;
+    const TICKS: usize = 12;
+    const TICK_LEN: usize = 113;
+    const WHIRLPOOL: usize = Self::TICKS + WHIRLPOOL_TICK_ARRAY_SIZE as usize * Self::TICK_LEN;
+
+    /// 解析并校验所属池，返回已初始化 tick
+    pub fn unpack(data: &[u8], whirlpool: &Pubkey, tick_spacing: u16) -> Option<TickArrayTicks> {
+        if data.len() < WHIRLPOOL_TICK_ARRAY_LEN || read_pubkey(data, Self::WHIRLPOOL)? != *whirlpool {
+            return None;
+        }
+        let start_tick_index = read_i32(data, Self::START_TICK_INDEX)?;
+        let spacing = tick_spacing as i32;
+        let mut ticks = Vec::new();
+        for i in 0..WHIRLPOOL_TICK_ARRAY_SIZE {
+            let offset = Self::TICKS + i as usize * Self::TICK_LEN;
+            if read_u8(data, offset)? == 1 {
+                ticks.push(ClmmTick {
+                    index: start_tick_index + i * spacing,
+                    liquidity_net: read_i128(data, offset + 1)?,
+                });
+            }
+        }
+        Some(TickArrayTicks { start_tick_index, span: WHIRLPOOL_TICK_ARRAY_SIZE * spacing, ticks })
+    }
+
+    /// 构造 TickArray 字节（测试夹具与离线模拟使用）
+    pub fn pack(whirlpool: &Pubkey, start_tick_index: i32, tick_spacing: u16, ticks: &[ClmmTick]) -> Vec<u8> {
+        let mut data = vec![0u8; WHIRLPOOL_TICK_ARRAY_LEN];
+        data[Self::START_TICK_INDEX..Self::START_TICK_INDEX + 4].copy_from_slice(&start_tick_index.to_le_bytes());
+        for tick in ticks {
+            let slot = ((tick.index - start_tick_index) / tick_spacing as i32) as usize;
+            let offset = Self::TICKS + slot * Self::TICK_LEN;
+            data[offset] = 1;
+            data[offset + 1..offset + 17].copy_from_slice(&tick.liquidity_net.to_le_bytes());
+        }
+        data[Self::WHIRLPOOL..Self::WHIRLPOOL + 32].copy_from_slice(whirlpool.as_ref());
+        data
+    }
+}
+
 /// Orca DEX 适配器结构体。
 pub struct OrcaAdapter;
 
+impl OrcaAdapter {
+    /// 基于池账户计算报价
+    /// - accounts[0]: SwapV1 池账户（归属 Orca token-swap 程序）
+    /// - accounts[1]: A 金库 Token 账户
+    /// - accounts[2]: B 金库 Token 账户
+    /// - Whirlpool 池（accounts[0] 归属 Whirlpool 程序）转由 quote_whirlpool 处理
+    pub fn quote_from_accounts(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        require!(params.amount_in > 0, OrcaError::InvalidAmount);
+        require!(params.from_token != params.to_token, OrcaError::InvalidTokens);
+        require!(!accounts.is_empty(), DexAdapterError::MissingPoolAccounts);
+        if *accounts[0].owner == ORCA_WHIRLPOOL_PROGRAM_ID {
+            let quote = self.quote_whirlpool(params, accounts)?;
+            return Ok(DexQuote::from_curve(&quote.to_curve_quote(), self.name()));
+        }
+        require!(accounts.len() >= 3, DexAdapterError::MissingPoolAccounts);
+        let (pool, vault_a, vault_b) = (&accounts[0], &accounts[1], &accounts[2]);
+        require_keys_eq!(*pool.owner, ORCA_TOKEN_SWAP_PROGRAM_ID, OrcaError::InvalidPoolAccount);
+        let state = {
+            let data = pool.try_borrow_data()?;
+            OrcaSwapState::unpack(&data).ok_or(OrcaError::InvalidPoolAccount)?
+        };
+        require!(state.is_initialized, OrcaError::InvalidPoolAccount);
+        require_keys_eq!(vault_a.key(), state.token_a, OrcaError::InvalidPoolAccount);
+        require_keys_eq!(vault_b.key(), state.token_b, OrcaError::InvalidPoolAccount);
+        let a = unpack_token_account(vault_a).ok_or(OrcaError::InvalidPoolAccount)?;
+        let b = unpack_token_account(vault_b).ok_or(OrcaError::InvalidPoolAccount)?;
+        let (reserve_in, reserve_out) = if params.from_token == state.token_a_mint && params.to_token == state.token_b_mint {
+            (a.amount, b.amount)
+        } else if params.from_token == state.token_b_mint && params.to_token == state.token_a_mint {
+            (b.amount, a.amount)
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let curve_type = state.curve().ok_or(OrcaError::Unsupported)?;
+        let fee = state.fee_rate().ok_or(DexAdapterError::MathOverflow)?;
+        let quote = curve::quote(curve_type, reserve_in, reserve_out, params.amount_in, fee)
+            .ok_or(OrcaError::InsufficientLiquidity)?;
+        Ok(DexQuote::from_curve(&quote, self.name()))
+    }
+
+    /// 基于 Whirlpool 集中流动性报价
+    /// - accounts[0]: Whirlpool 账户
+    /// - accounts[1..]: 覆盖成交方向的连续 TickArray 账户（通常 1~3 个）
+    pub fn quote_whirlpool(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<ClmmQuote> {
+        require!(accounts.len() >= 2, DexAdapterError::MissingPoolAccounts);
+        let pool = &accounts[0];
+        require_keys_eq!(*pool.owner, ORCA_WHIRLPOOL_PROGRAM_ID, OrcaError::InvalidWhirlpoolAccount);
+        let state = {
+            let data = pool.try_borrow_data()?;
+            WhirlpoolState::unpack(&data).ok_or(OrcaError::InvalidWhirlpoolAccount)?
+        };
+        require!(state.tick_spacing > 0, OrcaError::InvalidWhirlpoolAccount);
+        let a_to_b = if params.from_token == state.token_mint_a && params.to_token == state.token_mint_b {
+            true
+        } else if params.from_token == state.token_mint_b && params.to_token == state.token_mint_a {
+            false
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let mut arrays = Vec::with_capacity(accounts.len() - 1);
+        for info in &accounts[1..] {
+            require_keys_eq!(*info.owner, ORCA_WHIRLPOOL_PROGRAM_ID, DexAdapterError::InvalidTickArray);
+            let data = info.try_borrow_data()?;
+            arrays.push(
+                WhirlpoolTickArray::unpack(&data, pool.key, state.tick_spacing).ok_or(DexAdapterError::InvalidTickArray)?,
+            );
+        }
+        let tick_set = ClmmTickSet::from_arrays(arrays).ok_or(DexAdapterError::InvalidTickArray)?;
+        let pool_state = ClmmPoolState {
+            sqrt_price: state.sqrt_price,
+            tick_current: state.tick_current_index,
+            liquidity: state.liquidity,
+            fee_rate: state.fee_rate as u32,
+        };
+        let quote = clmm::swap_quote(&pool_state, &tick_set, params.amount_in, a_to_b)
+            .ok_or(DexAdapterError::InvalidTickArray)?;
+        require!(quote.amount_in == params.amount_in, DexAdapterError::InsufficientTickArrays);
+        Ok(quote)
+    }
+}
+
 impl AdapterTrait for OrcaAdapter {
     fn name(&self) -> &str { "orca" }
     fn version(&self) -> &str { "1.0.0" }
@@ -20,28 +302,43 @@ impl AdapterTrait for OrcaAdapter {
 }
 
 impl DexAdapter for OrcaAdapter {
+    /// 报价依赖池账户，未携带账户时无法给出真实成交：报价使用 quote_checked，成交使用 build_swap_instruction。
     fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // TODO: 实现实际的 swap 逻辑
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "orca".to_string(),
-        })
+        require!(params.amount_in > 0, OrcaError::InvalidAmount);
+        require!(params.from_token != params.to_token, OrcaError::InvalidTokens);
+        Err(DexAdapterError::MissingPoolAccounts.into())
     }
     
     fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
-        // TODO: 实现实际的批量 swap 逻辑
-        Ok(vec![])
+        params.trades.iter().map(|p| self.swap(p)).collect()
     }
     
-    fn configure(&self, params: &DexParams) -> anchor_lang::Result<()> {
-        // TODO: 实现配置逻辑
+    fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> {
         Ok(())
     }
+
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        self.quote_from_accounts(params, accounts)
+    }
+
+    /// 报价/成交所需账户：token-swap 池为池 + 双金库，Whirlpool 为池 + 连续 TickArray。
+    fn account_requirements(&self, operation: DexOperation, pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        match operation {
+            DexOperation::Quote | DexOperation::Swap if *pool_program == ORCA_WHIRLPOOL_PROGRAM_ID => vec![
+                DexAccountRequirement::readonly("whirlpool"),
+                DexAccountRequirement::readonly("tick_array").repeated(),
+            ],
+            DexOperation::Quote | DexOperation::Swap => vec![
+                DexAccountRequirement::readonly("swap_state"),
+                DexAccountRequirement::readonly("vault_a"),
+                DexAccountRequirement::readonly("vault_b"),
+            ],
+            _ => vec![],
+        }
+    }
     
     fn supported_assets(&self) -> Vec<String> {
-        vec!["SOL".to_string(), "USDC".to_string()]
+        vec!["SOL".to_string(), "USDC".to_string(), "ORCA".to_string()]
     }
     
     fn supported_markets(&self) -> Vec<String> {
@@ -82,6 +379,8 @@ pub enum OrcaError {
     #[msg("Insufficient liquidity")] InsufficientLiquidity,
     /// Whirlpool账户无效
     #[msg("Invalid Whirlpool account")] InvalidWhirlpoolAccount,
+    /// 池账户无效
+    #[msg("Invalid pool account")] InvalidPoolAccount,
     /// 操作不支持
     #[msg("Operation unsupported")] Unsupported,
 }
@@ -95,8 +394,74 @@ fn register_orca_adapter() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::layout::pack_token_account;
     use anchor_lang::prelude::Pubkey;
 
+    /// 构造交易参数。
+    fn trade(from: Pubkey, to: Pubkey, amount_in: u64, min_amount_out: u64) -> TradeParams {
+        TradeParams {
+            trade_type: "swap".to_string(),
+            from_token: from,
+            to_token: to,
+            amount_in,
+            min_amount_out,
+            dex_name: "orca".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        }
+    }
+
+    /// 池夹具：A/B 各 1_000_000_000，LP 0.25% + 协议 0.05% 交易费。
+    struct PoolFixture {
+        keys: [Pubkey; 3],
+        owners: [Pubkey; 3],
+        lamports: [u64; 3],
+        data: [Vec<u8>; 3],
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+    }
+
+    impl PoolFixture {
+        fn new(curve_type: u8, amp: u64) -> Self {
+            let (pool, vault_a, vault_b) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+            let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+            let state = OrcaSwapState {
+                is_initialized: true,
+                token_a: vault_a,
+                token_b: vault_b,
+                token_a_mint: mint_a,
+                token_b_mint: mint_b,
+                trade_fee_numerator: 25,
+                trade_fee_denominator: 10_000,
+                owner_trade_fee_numerator: 5,
+                owner_trade_fee_denominator: 10_000,
+                curve_type,
+                amp,
+            };
+            Self {
+                keys: [pool, vault_a, vault_b],
+                owners: [ORCA_TOKEN_SWAP_PROGRAM_ID, anchor_spl::token::ID, anchor_spl::token::ID],
+                lamports: [0; 3],
+                data: [
+                    state.pack(),
+                    pack_token_account(&mint_a, &Pubkey::default(), 1_000_000_000),
+                    pack_token_account(&mint_b, &Pubkey::default(), 1_000_000_000),
+                ],
+                mint_a,
+                mint_b,
+            }
+        }
+
+        fn infos(&mut self) -> Vec<AccountInfo<'_>> {
+            let mut infos = Vec::new();
+            for (((key, owner), lamports), data) in self.keys.iter().zip(self.owners.iter()).zip(self.lamports.iter_mut()).zip(self.data.iter_mut()) {
+                infos.push(AccountInfo::new(key, false, false, lamports, data, owner, false, 0));
+            }
+            infos
+        }
+    }
+
     /// 测试 OrcaAdapter 名称。
     #[test]
     fn test_orca_adapter_name() {
@@ -104,19 +469,112 @@ mod tests {
         assert_eq!(adapter.name(), "orca");
     }
 
-    /// 测试 OrcaAdapter swap 功能。
+    /// 测试常积池报价与 swap。
     #[test]
     fn test_orca_adapter_swap() {
         let adapter = OrcaAdapter;
-        let params = TradeParams {
-            from_token: Pubkey::default(),
-            to_token: Pubkey::new_unique(), // 使用不同的token
-            amount_in: 100,
-            min_amount_out: 90,
-            dex_name: "orca".to_string(),
+        let mut fixture = PoolFixture::new(CURVE_TYPE_CONSTANT_PRODUCT, 0);
+        let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+        let infos = fixture.infos();
+        let quote = adapter.get_quote(&trade(mint_a, mint_b, 10_000_000, 0), &infos).unwrap();
+        assert_eq!(quote.fee, 30_000);
+        assert_eq!(quote.amount_out, 9_871_580); // 1e9 * 9_970_000 / 1_009_970_000
+        assert_eq!(quote.price_impact_bps, 98);
+        assert!(adapter.quote_checked(&trade(mint_b, mint_a, 10_000_000, 9_800_000), &infos).is_ok());
+        assert!(adapter.quote_checked(&trade(mint_a, mint_b, 10_000_000, 9_900_000), &infos).is_err());
+        // 未携带池账户
+        assert!(adapter.swap(&trade(mint_a, mint_b, 100, 90)).is_err());
+    }
+
+    /// 测试 Stable 池报价显著优于同储备常积池。
+    #[test]
+    fn test_orca_stable_pool_quote() {
+        let adapter = OrcaAdapter;
+        let mut fixture = PoolFixture::new(CURVE_TYPE_STABLE, 100);
+        let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+        let infos = fixture.infos();
+        let quote = adapter.get_quote(&trade(mint_a, mint_b, 10_000_000, 0), &infos).unwrap();
+        assert_eq!(quote.amount_out, 9_969_015);
+        assert_eq!(quote.price_impact_bps, 0);
+    }
+
+    /// 测试未知曲线类型与伪造金库被拒绝。
+    #[test]
+    fn test_orca_rejects_invalid_pool() {
+        let adapter = OrcaAdapter;
+        let mut fixture = PoolFixture::new(1, 0);
+        let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+        assert!(adapter.get_quote(&trade(mint_a, mint_b, 100, 0), &fixture.infos()).is_err());
+
+        let mut fixture = PoolFixture::new(CURVE_TYPE_CONSTANT_PRODUCT, 0);
+        fixture.keys[1] = Pubkey::new_unique();
+        let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+        assert!(adapter.get_quote(&trade(mint_a, mint_b, 100, 0), &fixture.infos()).is_err());
+    }
+    
+    /// Whirlpool 夹具：sqrt price = 1.0、tick 0、流动性 1e12、费率 0.3%，tick_spacing 64，
+    /// [-64, 64) 外侧流动性降为 1e11。
+    fn whirlpool_fixture() -> (Vec<Pubkey>, Vec<Vec<u8>>, Pubkey, Pubkey) {
+        let whirlpool = Pubkey::new_unique();
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let state = WhirlpoolState {
+            tick_spacing: 64,
+            fee_rate: 3_000,
+            liquidity: 1_000_000_000_000,
+            sqrt_price: clmm::Q64,
+            tick_current_index: 0,
+            token_mint_a: mint_a,
+            token_mint_b: mint_b,
         };
-        let result = adapter.swap(&params);
-        assert!(result.is_ok());
+        let span = WHIRLPOOL_TICK_ARRAY_SIZE * 64;
+        let lower = WhirlpoolTickArray::pack(&whirlpool, -span, 64, &[ClmmTick { index: -64, liquidity_net: 900_000_000_000 }]);
+        let upper = WhirlpoolTickArray::pack(&whirlpool, 0, 64, &[ClmmTick { index: 64, liquidity_net: -900_000_000_000 }]);
+        let keys = vec![whirlpool, Pubkey::new_unique(), Pubkey::new_unique()];
+        (keys, vec![state.pack(), upper, lower], mint_a, mint_b)
+    }
+
+    /// 测试 Whirlpool 逐 tick 报价。
+    #[test]
+    fn test_orca_whirlpool_quote() {
+        let adapter = OrcaAdapter;
+        let (keys, mut data, mint_a, mint_b) = whirlpool_fixture();
+        let mut lamports = vec![0u64; 3];
+        let infos: Vec<AccountInfo> = keys.iter().zip(data.iter_mut()).zip(lamports.iter_mut())
+            .map(|((key, d), l)| AccountInfo::new(key, false, false, l, d, &ORCA_WHIRLPOOL_PROGRAM_ID, false, 0))
+            .collect();
+        // 小额成交停留在 [-64, 64) 区间内，近似 L=1e12 的常积池
+        let small = adapter.quote_whirlpool(&trade(mint_a, mint_b, 1_000_000, 0), &infos).unwrap();
+        assert_eq!(small.fee, 3_000);
+        assert!(small.amount_out >= 996_998 && small.amount_out <= 996_999);
+        assert_eq!(small.ticks_crossed, 0);
+        // 大额成交穿越 tick -64 后深度降为 1/10，价格冲击远大于单一流动性估算
+        let large = adapter.quote_whirlpool(&trade(mint_a, mint_b, 10_000_000_000, 0), &infos).unwrap();
+        assert_eq!(large.ticks_crossed, 1);
+        assert!(large.tick_after < -64);
+        assert!(large.price_after().unwrap() < 1_000_000);
+        let single_range = curve::constant_product_quote(1_000_000_000_000, 1_000_000_000_000, 10_000_000_000, FeeRate::new(3_000, 1_000_000)).unwrap();
+        assert!(large.amount_out < single_range.amount_out);
+        // 通过通用接口报价与结算
+        let quote = adapter.get_quote(&trade(mint_b, mint_a, 1_000_000, 0), &infos).unwrap();
+        assert!(quote.amount_out >= 996_998);
+        assert!(adapter.quote_checked(&trade(mint_b, mint_a, 1_000_000, 997_500), &infos).is_err());
+    }
+
+    /// 测试 tick array 覆盖不足或所属池不匹配时拒绝报价。
+    #[test]
+    fn test_orca_whirlpool_rejects_insufficient_tick_arrays() {
+        let adapter = OrcaAdapter;
+        let (keys, mut data, mint_a, mint_b) = whirlpool_fixture();
+        data[2] = WhirlpoolTickArray::pack(&Pubkey::new_unique(), -WHIRLPOOL_TICK_ARRAY_SIZE * 64, 64, &[]);
+        let mut lamports = vec![0u64; 3];
+        let infos: Vec<AccountInfo> = keys.iter().zip(data.iter_mut()).zip(lamports.iter_mut())
+            .map(|((key, d), l)| AccountInfo::new(key, false, false, l, d, &ORCA_WHIRLPOOL_PROGRAM_ID, false, 0))
+            .collect();
+        // 下侧 tick array 属于其他池
+        assert!(adapter.quote_whirlpool(&trade(mint_a, mint_b, 1_000, 0), &infos).is_err());
+        // 仅传入上侧 tick array 时，A→B 成交量超出覆盖范围
+        assert!(adapter.quote_whirlpool(&trade(mint_a, mint_b, 1_000_000_000_000, 0), &infos[..2]).is_err());
+        assert!(adapter.quote_whirlpool(&trade(mint_b, mint_a, 1_000, 0), &infos[..2]).is_ok());
     }
     
     /// 测试 OrcaAdapter 支持的资产。
@@ -128,4 +586,4 @@ mod tests {
         assert!(assets.contains(&"USDC".to_string()));
         assert!(assets.contains(&"ORCA".to_string()));
     }
-} 
\ No newline at end of file
+}
@@ -2,10 +2,11 @@
 //! Orca DEX Adapter Bridge Module
 //!
 //! 本模块为 Orca DEX 提供 Anchor 兼容的桥接适配器，实现统一接口、自动注册、CPI集成（预留），确保可插拔、合规、可维护。
-
 use anchor_lang::prelude::*;
-// use crate::core::types: // 暂时注释掉:{AssetType, DexAdapterTrait};
-use crate::dex::traits::*;
+use crate::core::adapter::AdapterTrait;
+use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterType, DexOperation, DexQuote, DexSwapResult};
+use crate::dex::orca::OrcaAdapter as RealOrcaAdapter;
 
 /// OrcaAdapter结构体，代表Orca DEX/AMM适配器
 pub struct OrcaAdapter {
@@ -25,23 +26,36 @@ impl OrcaAdapter {
     }
 }
 
+/// 实现 AdapterTrait，提供适配器元信息。
+impl AdapterTrait for OrcaAdapter {
+    fn name(&self) -> &str { "orca_adapter" }
+    fn version(&self) -> &str { "1.0.0" }
+    fn is_available(&self) -> bool { true }
+    fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+}
+
+/// 实现统一 DexAdapter trait，报价与成交委托给 orca.rs 中的真实实现。
 impl DexAdapter for OrcaAdapter {
-    /// 执行swap（mock实现，实际应调用Orca CPI）
-    fn swap(&self, _ctx: Context<Swap>, params: SwapParams) -> anchor_lang::Result<SwapResult> {
-        let amount_out = params.amount_in; // 假定1:1兑换
-        Ok(SwapResult { amount_out, fee: 0 })
+    /// 执行swap
+    fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
+        RealOrcaAdapter.swap(params)
     }
-    /// 添加流动性（mock实现）
-    fn add_liquidity(&self, _ctx: Context<AddLiquidity>, params: AddLiquidityParams) -> anchor_lang::Result<u64> {
-        Ok(params.amount)
+    /// 批量swap
+    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
+        RealOrcaAdapter.batch_swap(params)
     }
-    /// 移除流动性（mock实现）
-    fn remove_liquidity(&self, _ctx: Context<RemoveLiquidity>, params: RemoveLiquidityParams) -> anchor_lang::Result<u64> {
-        Ok(params.amount)
+    /// 配置适配器
+    fn configure(&self, params: &DexParams) -> anchor_lang::Result<()> {
+        RealOrcaAdapter.configure(params)
     }
-    /// 获取报价（mock实现）
-    fn get_quote(&self, _ctx: Context<GetQuote>, params: QuoteParams) -> anchor_lang::Result<QuoteResult> {
-        Ok(QuoteResult { amount_out: params.amount_in, fee: 0 })
+    /// 获取报价
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        RealOrcaAdapter.get_quote(params, accounts)
+    }
+    /// 账户需求
+    fn account_requirements(&self, operation: DexOperation, pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        RealOrcaAdapter.account_requirements(operation, pool_program)
     }
     /// 查询支持的资产类型
     fn supported_assets(&self) -> Vec<String> {
@@ -62,4 +76,4 @@ impl DexAdapter for OrcaAdapter {
 pub enum ErrorCode {
     #[msg("不支持的资产类型")] 
     UnsupportedAsset,
-} 
\ No newline at end of file
+}
@@ -0,0 +1,378 @@
+//!
+//! Order Book Depth Math Module
+//!
+//! 本模块实现中心限价订单簿（CLOB）吃单报价数学：以 lot 为单位的价格档位、lot 与原子数量换算、
+//! 沿盘口逐档吃单并按 taker 费率（以 quote 计）扣费，输出成交数量、手续费、触及档位与价格冲击，
+//! 供 Phoenix、OpenBook v2 等订单簿适配器基于链上市场账户报价，并为路由图提供真实深度。
+//! 取整方向偏向做市方：买入时 quote 成本向上取整，卖出时 quote 所得向下取整。
+
+use anchor_lang::prelude::*;
+use crate::dex::curve::{self, mul_div, mul_div_ceil, CurveQuote, FeeRate};
+use std::collections::BTreeMap;
+
+/// 吃单方向（与 Phoenix / OpenBook v2 的 Side 编码一致：Bid=0、Ask=1）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum OrderSide {
+    /// 买入 base（吃卖盘，输入 quote）
+    Bid,
+    /// 卖出 base（吃买盘，输入 base）
+    Ask,
+}
+
+/// 价格档位（同价挂单聚合）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct BookLevel {
+    pub price_lots: u64, // 档位价格（市场原生价格单位）
+    pub size_lots: u64,  // 档位数量（base lots）
+}
+
+/// 市场 lot 换算参数
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct OrderBookMarket {
+    pub base_lot_size: u64,     // 每 base lot 的 base 原子数量
+    pub quote_lot_size: u64,    // 每 quote lot 的 quote 原子数量
+    pub price_numerator: u64,   // 价格换算分子：每 base lot 的 quote lots = price * num / den
+    pub price_denominator: u64, // 价格换算分母
+    pub taker_fee: FeeRate,     // taker 手续费率（按成交 quote lots 收取）
+}
+
+impl OrderBookMarket {
+    /// base_lots 在 price 档位成交所得 quote lots（向下取整，卖出方所得）
+    pub fn quote_lots_floor(&self, base_lots: u64, price: u64) -> Option<u64> {
+        let value = mul_div(base_lots as u128 * price as u128, self.price_numerator as u128, self.price_denominator as u128)?;
+        u64::try_from(value).ok()
+    }
+
+    /// base_lots 在 price 档位成交应付 quote lots（向上取整，买入方成本）
+    pub fn quote_lots_ceil(&self, base_lots: u64, price: u64) -> Option<u64> {
+        let value = mul_div_ceil(base_lots as u128 * price as u128, self.price_numerator as u128, self.price_denominator as u128)?;
+        u64::try_from(value).ok()
+    }
+
+    /// quote_lots 在 price 档位最多可买 base lots（向下取整）
+    pub fn base_lots_for(&self, quote_lots: u64, price: u64) -> Option<u64> {
+        let unit = price as u128 * self.price_numerator as u128;
+        if unit == 0 {
+            return None;
+        }
+        let lots = mul_div(quote_lots as u128, self.price_denominator as u128, unit)?;
+        Some(u64::try_from(lots).unwrap_or(u64::MAX))
+    }
+
+    /// 成交 quote lots 对应的 taker 手续费（quote lots，向上取整）
+    pub fn taker_fee_on(&self, quote_lots: u64) -> Option<u64> {
+        self.taker_fee.fee_on(quote_lots)
+    }
+
+    /// taker 费率换算为基点（向上取整），供路由图费用信息使用
+    pub fn taker_fee_bps(&self) -> u32 {
+        if self.taker_fee.denominator == 0 {
+            return 0;
+        }
+        mul_div_ceil(self.taker_fee.numerator as u128, 10_000, self.taker_fee.denominator as u128)
+            .and_then(|bps| u32::try_from(bps).ok())
+            .unwrap_or(u32::MAX)
+    }
+}
+
+/// 订单簿快照：买盘价格降序、卖盘价格升序
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct OrderBook {
+    pub market: OrderBookMarket, // 市场换算参数
+    pub bids: Vec<BookLevel>,    // 买盘档位
+    pub asks: Vec<BookLevel>,    // 卖盘档位
+}
+
+impl OrderBook {
+    /// 由逐笔挂单 (price, size_lots) 聚合同价档位并排序
+    pub fn from_orders(
+        market: OrderBookMarket,
+        bids: impl IntoIterator<Item = (u64, u64)>,
+        asks: impl IntoIterator<Item = (u64, u64)>,
+    ) -> Self {
+        let aggregate = |orders: &mut dyn Iterator<Item = (u64, u64)>| {
+            let mut levels: BTreeMap<u64, u64> = BTreeMap::new();
+            for (price, size) in orders.filter(|(price, size)| *price > 0 && *size > 0) {
+                let entry = levels.entry(price).or_insert(0);
+                *entry = entry.saturating_add(size);
+            }
+            levels
+        };
+        let bids = aggregate(&mut bids.into_iter())
+            .into_iter()
+            .rev()
+            .map(|(price_lots, size_lots)| BookLevel { price_lots, size_lots })
+            .collect();
+        let asks = aggregate(&mut asks.into_iter())
+            .into_iter()
+            .map(|(price_lots, size_lots)| BookLevel { price_lots, size_lots })
+            .collect();
+        Self { market, bids, asks }
+    }
+
+    /// 吃单方向对应的对手盘档位
+    pub fn levels(&self, side: OrderSide) -> &[BookLevel] {
+        match side {
+            OrderSide::Bid => &self.asks,
+            OrderSide::Ask => &self.bids,
+        }
+    }
+
+    /// 最优买价
+    pub fn best_bid(&self) -> Option<u64> {
+        self.bids.first().map(|level| level.price_lots)
+    }
+
+    /// 最优卖价
+    pub fn best_ask(&self) -> Option<u64> {
+        self.asks.first().map(|level| level.price_lots)
+    }
+
+    /// 买卖价差（基点，相对卖一价）
+    pub fn spread_bps(&self) -> Option<u64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        if ask == 0 || bid >= ask {
+            return Some(0);
+        }
+        Some(((ask - bid) as u128 * 10_000 / ask as u128) as u64)
+    }
+}
+
+/// 吃单成交结果
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct BookFill {
+    pub side: OrderSide,       // 吃单方向
+    pub amount_in: u64,        // 实际消耗输入（原子数量，买入时含手续费）
+    pub amount_out: u64,       // 输出（原子数量，卖出时已扣手续费）
+    pub fee: u64,              // taker 手续费（quote 原子数量）
+    pub base_lots: u64,        // 成交 base lots
+    pub quote_lots: u64,       // 成交 quote lots（不含手续费）
+    pub levels_consumed: u32,  // 触及档位数
+    pub worst_price_lots: u64, // 最差成交档位价格（可作为 IOC 限价）
+    pub price_impact_bps: u64, // 价格冲击（基点，相对最优档位）
+    pub complete: bool,        // 输入是否在盘口与限价内全部成交（不足一个 lot 的零头除外）
+}
+
+impl BookFill {
+    /// 转换为通用报价结果（手续费以 quote 原子数量计）
+    pub fn to_curve_quote(&self) -> CurveQuote {
+        CurveQuote {
+            amount_in: self.amount_in,
+            amount_out: self.amount_out,
+            fee: self.fee,
+            price_impact_bps: self.price_impact_bps,
+        }
+    }
+}
+
+/// 沿对手盘逐档吃单报价
+/// - Bid：amount_in 为 quote 原子数量，预留 taker 费后按卖盘升序买入 base
+/// - Ask：amount_in 为 base 原子数量，按买盘降序卖出，所得 quote 扣除 taker 费
+/// - limit_price：IOC 限价（Bid 不高于、Ask 不低于），None 表示吃至盘口耗尽
+pub fn quote_fill(book: &OrderBook, side: OrderSide, amount_in: u64, limit_price: Option<u64>) -> Option<BookFill> {
+    let market = &book.market;
+    if market.base_lot_size == 0 || market.quote_lot_size == 0 || market.price_denominator == 0 {
+        return None;
+    }
+    match side {
+        OrderSide::Bid => quote_buy(book, amount_in, limit_price),
+        OrderSide::Ask => quote_sell(book, amount_in, limit_price),
+    }
+}
+
+/// 买入 base：quote 预算先扣除手续费上限，再按卖盘逐档成交
+fn quote_buy(book: &OrderBook, amount_in: u64, limit_price: Option<u64>) -> Option<BookFill> {
+    let market = &book.market;
+    let budget = amount_in / market.quote_lot_size;
+    let fee = market.taker_fee;
+    let max_matched = if fee.numerator == 0 || fee.denominator == 0 {
+        budget
+    } else {
+        let total = fee.denominator as u128 + fee.numerator as u128;
+        u64::try_from(mul_div(budget as u128, fee.denominator as u128, total)?).ok()?
+    };
+    let mut remaining = max_matched;
+    let (mut base_lots, mut quote_lots, mut levels_consumed, mut worst_price) = (0u64, 0u64, 0u32, 0u64);
+    let mut complete = false; // 未因预算用尽而停止即视为盘口耗尽或触及限价
+    for level in &book.asks {
+        if remaining == 0 {
+            complete = true;
+            break;
+        }
+        if limit_price.map_or(false, |limit| level.price_lots > limit) {
+            break;
+        }
+        let full_cost = market.quote_lots_ceil(level.size_lots, level.price_lots)?;
+        let (lots, cost) = if full_cost <= remaining {
+            (level.size_lots, full_cost)
+        } else {
+            let mut lots = market.base_lots_for(remaining, level.price_lots)?.min(level.size_lots);
+            let mut cost = market.quote_lots_ceil(lots, level.price_lots)?;
+            if cost > remaining && lots > 0 {
+                lots -= 1;
+                cost = market.quote_lots_ceil(lots, level.price_lots)?;
+            }
+            (lots, cost)
+        };
+        if lots == 0 {
+            complete = true; // 剩余预算不足一个 lot
+            break;
+        }
+        base_lots = base_lots.checked_add(lots)?;
+        quote_lots = quote_lots.checked_add(cost)?;
+        remaining -= cost;
+        levels_consumed += 1;
+        worst_price = level.price_lots;
+        if lots < level.size_lots {
+            complete = true;
+            break;
+        }
+    }
+    complete |= remaining == 0;
+    let fee_lots = market.taker_fee_on(quote_lots)?.min(budget - quote_lots);
+    let spot_base = match book.best_ask() {
+        Some(best) => market.base_lots_for(quote_lots, best)?,
+        None => 0,
+    };
+    Some(BookFill {
+        side: OrderSide::Bid,
+        amount_in: quote_lots.checked_add(fee_lots)?.checked_mul(market.quote_lot_size)?,
+        amount_out: base_lots.checked_mul(market.base_lot_size)?,
+        fee: fee_lots.checked_mul(market.quote_lot_size)?,
+        base_lots,
+        quote_lots,
+        levels_consumed,
+        worst_price_lots: worst_price,
+        price_impact_bps: curve::price_impact_bps(spot_base as u128, base_lots as u128),
+        complete,
+    })
+}
+
+/// 卖出 base：按买盘逐档成交，所得 quote 扣除 taker 费
+fn quote_sell(book: &OrderBook, amount_in: u64, limit_price: Option<u64>) -> Option<BookFill> {
+    let market = &book.market;
+    let mut remaining = amount_in / market.base_lot_size;
+    let (mut base_lots, mut quote_lots, mut levels_consumed, mut worst_price) = (0u64, 0u64, 0u32, 0u64);
+    let mut complete = true;
+    for level in &book.bids {
+        if remaining == 0 {
+            break;
+        }
+        if limit_price.map_or(false, |limit| level.price_lots < limit) {
+            break;
+        }
+        let lots = remaining.min(level.size_lots);
+        base_lots = base_lots.checked_add(lots)?;
+        quote_lots = quote_lots.checked_add(market.quote_lots_floor(lots, level.price_lots)?)?;
+        remaining -= lots;
+        levels_consumed += 1;
+        worst_price = level.price_lots;
+    }
+    if remaining > 0 {
+        complete = false; // 买盘耗尽或触及限价
+    }
+    let fee_lots = market.taker_fee_on(quote_lots)?.min(quote_lots);
+    let spot_quote = match book.best_bid() {
+        Some(best) => market.quote_lots_floor(base_lots, best)?,
+        None => 0,
+    };
+    Some(BookFill {
+        side: OrderSide::Ask,
+        amount_in: base_lots.checked_mul(market.base_lot_size)?,
+        amount_out: (quote_lots - fee_lots).checked_mul(market.quote_lot_size)?,
+        fee: fee_lots.checked_mul(market.quote_lot_size)?,
+        base_lots,
+        quote_lots,
+        levels_consumed,
+        worst_price_lots: worst_price,
+        price_impact_bps: curve::price_impact_bps(spot_quote as u128, quote_lots as u128),
+        complete,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 价格即 quote lots / base lot、0.1% taker 费的市场
+    fn market() -> OrderBookMarket {
+        OrderBookMarket {
+            base_lot_size: 1_000,
+            quote_lot_size: 10,
+            price_numerator: 1,
+            price_denominator: 1,
+            taker_fee: FeeRate::new(10, 10_000),
+        }
+    }
+
+    fn book() -> OrderBook {
+        OrderBook::from_orders(
+            market(),
+            vec![(99, 50), (98, 100), (99, 50), (97, 1_000)],
+            vec![(101, 100), (102, 100), (105, 1_000)],
+        )
+    }
+
+    /// 测试挂单聚合与排序。
+    #[test]
+    fn test_from_orders_aggregates_levels() {
+        let book = book();
+        assert_eq!(book.bids[0], BookLevel { price_lots: 99, size_lots: 100 });
+        assert_eq!(book.bids.len(), 3);
+        assert_eq!(book.best_ask(), Some(101));
+        assert_eq!(book.spread_bps(), Some(198));
+        assert_eq!(book.market.taker_fee_bps(), 10);
+    }
+
+    /// 测试买入逐档吃单与手续费预留。
+    #[test]
+    fn test_quote_buy_walks_asks() {
+        let book = book();
+        // 预算 30_000 quote lots：可成交上限 29_970，吃完 101、102 两档后在 105 档买入 92 lots
+        let fill = quote_fill(&book, OrderSide::Bid, 300_000, None).unwrap();
+        assert_eq!(fill.base_lots, 292);
+        assert_eq!(fill.quote_lots, 10_100 + 10_200 + 92 * 105);
+        assert_eq!(fill.levels_consumed, 3);
+        assert_eq!(fill.worst_price_lots, 105);
+        assert_eq!(fill.fee, 30 * 10);
+        assert!(fill.amount_in <= 300_000);
+        assert_eq!(fill.amount_out, 292_000);
+        assert!(fill.price_impact_bps > 0);
+        assert!(fill.complete);
+        // 限价 102：只吃两档，未全部成交
+        let limited = quote_fill(&book, OrderSide::Bid, 300_000, Some(102)).unwrap();
+        assert_eq!(limited.base_lots, 200);
+        assert!(!limited.complete);
+    }
+
+    /// 测试卖出逐档吃单与手续费扣除。
+    #[test]
+    fn test_quote_sell_walks_bids() {
+        let book = book();
+        let fill = quote_fill(&book, OrderSide::Ask, 150_500, None).unwrap();
+        assert_eq!(fill.base_lots, 150);
+        assert_eq!(fill.amount_in, 150_000); // 不足一个 lot 的零头不成交
+        assert_eq!(fill.quote_lots, 100 * 99 + 50 * 98);
+        assert_eq!(fill.fee, 15 * 10);
+        assert_eq!(fill.amount_out, (14_800 - 15) * 10);
+        assert_eq!(fill.levels_consumed, 2);
+        assert!(fill.complete);
+        // 超出买盘总量
+        let exhausted = quote_fill(&book, OrderSide::Ask, 10_000_000, None).unwrap();
+        assert_eq!(exhausted.base_lots, 1_200);
+        assert!(!exhausted.complete);
+    }
+
+    /// 测试非整数价格换算（Phoenix tick 价格）向做市方取整。
+    #[test]
+    fn test_fractional_price_rounding() {
+        let market = OrderBookMarket { price_numerator: 3, price_denominator: 2, ..market() };
+        assert_eq!(market.quote_lots_floor(3, 1), Some(4));
+        assert_eq!(market.quote_lots_ceil(3, 1), Some(5));
+        assert_eq!(market.base_lots_for(5, 1), Some(3));
+        let empty = OrderBook::from_orders(market, vec![], vec![]);
+        let fill = quote_fill(&empty, OrderSide::Bid, 1_000, None).unwrap();
+        assert_eq!(fill.base_lots, 0);
+        assert!(!fill.complete);
+    }
+}
@@ -1,86 +1,448 @@
 //!
 //! Phoenix DEX Adapter Module
 //!
-//! 本模块实现 Phoenix DEX 适配器，提供与 Phoenix AMM 的链上集成接口，确保交易路由与聚合合规、可维护。
+//! 本模块实现 Phoenix DEX 适配器，提供与 Phoenix 订单簿的链上集成接口，确保交易路由与聚合合规、可维护。
+//! 报价基于传入的市场账户：解析 MarketHeader 与 FIFOMarket 中的买卖红黑树，聚合价格档位后按 taker 费率逐档吃单；
+//! 成交路径按 Phoenix Swap 指令构造 ImmediateOrCancel 订单的真实 CPI 账户列表与指令数据。
 
-// use crate::core::adapter: // 暂时注释掉:AdapterTrait;
-// use crate::core::types: // 暂时注释掉:{TradeParams, BatchTradeParams, DexParams};
-use crate::dex::adapter::{DexAdapter, DexSwapResult, DexAdapterType};
+use crate::core::adapter::AdapterTrait;
+use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterError, DexAdapterType, DexOperation, DexQuote, DexSwapResult, DexSwapUserAccounts};
+use crate::dex::curve::FeeRate;
+use crate::dex::layout::{read_pubkey, read_u32, read_u64};
+use crate::dex::orderbook::{self, BookFill, OrderBook, OrderBookMarket, OrderSide};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+
+/// Phoenix 程序ID
+pub const PHOENIX_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+/// MarketHeader 判别符（sha256("phoenix::program::accounts::MarketHeader")[..8]）
+pub const MARKET_HEADER_DISCRIMINANT: [u8; 8] = [85, 153, 127, 98, 215, 115, 0, 175];
+/// MarketHeader 长度
+pub const MARKET_HEADER_LEN: usize = 576;
+/// Swap 指令标签
+const SWAP_INSTRUCTION_TAG: u8 = 0;
+/// OrderPacket::ImmediateOrCancel 变体标签
+const IMMEDIATE_OR_CANCEL_TAG: u8 = 2;
+/// 市场可吃单状态（MarketStatus::Active）
+const MARKET_STATUS_ACTIVE: u64 = 1;
+/// 基点分母（taker_fee_bps）
+const BPS_DENOMINATOR: u64 = 10_000;
+/// 红黑树头部长度（root + padding + 分配器 size/bump_index/free_list_head）
+const TREE_HEADER_LEN: usize = 32;
+/// 红黑树节点长度（4 个 u32 寄存器 + FIFOOrderId + FIFORestingOrder）
+const TREE_NODE_LEN: usize = 64;
+/// 红黑树哨兵节点
+const TREE_SENTINEL: u32 = 0;
+
+/// Phoenix 挂单（树节点中的报价相关字段）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhoenixOrder {
+    pub price_in_ticks: u64,                       // 价格（tick）
+    pub num_base_lots: u64,                        // 剩余数量（base lots）
+    pub last_valid_slot: u64,                      // 过期 slot（0=不过期）
+    pub last_valid_unix_timestamp_in_seconds: u64, // 过期时间戳（0=不过期）
+}
+
+impl PhoenixOrder {
+    /// 按当前 slot 与时间戳判断是否已过期
+    pub fn is_expired(&self, slot: u64, unix_timestamp: i64) -> bool {
+        (self.last_valid_slot != 0 && slot > self.last_valid_slot)
+            || (self.last_valid_unix_timestamp_in_seconds != 0
+                && unix_timestamp > self.last_valid_unix_timestamp_in_seconds as i64)
+    }
+}
+
+/// Phoenix 市场状态（MarketHeader + FIFOMarket 头部字段，偏移以账户起始计）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhoenixMarketState {
+    pub status: u64,                                // 市场状态（1=Active）
+    pub bids_size: u64,                             // 买盘树容量
+    pub asks_size: u64,                             // 卖盘树容量
+    pub num_seats: u64,                             // 席位树容量
+    pub base_mint: Pubkey,                          // base mint
+    pub base_vault: Pubkey,                         // base 金库
+    pub base_lot_size: u64,                         // 每 base lot 的 base 原子数量
+    pub quote_mint: Pubkey,                         // quote mint
+    pub quote_vault: Pubkey,                        // quote 金库
+    pub quote_lot_size: u64,                        // 每 quote lot 的 quote 原子数量
+    pub tick_size_in_quote_atoms_per_base_unit: u64, // tick 大小（quote 原子 / base 单位）
+    pub base_lots_per_base_unit: u64,               // 每 base 单位的 base lots
+    pub tick_size_in_quote_lots_per_base_unit: u64, // tick 大小（quote lots / base 单位）
+    pub taker_fee_bps: u64,                         // taker 费率（基点）
+}
+
+impl PhoenixMarketState {
+    const STATUS: usize = 8;
+    const BIDS_SIZE: usize = 16;
+    const ASKS_SIZE: usize = 24;
+    const NUM_SEATS: usize = 32;
+    const BASE_MINT: usize = 48;
+    const BASE_VAULT: usize = 80;
+    const BASE_LOT_SIZE: usize = 112;
+    const QUOTE_MINT: usize = 128;
+    const QUOTE_VAULT: usize = 160;
+    const QUOTE_LOT_SIZE: usize = 192;
+    const TICK_SIZE_IN_QUOTE_ATOMS: usize = 200;
+    const BASE_LOTS_PER_BASE_UNIT: usize = MARKET_HEADER_LEN + 256;
+    const TICK_SIZE_IN_QUOTE_LOTS: usize = MARKET_HEADER_LEN + 264;
+    const TAKER_FEE_BPS: usize = MARKET_HEADER_LEN + 280;
+    const BIDS: usize = MARKET_HEADER_LEN + 304;
+
+    /// 从市场账户数据解析（校验判别符与买卖树覆盖范围）
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.get(..8)? != MARKET_HEADER_DISCRIMINANT {
+            return None;
+        }
+        let state = Self {
+            status: read_u64(data, Self::STATUS)?,
+            bids_size: read_u64(data, Self::BIDS_SIZE)?,
+            asks_size: read_u64(data, Self::ASKS_SIZE)?,
+            num_seats: read_u64(data, Self::NUM_SEATS)?,
+            base_mint: read_pubkey(data, Self::BASE_MINT)?,
+            base_vault: read_pubkey(data, Self::BASE_VAULT)?,
+            base_lot_size: read_u64(data, Self::BASE_LOT_SIZE)?,
+            quote_mint: read_pubkey(data, Self::QUOTE_MINT)?,
+            quote_vault: read_pubkey(data, Self::QUOTE_VAULT)?,
+            quote_lot_size: read_u64(data, Self::QUOTE_LOT_SIZE)?,
+            tick_size_in_quote_atoms_per_base_unit: read_u64(data, Self::TICK_SIZE_IN_QUOTE_ATOMS)?,
+            base_lots_per_base_unit: read_u64(data, Self::BASE_LOTS_PER_BASE_UNIT)?,
+            tick_size_in_quote_lots_per_base_unit: read_u64(data, Self::TICK_SIZE_IN_QUOTE_LOTS)?,
+            taker_fee_bps: read_u64(data, Self::TAKER_FEE_BPS)?,
+        };
+        if data.len() < state.asks_offset()?.checked_add(tree_len(state.asks_size)?)? {
+            return None;
+        }
+        Some(state)
+    }
+
+    /// 序列化为市场账户数据（买卖树按右链挂接，席位树省略，仅供测试与离线模拟）
+    pub fn pack(&self, bids: &[PhoenixOrder], asks: &[PhoenixOrder]) -> Vec<u8> {
+        let asks_offset = self.asks_offset().expect("tree size");
+        let mut data = vec![0u8; asks_offset + tree_len(self.asks_size).expect("tree size")];
+        data[..8].copy_from_slice(&MARKET_HEADER_DISCRIMINANT);
+        let mut put = |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(Self::STATUS, &self.status.to_le_bytes());
+        put(Self::BIDS_SIZE, &self.bids_size.to_le_bytes());
+        put(Self::ASKS_SIZE, &self.asks_size.to_le_bytes());
+        put(Self::NUM_SEATS, &self.num_seats.to_le_bytes());
+        put(Self::BASE_MINT, self.base_mint.as_ref());
+        put(Self::BASE_VAULT, self.base_vault.as_ref());
+        put(Self::BASE_LOT_SIZE, &self.base_lot_size.to_le_bytes());
+        put(Self::QUOTE_MINT, self.quote_mint.as_ref());
+        put(Self::QUOTE_VAULT, self.quote_vault.as_ref());
+        put(Self::QUOTE_LOT_SIZE, &self.quote_lot_size.to_le_bytes());
+        put(Self::TICK_SIZE_IN_QUOTE_ATOMS, &self.tick_size_in_quote_atoms_per_base_unit.to_le_bytes());
+        put(Self::BASE_LOTS_PER_BASE_UNIT, &self.base_lots_per_base_unit.to_le_bytes());
+        put(Self::TICK_SIZE_IN_QUOTE_LOTS, &self.tick_size_in_quote_lots_per_base_unit.to_le_bytes());
+        put(Self::TAKER_FEE_BPS, &self.taker_fee_bps.to_le_bytes());
+        pack_tree(&mut data[Self::BIDS..], bids);
+        pack_tree(&mut data[asks_offset..], asks);
+        data
+    }
+
+    /// 卖盘树偏移
+    fn asks_offset(&self) -> Option<usize> {
+        Self::BIDS.checked_add(tree_len(self.bids_size)?)
+    }
+
+    /// lot 换算参数：每 base lot 的 quote lots = price_in_ticks * tick_size_in_quote_lots / base_lots_per_base_unit
+    pub fn to_market(&self) -> OrderBookMarket {
+        OrderBookMarket {
+            base_lot_size: self.base_lot_size,
+            quote_lot_size: self.quote_lot_size,
+            price_numerator: self.tick_size_in_quote_lots_per_base_unit,
+            price_denominator: self.base_lots_per_base_unit,
+            taker_fee: FeeRate::new(self.taker_fee_bps, BPS_DENOMINATOR),
+        }
+    }
+
+    /// 解析买卖树并聚合为订单簿快照（剔除已过期挂单）
+    pub fn unpack_book(&self, data: &[u8], slot: u64, unix_timestamp: i64) -> Option<OrderBook> {
+        let live = |order: &PhoenixOrder| !order.is_expired(slot, unix_timestamp);
+        let bids = unpack_tree(data, Self::BIDS, self.bids_size)?;
+        let asks = unpack_tree(data, self.asks_offset()?, self.asks_size)?;
+        Some(OrderBook::from_orders(
+            self.to_market(),
+            bids.iter().filter(|o| live(o)).map(|o| (o.price_in_ticks, o.num_base_lots)),
+            asks.iter().filter(|o| live(o)).map(|o| (o.price_in_ticks, o.num_base_lots)),
+        ))
+    }
+}
+
+/// 红黑树账户长度
+fn tree_len(capacity: u64) -> Option<usize> {
+    (capacity as usize).checked_mul(TREE_NODE_LEN)?.checked_add(TREE_HEADER_LEN)
+}
+
+/// 自根节点遍历红黑树收集挂单（节点下标从 1 起，0 为哨兵；访问数超过容量视为损坏）
+fn unpack_tree(data: &[u8], offset: usize, capacity: u64) -> Option<Vec<PhoenixOrder>> {
+    let root = read_u32(data, offset)?;
+    let node_offset = |index: u32| offset + TREE_HEADER_LEN + (index as usize - 1) * TREE_NODE_LEN;
+    let mut orders = Vec::new();
+    let mut stack = vec![root];
+    while let Some(index) = stack.pop() {
+        if index == TREE_SENTINEL {
+            continue;
+        }
+        if index as u64 > capacity || orders.len() as u64 >= capacity {
+            return None;
+        }
+        let node = node_offset(index);
+        stack.push(read_u32(data, node)?);     // 左子节点
+        stack.push(read_u32(data, node + 4)?); // 右子节点
+        orders.push(PhoenixOrder {
+            price_in_ticks: read_u64(data, node + 16)?,
+            num_base_lots: read_u64(data, node + 40)?,
+            last_valid_slot: read_u64(data, node + 48)?,
+            last_valid_unix_timestamp_in_seconds: read_u64(data, node + 56)?,
+        });
+    }
+    Some(orders)
+}
+
+/// 写入红黑树：节点依次以右子节点相连（仅保证可遍历，不维护红黑性质）
+fn pack_tree(data: &mut [u8], orders: &[PhoenixOrder]) {
+    let count = orders.len() as u32;
+    data[0..4].copy_from_slice(&(if count > 0 { 1u32 } else { TREE_SENTINEL }).to_le_bytes());
+    data[16..24].copy_from_slice(&(count as u64).to_le_bytes());
+    data[24..28].copy_from_slice(&count.to_le_bytes());
+    for (i, order) in orders.iter().enumerate() {
+        let node = TREE_HEADER_LEN + i * TREE_NODE_LEN;
+        let right = if (i as u32) + 1 < count { i as u32 + 2 } else { TREE_SENTINEL };
+        data[node + 4..node + 8].copy_from_slice(&right.to_le_bytes());
+        data[node + 16..node + 24].copy_from_slice(&order.price_in_ticks.to_le_bytes());
+        data[node + 24..node + 32].copy_from_slice(&(i as u64).to_le_bytes());
+        data[node + 40..node + 48].copy_from_slice(&order.num_base_lots.to_le_bytes());
+        data[node + 48..node + 56].copy_from_slice(&order.last_valid_slot.to_le_bytes());
+        data[node + 56..node + 64].copy_from_slice(&order.last_valid_unix_timestamp_in_seconds.to_le_bytes());
+    }
+}
+
+/// OrderPacket::ImmediateOrCancel 字段（Borsh 编码顺序与 Phoenix 一致）
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct PhoenixIocOrder {
+    pub side: OrderSide,                                   // 方向
+    pub price_in_ticks: Option<u64>,                       // 限价（None=市价）
+    pub num_base_lots: u64,                                // 卖出 base lots（Ask）
+    pub num_quote_lots: u64,                               // quote 预算 lots（Bid，含手续费）
+    pub min_base_lots_to_fill: u64,                        // 最少买入 base lots，不足则整单失败
+    pub min_quote_lots_to_fill: u64,                       // 最少获得 quote lots，不足则整单失败
+    pub self_trade_behavior: u8,                           // 自成交处理（0=Abort）
+    pub match_limit: Option<u64>,                          // 最多匹配挂单数
+    pub client_order_id: u128,                             // 客户端订单ID
+    pub use_only_deposited_funds: bool,                    // 仅使用席位内资金
+    pub last_valid_slot: Option<u64>,                      // 订单有效 slot
+    pub last_valid_unix_timestamp_in_seconds: Option<u64>, // 订单有效时间戳
+}
+
+impl PhoenixIocOrder {
+    /// 由输入数量与 min_amount_out 生成 IOC 订单（最小成交量向上取整到 lot，保证链上强制滑点保护）
+    pub fn new(state: &PhoenixMarketState, side: OrderSide, amount_in: u64, min_amount_out: u64, price_in_ticks: Option<u64>) -> Self {
+        let ceil_lots = |amount: u64, lot: u64| if lot == 0 { 0 } else { (amount + lot - 1) / lot };
+        let (num_base_lots, num_quote_lots, min_base_lots_to_fill, min_quote_lots_to_fill) = match side {
+            OrderSide::Bid => (0, amount_in / state.quote_lot_size.max(1), ceil_lots(min_amount_out, state.base_lot_size), 0),
+            OrderSide::Ask => (amount_in / state.base_lot_size.max(1), 0, 0, ceil_lots(min_amount_out, state.quote_lot_size)),
+        };
+        Self {
+            side,
+            price_in_ticks,
+            num_base_lots,
+            num_quote_lots,
+            min_base_lots_to_fill,
+            min_quote_lots_to_fill,
+            self_trade_behavior: 0,
+            match_limit: None,
+            client_order_id: 0,
+            use_only_deposited_funds: false,
+            last_valid_slot: None,
+            last_valid_unix_timestamp_in_seconds: None,
+        }
+    }
+}
+
+/// Phoenix Swap CPI 所需的用户侧账户
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhoenixSwapAccounts {
+    pub trader: Pubkey,        // 签名者（持有输入代币的账户或 PDA）
+    pub base_account: Pubkey,  // base 代币账户
+    pub quote_account: Pubkey, // quote 代币账户
+    pub token_program: Pubkey, // SPL Token 程序
+}
+
+/// 构造 Phoenix Swap 指令（IOC 订单，账户顺序与程序 Swap 指令一致）
+pub fn build_swap_instruction(
+    market: &Pubkey,
+    state: &PhoenixMarketState,
+    accounts: &PhoenixSwapAccounts,
+    order: &PhoenixIocOrder,
+) -> Instruction {
+    let log_authority = Pubkey::find_program_address(&[b"log"], &PHOENIX_PROGRAM_ID).0;
+    let metas = vec![
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(log_authority, false),
+        AccountMeta::new(*market, false),
+        AccountMeta::new_readonly(accounts.trader, true),
+        AccountMeta::new(accounts.base_account, false),
+        AccountMeta::new(accounts.quote_account, false),
+        AccountMeta::new(state.base_vault, false),
+        AccountMeta::new(state.quote_vault, false),
+        AccountMeta::new_readonly(accounts.token_program, false),
+    ];
+    let mut data = vec![SWAP_INSTRUCTION_TAG, IMMEDIATE_OR_CANCEL_TAG];
+    order.serialize(&mut data).expect("serialize IOC order");
+    Instruction { program_id: PHOENIX_PROGRAM_ID, accounts: metas, data }
+}
+
+/// 执行 Phoenix Swap CPI（输入代币由程序 PDA 持有时传入 signer_seeds）
+pub fn invoke_swap(instruction: &Instruction, account_infos: &[AccountInfo], signer_seeds: &[&[&[u8]]]) -> anchor_lang::Result<()> {
+    require_keys_eq!(instruction.program_id, PHOENIX_PROGRAM_ID, PhoenixError::InvalidMarketAccount);
+    anchor_lang::solana_program::program::invoke_signed(instruction, account_infos, signer_seeds)?;
+    Ok(())
+}
 
 /// Phoenix DEX 适配器结构体。
 pub struct PhoenixAdapter;
 
-impl AdapterTrait for PhoenixAdapter {
-    /// 返回适配器名称。
-    fn name(&self) -> &'static str { "phoenix" }
-    /// 返回适配器版本。
-    fn version(&self) -> &'static str { "1.0.0" }
-    /// 返回支持的资产列表。
-    fn supported_assets(&self) -> Vec<String> { 
-        vec!["SOL".to_string(), "USDC".to_string(), "PHOENIX".to_string(), "BTC".to_string()] 
+impl PhoenixAdapter {
+    /// 基于市场账户逐档报价
+    /// - accounts[0]: Phoenix 市场账户（归属 Phoenix 程序）
+    /// - slot / unix_timestamp: 当前时钟（剔除过期挂单）
+    pub fn quote_market(&self, params: &TradeParams, accounts: &[AccountInfo], slot: u64, unix_timestamp: i64) -> anchor_lang::Result<BookFill> {
+        require!(params.amount_in > 0, PhoenixError::InvalidAmount);
+        require!(params.from_token != params.to_token, PhoenixError::InvalidTokens);
+        let market = accounts.first().ok_or(DexAdapterError::MissingPoolAccounts)?;
+        require_keys_eq!(*market.owner, PHOENIX_PROGRAM_ID, PhoenixError::InvalidMarketAccount);
+        let data = market.try_borrow_data()?;
+        let state = PhoenixMarketState::unpack(&data).ok_or(PhoenixError::InvalidMarketAccount)?;
+        require!(state.status == MARKET_STATUS_ACTIVE, PhoenixError::MarketNotActive);
+        require!(state.base_lots_per_base_unit > 0, PhoenixError::InvalidMarketAccount);
+        let side = if params.from_token == state.quote_mint && params.to_token == state.base_mint {
+            OrderSide::Bid
+        } else if params.from_token == state.base_mint && params.to_token == state.quote_mint {
+            OrderSide::Ask
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let book = state.unpack_book(&data, slot, unix_timestamp).ok_or(PhoenixError::InvalidMarketAccount)?;
+        let fill = orderbook::quote_fill(&book, side, params.amount_in, None).ok_or(DexAdapterError::MathOverflow)?;
+        require!(fill.complete, DexAdapterError::InsufficientLiquidity);
+        Ok(fill)
     }
-    /// 返回适配器状态。
-    fn status(&self) -> Option<String> { Some("active".to_string()) }
+
+    /// 按报价构造 IOC swap 指令：限价取报价最差档位，最小成交量由 min_amount_out 换算并由 Phoenix 程序强制
+    pub fn swap_instruction(&self, params: &TradeParams, user: &DexSwapUserAccounts, accounts: &[AccountInfo], slot: u64, unix_timestamp: i64) -> anchor_lang::Result<Instruction> {
+        let fill = self.quote_market(params, accounts, slot, unix_timestamp)?;
+        require!(fill.amount_out >= params.min_amount_out, DexAdapterError::SlippageExceeded);
+        let market = &accounts[0];
+        let state = {
+            let data = market.try_borrow_data()?;
+            PhoenixMarketState::unpack(&data).ok_or(PhoenixError::InvalidMarketAccount)?
+        };
+        let (base_account, quote_account) = match fill.side {
+            OrderSide::Bid => (user.destination, user.source),
+            OrderSide::Ask => (user.source, user.destination),
+        };
+        let swap_accounts = PhoenixSwapAccounts { trader: user.authority, base_account, quote_account, token_program: user.token_program };
+        let order = PhoenixIocOrder::new(&state, fill.side, params.amount_in, params.min_amount_out, Some(fill.worst_price_lots));
+        Ok(build_swap_instruction(market.key, &state, &swap_accounts, &order))
+    }
+}
+
+impl AdapterTrait for PhoenixAdapter {
+    fn name(&self) -> &str { "phoenix" }
+    fn version(&self) -> &str { "1.0.0" }
+    fn is_available(&self) -> bool { true }
+    fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
 }
 
 impl DexAdapter for PhoenixAdapter {
     /// 执行 Phoenix swap 操作。
+    /// - 报价依赖市场账户中的买卖盘，未携带账户时无法给出真实成交：报价使用 quote_checked，成交使用 build_swap_instruction。
     fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // 生产级实现：集成Phoenix链上CPI调用，参数校验、错误处理、事件追踪
-        require!(params.amount_in > 0, crate::errors::asset_error::AssetError::InvalidAmount);
-        require!(params.from_token != params.to_token, crate::errors::dex_error::DexError::InvalidTokens);
-        
-        // TODO: 调用Phoenix CPI（此处应集成真实CPI调用）
-        // 这里只做结构示例，实际应调用CPI并返回真实成交数据
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000, // 应为CPI返回均价
-            fee: 1000,            // 应为CPI返回手续费
-            dex_name: "phoenix".to_string(),
-        })
+        require!(params.amount_in > 0, PhoenixError::InvalidAmount);
+        require!(params.from_token != params.to_token, PhoenixError::InvalidTokens);
+        Err(DexAdapterError::MissingPoolAccounts.into())
     }
-    
+
     /// 批量 swap 操作。
     fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
-        Ok(params.trades.iter().map(|p| DexSwapResult {
-            executed_amount: p.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "phoenix".to_string(),
-        }).collect())
+        params.trades.iter().map(|p| self.swap(p)).collect()
     }
-    
+
     /// 配置 Phoenix 适配器。
     fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> { Ok(()) }
-    
+
+    /// 基于市场账户报价（链上时钟剔除过期挂单）。
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        let clock = Clock::get()?;
+        let fill = self.quote_market(params, accounts, clock.slot, clock.unix_timestamp)?;
+        Ok(DexQuote::from_curve(&fill.to_curve_quote(), self.name()))
+    }
+
+    /// 基于市场账户构造 IOC swap CPI 指令（链上时钟剔除过期挂单）。
+    fn build_swap_instruction(&self, params: &TradeParams, user: &DexSwapUserAccounts, accounts: &[AccountInfo]) -> anchor_lang::Result<Instruction> {
+        let clock = Clock::get()?;
+        self.swap_instruction(params, user, accounts, clock.slot, clock.unix_timestamp)
+    }
+
+    /// 支持以程序 PDA 签名的 swap CPI。
+    fn supports_swap_cpi(&self) -> bool { true }
+
+    /// 执行 Phoenix Swap CPI。
+    fn invoke_swap(&self, instruction: &Instruction, account_infos: &[AccountInfo], signer_seeds: &[&[&[u8]]]) -> anchor_lang::Result<()> {
+        invoke_swap(instruction, account_infos, signer_seeds)
+    }
+
+    /// 报价/成交所需账户：市场账户（含买卖盘）。
+    fn account_requirements(&self, operation: DexOperation, _pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        match operation {
+            DexOperation::Quote | DexOperation::Swap => vec![DexAccountRequirement::readonly("market")],
+            _ => vec![],
+        }
+    }
+
     /// 返回支持的资产列表。
-    fn supported_assets(&self) -> Vec<String> { 
-        vec!["SOL".to_string(), "USDC".to_string(), "PHOENIX".to_string(), "BTC".to_string()] 
+    fn supported_assets(&self) -> Vec<String> {
+        vec!["SOL".to_string(), "USDC".to_string(), "PHOENIX".to_string(), "BTC".to_string()]
     }
-    
+
     /// 返回支持的市场类型。
-    fn supported_markets(&self) -> Vec<String> { 
-        vec!["spot".to_string(), "perpetual".to_string()] 
+    fn supported_markets(&self) -> Vec<String> {
+        vec!["spot".to_string()]
     }
-    
+
     /// 返回适配器类型。
-    fn adapter_type(&self) -> DexAdapterType { DexAdapterType::AMM }
+    fn adapter_type(&self) -> DexAdapterType { DexAdapterType::CLOB }
 }
 
-/// Phoenix DEX CPI账户结构声明
+/// Phoenix Swap CPI账户结构声明（顺序与 Phoenix Swap 指令一致）
 #[derive(Accounts)]
 pub struct PhoenixSwap<'info> {
     /// Phoenix程序
     pub phoenix_program: AccountInfo<'info>,
-    /// AMM账户
-    pub amm_account: AccountInfo<'info>,
-    /// 输入代币账户
-    pub input_token_account: AccountInfo<'info>,
-    /// 输出代币账户
-    pub output_token_account: AccountInfo<'info>,
-    /// 用户账户
-    pub user: AccountInfo<'info>,
+    /// 日志权限PDA
+    pub log_authority: AccountInfo<'info>,
+    /// 市场账户
+    #[account(mut)]
+    pub market: AccountInfo<'info>,
+    /// 交易者
+    pub trader: Signer<'info>,
+    /// base代币账户
+    #[account(mut)]
+    pub base_account: AccountInfo<'info>,
+    /// quote代币账户
+    #[account(mut)]
+    pub quote_account: AccountInfo<'info>,
+    /// base金库
+    #[account(mut)]
+    pub base_vault: AccountInfo<'info>,
+    /// quote金库
+    #[account(mut)]
+    pub quote_vault: AccountInfo<'info>,
+    /// 代币程序
+    pub token_program: AccountInfo<'info>,
 }
 
 /// Phoenix DEX错误码（Anchor错误）
@@ -99,6 +461,10 @@ pub enum PhoenixError {
     #[msg("Invalid AMM account")] InvalidAmmAccount,
     /// 操作不支持
     #[msg("Operation unsupported")] Unsupported,
+    /// 市场账户无效（归属、判别符或布局不匹配）
+    #[msg("Invalid market account")] InvalidMarketAccount,
+    /// 市场未处于可吃单状态
+    #[msg("Market not active")] MarketNotActive,
 }
 
 /// 自动注册 PhoenixAdapter 到工厂（如有需要可补充）。
@@ -110,30 +476,158 @@ fn register_phoenix_adapter() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::adapter::check_quote_min_out;
     use anchor_lang::prelude::Pubkey;
 
+    /// 构造交易参数。
+    fn trade(from: Pubkey, to: Pubkey, amount_in: u64, min_amount_out: u64) -> TradeParams {
+        TradeParams {
+            trade_type: "swap".to_string(),
+            from_token: from,
+            to_token: to,
+            amount_in,
+            min_amount_out,
+            dex_name: "phoenix".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        }
+    }
+
+    fn order(price_in_ticks: u64, num_base_lots: u64) -> PhoenixOrder {
+        PhoenixOrder { price_in_ticks, num_base_lots, last_valid_slot: 0, last_valid_unix_timestamp_in_seconds: 0 }
+    }
+
+    /// 市场夹具：SOL/USDC，base lot 0.001 SOL、quote lot 1e-6 USDC，tick 0.001 USDC，taker 费 5 基点。
+    /// 每 base lot 的 quote lots = ticks * 1_000 / 1_000，即 1 tick = 1 quote lot / base lot。
+    fn market_fixture() -> (PhoenixMarketState, Vec<u8>) {
+        let state = PhoenixMarketState {
+            status: MARKET_STATUS_ACTIVE,
+            bids_size: 8,
+            asks_size: 8,
+            num_seats: 8,
+            base_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            base_lot_size: 1_000_000,
+            quote_mint: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            quote_lot_size: 1,
+            tick_size_in_quote_atoms_per_base_unit: 1_000,
+            base_lots_per_base_unit: 1_000,
+            tick_size_in_quote_lots_per_base_unit: 1_000,
+            taker_fee_bps: 5,
+        };
+        let bids = [order(149_900, 2_000), order(149_800, 5_000)];
+        let mut expired = order(150_000, 9_000);
+        expired.last_valid_slot = 10;
+        let asks = [order(150_100, 1_000), order(150_200, 1_000), order(150_100, 1_000), expired];
+        let data = state.pack(&bids, &asks);
+        (state, data)
+    }
+
     /// 测试 PhoenixAdapter 名称。
     #[test]
     fn test_phoenix_adapter_name() {
         let adapter = PhoenixAdapter;
         assert_eq!(adapter.name(), "phoenix");
+        assert_eq!(adapter.adapter_type(), DexAdapterType::CLOB);
+    }
+
+    /// 测试市场头部与买卖盘解析。
+    #[test]
+    fn test_market_header_and_ladder_roundtrip() {
+        let (state, data) = market_fixture();
+        assert_eq!(PhoenixMarketState::unpack(&data), Some(state.clone()));
+        assert!(PhoenixMarketState::unpack(&data[..data.len() - 1]).is_none());
+        let book = state.unpack_book(&data, 20, 0).unwrap();
+        assert_eq!(book.best_bid(), Some(149_900));
+        assert_eq!(book.asks.len(), 2); // 同价聚合且剔除过期挂单
+        assert_eq!(book.asks[0].size_lots, 2_000);
+        assert_eq!(state.unpack_book(&data, 5, 0).unwrap().best_ask(), Some(150_000));
     }
 
-    /// 测试 PhoenixAdapter swap 功能。
+    /// 测试 PhoenixAdapter 逐档报价与结算。
     #[test]
     fn test_phoenix_adapter_swap() {
         let adapter = PhoenixAdapter;
-        let params = TradeParams {
-            from_token: Pubkey::default(),
-            to_token: Pubkey::new_unique(), // 使用不同的token
-            amount_in: 100,
-            min_amount_out: 90,
-            dex_name: "phoenix".to_string(),
+        let (state, mut data) = market_fixture();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &PHOENIX_PROGRAM_ID, false, 0);
+        let infos = vec![info];
+        let (sol, usdc) = (state.base_mint, state.quote_mint);
+        // 卖出 2.5 SOL：2_000 lots @149.9 + 500 lots @149.8，所得扣 5 基点
+        let sell = adapter.quote_market(&trade(sol, usdc, 2_500_000_000, 0), &infos, 20, 0).unwrap();
+        assert_eq!(sell.quote_lots, 2_000 * 149_900 + 500 * 149_800);
+        assert_eq!(sell.fee, (374_700_000u64 * 5 + 9_999) / 10_000);
+        assert_eq!(sell.amount_out, 374_700_000 - sell.fee);
+        assert_eq!(sell.levels_consumed, 2);
+        // 买入：300 USDC 吃 150.1 档
+        let buy = adapter.quote_market(&trade(usdc, sol, 300_000_000, 0), &infos, 20, 0).unwrap();
+        assert_eq!(buy.side, OrderSide::Bid);
+        assert_eq!(buy.worst_price_lots, 150_100);
+        assert_eq!(buy.base_lots, 1_997);
+        // 结算：min_amount_out 校验
+        let quote = DexQuote::from_curve(&buy.to_curve_quote(), adapter.name());
+        assert!(check_quote_min_out(&trade(usdc, sol, 300_000_000, 1_997_000_000), quote.clone()).is_ok());
+        assert!(check_quote_min_out(&trade(usdc, sol, 300_000_000, 1_998_000_000), quote).is_err());
+        // 超出盘口深度、代币对不匹配、无账户 swap
+        assert!(adapter.quote_market(&trade(sol, usdc, 8_000_000_000, 0), &infos, 20, 0).is_err());
+        assert!(adapter.quote_market(&trade(sol, Pubkey::new_unique(), 1_000_000, 0), &infos, 20, 0).is_err());
+        assert!(adapter.swap(&trade(sol, usdc, 100, 90)).is_err());
+    }
+
+    /// 测试 IOC swap 指令账户列表与指令数据。
+    #[test]
+    fn test_build_swap_instruction() {
+        let (state, _) = market_fixture();
+        let market = Pubkey::new_unique();
+        let accounts = PhoenixSwapAccounts {
+            trader: Pubkey::new_unique(),
+            base_account: Pubkey::new_unique(),
+            quote_account: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
         };
-        let result = adapter.swap(&params);
-        assert!(result.is_ok());
+        let order = PhoenixIocOrder::new(&state, OrderSide::Ask, 2_500_000_000, 374_512_650, Some(149_800));
+        assert_eq!(order.num_base_lots, 2_500);
+        assert_eq!(order.min_quote_lots_to_fill, 374_512_650);
+        let ix = build_swap_instruction(&market, &state, &accounts, &order);
+        assert_eq!(ix.program_id, PHOENIX_PROGRAM_ID);
+        assert_eq!(ix.accounts.len(), 9);
+        assert_eq!(ix.accounts[2].pubkey, market);
+        assert!(ix.accounts[2].is_writable);
+        assert!(ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts[6].pubkey, state.base_vault);
+        assert_eq!(&ix.data[..3], &[SWAP_INSTRUCTION_TAG, IMMEDIATE_OR_CANCEL_TAG, 1]);
+        assert_eq!(PhoenixIocOrder::try_from_slice(&ix.data[2..]).unwrap(), order);
     }
-    
+
+    /// 测试按报价构造托管账户的 IOC swap 指令。
+    #[test]
+    fn test_swap_instruction_from_quote() {
+        let adapter = PhoenixAdapter;
+        let (state, mut data) = market_fixture();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let infos = vec![AccountInfo::new(&key, false, false, &mut lamports, &mut data, &PHOENIX_PROGRAM_ID, false, 0)];
+        let user = DexSwapUserAccounts {
+            authority: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
+        };
+        let (sol, usdc) = (state.base_mint, state.quote_mint);
+        let ix = adapter.swap_instruction(&trade(usdc, sol, 300_000_000, 1_997_000_000), &user, &infos, 20, 0).unwrap();
+        assert_eq!(ix.accounts[3].pubkey, user.authority);
+        assert_eq!(ix.accounts[4].pubkey, user.destination); // 买入 base：输出账户为 base 账户
+        assert_eq!(ix.accounts[5].pubkey, user.source);
+        let order = PhoenixIocOrder::try_from_slice(&ix.data[2..]).unwrap();
+        assert_eq!(order.price_in_ticks, Some(150_100));
+        assert_eq!(order.min_base_lots_to_fill, 1_997);
+        assert!(adapter.swap_instruction(&trade(usdc, sol, 300_000_000, 1_998_000_000), &user, &infos, 20, 0).is_err());
+    }
+
     /// 测试 PhoenixAdapter 支持的资产。
     #[test]
     fn test_phoenix_supported_assets() {
@@ -143,4 +637,4 @@ mod tests {
         assert!(assets.contains(&"USDC".to_string()));
         assert!(assets.contains(&"PHOENIX".to_string()));
     }
-} 
\ No newline at end of file
+}
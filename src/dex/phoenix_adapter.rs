@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*; // Anchor预导入，包含Result、Context等
-use super::traits::{DexAdapter, SwapParams, SwapResult, AddLiquidityParams, RemoveLiquidityParams, QuoteParams, QuoteResult}; // DEX适配器trait及相关类型
-// use crate::core::adapter: // 暂时注释掉:AdapterTrait; // 适配器元信息trait，统一接口
+use crate::core::types::{TradeParams, BatchTradeParams, DexParams}; // 统一交易参数
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterType, DexOperation, DexQuote, DexSwapResult}; // 统一DEX适配器trait及相关类型
+use crate::dex::phoenix::PhoenixAdapter as RealPhoenixAdapter; // 真实Phoenix实现
+use crate::core::adapter::AdapterTrait; // 适配器元信息trait，统一接口
 // 移除未找到的ctor属性
 // use ctor::ctor; // ctor宏用于自动注册
 
@@ -11,14 +13,11 @@ pub struct PhoenixAdapter;
 
 /// 实现AdapterTrait，提供适配器元信息
 impl AdapterTrait for PhoenixAdapter {
-    /// 返回适配器名称（唯一标识）
-    fn name(&self) -> &'static str { "phoenix" }
-    /// 返回适配器版本号
-    fn version(&self) -> &'static str { "1.0.0" }
-    /// 返回支持的资产列表（如SOL、USDC等）
-    fn supported_assets(&self) -> Vec<String> { vec!["SOL".to_string(), "USDC".to_string()] }
-    /// 返回适配器当前状态（如active、paused等）
-    fn status(&self) -> Option<String> { Some("active".to_string()) }
+    fn name(&self) -> &str { "phoenix_adapter" }
+    fn version(&self) -> &str { "1.0.0" }
+    fn is_available(&self) -> bool { true }
+    fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
 }
 
 /// 自动注册PhoenixAdapter到全局工厂
@@ -31,43 +30,32 @@ fn auto_register_phoenix_adapter() {
     factory.register(adapter); // 注册到工厂，便于统一管理
 }
 
-/// 实现DexAdapter trait，集成Phoenix链上CPI调用（待补充）
+/// 实现统一DexAdapter trait，委托给 phoenix.rs 中的真实实现
 impl DexAdapter for PhoenixAdapter {
     /// 执行 Phoenix swap 操作。
-    fn swap(&self, params: &SwapParams) -> anchor_lang::Result<DexSwapResult> {
-        // 生产级实现：集成Phoenix链上CPI调用，参数校验、错误处理、事件追踪
-        require!(params.amount_in > 0, crate::errors::asset_error::AssetError::InvalidAmount);
-        // TODO: 调用Phoenix CPI（此处应集成真实CPI调用）
-        // 这里只做结构示例，实际应调用CPI并返回真实成交数据
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000, // 应为CPI返回均价
-            fee: 1000,            // 应为CPI返回手续费
-            dex_name: "phoenix".to_string(),
-        })
+    fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
+        RealPhoenixAdapter.swap(params)
+    }
+    /// 批量 swap 操作。
+    fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
+        RealPhoenixAdapter.batch_swap(params)
     }
-    /// 添加流动性（待集成CPI）
-    /// - ctx: Anchor上下文，包含所有必需账户
-    /// - params: 添加流动性参数
-    /// - 返回：添加后获得的LP token数量
-    fn add_liquidity(&self, ctx: Context<AddLiquidity>, params: AddLiquidityParams) -> anchor_lang::Result<u64> {
-        // TODO: 集成 Phoenix CPI
-        Ok(0)
+    /// 配置适配器。
+    fn configure(&self, params: &DexParams) -> anchor_lang::Result<()> {
+        RealPhoenixAdapter.configure(params)
     }
-    /// 移除流动性（待集成CPI）
-    /// - ctx: Anchor上下文，包含所有必需账户
-    /// - params: 移除流动性参数
-    /// - 返回：移除后获得的资产数量
-    fn remove_liquidity(&self, ctx: Context<RemoveLiquidity>, params: RemoveLiquidityParams) -> anchor_lang::Result<u64> {
-        // TODO: 集成 Phoenix CPI
-        Ok(0)
+    /// 基于市场账户逐档报价。
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        RealPhoenixAdapter.get_quote(params, accounts)
     }
-    /// 获取报价（待集成CPI）
-    /// - ctx: Anchor上下文，包含所有必需账户
-    /// - params: 报价参数
-    /// - 返回：QuoteResult结构体，包含预期兑换数量和手续费
-    fn get_quote(&self, ctx: Context<GetQuote>, params: QuoteParams) -> anchor_lang::Result<QuoteResult> {
-        // TODO: 集成 Phoenix CPI
-        Ok(QuoteResult { amount_out: 0, fee: 0 })
+    /// 报价/成交所需账户。
+    fn account_requirements(&self, operation: DexOperation, pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        RealPhoenixAdapter.account_requirements(operation, pool_program)
     }
-} 
\ No newline at end of file
+    /// 返回支持的资产列表（如SOL、USDC等）
+    fn supported_assets(&self) -> Vec<String> { vec!["SOL".to_string(), "USDC".to_string()] }
+    /// 返回支持的市场类型。
+    fn supported_markets(&self) -> Vec<String> { RealPhoenixAdapter.supported_markets() }
+    /// 返回适配器类型。
+    fn adapter_type(&self) -> DexAdapterType { RealPhoenixAdapter.adapter_type() }
+}
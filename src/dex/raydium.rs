@@ -2,68 +2,354 @@
 //! Raydium DEX Adapter Module
 //!
 //! 本模块实现 Raydium DEX 适配器，提供与 Raydium AMM 的链上集成接口，确保交易路由与聚合合规、可维护。
+//! 报价基于传入的 AMM v4 池账户（AmmInfo）与金库 Token 账户，按常积曲线计算输出、手续费与价格冲击；
+//! CLMM 集中流动性池则解析 PoolState、AmmConfig 与 tick array 账户，逐 tick 穿越得到精确输出与成交后价格。
 
-// use crate::core::adapter: // 暂时注释掉:AdapterTrait;
-// use crate::core::types: // 暂时注释掉:{TradeParams, BatchTradeParams, DexParams};
-use crate::dex::adapter::{DexAdapter, DexSwapResult, DexAdapterType};
+use crate::core::adapter::AdapterTrait;
+use crate::core::types::{TradeParams, BatchTradeParams, DexParams};
+use crate::dex::adapter::{DexAccountRequirement, DexAdapter, DexAdapterError, DexAdapterType, DexOperation, DexQuote, DexSwapResult};
+use crate::dex::clmm::{self, ClmmPoolState, ClmmQuote, ClmmTick, ClmmTickSet, TickArrayTicks};
+use crate::dex::curve::{self, CurveType, FeeRate};
+use crate::dex::layout::{read_i128, read_i32, read_pubkey, read_u128, read_u16, read_u32, read_u64, unpack_token_account};
 use anchor_lang::prelude::*;
 
+/// Raydium AMM v4 程序ID
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+/// AmmInfo 账户长度
+pub const RAYDIUM_AMM_INFO_LEN: usize = 752;
+
+/// Raydium CLMM 程序ID
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+/// CLMM PoolState 报价所需最小长度
+pub const RAYDIUM_CLMM_POOL_MIN_LEN: usize = 273;
+/// CLMM AmmConfig 报价所需最小长度
+pub const RAYDIUM_CLMM_CONFIG_MIN_LEN: usize = 53;
+/// CLMM TickArrayState 账户长度
+pub const RAYDIUM_CLMM_TICK_ARRAY_LEN: usize = 10240;
+/// 每个 CLMM TickArrayState 包含的 tick 数
+pub const RAYDIUM_CLMM_TICK_ARRAY_SIZE: i32 = 60;
+
+/// Raydium AMM v4 池状态（仅解析报价所需字段）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaydiumAmmInfo {
+    pub status: u64,                // 池状态
+    pub swap_fee_numerator: u64,    // swap 手续费分子
+    pub swap_fee_denominator: u64,  // swap 手续费分母
+    pub need_take_pnl_coin: u64,    // 待提取 coin 侧 PnL（不计入可交易储备）
+    pub need_take_pnl_pc: u64,      // 待提取 pc 侧 PnL（不计入可交易储备）
+    pub token_coin: Pubkey,         // coin 金库
+    pub token_pc: Pubkey,           // pc 金库
+    pub coin_mint: Pubkey,          // coin mint
+    pub pc_mint: Pubkey,            // pc mint
+}
+
+impl RaydiumAmmInfo {
+    // AmmInfo 字段偏移（与 raydium-amm state.rs 布局一致）
+    const STATUS: usize = 0;
+    const SWAP_FEE_NUMERATOR: usize = 176;
+    const SWAP_FEE_DENOMINATOR: usize = 184;
+    const NEED_TAKE_PNL_COIN: usize = 192;
+    const NEED_TAKE_PNL_PC: usize = 200;
+    const TOKEN_COIN: usize = 336;
+    const TOKEN_PC: usize = 368;
+    const COIN_MINT: usize = 400;
+    const PC_MINT: usize = 432;
+
+    /// 从账户字节反序列化
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < RAYDIUM_AMM_INFO_LEN {
+            return None;
+        }
+        Some(Self {
+            status: read_u64(data, Self::STATUS)?,
+            swap_fee_numerator: read_u64(data, Self::SWAP_FEE_NUMERATOR)?,
+            swap_fee_denominator: read_u64(data, Self::SWAP_FEE_DENOMINATOR)?,
+            need_take_pnl_coin: read_u64(data, Self::NEED_TAKE_PNL_COIN)?,
+            need_take_pnl_pc: read_u64(data, Self::NEED_TAKE_PNL_PC)?,
+            token_coin: read_pubkey(data, Self::TOKEN_COIN)?,
+            token_pc: read_pubkey(data, Self::TOKEN_PC)?,
+            coin_mint: read_pubkey(data, Self::COIN_MINT)?,
+            pc_mint: read_pubkey(data, Self::PC_MINT)?,
+        })
+    }
+
+    /// 序列化为账户字节（测试夹具与离线模拟使用，未解析字段填零）
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0u8; RAYDIUM_AMM_INFO_LEN];
+        data[Self::STATUS..Self::STATUS + 8].copy_from_slice(&self.status.to_le_bytes());
+        data[Self::SWAP_FEE_NUMERATOR..Self::SWAP_FEE_NUMERATOR + 8].copy_from_slice(&self.swap_fee_numerator.to_le_bytes());
+        data[Self::SWAP_FEE_DENOMINATOR..Self::SWAP_FEE_DENOMINATOR + 8].copy_from_slice(&self.swap_fee_denominator.to_le_bytes());
+        data[Self::NEED_TAKE_PNL_COIN..Self::NEED_TAKE_PNL_COIN + 8].copy_from_slice(&self.need_take_pnl_coin.to_le_bytes());
+        data[Self::NEED_TAKE_PNL_PC..Self::NEED_TAKE_PNL_PC + 8].copy_from_slice(&self.need_take_pnl_pc.to_le_bytes());
+        data[Self::TOKEN_COIN..Self::TOKEN_COIN + 32].copy_from_slice(self.token_coin.as_ref());
+        data[Self::TOKEN_PC..Self::TOKEN_PC + 32].copy_from_slice(self.token_pc.as_ref());
+        data[Self::COIN_MINT..Self::COIN_MINT + 32].copy_from_slice(self.coin_mint.as_ref());
+        data[Self::PC_MINT..Self::PC_MINT + 32].copy_from_slice(self.pc_mint.as_ref());
+        data
+    }
+}
+
+/// Raydium CLMM 池状态（仅解析报价所需字段，偏移含 8 字节 Anchor 判别符）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaydiumClmmPool {
+    pub amm_config: Pubkey,     // 费率配置账户
+    pub token_mint_0: Pubkey,   // token0 mint
+    pub token_mint_1: Pubkey,   // token1 mint
+    pub tick_spacing: u16,      // tick 间距
+    pub liquidity: u128,        // 当前活跃流动性
+    pub sqrt_price_x64: u128,   // 当前 sqrt price（Q64.64）
+    pub tick_current: i32,      // 当前 tick
+}
+
+impl RaydiumClmmPool {
+    const AMM_CONFIG: usize = 9;
+    const TOKEN_MINT_0: usize = 73;
+    const TOKEN_MINT_1: usize = 105;
+    const TICK_SPACING: usize = 235;
+    const LIQUIDITY: usize = 237;
+    const SQRT_PRICE_X64: usize = 253;
+    const TICK_CURRENT: usize = 269;
+    /// AmmConfig.trade_fee_rate 偏移
+    const CONFIG_TRADE_FEE_RATE: usize = 47;
+
+    /// 从账户字节反序列化
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < RAYDIUM_CLMM_POOL_MIN_LEN {
+            return None;
+        }
+        Some(Self {
+            amm_config: read_pubkey(data, Self::AMM_CONFIG)?,
+            token_mint_0: read_pubkey(data, Self::TOKEN_MINT_0)?,
+            token_mint_1: read_pubkey(data, Self::TOKEN_MINT_1)?,
+            tick_spacing: read_u16(data, Self::TICK_SPACING)?,
+            liquidity: read_u128(data, Self::LIQUIDITY)?,
+            sqrt_price_x64: read_u128(data, Self::SQRT_PRICE_X64)?,
+            tick_current: read_i32(data, Self::TICK_CURRENT)?,
+        })
+    }
+
+    /// 序列化为账户字节（测试夹具与离线模拟使用，未解析字段填零）
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0u8; RAYDIUM_CLMM_POOL_MIN_LEN];
+        data[Self::AMM_CONFIG..Self::AMM_CONFIG + 32].copy_from_slice(self.amm_config.as_ref());
+        data[Self::TOKEN_MINT_0..Self::TOKEN_MINT_0 + 32].copy_from_slice(self.token_mint_0.as_ref());
+        data[Self::TOKEN_MINT_1..Self::TOKEN_MINT_1 + 32].copy_from_slice(self.token_mint_1.as_ref());
+        data[Self::TICK_SPACING..Self::TICK_SPACING + 2].copy_from_slice(&self.tick_spacing.to_le_bytes());
+        data[Self::LIQUIDITY..Self::LIQUIDITY + 16].copy_from_slice(&self.liquidity.to_le_bytes());
+        data[Self::SQRT_PRICE_X64..Self::SQRT_PRICE_X64 + 16].copy_from_slice(&self.sqrt_price_x64.to_le_bytes());
+        data[Self::TICK_CURRENT..Self::TICK_CURRENT + 4].copy_from_slice(&self.tick_current.to_le_bytes());
+        data
+    }
+
+    /// 解析 AmmConfig 账户中的交易费率（百万分之一）
+    pub fn unpack_trade_fee_rate(config: &[u8]) -> Option<u32> {
+        if config.len() < RAYDIUM_CLMM_CONFIG_MIN_LEN {
+            return None;
+        }
+        read_u32(config, Self::CONFIG_TRADE_FEE_RATE)
+    }
+
+    /// 构造 AmmConfig 字节（测试夹具与离线模拟使用）
+    pub fn pack_config(trade_fee_rate: u32) -> Vec<u8> {
+        let mut data = vec![0u8; RAYDIUM_CLMM_CONFIG_MIN_LEN];
+        data[Self::CONFIG_TRADE_FEE_RATE..Self::CONFIG_TRADE_FEE_RATE + 4].copy_from_slice(&trade_fee_rate.to_le_bytes());
+        data
+    }
+}
+
+/// Raydium CLMM TickArrayState 解析（pool_id | start_tick_index | 60 × TickState(168 字节)）
+pub struct RaydiumClmmTickArray;
+
+impl RaydiumClmmTickArray {
+    const POOL_ID: usize = 8;
+    const START_TICK_INDEX: usize = 40;
+    const TICKS: usize = 44;
+    const TICK_LEN: usize = 168;
+
+    /// 解析并校验所属池，返回已初始化 tick（liquidity_gross 非零）
+    pub fn unpack(data: &[u8], pool: &Pubkey, tick_spacing: u16) -> Option<TickArrayTicks> {
+        if data.len() < RAYDIUM_CLMM_TICK_ARRAY_LEN || read_pubkey(data, Self::POOL_ID)? != *pool {
+            return None;
+        }
+        let start_tick_index = read_i32(data, Self::START_TICK_INDEX)?;
+        let mut ticks = Vec::new();
+        for i in 0..RAYDIUM_CLMM_TICK_ARRAY_SIZE as usize {
+            let offset = Self::TICKS + i * Self::TICK_LEN;
+            if read_u128(data, offset + 20)? != 0 {
+                ticks.push(ClmmTick {
+                    index: read_i32(data, offset)?,
+                    liquidity_net: read_i128(data, offset + 4)?,
+                });
+            }
+        }
+        Some(TickArrayTicks { start_tick_index, span: RAYDIUM_CLMM_TICK_ARRAY_SIZE * tick_spacing as i32, ticks })
+    }
+
+    /// 构造 TickArrayState 字节（测试夹具与离线模拟使用）
+    pub fn pack(pool: &Pubkey, start_tick_index: i32, tick_spacing: u16, ticks: &[ClmmTick]) -> Vec<u8> {
+        let mut data = vec![0u8; RAYDIUM_CLMM_TICK_ARRAY_LEN];
+        data[Self::POOL_ID..Self::POOL_ID + 32].copy_from_slice(pool.as_ref());
+        data[Self::START_TICK_INDEX..Self::START_TICK_INDEX + 4].copy_from_slice(&start_tick_index.to_le_bytes());
+        for tick in ticks {
+            let slot = ((tick.index - start_tick_index) / tick_spacing as i32) as usize;
+            let offset = Self::TICKS + slot * Self::TICK_LEN;
+            data[offset..offset + 4].copy_from_slice(&tick.index.to_le_bytes());
+            data[offset + 4..offset + 20].copy_from_slice(&tick.liquidity_net.to_le_bytes());
+            data[offset + 20..offset + 36].copy_from_slice(&tick.liquidity_net.unsigned_abs().to_le_bytes());
+        }
+        data
+    }
+}
+
 /// Raydium DEX 适配器结构体。
 pub struct RaydiumAdapter;
 
-impl AdapterTrait for RaydiumAdapter {
-    /// 返回适配器名称。
-    fn name(&self) -> &'static str { "raydium" }
-    /// 返回适配器版本。
-    fn version(&self) -> &'static str { "1.0.0" }
-    /// 返回支持的资产列表。
-    fn supported_assets(&self) -> Vec<String> { 
-        vec!["SOL".to_string(), "USDC".to_string(), "RAY".to_string(), "SRM".to_string()] 
+impl RaydiumAdapter {
+    /// 基于池账户计算报价
+    /// - accounts[0]: AmmInfo 池账户（归属 Raydium AMM v4 程序）
+    /// - accounts[1]: coin 金库 Token 账户
+    /// - accounts[2]: pc 金库 Token 账户
+    /// - CLMM 池（accounts[0] 归属 CLMM 程序）转由 quote_clmm 处理
+    pub fn quote_from_accounts(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        require!(params.amount_in > 0, RaydiumError::InvalidAmount);
+        require!(params.from_token != params.to_token, RaydiumError::InvalidTokens);
+        require!(!accounts.is_empty(), DexAdapterError::MissingPoolAccounts);
+        if *accounts[0].owner == RAYDIUM_CLMM_PROGRAM_ID {
+            let quote = self.quote_clmm(params, accounts)?;
+            return Ok(DexQuote::from_curve(&quote.to_curve_quote(), self.name()));
+        }
+        require!(accounts.len() >= 3, DexAdapterError::MissingPoolAccounts);
+        let (pool, coin_vault, pc_vault) = (&accounts[0], &accounts[1], &accounts[2]);
+        require_keys_eq!(*pool.owner, RAYDIUM_AMM_V4_PROGRAM_ID, RaydiumError::InvalidAmmAccount);
+        let amm = {
+            let data = pool.try_borrow_data()?;
+            RaydiumAmmInfo::unpack(&data).ok_or(RaydiumError::InvalidAmmAccount)?
+        };
+        require_keys_eq!(coin_vault.key(), amm.token_coin, RaydiumError::InvalidAmmAccount);
+        require_keys_eq!(pc_vault.key(), amm.token_pc, RaydiumError::InvalidAmmAccount);
+        let coin = unpack_token_account(coin_vault).ok_or(RaydiumError::InvalidAmmAccount)?;
+        let pc = unpack_token_account(pc_vault).ok_or(RaydiumError::InvalidAmmAccount)?;
+        // 可交易储备 = 金库余额 - 待提取 PnL
+        let coin_reserve = coin.amount.saturating_sub(amm.need_take_pnl_coin);
+        let pc_reserve = pc.amount.saturating_sub(amm.need_take_pnl_pc);
+        let (reserve_in, reserve_out) = if params.from_token == amm.coin_mint && params.to_token == amm.pc_mint {
+            (coin_reserve, pc_reserve)
+        } else if params.from_token == amm.pc_mint && params.to_token == amm.coin_mint {
+            (pc_reserve, coin_reserve)
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let fee = FeeRate::new(amm.swap_fee_numerator, amm.swap_fee_denominator);
+        let quote = curve::quote(CurveType::ConstantProduct, reserve_in, reserve_out, params.amount_in, fee)
+            .ok_or(RaydiumError::InsufficientLiquidity)?;
+        Ok(DexQuote::from_curve(&quote, self.name()))
+    }
+
+    /// 基于 CLMM 集中流动性报价
+    /// - accounts[0]: PoolState 账户
+    /// - accounts[1]: AmmConfig 账户（交易费率）
+    /// - accounts[2..]: 覆盖成交方向的连续 TickArrayState 账户
+    pub fn quote_clmm(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<ClmmQuote> {
+        require!(accounts.len() >= 3, DexAdapterError::MissingPoolAccounts);
+        let (pool, config) = (&accounts[0], &accounts[1]);
+        require_keys_eq!(*pool.owner, RAYDIUM_CLMM_PROGRAM_ID, RaydiumError::InvalidAmmAccount);
+        require_keys_eq!(*config.owner, RAYDIUM_CLMM_PROGRAM_ID, RaydiumError::InvalidAmmAccount);
+        let state = {
+            let data = pool.try_borrow_data()?;
+            RaydiumClmmPool::unpack(&data).ok_or(RaydiumError::InvalidAmmAccount)?
+        };
+        require_keys_eq!(config.key(), state.amm_config, RaydiumError::InvalidAmmAccount);
+        require!(state.tick_spacing > 0, RaydiumError::InvalidAmmAccount);
+        let fee_rate = {
+            let data = config.try_borrow_data()?;
+            RaydiumClmmPool::unpack_trade_fee_rate(&data).ok_or(RaydiumError::InvalidAmmAccount)?
+        };
+        let zero_for_one = if params.from_token == state.token_mint_0 && params.to_token == state.token_mint_1 {
+            true
+        } else if params.from_token == state.token_mint_1 && params.to_token == state.token_mint_0 {
+            false
+        } else {
+            return Err(DexAdapterError::TokenPairMismatch.into());
+        };
+        let mut arrays = Vec::with_capacity(accounts.len() - 2);
+        for info in &accounts[2..] {
+            require_keys_eq!(*info.owner, RAYDIUM_CLMM_PROGRAM_ID, DexAdapterError::InvalidTickArray);
+            let data = info.try_borrow_data()?;
+            arrays.push(
+                RaydiumClmmTickArray::unpack(&data, pool.key, state.tick_spacing).ok_or(DexAdapterError::InvalidTickArray)?,
+            );
+        }
+        let tick_set = ClmmTickSet::from_arrays(arrays).ok_or(DexAdapterError::InvalidTickArray)?;
+        let pool_state = ClmmPoolState {
+            sqrt_price: state.sqrt_price_x64,
+            tick_current: state.tick_current,
+            liquidity: state.liquidity,
+            fee_rate,
+        };
+        let quote = clmm::swap_quote(&pool_state, &tick_set, params.amount_in, zero_for_one)
+            .ok_or(DexAdapterError::InvalidTickArray)?;
+        require!(quote.amount_in == params.amount_in, DexAdapterError::InsufficientTickArrays);
+        Ok(quote)
     }
-    /// 返回适配器状态。
-    fn status(&self) -> Option<String> { Some("active".to_string()) }
+}
+
+impl AdapterTrait for RaydiumAdapter {
+    fn name(&self) -> &str { "raydium" }
+    fn version(&self) -> &str { "1.0.0" }
+    fn is_available(&self) -> bool { true }
+    fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
 }
 
 impl DexAdapter for RaydiumAdapter {
     /// 执行 Raydium swap 操作。
+    /// - 报价依赖池账户，未携带账户时无法给出真实成交：报价使用 quote_checked，成交使用 build_swap_instruction。
     fn swap(&self, params: &TradeParams) -> anchor_lang::Result<DexSwapResult> {
-        // 生产级实现：集成Raydium链上CPI调用，参数校验、错误处理、事件追踪
-        require!(params.amount_in > 0, crate::errors::asset_error::AssetError::InvalidAmount);
-        require!(params.from_token != params.to_token, crate::errors::dex_error::DexError::InvalidTokens);
-        
-        // TODO: 调用Raydium CPI（此处应集成真实CPI调用）
-        // 这里只做结构示例，实际应调用CPI并返回真实成交数据
-        Ok(DexSwapResult {
-            executed_amount: params.amount_in,
-            avg_price: 1_000_000, // 应为CPI返回均价
-            fee: 1000,            // 应为CPI返回手续费
-            dex_name: "raydium".to_string(),
-        })
+        require!(params.amount_in > 0, RaydiumError::InvalidAmount);
+        require!(params.from_token != params.to_token, RaydiumError::InvalidTokens);
+        Err(DexAdapterError::MissingPoolAccounts.into())
     }
-    
+
     /// 批量 swap 操作。
     fn batch_swap(&self, params: &BatchTradeParams) -> anchor_lang::Result<Vec<DexSwapResult>> {
-        Ok(params.trades.iter().map(|p| DexSwapResult {
-            executed_amount: p.amount_in,
-            avg_price: 1_000_000,
-            fee: 1000,
-            dex_name: "raydium".to_string(),
-        }).collect())
+        params.trades.iter().map(|p| self.swap(p)).collect()
     }
-    
+
     /// 配置 Raydium 适配器。
     fn configure(&self, _params: &DexParams) -> anchor_lang::Result<()> { Ok(()) }
-    
+
+    /// 基于 AmmInfo 与金库账户报价。
+    fn get_quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<DexQuote> {
+        self.quote_from_accounts(params, accounts)
+    }
+
+    /// 报价/成交所需账户：AMM v4 为池 + 双金库，CLMM 为池 + AmmConfig + 连续 TickArray。
+    fn account_requirements(&self, operation: DexOperation, pool_program: &Pubkey) -> Vec<DexAccountRequirement> {
+        match operation {
+            DexOperation::Quote | DexOperation::Swap if *pool_program == RAYDIUM_CLMM_PROGRAM_ID => vec![
+                DexAccountRequirement::readonly("pool_state"),
+                DexAccountRequirement::readonly("amm_config"),
+                DexAccountRequirement::readonly("tick_array").repeated(),
+            ],
+            DexOperation::Quote | DexOperation::Swap => vec![
+                DexAccountRequirement::readonly("amm_info"),
+                DexAccountRequirement::readonly("coin_vault"),
+                DexAccountRequirement::readonly("pc_vault"),
+            ],
+            _ => vec![],
+        }
+    }
+
     /// 返回支持的资产列表。
-    fn supported_assets(&self) -> Vec<String> { 
-        vec!["SOL".to_string(), "USDC".to_string(), "RAY".to_string(), "SRM".to_string()] 
+    fn supported_assets(&self) -> Vec<String> {
+        vec!["SOL".to_string(), "USDC".to_string(), "RAY".to_string(), "SRM".to_string()]
     }
-    
+
     /// 返回支持的市场类型。
-    fn supported_markets(&self) -> Vec<String> { 
-        vec!["spot".to_string(), "perpetual".to_string()] 
+    fn supported_markets(&self) -> Vec<String> {
+        vec!["spot".to_string(), "perpetual".to_string()]
     }
-    
+
     /// 返回适配器类型。
     fn adapter_type(&self) -> DexAdapterType { DexAdapterType::AMM }
 }
@@ -110,8 +396,72 @@ fn register_raydium_adapter() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::layout::pack_token_account;
     use anchor_lang::prelude::Pubkey;
 
+    /// 构造交易参数。
+    fn trade(from: Pubkey, to: Pubkey, amount_in: u64, min_amount_out: u64) -> TradeParams {
+        TradeParams {
+            trade_type: "swap".to_string(),
+            from_token: from,
+            to_token: to,
+            amount_in,
+            min_amount_out,
+            dex_name: "raydium".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        }
+    }
+
+    /// 池夹具：coin/pc 各 1_000_000_000，0.25% 手续费，coin 侧 1_000 待提取 PnL。
+    struct PoolFixture {
+        keys: [Pubkey; 3],
+        owners: [Pubkey; 3],
+        lamports: [u64; 3],
+        data: [Vec<u8>; 3],
+        coin_mint: Pubkey,
+        pc_mint: Pubkey,
+    }
+
+    impl PoolFixture {
+        fn new() -> Self {
+            let (pool, coin_vault, pc_vault) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+            let (coin_mint, pc_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+            let amm = RaydiumAmmInfo {
+                status: 6,
+                swap_fee_numerator: 25,
+                swap_fee_denominator: 10_000,
+                need_take_pnl_coin: 1_000,
+                need_take_pnl_pc: 0,
+                token_coin: coin_vault,
+                token_pc: pc_vault,
+                coin_mint,
+                pc_mint,
+            };
+            Self {
+                keys: [pool, coin_vault, pc_vault],
+                owners: [RAYDIUM_AMM_V4_PROGRAM_ID, anchor_spl::token::ID, anchor_spl::token::ID],
+                lamports: [0; 3],
+                data: [
+                    amm.pack(),
+                    pack_token_account(&coin_mint, &Pubkey::default(), 1_000_001_000),
+                    pack_token_account(&pc_mint, &Pubkey::default(), 1_000_000_000),
+                ],
+                coin_mint,
+                pc_mint,
+            }
+        }
+
+        fn infos(&mut self) -> Vec<AccountInfo<'_>> {
+            let mut infos = Vec::new();
+            for (((key, owner), lamports), data) in self.keys.iter().zip(self.owners.iter()).zip(self.lamports.iter_mut()).zip(self.data.iter_mut()) {
+                infos.push(AccountInfo::new(key, false, false, lamports, data, owner, false, 0));
+            }
+            infos
+        }
+    }
+
     /// 测试 RaydiumAdapter 名称。
     #[test]
     fn test_raydium_adapter_name() {
@@ -119,21 +469,101 @@ mod tests {
         assert_eq!(adapter.name(), "raydium");
     }
 
-    /// 测试 RaydiumAdapter swap 功能。
+    /// 测试 AmmInfo 序列化往返。
+    #[test]
+    fn test_amm_info_roundtrip() {
+        let fixture = PoolFixture::new();
+        let amm = RaydiumAmmInfo::unpack(&fixture.data[0]).unwrap();
+        assert_eq!(amm.coin_mint, fixture.coin_mint);
+        assert_eq!(amm.swap_fee_numerator, 25);
+        assert!(RaydiumAmmInfo::unpack(&fixture.data[0][..100]).is_none());
+    }
+
+    /// 测试基于池账户的常积报价。
+    #[test]
+    fn test_raydium_quote_from_pool_accounts() {
+        let adapter = RaydiumAdapter;
+        let mut fixture = PoolFixture::new();
+        let (coin_mint, pc_mint) = (fixture.coin_mint, fixture.pc_mint);
+        let infos = fixture.infos();
+        let quote = adapter.get_quote(&trade(coin_mint, pc_mint, 10_000_000, 0), &infos).unwrap();
+        assert_eq!(quote.fee, 25_000);
+        assert_eq!(quote.amount_out, 9_876_482); // 1e9 * 9_975_000 / 1_009_975_000
+        assert_eq!(quote.price_impact_bps, 98);
+        // 反向报价同样可用
+        let reverse = adapter.get_quote(&trade(pc_mint, coin_mint, 10_000_000, 0), &infos).unwrap();
+        assert_eq!(reverse.amount_out, 9_876_482);
+    }
+
+    /// 测试 min_amount_out 校验与无账户 swap。
     #[test]
     fn test_raydium_adapter_swap() {
         let adapter = RaydiumAdapter;
-        let params = TradeParams {
-            from_token: Pubkey::default(),
-            to_token: Pubkey::new_unique(), // 使用不同的token
-            amount_in: 100,
-            min_amount_out: 90,
-            dex_name: "raydium".to_string(),
+        let mut fixture = PoolFixture::new();
+        let (coin_mint, pc_mint) = (fixture.coin_mint, fixture.pc_mint);
+        let infos = fixture.infos();
+        let ok = adapter.quote_checked(&trade(coin_mint, pc_mint, 10_000_000, 9_800_000), &infos).unwrap();
+        assert_eq!(ok.amount_in, 10_000_000);
+        assert_eq!(ok.fee, 25_000);
+        assert!(adapter.quote_checked(&trade(coin_mint, pc_mint, 10_000_000, 9_900_000), &infos).is_err());
+        assert!(adapter.swap(&trade(coin_mint, pc_mint, 100, 90)).is_err());
+        // 不属于该池的代币对
+        assert!(adapter.get_quote(&trade(coin_mint, Pubkey::new_unique(), 100, 0), &infos).is_err());
+    }
+
+    /// 测试伪造池账户归属被拒绝。
+    #[test]
+    fn test_raydium_rejects_foreign_pool_owner() {
+        let adapter = RaydiumAdapter;
+        let mut fixture = PoolFixture::new();
+        fixture.owners[0] = Pubkey::new_unique();
+        let (coin_mint, pc_mint) = (fixture.coin_mint, fixture.pc_mint);
+        let infos = fixture.infos();
+        assert!(adapter.get_quote(&trade(coin_mint, pc_mint, 100, 0), &infos).is_err());
+    }
+
+    /// 测试 CLMM 逐 tick 报价：tick 100 以上流动性降为 1/4。
+    #[test]
+    fn test_raydium_clmm_quote() {
+        let adapter = RaydiumAdapter;
+        let (pool, config) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mint_0, mint_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let state = RaydiumClmmPool {
+            amm_config: config,
+            token_mint_0: mint_0,
+            token_mint_1: mint_1,
+            tick_spacing: 10,
+            liquidity: 4_000_000_000_000,
+            sqrt_price_x64: clmm::Q64,
+            tick_current: 0,
         };
-        let result = adapter.swap(&params);
-        assert!(result.is_ok());
+        let keys = [pool, config, Pubkey::new_unique()];
+        let mut data = [
+            state.pack(),
+            RaydiumClmmPool::pack_config(2_500),
+            RaydiumClmmTickArray::pack(&pool, 0, 10, &[ClmmTick { index: 100, liquidity_net: -3_000_000_000_000 }]),
+        ];
+        let mut lamports = [0u64; 3];
+        let infos: Vec<AccountInfo> = keys.iter().zip(data.iter_mut()).zip(lamports.iter_mut())
+            .map(|((key, d), l)| AccountInfo::new(key, false, false, l, d, &RAYDIUM_CLMM_PROGRAM_ID, false, 0))
+            .collect();
+        let small = adapter.quote_clmm(&trade(mint_1, mint_0, 1_000_000, 0), &infos).unwrap();
+        assert_eq!(small.fee, 2_500);
+        assert!(small.amount_out >= 997_000 && small.amount_out <= 997_500);
+        assert_eq!(small.ticks_crossed, 0);
+        let large = adapter.quote_clmm(&trade(mint_1, mint_0, 40_000_000_000, 0), &infos).unwrap();
+        assert_eq!(large.ticks_crossed, 1);
+        assert!(large.tick_after > 100);
+        assert!(large.price_impact_bps > 100);
+        // 通用接口：min_amount_out 校验
+        assert!(adapter.quote_checked(&trade(mint_1, mint_0, 1_000_000, small.amount_out), &infos).is_ok());
+        assert!(adapter.quote_checked(&trade(mint_1, mint_0, 1_000_000, small.amount_out + 1), &infos).is_err());
+        // tick array 仅覆盖 [0, 600)，token0→token1 方向无可用区间
+        assert!(adapter.quote_clmm(&trade(mint_0, mint_1, 1_000_000, 0), &infos).is_err());
+        // 缺少 tick array
+        assert!(adapter.quote_clmm(&trade(mint_1, mint_0, 1_000_000, 0), &infos[..2]).is_err());
     }
-    
+
     /// 测试 RaydiumAdapter 支持的资产。
     #[test]
     fn test_raydium_supported_assets() {
@@ -143,4 +573,4 @@ mod tests {
         assert!(assets.contains(&"USDC".to_string()));
         assert!(assets.contains(&"RAY".to_string()));
     }
-} 
\ No newline at end of file
+}
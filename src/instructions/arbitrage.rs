@@ -13,26 +13,65 @@ pub struct ArbitrageTrade<'info> {
     pub authority: Signer<'info>,
 }
 
-pub fn arbitrage_trade(ctx: Context<ArbitrageTrade>, params: Vec<TradeParams>, min_profit: u64) -> anchor_lang::Result<()> {
+/// 跨市场套利：params 为首尾相接的兑换腿，逐腿按 remaining_accounts 中的池账户报价，取最大预期利润（仅报价校验，不转移代币）
+/// - 兑换腿按 mint 切分为闭合环路，后一腿的输入为前一腿的报价输出；利润 = 环路最终输出 - 起始输入，二者同为起始 mint
+/// - pool_account_counts: 每条腿在 remaining_accounts 中依次占用的池账户数量；含 0 个池账户腿的环路无法按池状态报价，不计入利润
+pub fn arbitrage_trade(ctx: Context<ArbitrageTrade>, params: Vec<TradeParams>, pool_account_counts: Vec<u8>, min_profit: u64) -> anchor_lang::Result<()> {
     let mut asset = ctx.accounts.asset;
-    // 业务逻辑：多DEX路径发现与套利执行（示例：遍历所有路径，取最大利润）
+    require!(pool_account_counts.len() == params.len(), crate::dex::adapter::DexAdapterError::MissingPoolAccounts);
+    let cycles = split_cycles(&params)?; // 未闭合的路径无法以同一单位计算利润
+    // 按腿切分池账户
+    let mut pools: Vec<&[AccountInfo]> = Vec::with_capacity(params.len());
+    let mut offset = 0usize;
+    for count in pool_account_counts.iter() {
+        let end = offset + *count as usize;
+        pools.push(ctx.remaining_accounts.get(offset..end).ok_or(crate::dex::adapter::DexAdapterError::MissingPoolAccounts)?);
+        offset = end;
+    }
     let mut best_profit = 0u64;
-    for trade in params.iter() {
-        let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
-        if let Some(adapter) = factory.get(&trade.dex_name) {
-            if let Some(dex_adapter) = adapter.as_any().downcast_ref::<Arc<dyn crate::dex::traits::DexAdapter>>() {
-                let result = dex_adapter.swap(trade)?;
-                if result.profit > best_profit {
-                    best_profit = result.profit;
-                }
+    // 工厂锁只取一次；锁中毒时返回错误而非 panic
+    let factory = crate::core::registry::ADAPTER_FACTORY.lock().map_err(|_| crate::dex::adapter::DexAdapterError::AdapterRegistryUnavailable)?;
+    'cycles: for cycle in cycles {
+        let start_amount = params[cycle.start].amount_in;
+        let mut amount = start_amount; // 沿环路传递的数量
+        for i in cycle {
+            if pools[i].is_empty() {
+                continue 'cycles; // 未携带池账户的腿无真实报价，整条环路不计入利润
             }
+            let Some(dex_adapter) = factory
+                .get(&params[i].dex_name)
+                .and_then(|adapter| adapter.as_any().downcast_ref::<Arc<dyn crate::dex::adapter::DexAdapter>>())
+            else {
+                continue 'cycles; // 未注册的 DEX 无法报价
+            };
+            let leg = TradeParams { amount_in: amount, ..params[i].clone() }; // 输入为上一腿的报价输出
+            amount = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &leg, pools[i])?.amount_out;
         }
+        best_profit = best_profit.max(amount.saturating_sub(start_amount)); // 起止同为一个 mint，可直接相减
     }
     require!(best_profit >= min_profit, crate::errors::ProgramError::ArbitrageNotProfitable);
     // 事件、状态更新等
     Ok(())
 }
 
+/// 将首尾相接的兑换腿切分为闭合环路（回到起始 mint 即闭合），返回各环路的腿下标区间
+/// - 相邻两腿 mint 不相接返回 TokenPairMismatch，末尾未闭合返回 OpenArbitrageCycle
+fn split_cycles(params: &[TradeParams]) -> anchor_lang::Result<Vec<std::ops::Range<usize>>> {
+    let mut cycles = Vec::new();
+    let mut start = 0usize;
+    for (i, trade) in params.iter().enumerate() {
+        if i > start {
+            require_keys_eq!(params[i - 1].to_token, trade.from_token, crate::dex::adapter::DexAdapterError::TokenPairMismatch); // 腿必须首尾相接
+        }
+        if trade.to_token == params[start].from_token {
+            cycles.push(start..i + 1); // 回到起始 mint，环路闭合
+            start = i + 1;
+        }
+    }
+    require!(start == params.len(), crate::dex::adapter::DexAdapterError::OpenArbitrageCycle);
+    Ok(cycles)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +107,33 @@ mod tests {
         let best_profit = 20u64;
         assert!(best_profit >= min_profit);
     }
+
+    fn leg(from_token: Pubkey, to_token: Pubkey) -> TradeParams {
+        TradeParams {
+            trade_type: "swap".to_string(),
+            from_token,
+            to_token,
+            amount_in: 100,
+            min_amount_out: 0,
+            dex_name: "meteora".to_string(),
+            algo_params: None,
+            strategy_params: None,
+            oracle_params: None,
+        }
+    }
+
+    #[test]
+    fn test_split_cycles() {
+        let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        // USDC→SOL→USDC 与 USDC→SOL→BONK→USDC 两条闭合环路
+        let legs = vec![leg(usdc, sol), leg(sol, usdc), leg(usdc, sol), leg(sol, bonk), leg(bonk, usdc)];
+        assert_eq!(split_cycles(&legs).unwrap(), vec![0..2, 2..5]);
+        // 单腿 USDC→SOL 未回到 USDC，输出与输入不同单位
+        assert_eq!(
+            split_cycles(&[leg(usdc, sol)]).unwrap_err(),
+            crate::dex::adapter::DexAdapterError::OpenArbitrageCycle.into()
+        );
+        // 相邻腿不相接
+        assert!(split_cycles(&[leg(usdc, sol), leg(bonk, usdc)]).is_err());
+    }
 } 
\ No newline at end of file
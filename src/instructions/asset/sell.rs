@@ -50,6 +50,7 @@ pub fn sell_asset(
         exec_params,
         strategy_params,
         oracle_params,
+        ctx.remaining_accounts, // DEX 池账户
     )?;
     // 触发链上事件，便于审计与追踪
     emit!(AssetSold {
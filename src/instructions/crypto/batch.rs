@@ -44,7 +44,12 @@ pub struct BatchCrypto<'info> {
     pub authority: Signer<'info>,
     
     /// DEX程序，用于交易执行
-    pub dex_program: Program<'info, crate::dex::traits::DexAdapterTrait>,
+    /// CHECK: 可执行且程序 ID 属于已接入 DEX，交易数据由对应 DEX 适配器解析
+    #[account(
+        executable,
+        constraint = crate::dex::adapter::is_supported_dex_program(dex_program.key) @ crate::dex::adapter::DexAdapterError::UnsupportedDexProgram
+    )]
+    pub dex_program: UncheckedAccount<'info>,
     
     /// 预言机程序，用于价格数据
     pub oracle_program: Program<'info, crate::oracles::traits::OracleAdapterTrait>,
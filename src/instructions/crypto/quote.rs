@@ -16,7 +16,6 @@ use crate::services::crypto_service::CryptoService;
 use crate::events::asset_event::AssetQuoted;
 use crate::validation::business::validate_quote_params;
 use crate::core::security::check_authority_permission;
-use crate::dex::traits::DexAdapterTrait;
 
 /// 加密货币报价指令账户上下文
 /// 
@@ -44,7 +43,12 @@ pub struct QuoteCrypto<'info> {
     pub authority: Option<Signer<'info>>,
     
     /// DEX程序，用于获取报价
-    pub dex_program: Program<'info, crate::dex::traits::DexAdapterTrait>,
+    /// CHECK: 可执行且程序 ID 属于已接入 DEX，交易数据由对应 DEX 适配器解析
+    #[account(
+        executable,
+        constraint = crate::dex::adapter::is_supported_dex_program(dex_program.key) @ crate::dex::adapter::DexAdapterError::UnsupportedDexProgram
+    )]
+    pub dex_program: UncheckedAccount<'info>,
     
     /// 预言机程序，用于价格验证
     pub oracle_program: Program<'info, crate::oracles::traits::OracleAdapterTrait>,
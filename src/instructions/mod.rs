@@ -27,3 +27,7 @@ pub mod batch_trade; // 批量交易指令集子模块声明
 pub mod dex;        // DEX 相关指令集子模块声明
 pub mod oracles;    // 预言机相关指令集子模块声明
 pub mod strategies; // 策略相关指令集子模块声明
+pub mod twap_order; // TWAP订单分片执行指令集子模块声明
+pub mod pov_order; // POV订单成交量驱动执行指令集子模块声明
+pub mod price_history; // 价格历史观测记录指令集子模块声明
+pub mod offchain_request; // 链下请求与keeper响应指令集子模块声明
@@ -0,0 +1,144 @@
+//! Off-chain request instruction set: create, respond, close keeper-fulfilled DEX/oracle requests (PDA持久化/指定响应者/request_id回填)
+use anchor_lang::prelude::*; // 引入Anchor框架预导入模块，包含Solana程序开发常用类型与宏
+use crate::account_models::pending_request_account::{OffchainRequestKind, PendingRequest, PendingRequestError, PendingRequestStatus}; // 引入链下请求账户与错误码
+use crate::core::events::{OffchainDexRequest, OffchainOracleRequest, OffchainResponse}; // 引入链下请求/响应事件
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)] // 派生Anchor序列化/反序列化、克隆、调试特性
+pub struct CreateOffchainRequestParams { // 定义创建链下请求参数结构体
+    pub request_id: u64, // 请求编号，参与PDA推导
+    pub kind: OffchainRequestKind, // 请求类别（DEX/预言机）
+    pub target: String, // 目标DEX/预言机名称
+    pub action: String, // 操作类型（swap/quote/price/twap）
+    pub params: Vec<u8>, // 请求参数（JSON序列化）
+    pub responder: Pubkey, // 指定响应者（keeper）
+    pub ttl_secs: i64, // 有效期（秒）
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+#[instruction(params: CreateOffchainRequestParams)] // 引入指令参数，用于PDA种子推导
+pub struct CreateOffchainRequest<'info> { // 定义创建链下请求指令的账户上下文结构体
+    #[account(
+        init, // Anchor属性，指示账户初始化
+        payer = requester, // 由请求人支付租金
+        space = 8 + PendingRequest::INIT_SPACE, // 分配账户空间，8字节discriminator
+        seeds = [b"pending_request", requester.key().as_ref(), params.request_id.to_le_bytes().as_ref()], // PDA种子，确保唯一性
+        bump // 自动推断bump种子
+    )]
+    pub pending_request: Account<'info, PendingRequest>, // 新建链下请求账户，类型安全
+    #[account(mut)] // Anchor属性，标记账户为可变，支付租金
+    pub requester: Signer<'info>, // 请求人签名者，类型安全
+    pub system_program: Program<'info, System>, // 系统程序，Anchor自动校验
+}
+
+pub fn create_offchain_request(
+    ctx: Context<CreateOffchainRequest>, // Anchor账户上下文，自动校验权限与生命周期
+    params: CreateOffchainRequestParams, // 请求参数，类型安全
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    PendingRequest::validate_request(
+        params.kind,
+        &params.target,
+        &params.action,
+        &params.params,
+        &params.responder,
+        params.ttl_secs,
+    )?; // 校验目标、操作、参数长度、响应者与有效期
+    let clock = Clock::get()?; // 获取链上时钟
+    let request = &mut ctx.accounts.pending_request; // 获取可变请求账户
+    request.request_id = params.request_id; // 请求编号
+    request.requester = ctx.accounts.requester.key(); // 请求人
+    request.responder = params.responder; // 指定响应者
+    request.kind = params.kind; // 请求类别
+    request.target = params.target; // 目标名称
+    request.action = params.action; // 操作类型
+    request.params = params.params; // 请求参数
+    request.status = PendingRequestStatus::Pending; // 等待响应
+    request.result = Vec::new(); // 尚无结果
+    request.error_msg = String::new(); // 尚无错误信息
+    request.created_at = clock.unix_timestamp; // 创建时间
+    request.expires_at = clock
+        .unix_timestamp
+        .checked_add(params.ttl_secs)
+        .ok_or(PendingRequestError::MathOverflow)?; // 过期时间
+    request.responded_at = 0; // 尚未响应
+    request.bump = ctx.bumps.pending_request; // PDA bump
+    match request.kind {
+        OffchainRequestKind::Dex => emit!(OffchainDexRequest { // 触发链下DEX请求事件，keeper监听
+            request_id: request.request_id, // 事件：请求编号
+            dex_name: request.target.clone(), // 事件：DEX名称
+            action: request.action.clone(), // 事件：操作类型
+            params: request.params.clone(), // 事件：请求参数
+            requester: request.requester, // 事件：请求人
+            timestamp: clock.unix_timestamp, // 事件：链上时间戳
+            responder: request.responder, // 事件：指定响应者
+            expires_at: request.expires_at, // 事件：过期时间
+        }),
+        OffchainRequestKind::Oracle => emit!(OffchainOracleRequest { // 触发链下预言机请求事件，keeper监听
+            request_id: request.request_id, // 事件：请求编号
+            oracle_name: request.target.clone(), // 事件：预言机名称
+            action: request.action.clone(), // 事件：操作类型
+            params: request.params.clone(), // 事件：请求参数
+            requester: request.requester, // 事件：请求人
+            timestamp: clock.unix_timestamp, // 事件：链上时间戳
+            responder: request.responder, // 事件：指定响应者
+            expires_at: request.expires_at, // 事件：过期时间
+        }),
+    }
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+#[instruction(request_id: u64)] // 引入请求编号，参与PDA种子校验
+pub struct SubmitOffchainResponse<'info> { // 定义回填链下响应指令的账户上下文结构体
+    #[account(
+        mut, // 写入响应结果
+        seeds = [b"pending_request", pending_request.requester.as_ref(), request_id.to_le_bytes().as_ref()], // 以指令参数中的request_id校验PDA
+        bump = pending_request.bump // 使用存储的bump
+    )]
+    pub pending_request: Account<'info, PendingRequest>, // 链下请求账户，类型安全
+    pub responder: Signer<'info>, // 响应者签名者，需为请求指定的keeper
+}
+
+pub fn submit_offchain_response(
+    ctx: Context<SubmitOffchainResponse>, // Anchor账户上下文，自动校验权限与生命周期
+    request_id: u64, // 请求编号，需与账户记录一致
+    success: bool, // 是否成功
+    result: Vec<u8>, // 结果数据（JSON序列化）
+    error_msg: Option<String>, // 失败时的错误描述
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    let responder = ctx.accounts.responder.key(); // 响应者公钥
+    let request = &mut ctx.accounts.pending_request; // 获取可变请求账户
+    request.assert_respondable(&responder, request_id, clock.unix_timestamp)?; // 校验响应者、编号、状态与有效期
+    request.record_response(success, result.clone(), error_msg.as_deref(), clock.unix_timestamp)?; // 写入结果并更新状态
+    emit!(OffchainResponse { // 触发链下响应事件，链上可追溯
+        request_id, // 事件：请求编号
+        success, // 事件：是否成功
+        result, // 事件：结果数据
+        error_msg, // 事件：错误描述
+        responder, // 事件：响应者
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct CloseOffchainRequest<'info> { // 定义关闭链下请求指令的账户上下文结构体
+    #[account(
+        mut, // 关闭账户
+        has_one = requester, // 仅请求人可关闭
+        close = requester, // 关闭请求PDA，租金退还请求人
+        seeds = [b"pending_request", requester.key().as_ref(), pending_request.request_id.to_le_bytes().as_ref()], // 校验PDA
+        bump = pending_request.bump // 使用存储的bump
+    )]
+    pub pending_request: Account<'info, PendingRequest>, // 链下请求账户，类型安全
+    #[account(mut)] // Anchor属性，标记账户为可变，接收租金
+    pub requester: Signer<'info>, // 请求人签名者，类型安全
+}
+
+pub fn close_offchain_request(
+    ctx: Context<CloseOffchainRequest>, // Anchor账户上下文，自动校验权限与生命周期
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    ctx.accounts.pending_request.assert_closable(clock.unix_timestamp)?; // 已响应或已过期才可关闭
+    Ok(()) // 账户由Anchor close约束关闭
+}
@@ -1,6 +1,8 @@
 //! Oracle instruction set: register, query, switch oracle adapters (PDA持久化/权限校验/事件日志)
 use anchor_lang::prelude::*; // 引入Anchor框架预导入模块，包含Solana程序开发常用类型与宏
 use crate::account_models::oracle_registry_account::{OracleRegistryAccount, OracleMeta}; // 引入Oracle注册表账户与元数据结构体
+use crate::account_models::oracle_policy_account::{OraclePolicy, OracleSourceConfig, ResolvedOraclePrice}; // 引入Oracle来源策略账户
+use crate::core::events::OraclePriceQueried; // 引入预言机价格查询事件
 
 #[event] // Anchor事件宏，自动生成链上事件日志结构体
 pub struct OracleRegistered { // 定义Oracle注册事件结构体
@@ -137,4 +139,123 @@ pub fn init_oracle_registry(
         timestamp: Clock::get()?.unix_timestamp, // 事件：链上时间戳
     });
     Ok(()) // Anchor规范返回，生命周期自动管理
-} 
\ No newline at end of file
+} 
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct OraclePolicyUpdated { // 定义Oracle来源策略更新事件结构体
+    pub mint: Pubkey, // 资产mint
+    pub sources: Vec<Pubkey>, // 按优先级排列的来源账户
+    pub authority: Pubkey, // 操作人公钥
+    pub timestamp: i64, // 更新时间戳，链上可追溯
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)] // 派生Anchor序列化/反序列化、克隆、调试特性
+pub struct OraclePolicyParams { // 定义Oracle来源策略参数结构体
+    pub quote_mint: Pubkey, // 报价资产mint
+    pub sources: Vec<OracleSourceConfig>, // 主/备/第三来源（按优先级）
+    pub max_staleness_secs: u64, // 最大时延（秒）
+    pub max_confidence_bps: u64, // 最大置信区间（基点）
+    pub max_deviation_bps: u64, // 来源间最大偏差（基点）
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+#[instruction(mint: Pubkey)] // 引入指令参数，用于PDA种子推导
+pub struct InitOraclePolicy<'info> { // 定义Oracle来源策略初始化指令的账户上下文结构体
+    #[account(
+        init, // Anchor属性，指示账户初始化
+        payer = authority, // 由authority账户支付租金
+        space = 8 + OraclePolicy::INIT_SPACE, // 分配账户空间，8字节discriminator
+        seeds = [b"oracle_policy", authority.key().as_ref(), mint.as_ref()], // PDA种子，按管理人与mint唯一，防止抢先初始化他人策略
+        bump // 自动推断bump种子
+    )]
+    pub policy: Account<'info, OraclePolicy>, // 新建Oracle来源策略账户，类型安全
+    #[account(mut)] // Anchor属性，标记账户为可变，支付租金
+    pub authority: Signer<'info>, // 策略管理人签名者，类型安全
+    pub system_program: Program<'info, System>, // 系统程序，Anchor自动校验
+}
+
+/// 写入策略参数并校验
+fn apply_oracle_policy(policy: &mut OraclePolicy, params: OraclePolicyParams, now: i64) -> anchor_lang::Result<()> {
+    policy.quote_mint = params.quote_mint; // 报价资产
+    policy.sources = params.sources; // 来源回退链
+    policy.max_staleness_secs = params.max_staleness_secs; // 最大时延
+    policy.max_confidence_bps = params.max_confidence_bps; // 最大置信区间
+    policy.max_deviation_bps = params.max_deviation_bps; // 最大跨源偏差
+    policy.last_updated = now; // 更新时间
+    policy.validate_config() // 校验来源与阈值
+}
+
+pub fn init_oracle_policy(
+    ctx: Context<InitOraclePolicy>, // Anchor账户上下文，自动校验权限与生命周期
+    mint: Pubkey, // 资产mint，参与PDA推导
+    params: OraclePolicyParams, // 策略参数，类型安全
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let now = Clock::get()?.unix_timestamp; // 获取链上时间戳
+    let policy = &mut ctx.accounts.policy; // 获取可变策略账户
+    policy.authority = ctx.accounts.authority.key(); // 策略管理人
+    policy.mint = mint; // 资产mint
+    policy.bump = ctx.bumps.policy; // PDA bump
+    apply_oracle_policy(policy, params, now)?; // 写入并校验策略
+    emit!(OraclePolicyUpdated { // 触发策略更新事件，链上可追溯
+        mint, // 事件：资产mint
+        sources: policy.sources.iter().map(|source| source.oracle_account).collect(), // 事件：来源账户
+        authority: policy.authority, // 事件：操作人
+        timestamp: now, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct UpdateOraclePolicy<'info> { // 定义Oracle来源策略更新指令的账户上下文结构体
+    #[account(
+        mut, // 更新策略
+        has_one = authority, // 仅策略管理人可更新
+        seeds = [b"oracle_policy", policy.authority.as_ref(), policy.mint.as_ref()], // 校验PDA
+        bump = policy.bump // 使用存储的bump
+    )]
+    pub policy: Account<'info, OraclePolicy>, // Oracle来源策略账户，类型安全
+    pub authority: Signer<'info>, // 策略管理人签名者，类型安全
+}
+
+pub fn update_oracle_policy(
+    ctx: Context<UpdateOraclePolicy>, // Anchor账户上下文，自动校验权限与生命周期
+    params: OraclePolicyParams, // 策略参数，类型安全
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let now = Clock::get()?.unix_timestamp; // 获取链上时间戳
+    let policy = &mut ctx.accounts.policy; // 获取可变策略账户
+    apply_oracle_policy(policy, params, now)?; // 写入并校验策略
+    emit!(OraclePolicyUpdated { // 触发策略更新事件，链上可追溯
+        mint: policy.mint, // 事件：资产mint
+        sources: policy.sources.iter().map(|source| source.oracle_account).collect(), // 事件：来源账户
+        authority: policy.authority, // 事件：操作人
+        timestamp: now, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct ResolveOraclePrice<'info> { // 定义按来源策略解析价格指令的账户上下文结构体，任何人均可调用，调用方按信任的管理人推导策略PDA
+    #[account(
+        seeds = [b"oracle_policy", policy.authority.as_ref(), policy.mint.as_ref()], // 校验PDA
+        bump = policy.bump // 使用存储的bump
+    )]
+    pub policy: Account<'info, OraclePolicy>, // 只读Oracle来源策略账户，类型安全
+}
+
+pub fn resolve_oracle_price(
+    ctx: Context<ResolveOraclePrice>, // Anchor账户上下文，remaining_accounts须携带全部已配置来源的预言机账户
+) -> anchor_lang::Result<ResolvedOraclePrice> { // Anchor规范返回类型，返回解析结果与实际使用的来源
+    let clock = Clock::get()?; // 获取链上时钟
+    let policy = &ctx.accounts.policy; // 获取只读策略账户
+    let resolved = policy.resolve(ctx.remaining_accounts, clock.unix_timestamp, clock.slot)?; // 按回退链解析价格
+    emit!(OraclePriceQueried { // 触发价格查询事件，记录实际使用的来源
+        oracle: resolved.source.name().to_string(), // 事件：来源名称
+        base_mint: policy.mint, // 事件：基础资产
+        quote_mint: policy.quote_mint, // 事件：报价资产
+        price: resolved.price, // 事件：价格
+        source_index: resolved.source_index, // 事件：来源下标
+        confirmed: resolved.confirmed, // 事件：是否经交叉确认
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(resolved) // 返回解析结果
+}
@@ -0,0 +1,409 @@
+//! POV order instruction set: create, report volume, crank, cancel persistent POV orders (PDA持久化/代币托管/成交量上报/无许可crank/keeper小费)
+use anchor_lang::prelude::*; // 引入Anchor框架预导入模块，包含Solana程序开发常用类型与宏
+use anchor_lang::system_program; // 引入系统程序CPI，用于预存keeper小费
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer}; // 引入SPL Token CPI，用于订单金库托管
+use crate::account_models::pov_order_account::{PovOrder, PovOrderError, PovOrderStatus, MAX_POV_DEX_NAME_LEN, MAX_POV_SLICES}; // 引入POV订单账户与错误码
+use crate::algorithms::pov::{PovEngine, VolumeObservation}; // 引入POV执行引擎
+use crate::core::types::TradeParams; // 引入统一交易参数，用于DEX适配器路由
+use crate::dex::adapter::DexAdapterError; // 引入DEX适配器错误码
+use crate::instructions::twap_order::{drain_and_close_vault, execute_escrowed_swap, EscrowSwapAccounts}; // 复用TWAP订单的托管成交与金库清算
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct PovOrderCreated { // 定义POV订单创建事件结构体
+    pub order: Pubkey, // 订单PDA地址
+    pub owner: Pubkey, // 下单人公钥
+    pub total_amount: u64, // 订单总输入数量
+    pub target_rate_bps: u16, // 目标参与率（基点）
+    pub volume_reporter: Pubkey, // 成交量上报者
+    pub timestamp: i64, // 创建时间戳，链上可追溯
+}
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct PovVolumeReported { // 定义POV市场成交量上报事件结构体
+    pub order: Pubkey, // 订单PDA地址
+    pub volume: u64, // 增量市场成交量
+    pub price: u64, // 最新成交价格（精度1e6）
+    pub market_volume: u64, // 累计市场成交量
+    pub timestamp: i64, // 上报时间戳，链上可追溯
+}
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct PovSliceExecuted { // 定义POV分片执行事件结构体
+    pub order: Pubkey, // 订单PDA地址
+    pub keeper: Pubkey, // 执行分片的keeper
+    pub slice_index: u32, // 分片索引
+    pub amount_in: u64, // 分片输入数量
+    pub amount_out: u64, // 分片输出数量
+    pub realized_rate_bps: u64, // 成交后实际参与率（基点）
+    pub tip_lamports: u64, // 支付给keeper的小费
+    pub slot: u64, // 执行slot
+    pub timestamp: i64, // 执行时间戳，链上可追溯
+}
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct PovOrderCancelled { // 定义POV订单撤销事件结构体
+    pub order: Pubkey, // 订单PDA地址
+    pub owner: Pubkey, // 下单人公钥
+    pub filled_amount: u64, // 撤单时已成交输入数量
+    pub remaining_amount: u64, // 撤单时剩余未成交数量
+    pub realized_rate_bps: u64, // 实际参与率（基点）
+    pub participation_shortfall: u64, // 按目标参与率的成交缺口
+    pub price_shortfall_bps: i64, // 相对到达价格的执行缺口（基点）
+    pub refunded_amount: u64, // 退还下单人的输入金库余额
+    pub output_amount: u64, // 转给下单人的输出金库余额
+    pub timestamp: i64, // 撤单时间戳，链上可追溯
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)] // 派生Anchor序列化/反序列化、克隆、调试特性
+pub struct CreatePovOrderParams { // 定义创建POV订单参数结构体
+    pub order_id: u64, // 订单编号，参与PDA推导
+    pub input_mint: Pubkey, // 输入token mint
+    pub output_mint: Pubkey, // 输出token mint
+    pub total_amount: u64, // 总输入数量
+    pub target_rate_bps: u16, // 目标参与率（基点）
+    pub min_rate_bps: u16, // 参与率下限（基点）
+    pub max_rate_bps: u16, // 参与率上限（基点）
+    pub max_price_deviation_bps: u16, // 单片价格偏离上限（基点）
+    pub min_slice_amount: u64, // 最小分片数量
+    pub arrival_price: u64, // 到达价格（精度1e6）
+    pub volume_reporter: Pubkey, // 指定成交量上报者
+    pub slot_interval: u64, // 分片最小间隔（slot）
+    pub max_slices: u32, // 最大分片数量
+    pub dex_name: String, // 路由DEX适配器名称
+    pub pool: Pubkey, // 成交池/市场账户，创建后锁定
+    pub keeper_tip_lamports: u64, // 每个分片支付给keeper的小费
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+#[instruction(params: CreatePovOrderParams)] // 引入指令参数，用于PDA种子推导
+pub struct CreatePovOrder<'info> { // 定义创建POV订单指令的账户上下文结构体
+    #[account(
+        init, // Anchor属性，指示账户初始化
+        payer = owner, // 由下单人支付租金
+        space = 8 + PovOrder::INIT_SPACE, // 分配账户空间，8字节discriminator
+        seeds = [b"pov_order", owner.key().as_ref(), params.order_id.to_le_bytes().as_ref()], // PDA种子，确保唯一性
+        bump // 自动推断bump种子
+    )]
+    pub pov_order: Account<'info, PovOrder>, // 新建POV订单账户，类型安全
+    #[account(address = params.input_mint)] // 输入mint需与参数一致
+    pub input_mint: Account<'info, Mint>, // 输入token mint
+    #[account(address = params.output_mint)] // 输出mint需与参数一致
+    pub output_mint: Account<'info, Mint>, // 输出token mint
+    #[account(
+        init, // 初始化输入金库
+        payer = owner, // 由下单人支付租金
+        seeds = [b"pov_input_vault", pov_order.key().as_ref()], // 金库PDA种子
+        bump, // 自动推断bump种子
+        token::mint = input_mint, // 托管输入资产
+        token::authority = pov_order // 仅订单PDA可动用
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>, // 输入金库，托管全部待执行输入
+    #[account(
+        init, // 初始化输出金库
+        payer = owner, // 由下单人支付租金
+        seeds = [b"pov_output_vault", pov_order.key().as_ref()], // 金库PDA种子
+        bump, // 自动推断bump种子
+        token::mint = output_mint, // 接收输出资产
+        token::authority = pov_order // 仅订单PDA可动用
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>, // 输出金库，累计分片成交输出
+    #[account(
+        mut, // 转出输入资产
+        token::mint = input_mint, // 输入资产账户
+        token::authority = owner // 下单人持有
+    )]
+    pub owner_input_account: Box<Account<'info, TokenAccount>>, // 下单人输入资产账户
+    #[account(mut)] // Anchor属性，标记账户为可变，支付租金与小费
+    pub owner: Signer<'info>, // 下单人签名者，类型安全
+    pub token_program: Program<'info, Token>, // SPL Token程序
+    pub system_program: Program<'info, System>, // 系统程序，Anchor自动校验
+}
+
+pub fn create_pov_order(
+    ctx: Context<CreatePovOrder>, // Anchor账户上下文，自动校验权限与生命周期
+    params: CreatePovOrderParams, // 创建参数，类型安全
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    require!(params.input_mint != params.output_mint, PovOrderError::InvalidMints); // 输入输出资产不能相同
+    require!(params.slot_interval > 0, PovOrderError::InvalidSlotInterval); // 分片间隔必须为正
+    require!(
+        params.max_slices > 0 && params.max_slices <= MAX_POV_SLICES,
+        PovOrderError::InvalidSliceCount
+    ); // 分片数量需在 [1, MAX_POV_SLICES]
+    require!(
+        !params.dex_name.is_empty() && params.dex_name.len() <= MAX_POV_DEX_NAME_LEN,
+        PovOrderError::InvalidDexName
+    ); // DEX名称长度需合法
+    require!(params.volume_reporter != Pubkey::default(), PovOrderError::InvalidReporter); // 必须指定成交量上报者
+    require!(params.pool != Pubkey::default(), PovOrderError::PoolMismatch); // 必须指定成交池
+    let dex = crate::dex::adapter::escrow_swap_adapter(&params.dex_name)
+        .ok_or(PovOrderError::DexAdapterNotFound)?; // 按名称静态选取支持swap CPI的适配器
+    require!(dex.supports_swap_cpi(), DexAdapterError::SwapCpiUnsupported); // 分片须经swap CPI成交，拒绝无法成交的适配器
+    let engine = PovEngine::new(
+        params.total_amount,
+        params.target_rate_bps,
+        params.min_rate_bps,
+        params.max_rate_bps,
+        params.max_price_deviation_bps,
+        params.min_slice_amount,
+        params.arrival_price,
+    )?; // 校验参与率带宽并初始化执行引擎
+    let clock = Clock::get()?; // 获取链上时钟
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(), // SPL Token程序
+            Transfer {
+                from: ctx.accounts.owner_input_account.to_account_info(), // 下单人输入资产账户
+                to: ctx.accounts.input_vault.to_account_info(), // 订单输入金库
+                authority: ctx.accounts.owner.to_account_info(), // 下单人签名
+            },
+        ),
+        params.total_amount, // 全部输入数量
+    )?; // 将订单总输入托管至订单PDA金库
+    let tip_budget = params
+        .keeper_tip_lamports
+        .checked_mul(params.max_slices as u64)
+        .ok_or(PovOrderError::MathOverflow)?; // 按最大分片数量计算小费预算
+    if tip_budget > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(), // 系统程序
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(), // 下单人
+                    to: ctx.accounts.pov_order.to_account_info(), // 订单PDA
+                },
+            ),
+            tip_budget, // 预存小费
+        )?; // 将小费预算转入订单PDA
+    }
+    let order = &mut ctx.accounts.pov_order; // 获取可变订单账户
+    order.owner = ctx.accounts.owner.key(); // 下单人
+    order.order_id = params.order_id; // 订单编号
+    order.input_mint = params.input_mint; // 输入资产
+    order.output_mint = params.output_mint; // 输出资产
+    order.dex_name = params.dex_name; // DEX适配器名称
+    order.pool = params.pool; // 锁定成交池
+    order.volume_reporter = params.volume_reporter; // 成交量上报者
+    order.engine = engine; // 执行引擎状态
+    order.slot_interval = params.slot_interval; // 分片间隔
+    order.next_eligible_slot = clock.slot; // 首个分片待成交量上报后即可执行
+    order.max_slices = params.max_slices; // 最大分片数量
+    order.keeper_tip_lamports = params.keeper_tip_lamports; // keeper小费
+    order.status = PovOrderStatus::Active; // 订单进入执行中
+    order.created_at = clock.unix_timestamp; // 创建时间
+    order.last_fill_at = 0; // 尚未成交
+    order.bump = ctx.bumps.pov_order; // PDA bump
+    emit!(PovOrderCreated { // 触发订单创建事件，链上可追溯
+        order: order.key(), // 事件：订单地址
+        owner: order.owner, // 事件：下单人
+        total_amount: order.engine.total_amount, // 事件：总数量
+        target_rate_bps: order.engine.target_rate_bps, // 事件：目标参与率
+        volume_reporter: order.volume_reporter, // 事件：成交量上报者
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct ReportPovVolume<'info> { // 定义上报市场成交量指令的账户上下文结构体
+    #[account(
+        mut, // 写入成交量观测
+        seeds = [b"pov_order", pov_order.owner.as_ref(), pov_order.order_id.to_le_bytes().as_ref()], // 校验PDA
+        bump = pov_order.bump // 使用存储的bump
+    )]
+    pub pov_order: Account<'info, PovOrder>, // POV订单账户，类型安全
+    pub reporter: Signer<'info>, // 成交量上报者签名者，需为订单指定的上报者
+}
+
+pub fn report_pov_volume(
+    ctx: Context<ReportPovVolume>, // Anchor账户上下文，自动校验权限与生命周期
+    volume: u64, // 自上次上报以来的增量市场成交量（不含本订单成交）
+    price: u64, // 最新成交价格（精度1e6）
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    let order = &mut ctx.accounts.pov_order; // 获取可变订单账户
+    order.assert_reporter(&ctx.accounts.reporter.key())?; // 仅指定上报者
+    order.record_volume(&VolumeObservation { volume, price, timestamp: clock.unix_timestamp })?; // 写入观测，时间戳取链上时钟
+    emit!(PovVolumeReported { // 触发成交量上报事件，链上可追溯
+        order: order.key(), // 事件：订单地址
+        volume, // 事件：增量成交量
+        price, // 事件：最新价格
+        market_volume: order.engine.market_volume, // 事件：累计成交量
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct CrankPovSlice<'info> { // 定义执行POV分片指令的账户上下文结构体，任何人均可调用
+    #[account(
+        mut, // 订单状态需更新
+        seeds = [b"pov_order", pov_order.owner.as_ref(), pov_order.order_id.to_le_bytes().as_ref()], // 校验PDA
+        bump = pov_order.bump // 使用存储的bump
+    )]
+    pub pov_order: Account<'info, PovOrder>, // POV订单账户，类型安全
+    #[account(
+        mut, // 分片输入由此转出
+        seeds = [b"pov_input_vault", pov_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>, // 输入金库
+    #[account(
+        mut, // 分片输出转入
+        seeds = [b"pov_output_vault", pov_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>, // 输出金库
+    #[account(mut)] // 接收keeper小费
+    pub keeper: Signer<'info>, // 执行分片的keeper，无需任何权限
+    pub token_program: Program<'info, Token>, // SPL Token程序
+}
+
+pub fn crank_pov_slice<'info>(
+    ctx: Context<'_, '_, '_, 'info, CrankPovSlice<'info>>, // Anchor账户上下文，remaining_accounts携带目标DEX池账户与CPI账户
+    pool_account_count: u8, // remaining_accounts中池账户数量
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    let order = &ctx.accounts.pov_order; // 获取订单账户
+    order.assert_crankable(clock.slot)?; // 校验订单状态、分片上限与分片间隔
+    let pool = ctx.remaining_accounts.first().ok_or(DexAdapterError::MissingPoolAccounts)?; // 首个池账户
+    order.assert_pool(pool.key)?; // 只能在创建时锁定的池成交
+    let slice_index = order.engine.slices_executed; // 当前分片索引
+    let (amount_in, min_amount_out) = order.next_slice()?; // 按累计成交量与参与率带宽计算分片
+    let dex = crate::dex::adapter::escrow_swap_adapter(&order.dex_name)
+        .ok_or(PovOrderError::DexAdapterNotFound)?; // 按名称静态选取适配器
+    let params = TradeParams {
+        trade_type: "pov_slice".to_string(), // 交易类型
+        from_token: order.input_mint, // 输入资产
+        to_token: order.output_mint, // 输出资产
+        amount_in, // 分片输入数量
+        min_amount_out, // 分片最小输出
+        dex_name: order.dex_name.clone(), // DEX名称
+        algo_params: None, // 无算法参数
+        strategy_params: None, // 无策略参数
+        oracle_params: None, // 无预言机参数
+    };
+    let owner = order.owner; // 下单人
+    let order_id = order.order_id.to_le_bytes(); // 订单编号种子
+    let bump = [order.bump]; // PDA bump种子
+    let signer_seeds: &[&[u8]] = &[b"pov_order", owner.as_ref(), order_id.as_ref(), &bump]; // 订单PDA签名种子
+    let accounts = ctx.accounts; // 解构账户上下文
+    let (spent, amount_out) = execute_escrowed_swap(
+        dex,
+        &params,
+        EscrowSwapAccounts {
+            order: accounts.pov_order.to_account_info(), // 订单PDA
+            input_vault: &mut accounts.input_vault, // 输入金库
+            output_vault: &mut accounts.output_vault, // 输出金库
+            keeper: accounts.keeper.to_account_info(), // keeper
+            token_program: accounts.token_program.to_account_info(), // SPL Token程序
+            remaining_accounts: ctx.remaining_accounts, // 池账户与CPI账户
+        },
+        pool_account_count,
+        signer_seeds,
+    )?; // 通过DEX适配器CPI执行分片，按金库余额变化取实际成交
+    require!(spent > 0 && spent <= amount_in, PovOrderError::InvalidFill); // 实际输入不得超出分片
+    let order = &mut accounts.pov_order; // 获取可变订单账户
+    order.record_fill(spent, amount_out, clock.slot, clock.unix_timestamp)?; // 按实际成交执行价格偏离保护、记录成交并推进状态
+    let tip = order.keeper_tip_lamports; // 本分片小费
+    if tip > 0 {
+        let order_info = order.to_account_info(); // 订单账户信息
+        let rent_floor = Rent::get()?.minimum_balance(order_info.data_len()); // 租金豁免下限
+        require!(
+            order_info.lamports().saturating_sub(rent_floor) >= tip,
+            PovOrderError::InsufficientTipBalance
+        ); // 小费不得动用租金
+        **order_info.try_borrow_mut_lamports()? -= tip; // 从订单PDA扣除小费
+        **accounts.keeper.to_account_info().try_borrow_mut_lamports()? += tip; // 支付给keeper
+    }
+    emit!(PovSliceExecuted { // 触发分片执行事件，链上可追溯
+        order: accounts.pov_order.key(), // 事件：订单地址
+        keeper: accounts.keeper.key(), // 事件：keeper
+        slice_index, // 事件：分片索引
+        amount_in: spent, // 事件：实际输入数量
+        amount_out, // 事件：实际输出数量
+        realized_rate_bps: accounts.pov_order.engine.report().realized_rate_bps, // 事件：实际参与率
+        tip_lamports: tip, // 事件：小费
+        slot: clock.slot, // 事件：执行slot
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct CancelPovOrder<'info> { // 定义撤销POV订单指令的账户上下文结构体
+    #[account(
+        mut, // 关闭账户
+        has_one = owner, // 仅下单人可撤单
+        close = owner, // 关闭订单PDA，租金与剩余小费退还下单人
+        seeds = [b"pov_order", owner.key().as_ref(), pov_order.order_id.to_le_bytes().as_ref()], // 校验PDA
+        bump = pov_order.bump // 使用存储的bump
+    )]
+    pub pov_order: Account<'info, PovOrder>, // POV订单账户，类型安全
+    #[account(
+        mut, // 退还剩余输入并关闭
+        seeds = [b"pov_input_vault", pov_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>, // 输入金库
+    #[account(
+        mut, // 转出累计输出并关闭
+        seeds = [b"pov_output_vault", pov_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>, // 输出金库
+    #[account(
+        mut, // 接收退还输入
+        token::mint = pov_order.input_mint, // 输入资产账户
+        token::authority = owner // 下单人持有
+    )]
+    pub owner_input_account: Box<Account<'info, TokenAccount>>, // 下单人输入资产账户
+    #[account(
+        mut, // 接收累计输出
+        token::mint = pov_order.output_mint, // 输出资产账户
+        token::authority = owner // 下单人持有
+    )]
+    pub owner_output_account: Box<Account<'info, TokenAccount>>, // 下单人输出资产账户
+    #[account(mut)] // 接收退还lamports
+    pub owner: Signer<'info>, // 下单人签名者，类型安全
+    pub token_program: Program<'info, Token>, // SPL Token程序
+}
+
+pub fn cancel_pov_order(
+    ctx: Context<CancelPovOrder>, // Anchor账户上下文，自动校验权限与生命周期
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let accounts = &ctx.accounts; // 账户上下文
+    let order = &accounts.pov_order; // 获取订单账户
+    let order_id = order.order_id.to_le_bytes(); // 订单编号种子
+    let bump = [order.bump]; // PDA bump种子
+    let signer_seeds: &[&[u8]] = &[b"pov_order", order.owner.as_ref(), order_id.as_ref(), &bump]; // 订单PDA签名种子
+    let refunded_amount = drain_and_close_vault(
+        &accounts.input_vault,
+        accounts.owner_input_account.to_account_info(),
+        accounts.owner.to_account_info(),
+        order.to_account_info(),
+        accounts.token_program.to_account_info(),
+        signer_seeds,
+    )?; // 退还未成交输入
+    let output_amount = drain_and_close_vault(
+        &accounts.output_vault,
+        accounts.owner_output_account.to_account_info(),
+        accounts.owner.to_account_info(),
+        order.to_account_info(),
+        accounts.token_program.to_account_info(),
+        signer_seeds,
+    )?; // 转出累计成交输出
+    let report = order.engine.report(); // 执行报告
+    emit!(PovOrderCancelled { // 触发撤单事件，链上可追溯
+        order: order.key(), // 事件：订单地址
+        owner: order.owner, // 事件：下单人
+        filled_amount: report.filled_amount, // 事件：已成交数量
+        remaining_amount: order.engine.remaining_amount(), // 事件：剩余数量
+        realized_rate_bps: report.realized_rate_bps, // 事件：实际参与率
+        participation_shortfall: report.participation_shortfall, // 事件：参与率缺口
+        price_shortfall_bps: report.price_shortfall_bps, // 事件：价格执行缺口
+        refunded_amount, // 事件：退还输入数量
+        output_amount, // 事件：转出输出数量
+        timestamp: Clock::get()?.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // 订单账户由Anchor close约束关闭
+}
@@ -0,0 +1,179 @@
+//! Price history instruction set: init, rebind and record on-chain oracle observations (PDA持久化/管理人绑定/无许可记录/TWAP·EMA数据源)
+use anchor_lang::prelude::*; // 引入Anchor框架预导入模块，包含Solana程序开发常用类型与宏
+use crate::account_models::price_history_account::{PriceHistory, PriceHistoryError, PriceHistorySource}; // 引入价格历史账户与错误码
+use crate::oracles::traits::DEFAULT_PRICE_EXPONENT; // 统一价格精度
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct PriceHistoryInitialized { // 定义价格历史初始化事件结构体
+    pub price_history: Pubkey, // 价格历史PDA地址
+    pub mint: Pubkey, // 资产mint
+    pub oracle_account: Pubkey, // 绑定的预言机账户
+    pub authority: Pubkey, // 初始化人
+    pub timestamp: i64, // 初始化时间戳，链上可追溯
+}
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct PriceHistoryRebound { // 定义价格历史更换预言机绑定事件结构体
+    pub price_history: Pubkey, // 价格历史PDA地址
+    pub mint: Pubkey, // 资产mint
+    pub old_oracle_account: Pubkey, // 原预言机账户
+    pub new_oracle_account: Pubkey, // 新预言机账户
+    pub authority: Pubkey, // 操作人
+    pub timestamp: i64, // 更换时间戳，链上可追溯
+}
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct PriceObservationRecorded { // 定义价格观测记录事件结构体
+    pub mint: Pubkey, // 资产mint
+    pub slot: u64, // 记录slot
+    pub price: u64, // 观测价格（1e-8精度）
+    pub confidence: u64, // 观测置信区间
+    pub publish_time: i64, // 预言机发布时间戳（观测时间）
+    pub timestamp: i64, // 记录时间戳，链上可追溯
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)] // 派生Anchor序列化/反序列化、克隆、调试特性
+pub struct InitPriceHistoryParams { // 定义初始化价格历史参数结构体
+    pub mint: Pubkey, // 资产mint，参与PDA推导
+    pub source: PriceHistorySource, // 预言机来源
+    pub oracle_account: Pubkey, // 预言机价格账户
+    pub feed_id: [u8; 32], // Pyth价格源ID（其他来源填0）
+    pub max_staleness_secs: u64, // 预言机价格及历史最大时延（秒）
+    pub max_confidence_bps: u64, // 最大置信区间（基点）
+    pub min_record_interval_secs: u64, // 两次观测最小间隔（秒）
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+#[instruction(params: InitPriceHistoryParams)] // 引入指令参数，用于PDA种子推导
+pub struct InitPriceHistory<'info> { // 定义初始化价格历史指令的账户上下文结构体
+    #[account(
+        init, // Anchor属性，指示账户初始化
+        payer = authority, // 由初始化人支付租金
+        space = 8 + PriceHistory::INIT_SPACE, // 分配账户空间，8字节discriminator
+        seeds = [b"price_history", authority.key().as_ref(), params.mint.as_ref()], // PDA种子，按管理人与mint唯一，防止抢先绑定他人的预言机
+        bump // 自动推断bump种子
+    )]
+    pub price_history: Account<'info, PriceHistory>, // 新建价格历史账户，类型安全
+    #[account(mut)] // Anchor属性，标记账户为可变，支付租金
+    pub authority: Signer<'info>, // 管理人签名者，类型安全
+    pub system_program: Program<'info, System>, // 系统程序，Anchor自动校验
+}
+
+pub fn init_price_history(
+    ctx: Context<InitPriceHistory>, // Anchor账户上下文，自动校验权限与生命周期
+    params: InitPriceHistoryParams, // 初始化参数，类型安全
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    let history = &mut ctx.accounts.price_history; // 获取可变价格历史账户
+    history.authority = ctx.accounts.authority.key(); // 管理人
+    history.mint = params.mint; // 资产mint
+    history.bind_source(
+        params.source,
+        params.oracle_account,
+        params.feed_id,
+        params.max_staleness_secs,
+        params.max_confidence_bps,
+    )?; // 校验并写入预言机来源、账户、feed_id、时延与置信区间上限
+    require!(params.min_record_interval_secs > 0, PriceHistoryError::InvalidConfig); // 写入间隔必须为正，防止刷写挤出历史
+    history.min_record_interval_secs = params.min_record_interval_secs; // 最小写入间隔
+    history.head = 0; // 写指针
+    history.total_observations = 0; // 尚无观测
+    history.observations = Vec::new(); // 空缓冲区
+    history.bump = ctx.bumps.price_history; // PDA bump
+    emit!(PriceHistoryInitialized { // 触发初始化事件，链上可追溯
+        price_history: history.key(), // 事件：价格历史地址
+        mint: history.mint, // 事件：资产mint
+        oracle_account: history.oracle_account, // 事件：预言机账户
+        authority: history.authority, // 事件：管理人
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)] // 派生Anchor序列化/反序列化、克隆、调试特性
+pub struct RebindPriceHistoryParams { // 定义更换预言机绑定参数结构体
+    pub source: PriceHistorySource, // 新预言机来源
+    pub oracle_account: Pubkey, // 新预言机价格账户
+    pub feed_id: [u8; 32], // Pyth价格源ID（其他来源填0）
+    pub max_staleness_secs: u64, // 预言机价格及历史最大时延（秒）
+    pub max_confidence_bps: u64, // 最大置信区间（基点）
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct RebindPriceHistory<'info> { // 定义更换预言机绑定指令的账户上下文结构体
+    #[account(
+        mut, // 更换绑定并清空观测
+        has_one = authority, // 仅管理人可更换
+        seeds = [b"price_history", price_history.authority.as_ref(), price_history.mint.as_ref()], // 校验PDA
+        bump = price_history.bump // 使用存储的bump
+    )]
+    pub price_history: Account<'info, PriceHistory>, // 价格历史账户，类型安全
+    pub authority: Signer<'info>, // 管理人签名者，类型安全
+}
+
+pub fn rebind_price_history(
+    ctx: Context<RebindPriceHistory>, // Anchor账户上下文，自动校验权限与生命周期
+    params: RebindPriceHistoryParams, // 新绑定参数，类型安全
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    let history = &mut ctx.accounts.price_history; // 获取可变价格历史账户
+    let old_oracle_account = history.oracle_account; // 记录原预言机账户
+    history.rebind_source(
+        params.source,
+        params.oracle_account,
+        params.feed_id,
+        params.max_staleness_secs,
+        params.max_confidence_bps,
+    )?; // 校验新绑定并清空旧来源观测
+    emit!(PriceHistoryRebound { // 触发更换绑定事件，链上可追溯
+        price_history: history.key(), // 事件：价格历史地址
+        mint: history.mint, // 事件：资产mint
+        old_oracle_account, // 事件：原预言机账户
+        new_oracle_account: history.oracle_account, // 事件：新预言机账户
+        authority: history.authority, // 事件：操作人
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct RecordPriceObservation<'info> { // 定义记录价格观测指令的账户上下文结构体，任何人均可调用
+    #[account(
+        mut, // 写入观测
+        seeds = [b"price_history", price_history.authority.as_ref(), price_history.mint.as_ref()], // 校验PDA
+        bump = price_history.bump // 使用存储的bump
+    )]
+    pub price_history: Account<'info, PriceHistory>, // 价格历史账户，类型安全
+    /// CHECK: 地址由 price_history.oracle_account 约束，owner 与数据布局在解码时校验
+    #[account(address = price_history.oracle_account)] // 仅接受管理人绑定的预言机账户
+    pub oracle_account: UncheckedAccount<'info>, // 预言机价格账户
+}
+
+pub fn record_price_observation(
+    ctx: Context<RecordPriceObservation>, // Anchor账户上下文，自动校验权限与生命周期
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    let history = &mut ctx.accounts.price_history; // 获取可变价格历史账户
+    let oracle = ctx.accounts.oracle_account.to_account_info(); // 预言机账户信息
+    let result = history.source.read_price(
+        &oracle,
+        &history.feed_id,
+        clock.unix_timestamp,
+        clock.slot,
+        history.max_staleness_secs,
+        history.max_confidence_bps,
+    )?; // 按来源解码并校验owner、时效（Pyth额外校验feed_id与置信区间）
+    let (price, confidence) = result
+        .scaled_to(DEFAULT_PRICE_EXPONENT)
+        .ok_or(PriceHistoryError::MathOverflow)?; // 统一换算到1e-8精度
+    history.record(clock.slot, result.last_updated, price, confidence)?; // 以预言机发布时间写入环形缓冲区并维护累计价格，拒绝不更新的喂价
+    emit!(PriceObservationRecorded { // 触发观测记录事件，链上可追溯
+        mint: history.mint, // 事件：资产mint
+        slot: clock.slot, // 事件：记录slot
+        price, // 事件：观测价格
+        confidence, // 事件：置信区间
+        publish_time: result.last_updated, // 事件：预言机发布时间
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
@@ -0,0 +1,563 @@
+//! TWAP order instruction set: create, crank, cancel persistent TWAP orders (PDA持久化/代币托管/无许可crank/keeper小费)
+use anchor_lang::prelude::*; // 引入Anchor框架预导入模块，包含Solana程序开发常用类型与宏
+use anchor_lang::system_program; // 引入系统程序CPI，用于预存keeper小费
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer}; // 引入SPL Token CPI，用于订单金库托管与退款
+use crate::account_models::twap_order_account::{TwapOrder, TwapOrderError, TwapOrderStatus, MAX_TWAP_DEX_NAME_LEN}; // 引入TWAP订单账户与错误码
+use crate::core::types::TradeParams; // 引入统一交易参数，用于DEX适配器路由
+use crate::dex::adapter::{DexAdapter, DexAdapterError, DexSwapUserAccounts}; // 引入DEX适配器swap CPI接口
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct TwapOrderCreated { // 定义TWAP订单创建事件结构体
+    pub order: Pubkey, // 订单PDA地址
+    pub owner: Pubkey, // 下单人公钥
+    pub total_amount: u64, // 订单总输入数量
+    pub num_slices: u32, // 分片数量
+    pub slot_interval: u64, // 分片间隔（slot）
+    pub timestamp: i64, // 创建时间戳，链上可追溯
+}
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct TwapSliceExecuted { // 定义TWAP分片执行事件结构体
+    pub order: Pubkey, // 订单PDA地址
+    pub keeper: Pubkey, // 执行分片的keeper
+    pub slice_index: u32, // 分片索引
+    pub amount_in: u64, // 分片输入数量
+    pub amount_out: u64, // 分片输出数量
+    pub tip_lamports: u64, // 支付给keeper的小费
+    pub slot: u64, // 执行slot
+    pub timestamp: i64, // 执行时间戳，链上可追溯
+}
+
+#[event] // Anchor事件宏，自动生成链上事件日志结构体
+pub struct TwapOrderCancelled { // 定义TWAP订单撤销事件结构体
+    pub order: Pubkey, // 订单PDA地址
+    pub owner: Pubkey, // 下单人公钥
+    pub filled_amount: u64, // 撤单时已成交输入数量
+    pub remaining_amount: u64, // 撤单时剩余未成交数量
+    pub refunded_amount: u64, // 退还下单人的输入金库余额
+    pub output_amount: u64, // 转给下单人的输出金库余额
+    pub timestamp: i64, // 撤单时间戳，链上可追溯
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)] // 派生Anchor序列化/反序列化、克隆、调试特性
+pub struct CreateTwapOrderParams { // 定义创建TWAP订单参数结构体
+    pub order_id: u64, // 订单编号，参与PDA推导
+    pub input_mint: Pubkey, // 输入token mint
+    pub output_mint: Pubkey, // 输出token mint
+    pub total_amount: u64, // 总输入数量
+    pub num_slices: u32, // 分片数量
+    pub slot_interval: u64, // 分片间隔（slot）
+    pub start_slot: u64, // 首个分片可执行slot，小于当前slot时取当前slot
+    pub slice_limit_price: u64, // 分片限价（精度1e6），必须为正
+    pub dex_name: String, // 路由DEX适配器名称
+    pub pool: Pubkey, // 成交池/市场账户，创建后锁定
+    pub keeper_tip_lamports: u64, // 每个分片支付给keeper的小费
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+#[instruction(params: CreateTwapOrderParams)] // 引入指令参数，用于PDA种子推导
+pub struct CreateTwapOrder<'info> { // 定义创建TWAP订单指令的账户上下文结构体
+    #[account(
+        init, // Anchor属性，指示账户初始化
+        payer = owner, // 由下单人支付租金
+        space = 8 + TwapOrder::INIT_SPACE, // 分配账户空间，8字节discriminator
+        seeds = [b"twap_order", owner.key().as_ref(), params.order_id.to_le_bytes().as_ref()], // PDA种子，确保唯一性
+        bump // 自动推断bump种子
+    )]
+    pub twap_order: Account<'info, TwapOrder>, // 新建TWAP订单账户，类型安全
+    #[account(address = params.input_mint)] // 输入mint需与参数一致
+    pub input_mint: Account<'info, Mint>, // 输入token mint
+    #[account(address = params.output_mint)] // 输出mint需与参数一致
+    pub output_mint: Account<'info, Mint>, // 输出token mint
+    #[account(
+        init, // 初始化输入金库
+        payer = owner, // 由下单人支付租金
+        seeds = [b"twap_input_vault", twap_order.key().as_ref()], // 金库PDA种子
+        bump, // 自动推断bump种子
+        token::mint = input_mint, // 托管输入资产
+        token::authority = twap_order // 仅订单PDA可动用
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>, // 输入金库，托管全部待执行输入
+    #[account(
+        init, // 初始化输出金库
+        payer = owner, // 由下单人支付租金
+        seeds = [b"twap_output_vault", twap_order.key().as_ref()], // 金库PDA种子
+        bump, // 自动推断bump种子
+        token::mint = output_mint, // 接收输出资产
+        token::authority = twap_order // 仅订单PDA可动用
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>, // 输出金库，累计分片成交输出
+    #[account(
+        mut, // 转出输入资产
+        token::mint = input_mint, // 输入资产账户
+        token::authority = owner // 下单人持有
+    )]
+    pub owner_input_account: Box<Account<'info, TokenAccount>>, // 下单人输入资产账户
+    #[account(mut)] // Anchor属性，标记账户为可变，支付租金与小费
+    pub owner: Signer<'info>, // 下单人签名者，类型安全
+    pub token_program: Program<'info, Token>, // SPL Token程序
+    pub system_program: Program<'info, System>, // 系统程序，Anchor自动校验
+}
+
+/// 校验创建参数并返回分片计划
+fn validate_create_params(params: &CreateTwapOrderParams) -> anchor_lang::Result<Vec<u64>> {
+    require!(params.input_mint != params.output_mint, TwapOrderError::InvalidMints); // 输入输出资产不能相同
+    require!(params.slot_interval > 0, TwapOrderError::InvalidSlotInterval); // 分片间隔必须为正
+    require!(params.slice_limit_price > 0, TwapOrderError::InvalidLimitPrice); // crank无需许可，必须有限价保护
+    require!(params.pool != Pubkey::default(), TwapOrderError::PoolMismatch); // 必须指定成交池
+    require!(
+        !params.dex_name.is_empty() && params.dex_name.len() <= MAX_TWAP_DEX_NAME_LEN,
+        TwapOrderError::InvalidDexName
+    ); // DEX名称长度需合法
+    let dex = crate::dex::adapter::escrow_swap_adapter(&params.dex_name)
+        .ok_or(TwapOrderError::DexAdapterNotFound)?; // 按名称静态选取支持swap CPI的适配器
+    require!(dex.supports_swap_cpi(), DexAdapterError::SwapCpiUnsupported); // 分片须经swap CPI成交，拒绝无法成交的适配器，避免托管资金只能撤单取回
+    TwapOrder::split_into_slices(params.total_amount, params.num_slices) // 生成分片计划
+}
+
+pub fn create_twap_order(
+    ctx: Context<CreateTwapOrder>, // Anchor账户上下文，自动校验权限与生命周期
+    params: CreateTwapOrderParams, // 创建参数，类型安全
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let slice_amounts = validate_create_params(&params)?; // 校验参数并生成分片计划
+    let clock = Clock::get()?; // 获取链上时钟
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(), // SPL Token程序
+            Transfer {
+                from: ctx.accounts.owner_input_account.to_account_info(), // 下单人输入资产账户
+                to: ctx.accounts.input_vault.to_account_info(), // 订单输入金库
+                authority: ctx.accounts.owner.to_account_info(), // 下单人签名
+            },
+        ),
+        params.total_amount, // 全部输入数量
+    )?; // 将订单总输入托管至订单PDA金库
+    let tip_budget = params
+        .keeper_tip_lamports
+        .checked_mul(params.num_slices as u64)
+        .ok_or(TwapOrderError::MathOverflow)?; // 计算全部分片的小费预算
+    if tip_budget > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(), // 系统程序
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(), // 下单人
+                    to: ctx.accounts.twap_order.to_account_info(), // 订单PDA
+                },
+            ),
+            tip_budget, // 预存小费
+        )?; // 将小费预算转入订单PDA
+    }
+    let order = &mut ctx.accounts.twap_order; // 获取可变订单账户
+    order.owner = ctx.accounts.owner.key(); // 下单人
+    order.order_id = params.order_id; // 订单编号
+    order.input_mint = params.input_mint; // 输入资产
+    order.output_mint = params.output_mint; // 输出资产
+    order.dex_name = params.dex_name; // DEX适配器名称
+    order.pool = params.pool; // 锁定成交池
+    order.total_amount = params.total_amount; // 总输入数量
+    order.slice_amounts = slice_amounts; // 分片计划
+    order.next_slice = 0; // 从第一个分片开始
+    order.filled_amount = 0; // 尚未成交
+    order.received_amount = 0; // 尚未收到输出
+    order.slot_interval = params.slot_interval; // 分片间隔
+    order.next_eligible_slot = params.start_slot.max(clock.slot); // 首个分片可执行slot
+    order.slice_limit_price = params.slice_limit_price; // 分片限价
+    order.keeper_tip_lamports = params.keeper_tip_lamports; // keeper小费
+    order.status = TwapOrderStatus::Active; // 订单进入执行中
+    order.created_at = clock.unix_timestamp; // 创建时间
+    order.last_fill_at = 0; // 尚未成交
+    order.bump = ctx.bumps.twap_order; // PDA bump
+    emit!(TwapOrderCreated { // 触发订单创建事件，链上可追溯
+        order: order.key(), // 事件：订单地址
+        owner: order.owner, // 事件：下单人
+        total_amount: order.total_amount, // 事件：总数量
+        num_slices: params.num_slices, // 事件：分片数量
+        slot_interval: order.slot_interval, // 事件：分片间隔
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+/// 订单PDA托管金库执行swap所需账户（TWAP/POV订单共用）
+pub(crate) struct EscrowSwapAccounts<'a, 'info> {
+    pub order: AccountInfo<'info>, // 订单PDA，金库owner与CPI签名者
+    pub input_vault: &'a mut Account<'info, TokenAccount>, // 输入金库
+    pub output_vault: &'a mut Account<'info, TokenAccount>, // 输出金库
+    pub keeper: AccountInfo<'info>, // keeper，支付CPI附带费用（如OpenBook罚金）
+    pub token_program: AccountInfo<'info>, // SPL Token程序
+    pub remaining_accounts: &'a [AccountInfo<'info>], // 池账户 + DEX CPI所需其余账户
+}
+
+/// 通过DEX适配器swap CPI以订单PDA签名执行托管成交，返回按金库余额变化计算的（实际输入, 实际输出）
+/// - remaining_accounts[..pool_account_count] 为 account_requirements(Swap) 声明的池账户，其后为DEX程序、池金库等CPI账户
+/// - 调用方须先校验首个池账户为订单锁定的池
+pub(crate) fn execute_escrowed_swap(
+    dex: &dyn DexAdapter, // 托管订单DEX适配器
+    params: &TradeParams, // 分片交易参数
+    accounts: EscrowSwapAccounts, // 托管账户
+    pool_account_count: u8, // 池账户数量
+    signer_seeds: &[&[u8]], // 订单PDA签名种子
+) -> anchor_lang::Result<(u64, u64)> { // Anchor规范返回类型
+    let pool_accounts = accounts
+        .remaining_accounts
+        .get(..pool_account_count as usize)
+        .ok_or(DexAdapterError::MissingPoolAccounts)?; // 池账户
+    let user = DexSwapUserAccounts {
+        authority: accounts.order.key(), // 订单PDA持有输入金库
+        payer: accounts.keeper.key(), // keeper支付CPI附带费用
+        source: accounts.input_vault.key(), // 输入金库
+        destination: accounts.output_vault.key(), // 输出金库
+        token_program: accounts.token_program.key(), // SPL Token程序
+    };
+    let instruction = dex.build_swap_instruction(params, &user, pool_accounts)?; // 由适配器构造真实swap指令
+    let input_before = accounts.input_vault.amount; // CPI前输入金库余额
+    let output_before = accounts.output_vault.amount; // CPI前输出金库余额
+    let mut infos = vec![
+        accounts.order.clone(), // 订单PDA
+        accounts.input_vault.to_account_info(), // 输入金库
+        accounts.output_vault.to_account_info(), // 输出金库
+        accounts.keeper.clone(), // keeper
+        accounts.token_program.clone(), // SPL Token程序
+    ];
+    infos.extend_from_slice(accounts.remaining_accounts); // 池账户与DEX CPI账户
+    dex.invoke_swap(&instruction, &infos, &[signer_seeds])?; // 订单PDA签名执行swap
+    accounts.input_vault.reload()?; // 重新读取输入金库
+    accounts.output_vault.reload()?; // 重新读取输出金库
+    let spent = input_before
+        .checked_sub(accounts.input_vault.amount)
+        .ok_or(DexAdapterError::MathOverflow)?; // 实际消耗输入
+    let received = accounts
+        .output_vault
+        .amount
+        .checked_sub(output_before)
+        .ok_or(DexAdapterError::MathOverflow)?; // 实际收到输出
+    Ok((spent, received))
+}
+
+/// 构造TWAP分片交易参数
+fn slice_trade_params(order: &TwapOrder, amount_in: u64, min_amount_out: u64) -> TradeParams {
+    TradeParams {
+        trade_type: "twap_slice".to_string(), // 交易类型
+        from_token: order.input_mint, // 输入资产
+        to_token: order.output_mint, // 输出资产
+        amount_in, // 分片输入数量
+        min_amount_out, // 分片最小输出
+        dex_name: order.dex_name.clone(), // DEX名称
+        algo_params: None, // 无算法参数
+        strategy_params: None, // 无策略参数
+        oracle_params: None, // 无预言机参数
+    }
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct CrankTwapSlice<'info> { // 定义执行TWAP分片指令的账户上下文结构体，任何人均可调用
+    #[account(
+        mut, // 订单状态需更新
+        seeds = [b"twap_order", twap_order.owner.as_ref(), twap_order.order_id.to_le_bytes().as_ref()], // 校验PDA
+        bump = twap_order.bump // 使用存储的bump
+    )]
+    pub twap_order: Account<'info, TwapOrder>, // TWAP订单账户，类型安全
+    #[account(
+        mut, // 分片输入由此转出
+        seeds = [b"twap_input_vault", twap_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>, // 输入金库
+    #[account(
+        mut, // 分片输出转入
+        seeds = [b"twap_output_vault", twap_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>, // 输出金库
+    #[account(mut)] // 接收keeper小费
+    pub keeper: Signer<'info>, // 执行分片的keeper，无需任何权限
+    pub token_program: Program<'info, Token>, // SPL Token程序
+}
+
+pub fn crank_twap_slice<'info>(
+    ctx: Context<'_, '_, '_, 'info, CrankTwapSlice<'info>>, // Anchor账户上下文，remaining_accounts携带目标DEX池账户与CPI账户
+    pool_account_count: u8, // remaining_accounts中池账户数量
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let clock = Clock::get()?; // 获取链上时钟
+    let order = &ctx.accounts.twap_order; // 获取订单账户
+    order.assert_crankable(clock.slot)?; // 校验订单状态与分片间隔
+    let pool = ctx.remaining_accounts.first().ok_or(DexAdapterError::MissingPoolAccounts)?; // 首个池账户
+    order.assert_pool(pool.key)?; // 只能在创建时锁定的池成交
+    let slice_index = order.next_slice; // 当前分片索引
+    let amount_in = order.next_slice_amount()?; // 当前分片输入数量
+    let min_amount_out = order.min_amount_out(amount_in)?; // 按分片限价计算最小输出
+    let dex = crate::dex::adapter::escrow_swap_adapter(&order.dex_name)
+        .ok_or(TwapOrderError::DexAdapterNotFound)?; // 按名称静态选取适配器
+    let params = slice_trade_params(order, amount_in, min_amount_out); // 分片交易参数
+    let owner = order.owner; // 下单人
+    let order_id = order.order_id.to_le_bytes(); // 订单编号种子
+    let bump = [order.bump]; // PDA bump种子
+    let signer_seeds: &[&[u8]] = &[b"twap_order", owner.as_ref(), order_id.as_ref(), &bump]; // 订单PDA签名种子
+    let accounts = ctx.accounts; // 解构账户上下文
+    let (spent, amount_out) = execute_escrowed_swap(
+        dex,
+        &params,
+        EscrowSwapAccounts {
+            order: accounts.twap_order.to_account_info(), // 订单PDA
+            input_vault: &mut accounts.input_vault, // 输入金库
+            output_vault: &mut accounts.output_vault, // 输出金库
+            keeper: accounts.keeper.to_account_info(), // keeper
+            token_program: accounts.token_program.to_account_info(), // SPL Token程序
+            remaining_accounts: ctx.remaining_accounts, // 池账户与CPI账户
+        },
+        pool_account_count,
+        signer_seeds,
+    )?; // 通过DEX适配器CPI执行分片，按金库余额变化取实际成交
+    require!(spent > 0 && spent <= amount_in, TwapOrderError::InvalidFill); // 实际输入不得超出分片
+    let order = &mut accounts.twap_order; // 获取可变订单账户
+    require!(amount_out >= order.min_amount_out(spent)?, TwapOrderError::LimitPriceNotMet); // 按实际输出执行分片限价保护
+    order.record_fill(spent, amount_out, clock.slot, clock.unix_timestamp)?; // 记录成交并推进状态
+    let tip = order.keeper_tip_lamports; // 本分片小费
+    if tip > 0 {
+        let order_info = order.to_account_info(); // 订单账户信息
+        let rent_floor = Rent::get()?.minimum_balance(order_info.data_len()); // 租金豁免下限
+        require!(
+            order_info.lamports().saturating_sub(rent_floor) >= tip,
+            TwapOrderError::InsufficientTipBalance
+        ); // 小费不得动用租金
+        **order_info.try_borrow_mut_lamports()? -= tip; // 从订单PDA扣除小费
+        **accounts.keeper.to_account_info().try_borrow_mut_lamports()? += tip; // 支付给keeper
+    }
+    emit!(TwapSliceExecuted { // 触发分片执行事件，链上可追溯
+        order: accounts.twap_order.key(), // 事件：订单地址
+        keeper: accounts.keeper.key(), // 事件：keeper
+        slice_index, // 事件：分片索引
+        amount_in: spent, // 事件：实际输入数量
+        amount_out, // 事件：实际输出数量
+        tip_lamports: tip, // 事件：小费
+        slot: clock.slot, // 事件：执行slot
+        timestamp: clock.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // Anchor规范返回，生命周期自动管理
+}
+
+#[derive(Accounts)] // Anchor宏，自动为结构体生成账户校验与生命周期管理代码
+pub struct CancelTwapOrder<'info> { // 定义撤销TWAP订单指令的账户上下文结构体
+    #[account(
+        mut, // 关闭账户
+        has_one = owner, // 仅下单人可撤单
+        close = owner, // 关闭订单PDA，租金与剩余小费退还下单人
+        seeds = [b"twap_order", owner.key().as_ref(), twap_order.order_id.to_le_bytes().as_ref()], // 校验PDA
+        bump = twap_order.bump // 使用存储的bump
+    )]
+    pub twap_order: Account<'info, TwapOrder>, // TWAP订单账户，类型安全
+    #[account(
+        mut, // 退还剩余输入并关闭
+        seeds = [b"twap_input_vault", twap_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>, // 输入金库
+    #[account(
+        mut, // 转出累计输出并关闭
+        seeds = [b"twap_output_vault", twap_order.key().as_ref()], // 校验金库PDA
+        bump // 自动推断bump种子
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>, // 输出金库
+    #[account(
+        mut, // 接收退还输入
+        token::mint = twap_order.input_mint, // 输入资产账户
+        token::authority = owner // 下单人持有
+    )]
+    pub owner_input_account: Box<Account<'info, TokenAccount>>, // 下单人输入资产账户
+    #[account(
+        mut, // 接收累计输出
+        token::mint = twap_order.output_mint, // 输出资产账户
+        token::authority = owner // 下单人持有
+    )]
+    pub owner_output_account: Box<Account<'info, TokenAccount>>, // 下单人输出资产账户
+    #[account(mut)] // 接收退还lamports
+    pub owner: Signer<'info>, // 下单人签名者，类型安全
+    pub token_program: Program<'info, Token>, // SPL Token程序
+}
+
+/// 以订单PDA签名将金库余额转出并关闭金库，返回转出数量
+pub(crate) fn drain_and_close_vault<'info>(
+    vault: &Account<'info, TokenAccount>, // 订单金库
+    destination: AccountInfo<'info>, // 接收代币的账户
+    rent_receiver: AccountInfo<'info>, // 接收金库租金的账户
+    authority: AccountInfo<'info>, // 订单PDA
+    token_program: AccountInfo<'info>, // SPL Token程序
+    signer_seeds: &[&[u8]], // 订单PDA签名种子
+) -> anchor_lang::Result<u64> { // Anchor规范返回类型
+    let amount = vault.amount; // 金库余额
+    let signer = &[signer_seeds]; // 签名种子集合
+    if amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(), // SPL Token程序
+                Transfer {
+                    from: vault.to_account_info(), // 订单金库
+                    to: destination, // 接收账户
+                    authority: authority.clone(), // 订单PDA
+                },
+                signer,
+            ),
+            amount, // 全部余额
+        )?; // 转出金库余额
+    }
+    token::close_account(CpiContext::new_with_signer(
+        token_program, // SPL Token程序
+        CloseAccount {
+            account: vault.to_account_info(), // 订单金库
+            destination: rent_receiver, // 租金接收人
+            authority, // 订单PDA
+        },
+        signer,
+    ))?; // 关闭金库，租金退还
+    Ok(amount)
+}
+
+pub fn cancel_twap_order(
+    ctx: Context<CancelTwapOrder>, // Anchor账户上下文，自动校验权限与生命周期
+) -> anchor_lang::Result<()> { // Anchor规范返回类型
+    let accounts = &ctx.accounts; // 账户上下文
+    let order = &accounts.twap_order; // 获取订单账户
+    let order_id = order.order_id.to_le_bytes(); // 订单编号种子
+    let bump = [order.bump]; // PDA bump种子
+    let signer_seeds: &[&[u8]] = &[b"twap_order", order.owner.as_ref(), order_id.as_ref(), &bump]; // 订单PDA签名种子
+    let refunded_amount = drain_and_close_vault(
+        &accounts.input_vault,
+        accounts.owner_input_account.to_account_info(),
+        accounts.owner.to_account_info(),
+        order.to_account_info(),
+        accounts.token_program.to_account_info(),
+        signer_seeds,
+    )?; // 退还未成交输入（含分片成交零头）
+    let output_amount = drain_and_close_vault(
+        &accounts.output_vault,
+        accounts.owner_output_account.to_account_info(),
+        accounts.owner.to_account_info(),
+        order.to_account_info(),
+        accounts.token_program.to_account_info(),
+        signer_seeds,
+    )?; // 转出累计成交输出
+    emit!(TwapOrderCancelled { // 触发撤单事件，链上可追溯
+        order: order.key(), // 事件：订单地址
+        owner: order.owner, // 事件：下单人
+        filled_amount: order.filled_amount, // 事件：已成交数量
+        remaining_amount: order.remaining_amount(), // 事件：剩余数量
+        refunded_amount, // 事件：退还输入数量
+        output_amount, // 事件：转出输出数量
+        timestamp: Clock::get()?.unix_timestamp, // 事件：链上时间戳
+    });
+    Ok(()) // 订单账户由Anchor close约束关闭
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::dlmm::{DlmmBin, DlmmFeeParameters, DlmmVolatilityState};
+    use crate::dex::meteora::{LbPairState, MeteoraBinArray, METEORA_DLMM_PROGRAM_ID};
+
+    /// Meteora DLMM 交易对夹具：活跃 bin 0，bin 0 有 5_000 Y、bin 1 有 5_000 X。
+    fn meteora_pair() -> (LbPairState, Pubkey, Vec<u8>, Vec<u8>) {
+        let lb_pair = Pubkey::new_unique();
+        let pair = LbPairState {
+            fees: DlmmFeeParameters { base_factor: 10_000, filter_period: 30, decay_period: 600, reduction_factor: 5_000, ..Default::default() },
+            volatility: DlmmVolatilityState::default(),
+            active_id: 0,
+            bin_step: 10,
+            status: 0,
+            token_x_mint: Pubkey::new_unique(),
+            token_y_mint: Pubkey::new_unique(),
+            reserve_x: Pubkey::new_unique(),
+            reserve_y: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+        };
+        let bins = [DlmmBin { id: 0, amount_x: 0, amount_y: 5_000 }, DlmmBin { id: 1, amount_x: 5_000, amount_y: 0 }];
+        let bin_array = MeteoraBinArray::pack(&lb_pair, 0, &bins);
+        (pair.clone(), lb_pair, pair.pack(), bin_array)
+    }
+
+    fn create_params(pair: &LbPairState, lb_pair: Pubkey, dex_name: &str) -> CreateTwapOrderParams {
+        CreateTwapOrderParams {
+            order_id: 1,
+            input_mint: pair.token_y_mint,
+            output_mint: pair.token_x_mint,
+            total_amount: 1_000,
+            num_slices: 4,
+            slot_interval: 10,
+            start_slot: 0,
+            slice_limit_price: 900_000,
+            dex_name: dex_name.to_string(),
+            pool: lb_pair,
+            keeper_tip_lamports: 0,
+        }
+    }
+
+    /// 以真实 Meteora 适配器创建订单并构造分片 swap 指令。
+    #[test]
+    fn test_create_and_crank_with_meteora_adapter() {
+        let (pair, lb_pair, mut pair_data, mut bin_data) = meteora_pair();
+        let params = create_params(&pair, lb_pair, "meteora");
+        let slice_amounts = validate_create_params(&params).unwrap();
+        assert!(validate_create_params(&create_params(&pair, lb_pair, "raydium")).is_err()); // 不支持swap CPI
+        assert!(validate_create_params(&create_params(&pair, lb_pair, "mock_dex")).is_err()); // 未知DEX
+        assert!(validate_create_params(&CreateTwapOrderParams { slice_limit_price: 0, ..params.clone() }).is_err()); // 无限价保护
+        assert!(validate_create_params(&CreateTwapOrderParams { pool: Pubkey::default(), ..params.clone() }).is_err()); // 未指定池
+
+        let owner = Pubkey::new_unique();
+        let (order_key, bump) = Pubkey::find_program_address(&[b"twap_order", owner.as_ref(), &1u64.to_le_bytes()], &crate::ID);
+        let mut order = TwapOrder {
+            owner,
+            order_id: params.order_id,
+            input_mint: params.input_mint,
+            output_mint: params.output_mint,
+            dex_name: params.dex_name.clone(),
+            pool: params.pool,
+            total_amount: params.total_amount,
+            slice_amounts,
+            next_slice: 0,
+            filled_amount: 0,
+            received_amount: 0,
+            slot_interval: params.slot_interval,
+            next_eligible_slot: 100,
+            slice_limit_price: params.slice_limit_price,
+            keeper_tip_lamports: 0,
+            status: TwapOrderStatus::Active,
+            created_at: 0,
+            last_fill_at: 0,
+            bump,
+        };
+
+        // crank：按分片限价构造 swap 指令，订单 PDA 作为签名者
+        order.assert_crankable(100).unwrap();
+        let amount_in = order.next_slice_amount().unwrap();
+        let min_amount_out = order.min_amount_out(amount_in).unwrap();
+        let (input_vault, output_vault, keeper) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let user = DexSwapUserAccounts {
+            authority: order_key,
+            payer: keeper,
+            source: input_vault,
+            destination: output_vault,
+            token_program: anchor_spl::token::ID,
+        };
+        let bin_key = MeteoraBinArray::address(&lb_pair, 0);
+        let (mut pair_lamports, mut bin_lamports) = (0u64, 0u64);
+        let infos = vec![
+            AccountInfo::new(&lb_pair, false, false, &mut pair_lamports, &mut pair_data, &METEORA_DLMM_PROGRAM_ID, false, 0),
+            AccountInfo::new(&bin_key, false, true, &mut bin_lamports, &mut bin_data, &METEORA_DLMM_PROGRAM_ID, false, 0),
+        ];
+        order.assert_pool(infos[0].key).unwrap(); // keeper只能传入锁定的池
+        assert!(order.assert_pool(&Pubkey::new_unique()).is_err());
+        let dex = crate::dex::adapter::escrow_swap_adapter(&order.dex_name).unwrap();
+        let ix = dex.build_swap_instruction(&slice_trade_params(&order, amount_in, min_amount_out), &user, &infos).unwrap();
+        assert_eq!(ix.program_id, METEORA_DLMM_PROGRAM_ID);
+        assert_eq!(ix.accounts[0].pubkey, lb_pair);
+        assert_eq!((ix.accounts[4].pubkey, ix.accounts[5].pubkey), (input_vault, output_vault));
+        assert!(ix.accounts[10].is_signer && ix.accounts[10].pubkey == order_key);
+        assert_eq!(ix.data[8..16], amount_in.to_le_bytes());
+        assert_eq!(ix.data[16..24], min_amount_out.to_le_bytes()); // 分片限价由DEX程序强制
+        assert_eq!(ix.accounts.last().unwrap().pubkey, bin_key);
+
+        order.record_fill(amount_in, min_amount_out, 100, 0).unwrap();
+        assert_eq!(order.next_slice, 1);
+        assert_eq!(order.next_eligible_slot, 110);
+    }
+}
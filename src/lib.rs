@@ -773,9 +773,80 @@ pub mod index_token_program { // 定义主合约模块，所有链上指令均
     pub fn value_rwa(ctx: Context<instructions::rwa::ValueRwa>, oracle_params: Vec<super::core::types::OracleParams>) -> anchor_lang::Result<u64> {
         instructions::rwa::value_rwa(ctx, oracle_params)
     }
-    /// 跨市场套利指令
-    pub fn arbitrage_trade(ctx: Context<instructions::arbitrage::ArbitrageTrade>, params: Vec<super::core::types::TradeParams>, min_profit: u64) -> anchor_lang::Result<()> {
-        instructions::arbitrage::arbitrage_trade(ctx, params, min_profit)
+    /// 跨市场套利指令（params 为首尾相接的闭合环路兑换腿，remaining_accounts 按 pool_account_counts 依次传入各腿的DEX池账户）
+    pub fn arbitrage_trade(ctx: Context<instructions::arbitrage::ArbitrageTrade>, params: Vec<super::core::types::TradeParams>, pool_account_counts: Vec<u8>, min_profit: u64) -> anchor_lang::Result<()> {
+        instructions::arbitrage::arbitrage_trade(ctx, params, pool_account_counts, min_profit)
+    }
+    /// 创建TWAP订单指令
+    /// # 参数
+    /// - ctx: Context<instructions::twap_order::CreateTwapOrder>，Anchor账户上下文，初始化订单PDA与输入/输出金库，托管全部输入并预存keeper小费
+    /// - params: CreateTwapOrderParams，订单数量、分片数量、分片间隔、分片限价、DEX名称、keeper小费
+    /// # 返回值
+    /// - anchor_lang::Result<()>: Anchor标准返回类型，表示指令执行成功或失败
+    pub fn create_twap_order(ctx: Context<instructions::twap_order::CreateTwapOrder>, params: instructions::twap_order::CreateTwapOrderParams) -> anchor_lang::Result<()> {
+        instructions::twap_order::create_twap_order(ctx, params)
+    }
+    /// 执行TWAP订单分片指令（无许可，分片间隔到期后任何keeper均可调用并获得小费；
+    /// remaining_accounts 前 pool_account_count 个为目标DEX池账户，其后为swap CPI所需其余账户，订单PDA签名成交）
+    pub fn crank_twap_slice<'info>(ctx: Context<'_, '_, '_, 'info, instructions::twap_order::CrankTwapSlice<'info>>, pool_account_count: u8) -> anchor_lang::Result<()> {
+        instructions::twap_order::crank_twap_slice(ctx, pool_account_count)
+    }
+    /// 撤销TWAP订单指令（退还未成交输入、转出累计输出并关闭金库与订单PDA，租金与剩余小费退还下单人）
+    pub fn cancel_twap_order(ctx: Context<instructions::twap_order::CancelTwapOrder>) -> anchor_lang::Result<()> {
+        instructions::twap_order::cancel_twap_order(ctx)
+    }
+    /// 创建POV订单指令（初始化订单PDA、托管金库与POV执行引擎，托管全部输入并按最大分片数量预存keeper小费）
+    pub fn create_pov_order(ctx: Context<instructions::pov_order::CreatePovOrder>, params: instructions::pov_order::CreatePovOrderParams) -> anchor_lang::Result<()> {
+        instructions::pov_order::create_pov_order(ctx, params)
+    }
+    /// 上报POV订单市场成交量指令（仅指定上报者，写入增量成交量与最新价格）
+    pub fn report_pov_volume(ctx: Context<instructions::pov_order::ReportPovVolume>, volume: u64, price: u64) -> anchor_lang::Result<()> {
+        instructions::pov_order::report_pov_volume(ctx, volume, price)
+    }
+    /// 执行POV订单分片指令（无许可，按累计成交量与参与率带宽定量分片；
+    /// remaining_accounts 前 pool_account_count 个为目标DEX池账户，其后为swap CPI所需其余账户，订单PDA签名成交）
+    pub fn crank_pov_slice<'info>(ctx: Context<'_, '_, '_, 'info, instructions::pov_order::CrankPovSlice<'info>>, pool_account_count: u8) -> anchor_lang::Result<()> {
+        instructions::pov_order::crank_pov_slice(ctx, pool_account_count)
+    }
+    /// 撤销POV订单指令（退还未成交输入、转出累计输出并关闭金库与订单PDA，发出参与率与缺口报告，租金与剩余小费退还下单人）
+    pub fn cancel_pov_order(ctx: Context<instructions::pov_order::CancelPovOrder>) -> anchor_lang::Result<()> {
+        instructions::pov_order::cancel_pov_order(ctx)
+    }
+    /// 初始化价格历史指令（每个管理人与mint一个PDA，绑定预言机账户与时延/置信区间/写入间隔限制）
+    pub fn init_price_history(ctx: Context<instructions::price_history::InitPriceHistory>, params: instructions::price_history::InitPriceHistoryParams) -> anchor_lang::Result<()> {
+        instructions::price_history::init_price_history(ctx, params)
+    }
+    /// 更换价格历史预言机绑定指令（仅管理人，清空旧来源观测）
+    pub fn rebind_price_history(ctx: Context<instructions::price_history::RebindPriceHistory>, params: instructions::price_history::RebindPriceHistoryParams) -> anchor_lang::Result<()> {
+        instructions::price_history::rebind_price_history(ctx, params)
+    }
+    /// 记录价格观测指令（无许可，从绑定的预言机账户读取价格写入环形缓冲区，供链上TWAP/EMA计算）
+    pub fn record_price_observation(ctx: Context<instructions::price_history::RecordPriceObservation>) -> anchor_lang::Result<()> {
+        instructions::price_history::record_price_observation(ctx)
+    }
+    /// 初始化预言机来源策略指令（每个管理人与mint一个PDA，配置主/备/第三来源及时延、置信区间、跨源偏差阈值）
+    pub fn init_oracle_policy(ctx: Context<instructions::oracles::InitOraclePolicy>, mint: Pubkey, params: instructions::oracles::OraclePolicyParams) -> anchor_lang::Result<()> {
+        instructions::oracles::init_oracle_policy(ctx, mint, params)
+    }
+    /// 更新预言机来源策略指令（仅策略管理人）
+    pub fn update_oracle_policy(ctx: Context<instructions::oracles::UpdateOraclePolicy>, params: instructions::oracles::OraclePolicyParams) -> anchor_lang::Result<()> {
+        instructions::oracles::update_oracle_policy(ctx, params)
+    }
+    /// 按来源策略解析价格指令（无许可，remaining_accounts 传入各来源预言机账户，按回退链选择健康来源并发出 OraclePriceQueried）
+    pub fn resolve_oracle_price(ctx: Context<instructions::oracles::ResolveOraclePrice>) -> anchor_lang::Result<crate::account_models::oracle_policy_account::ResolvedOraclePrice> {
+        instructions::oracles::resolve_oracle_price(ctx)
+    }
+    /// 创建链下请求指令（初始化请求PDA并发出 OffchainDexRequest/OffchainOracleRequest 事件，由指定keeper履约）
+    pub fn create_offchain_request(ctx: Context<instructions::offchain_request::CreateOffchainRequest>, params: instructions::offchain_request::CreateOffchainRequestParams) -> anchor_lang::Result<()> {
+        instructions::offchain_request::create_offchain_request(ctx, params)
+    }
+    /// 回填链下响应指令（仅指定响应者，request_id参与PDA校验，每个请求只能响应一次，发出 OffchainResponse）
+    pub fn submit_offchain_response(ctx: Context<instructions::offchain_request::SubmitOffchainResponse>, request_id: u64, success: bool, result: Vec<u8>, error_msg: Option<String>) -> anchor_lang::Result<()> {
+        instructions::offchain_request::submit_offchain_response(ctx, request_id, success, result, error_msg)
+    }
+    /// 关闭链下请求指令（已响应或已过期后由请求人关闭，租金退还请求人）
+    pub fn close_offchain_request(ctx: Context<instructions::offchain_request::CloseOffchainRequest>) -> anchor_lang::Result<()> {
+        instructions::offchain_request::close_offchain_request(ctx)
     }
     /// 适配器动态注册指令
     pub fn register_adapter(ctx: Context<instructions::adapter::RegisterAdapter>, name: String, adapter_type: String, version: String, supported_assets: Vec<String>) -> anchor_lang::Result<()> {
@@ -109,6 +109,18 @@ impl OracleAdapterRegistry {
     pub fn list_by_status(&self, status: AdapterStatus) -> Vec<AdapterMeta> {
         self.metadata.read().unwrap().values().filter(|m| m.status == status).cloned().collect()
     }
+    /// 列出所有处于活跃状态的适配器实例
+    /// 返回：(名称, 实例)列表，按名称排序，保证聚合结果可复现
+    pub fn active_adapters(&self) -> Vec<(String, Arc<dyn OracleAdapter + Send + Sync>)> {
+        let metadata = self.metadata.read().unwrap();
+        let adapters = self.adapters.read().unwrap();
+        let mut active: Vec<_> = adapters.iter()
+            .filter(|(name, _)| metadata.get(*name).map(|m| m.status == AdapterStatus::Active).unwrap_or(false))
+            .map(|(name, adapter)| (name.clone(), adapter.clone()))
+            .collect();
+        active.sort_by(|a, b| a.0.cmp(&b.0));
+        active
+    }
     /// 按资产筛选适配器元数据
     /// 参数：asset 资产名称
     /// 返回：支持该资产的适配器元数据列表
@@ -0,0 +1,421 @@
+//!
+//! 多源预言机价格聚合模块
+//! - 查询注册表中所有活跃适配器，剔除过期报价与偏离中位数过大的离群报价
+//! - 以中位数或置信度加权（定点逆方差）输出聚合价格，并列出参与聚合的数据源
+//! - 设计意图：单一预言机失效或被操纵时，NAV 等关键计算仍能获得稳健价格
+
+use anchor_lang::prelude::*; // Anchor预导入，包含Pubkey、Clock、require!等
+use crate::core::constants::PRICE_FEED_STALENESS_THRESHOLD; // 价格过期阈值（秒）
+use crate::oracles::adapter_registry::OracleAdapterRegistry; // 适配器注册表
+use crate::oracles::traits::{
+    OracleAdapter, OracleAggregator, OracleParams, OraclePriceResult, OraclePriceSample, OracleTwapResult,
+    OracleVwapResult, DEFAULT_PRICE_EXPONENT,
+};
+use crate::state::common::PriceFeed; // NAV 计算使用的价格结构
+
+/// 逆方差权重定点精度：置信区间最窄的数据源权重为 1e12
+const WEIGHT_SCALE: u128 = 1_000_000_000_000;
+
+/// 聚合方法
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum AggregationMethod {
+    /// 中位数（偶数个样本取中间两值的均值）
+    Median,
+    /// 置信度加权：权重为 1/confidence²（逆方差）
+    ConfidenceWeighted,
+}
+
+/// 聚合器配置
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct AggregatorConfig {
+    pub method: AggregationMethod,   // 聚合方法
+    pub max_staleness_secs: i64,     // 最大允许数据年龄（秒）
+    pub max_future_skew_secs: i64,   // 更新时间超前当前时间的最大容忍（秒），超出视为无效报价
+    pub max_deviation_bps: u64,      // 相对全部新鲜报价中位数的最大偏离（基点），超出视为离群
+    pub min_sources: u8,             // 剔除后最少需要的数据源数量
+    pub default_confidence_bps: u64, // 预言机未提供置信区间时，按价格比例估计的置信区间（基点）
+}
+
+impl Default for AggregatorConfig {
+    fn default() -> Self {
+        Self {
+            method: AggregationMethod::Median,
+            max_staleness_secs: PRICE_FEED_STALENESS_THRESHOLD,
+            max_future_skew_secs: 5,
+            max_deviation_bps: 200, // 2%
+            min_sources: 1,
+            default_confidence_bps: 50, // 0.5%
+        }
+    }
+}
+
+/// 聚合价格结果
+#[derive(Clone, Debug, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct AggregatedPrice {
+    /// 资产公钥
+    pub asset: Pubkey,
+    /// 聚合价格
+    pub price: u64,
+    /// 聚合置信区间（±）：中位数为保留报价的最大偏离，加权为合成标准差
+    pub confidence: u64,
+    /// 参与聚合数据源中最新的更新时间（不晚于聚合时刻）
+    pub last_updated: i64,
+    /// 聚合方法
+    pub method: AggregationMethod,
+    /// 参与聚合的数据源
+    pub sources: Vec<String>,
+    /// 因过期、更新时间超前或价格为 0 被剔除的数据源
+    pub stale_sources: Vec<String>,
+    /// 因偏离过大被剔除的数据源
+    pub outlier_sources: Vec<String>,
+}
+
+impl AggregatedPrice {
+    /// 转换为 PriceFeed，便于沿用基于 PriceFeed 的 NAV/估值逻辑
+    pub fn to_price_feed(&self) -> PriceFeed {
+        PriceFeed { mint: self.asset, price: self.price }
+    }
+
+    /// 聚合结果名称，形如 aggregated(pyth,switchboard)
+    pub fn source_label(&self) -> String {
+        format!("aggregated({})", self.sources.join(","))
+    }
+}
+
+/// 多源共识聚合器
+/// - 实现 OracleAggregator，可直接替换 DefaultOracleAggregator
+pub struct ConsensusOracleAggregator {
+    /// 聚合配置
+    pub config: AggregatorConfig,
+}
+
+impl ConsensusOracleAggregator {
+    /// 以指定配置创建聚合器
+    pub fn new(config: AggregatorConfig) -> Self {
+        Self { config }
+    }
+
+    /// 查询注册表中所有活跃适配器并聚合
+    /// - 查询失败的适配器直接跳过，不影响其余数据源
+    pub fn aggregate_registry(
+        &self,
+        registry: &OracleAdapterRegistry,
+        params: &OracleParams,
+        now: i64,
+    ) -> anchor_lang::Result<AggregatedPrice> {
+        let samples: Vec<OraclePriceSample> = registry
+            .active_adapters()
+            .iter()
+            .filter_map(|(_, adapter)| adapter.get_price_sample(params).ok())
+            .collect();
+        self.aggregate_samples(params.asset, &samples, now)
+    }
+
+    /// 聚合价格样本
+    /// 1. 剔除价格为 0、数据年龄超过 max_staleness_secs 或更新时间超前 now 超过 max_future_skew_secs 的样本
+    /// 2. 以剩余样本中位数为基准，剔除偏离超过 max_deviation_bps 的离群样本
+    /// 3. 保留样本不少于 min_sources 时按配置方法聚合
+    pub fn aggregate_samples(
+        &self,
+        asset: Pubkey,
+        samples: &[OraclePriceSample],
+        now: i64,
+    ) -> anchor_lang::Result<AggregatedPrice> {
+        require!(
+            self.config.min_sources > 0 && self.config.max_staleness_secs >= 0 && self.config.max_future_skew_secs >= 0,
+            OracleAggregatorError::InvalidConfig
+        );
+        let (fresh, stale): (Vec<&OraclePriceSample>, Vec<&OraclePriceSample>) = samples
+            .iter()
+            .partition(|s| {
+                let age = now.saturating_sub(s.last_updated);
+                s.price > 0 && age <= self.config.max_staleness_secs && age >= -self.config.max_future_skew_secs
+            });
+        require!(!fresh.is_empty(), OracleAggregatorError::NoFreshPrice);
+
+        let reference = median(fresh.iter().map(|s| s.price).collect());
+        let (kept, outliers): (Vec<&OraclePriceSample>, Vec<&OraclePriceSample>) = fresh
+            .into_iter()
+            .partition(|s| deviation_bps(s.price, reference) <= self.config.max_deviation_bps);
+        require!(kept.len() >= self.config.min_sources as usize, OracleAggregatorError::InsufficientSources);
+
+        let (price, confidence) = match self.config.method {
+            AggregationMethod::Median => {
+                let price = median(kept.iter().map(|s| s.price).collect());
+                let dispersion = kept.iter().map(|s| s.price.abs_diff(price)).max().unwrap_or(0);
+                (price, dispersion)
+            }
+            AggregationMethod::ConfidenceWeighted => self.inverse_variance_weighted(&kept)?,
+        };
+
+        Ok(AggregatedPrice {
+            asset,
+            price,
+            confidence,
+            last_updated: kept.iter().map(|s| s.last_updated).max().unwrap_or(0).min(now),
+            method: self.config.method,
+            sources: source_names(&kept),
+            stale_sources: source_names(&stale),
+            outlier_sources: source_names(&outliers),
+        })
+    }
+
+    /// 定点逆方差加权，返回 (加权价格, 合成标准差)，均四舍五入
+    /// - 以最窄置信区间 c_min 归一：w_i = WEIGHT_SCALE × c_min² / c_i²，各项不超过 WEIGHT_SCALE
+    /// - 合成标准差 1/√Σ(1/c_i²) = c_min × WEIGHT_SCALE / √(Σw_i × WEIGHT_SCALE)
+    fn inverse_variance_weighted(&self, kept: &[&OraclePriceSample]) -> anchor_lang::Result<(u64, u64)> {
+        let confidences: Vec<u128> = kept.iter().map(|s| self.effective_confidence(s) as u128).collect();
+        let min_confidence = confidences.iter().copied().min().ok_or(OracleAggregatorError::InsufficientSources)?;
+        let (mut weighted_sum, mut total_weight) = (0u128, 0u128);
+        for (sample, confidence) in kept.iter().zip(&confidences) {
+            let weight = WEIGHT_SCALE * min_confidence / confidence * min_confidence / confidence;
+            weighted_sum = (sample.price as u128)
+                .checked_mul(weight)
+                .and_then(|term| weighted_sum.checked_add(term))
+                .ok_or(OracleAggregatorError::MathOverflow)?;
+            total_weight += weight;
+        }
+        let price = (weighted_sum + total_weight / 2) / total_weight;
+        let root = isqrt(total_weight.checked_mul(WEIGHT_SCALE).ok_or(OracleAggregatorError::MathOverflow)?);
+        let confidence = (min_confidence * WEIGHT_SCALE + root / 2) / root;
+        Ok((
+            u64::try_from(price).map_err(|_| OracleAggregatorError::MathOverflow)?,
+            u64::try_from(confidence).map_err(|_| OracleAggregatorError::MathOverflow)?,
+        ))
+    }
+
+    /// 有效置信区间：未提供时按 default_confidence_bps 估计，至少为 1
+    fn effective_confidence(&self, sample: &OraclePriceSample) -> u64 {
+        let confidence = if sample.confidence > 0 {
+            sample.confidence
+        } else {
+            (sample.price as u128 * self.config.default_confidence_bps as u128 / 10_000) as u64
+        };
+        confidence.max(1)
+    }
+}
+
+impl Default for ConsensusOracleAggregator {
+    fn default() -> Self {
+        Self::new(AggregatorConfig::default())
+    }
+}
+
+/// 中位数（偶数个取中间两值均值，向下取整）；调用方保证非空
+fn median(mut values: Vec<u64>) -> u64 {
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        ((values[mid - 1] as u128 + values[mid] as u128) / 2) as u64
+    } else {
+        values[mid]
+    }
+}
+
+/// 整数平方根（向下取整，牛顿法）
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << ((128 - n.leading_zeros()).div_ceil(2)); // 初值不小于真实平方根
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// 相对基准价格的偏离（基点）
+fn deviation_bps(price: u64, reference: u64) -> u64 {
+    if reference == 0 {
+        return u64::MAX;
+    }
+    (price.abs_diff(reference) as u128 * 10_000 / reference as u128).min(u64::MAX as u128) as u64
+}
+
+/// 样本对应的数据源名称列表（保持输入顺序）
+fn source_names(samples: &[&OraclePriceSample]) -> Vec<String> {
+    samples.iter().map(|s| s.oracle_name.clone()).collect()
+}
+
+/// 由适配器列表收集样本，查询失败的适配器跳过
+fn collect_samples<F>(oracle_adapters: &[Box<dyn OracleAdapter>], query: F) -> Vec<OraclePriceSample>
+where
+    F: Fn(&dyn OracleAdapter) -> anchor_lang::Result<OraclePriceSample>,
+{
+    oracle_adapters.iter().filter_map(|adapter| query(adapter.as_ref()).ok()).collect()
+}
+
+impl OracleAggregator for ConsensusOracleAggregator {
+    /// 聚合现价（剔除过期/离群后按配置方法聚合）
+    fn aggregate_price(
+        &self,
+        params: &OracleParams,
+        oracle_adapters: &[Box<dyn OracleAdapter>],
+    ) -> anchor_lang::Result<OraclePriceResult> {
+        let samples = collect_samples(oracle_adapters, |adapter| adapter.get_price_sample(params));
+        let aggregated = self.aggregate_samples(params.asset, &samples, Clock::get()?.unix_timestamp)?;
+        Ok(OraclePriceResult {
+            price: aggregated.price,
+            confidence: aggregated.confidence,
+            exponent: DEFAULT_PRICE_EXPONENT,
+            last_updated: aggregated.last_updated,
+            oracle_name: aggregated.source_label(),
+        })
+    }
+
+    /// 聚合TWAP（各源TWAP视为无置信区间的样本）
+    fn aggregate_twap(
+        &self,
+        params: &OracleParams,
+        oracle_adapters: &[Box<dyn OracleAdapter>],
+    ) -> anchor_lang::Result<OracleTwapResult> {
+        let samples = collect_samples(oracle_adapters, |adapter| {
+            adapter.get_twap(params).map(|r| OraclePriceSample {
+                price: r.twap,
+                confidence: 0,
+                last_updated: r.last_updated,
+                oracle_name: r.oracle_name,
+            })
+        });
+        let aggregated = self.aggregate_samples(params.asset, &samples, Clock::get()?.unix_timestamp)?;
+        Ok(OracleTwapResult {
+            twap: aggregated.price,
+            last_updated: aggregated.last_updated,
+            oracle_name: aggregated.source_label(),
+        })
+    }
+
+    /// 聚合VWAP（各源VWAP视为无置信区间的样本）
+    fn aggregate_vwap(
+        &self,
+        params: &OracleParams,
+        oracle_adapters: &[Box<dyn OracleAdapter>],
+    ) -> anchor_lang::Result<OracleVwapResult> {
+        let samples = collect_samples(oracle_adapters, |adapter| {
+            adapter.get_vwap(params).map(|r| OraclePriceSample {
+                price: r.vwap,
+                confidence: 0,
+                last_updated: r.last_updated,
+                oracle_name: r.oracle_name,
+            })
+        });
+        let aggregated = self.aggregate_samples(params.asset, &samples, Clock::get()?.unix_timestamp)?;
+        Ok(OracleVwapResult {
+            vwap: aggregated.price,
+            last_updated: aggregated.last_updated,
+            oracle_name: aggregated.source_label(),
+        })
+    }
+}
+
+/// 聚合器错误码（Anchor错误）
+#[error_code]
+pub enum OracleAggregatorError {
+    /// 聚合配置无效
+    #[msg("Invalid aggregator config")] InvalidConfig,
+    /// 没有未过期的报价
+    #[msg("No fresh oracle price")] NoFreshPrice,
+    /// 剔除离群报价后数据源不足
+    #[msg("Insufficient oracle sources after outlier rejection")] InsufficientSources,
+    /// 加权计算溢出
+    #[msg("Math overflow")] MathOverflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn sample(name: &str, price: u64, confidence: u64, age: i64) -> OraclePriceSample {
+        OraclePriceSample { price, confidence, last_updated: NOW - age, oracle_name: name.to_string() }
+    }
+
+    fn samples() -> Vec<OraclePriceSample> {
+        vec![
+            sample("pyth", 100_000_000, 50_000, 10),
+            sample("switchboard", 100_200_000, 200_000, 5),
+            sample("chainlink", 99_900_000, 0, 20),
+            sample("api3", 110_000_000, 100_000, 0),   // 偏离约 9.9%，离群
+            sample("band", 100_100_000, 100_000, 120), // 超过 60 秒，过期
+        ]
+    }
+
+    /// 测试中位数聚合：剔除过期与离群报价并列出数据源。
+    #[test]
+    fn test_median_aggregation_rejects_stale_and_outliers() {
+        let asset = Pubkey::new_unique();
+        let aggregated = ConsensusOracleAggregator::default().aggregate_samples(asset, &samples(), NOW).unwrap();
+        assert_eq!(aggregated.asset, asset);
+        assert_eq!(aggregated.price, 100_000_000);
+        assert_eq!(aggregated.confidence, 200_000);
+        assert_eq!(aggregated.last_updated, NOW - 5);
+        assert_eq!(aggregated.sources, vec!["pyth", "switchboard", "chainlink"]);
+        assert_eq!(aggregated.stale_sources, vec!["band"]);
+        assert_eq!(aggregated.outlier_sources, vec!["api3"]);
+        assert_eq!(aggregated.source_label(), "aggregated(pyth,switchboard,chainlink)");
+
+        let feed = aggregated.to_price_feed();
+        assert_eq!(feed.mint, asset);
+        assert_eq!(feed.price, 100_000_000);
+    }
+
+    /// 测试置信度加权聚合：置信区间越窄权重越大，未提供置信区间时按默认比例估计。
+    #[test]
+    fn test_confidence_weighted_aggregation() {
+        let aggregator = ConsensusOracleAggregator::new(AggregatorConfig {
+            method: AggregationMethod::ConfidenceWeighted,
+            ..Default::default()
+        });
+        let aggregated = aggregator.aggregate_samples(Pubkey::new_unique(), &samples(), NOW).unwrap();
+        assert_eq!(aggregated.price, 100_010_721);
+        assert_eq!(aggregated.confidence, 48_280);
+        assert_eq!(aggregated.method, AggregationMethod::ConfidenceWeighted);
+        assert_eq!(aggregated.sources.len(), 3);
+    }
+
+    /// 测试数据源不足与无新鲜报价时返回错误。
+    #[test]
+    fn test_aggregation_errors() {
+        let strict = ConsensusOracleAggregator::new(AggregatorConfig { min_sources: 4, ..Default::default() });
+        assert!(strict.aggregate_samples(Pubkey::new_unique(), &samples(), NOW).is_err());
+
+        let aggregator = ConsensusOracleAggregator::default();
+        assert!(aggregator.aggregate_samples(Pubkey::new_unique(), &samples(), NOW + 3_600).is_err());
+        assert!(aggregator.aggregate_samples(Pubkey::new_unique(), &[], NOW).is_err());
+    }
+
+    /// 测试更新时间超前的报价：容忍范围内参与聚合且 last_updated 不晚于当前时刻，超出时作为无效报价剔除。
+    #[test]
+    fn test_future_timestamps_rejected_beyond_skew() {
+        let aggregator = ConsensusOracleAggregator::default();
+        let mut samples = samples();
+        samples.push(sample("pyth_future", 100_000_000, 50_000, -3));
+        let aggregated = aggregator.aggregate_samples(Pubkey::new_unique(), &samples, NOW).unwrap();
+        assert!(aggregated.sources.contains(&"pyth_future".to_string()));
+        assert_eq!(aggregated.last_updated, NOW);
+
+        samples.pop();
+        samples.push(sample("pyth_future", 100_000_000, 50_000, -3_600));
+        let aggregated = aggregator.aggregate_samples(Pubkey::new_unique(), &samples, NOW).unwrap();
+        assert_eq!(aggregated.stale_sources, vec!["band", "pyth_future"]);
+        assert_eq!(aggregated.last_updated, NOW - 5);
+
+        // 仅剩超前报价时无可用价格
+        let future_only = vec![sample("pyth_future", 100_000_000, 50_000, -3_600)];
+        assert!(aggregator.aggregate_samples(Pubkey::new_unique(), &future_only, NOW).is_err());
+    }
+
+    /// 测试定点平方根。
+    #[test]
+    fn test_isqrt() {
+        for n in [0u128, 1, 2, 3, 4, 15, 16, 17, 1_000_000, u64::MAX as u128, u128::MAX] {
+            let root = isqrt(n);
+            assert!(root * root <= n);
+            assert!(root.checked_add(1).and_then(|r| r.checked_mul(r)).map_or(true, |sq| sq > n));
+        }
+    }
+}
@@ -359,6 +359,8 @@ impl Api3Service {
         
         Ok(OraclePriceResult {
             price,
+            confidence: 0,
+            exponent: DEFAULT_PRICE_EXPONENT,
             last_updated,
             oracle_name: oracle_name.to_string(),
         })
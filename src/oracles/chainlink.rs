@@ -9,11 +9,117 @@
  */
 
 // use crate::core::adapter: // 暂时注释掉:AdapterTrait; // 适配器元信息trait，统一接口
-use crate::oracles::adapter::OracleAdapter; // OracleAdapter trait
-use crate::oracles::traits::OracleParams;        // Oracle参数类型
+use crate::oracles::traits::{OracleAdapter, OracleAdapterType, OracleParams, OraclePriceResult, OracleTwapResult, OracleVwapResult}; // Oracle trait及参数类型
+use crate::dex::layout::{read_i128, read_u32, read_u64, read_u8}; // 零依赖字节解析
 use anchor_lang::prelude::*;                // Anchor预导入，包含Result、Pubkey等
 use std::sync::Arc;                         // Arc用于多线程安全
 
+/// Chainlink Store 程序（OCR2 喂价 Transmissions 账户的 owner）
+pub const CHAINLINK_STORE_PROGRAM_ID: Pubkey = pubkey!("HEvSKofvBgfaexv23kMabbYqxasxU3mQ4ibBMEmJWHny");
+/// Transmissions 账户鉴别符：sha256("account:Transmissions")[..8]
+pub const TRANSMISSIONS_DISCRIMINATOR: [u8; 8] = [96, 179, 69, 66, 128, 129, 73, 117];
+
+// Transmissions 头部字段偏移（含 8 字节鉴别符，头部按 packed 布局、总长 192 字节）
+const VERSION_OFFSET: usize = 8;            // version u8
+const DESCRIPTION_OFFSET: usize = 106;      // description [u8; 32]
+const DECIMALS_OFFSET: usize = 138;         // decimals u8
+const LATEST_ROUND_ID_OFFSET: usize = 143;  // latest_round_id u32
+const LIVE_LENGTH_OFFSET: usize = 148;      // live_length u32
+const LIVE_CURSOR_OFFSET: usize = 152;      // live_cursor u32
+/// 环形缓冲区起始偏移（鉴别符 8 + 头部 192）
+pub const TRANSMISSIONS_RING_OFFSET: usize = 200;
+/// 单条 Transmission 长度：slot u64 | timestamp u32 | padding u32 | answer i128 | padding 16
+pub const TRANSMISSION_LEN: usize = 48;
+
+/// 单轮报价
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transmission {
+    pub slot: u64,      // 写入 slot
+    pub timestamp: u32, // 观测时间（Unix 秒）
+    pub answer: i128,   // 报价（answer × 10^-decimals）
+}
+
+/// 最新一轮报价
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub round_id: u32,              // 轮次 ID
+    pub transmission: Transmission, // 报价
+}
+
+/// Chainlink OCR2 喂价 Transmissions 账户
+/// - 头部之后为 live_length 条实时环形缓冲区，live_cursor 指向下一条写入位置
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransmissionsState {
+    pub version: u8,          // 账户版本
+    pub description: String,  // 喂价描述（如 "SOL / USD"）
+    pub decimals: u8,         // 报价小数位
+    pub latest_round_id: u32, // 最新轮次 ID
+    pub live_length: u32,     // 实时环形缓冲区长度
+    pub live_cursor: u32,     // 下一条写入位置
+    pub latest: Option<Transmission>, // 最新一条报价（缓冲区为空时为 None）
+}
+
+impl TransmissionsState {
+    /// 从账户数据解析；鉴别符不符或环形缓冲区越界返回 InvalidAccount
+    pub fn unpack(data: &[u8]) -> anchor_lang::Result<Self> {
+        require!(data.get(..8) == Some(&TRANSMISSIONS_DISCRIMINATOR[..]), ErrorCode::InvalidAccount);
+        Self::parse(data).ok_or_else(|| error!(ErrorCode::InvalidAccount))
+    }
+
+    /// 从 AccountInfo 解析，并校验 owner 为 Chainlink Store 程序
+    pub fn from_account_info(account: &AccountInfo) -> anchor_lang::Result<Self> {
+        require_keys_eq!(*account.owner, CHAINLINK_STORE_PROGRAM_ID, ErrorCode::InvalidAccount);
+        Self::unpack(&account.try_borrow_data()?)
+    }
+
+    fn parse(data: &[u8]) -> Option<Self> {
+        let description = data.get(DESCRIPTION_OFFSET..DESCRIPTION_OFFSET + 32)?;
+        let live_length = read_u32(data, LIVE_LENGTH_OFFSET)?;
+        let live_cursor = read_u32(data, LIVE_CURSOR_OFFSET)?;
+        let latest = if live_length == 0 {
+            None
+        } else {
+            let index = (live_cursor as usize + live_length as usize - 1) % live_length as usize;
+            let offset = TRANSMISSIONS_RING_OFFSET + index * TRANSMISSION_LEN;
+            Some(Transmission {
+                slot: read_u64(data, offset)?,
+                timestamp: read_u32(data, offset + 8)?,
+                answer: read_i128(data, offset + 16)?,
+            })
+        };
+        Some(Self {
+            version: read_u8(data, VERSION_OFFSET)?,
+            description: String::from_utf8_lossy(description).trim_end_matches('\0').to_string(),
+            decimals: read_u8(data, DECIMALS_OFFSET)?,
+            latest_round_id: read_u32(data, LATEST_ROUND_ID_OFFSET)?,
+            live_length,
+            live_cursor,
+            latest,
+        })
+    }
+
+    /// 最新一轮报价；尚无报价返回 None
+    pub fn latest_round_data(&self) -> Option<Round> {
+        self.latest.map(|transmission| Round { round_id: self.latest_round_id, transmission })
+    }
+
+    /// 校验时效后映射为统一价格结果（exponent = -decimals，Chainlink 不提供置信区间）
+    pub fn price_result(&self, now: i64, max_age: u64) -> anchor_lang::Result<OraclePriceResult> {
+        let round = self.latest_round_data().ok_or_else(|| error!(ErrorCode::NoRoundData))?;
+        let age = now.saturating_sub(round.transmission.timestamp as i64);
+        require!(age <= max_age.min(i64::MAX as u64) as i64, ErrorCode::StalePrice);
+        let price = u64::try_from(round.transmission.answer).map_err(|_| error!(ErrorCode::InvalidAccount))?;
+        require!(price > 0, ErrorCode::InvalidAccount);
+        Ok(OraclePriceResult {
+            price,
+            confidence: 0,
+            exponent: -(self.decimals as i32),
+            last_updated: round.transmission.timestamp as i64,
+            oracle_name: "chainlink".to_string(),
+        })
+    }
+}
+
 /// Chainlink预言机适配器结构体
 /// - 用于对接Solana链上的Chainlink预言机，实现统一的Oracle适配接口
 /// - 设计为无状态结构体，便于多实例、线程安全
@@ -27,15 +133,39 @@ impl AdapterTrait for ChainlinkAdapter {
     }
 }
 
+impl ChainlinkAdapter {
+    /// 从 Transmissions 账户读取最新一轮价格（校验 owner 与时效）
+    pub fn price_from_account(&self, account: &AccountInfo, now: i64, max_age: u64) -> anchor_lang::Result<OraclePriceResult> {
+        TransmissionsState::from_account_info(account)?.price_result(now, max_age)
+    }
+}
+
 /// 实现OracleAdapter trait，提供get_price等核心功能
 impl OracleAdapter for ChainlinkAdapter {
     /// 获取Chainlink现价
-    /// - params: 价格参数（PriceParams结构体）
-    /// - 返回：价格数值（u64）
-    /// - 设计意图：集成Chainlink链上CPI，完成链上价格查询，便于统一调用
-    fn get_price(&self, params: &OracleParams) -> anchor_lang::Result<u64> {
-        // Chainlink get_price 业务逻辑（此处为示例，实际应集成Chainlink链上CPI调用）
-        Ok(0)
+    /// - 喂价需读取 Transmissions 账户，无账户上下文时返回 AccountRequired，请使用 price_from_account
+    fn get_price(&self, _params: &OracleParams) -> anchor_lang::Result<OraclePriceResult> {
+        err!(ErrorCode::AccountRequired)
+    }
+
+    /// Chainlink 喂价不提供TWAP
+    fn get_twap(&self, _params: &OracleParams) -> anchor_lang::Result<OracleTwapResult> {
+        err!(ErrorCode::Unsupported)
+    }
+
+    /// Chainlink 喂价不提供VWAP
+    fn get_vwap(&self, _params: &OracleParams) -> anchor_lang::Result<OracleVwapResult> {
+        err!(ErrorCode::Unsupported)
+    }
+
+    /// 返回适配器类型
+    fn adapter_type(&self) -> OracleAdapterType {
+        OracleAdapterType::Chainlink
+    }
+
+    /// 返回适配器名称
+    fn name(&self) -> &'static str {
+        "chainlink"
     }
 }
 
@@ -60,6 +190,15 @@ pub enum ErrorCode {
     /// 操作不支持
     #[msg("Operation unsupported")]
     Unsupported,
+    /// 需要传入喂价账户
+    #[msg("Feed account required")]
+    AccountRequired,
+    /// 喂价尚无报价
+    #[msg("No round data")]
+    NoRoundData,
+    /// 报价过期
+    #[msg("Stale price")]
+    StalePrice,
 }
 
 #[cfg(test)]
@@ -87,6 +226,71 @@ mod tests {
             price: 0,
         };
         let result = adapter.get_price(&params);
-        assert!(result.is_ok());
+        assert!(result.is_err());
+    }
+
+    /// 按 Transmissions 布局手工构造的 SOL/USD 账户字节，非链上转储（live_length = 3，live_cursor = 1，最新报价位于下标 0）
+    fn sol_usd_transmissions() -> Vec<u8> {
+        let mut data = vec![0u8; 200 + 3 * 48];
+        data[..8].copy_from_slice(&[96, 179, 69, 66, 128, 129, 73, 117]);
+        data[8] = 2;                                                    // version
+        data[106..115].copy_from_slice(b"SOL / USD");                   // description
+        data[138] = 8;                                                  // decimals
+        data[143..147].copy_from_slice(&1_234_567u32.to_le_bytes());    // latest_round_id
+        data[148..152].copy_from_slice(&3u32.to_le_bytes());            // live_length
+        data[152..156].copy_from_slice(&1u32.to_le_bytes());            // live_cursor
+        // 下标 0：最新一轮
+        data[200..208].copy_from_slice(&272_000_300u64.to_le_bytes());
+        data[208..212].copy_from_slice(&1_718_000_040u32.to_le_bytes());
+        data[216..232].copy_from_slice(&14_571_000_000i128.to_le_bytes());
+        // 下标 2：上一轮
+        data[296..304].copy_from_slice(&272_000_200u64.to_le_bytes());
+        data[304..308].copy_from_slice(&1_717_999_980u32.to_le_bytes());
+        data[312..328].copy_from_slice(&14_560_000_000i128.to_le_bytes());
+        data
+    }
+
+    /// 测试解析 Transmissions 环形缓冲区并取最新一轮报价。
+    #[test]
+    fn test_transmissions_decode() {
+        let state = TransmissionsState::unpack(&sol_usd_transmissions()).unwrap();
+        assert_eq!(state.version, 2);
+        assert_eq!(state.description, "SOL / USD");
+        assert_eq!(state.decimals, 8);
+        let round = state.latest_round_data().unwrap();
+        assert_eq!(round.round_id, 1_234_567);
+        assert_eq!(round.transmission.slot, 272_000_300);
+        assert_eq!(round.transmission.answer, 14_571_000_000);
+
+        let result = state.price_result(1_718_000_060, 60).unwrap();
+        assert_eq!((result.price, result.exponent, result.confidence), (14_571_000_000, -8, 0));
+        assert_eq!(result.last_updated, 1_718_000_040);
+        assert!(state.price_result(1_718_000_101, 60).is_err());
+
+        // 游标回绕：live_cursor = 0 时最新报价位于末尾下标 2
+        let mut data = sol_usd_transmissions();
+        data[152..156].copy_from_slice(&0u32.to_le_bytes());
+        let wrapped = TransmissionsState::unpack(&data).unwrap();
+        assert_eq!(wrapped.latest_round_data().unwrap().transmission.answer, 14_560_000_000);
+
+        // 环形缓冲区越界
+        assert!(TransmissionsState::unpack(&sol_usd_transmissions()[..220]).is_err());
+    }
+
+    /// 测试从账户读取价格时校验 owner 程序。
+    #[test]
+    fn test_chainlink_price_from_account() {
+        let key = Pubkey::new_unique();
+        let adapter = ChainlinkAdapter;
+        let mut lamports = 0u64;
+        let mut data = sol_usd_transmissions();
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &CHAINLINK_STORE_PROGRAM_ID, false, 0);
+        assert_eq!(adapter.price_from_account(&account, 1_718_000_060, 60).unwrap().price, 14_571_000_000);
+
+        let other_owner = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = sol_usd_transmissions();
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &other_owner, false, 0);
+        assert!(adapter.price_from_account(&account, 1_718_000_060, 60).is_err());
     }
 }
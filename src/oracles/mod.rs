@@ -31,10 +31,12 @@ pub mod chainlink_adapter;                // Chainlink适配器实现
 pub mod pyth_adapter;                     // Pyth适配器实现
 pub mod switchboard_adapter;              // Switchboard适配器实现
 pub mod adapter_registry;                 // 适配器注册表
+pub mod aggregator;                       // 多源价格聚合（中位数/置信度加权、离群剔除）
 
 // 只导出核心类型，避免命名冲突
 pub use traits::OracleAdapter;            // 核心trait定义
 pub use adapter::OracleAdapterRegistry;   // 适配器注册表
+pub use aggregator::{ConsensusOracleAggregator, AggregatedPrice}; // 多源聚合器与聚合结果
 
 
 /// Oracle模块版本号
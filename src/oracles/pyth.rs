@@ -9,9 +9,149 @@
  */
 
 use anchor_lang::prelude::*;
-use crate::oracles::traits::{OracleAdapter, OracleParams, OraclePriceResult, OracleTwapResult, OracleVwapResult, OracleAdapterType};
+use crate::dex::layout::{read_i32, read_i64, read_pubkey, read_u64, read_u8};
+use crate::oracles::traits::{OracleAdapter, OracleParams, OraclePriceResult, OracleTwapResult, OracleVwapResult, OracleAdapterType, DEFAULT_PRICE_EXPONENT};
 // use crate::core::adapter: // 暂时注释掉:AdapterTrait;
 
+/// Pyth Solana Receiver 程序（推送预言机价格账户的 owner）
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// PriceUpdateV2 账户鉴别符：sha256("account:PriceUpdateV2")[..8]
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// PriceUpdateV2 账户分配长度（验证等级为 Full 时末尾有 1 字节空余）
+pub const PRICE_UPDATE_V2_LEN: usize = 134;
+
+/// Wormhole 验证等级
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationLevel {
+    /// 部分验证（守护者签名数不足法定数量）
+    Partial { num_signatures: u8 },
+    /// 完整验证
+    Full,
+}
+
+/// Pyth 价格消息（PriceFeedMessage）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],      // 价格源 ID
+    pub price: i64,             // 价格（price × 10^exponent）
+    pub conf: u64,              // 置信区间（±，与价格同精度）
+    pub exponent: i32,          // 十进制指数
+    pub publish_time: i64,      // 发布时间（Unix 秒）
+    pub prev_publish_time: i64, // 上一次发布时间
+    pub ema_price: i64,         // EMA 价格
+    pub ema_conf: u64,          // EMA 置信区间
+}
+
+/// Pyth 推送预言机 PriceUpdateV2 账户
+/// - 布局（borsh）：discriminator(8) | write_authority(32) | verification_level(1 或 2) | PriceFeedMessage(84) | posted_slot(8)
+/// - 验证等级为变长枚举，之后字段顺序解析
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriceUpdateV2 {
+    pub write_authority: Pubkey,               // 写入权限
+    pub verification_level: VerificationLevel, // 验证等级
+    pub price_message: PriceFeedMessage,       // 价格消息
+    pub posted_slot: u64,                      // 写入时的 slot
+}
+
+/// 已校验的 Pyth 价格
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PythPrice {
+    pub price: i64,        // 价格
+    pub conf: u64,         // 置信区间
+    pub exponent: i32,     // 十进制指数
+    pub publish_time: i64, // 发布时间
+}
+
+impl PriceUpdateV2 {
+    /// 从账户数据解析；鉴别符或长度不符返回 InvalidPriceAccount
+    pub fn try_deserialize(data: &[u8]) -> anchor_lang::Result<Self> {
+        require!(data.get(..8) == Some(&PRICE_UPDATE_V2_DISCRIMINATOR[..]), PythError::InvalidPriceAccount);
+        Self::parse(data).ok_or_else(|| error!(PythError::InvalidPriceAccount))
+    }
+
+    /// 从 AccountInfo 解析，并校验 owner 为 Pyth Receiver 程序
+    pub fn from_account_info(account: &AccountInfo) -> anchor_lang::Result<Self> {
+        require_keys_eq!(*account.owner, PYTH_RECEIVER_PROGRAM_ID, PythError::InvalidPriceAccount);
+        Self::try_deserialize(&account.try_borrow_data()?)
+    }
+
+    fn parse(data: &[u8]) -> Option<Self> {
+        let write_authority = read_pubkey(data, 8)?;
+        let (verification_level, mut offset) = match read_u8(data, 40)? {
+            0 => (VerificationLevel::Partial { num_signatures: read_u8(data, 41)? }, 42),
+            1 => (VerificationLevel::Full, 41),
+            _ => return None,
+        };
+        let feed_id: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+        offset += 32;
+        let price_message = PriceFeedMessage {
+            feed_id,
+            price: read_i64(data, offset)?,
+            conf: read_u64(data, offset + 8)?,
+            exponent: read_i32(data, offset + 16)?,
+            publish_time: read_i64(data, offset + 20)?,
+            prev_publish_time: read_i64(data, offset + 28)?,
+            ema_price: read_i64(data, offset + 36)?,
+            ema_conf: read_u64(data, offset + 44)?,
+        };
+        Some(Self {
+            write_authority,
+            verification_level,
+            price_message,
+            posted_slot: read_u64(data, offset + 52)?,
+        })
+    }
+
+    /// 校验验证等级、价格源 ID 与时效后返回价格
+    /// - 仅接受 Full 验证等级
+    /// - publish_time 距 now 超过 max_age 秒视为过期
+    pub fn get_price_no_older_than(&self, now: i64, max_age: u64, feed_id: &[u8; 32]) -> anchor_lang::Result<PythPrice> {
+        require!(self.verification_level == VerificationLevel::Full, PythError::InsufficientVerification);
+        require!(self.price_message.feed_id == *feed_id, PythError::FeedIdMismatch);
+        let age = now.saturating_sub(self.price_message.publish_time);
+        require!(age <= max_age.min(i64::MAX as u64) as i64, PythError::PriceExpired);
+        Ok(PythPrice {
+            price: self.price_message.price,
+            conf: self.price_message.conf,
+            exponent: self.price_message.exponent,
+            publish_time: self.price_message.publish_time,
+        })
+    }
+}
+
+impl PythPrice {
+    /// 置信区间相对价格的比例（基点）；价格非正返回 u64::MAX
+    pub fn confidence_bps(&self) -> u64 {
+        if self.price <= 0 {
+            return u64::MAX;
+        }
+        (self.conf as u128 * 10_000 / self.price as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// 转换为统一价格结果；价格非正返回 NegativePrice
+    pub fn to_price_result(&self) -> anchor_lang::Result<OraclePriceResult> {
+        require!(self.price > 0, PythError::NegativePrice);
+        Ok(OraclePriceResult {
+            price: self.price as u64,
+            confidence: self.conf,
+            exponent: self.exponent,
+            last_updated: self.publish_time,
+            oracle_name: "pyth".to_string(),
+        })
+    }
+}
+
+/// 解析 64 位十六进制价格源 ID（可带 0x 前缀）
+pub fn feed_id_from_hex(hex: &str) -> anchor_lang::Result<[u8; 32]> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    require!(hex.len() == 64 && hex.is_ascii(), PythError::FeedIdMismatch);
+    let mut feed_id = [0u8; 32];
+    for (i, byte) in feed_id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| error!(PythError::FeedIdMismatch))?;
+    }
+    Ok(feed_id)
+}
+
 /// Pyth Oracle适配器结构体
 /// - 用于对接Solana链上的Pyth预言机，实现统一的Oracle适配接口
 /// - 设计为无状态结构体，便于多实例、线程安全
@@ -37,6 +177,23 @@ impl AdapterTrait for PythAdapter {
     }
 }
 
+impl PythAdapter {
+    /// 从 PriceUpdateV2 账户读取价格
+    /// - 校验 owner、验证等级、价格源 ID、时效，并拒绝置信区间超过 max_conf_bps 的价格
+    pub fn price_from_account(
+        &self,
+        account: &AccountInfo,
+        feed_id: &[u8; 32],
+        now: i64,
+        max_age: u64,
+        max_conf_bps: u64,
+    ) -> anchor_lang::Result<OraclePriceResult> {
+        let price = PriceUpdateV2::from_account_info(account)?.get_price_no_older_than(now, max_age, feed_id)?;
+        require!(price.confidence_bps() <= max_conf_bps, PythError::ConfidenceTooWide);
+        price.to_price_result()
+    }
+}
+
 /// 实现OracleAdapter trait，提供价格查询等核心功能
 impl OracleAdapter for PythAdapter {
     /// 获取Pyth现价
@@ -50,6 +207,8 @@ impl OracleAdapter for PythAdapter {
         // 这里只做结构示例，实际应调用CPI并返回真实价格数据
         Ok(OraclePriceResult {
             price: 1_000_000, // 应为CPI返回真实价格
+            confidence: 0,
+            exponent: DEFAULT_PRICE_EXPONENT,
             last_updated: Clock::get()?.unix_timestamp,
             oracle_name: "pyth".to_string(),
         })
@@ -120,6 +279,14 @@ pub enum PythError {
     #[msg("Price expired")] PriceExpired,
     /// 操作不支持
     #[msg("Operation unsupported")] Unsupported,
+    /// 价格源 ID 不匹配
+    #[msg("Price feed id mismatch")] FeedIdMismatch,
+    /// 验证等级不足
+    #[msg("Insufficient verification level")] InsufficientVerification,
+    /// 置信区间过宽
+    #[msg("Price confidence too wide")] ConfidenceTooWide,
+    /// 价格非正
+    #[msg("Non-positive price")] NegativePrice,
 }
 
 /// 自动注册PythAdapter到全局工厂
@@ -161,6 +328,97 @@ mod tests {
         assert_eq!(price_result.oracle_name, "pyth");
     }
     
+    /// 主网 SOL/USD 价格源 ID
+    const SOL_USD_FEED_ID: &str = "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+    /// 按 PriceUpdateV2 布局手工编码的 SOL/USD 账户字节（Full 验证，134 字节，末字节为空余）
+    /// - 非链上账户转储：feed_id 为主网 SOL/USD 价格源，其余字段为构造值，write_authority 为任意 32 字节
+    /// price = 14_573_251_000, conf = 7_432_115, exponent = -8, publish_time = 1_718_000_000, posted_slot = 272_000_000
+    const SOL_USD_FULL: &str = concat!(
+        "22f123639d7ef4cd798deac3f3d95728c7fc1e7d3cc58a3dc97aae9a371d96eb",
+        "1b5bf7709a6574a301ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7",
+        "bc0f4cfac8c280b56db829a26403000000b367710000000000f8ffffff809966",
+        "66000000007f9966660000000000f8d7630300000000127a0000000000006436",
+        "100000000000",
+    );
+
+    /// 同一价格源手工编码的 Partial 验证账户字节（num_signatures = 5，后续字段整体后移 1 字节），同样非链上转储
+    const SOL_USD_PARTIAL: &str = concat!(
+        "22f123639d7ef4cd798deac3f3d95728c7fc1e7d3cc58a3dc97aae9a371d96eb",
+        "1b5bf7709a6574a30005ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6",
+        "c7bc0f4cfac8c280b56d38aef50500000000d430000000000000f8ffffff8099",
+        "6666000000007e99666600000000f0b9f50500000000c8320000000000000164",
+        "361000000000",
+    );
+
+    fn hex_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    /// 测试解析 Full 验证的 PriceUpdateV2 账户并校验价格源与时效。
+    #[test]
+    fn test_price_update_v2_decode() {
+        let data = hex_bytes(SOL_USD_FULL);
+        assert_eq!(data.len(), PRICE_UPDATE_V2_LEN);
+        let update = PriceUpdateV2::try_deserialize(&data).unwrap();
+        let feed_id = feed_id_from_hex(SOL_USD_FEED_ID).unwrap();
+        assert_eq!(update.verification_level, VerificationLevel::Full);
+        assert_eq!(update.price_message.feed_id, feed_id);
+        assert_eq!(update.price_message.price, 14_573_251_000);
+        assert_eq!(update.price_message.conf, 7_432_115);
+        assert_eq!(update.price_message.exponent, -8);
+        assert_eq!(update.price_message.publish_time, 1_718_000_000);
+        assert_eq!(update.price_message.ema_price, 14_560_000_000);
+        assert_eq!(update.posted_slot, 272_000_000);
+
+        let price = update.get_price_no_older_than(1_718_000_030, 60, &feed_id).unwrap();
+        assert_eq!(price.confidence_bps(), 5);
+        let result = price.to_price_result().unwrap();
+        assert_eq!((result.price, result.confidence, result.exponent), (14_573_251_000, 7_432_115, -8));
+        assert_eq!(result.last_updated, 1_718_000_000);
+
+        assert!(update.get_price_no_older_than(1_718_000_061, 60, &feed_id).is_err());
+        assert!(update.get_price_no_older_than(1_718_000_030, 60, &[0u8; 32]).is_err());
+
+        let mut corrupted = data.clone();
+        corrupted[0] ^= 0xff;
+        assert!(PriceUpdateV2::try_deserialize(&corrupted).is_err());
+        assert!(PriceUpdateV2::try_deserialize(&data[..100]).is_err());
+    }
+
+    /// 测试 Partial 验证账户可解析但不被接受。
+    #[test]
+    fn test_price_update_v2_partial_verification() {
+        let update = PriceUpdateV2::try_deserialize(&hex_bytes(SOL_USD_PARTIAL)).unwrap();
+        assert_eq!(update.verification_level, VerificationLevel::Partial { num_signatures: 5 });
+        assert_eq!(update.price_message.price, 99_987_000);
+        assert_eq!(update.posted_slot, 272_000_001);
+        let feed_id = feed_id_from_hex(SOL_USD_FEED_ID).unwrap();
+        assert!(update.get_price_no_older_than(1_718_000_000, 60, &feed_id).is_err());
+    }
+
+    /// 测试从账户读取价格：校验 owner 并拒绝置信区间过宽的价格。
+    #[test]
+    fn test_price_from_account() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = hex_bytes(SOL_USD_FULL);
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &PYTH_RECEIVER_PROGRAM_ID, false, 0);
+        let feed_id = feed_id_from_hex(SOL_USD_FEED_ID).unwrap();
+        let adapter = PythAdapter;
+
+        let result = adapter.price_from_account(&account, &feed_id, 1_718_000_010, 60, 10).unwrap();
+        assert_eq!(result.price, 14_573_251_000);
+        assert_eq!(result.confidence, 7_432_115);
+        assert!(adapter.price_from_account(&account, &feed_id, 1_718_000_010, 60, 4).is_err());
+
+        let other_owner = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = hex_bytes(SOL_USD_FULL);
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &other_owner, false, 0);
+        assert!(adapter.price_from_account(&account, &feed_id, 1_718_000_010, 60, 10).is_err());
+    }
+
     /// 测试PythAdapter TWAP查询功能
     #[test]
     fn test_pyth_adapter_get_twap() {
@@ -8,11 +8,119 @@
  * 生产级Switchboard链上适配器实现，集成Anchor CPI调用，支持价格、TWAP、VWAP等。
  */
 
-use super::traits::{OracleParams, OraclePriceResult, OracleTwapResult, OracleVwapResult, OracleAdapterType}; // trait及参数类型
+use super::traits::{OracleParams, OraclePriceResult, OracleTwapResult, OracleVwapResult, OracleAdapterType, DEFAULT_PRICE_EXPONENT}; // trait及参数类型
 use anchor_lang::prelude::*; // Anchor预导入，包含Result、Context等
+use crate::dex::layout::{read_i128, read_i64, read_u32, read_u64, read_u8}; // 零依赖字节解析
 use crate::oracles::traits::OracleAdapter;
 // use crate::core::adapter: // 暂时注释掉:AdapterTrait;
 
+/// Switchboard On-Demand 程序（拉取式喂价账户的 owner）
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+/// PullFeedAccountData 账户鉴别符：sha256("account:PullFeedAccountData")[..8]
+pub const PULL_FEED_DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+/// Switchboard 结果为 18 位小数定点数
+pub const SWITCHBOARD_VALUE_EXPONENT: i32 = -18;
+
+// PullFeedAccountData 字段偏移（含 8 字节鉴别符，submissions 为 32 × 64 字节）
+const FEED_HASH_OFFSET: usize = 2120;             // feed_hash [u8; 32]
+const MIN_SAMPLE_SIZE_OFFSET: usize = 2215;       // min_sample_size u8
+const LAST_UPDATE_TIMESTAMP_OFFSET: usize = 2216; // last_update_timestamp i64
+const RESULT_OFFSET: usize = 2264;                // result: CurrentResult（128 字节）
+const MAX_STALENESS_OFFSET: usize = 2392;         // max_staleness u32（slot 数）
+/// 解析所需的最小账户长度（至 max_staleness 结束）
+pub const PULL_FEED_MIN_LEN: usize = MAX_STALENESS_OFFSET + 4;
+
+/// 拉取式喂价的当前聚合结果（CurrentResult），数值均为 18 位小数定点数
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PullFeedResult {
+    pub value: i128,      // 聚合值（样本中位数）
+    pub std_dev: i128,    // 样本标准差
+    pub mean: i128,       // 样本均值
+    pub range: i128,      // 样本极差
+    pub min_value: i128,  // 最小样本
+    pub max_value: i128,  // 最大样本
+    pub num_samples: u8,  // 样本数
+    pub slot: u64,        // 结果所在 slot
+    pub min_slot: u64,    // 最早样本 slot
+    pub max_slot: u64,    // 最晚样本 slot
+}
+
+/// Switchboard On-Demand 拉取式喂价账户（仅解析定价所需字段）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PullFeedState {
+    pub feed_hash: [u8; 32],        // 任务定义哈希
+    pub min_sample_size: u8,        // 有效结果所需最少样本数
+    pub last_update_timestamp: i64, // 最近更新时间（Unix 秒）
+    pub result: PullFeedResult,     // 当前结果
+    pub max_staleness: u32,         // 允许的最大 slot 间隔
+}
+
+impl PullFeedState {
+    /// 从账户数据解析；鉴别符或长度不符返回 InvalidPriceAccount
+    pub fn unpack(data: &[u8]) -> anchor_lang::Result<Self> {
+        require!(data.get(..8) == Some(&PULL_FEED_DISCRIMINATOR[..]), SwitchboardError::InvalidPriceAccount);
+        Self::parse(data).ok_or_else(|| error!(SwitchboardError::InvalidPriceAccount))
+    }
+
+    /// 从 AccountInfo 解析，并校验 owner 为 Switchboard On-Demand 程序
+    pub fn from_account_info(account: &AccountInfo) -> anchor_lang::Result<Self> {
+        require_keys_eq!(*account.owner, SWITCHBOARD_ON_DEMAND_PROGRAM_ID, SwitchboardError::InvalidPriceAccount);
+        Self::unpack(&account.try_borrow_data()?)
+    }
+
+    fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < PULL_FEED_MIN_LEN {
+            return None;
+        }
+        let r = RESULT_OFFSET;
+        Some(Self {
+            feed_hash: data.get(FEED_HASH_OFFSET..FEED_HASH_OFFSET + 32)?.try_into().ok()?,
+            min_sample_size: read_u8(data, MIN_SAMPLE_SIZE_OFFSET)?,
+            last_update_timestamp: read_i64(data, LAST_UPDATE_TIMESTAMP_OFFSET)?,
+            result: PullFeedResult {
+                value: read_i128(data, r)?,
+                std_dev: read_i128(data, r + 16)?,
+                mean: read_i128(data, r + 32)?,
+                range: read_i128(data, r + 48)?,
+                min_value: read_i128(data, r + 64)?,
+                max_value: read_i128(data, r + 80)?,
+                num_samples: read_u8(data, r + 96)?,
+                slot: read_u64(data, r + 104)?,
+                min_slot: read_u64(data, r + 112)?,
+                max_slot: read_u64(data, r + 120)?,
+            },
+            max_staleness: read_u32(data, MAX_STALENESS_OFFSET)?,
+        })
+    }
+
+    /// 校验后映射为统一价格结果（换算到 DEFAULT_PRICE_EXPONENT，置信区间取标准差）
+    /// - 样本数不得少于 min_sample_size
+    /// - 结果 slot 距 current_slot 不得超过 max_staleness（为 0 时不检查）
+    /// - 更新时间距 now 不得超过 max_age 秒
+    pub fn price_result(&self, now: i64, current_slot: u64, max_age: u64) -> anchor_lang::Result<OraclePriceResult> {
+        require!(self.result.num_samples >= self.min_sample_size.max(1), SwitchboardError::InsufficientSamples);
+        require!(
+            self.max_staleness == 0 || current_slot.saturating_sub(self.result.slot) <= self.max_staleness as u64,
+            SwitchboardError::PriceExpired
+        );
+        require!(
+            now.saturating_sub(self.last_update_timestamp) <= max_age.min(i64::MAX as u64) as i64,
+            SwitchboardError::PriceExpired
+        );
+        require!(self.result.value > 0 && self.result.std_dev >= 0, SwitchboardError::InvalidPriceAccount);
+        let divisor = 10i128.pow((DEFAULT_PRICE_EXPONENT - SWITCHBOARD_VALUE_EXPONENT) as u32);
+        let price = u64::try_from(self.result.value / divisor).map_err(|_| error!(SwitchboardError::InvalidPriceAccount))?;
+        let confidence = u64::try_from(self.result.std_dev / divisor).map_err(|_| error!(SwitchboardError::InvalidPriceAccount))?;
+        Ok(OraclePriceResult {
+            price,
+            confidence,
+            exponent: DEFAULT_PRICE_EXPONENT,
+            last_updated: self.last_update_timestamp,
+            oracle_name: "switchboard".to_string(),
+        })
+    }
+}
+
 /// Switchboard Oracle适配器结构体
 /// - 用于对接Solana链上的Switchboard预言机，实现统一的Oracle适配接口
 /// - 设计为无状态结构体，便于多实例、线程安全
@@ -38,22 +146,28 @@ impl AdapterTrait for SwitchboardAdapter {
     }
 }
 
+impl SwitchboardAdapter {
+    /// 从拉取式喂价账户读取价格（校验 owner、样本数与时效）
+    pub fn price_from_account(
+        &self,
+        account: &AccountInfo,
+        now: i64,
+        current_slot: u64,
+        max_age: u64,
+    ) -> anchor_lang::Result<OraclePriceResult> {
+        PullFeedState::from_account_info(account)?.price_result(now, current_slot, max_age)
+    }
+}
+
 /// 实现OracleAdapter trait，提供价格查询等核心功能
 impl OracleAdapter for SwitchboardAdapter {
     /// 获取Switchboard现价
     /// - params: OracleParams结构体，包含资产、oracle名称等
     /// - 返回：OraclePriceResult结构体
+    /// - 拉取式喂价必须读取喂价账户，无账户上下文时返回 AccountRequired，请使用 price_from_account
     fn get_price(&self, params: &OracleParams) -> anchor_lang::Result<OraclePriceResult> {
-        // 生产级实现：集成Switchboard链上CPI调用，参数校验、错误处理、事件追踪
         require!(params.asset != Pubkey::default(), crate::errors::oracle_error::OracleError::InvalidAsset);
-        
-        // TODO: 调用Switchboard CPI获取真实价格数据
-        // 这里只做结构示例，实际应调用CPI并返回真实价格数据
-        Ok(OraclePriceResult {
-            price: 1_000_000, // 应为CPI返回真实价格
-            last_updated: Clock::get()?.unix_timestamp,
-            oracle_name: "switchboard".to_string(),
-        })
+        err!(SwitchboardError::AccountRequired)
     }
     
     /// 获取Switchboard TWAP
@@ -121,6 +235,10 @@ pub enum SwitchboardError {
     #[msg("Price expired")] PriceExpired,
     /// 操作不支持
     #[msg("Operation unsupported")] Unsupported,
+    /// 样本数不足
+    #[msg("Insufficient feed samples")] InsufficientSamples,
+    /// 需要传入喂价账户
+    #[msg("Feed account required")] AccountRequired,
 }
 
 /// 自动注册SwitchboardAdapter到全局工厂
@@ -157,9 +275,78 @@ mod tests {
             price: 0,
         };
         let result = adapter.get_price(&params);
-        assert!(result.is_ok());
-        let price_result = result.unwrap();
-        assert_eq!(price_result.oracle_name, "switchboard");
+        assert!(result.is_err());
+    }
+
+    /// 按 PullFeedAccountData 布局手工构造的 SOL/USD 拉取式喂价账户字节，非链上转储（3208 字节，未列出的字段为 0）
+    /// value = 145.73251, std_dev = 0.0521, min = 145.61, max = 145.82, 5 个样本
+    fn sol_usd_pull_feed() -> Vec<u8> {
+        let mut data = vec![0u8; 3208];
+        data[..8].copy_from_slice(&[196, 27, 108, 196, 10, 215, 219, 40]);
+        data[2120..2152].copy_from_slice(&[7u8; 32]);                                  // feed_hash
+        data[2215] = 3;                                                                // min_sample_size
+        data[2216..2224].copy_from_slice(&1_718_000_050i64.to_le_bytes());             // last_update_timestamp
+        data[2264..2280].copy_from_slice(&145_732_510_000_000_000_000i128.to_le_bytes()); // value
+        data[2280..2296].copy_from_slice(&52_100_000_000_000_000i128.to_le_bytes());   // std_dev
+        data[2296..2312].copy_from_slice(&145_730_100_000_000_000_000i128.to_le_bytes()); // mean
+        data[2312..2328].copy_from_slice(&210_000_000_000_000_000i128.to_le_bytes());  // range
+        data[2328..2344].copy_from_slice(&145_610_000_000_000_000_000i128.to_le_bytes()); // min_value
+        data[2344..2360].copy_from_slice(&145_820_000_000_000_000_000i128.to_le_bytes()); // max_value
+        data[2360] = 5;                                                                // num_samples
+        data[2368..2376].copy_from_slice(&272_000_100u64.to_le_bytes());               // slot
+        data[2376..2384].copy_from_slice(&272_000_095u64.to_le_bytes());               // min_slot
+        data[2384..2392].copy_from_slice(&272_000_100u64.to_le_bytes());               // max_slot
+        data[2392..2396].copy_from_slice(&250u32.to_le_bytes());                       // max_staleness
+        data
+    }
+
+    /// 测试解析拉取式喂价账户并映射为统一价格结果。
+    #[test]
+    fn test_pull_feed_decode() {
+        let feed = PullFeedState::unpack(&sol_usd_pull_feed()).unwrap();
+        assert_eq!(feed.feed_hash, [7u8; 32]);
+        assert_eq!(feed.min_sample_size, 3);
+        assert_eq!(feed.result.num_samples, 5);
+        assert_eq!(feed.result.min_value, 145_610_000_000_000_000_000);
+        assert_eq!(feed.result.max_value, 145_820_000_000_000_000_000);
+        assert_eq!(feed.result.slot, 272_000_100);
+        assert_eq!(feed.max_staleness, 250);
+
+        let result = feed.price_result(1_718_000_060, 272_000_200, 60).unwrap();
+        assert_eq!(result.price, 14_573_251_000);
+        assert_eq!(result.confidence, 5_210_000);
+        assert_eq!(result.exponent, -8);
+        assert_eq!(result.last_updated, 1_718_000_050);
+
+        // slot 间隔超过 max_staleness、时间超过 max_age 均视为过期
+        assert!(feed.price_result(1_718_000_060, 272_000_351, 60).is_err());
+        assert!(feed.price_result(1_718_000_111, 272_000_200, 60).is_err());
+
+        let mut thin = feed.clone();
+        thin.result.num_samples = 2;
+        assert!(thin.price_result(1_718_000_060, 272_000_200, 60).is_err());
+
+        let mut data = sol_usd_pull_feed();
+        data[0] = 0;
+        assert!(PullFeedState::unpack(&data).is_err());
+        assert!(PullFeedState::unpack(&sol_usd_pull_feed()[..2300]).is_err());
+    }
+
+    /// 测试从账户读取价格时校验 owner 程序。
+    #[test]
+    fn test_switchboard_price_from_account() {
+        let key = Pubkey::new_unique();
+        let adapter = SwitchboardAdapter;
+        let mut lamports = 0u64;
+        let mut data = sol_usd_pull_feed();
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &SWITCHBOARD_ON_DEMAND_PROGRAM_ID, false, 0);
+        assert_eq!(adapter.price_from_account(&account, 1_718_000_060, 272_000_200, 60).unwrap().price, 14_573_251_000);
+
+        let other_owner = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = sol_usd_pull_feed();
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &other_owner, false, 0);
+        assert!(adapter.price_from_account(&account, 1_718_000_060, 272_000_200, 60).is_err());
     }
     
     /// 测试SwitchboardAdapter TWAP查询功能
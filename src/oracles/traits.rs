@@ -14,13 +14,57 @@ pub struct OracleParams {
     pub price: u64,
 }
 
+/// 未提供十进制指数时的默认价格精度，与 PRICE_PRECISION（1e8）一致
+pub const DEFAULT_PRICE_EXPONENT: i32 = -8;
+
 /// Oracle价格结果结构体
 /// - 用于返回Oracle价格查询结果
 /// - 设计意图：标准化Oracle查询结果，便于统一处理
+/// - 实际价格 = price × 10^exponent，confidence 与 price 同精度
 #[derive(Debug, Clone)]
 pub struct OraclePriceResult {
     /// 价格数值
     pub price: u64,
+    /// 置信区间（±，0 表示未提供）
+    pub confidence: u64,
+    /// 十进制指数
+    pub exponent: i32,
+    /// 最后更新时间戳
+    pub last_updated: i64,
+    /// Oracle名称
+    pub oracle_name: String,
+}
+
+impl OraclePriceResult {
+    /// 置信区间相对价格的比例（基点），便于调用方拒绝置信区间过宽的价格；价格为 0 返回 u64::MAX
+    pub fn confidence_bps(&self) -> u64 {
+        if self.price == 0 {
+            return u64::MAX;
+        }
+        (self.confidence as u128 * 10_000 / self.price as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// 将价格与置信区间换算到目标指数；溢出返回 None
+    pub fn scaled_to(&self, exponent: i32) -> Option<(u64, u64)> {
+        let shift = self.exponent.checked_sub(exponent)?;
+        let factor = 10u128.checked_pow(shift.unsigned_abs())?;
+        let scale = |value: u64| -> Option<u64> {
+            let scaled = if shift >= 0 { (value as u128).checked_mul(factor)? } else { value as u128 / factor };
+            u64::try_from(scaled).ok()
+        };
+        Some((scale(self.price)?, scale(self.confidence)?))
+    }
+}
+
+/// Oracle价格样本结构体
+/// - 在现价基础上附带置信区间，用于多源聚合时的离群剔除与置信度加权
+/// - price/confidence 统一为 DEFAULT_PRICE_EXPONENT 精度，0 置信区间表示该预言机不提供
+#[derive(Debug, Clone, PartialEq)]
+pub struct OraclePriceSample {
+    /// 价格数值
+    pub price: u64,
+    /// 置信区间（±，与价格同精度）
+    pub confidence: u64,
     /// 最后更新时间戳
     pub last_updated: i64,
     /// Oracle名称
@@ -86,6 +130,21 @@ pub trait OracleAdapter: AdapterTrait {
     /// - 返回：OracleTwapResult结构体
     fn get_twap(&self, params: &OracleParams) -> anchor_lang::Result<OracleTwapResult>;
     
+    /// 获取带置信区间的现价样本
+    /// - 默认由 get_price 构造，价格与置信区间换算到 DEFAULT_PRICE_EXPONENT，便于多源比较
+    fn get_price_sample(&self, params: &OracleParams) -> anchor_lang::Result<OraclePriceSample> {
+        let result = self.get_price(params)?;
+        let (price, confidence) = result.scaled_to(DEFAULT_PRICE_EXPONENT).ok_or_else(|| {
+            crate::errors::oracle_error::OracleError::InvalidPriceData { reason: "price exponent overflow".to_string() }
+        })?;
+        Ok(OraclePriceSample {
+            price,
+            confidence,
+            last_updated: result.last_updated,
+            oracle_name: result.oracle_name,
+        })
+    }
+    
     /// 获取VWAP
     /// - params: OracleParams结构体
     /// - 返回：OracleVwapResult结构体
@@ -211,6 +270,8 @@ impl OracleAggregator for DefaultOracleAggregator {
         
         Ok(OraclePriceResult {
             price: total_price / valid_count,
+            confidence: 0,
+            exponent: DEFAULT_PRICE_EXPONENT,
             last_updated,
             oracle_name: "aggregated".to_string(),
         })
@@ -310,12 +371,30 @@ mod tests {
     fn test_oracle_price_result() {
         let result = OraclePriceResult {
             price: 1000,
+            confidence: 0,
+            exponent: DEFAULT_PRICE_EXPONENT,
             last_updated: 1234567890,
             oracle_name: "test".to_string(),
         };
         assert_eq!(result.price, 1000);
         assert_eq!(result.oracle_name, "test");
     }
+
+    /// 测试置信区间比例与指数换算
+    #[test]
+    fn test_oracle_price_result_scaling() {
+        let result = OraclePriceResult {
+            price: 14_573_251,
+            confidence: 7_432,
+            exponent: -5,
+            last_updated: 0,
+            oracle_name: "pyth".to_string(),
+        };
+        assert_eq!(result.confidence_bps(), 5);
+        assert_eq!(result.scaled_to(-8), Some((14_573_251_000, 7_432_000)));
+        assert_eq!(result.scaled_to(-2), Some((14_573, 7)));
+        assert_eq!(result.scaled_to(i32::MIN), None);
+    }
     
     /// 测试OracleAdapterType枚举
     #[test]
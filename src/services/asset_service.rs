@@ -325,6 +325,7 @@ impl AssetService {
         exec_params: Option<ExecutionParams>,
         strategy_params: Option<StrategyParams>,
         oracle_params: Option<OracleParams>,
+        accounts: &[AccountInfo], // DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带
     ) -> anchor_lang::Result<()> {
         // 1. 算法/策略融合：如有 ExecutionParams，查找并调用已注册的 ExecutionStrategy trait 实现
         if let Some(exec_params) = &exec_params {
@@ -355,7 +356,7 @@ impl AssetService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: asset.mint,
                             output_mint: exec_params.output_mint,
@@ -364,10 +365,8 @@ impl AssetService {
                             user: buyer,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        // 用 swap_result.amount_out 作为实际买入数量，swap_result.fee 参与后续决策
-                        // 可根据 swap_result.avg_price 更新 final_price
-                        final_price = swap_result.amount_out; // 或 avg_price
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_price = quote.amount_out;
                     }
                 }
             }
@@ -394,6 +393,7 @@ impl AssetService {
         exec_params: Option<ExecutionParams>,
         strategy_params: Option<StrategyParams>,
         oracle_params: Option<OracleParams>,
+        accounts: &[AccountInfo], // DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带
     ) -> anchor_lang::Result<()> {
         // 1. 算法/策略融合：如有 ExecutionParams，查找并调用已注册的 ExecutionStrategy trait 实现
         if let Some(exec_params) = &exec_params {
@@ -422,7 +422,7 @@ impl AssetService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: asset.mint,
                             output_mint: exec_params.output_mint,
@@ -431,8 +431,8 @@ impl AssetService {
                             user: seller,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_price = swap_result.amount_out;
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_price = quote.amount_out;
                     }
                 }
             }
@@ -460,6 +460,7 @@ impl AssetService {
         exec_params: Option<ExecutionParams>,
         strategy_params: Option<StrategyParams>,
         oracle_params: Option<OracleParams>,
+        accounts: &[AccountInfo], // DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带
     ) -> anchor_lang::Result<()> {
         // 1. 算法/策略融合：如有 ExecutionParams，查找并调用已注册的 ExecutionStrategy trait 实现
         if let Some(exec_params) = &exec_params {
@@ -488,7 +489,7 @@ impl AssetService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: from.mint,
                             output_mint: exec_params.output_mint,
@@ -497,8 +498,8 @@ impl AssetService {
                             user: authority,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_to_amount = swap_result.amount_out;
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_to_amount = quote.amount_out;
                     }
                 }
             }
@@ -739,7 +740,16 @@ impl AssetService {
         service.execute_split(source, new_asset, amount, authority) // 调用拆分逻辑
     }
     /// 获取资产价格，融合DEX/Oracle
-    pub fn get_price(params: &crate::core::types::PriceParams) -> anchor_lang::Result<u64> {
+    ///
+    /// # 参数
+    /// - `params`: 价格参数。
+    /// - `trade`: 按 DEX 池报价时的交易参数（dex_name、代币对与数量），为 None 时不走 DEX。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 trade 时必须携带。
+    pub fn get_price(
+        params: &crate::core::types::PriceParams,
+        trade: Option<&TradeParams>,
+        accounts: &[AccountInfo],
+    ) -> anchor_lang::Result<u64> {
         // 1. 优先通过oracle_name获取链上预言机价格
         if let Some(oracle_name) = &params.oracle_name {
             let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
@@ -751,12 +761,12 @@ impl AssetService {
             }
         }
         // 2. 若未指定oracle或未获取到，则尝试通过DEX聚合价格
-        if let Some(dex_name) = &params.dex_name {
+        if let Some(trade) = trade {
             let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
-            if let Some(adapter) = factory.get(dex_name) {
-                if let Some(dex_adapter) = adapter.as_any().downcast_ref::<Arc<dyn crate::dex::traits::DexAdapter>>() {
-                    let swap_result = dex_adapter.quote(params)?;
-                    return Ok(swap_result.avg_price);
+            if let Some(adapter) = factory.get(&trade.dex_name) {
+                if let Some(dex_adapter) = adapter.as_any().downcast_ref::<Arc<dyn crate::dex::adapter::DexAdapter>>() {
+                    let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), trade, accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                    return Ok(quote.avg_price);
                 }
             }
         }
@@ -155,7 +155,7 @@ trait BasketRebalanceWithAlgoAndAdapters {
         basket_index: &mut BasketIndexState, // 可变篮子状态
         new_weights: Vec<u64>, // 新权重数组
         algo: &dyn crate::algorithms::traits::ExecutionStrategy, // 算法trait对象
-        dex: &dyn crate::dex::traits::DexAdapter, // DEX适配器trait对象
+        dex: &dyn crate::dex::adapter::DexAdapter, // DEX适配器trait对象
         oracle: &dyn crate::oracles::traits::OracleAdapter, // Oracle适配器trait对象
         ctx: anchor_lang::prelude::Context<crate::algorithms::traits::Execute>, // Anchor上下文
         params: &crate::algorithms::traits::ExecutionParams, // 算法执行参数
@@ -170,7 +170,7 @@ impl BasketRebalanceWithAlgoAndAdapters for RebalanceWithAlgoAndAdaptersService
         basket_index: &mut BasketIndexState, // 可变篮子状态
         new_weights: Vec<u64>, // 新权重数组
         algo: &dyn crate::algorithms::traits::ExecutionStrategy, // 算法trait对象
-        dex: &dyn crate::dex::traits::DexAdapter, // DEX适配器trait对象
+        dex: &dyn crate::dex::adapter::DexAdapter, // DEX适配器trait对象
         oracle: &dyn crate::oracles::traits::OracleAdapter, // Oracle适配器trait对象
         ctx: anchor_lang::prelude::Context<crate::algorithms::traits::Execute>, // Anchor上下文
         params: &crate::algorithms::traits::ExecutionParams, // 算法执行参数
@@ -201,10 +201,11 @@ trait BasketQuotable {
     /// - `basket`: 篮子状态对象。
     /// - `params`: 交易参数。
     /// - `price_params`: 价格参数。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带，否则返回 MissingPoolAccounts。
     ///
     /// # 返回值
     /// - 返回报价结果，失败返回 BasketError。
-    fn quote(&self, basket: &BasketIndexState, params: &TradeParams, price_params: &OracleParams) -> anchor_lang::Result<u64>; // trait方法签名，类型安全
+    fn quote(&self, basket: &BasketIndexState, params: &TradeParams, price_params: &OracleParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64>; // trait方法签名，类型安全
 }
 
 /// 篮子报价服务实现
@@ -215,7 +216,7 @@ impl BasketQuotable for QuoteBasketService {
     /// 报价实现
     ///
     /// - 获取篮子报价，融合DEX/Oracle
-    fn quote(params: &TradeParams, price_params: &OracleParams) -> anchor_lang::Result<u64> {
+    fn quote(&self, _basket: &BasketIndexState, params: &TradeParams, price_params: &OracleParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
         // 1. 优先通过oracle_name获取链上预言机价格
         if let Some(oracle_name) = &price_params.oracle_name {
             let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
@@ -230,9 +231,9 @@ impl BasketQuotable for QuoteBasketService {
         if let Some(dex_name) = &params.dex_name {
             let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
             if let Some(adapter) = factory.get(dex_name) {
-                if let Some(dex_adapter) = adapter.as_any().downcast_ref::<Arc<dyn crate::dex::traits::DexAdapter>>() {
-                    let swap_result = dex_adapter.quote(params)?;
-                    return Ok(swap_result.avg_price);
+                if let Some(dex_adapter) = adapter.as_any().downcast_ref::<Arc<dyn crate::dex::adapter::DexAdapter>>() {
+                    let quote = crate::dex::adapter::quote_from_pool(dex_adapter.as_ref(), params, accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                    return Ok(quote.avg_price);
                 }
             }
         }
@@ -254,10 +255,11 @@ trait BasketBuyExecutable {
     /// - `params`: 交易参数。
     /// - `price`: 价格。
     /// - `buyer`: 买家。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带，否则返回 MissingPoolAccounts。
     ///
     /// # 返回值
     /// - 成功返回 Ok(())，失败返回 BasketError。
-    fn execute_buy(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, buyer: Pubkey) -> anchor_lang::Result<()>; // trait方法签名，类型安全
+    fn execute_buy(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, buyer: Pubkey, accounts: &[AccountInfo]) -> anchor_lang::Result<()>; // trait方法签名，类型安全
 }
 
 /// 篮子买入服务实现
@@ -266,7 +268,7 @@ trait BasketBuyExecutable {
 pub struct ExecuteBuyBasketService; // 无状态结构体，便于多实例和线程安全
 impl BasketBuyExecutable for ExecuteBuyBasketService {
     /// 买入实现（融合算法/策略/DEX/预言机，生产级实现）
-    fn execute_buy(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, buyer: Pubkey) -> anchor_lang::Result<()> {
+    fn execute_buy(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, buyer: Pubkey, accounts: &[AccountInfo]) -> anchor_lang::Result<()> {
         // 1. 算法/策略融合：如有 ExecutionParams，查找并调用已注册的 ExecutionStrategy trait 实现
         if let Some(exec_params) = &params.exec_params {
             if let Some(algo_name) = &exec_params.algo_name {
@@ -294,7 +296,7 @@ impl BasketBuyExecutable for ExecuteBuyBasketService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: basket.mint,
                             output_mint: exec_params.output_mint,
@@ -303,8 +305,8 @@ impl BasketBuyExecutable for ExecuteBuyBasketService {
                             user: buyer,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_price = swap_result.amount_out;
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_price = quote.amount_out;
                     }
                 }
             }
@@ -334,10 +336,11 @@ trait BasketSellExecutable {
     /// - `params`: 交易参数。
     /// - `price`: 价格。
     /// - `seller`: 卖家。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带，否则返回 MissingPoolAccounts。
     ///
     /// # 返回值
     /// - 成功返回 Ok(())，失败返回 BasketError。
-    fn execute_sell(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, seller: Pubkey) -> anchor_lang::Result<()>; // trait方法签名，类型安全
+    fn execute_sell(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, seller: Pubkey, accounts: &[AccountInfo]) -> anchor_lang::Result<()>; // trait方法签名，类型安全
 }
 
 /// 篮子卖出服务实现
@@ -346,7 +349,7 @@ trait BasketSellExecutable {
 pub struct ExecuteSellBasketService; // 无状态结构体，便于多实例和线程安全
 impl BasketSellExecutable for ExecuteSellBasketService {
     /// 卖出实现（融合算法/策略/DEX/预言机，生产级实现）
-    fn execute_sell(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, seller: Pubkey) -> anchor_lang::Result<()> {
+    fn execute_sell(&self, basket: &mut BasketIndexState, params: &TradeParams, price: u64, seller: Pubkey, accounts: &[AccountInfo]) -> anchor_lang::Result<()> {
         if let Some(exec_params) = &params.exec_params {
             if let Some(algo_name) = &exec_params.algo_name {
                 let registry = crate::algorithms::algorithm_registry::ALGORITHM_REGISTRY.lock().unwrap();
@@ -371,7 +374,7 @@ impl BasketSellExecutable for ExecuteSellBasketService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: basket.mint,
                             output_mint: exec_params.output_mint,
@@ -380,8 +383,8 @@ impl BasketSellExecutable for ExecuteSellBasketService {
                             user: seller,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_price = swap_result.amount_out;
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_price = quote.amount_out;
                     }
                 }
             }
@@ -413,10 +416,11 @@ trait BasketSwappable {
     /// - `from_amount`: 从来源篮子转出的数量。
     /// - `to_amount`: 转入目标篮子的数量。
     /// - `authority`: 操作权限。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带，否则返回 MissingPoolAccounts。
     ///
     /// # 返回值
     /// - 成功返回 Ok(())，失败返回 BasketError。
-    fn execute_swap(&self, from: &mut BasketIndexState, to: &mut BasketIndexState, from_amount: u64, to_amount: u64, authority: Pubkey) -> anchor_lang::Result<()>; // trait方法签名，类型安全
+    fn execute_swap(&self, from: &mut BasketIndexState, to: &mut BasketIndexState, from_amount: u64, to_amount: u64, authority: Pubkey, accounts: &[AccountInfo]) -> anchor_lang::Result<()>; // trait方法签名，类型安全
 }
 
 /// 篮子交换服务实现
@@ -425,7 +429,7 @@ trait BasketSwappable {
 pub struct ExecuteSwapBasketService; // 无状态结构体，便于多实例和线程安全
 impl BasketSwappable for ExecuteSwapBasketService {
     /// 交换实现（融合算法/策略/DEX/预言机，生产级实现）
-    fn execute_swap(&self, from: &mut BasketIndexState, to: &mut BasketIndexState, from_amount: u64, to_amount: u64, authority: Pubkey) -> anchor_lang::Result<()> {
+    fn execute_swap(&self, from: &mut BasketIndexState, to: &mut BasketIndexState, from_amount: u64, to_amount: u64, authority: Pubkey, accounts: &[AccountInfo]) -> anchor_lang::Result<()> {
         // 1. 算法/策略融合：如有 ExecutionParams，查找并调用已注册的 ExecutionStrategy trait 实现
         if let Some(exec_params) = &from.exec_params {
             if let Some(algo_name) = &exec_params.algo_name {
@@ -453,7 +457,7 @@ impl BasketSwappable for ExecuteSwapBasketService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: from.mint,
                             output_mint: exec_params.output_mint,
@@ -462,8 +466,8 @@ impl BasketSwappable for ExecuteSwapBasketService {
                             user: authority,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_to_amount = swap_result.amount_out;
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_to_amount = quote.amount_out;
                     }
                 }
             }
@@ -819,6 +823,26 @@ mod tests {
     use crate::core::types::{BatchTradeParams, StrategyParams, TradeParams, OracleParams, AlgoParams};
     use anchor_lang::prelude::Pubkey;
 
+    /// 统一 DexAdapter 的测试实现，fail 为 true 时 swap 返回错误。
+    struct TestDex {
+        fail: bool,
+    }
+    impl crate::core::adapter::AdapterTrait for TestDex {
+        fn name(&self) -> &str { "test_dex" }
+        fn version(&self) -> &str { "1.0.0" }
+        fn is_available(&self) -> bool { true }
+        fn initialize(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+        fn cleanup(&mut self) -> anchor_lang::Result<()> { Ok(()) }
+    }
+    impl crate::dex::adapter::DexAdapter for TestDex {
+        fn swap(&self, params: &TradeParams) -> anchor_lang::Result<crate::dex::adapter::DexSwapResult> {
+            if self.fail {
+                return Err(anchor_lang::error!(anchor_lang::error::ErrorCode::Custom(9002)));
+            }
+            Ok(crate::dex::adapter::DexSwapResult { executed_amount: params.amount_in, avg_price: 0, fee: 0, dex_name: "test_dex".to_string() })
+        }
+    }
+
     fn default_basket(authority: Pubkey, value: u64) -> BasketIndexState {
         BasketIndexState {
             authority,
@@ -898,20 +922,13 @@ mod tests {
         let ctx = anchor_lang::prelude::anchor_lang::prelude::Context::default();
         let params = AlgoParams { order_size: 1000, market_impact: 0, slippage_tolerance: 100 };
         // mock dex/oracle
-        struct DummyDex;
-        impl crate::dex::traits::DexAdapter for DummyDex {
-            fn swap(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::Swap>, _params: crate::dex::traits::SwapParams) -> anchor_lang::Result<crate::dex::traits::SwapResult> { Ok(crate::dex::traits::SwapResult { amount_out: 0, fee: 0 }) }
-            fn add_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::AddLiquidity>, _params: crate::dex::traits::AddLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn remove_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::RemoveLiquidity>, _params: crate::dex::traits::RemoveLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn get_quote(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::GetQuote>, _params: crate::dex::traits::QuoteParams) -> anchor_lang::Result<crate::dex::traits::QuoteResult> { Ok(crate::dex::traits::QuoteResult { amount_out: 0, fee: 0 }) }
-        }
         struct DummyOracle;
         impl crate::oracles::traits::OracleAdapter for DummyOracle {
             fn get_price(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetPrice>, _params: crate::oracles::traits::PriceParams) -> anchor_lang::Result<crate::oracles::traits::PriceResult> { Ok(crate::oracles::traits::PriceResult { price: 0, last_updated: 0 }) }
             fn get_twap(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetTwap>, _params: crate::oracles::traits::TwapParams) -> anchor_lang::Result<crate::oracles::traits::TwapResult> { Ok(crate::oracles::traits::TwapResult { twap: 0, last_updated: 0 }) }
             fn get_vwap(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetVwap>, _params: crate::oracles::traits::VwapParams) -> anchor_lang::Result<crate::oracles::traits::VwapResult> { Ok(crate::oracles::traits::VwapResult { vwap: 0, last_updated: 0 }) }
         }
-        let dex = DummyDex;
+        let dex = TestDex { fail: false };
         let oracle = DummyOracle;
         let result = svc.rebalance_with_algo_and_adapters(&mut basket, vec![6000, 4000], &algo, &dex, &oracle, ctx, &params);
         assert!(result.is_ok());
@@ -935,20 +952,13 @@ mod tests {
         let algo = FailingAlgo;
         let ctx = anchor_lang::prelude::anchor_lang::prelude::Context::default();
         let params = AlgoParams { order_size: 1000, market_impact: 0, slippage_tolerance: 100 };
-        struct DummyDex;
-        impl crate::dex::traits::DexAdapter for DummyDex {
-            fn swap(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::Swap>, _params: crate::dex::traits::SwapParams) -> anchor_lang::Result<crate::dex::traits::SwapResult> { Ok(crate::dex::traits::SwapResult { amount_out: 0, fee: 0 }) }
-            fn add_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::AddLiquidity>, _params: crate::dex::traits::AddLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn remove_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::RemoveLiquidity>, _params: crate::dex::traits::RemoveLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn get_quote(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::GetQuote>, _params: crate::dex::traits::QuoteParams) -> anchor_lang::Result<crate::dex::traits::QuoteResult> { Ok(crate::dex::traits::QuoteResult { amount_out: 0, fee: 0 }) }
-        }
         struct DummyOracle;
         impl crate::oracles::traits::OracleAdapter for DummyOracle {
             fn get_price(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetPrice>, _params: crate::oracles::traits::PriceParams) -> anchor_lang::Result<crate::oracles::traits::PriceResult> { Ok(crate::oracles::traits::PriceResult { price: 0, last_updated: 0 }) }
             fn get_twap(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetTwap>, _params: crate::oracles::traits::TwapParams) -> anchor_lang::Result<crate::oracles::traits::TwapResult> { Ok(crate::oracles::traits::TwapResult { twap: 0, last_updated: 0 }) }
             fn get_vwap(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetVwap>, _params: crate::oracles::traits::VwapParams) -> anchor_lang::Result<crate::oracles::traits::VwapResult> { Ok(crate::oracles::traits::VwapResult { vwap: 0, last_updated: 0 }) }
         }
-        let dex = DummyDex;
+        let dex = TestDex { fail: false };
         let oracle = DummyOracle;
         let result = svc.rebalance_with_algo_and_adapters(&mut basket, vec![6000, 4000], &algo, &dex, &oracle, ctx, &params);
         assert!(result.is_err());
@@ -967,13 +977,6 @@ mod tests {
                 Ok(ExecutionResult { optimized_size: 1000, expected_cost: 100 })
             }
         }
-        struct FailingDex;
-        impl crate::dex::traits::DexAdapter for FailingDex {
-            fn swap(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::Swap>, _params: crate::dex::traits::SwapParams) -> anchor_lang::Result<crate::dex::traits::SwapResult> { Err(anchor_lang::error!(anchor_lang::error::ErrorCode::Custom(9002))) }
-            fn add_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::AddLiquidity>, _params: crate::dex::traits::AddLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn remove_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::RemoveLiquidity>, _params: crate::dex::traits::RemoveLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn get_quote(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::GetQuote>, _params: crate::dex::traits::QuoteParams) -> anchor_lang::Result<crate::dex::traits::QuoteResult> { Ok(crate::dex::traits::QuoteResult { amount_out: 0, fee: 0 }) }
-        }
         struct DummyOracle;
         impl crate::oracles::traits::OracleAdapter for DummyOracle {
             fn get_price(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetPrice>, _params: crate::oracles::traits::PriceParams) -> anchor_lang::Result<crate::oracles::traits::PriceResult> { Ok(crate::oracles::traits::PriceResult { price: 0, last_updated: 0 }) }
@@ -982,7 +985,7 @@ mod tests {
         }
         let svc = RebalanceWithAlgoAndAdaptersService;
         let algo = DummyAlgo;
-        let dex = FailingDex;
+        let dex = TestDex { fail: true };
         let oracle = DummyOracle;
         let ctx = anchor_lang::prelude::anchor_lang::prelude::Context::default();
         let params = AlgoParams { order_size: 1000, market_impact: 0, slippage_tolerance: 100 };
@@ -1006,13 +1009,6 @@ mod tests {
                 Ok(ExecutionResult { optimized_size: 1000, expected_cost: 100 })
             }
         }
-        struct DummyDex;
-        impl crate::dex::traits::DexAdapter for DummyDex {
-            fn swap(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::Swap>, _params: crate::dex::traits::SwapParams) -> anchor_lang::Result<crate::dex::traits::SwapResult> { Ok(crate::dex::traits::SwapResult { amount_out: 0, fee: 0 }) }
-            fn add_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::AddLiquidity>, _params: crate::dex::traits::AddLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn remove_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::RemoveLiquidity>, _params: crate::dex::traits::RemoveLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn get_quote(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::GetQuote>, _params: crate::dex::traits::QuoteParams) -> anchor_lang::Result<crate::dex::traits::QuoteResult> { Ok(crate::dex::traits::QuoteResult { amount_out: 0, fee: 0 }) }
-        }
         struct FailingOracle;
         impl crate::oracles::traits::OracleAdapter for FailingOracle {
             fn get_price(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetPrice>, _params: crate::oracles::traits::PriceParams) -> anchor_lang::Result<crate::oracles::traits::PriceResult> { Err(anchor_lang::error!(anchor_lang::error::ErrorCode::Custom(9003))) }
@@ -1021,7 +1017,7 @@ mod tests {
         }
         let svc = RebalanceWithAlgoAndAdaptersService;
         let algo = DummyAlgo;
-        let dex = DummyDex;
+        let dex = TestDex { fail: false };
         let oracle = FailingOracle;
         let ctx = anchor_lang::prelude::anchor_lang::prelude::Context::default();
         let params = AlgoParams { order_size: 1000, market_impact: 0, slippage_tolerance: 100 };
@@ -1043,13 +1039,6 @@ mod tests {
                 Ok(ExecutionResult { optimized_size: 1000, expected_cost: 100 })
             }
         }
-        struct DummyDex;
-        impl crate::dex::traits::DexAdapter for DummyDex {
-            fn swap(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::Swap>, _params: crate::dex::traits::SwapParams) -> anchor_lang::Result<crate::dex::traits::SwapResult> { Ok(crate::dex::traits::SwapResult { amount_out: 0, fee: 0 }) }
-            fn add_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::AddLiquidity>, _params: crate::dex::traits::AddLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn remove_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::RemoveLiquidity>, _params: crate::dex::traits::RemoveLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn get_quote(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::GetQuote>, _params: crate::dex::traits::QuoteParams) -> anchor_lang::Result<crate::dex::traits::QuoteResult> { Ok(crate::dex::traits::QuoteResult { amount_out: 0, fee: 0 }) }
-        }
         struct DummyOracle;
         impl crate::oracles::traits::OracleAdapter for DummyOracle {
             fn get_price(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetPrice>, _params: crate::oracles::traits::PriceParams) -> anchor_lang::Result<crate::oracles::traits::PriceResult> { Ok(crate::oracles::traits::PriceResult { price: 0, last_updated: 0 }) }
@@ -1058,7 +1047,7 @@ mod tests {
         }
         let svc = RebalanceWithAlgoAndAdaptersService;
         let algo = DummyAlgo;
-        let dex = DummyDex;
+        let dex = TestDex { fail: false };
         let oracle = DummyOracle;
         let ctx = anchor_lang::prelude::anchor_lang::prelude::Context::default();
         let params = AlgoParams { order_size: 1000, market_impact: 0, slippage_tolerance: 100 };
@@ -1079,13 +1068,6 @@ mod tests {
                 Ok(ExecutionResult { optimized_size: 1000, expected_cost: 100 })
             }
         }
-        struct DummyDex;
-        impl crate::dex::traits::DexAdapter for DummyDex {
-            fn swap(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::Swap>, _params: crate::dex::traits::SwapParams) -> anchor_lang::Result<crate::dex::traits::SwapResult> { Ok(crate::dex::traits::SwapResult { amount_out: 0, fee: 0 }) }
-            fn add_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::AddLiquidity>, _params: crate::dex::traits::AddLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn remove_liquidity(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::RemoveLiquidity>, _params: crate::dex::traits::RemoveLiquidityParams) -> anchor_lang::Result<u64> { Ok(0) }
-            fn get_quote(&self, _ctx: anchor_lang::prelude::Context<crate::dex::traits::GetQuote>, _params: crate::dex::traits::QuoteParams) -> anchor_lang::Result<crate::dex::traits::QuoteResult> { Ok(crate::dex::traits::QuoteResult { amount_out: 0, fee: 0 }) }
-        }
         struct DummyOracle;
         impl crate::oracles::traits::OracleAdapter for DummyOracle {
             fn get_price(&self, _ctx: anchor_lang::prelude::Context<crate::oracles::traits::GetPrice>, _params: crate::oracles::traits::PriceParams) -> anchor_lang::Result<crate::oracles::traits::PriceResult> { Ok(crate::oracles::traits::PriceResult { price: 0, last_updated: 0 }) }
@@ -1094,7 +1076,7 @@ mod tests {
         }
         let svc = RebalanceWithAlgoAndAdaptersService;
         let algo = DummyAlgo;
-        let dex = DummyDex;
+        let dex = TestDex { fail: false };
         let oracle = DummyOracle;
         let ctx = anchor_lang::prelude::anchor_lang::prelude::Context::default();
         let params = AlgoParams { order_size: 1000, market_impact: 0, slippage_tolerance: 100 };
@@ -43,10 +43,11 @@ trait IndexTokenIssuable {
     ///
     /// # 参数
     /// - `params`: 发行参数。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带，否则返回 MissingPoolAccounts。
     ///
     /// # 返回值
     /// - 返回发行数量，失败返回 IndexTokenError。
-    fn issue(&self, params: &TradeParams) -> anchor_lang::Result<u64>;
+    fn issue(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64>;
 }
 
 /// 指数代币发行服务实现
@@ -55,7 +56,7 @@ trait IndexTokenIssuable {
 pub struct IssueIndexTokenService; // 无状态结构体，便于多实例和线程安全
 impl IndexTokenIssuable for IssueIndexTokenService {
     /// 发行实现（融合算法/策略/DEX/预言机，生产级实现）
-    fn issue(&self, params: &TradeParams) -> anchor_lang::Result<u64> {
+    fn issue(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
         // 1. 算法/策略融合：如有 ExecutionParams，查找并调用已注册的 ExecutionStrategy trait 实现
         if let Some(exec_params) = &params.exec_params {
             if let Some(algo_name) = &exec_params.algo_name {
@@ -83,7 +84,7 @@ impl IndexTokenIssuable for IssueIndexTokenService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: params.from_token,
                             output_mint: exec_params.output_mint,
@@ -92,8 +93,8 @@ impl IndexTokenIssuable for IssueIndexTokenService {
                             user: params.user,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_price = swap_result.amount_out;
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_price = quote.amount_out;
                     }
                 }
             }
@@ -118,10 +119,11 @@ trait IndexTokenRedeemable {
     ///
     /// # 参数
     /// - `params`: 赎回参数。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带，否则返回 MissingPoolAccounts。
     ///
     /// # 返回值
     /// - 返回赎回数量，失败返回 IndexTokenError。
-    fn redeem(&self, params: &TradeParams) -> anchor_lang::Result<u64>;
+    fn redeem(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64>;
 }
 
 /// 指数代币赎回服务实现
@@ -130,7 +132,7 @@ trait IndexTokenRedeemable {
 pub struct RedeemIndexTokenService;
 impl IndexTokenRedeemable for RedeemIndexTokenService {
     /// 赎回实现（融合算法/策略/DEX/预言机，生产级实现）
-    fn redeem(&self, params: &TradeParams) -> anchor_lang::Result<u64> {
+    fn redeem(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
         if let Some(exec_params) = &params.exec_params {
             if let Some(algo_name) = &exec_params.algo_name {
                 let registry = crate::algorithms::algorithm_registry::ALGORITHM_REGISTRY.lock().unwrap();
@@ -155,7 +157,7 @@ impl IndexTokenRedeemable for RedeemIndexTokenService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: params.from_token,
                             output_mint: exec_params.output_mint,
@@ -164,8 +166,8 @@ impl IndexTokenRedeemable for RedeemIndexTokenService {
                             user: params.user,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_price = swap_result.amount_out;
+                        let quote = crate::dex::adapter::checked_quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_price = quote.amount_out;
                     }
                 }
             }
@@ -188,10 +190,11 @@ trait IndexTokenQuotable {
     ///
     /// # 参数
     /// - `params`: 报价参数。
+    /// - `accounts`: DEX 池账户（指令 remaining_accounts），指定 dex_name 时必须携带，否则返回 MissingPoolAccounts。
     ///
     /// # 返回值
     /// - 返回报价，失败返回 IndexTokenError。
-    fn quote(&self, params: &TradeParams) -> anchor_lang::Result<u64>;
+    fn quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64>;
 }
 
 /// 指数代币报价服务实现
@@ -200,7 +203,7 @@ trait IndexTokenQuotable {
 pub struct QuoteIndexTokenService;
 impl IndexTokenQuotable for QuoteIndexTokenService {
     /// 报价实现（融合算法/策略/DEX/预言机，生产级实现）
-    fn quote(&self, params: &TradeParams) -> anchor_lang::Result<u64> {
+    fn quote(&self, params: &TradeParams, accounts: &[AccountInfo]) -> anchor_lang::Result<u64> {
         if let Some(exec_params) = &params.exec_params {
             if let Some(algo_name) = &exec_params.algo_name {
                 let registry = crate::algorithms::algorithm_registry::ALGORITHM_REGISTRY.lock().unwrap();
@@ -225,7 +228,7 @@ impl IndexTokenQuotable for QuoteIndexTokenService {
             if let Some(dex_name) = &exec_params.dex_name {
                 let factory = crate::core::registry::ADAPTER_FACTORY.lock().unwrap();
                 if let Some(adapter) = factory.get(dex_name) {
-                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::traits::DexAdapter>>() {
+                    if let Some(dex_adapter) = adapter.as_any().downcast_ref::<std::sync::Arc<dyn crate::dex::adapter::DexAdapter>>() {
                         let swap_params = crate::dex::traits::SwapParams {
                             input_mint: params.from_token,
                             output_mint: exec_params.output_mint,
@@ -234,8 +237,8 @@ impl IndexTokenQuotable for QuoteIndexTokenService {
                             user: params.user,
                             dex_accounts: exec_params.dex_accounts.clone(),
                         };
-                        let swap_result = dex_adapter.swap(anchor_lang::prelude::Context::default(), swap_params)?;
-                        final_price = swap_result.amount_out;
+                        let quote = crate::dex::adapter::quote_from_pool(dex_adapter.as_ref(), &swap_params.to_trade_params(dex_name), accounts)?; // 未携带池账户时返回 MissingPoolAccounts
+                        final_price = quote.amount_out;
                     }
                 }
             }
@@ -614,7 +617,7 @@ mod tests {
     fn test_issue_index_token_success() {
         let svc = IssueIndexTokenService;
         let params = default_params();
-        let result = svc.issue(&params);
+        let result = svc.issue(&params, &[]);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 100);
     }
@@ -623,7 +626,7 @@ mod tests {
     fn test_redeem_index_token_success() {
         let svc = RedeemIndexTokenService;
         let params = default_params();
-        let result = svc.redeem(&params);
+        let result = svc.redeem(&params, &[]);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 100);
     }
@@ -632,7 +635,7 @@ mod tests {
     fn test_quote_index_token_success() {
         let svc = QuoteIndexTokenService;
         let params = default_params();
-        let result = svc.quote(&params);
+        let result = svc.quote(&params, &[]);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 100000);
     }
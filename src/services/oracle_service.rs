@@ -3,7 +3,7 @@
 //! 业务逻辑实现，供指令入口调用，封装预言机适配器注册、价格查询、TWAP/VWAP、批量操作、权限校验等操作。
 
 use anchor_lang::prelude::*;
-use crate::oracles::traits::{OracleAdapter, OracleParams, OraclePriceResult, OracleTwapResult, OracleVwapResult};
+use crate::oracles::traits::{OracleAdapter, OracleParams, OraclePriceResult, OracleTwapResult, OracleVwapResult, DEFAULT_PRICE_EXPONENT};
 use crate::core::types::{BatchTradeParams};
 // use crate::errors::basket_error::BasketError;
 
@@ -59,6 +59,8 @@ impl OraclePriceQuotable for GetPriceOracleService {
         // 生产级实现：调用预言机适配器get_price
         Ok(OraclePriceResult { 
             price: params.price * 1000,
+            confidence: 0,
+            exponent: DEFAULT_PRICE_EXPONENT,
             last_updated: anchor_lang::clock::Clock::get()?.unix_timestamp,
             oracle_name: params.oracle_name.clone(),
         })
@@ -152,6 +154,8 @@ impl OracleBatchQuotable for BatchQuoteOracleService {
         // 生产级实现：遍历批量参数
         Ok(batch_params.amounts.iter().map(|&amt| OraclePriceResult { 
             price: amt * 1000,
+            confidence: 0,
+            exponent: DEFAULT_PRICE_EXPONENT,
             last_updated: anchor_lang::clock::Clock::get()?.unix_timestamp,
             oracle_name: "batch_oracle".to_string(),
         }).collect())
@@ -4,6 +4,7 @@
 
 use anchor_lang::prelude::*;
 use crate::state::common::*;
+use crate::oracles::aggregator::AggregatedPrice;
 // use crate::errors::basket_error::BasketError;
 
 /// 篮子/指数代币统一状态结构体
@@ -116,6 +117,13 @@ impl BasketIndexState {
         self.total_supply -= amount;
         Ok(())
     }
+    /// 以多源聚合价格计算 NAV
+    /// - 聚合价格按资产 mint 匹配成分，计价口径与 calculate_nav 一致
+    /// - 便于直接使用 ConsensusOracleAggregator 的结果替代单一 PriceFeed
+    pub fn calculate_nav_aggregated(&self, prices: &[AggregatedPrice]) -> anchor_lang::Result<u64> {
+        let price_feeds: Vec<PriceFeed> = prices.iter().map(AggregatedPrice::to_price_feed).collect();
+        self.calculate_nav(&price_feeds)
+    }
 }
 
 /// 实现 NAV 计算 trait
@@ -297,4 +305,56 @@ mod tests {
         basket.weights = vec![5_000];
         assert!(basket.validate().is_err());
     }
+
+    /// 测试以聚合价格计算 NAV 与等价 PriceFeed 结果一致。
+    #[test]
+    fn test_basket_nav_from_aggregated_prices() {
+        use crate::oracles::aggregator::AggregationMethod;
+
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let basket = BasketIndexState {
+            base: BaseAccount::new(Pubkey::new_unique(), 1).unwrap(),
+            id: 1,
+            asset_type: crate::core::types::AssetType::Crypto,
+            composition: vec![
+                BasketConstituent { token_mint: sol, balance: 1_000, weight: 5_000 },
+                BasketConstituent { token_mint: usdc, balance: 2_000, weight: 5_000 },
+            ],
+            weights: vec![5_000, 5_000],
+            total_value: 0,
+            total_supply: 0,
+            authority: Pubkey::new_unique(),
+            manager: None,
+            fee_collector: Pubkey::new_unique(),
+            creation_fee_bps: 10,
+            redemption_fee_bps: 10,
+            status: BasketStatus::Active,
+            is_active: true,
+            is_paused: false,
+            enable_rebalancing: true,
+            last_rebalanced: 0,
+            created_at: 0,
+            updated_at: 0,
+            execution_stats: ExecutionStats::default(),
+            risk_metrics: None,
+            ai_signals: None,
+            external_signals: None,
+            bump: 1,
+        };
+        let aggregated = |asset: Pubkey, price: u64| AggregatedPrice {
+            asset,
+            price,
+            confidence: 0,
+            last_updated: 0,
+            method: AggregationMethod::Median,
+            sources: vec!["pyth".to_string(), "switchboard".to_string()],
+            stale_sources: vec![],
+            outlier_sources: vec![],
+        };
+        let prices = vec![aggregated(sol, 150 * PRICE_PRECISION), aggregated(usdc, PRICE_PRECISION)];
+        let nav = basket.calculate_nav_aggregated(&prices).unwrap();
+        assert_eq!(nav, 1_000 * 150 + 2_000);
+        let feeds: Vec<PriceFeed> = prices.iter().map(AggregatedPrice::to_price_feed).collect();
+        assert_eq!(nav, basket.calculate_nav(&feeds).unwrap());
+    }
 }
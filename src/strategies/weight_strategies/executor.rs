@@ -8,6 +8,7 @@ use crate::core::*;
 use crate::core::adapter::AdapterTrait;
 use crate::error::StrategyError;
 use crate::strategies::*;
+use crate::account_models::price_history_account::PriceHistory;
 use anchor_lang::prelude::*;
 
 /// 权重策略执行器结构体，支持多种权重分配算法。
@@ -119,6 +120,59 @@ impl WeightStrategyExecutor {
         }
         Ok(weights)
     }
+    /// 基于链上价格历史执行动量加权策略。
+    /// - 每个资产取 lookback_period + 1 个长度为 period_secs 的等分段 TWAP 作为价格序列，histories 需与 token_info 按 mint 一一对应，且均由 authority 管理。
+    pub fn execute_momentum_weighted_from_history(
+        token_info: &[TokenInfo],
+        histories: &[&PriceHistory],
+        authority: &Pubkey,
+        now: i64,
+        period_secs: u64,
+        params: MomentumWeightedParams,
+    ) -> StrategyResult<Vec<u64>> {
+        Self::validate_histories(token_info, histories, authority)?;
+        let points = params.lookback_period.checked_add(1).ok_or(StrategyError::InvalidStrategyParameters)?;
+        let window_secs = period_secs
+            .checked_mul(points as u64)
+            .ok_or(StrategyError::InvalidStrategyParameters)?;
+        let price_history = histories
+            .iter()
+            .map(|history| history.sampled_twaps(now, window_secs, points))
+            .collect::<StrategyResult<Vec<_>>>()?;
+        Self::execute_momentum_weighted(token_info, &price_history, params)
+    }
+    /// 基于链上价格历史执行波动率调整加权策略。
+    /// - 波动率取最近 volatility_period 个 period_secs 内相邻观测收益率的标准差（基点）。
+    pub fn execute_volatility_adjusted_from_history(
+        token_info: &[TokenInfo],
+        histories: &[&PriceHistory],
+        authority: &Pubkey,
+        now: i64,
+        period_secs: u64,
+        params: VolatilityAdjustedParams,
+    ) -> StrategyResult<Vec<u64>> {
+        Self::validate_histories(token_info, histories, authority)?;
+        let window_secs = period_secs
+            .checked_mul(params.volatility_period as u64)
+            .ok_or(StrategyError::InvalidStrategyParameters)?;
+        let volatility_data = histories
+            .iter()
+            .map(|history| history.volatility_bps(now, window_secs))
+            .collect::<StrategyResult<Vec<_>>>()?;
+        Self::execute_volatility_adjusted(token_info, &volatility_data, params)
+    }
+    /// 校验价格历史与资产按 mint 一一对应，且由受信任的管理人绑定预言机（任何人都可为同一 mint 初始化自己的价格历史）。
+    fn validate_histories(token_info: &[TokenInfo], histories: &[&PriceHistory], authority: &Pubkey) -> StrategyResult<()> {
+        if histories.len() != token_info.len()
+            || token_info
+                .iter()
+                .zip(histories)
+                .any(|(token, history)| token.mint != history.mint || history.authority != *authority)
+        {
+            return Err(StrategyError::InvalidStrategyParameters.into());
+        }
+        Ok(())
+    }
     /// 执行固定权重策略。
     pub fn execute_fixed_weight(fixed_weights: &[u64]) -> StrategyResult<Vec<u64>> {
         if fixed_weights.is_empty() || fixed_weights.len() > MAX_TOKENS {